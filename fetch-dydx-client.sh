@@ -0,0 +1,28 @@
+#!/bin/bash
+
+# Fetch the dYdX v4 Rust client into dydx-clients/v4-client-rs/client, the
+# path dependency in Cargo.toml
+# Usage: ./fetch-dydx-client.sh
+# The release is pinned by DYDX_CLIENT_VERSION, the version Cargo.lock was
+# resolved against; CI fetches the same one
+
+set -euo pipefail
+
+DYDX_CLIENT_VERSION=${DYDX_CLIENT_VERSION:-"0.2.0"}
+DEST="$(cd "$(dirname "$0")" && pwd)/dydx-clients/v4-client-rs/client"
+
+if [ -f "$DEST/Cargo.toml" ]; then
+    if grep -q "^version = \"$DYDX_CLIENT_VERSION\"" "$DEST/Cargo.toml"; then
+        echo "dYdX v4 client $DYDX_CLIENT_VERSION already in $DEST"
+        exit 0
+    fi
+    echo "❌ $DEST holds another version of the dYdX v4 client."
+    echo "   Remove it to fetch $DYDX_CLIENT_VERSION."
+    exit 1
+fi
+
+echo "📦 Fetching dYdX v4 client $DYDX_CLIENT_VERSION..."
+mkdir -p "$DEST"
+curl -fsSL "https://static.crates.io/crates/dydx/dydx-$DYDX_CLIENT_VERSION.crate" \
+    | tar -xz --strip-components=1 -C "$DEST"
+echo "✅ dYdX v4 client $DYDX_CLIENT_VERSION in $DEST"
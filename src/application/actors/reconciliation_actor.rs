@@ -2,11 +2,20 @@
 //!
 //! This actor manages portfolio reconciliation operations asynchronously.
 //! It handles scheduling, retries, and coordination between different exchanges.
+//! Every report it produces is written to the audit repository when one is set.
+//!
+//! The actor keeps the balances it expects on each exchange. The first
+//! snapshot of an exchange (or its last audited balances) is the baseline,
+//! moved forward by the fills, fees and funding the bot reports with each
+//! run; the unrealized PnL of its open perp positions is added before
+//! comparing. Discrepancies are remediated according to the remediation
+//! policy, and a gap already waiting for an operator is not flagged again.
 
 use crate::domain::entities::exchange::Exchange;
 use crate::domain::services::reconciliation::*;
 use crate::infrastructure::coinbase_client::CoinbaseClient;
 use crate::infrastructure::dydx_client::DydxClient;
+use crate::persistence::reconciliation_audit::ReconciliationRepository;
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -23,14 +32,25 @@ pub enum ReconciliationMessage {
     /// Trigger reconciliation for a specific exchange
     ReconcileExchange {
         exchange: Exchange,
+        activity: OwnActivity,
         reply: mpsc::Sender<Result<ReconciliationReport, ReconciliationError>>,
     },
 
-    /// Trigger reconciliation for all configured exchanges
+    /// Trigger reconciliation for every exchange with a client
     ReconcileAll {
+        activity: HashMap<Exchange, OwnActivity>,
         reply: mpsc::Sender<Vec<ReconciliationReport>>,
     },
 
+    /// Fetch the perp positions and open orders held on an exchange
+    FetchVenueState {
+        exchange: Exchange,
+        reply: mpsc::Sender<Result<VenueState, ReconciliationError>>,
+    },
+
+    /// Adopt the exchange balances of a report an operator resolved
+    Resolve { report: ReconciliationReport },
+
     /// Get status of last reconciliation
     GetStatus {
         reply: mpsc::Sender<HashMap<Exchange, Option<ReconciliationReport>>>,
@@ -56,6 +76,12 @@ pub struct ReconciliationActor {
     config: ReconciliationConfig,
     stats: ReconciliationStats,
     last_reports: HashMap<Exchange, ReconciliationReport>,
+    repository: Option<Arc<dyn ReconciliationRepository>>,
+    policy: RemediationPolicy,
+    /// Expected balances per exchange, without unrealized PnL
+    local_balances: HashMap<Exchange, Portfolio>,
+    /// Gaps per exchange and currency still waiting for an operator
+    flagged: HashMap<Exchange, HashMap<String, f64>>,
 }
 
 impl ReconciliationActor {
@@ -76,21 +102,40 @@ impl ReconciliationActor {
                 last_reconciliation: None,
             },
             last_reports: HashMap::new(),
+            repository: None,
+            policy: RemediationPolicy::default(),
+            local_balances: HashMap::new(),
+            flagged: HashMap::new(),
         }
     }
 
+    /// Remediate discrepancies with this policy instead of the default
+    pub fn with_remediation_policy(mut self, policy: RemediationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Persist every report to the reconciliation audit trail
+    pub fn with_repository(mut self, repository: Arc<dyn ReconciliationRepository>) -> Self {
+        self.repository = Some(repository);
+        self
+    }
+
     /// Spawn a new reconciliation actor
     pub fn spawn(
         coinbase_client: Option<Arc<CoinbaseClient>>,
         dydx_client: Option<Arc<DydxClient>>,
         config: ReconciliationConfig,
     ) -> mpsc::Sender<ReconciliationMessage> {
-        let (tx, rx) = mpsc::channel(RECONCILIATION_CHANNEL_CAPACITY);
+        Self::new(coinbase_client, dydx_client, config).start()
+    }
 
-        let actor = Self::new(coinbase_client, dydx_client, config);
+    /// Spawn this actor, returning its message sender
+    pub fn start(self) -> mpsc::Sender<ReconciliationMessage> {
+        let (tx, rx) = mpsc::channel(RECONCILIATION_CHANNEL_CAPACITY);
 
         tokio::spawn(async move {
-            actor.run(rx).await;
+            self.run(rx).await;
         });
 
         info!("ReconciliationActor spawned");
@@ -103,30 +148,66 @@ impl ReconciliationActor {
 
         while let Some(msg) = rx.recv().await {
             match msg {
-                ReconciliationMessage::ReconcileExchange { exchange, reply } => {
+                ReconciliationMessage::ReconcileExchange {
+                    exchange,
+                    activity,
+                    reply,
+                } => {
                     debug!(
                         "ReconciliationActor received ReconcileExchange for {:?}",
                         exchange
                     );
-                    let result = self.reconcile_exchange(&exchange).await;
+                    let result = self.reconcile_exchange(&exchange, &activity).await;
                     self.update_stats(&result);
+                    if let Ok(report) = &result {
+                        self.persist(report).await;
+                    }
                     if let Err(e) = reply.send(result).await {
                         error!("Failed to send ReconcileExchange reply: {:?}", e);
                     }
                 }
 
-                ReconciliationMessage::ReconcileAll { reply } => {
+                ReconciliationMessage::ReconcileAll { activity, reply } => {
                     debug!("ReconciliationActor received ReconcileAll");
-                    let reports = self.reconcile_all().await;
+                    let reports = self.reconcile_all(activity).await;
                     for report in &reports {
                         self.last_reports
                             .insert(report.exchange.clone(), report.clone());
+                        self.persist(report).await;
                     }
                     if let Err(e) = reply.send(reports).await {
                         error!("Failed to send ReconcileAll reply: {:?}", e);
                     }
                 }
 
+                ReconciliationMessage::FetchVenueState { exchange, reply } => {
+                    debug!(
+                        "ReconciliationActor received FetchVenueState for {:?}",
+                        exchange
+                    );
+                    let result = self.fetch_venue_state(&exchange).await;
+                    if let Err(e) = reply.send(result).await {
+                        error!("Failed to send FetchVenueState reply: {:?}", e);
+                    }
+                }
+
+                ReconciliationMessage::Resolve { report } => {
+                    debug!("ReconciliationActor received Resolve for {}", report.id());
+                    if let (Some(remediation), Some(local)) = (
+                        &report.remediation,
+                        self.local_balances.get_mut(&report.exchange),
+                    ) {
+                        remediation.apply_adoptions(local);
+                    }
+                    // Only the report's currencies are cleared; other open
+                    // remediations of the exchange keep their flags
+                    if let Some(flagged) = self.flagged.get_mut(&report.exchange) {
+                        for discrepancy in &report.discrepancies {
+                            flagged.remove(discrepancy.currency());
+                        }
+                    }
+                }
+
                 ReconciliationMessage::GetStatus { reply } => {
                     debug!("ReconciliationActor received GetStatus");
                     let status: HashMap<Exchange, Option<ReconciliationReport>> = self
@@ -149,19 +230,89 @@ impl ReconciliationActor {
         info!("ReconciliationActor stopped");
     }
 
-    /// Reconcile a specific exchange
+    /// Reconcile a specific exchange, given the bot's own activity on it
     async fn reconcile_exchange(
-        &self,
+        &mut self,
         exchange: &Exchange,
+        activity: &OwnActivity,
     ) -> Result<ReconciliationReport, ReconciliationError> {
-        let service = self.create_service_for_exchange(exchange)?;
+        if !self.local_balances.contains_key(exchange) {
+            if let Some(mut local) = self.audited_balances(exchange).await {
+                // Audited balances include the unrealized PnL of their run;
+                // the current figure is the closest estimate of it
+                for (currency, unrealized) in &activity.unrealized {
+                    local.adjust_balance(currency, -unrealized);
+                }
+                self.local_balances.insert(exchange.clone(), local);
+                let gaps = self.open_gaps(exchange).await;
+                self.flagged.insert(exchange.clone(), gaps);
+            }
+        }
+        let baseline = !self.local_balances.contains_key(exchange);
+        let mut expected = Portfolio::new();
+        if let Some(local) = self.local_balances.get_mut(exchange) {
+            for (currency, flow) in &activity.flows {
+                local.adjust_balance(currency, *flow);
+            }
+            expected = local.clone();
+            for (currency, unrealized) in &activity.unrealized {
+                expected.adjust_balance(currency, *unrealized);
+            }
+        }
+        let service = self.create_service_for_exchange(exchange, expected)?;
 
         // Apply timeout
         let timeout_duration = Duration::from_millis(self.config.timeout_milliseconds);
         let result = timeout(timeout_duration, service.reconcile(exchange.clone())).await;
 
         match result {
-            Ok(Ok(report)) => {
+            Ok(Ok(mut report)) => {
+                if baseline {
+                    // Nothing to compare the first snapshot against
+                    let mut local = Portfolio::new();
+                    for (currency, balance) in &report.exchange_balances {
+                        local.add_balance(currency.clone(), balance.amount);
+                    }
+                    for (currency, unrealized) in &activity.unrealized {
+                        local.adjust_balance(currency, -unrealized);
+                    }
+                    self.local_balances.insert(exchange.clone(), local);
+                    report.local_balances = report.exchange_balances.values().cloned().collect();
+                } else {
+                    let flagged = self.flagged.entry(exchange.clone()).or_default();
+                    // Gaps that closed no longer wait for an operator
+                    flagged.retain(|currency, _| {
+                        report
+                            .discrepancies
+                            .iter()
+                            .any(|discrepancy| discrepancy.currency() == currency)
+                    });
+                    report.remediation = self.policy.plan(&report).and_then(|remediation| {
+                        Remediation::from_steps(
+                            remediation
+                                .steps
+                                .into_iter()
+                                .filter(|step| !already_flagged(flagged, step, &self.config))
+                                .collect(),
+                        )
+                    });
+                    if let Some(remediation) = &report.remediation {
+                        if let Some(local) = self.local_balances.get_mut(exchange) {
+                            remediation.apply_adoptions(local);
+                        }
+                        for step in &remediation.steps {
+                            if step.action == RemediationAction::AdoptExchangeBalance {
+                                flagged.remove(&step.currency);
+                            } else {
+                                flagged.insert(step.currency.clone(), step.gap.unwrap_or(0.0));
+                            }
+                        }
+                        info!(
+                            "Reconciliation remediation for {:?}: {}",
+                            exchange, remediation.status
+                        );
+                    }
+                }
                 info!("Successfully reconciled {:?}", exchange);
                 Ok(report)
             }
@@ -176,13 +327,51 @@ impl ReconciliationActor {
         }
     }
 
+    /// Perp positions and open orders of an exchange
+    ///
+    /// Only dYdX holds perp positions; spot venues are reported as unsupported.
+    async fn fetch_venue_state(
+        &self,
+        exchange: &Exchange,
+    ) -> Result<VenueState, ReconciliationError> {
+        let client = match (exchange, &self.dydx_client) {
+            (Exchange::Dydx, Some(client)) => client.clone(),
+            (Exchange::Dydx, None) => {
+                return Err(ReconciliationError::ApiError(
+                    "dYdX client not configured".to_string(),
+                ))
+            }
+            _ => {
+                return Err(ReconciliationError::ApiError(format!(
+                    "{:?} exchange not yet supported for position reconciliation",
+                    exchange
+                )))
+            }
+        };
+        let reconciler = DydxReconciler::new(client, self.config.clone());
+
+        let timeout_duration = Duration::from_millis(self.config.timeout_milliseconds);
+        match timeout(timeout_duration, reconciler.fetch_venue_state()).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!("Position fetch timed out for {:?}", exchange);
+                Err(ReconciliationError::NetworkTimeout)
+            }
+        }
+    }
+
     /// Reconcile all configured exchanges
-    async fn reconcile_all(&self) -> Vec<ReconciliationReport> {
+    ///
+    /// Exchanges without a client are skipped rather than reported as failed.
+    async fn reconcile_all(
+        &mut self,
+        mut activity: HashMap<Exchange, OwnActivity>,
+    ) -> Vec<ReconciliationReport> {
         let mut reports = Vec::new();
-        let exchanges = vec![Exchange::Coinbase, Exchange::Dydx];
 
-        for exchange in exchanges {
-            match self.reconcile_exchange(&exchange).await {
+        for exchange in self.configured_exchanges() {
+            let activity = activity.remove(&exchange).unwrap_or_default();
+            match self.reconcile_exchange(&exchange, &activity).await {
                 Ok(report) => reports.push(report),
                 Err(e) => {
                     warn!("Failed to reconcile {:?}: {:?}", exchange, e);
@@ -197,15 +386,97 @@ impl ReconciliationActor {
         reports
     }
 
-    /// Create appropriate service for exchange
+    /// Exchanges this actor holds a client for
+    fn configured_exchanges(&self) -> Vec<Exchange> {
+        let mut exchanges = Vec::new();
+        if self.coinbase_client.is_some() {
+            exchanges.push(Exchange::Coinbase);
+        }
+        if self.dydx_client.is_some() {
+            exchanges.push(Exchange::Dydx);
+        }
+        exchanges
+    }
+
+    /// Balances expected after the last audited report of an exchange
+    async fn audited_balances(&self, exchange: &Exchange) -> Option<Portfolio> {
+        let last = match &self.repository {
+            Some(repository) => repository.get_last_reconciliation(exchange).await,
+            None => return None,
+        };
+        let last = match last {
+            Ok(Some(last)) if !last.local_balances.is_empty() => last,
+            Ok(_) => return None,
+            Err(e) => {
+                warn!("Failed to load audited balances for {:?}: {}", exchange, e);
+                return None;
+            }
+        };
+
+        let mut local = Portfolio::new();
+        for balance in &last.local_balances {
+            local.add_balance(balance.currency.clone(), balance.amount);
+        }
+        if let Some(remediation) = &last.remediation {
+            remediation.apply_adoptions(&mut local);
+        }
+        Some(local)
+    }
+
+    /// Gaps of an exchange still waiting for an operator in the audit trail
+    async fn open_gaps(&self, exchange: &Exchange) -> HashMap<String, f64> {
+        let open = match &self.repository {
+            Some(repository) => repository.get_open_remediations().await,
+            None => return HashMap::new(),
+        };
+        let open = match open {
+            Ok(open) => open,
+            Err(e) => {
+                warn!("Failed to load open remediations for {:?}: {}", exchange, e);
+                return HashMap::new();
+            }
+        };
+
+        let mut gaps = HashMap::new();
+        for report in open.iter().filter(|report| &report.exchange == exchange) {
+            let Some(remediation) = &report.remediation else {
+                continue;
+            };
+            for step in &remediation.steps {
+                if step.action == RemediationAction::AdoptExchangeBalance {
+                    continue;
+                }
+                if let Some(gap) = step.gap {
+                    gaps.insert(step.currency.clone(), gap);
+                }
+            }
+        }
+        gaps
+    }
+
+    /// Write a report to the audit trail, logging failures
+    async fn persist(&self, report: &ReconciliationReport) {
+        if let Some(repository) = &self.repository {
+            if let Err(e) = repository.save_reconciliation(report).await {
+                error!(
+                    "Failed to persist reconciliation report for {:?}: {}",
+                    report.exchange, e
+                );
+            }
+        }
+    }
+
+    /// Create appropriate service for exchange, expecting `local` balances
     fn create_service_for_exchange(
         &self,
         exchange: &Exchange,
+        local: Portfolio,
     ) -> Result<Box<dyn PortfolioReconciliationService>, ReconciliationError> {
         match exchange {
             Exchange::Coinbase => {
                 if let Some(client) = &self.coinbase_client {
-                    let reconciler = CoinbaseReconciler::new(client.clone(), self.config.clone());
+                    let reconciler = CoinbaseReconciler::new(client.clone(), self.config.clone())
+                        .with_local_balances(local);
                     Ok(Box::new(reconciler))
                 } else {
                     Err(ReconciliationError::ApiError(
@@ -215,7 +486,8 @@ impl ReconciliationActor {
             }
             Exchange::Dydx => {
                 if let Some(client) = &self.dydx_client {
-                    let reconciler = DydxReconciler::new(client.clone(), self.config.clone());
+                    let reconciler = DydxReconciler::new(client.clone(), self.config.clone())
+                        .with_local_balances(local);
                     Ok(Box::new(reconciler))
                 } else {
                     Err(ReconciliationError::ApiError(
@@ -249,3 +521,64 @@ impl ReconciliationActor {
         }
     }
 }
+
+/// Whether a step repeats a gap already waiting for an operator
+///
+/// The gap counts as unchanged while it moved by less than the mismatch
+/// threshold of the exchange balance.
+fn already_flagged(
+    flagged: &HashMap<String, f64>,
+    step: &RemediationStep,
+    config: &ReconciliationConfig,
+) -> bool {
+    let (Some(open), Some(gap)) = (flagged.get(&step.currency), step.gap) else {
+        return false;
+    };
+    let tolerance = (step.exchange_amount.unwrap_or(0.0).abs() * config.threshold_percentage)
+        .max(config.precision_tolerance);
+    (gap - open).abs() <= tolerance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reconcile_all_skips_exchanges_without_clients() {
+        let actor_tx = ReconciliationActor::spawn(None, None, ReconciliationConfig::default());
+        let (reply_tx, mut reply_rx) = mpsc::channel(1);
+
+        actor_tx
+            .send(ReconciliationMessage::ReconcileAll {
+                activity: HashMap::new(),
+                reply: reply_tx,
+            })
+            .await
+            .unwrap();
+
+        assert!(reply_rx.recv().await.unwrap().is_empty());
+        let _ = actor_tx.send(ReconciliationMessage::Shutdown).await;
+    }
+
+    #[test]
+    fn test_unchanged_gap_is_not_flagged_again() {
+        let config = ReconciliationConfig {
+            threshold_percentage: 0.01,
+            ..Default::default()
+        };
+        let flagged = HashMap::from([("USD".to_string(), -500.0)]);
+        let step = |gap: f64| RemediationStep {
+            currency: "USD".to_string(),
+            severity: DiscrepancySeverity::Major,
+            action: RemediationAction::RequireAcknowledgement,
+            exchange_amount: Some(9_500.0),
+            gap: Some(gap),
+        };
+
+        // Equity drifting by less than the threshold is the same gap
+        assert!(already_flagged(&flagged, &step(-520.0), &config));
+        // A further withdrawal is a new gap
+        assert!(!already_flagged(&flagged, &step(-1_500.0), &config));
+        assert!(!already_flagged(&HashMap::new(), &step(-500.0), &config));
+    }
+}
@@ -1,11 +1,14 @@
+use async_trait::async_trait;
+use chrono::Utc;
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::time::interval;
-use tracing::{error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::domain::entities::symbol_screening::SymbolScreeningResult;
-use crate::domain::services::indicators::Candle;
+use crate::domain::services::screening::ScalpingPotentialAggregator;
 use crate::domain::services::symbol_screening::{SymbolMarketData, SymbolScreeningService};
 use crate::persistence::screening_repository::ScreeningRepository;
 
@@ -19,6 +22,13 @@ pub enum ScreeningMessage {
     Shutdown,
 }
 
+/// Source of the market data a screening run scores
+#[async_trait]
+pub trait ScreeningDataSource: Send + Sync {
+    /// Market data of every symbol tracked on `exchange`, keyed by symbol
+    async fn market_data(&self, exchange: &str) -> HashMap<String, SymbolMarketData>;
+}
+
 /// Results from screening
 #[derive(Debug, Clone)]
 pub struct ScreeningResults {
@@ -29,7 +39,8 @@ pub struct ScreeningResults {
 /// Actor that periodically screens symbols for scalping potential
 pub struct ScreeningActor {
     service: SymbolScreeningService,
-    repository: Option<ScreeningRepository>,
+    repository: Option<Arc<dyn ScreeningRepository>>,
+    data_source: Option<Arc<dyn ScreeningDataSource>>,
     exchange: String,
     screening_interval: Duration,
     tx: mpsc::Sender<ScreeningResults>,
@@ -44,19 +55,35 @@ impl ScreeningActor {
         ScreeningActor {
             service: SymbolScreeningService::with_default_cache_ttl(),
             repository: None,
+            data_source: None,
             exchange,
             screening_interval,
             tx,
         }
     }
 
-    pub fn with_repository(mut self, repository: ScreeningRepository) -> Self {
+    pub fn with_repository(mut self, repository: Arc<dyn ScreeningRepository>) -> Self {
         self.repository = Some(repository);
         self
     }
 
+    pub fn with_data_source(mut self, data_source: Arc<dyn ScreeningDataSource>) -> Self {
+        self.data_source = Some(data_source);
+        self
+    }
+
+    pub fn with_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.service = self.service.with_cache_ttl(cache_ttl);
+        self
+    }
+
+    pub fn with_aggregator(mut self, aggregator: ScalpingPotentialAggregator) -> Self {
+        self.service = self.service.with_aggregator(aggregator);
+        self
+    }
+
     /// Run the screening actor
-    pub async fn run(mut self, mut rx: mpsc::Receiver<ScreeningMessage>) {
+    pub async fn run(self, mut rx: mpsc::Receiver<ScreeningMessage>) {
         info!(
             "Starting screening actor for {} with interval {:?}",
             self.exchange, self.screening_interval
@@ -94,23 +121,57 @@ impl ScreeningActor {
         }
     }
 
-    /// Perform screening (to be called with actual market data)
-    async fn perform_screening(&self, _symbols_filter: Option<Vec<String>>) {
-        // In production, this would:
-        // 1. Get current market data from exchanges
-        // 2. Screen symbols
-        // 3. Persist results
-        // 4. Send via tx channel
+    /// Screen the symbols of the data source, optionally restricted to
+    /// `symbols_filter`, then persist and publish the ranked results
+    ///
+    /// Only freshly scored results are persisted; results served from the
+    /// service cache were saved by an earlier run.
+    async fn perform_screening(&self, symbols_filter: Option<Vec<String>>) {
+        let Some(data_source) = &self.data_source else {
+            debug!(
+                "No market data source for {}, skipping screening",
+                self.exchange
+            );
+            return;
+        };
+
+        let mut symbols_data = data_source.market_data(&self.exchange).await;
+        if let Some(filter) = symbols_filter {
+            symbols_data.retain(|symbol, _| filter.contains(symbol));
+        }
+        if symbols_data.is_empty() {
+            debug!("No market data to screen on {}", self.exchange);
+            return;
+        }
+
+        let started = Utc::now();
+        let results = self
+            .service
+            .screen_all_symbols(self.exchange.clone(), symbols_data)
+            .await;
 
-        // For now, this is a placeholder for the full implementation
-        // that would integrate with exchange clients
+        if let Some(repository) = &self.repository {
+            for result in results.iter().filter(|r| r.screened_at >= started) {
+                if let Err(e) = repository.save(result).await {
+                    warn!("Failed to persist screening of {}: {}", result.symbol, e);
+                }
+            }
+        }
+
+        let screening = ScreeningResults {
+            exchange: self.exchange.clone(),
+            results,
+        };
+        if let Err(e) = self.tx.send(screening).await {
+            error!("Failed to publish screening results: {}", e);
+        }
     }
 }
 
 /// Spawn a screening actor for an exchange
 pub async fn spawn_screening_actor(
     exchange: String,
-    repository: Option<ScreeningRepository>,
+    repository: Option<Arc<dyn ScreeningRepository>>,
     screening_interval: Duration,
 ) -> (
     mpsc::Sender<ScreeningMessage>,
@@ -134,6 +195,74 @@ pub async fn spawn_screening_actor(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::services::indicators::Candle;
+    use crate::domain::value_objects::price::Price;
+
+    struct FixedData(HashMap<String, SymbolMarketData>);
+
+    #[async_trait]
+    impl ScreeningDataSource for FixedData {
+        async fn market_data(&self, _exchange: &str) -> HashMap<String, SymbolMarketData> {
+            self.0.clone()
+        }
+    }
+
+    fn market_data(swing: f64) -> SymbolMarketData {
+        let candles: Vec<Candle> = (0..20)
+            .map(|i| {
+                let close = 100.0 + i as f64 * swing;
+                Candle {
+                    open: Price::new(close).unwrap(),
+                    high: Price::new(close * (1.0 + swing / 100.0)).unwrap(),
+                    low: Price::new(close * (1.0 - swing / 100.0)).unwrap(),
+                    close: Price::new(close).unwrap(),
+                    volume: 1000.0 * (i + 1) as f64,
+                }
+            })
+            .collect();
+        SymbolMarketData {
+            volumes: candles.iter().map(|c| c.volume).collect(),
+            candles,
+            bid: 100.0,
+            ask: 100.05,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_screening_actor_scores_data_source_symbols() {
+        let source = FixedData(HashMap::from([
+            ("BTC-USD".to_string(), market_data(1.0)),
+            ("ETH-USD".to_string(), market_data(0.1)),
+        ]));
+        let (tx, mut results_rx) = mpsc::channel(10);
+        let actor = ScreeningActor::new("coinbase".to_string(), Duration::from_secs(3600), tx)
+            .with_data_source(Arc::new(source));
+        let (msg_tx, msg_rx) = mpsc::channel(10);
+        tokio::spawn(actor.run(msg_rx));
+
+        // The first tick screens every symbol, ranked by score
+        let screening = tokio::time::timeout(Duration::from_secs(5), results_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(screening.exchange, "coinbase");
+        assert_eq!(screening.results.len(), 2);
+        assert!(screening.results[0].overall_score >= screening.results[1].overall_score);
+
+        msg_tx
+            .send(ScreeningMessage::ScreenSymbols(vec!["ETH-USD".to_string()]))
+            .await
+            .unwrap();
+        let screening = tokio::time::timeout(Duration::from_secs(5), results_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(screening.results.len(), 1);
+        assert_eq!(screening.results[0].symbol, "ETH-USD");
+
+        msg_tx.send(ScreeningMessage::Shutdown).await.unwrap();
+    }
 
     #[tokio::test]
     async fn test_screening_actor_spawning() {
@@ -7,9 +7,11 @@
 //! - Clean separation from exchange actors
 //! - Easy scaling (multiple traders per exchange)
 
+use crate::domain::entities::bracket_order::{BracketExitIds, BracketOrder};
 use crate::domain::entities::exchange::Exchange;
 use crate::domain::entities::order::Order;
 use crate::domain::entities::trader::Trader;
+use crate::domain::repositories::exchange_client::{OrderFill, OrderStatus};
 use crate::domain::services::strategies::TradingSignal;
 use crate::domain::value_objects::price::Price;
 use std::collections::HashMap;
@@ -34,6 +36,26 @@ pub enum TraderMessage {
         order: Order,
         reply: mpsc::Sender<Result<String, String>>,
     },
+    /// Place bracket exits natively (replies `None` if they must be emulated)
+    PlaceBracketExits {
+        bracket: BracketOrder,
+        reply: mpsc::Sender<Result<Option<BracketExitIds>, String>>,
+    },
+    /// Cancel an order on the active exchange
+    CancelOrder {
+        order_id: String,
+        reply: mpsc::Sender<Result<(), String>>,
+    },
+    /// Get order status from the active exchange
+    GetOrderStatus {
+        order_id: String,
+        reply: mpsc::Sender<Result<OrderStatus, String>>,
+    },
+    /// Get order status and filled quantity from the active exchange
+    GetOrderFill {
+        order_id: String,
+        reply: mpsc::Sender<Result<OrderFill, String>>,
+    },
     /// Set active exchange
     SetActiveExchange {
         exchange: Exchange,
@@ -197,6 +219,39 @@ impl TraderActor {
                     }
                 }
 
+                TraderMessage::PlaceBracketExits { bracket, reply } => {
+                    debug!(
+                        "Trader {} received PlaceBracketExits for {}",
+                        self.stats.id, bracket.id
+                    );
+                    let result = self.trader.place_bracket_exits(&bracket).await;
+                    if let Err(e) = reply.send(result).await {
+                        error!("Failed to send PlaceBracketExits reply: {:?}", e);
+                    }
+                }
+
+                TraderMessage::CancelOrder { order_id, reply } => {
+                    debug!("Trader {} cancelling order {}", self.stats.id, order_id);
+                    let result = self.trader.cancel_order(&order_id).await;
+                    if let Err(e) = reply.send(result).await {
+                        error!("Failed to send CancelOrder reply: {:?}", e);
+                    }
+                }
+
+                TraderMessage::GetOrderStatus { order_id, reply } => {
+                    let result = self.trader.get_order_status(&order_id).await;
+                    if let Err(e) = reply.send(result).await {
+                        error!("Failed to send GetOrderStatus reply: {:?}", e);
+                    }
+                }
+
+                TraderMessage::GetOrderFill { order_id, reply } => {
+                    let result = self.trader.get_order_fill(&order_id).await;
+                    if let Err(e) = reply.send(result).await {
+                        error!("Failed to send GetOrderFill reply: {:?}", e);
+                    }
+                }
+
                 TraderMessage::SetActiveExchange { exchange, reply } => {
                     debug!(
                         "Trader {} setting active exchange to {}",
@@ -344,6 +399,7 @@ mod tests {
         let signal = TradingSignal {
             signal: Signal::Buy,
             confidence: 0.8,
+            ..Default::default()
         };
 
         let (reply_tx, mut reply_rx) = mpsc::channel(1);
@@ -381,6 +437,7 @@ mod tests {
         let signal = TradingSignal {
             signal: Signal::Buy,
             confidence: 0.8,
+            ..Default::default()
         };
 
         let (reply_tx, mut reply_rx) = mpsc::channel(1);
@@ -428,6 +485,7 @@ mod tests {
         let signal = TradingSignal {
             signal: Signal::Hold,
             confidence: 0.9,
+            ..Default::default()
         };
 
         let (reply_tx, mut reply_rx) = mpsc::channel(1);
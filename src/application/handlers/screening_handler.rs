@@ -4,8 +4,13 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 
-use crate::domain::entities::symbol_screening::RecommendationCategory;
+use crate::config::TradingConfig;
+use crate::domain::entities::symbol_screening::{RecommendationCategory, SymbolScreeningResult};
+use crate::persistence::screening_repository::ScreeningRepository;
 
 /// Query parameters for screening endpoint
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,11 +32,30 @@ pub struct ScreeningResultResponse {
     pub volume_score: f64,
     pub spread_score: f64,
     pub momentum_score: f64,
+    /// Every scorer's score, including the four above
+    pub component_scores: BTreeMap<String, f64>,
     pub overall_score: f64,
     pub recommendation: String,
     pub screened_at: String,
 }
 
+impl From<&SymbolScreeningResult> for ScreeningResultResponse {
+    fn from(result: &SymbolScreeningResult) -> Self {
+        ScreeningResultResponse {
+            symbol: result.symbol.clone(),
+            exchange: result.exchange.clone(),
+            volatility_score: result.volatility_score,
+            volume_score: result.volume_score,
+            spread_score: result.spread_score,
+            momentum_score: result.momentum_score,
+            component_scores: result.component_scores.clone(),
+            overall_score: result.overall_score,
+            recommendation: format!("{:?}", result.recommendation),
+            screened_at: result.screened_at.to_rfc3339(),
+        }
+    }
+}
+
 /// API response for screening results
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ScreeningResponse {
@@ -48,139 +72,244 @@ pub struct ErrorResponse {
     pub error: String,
 }
 
-/// Get ranked symbols for an exchange
-pub async fn get_screening_results(
-    Path(exchange): Path<String>,
-    Query(params): Query<ScreeningQuery>,
-) -> Result<Json<ScreeningResponse>, (StatusCode, Json<ErrorResponse>)> {
-    // Validate exchange
-    let valid_exchanges = vec!["dydx", "coinbase", "binance", "kraken", "hyperliquid"];
-    if !valid_exchanges.contains(&exchange.as_str()) {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Unknown exchange: {}", exchange),
-            }),
-        ));
+/// State shared by the screening endpoints and the task feeding them
+#[derive(Clone)]
+pub struct ScreeningState {
+    /// Exchange the screening actor scores
+    pub exchange: String,
+    /// Ranked results of the latest screening run
+    pub latest: Arc<RwLock<Vec<SymbolScreeningResult>>>,
+    /// Persisted screening history
+    pub repository: Option<Arc<dyn ScreeningRepository>>,
+}
+
+impl ScreeningState {
+    pub fn new(exchange: String, repository: Option<Arc<dyn ScreeningRepository>>) -> Self {
+        ScreeningState {
+            exchange,
+            latest: Arc::new(RwLock::new(Vec::new())),
+            repository,
+        }
     }
+}
+
+type ErrorReply = (StatusCode, Json<ErrorResponse>);
+
+fn error_reply(status: StatusCode, error: String) -> ErrorReply {
+    (status, Json(ErrorResponse { error }))
+}
 
-    // Parse pagination
+/// Parse a recommendation level, ignoring case and underscores
+fn parse_level(level: &str) -> Result<RecommendationCategory, ErrorReply> {
+    match level.replace('_', "").to_lowercase().as_str() {
+        "bestcandidate" => Ok(RecommendationCategory::BestCandidate),
+        "goodcandidate" => Ok(RecommendationCategory::GoodCandidate),
+        "faircandidate" => Ok(RecommendationCategory::FairCandidate),
+        "avoid" => Ok(RecommendationCategory::Avoid),
+        _ => Err(error_reply(
+            StatusCode::BAD_REQUEST,
+            format!("Unknown recommendation level: {}", level),
+        )),
+    }
+}
+
+/// Level filter, page and limit of a query
+fn parse_query(
+    params: &ScreeningQuery,
+) -> Result<(Option<RecommendationCategory>, u32, u32), ErrorReply> {
+    let level = params.level.as_deref().map(parse_level).transpose()?;
     let page = params.page.unwrap_or(1).max(1);
-    let limit = params.limit.unwrap_or(10).min(100).max(1);
+    let limit = params.limit.unwrap_or(10).clamp(1, 100);
+    Ok((level, page, limit))
+}
+
+fn page_of(
+    results: &[SymbolScreeningResult],
+    level: Option<RecommendationCategory>,
+    page: u32,
+    limit: u32,
+) -> (usize, Vec<ScreeningResultResponse>) {
+    let matching: Vec<&SymbolScreeningResult> = results
+        .iter()
+        .filter(|r| level.is_none_or(|level| r.recommendation == level))
+        .collect();
+    let start = ((page - 1) * limit) as usize;
+    let page = matching
+        .iter()
+        .skip(start)
+        .take(limit as usize)
+        .map(|r| ScreeningResultResponse::from(*r))
+        .collect();
+    (matching.len(), page)
+}
+
+/// Get the ranking of the latest screening run
+pub async fn get_screening_results(
+    State(state): State<ScreeningState>,
+    Query(params): Query<ScreeningQuery>,
+) -> Result<Json<ScreeningResponse>, ErrorReply> {
+    let (level, page, limit) = parse_query(&params)?;
+
+    let latest = state.latest.read().await;
+    let (total, results) = page_of(&latest, level, page, limit);
 
-    // For now, return empty response (to be filled by actual screening service)
     Ok(Json(ScreeningResponse {
-        exchange,
-        results: vec![],
-        total: 0,
+        exchange: state.exchange.clone(),
+        results,
+        total,
         page,
         limit,
     }))
 }
 
-/// Get screening result details for a specific symbol
+/// Get the screening history of a symbol, newest first
+///
+/// History comes from the repository when one is configured, otherwise
+/// from the latest run only.
 pub async fn get_symbol_screening_details(
-    Path((exchange, symbol)): Path<(String, String)>,
-) -> Result<Json<Vec<ScreeningResultResponse>>, (StatusCode, Json<ErrorResponse>)> {
-    // Validate exchange
-    let valid_exchanges = vec!["dydx", "coinbase", "binance", "kraken", "hyperliquid"];
-    if !valid_exchanges.contains(&exchange.as_str()) {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Unknown exchange: {}", exchange),
-            }),
-        ));
-    }
+    State(state): State<ScreeningState>,
+    Path(symbol): Path<String>,
+    Query(params): Query<ScreeningQuery>,
+) -> Result<Json<Vec<ScreeningResultResponse>>, ErrorReply> {
+    let (level, page, limit) = parse_query(&params)?;
+    let symbol = TradingConfig::normalize_symbol(&symbol);
 
-    // For now, return empty response (to be filled by actual screening service)
-    Ok(Json(vec![]))
-}
+    let history = match &state.repository {
+        Some(repository) => repository
+            .get_by_symbol_and_exchange(&symbol, &state.exchange, level, i64::from(page * limit))
+            .await
+            .map_err(|e| {
+                error_reply(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to load screening history: {}", e),
+                )
+            })?,
+        None => Vec::new(),
+    };
+    let history = if history.is_empty() {
+        let latest = state.latest.read().await;
+        latest
+            .iter()
+            .filter(|r| r.symbol == symbol)
+            .cloned()
+            .collect()
+    } else {
+        history
+    };
 
-/// Get screening history for a symbol
-pub async fn get_screening_history(
-    Path((exchange, symbol)): Path<(String, String)>,
-    Query(params): Query<ScreeningQuery>,
-) -> Result<Json<Vec<ScreeningResultResponse>>, (StatusCode, Json<ErrorResponse>)> {
-    // Validate exchange
-    let valid_exchanges = vec!["dydx", "coinbase", "binance", "kraken", "hyperliquid"];
-    if !valid_exchanges.contains(&exchange.as_str()) {
-        return Err((
+    if history.is_empty() {
+        return Err(error_reply(
             StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Unknown exchange: {}", exchange),
-            }),
+            format!("No screening results for {}", symbol),
         ));
     }
 
-    // For now, return empty response
-    Ok(Json(vec![]))
+    Ok(Json(page_of(&history, level, page, limit).1))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn query(level: Option<&str>, page: Option<u32>, limit: Option<u32>) -> ScreeningQuery {
+        ScreeningQuery {
+            level: level.map(str::to_string),
+            page,
+            limit,
+        }
+    }
+
+    async fn state() -> ScreeningState {
+        let state = ScreeningState::new("dydx".to_string(), None);
+        *state.latest.write().await = [
+            ("BTC-USD", 0.9),
+            ("ETH-USD", 0.8),
+            ("SOL-USD", 0.55),
+            ("DOGE-USD", 0.2),
+        ]
+        .into_iter()
+        .map(|(symbol, score)| {
+            SymbolScreeningResult::new(
+                symbol.to_string(),
+                "dydx".to_string(),
+                score,
+                score,
+                score,
+                score,
+            )
+        })
+        .collect();
+        state
+    }
+
     #[tokio::test]
     async fn test_api_endpoint_get_ranked_symbols() {
-        let result = get_screening_results(
-            Path("dydx".to_string()),
-            Query(ScreeningQuery {
-                level: None,
-                page: None,
-                limit: None,
-            }),
-        )
-        .await;
+        let result =
+            get_screening_results(State(state().await), Query(query(None, None, None))).await;
 
         assert!(result.is_ok());
         let response = result.unwrap().0;
         assert_eq!(response.exchange, "dydx");
+        assert_eq!(response.total, 4);
+        assert_eq!(response.results[0].symbol, "BTC-USD");
+        assert_eq!(response.results[0].recommendation, "BestCandidate");
     }
 
     #[tokio::test]
     async fn test_api_endpoint_error_handling() {
         let result = get_screening_results(
-            Path("invalid_exchange".to_string()),
-            Query(ScreeningQuery {
-                level: None,
-                page: None,
-                limit: None,
-            }),
+            State(state().await),
+            Query(query(Some("great"), None, None)),
         )
         .await;
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().0, StatusCode::BAD_REQUEST);
 
+        let result = get_symbol_screening_details(
+            State(state().await),
+            Path("XRP-USD".to_string()),
+            Query(query(None, None, None)),
+        )
+        .await;
         assert!(result.is_err());
         assert_eq!(result.err().unwrap().0, StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
-    async fn test_api_endpoint_pagination() {
+    async fn test_api_endpoint_level_filter() {
         let result = get_screening_results(
-            Path("dydx".to_string()),
-            Query(ScreeningQuery {
-                level: None,
-                page: Some(2),
-                limit: Some(20),
-            }),
+            State(state().await),
+            Query(query(Some("best_candidate"), None, None)),
         )
         .await;
 
+        let response = result.unwrap().0;
+        assert_eq!(response.total, 2);
+        assert!(response
+            .results
+            .iter()
+            .all(|r| r.recommendation == "BestCandidate"));
+    }
+
+    #[tokio::test]
+    async fn test_api_endpoint_pagination() {
+        let result =
+            get_screening_results(State(state().await), Query(query(None, Some(2), Some(3)))).await;
+
         assert!(result.is_ok());
         let response = result.unwrap().0;
         assert_eq!(response.page, 2);
-        assert_eq!(response.limit, 20);
+        assert_eq!(response.limit, 3);
+        assert_eq!(response.total, 4);
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].symbol, "DOGE-USD");
     }
 
     #[tokio::test]
     async fn test_api_endpoint_pagination_limits() {
         let result = get_screening_results(
-            Path("dydx".to_string()),
-            Query(ScreeningQuery {
-                level: None,
-                page: Some(0),    // Should be clamped to 1
-                limit: Some(200), // Should be clamped to 100
-            }),
+            State(state().await),
+            Query(query(None, Some(0), Some(200))), // Clamped to page 1, limit 100
         )
         .await;
 
@@ -189,4 +318,18 @@ mod tests {
         assert_eq!(response.page, 1);
         assert_eq!(response.limit, 100);
     }
+
+    #[tokio::test]
+    async fn test_symbol_details_from_latest_run() {
+        let result = get_symbol_screening_details(
+            State(state().await),
+            Path("ETH-USD".to_string()),
+            Query(query(None, None, None)),
+        )
+        .await;
+
+        let history = result.unwrap().0;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].symbol, "ETH-USD");
+    }
 }
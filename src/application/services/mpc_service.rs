@@ -1,21 +1,73 @@
+mod arbitrage;
+mod dynamic_universe;
+mod market_making;
+mod pairs_trading;
+mod position_reconciliation;
+#[cfg(test)]
+mod test_support;
+
+use crate::application::actors::reconciliation_actor::ReconciliationMessage;
+use crate::application::actors::screening_actor::ScreeningDataSource;
 use crate::application::actors::trader_actor::TraderMessage;
 use crate::config::TradingConfig;
+use crate::domain::entities::bracket_order::{BracketLegRole, BracketLinking, BracketOrder};
 use crate::domain::entities::exchange::Exchange;
-use crate::domain::entities::order::Order;
-use crate::domain::entities::position::{Position, PositionSide};
+use crate::domain::entities::order::{Order, OrderSide, OrderType};
+use crate::domain::entities::position::{ExitReason, Position, PositionSide};
+use crate::domain::entities::position_group::PositionGroup;
+use crate::domain::entities::symbol_screening::{RecommendationCategory, SymbolScreeningResult};
 use crate::domain::errors::MpcError;
+use crate::domain::repositories::exchange_client::{OrderFill, OrderStatus};
+use crate::domain::repositories::funding_source::FundingSource;
+use crate::domain::repositories::market_discovery::MarketDiscovery;
+use crate::domain::services::arbitrage::{
+    best_divergence, can_route_orders, ArbitrageExecution, ArbitrageOutcome, PriceDivergence,
+};
 use crate::domain::services::candle_builder::CandleBuilder;
+use crate::domain::services::indicators::{Indicator, ATR};
+use crate::domain::services::market_making::{MarketMaker, QuoteSide, SymbolQuotes, WorkingQuote};
+use crate::domain::services::market_regime::{realized_volatility, MarketRegime, RegimeReading};
 use crate::domain::services::metrics::{
-    AlertConfig, PerformanceProfiler, StrategyMetrics, SystemAlert, SystemHealthMetrics,
+    AlertConfig, AlertType, PerformanceProfiler, StrategyMetrics, SystemAlert, SystemHealthMetrics,
     TradingMetrics,
 };
+use crate::domain::services::pairs_trading::{
+    GroupAction, MultiAssetSignal, MultiAssetStrategy, PairsStrategy,
+};
+use crate::domain::services::paper_trading::{PaperBook, PaperLimits, PaperSummary};
+use crate::domain::services::pnl_ledger::{
+    Fill, FillSide, FundingPayment, PnlBreakdown, PnlGroup, PnlLedger, PnlReport, RealizedEntry,
+};
+use crate::domain::services::portfolio_manager::{Inventory, PortfolioManager};
+use crate::domain::services::position_sizer::PositionSizer;
+use crate::domain::services::reconciliation::{
+    reconcile_positions, DiscrepancySeverity, OwnActivity, PositionReconciliationReport,
+    ReconciliationReport, ReconciliationStatus, VenueOrder,
+};
+use crate::domain::services::screening::ScalpingPotentialAggregator;
 use crate::domain::services::strategies::{SignalCombiner, TradingSignal};
+use crate::domain::services::strategy_registry::{GroupDefinition, StrategyRegistry, StrategySpec};
+use crate::domain::services::symbol_screening::SymbolMarketData;
+use crate::domain::services::symbol_universe::{MarketSnapshot, SymbolUniverse, UniverseMember};
+use crate::domain::services::weight_allocator::{AllocatorState, WeightAllocator};
 use crate::domain::value_objects::pnl::PnL;
+use crate::domain::value_objects::position_sizing::{SizingInputs, SizingMode};
 use crate::domain::value_objects::price::Price;
 use crate::domain::value_objects::quantity::Quantity;
 use crate::infrastructure::adapters::exchange_actor::ExchangeMessage;
+use crate::persistence::models::{
+    CreateAuditLog, CreateFundingPayment, CreatePosition, CreateSignal, CreateTrade,
+    FundingPaymentRecord, PositionGroupRecord, PositionRecord, SignalQuery, SignalRecord,
+    SignalSkipReason, TradeRecord,
+};
+use crate::persistence::reconciliation_audit::ReconciliationRepository;
+use crate::persistence::repository::{
+    AllocatorStateRepository, AuditLogRepository, BracketOrderRepository,
+    DydxOrderMetadataRepository, FundingPaymentRepository, PositionRepository, SignalRepository,
+    TradeRepository,
+};
 use lru::LruCache;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -29,9 +81,18 @@ const CHANNEL_REPLY_TIMEOUT: Duration = Duration::from_secs(5);
 /// Candle interval in seconds (10 seconds for faster signal generation)
 const CANDLE_INTERVAL_SECS: u64 = 10;
 
+/// Candles per year at the candle interval, used to annualise volatility
+const CANDLES_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0 / CANDLE_INTERVAL_SECS as f64;
+
 /// Number of candles to keep in history
 const CANDLE_HISTORY_SIZE: usize = 100;
 
+/// Strategy of the trades recorded for arbitrage fills
+const ARBITRAGE_STRATEGY: &str = "Arbitrage";
+
+/// Strategy of the trades recorded for market making fills
+const MARKET_MAKING_STRATEGY: &str = "MarketMaking";
+
 /// LRU cache capacity for last signals
 const SIGNAL_CACHE_CAPACITY: usize = 1000;
 
@@ -41,19 +102,144 @@ const MIN_CANDLES_FOR_SIGNAL: usize = 5;
 /// Minimum quantity to avoid dust orders
 const MIN_ORDER_QUANTITY: f64 = 0.0001;
 
+/// Row of `allocator_state` holding the strategy weight allocator
+const WEIGHT_ALLOCATOR_STATE: &str = "strategy_weights";
+
+/// Collateral currency perp venues settle PnL, fees and funding in
+const PERP_COLLATERAL: &str = "USD";
+
 /// ## Lock Ordering Convention (to prevent deadlocks)
 ///
 /// Always acquire locks in this order:
 /// 1. signal_combiner (RwLock)
-/// 2. strategy_order (Mutex)
-/// 3. strategy_metrics (Mutex)
-/// 4. traders (Mutex)
-/// 5. Other Mutexes (alphabetically: active_alerts, candle_builder, last_signals,
-///    open_positions, performance_profiler, system_health, trade_history, trading_metrics)
+/// 2. group_combiners (RwLock)
+/// 3. strategy_order (Mutex)
+/// 4. strategy_metrics (Mutex)
+/// 5. traders (Mutex)
+/// 6. Other Mutexes (alphabetically: active_alerts, arbitrage_executions,
+///    candle_builder, last_signals, market_quotes, market_regimes, open_positions,
+///    own_activity, paused_currencies, pending_signal_ids, performance_profiler,
+///    pnl_ledger, portfolio_manager, position_groups, price_divergences, shadows,
+///    symbol_categories, symbol_strategy_metrics, symbol_universes, system_health,
+///    trade_history, trading_metrics, weight_allocator)
 ///
 /// IMPORTANT: Release locks as soon as possible to minimize contention.
 /// Clone data if needed to release locks early.
 
+/// Weights of one combiner before and after an adjustment
+struct WeightChange {
+    group: String,
+    strategies: Vec<String>,
+    previous: Vec<f64>,
+    weights: Vec<f64>,
+}
+
+/// Adjust one combiner's weights, returning the change if any weight moved
+fn reweight(
+    group: &str,
+    combiner: &mut SignalCombiner,
+    allocator: Option<&mut WeightAllocator>,
+    strategy_metrics: &HashMap<String, StrategyMetrics>,
+) -> Result<Option<WeightChange>, String> {
+    let strategies = combiner.get_strategy_names();
+    let previous = combiner.weights().to_vec();
+    match allocator {
+        Some(allocator) => {
+            let min_change = allocator.min_change();
+            let weights = allocator.allocate(&strategies)?;
+            let moved = previous.len() != weights.len()
+                || previous
+                    .iter()
+                    .zip(&weights)
+                    .any(|(old, new)| (old - new).abs() >= min_change);
+            // Smaller moves are sampling noise rather than new information
+            if moved {
+                combiner.set_weights(weights)?;
+            }
+        }
+        None => {
+            let metrics: Vec<StrategyMetrics> = strategies
+                .iter()
+                .map(|name| {
+                    strategy_metrics
+                        .get(name)
+                        .cloned()
+                        .unwrap_or_else(|| StrategyMetrics::new(name.clone()))
+                })
+                .collect();
+            combiner.adjust_weights(&metrics)?;
+        }
+    }
+
+    let weights = combiner.weights().to_vec();
+    let moved = previous
+        .iter()
+        .zip(&weights)
+        .any(|(old, new)| (old - new).abs() > 1e-6);
+    Ok(moved.then(|| WeightChange {
+        group: group.to_string(),
+        strategies,
+        previous,
+        weights,
+    }))
+}
+
+/// Whether an execution attempt failed temporarily and its signal should be retried
+fn is_retryable<T>(result: &Result<T, MpcError>) -> bool {
+    matches!(
+        result,
+        Err(MpcError::ChannelSendError(_)) | Err(MpcError::Timeout)
+    )
+}
+
+/// What became of a signal handed to `execute_order_from_signal`
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignalExecution {
+    /// An order was placed
+    Executed(String),
+    /// The signal was evaluated but no order was placed
+    Skipped {
+        reason: SignalSkipReason,
+        message: String,
+    },
+}
+
+impl SignalExecution {
+    fn skipped(reason: SignalSkipReason, message: String) -> Self {
+        SignalExecution::Skipped { reason, message }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            SignalExecution::Executed(message) => message,
+            SignalExecution::Skipped { message, .. } => message,
+        }
+    }
+
+    /// Why no order was placed, None when one was
+    pub fn skip_reason(&self) -> Option<SignalSkipReason> {
+        match self {
+            SignalExecution::Executed(_) => None,
+            SignalExecution::Skipped { reason, .. } => Some(*reason),
+        }
+    }
+}
+
+/// Classify the result of `execute_order_from_signal` for the signal history
+///
+/// Returns None when an order was placed.
+fn signal_skip_reason(result: &Result<SignalExecution, MpcError>) -> Option<SignalSkipReason> {
+    match result {
+        Ok(execution) => execution.skip_reason(),
+        Err(MpcError::TradeLimitExceeded(_)) => Some(SignalSkipReason::Limits),
+        Err(MpcError::PositionSizingRefused(_)) => Some(SignalSkipReason::Risk),
+        Err(MpcError::InvalidInput(_)) | Err(MpcError::InvalidConfiguration(_)) => {
+            Some(SignalSkipReason::Rejected)
+        }
+        Err(_) => Some(SignalSkipReason::Failed),
+    }
+}
+
 /// Validate trading symbol format
 ///
 /// Symbols must be:
@@ -80,6 +266,92 @@ fn validate_symbol(symbol: &str) -> Result<(), MpcError> {
     Ok(())
 }
 
+/// Ledger fill of a persisted trade, `None` for an unknown side
+fn ledger_fill(record: &TradeRecord) -> Option<Fill> {
+    Some(Fill {
+        id: record.id.clone(),
+        symbol: record.symbol.clone(),
+        exchange: record.exchange.clone(),
+        strategy: record.strategy.clone(),
+        side: FillSide::from_name(&record.side)?,
+        price: record.price,
+        quantity: record.quantity,
+        fee: record.fee,
+        fee_estimated: record.fee_estimated,
+        executed_at: record.executed_at,
+    })
+}
+
+/// Ledger payment of a persisted funding payment
+fn ledger_funding(record: FundingPaymentRecord) -> FundingPayment {
+    FundingPayment {
+        symbol: record.symbol,
+        exchange: record.exchange,
+        strategy: Some(record.strategy),
+        amount: record.amount,
+        paid_at: record.paid_at,
+    }
+}
+
+/// Combiner serving the symbols, globs or screening categories of a group
+/// instead of the default combiner
+pub struct GroupCombiner {
+    pub group: GroupDefinition, // Symbols normalized
+    pub combiner: SignalCombiner,
+}
+
+/// Pick the group serving a symbol: exact symbol, then glob, then screening
+/// category; the first group wins among equal matches
+fn select_group<'a>(
+    groups: &'a [GroupCombiner],
+    symbol: &str,
+    category: Option<RecommendationCategory>,
+) -> Option<&'a GroupCombiner> {
+    groups
+        .iter()
+        .filter_map(|g| g.group.matches(symbol, category).map(|m| (m, g)))
+        .min_by_key(|(m, _)| *m)
+        .map(|(_, g)| g)
+}
+
+/// Combiner that paper-trades next to the live ones without placing orders
+pub struct ShadowCombiner {
+    pub group: GroupDefinition, // Symbols normalized; no symbols or categories = every symbol
+    pub combiner: SignalCombiner,
+    pub book: PaperBook,
+}
+
+impl ShadowCombiner {
+    fn serves(&self, symbol: &str, category: Option<RecommendationCategory>) -> bool {
+        self.group.is_default() || self.group.matches(symbol, category).is_some()
+    }
+}
+
+/// Paper results of a shadow combiner, for reporting
+#[derive(Debug, Clone)]
+pub struct ShadowReport {
+    pub name: String,
+    pub symbols: Vec<String>,
+    pub categories: Vec<RecommendationCategory>,
+    pub min_confidence_threshold: Option<f64>, // None = live threshold of each symbol
+    pub weights: Vec<(String, f64)>,
+    pub signals_generated: u32,
+    pub signals_executed: u32,
+    pub results: PaperSummary,
+    pub live: PaperSummary, // Live results on the symbols the shadow serves
+}
+
+/// Current weights of a symbol group, for reporting
+#[derive(Debug, Clone)]
+pub struct StrategyGroupWeights {
+    pub name: String,
+    pub symbols: Vec<String>, // Empty for the default group
+    pub categories: Vec<RecommendationCategory>,
+    pub min_confidence_threshold: f64, // Effective threshold
+    pub weights: Vec<(String, f64)>,
+    pub regimes: BTreeMap<MarketRegime, BTreeMap<String, f64>>, // Weight multipliers per regime
+}
+
 /// Portfolio state tracking
 #[derive(Debug, Clone)]
 pub struct PortfolioState {
@@ -107,23 +379,80 @@ impl Default for PortfolioState {
     }
 }
 
+/// State rebuilt from the database by the startup recovery phase
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct RecoverySummary {
+    /// Open positions reloaded with their stops
+    pub positions_restored: usize,
+    /// Restored positions left without a stop-loss
+    pub positions_without_stop: Vec<String>,
+    /// Entry value of the restored positions
+    pub position_value: f64,
+    /// Opening trades of the last 24 hours replayed into the trade limits
+    pub trades_replayed: usize,
+    /// Active dYdX orders whose status was re-queried
+    pub orders_checked: usize,
+    pub orders_filled: usize,
+    /// Cancelled, rejected or expired while the service was down
+    pub orders_closed: usize,
+    /// Still working on the venue
+    pub orders_open: usize,
+    /// No trader could tell, left active
+    pub orders_unverified: usize,
+    /// Position groups relinked from their restored legs
+    pub groups_restored: usize,
+    /// Filled orders no recorded trade or bracket accounts for
+    pub untracked_fills: Vec<String>,
+}
+
 pub struct MpcService {
     pub senders: Arc<HashMap<Exchange, mpsc::Sender<ExchangeMessage>>>, // Exchange actors for market data
     pub traders: Arc<Mutex<HashMap<String, mpsc::Sender<TraderMessage>>>>, // Trader actors for execution
     pub signal_combiner: Arc<RwLock<Option<SignalCombiner>>>, // RwLock for better concurrency
+    pub group_combiners: Arc<RwLock<Vec<GroupCombiner>>>, // Per symbol group, override signal_combiner
+    pub shadows: Arc<Mutex<Vec<ShadowCombiner>>>, // Paper-traded combiners, never place orders
+    pub strategy_registry: Arc<RwLock<Option<StrategyRegistry>>>, // Source of the active strategies
     pub candle_builder: Arc<Mutex<CandleBuilder>>,
     pub last_signals: Arc<Mutex<LruCache<String, TradingSignal>>>, // LRU cache to prevent unbounded growth
     pub open_positions: Arc<Mutex<HashMap<String, Position>>>,
+    pub position_groups: Arc<Mutex<HashMap<String, PositionGroup>>>, // Linked legs of multi-asset strategies
     pub config: TradingConfig,
     pub trade_history: Arc<Mutex<Vec<(SystemTime, String)>>>, // (timestamp, symbol) for rate limiting
     pub trading_metrics: Arc<Mutex<TradingMetrics>>,
+    pub pnl_ledger: Arc<Mutex<PnlLedger>>, // Lots and realized results of persisted trades
     pub system_health: Arc<Mutex<SystemHealthMetrics>>,
     pub strategy_metrics: Arc<Mutex<HashMap<String, StrategyMetrics>>>,
+    pub symbol_strategy_metrics: Arc<Mutex<HashMap<(String, String), StrategyMetrics>>>, // (strategy, symbol)
+    pub symbol_categories: Arc<Mutex<HashMap<String, RecommendationCategory>>>, // Latest screening category
+    pub symbol_universes: Arc<Mutex<HashMap<Exchange, SymbolUniverse>>>, // Screening-driven subscriptions
+    pub market_regimes: Arc<Mutex<HashMap<String, RegimeReading>>>,      // Latest regime per symbol
     pub strategy_order: Arc<Mutex<Vec<String>>>,
     pub alert_config: AlertConfig,
     pub active_alerts: Arc<Mutex<Vec<SystemAlert>>>,
     pub performance_profiler: Arc<Mutex<PerformanceProfiler>>,
     pub portfolio_state: Arc<Mutex<PortfolioState>>, // Real-time portfolio tracking
+    pub portfolio_manager: Arc<Mutex<PortfolioManager>>, // Inventory built up by market making fills
+    pub market_quotes: Arc<Mutex<HashMap<String, SymbolQuotes>>>, // Resting market making quotes by symbol
+    pub price_divergences: Arc<Mutex<HashMap<String, PriceDivergence>>>, // Widest cross-exchange spread by symbol
+    pub arbitrage_executions: Arc<Mutex<Vec<ArbitrageExecution>>>, // Recent arbitrage attempts, oldest first
+    pub position_repository: Option<Arc<dyn PositionRepository>>, // Position persistence (optional)
+    pub trade_repository: Option<Arc<dyn TradeRepository>>,       // Trade persistence (optional)
+    pub funding_repository: Option<Arc<dyn FundingPaymentRepository>>, // Funding payments replayed into the PnL ledger (optional)
+    pub brackets: Arc<Mutex<HashMap<String, BracketOrder>>>,  // Linked SL/TP exits by bracket id
+    pub bracket_repository: Option<Arc<dyn BracketOrderRepository>>, // Bracket leg persistence (optional)
+    pub signal_repository: Option<Arc<dyn SignalRepository>>, // Signal history persistence (optional)
+    pub pending_signal_ids: Arc<Mutex<HashMap<String, i64>>>, // Symbol -> signal row awaiting a decision
+    pub weight_allocator: Arc<Mutex<Option<WeightAllocator>>>, // Bandit weights, None for performance scores
+    pub allocator_repository: Option<Arc<dyn AllocatorStateRepository>>, // Allocator state persistence (optional)
+    pub audit_repository: Option<Arc<dyn AuditLogRepository>>, // Weight change audit trail (optional)
+    pub market_discovery: Option<Arc<dyn MarketDiscovery>>, // Market lists for the dynamic universe (optional)
+    pub funding_source: Option<Arc<dyn FundingSource>>, // Funding payments pulled into the PnL ledger (optional)
+    pub reconciliation: Option<mpsc::Sender<ReconciliationMessage>>, // Balance reconciliation actor (optional)
+    pub reconciliation_repository: Option<Arc<dyn ReconciliationRepository>>, // Reconciliation audit trail (optional)
+    pub paused_currencies: Arc<Mutex<HashMap<String, Exchange>>>, // Currency -> exchange whose reconciliation paused it
+    pub own_activity: Arc<Mutex<HashMap<Exchange, OwnActivity>>>, // Balance flows of own fills since the last reconciliation
+    pub order_metadata_repository: Option<Arc<dyn DydxOrderMetadataRepository>>, // Active dYdX orders (optional)
+    pub position_reconciliations: Arc<Mutex<HashMap<Exchange, PositionReconciliationReport>>>, // Latest per exchange
 }
 
 impl MpcService {
@@ -137,27 +466,155 @@ impl MpcService {
         // LRU cache capacity for signal storage
         let cache_capacity =
             NonZeroUsize::new(SIGNAL_CACHE_CAPACITY).expect("Cache capacity must be non-zero");
+        let lot_matching = config.pnl_lot_matching;
 
         Self {
             senders: Arc::new(HashMap::new()),
             traders: Arc::new(Mutex::new(HashMap::new())),
             signal_combiner: Arc::new(RwLock::new(None)),
+            group_combiners: Arc::new(RwLock::new(Vec::new())),
+            shadows: Arc::new(Mutex::new(Vec::new())),
+            strategy_registry: Arc::new(RwLock::new(None)),
             candle_builder,
             last_signals: Arc::new(Mutex::new(LruCache::new(cache_capacity))),
             open_positions: Arc::new(Mutex::new(HashMap::new())),
+            position_groups: Arc::new(Mutex::new(HashMap::new())),
             config,
             trade_history: Arc::new(Mutex::new(Vec::new())),
             trading_metrics: Arc::new(Mutex::new(TradingMetrics::new())),
+            pnl_ledger: Arc::new(Mutex::new(PnlLedger::new(lot_matching))),
             system_health: Arc::new(Mutex::new(SystemHealthMetrics::new())),
             strategy_metrics: Arc::new(Mutex::new(HashMap::new())),
+            symbol_strategy_metrics: Arc::new(Mutex::new(HashMap::new())),
+            symbol_categories: Arc::new(Mutex::new(HashMap::new())),
+            symbol_universes: Arc::new(Mutex::new(HashMap::new())),
+            market_regimes: Arc::new(Mutex::new(HashMap::new())),
             strategy_order: Arc::new(Mutex::new(Vec::new())),
             alert_config: AlertConfig::default(),
             active_alerts: Arc::new(Mutex::new(Vec::new())),
             performance_profiler: Arc::new(Mutex::new(PerformanceProfiler::new())),
             portfolio_state: Arc::new(Mutex::new(PortfolioState::default())),
+            portfolio_manager: Arc::new(Mutex::new(PortfolioManager::new(
+                PortfolioState::default().total_value,
+            ))),
+            market_quotes: Arc::new(Mutex::new(HashMap::new())),
+            price_divergences: Arc::new(Mutex::new(HashMap::new())),
+            arbitrage_executions: Arc::new(Mutex::new(Vec::new())),
+            position_repository: None,
+            trade_repository: None,
+            funding_repository: None,
+            brackets: Arc::new(Mutex::new(HashMap::new())),
+            bracket_repository: None,
+            signal_repository: None,
+            pending_signal_ids: Arc::new(Mutex::new(HashMap::new())),
+            weight_allocator: Arc::new(Mutex::new(None)),
+            allocator_repository: None,
+            audit_repository: None,
+            market_discovery: None,
+            funding_source: None,
+            reconciliation: None,
+            reconciliation_repository: None,
+            paused_currencies: Arc::new(Mutex::new(HashMap::new())),
+            own_activity: Arc::new(Mutex::new(HashMap::new())),
+            order_metadata_repository: None,
+            position_reconciliations: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    /// Attach repositories so opened and closed positions are recorded in
+    /// `positions` and `trades`
+    ///
+    /// # Important
+    /// This must be called during initialization before the service is wrapped in Arc and shared
+    pub fn set_repositories(
+        &mut self,
+        positions: Arc<dyn PositionRepository>,
+        trades: Arc<dyn TradeRepository>,
+    ) {
+        self.position_repository = Some(positions);
+        self.trade_repository = Some(trades);
+    }
+
+    /// Attach a repository so funding payments entered in the PnL ledger
+    /// survive restarts
+    ///
+    /// # Important
+    /// This must be called during initialization before the service is wrapped in Arc and shared
+    pub fn set_funding_repository(&mut self, funding: Arc<dyn FundingPaymentRepository>) {
+        self.funding_repository = Some(funding);
+    }
+
+    /// Attach a repository so bracket leg state survives restarts
+    ///
+    /// # Important
+    /// This must be called during initialization before the service is wrapped in Arc and shared
+    pub fn set_bracket_repository(&mut self, brackets: Arc<dyn BracketOrderRepository>) {
+        self.bracket_repository = Some(brackets);
+    }
+
+    /// Attach a repository so every generated signal and its outcome is recorded
+    ///
+    /// # Important
+    /// This must be called during initialization before the service is wrapped in Arc and shared
+    pub fn set_signal_repository(&mut self, signals: Arc<dyn SignalRepository>) {
+        self.signal_repository = Some(signals);
+    }
+
+    /// Attach the exchange market lists screened by the dynamic universe
+    pub fn set_market_discovery(&mut self, discovery: Arc<dyn MarketDiscovery>) {
+        self.market_discovery = Some(discovery);
+    }
+
+    /// Attach the exchange whose funding payments `sync_funding_payments` pulls
+    pub fn set_funding_source(&mut self, source: Arc<dyn FundingSource>) {
+        self.funding_source = Some(source);
+    }
+
+    /// Attach the reconciliation actor and the audit trail its reports are
+    /// written to
+    ///
+    /// # Important
+    /// This must be called during initialization before the service is wrapped in Arc and shared
+    pub fn set_reconciliation(
+        &mut self,
+        actor: mpsc::Sender<ReconciliationMessage>,
+        repository: Arc<dyn ReconciliationRepository>,
+    ) {
+        self.reconciliation = Some(actor);
+        self.reconciliation_repository = Some(repository);
+    }
+
+    /// Attach the dYdX order metadata so position reconciliation compares our
+    /// active orders with the venue's open orders
+    ///
+    /// # Important
+    /// This must be called during initialization before the service is wrapped in Arc and shared
+    pub fn set_order_metadata_repository(&mut self, orders: Arc<dyn DydxOrderMetadataRepository>) {
+        self.order_metadata_repository = Some(orders);
+    }
+
+    /// Attach repositories so weight allocator state survives restarts and
+    /// every strategy weight change is written to the audit log
+    ///
+    /// # Important
+    /// This must be called during initialization before the service is wrapped in Arc and shared
+    pub fn set_weight_repositories(
+        &mut self,
+        allocator: Arc<dyn AllocatorStateRepository>,
+        audit: Arc<dyn AuditLogRepository>,
+    ) {
+        self.allocator_repository = Some(allocator);
+        self.audit_repository = Some(audit);
+    }
+
+    /// Attach the audit log on its own, without persisted allocator state
+    ///
+    /// # Important
+    /// This must be called during initialization before the service is wrapped in Arc and shared
+    pub fn set_audit_repository(&mut self, audit: Arc<dyn AuditLogRepository>) {
+        self.audit_repository = Some(audit);
+    }
+
     /// Add a trader actor
     pub async fn add_trader(&self, trader_id: String, sender: mpsc::Sender<TraderMessage>) {
         let mut traders = self.traders.lock().await;
@@ -223,7 +680,379 @@ impl MpcService {
             for name in strategy_names {
                 strategy_metrics.insert(name.clone(), StrategyMetrics::new(name));
             }
+
+            // Keep the indicators the strategies read up to date as candles complete
+            let mut builder = self.candle_builder.lock().await;
+            builder.register_indicators(&combiner.required_indicators());
+        }
+    }
+
+    /// Install the strategies and symbol groups of a registry
+    ///
+    /// The default group becomes the signal combiner; other groups get their
+    /// own combiner and weights for the symbols, globs and screening
+    /// categories they list. Every registered strategy is tracked in metrics
+    /// and trader selection, including ones only used by a group.
+    pub async fn apply_strategy_registry(&self, registry: StrategyRegistry) -> Result<(), String> {
+        let default_combiner = registry.build_combiner(registry.default_group())?;
+        let mut groups = Vec::new();
+        for group in registry.groups().iter().filter(|g| !g.is_default()) {
+            let mut normalized = group.clone();
+            normalized.symbols = group
+                .symbols
+                .iter()
+                .map(|s| TradingConfig::normalize_symbol(s))
+                .collect();
+            groups.push(GroupCombiner {
+                group: normalized,
+                combiner: registry.build_combiner(group)?,
+            });
+        }
+
+        self.set_signal_combiner(default_combiner).await;
+
+        {
+            let mut group_combiners = self.group_combiners.write().await;
+            let mut order = self.strategy_order.lock().await;
+            let mut strategy_metrics = self.strategy_metrics.lock().await;
+            let mut builder = self.candle_builder.lock().await;
+
+            *order = registry.strategy_names();
+            for name in order.iter() {
+                strategy_metrics
+                    .entry(name.clone())
+                    .or_insert_with(|| StrategyMetrics::new(name.clone()));
+            }
+            for group in &groups {
+                builder.register_indicators(&group.combiner.required_indicators());
+            }
+            *group_combiners = groups;
+        }
+        *self.weight_allocator.lock().await =
+            WeightAllocator::from_config(registry.allocator_config().clone());
+        self.shadows.lock().await.clear();
+        for shadow in registry.shadows() {
+            self.add_shadow_combiner(shadow.clone(), registry.build_combiner(shadow)?)
+                .await;
+        }
+
+        info!(
+            "Strategy registry applied: {} strategies, {} symbol groups, {} shadows, {} weight allocation",
+            registry.strategies().len(),
+            registry.groups().len(),
+            registry.shadows().len(),
+            registry.allocator_config().policy.as_str()
+        );
+        *self.strategy_registry.write().await = Some(registry);
+        Ok(())
+    }
+
+    /// Registered strategies and the current weights of every symbol group
+    pub async fn get_active_strategies(&self) -> (Vec<StrategySpec>, Vec<StrategyGroupWeights>) {
+        let signal_combiner_guard = self.signal_combiner.read().await;
+        let group_combiners = self.group_combiners.read().await;
+        let registry = self.strategy_registry.read().await;
+
+        let describe = |combiner: &SignalCombiner| {
+            combiner
+                .get_strategy_names()
+                .into_iter()
+                .zip(combiner.weights().iter().copied())
+                .collect::<Vec<_>>()
+        };
+
+        let default_group = registry.as_ref().map(|r| r.default_group());
+        let default_threshold = default_group
+            .and_then(|g| g.min_confidence_threshold)
+            .unwrap_or(self.config.min_confidence_threshold);
+
+        let mut groups = Vec::new();
+        if let Some(combiner) = signal_combiner_guard.as_ref() {
+            groups.push(StrategyGroupWeights {
+                name: default_group
+                    .map(|g| g.name.clone())
+                    .unwrap_or_else(|| "default".to_string()),
+                symbols: Vec::new(),
+                categories: Vec::new(),
+                min_confidence_threshold: default_threshold,
+                weights: describe(combiner),
+                regimes: default_group.map(|g| g.regimes.clone()).unwrap_or_default(),
+            });
+        }
+        groups.extend(group_combiners.iter().map(|g| {
+            StrategyGroupWeights {
+                name: g.group.name.clone(),
+                symbols: g.group.symbols.clone(),
+                categories: g.group.categories.clone(),
+                min_confidence_threshold: g
+                    .group
+                    .min_confidence_threshold
+                    .unwrap_or(default_threshold),
+                weights: describe(&g.combiner),
+                regimes: g.group.regimes.clone(),
+            }
+        }));
+
+        let strategies = registry
+            .as_ref()
+            .map(|r| r.strategies().to_vec())
+            .unwrap_or_default();
+        (strategies, groups)
+    }
+
+    /// Record the latest screening category of each screened symbol so
+    /// category-mapped strategy groups follow it
+    pub async fn update_symbol_categories(&self, results: &[SymbolScreeningResult]) {
+        let mut categories = self.symbol_categories.lock().await;
+        for result in results {
+            categories.insert(
+                TradingConfig::normalize_symbol(&result.symbol),
+                result.recommendation,
+            );
+        }
+    }
+
+    async fn symbol_category(&self, symbol: &str) -> Option<RecommendationCategory> {
+        self.symbol_categories.lock().await.get(symbol).copied()
+    }
+
+    /// Strategies of the combiner serving a symbol
+    pub async fn strategies_for_symbol(&self, symbol: &str) -> Vec<String> {
+        let category = self.symbol_category(symbol).await;
+        let signal_combiner_guard = self.signal_combiner.read().await;
+        let group_combiners = self.group_combiners.read().await;
+        select_group(&group_combiners, symbol, category)
+            .map(|g| &g.combiner)
+            .or(signal_combiner_guard.as_ref())
+            .map(|c| c.get_strategy_names())
+            .unwrap_or_default()
+    }
+
+    /// Minimum confidence for executing a signal on a symbol: the threshold
+    /// of its group, else of the default group, else the configured one
+    pub async fn confidence_threshold_for(&self, symbol: &str) -> f64 {
+        let category = self.symbol_category(symbol).await;
+        let group_combiners = self.group_combiners.read().await;
+        let registry = self.strategy_registry.read().await;
+        select_group(&group_combiners, symbol, category)
+            .and_then(|g| g.group.min_confidence_threshold)
+            .or_else(|| {
+                registry
+                    .as_ref()
+                    .and_then(|r| r.default_group().min_confidence_threshold)
+            })
+            .unwrap_or(self.config.min_confidence_threshold)
+    }
+
+    /// Register a combiner that paper-trades the symbols of `group`
+    ///
+    /// It sees the same candles as the live combiners and its signals go
+    /// through the same sizing and risk limits, but orders are only simulated
+    /// at the current price in its own `PaperBook`.
+    pub async fn add_shadow_combiner(&self, mut group: GroupDefinition, combiner: SignalCombiner) {
+        group.symbols = group
+            .symbols
+            .iter()
+            .map(|s| TradingConfig::normalize_symbol(s))
+            .collect();
+        {
+            let mut builder = self.candle_builder.lock().await;
+            builder.register_indicators(&combiner.required_indicators());
+        }
+        let limits = PaperLimits {
+            max_positions_per_symbol: self.config.max_positions_per_symbol,
+            max_total_positions: self.config.max_total_positions,
+            max_trades_per_hour: self.config.max_trades_per_hour,
+            max_trades_per_day: self.config.max_trades_per_day,
+            stop_loss_percentage: self.config.stop_loss_percentage,
+            take_profit_percentage: self.config.take_profit_percentage,
+        };
+        info!("Shadow combiner '{}' registered", group.name);
+        let book = PaperBook::new(&group.name, limits);
+        self.shadows.lock().await.push(ShadowCombiner {
+            group,
+            combiner,
+            book,
+        });
+    }
+
+    /// Run every shadow combiner serving a symbol on its latest candles
+    ///
+    /// Paper positions are first marked to the current price and closed on
+    /// their exits, then each shadow's signal is paper-traded when it clears
+    /// the shadow's confidence threshold (or the symbol's live one).
+    pub async fn run_shadow_strategies(&self, symbol: &str) -> Vec<Result<String, MpcError>> {
+        if self.shadows.lock().await.is_empty() {
+            return Vec::new();
+        }
+        match self.get_aggregated_price(symbol).await {
+            Ok(price) => self.paper_trade_shadows(symbol, price).await,
+            Err(e) => vec![Err(e)],
+        }
+    }
+
+    /// Paper-trade the shadow combiners serving a symbol at `price`
+    async fn paper_trade_shadows(
+        &self,
+        symbol: &str,
+        price: Price,
+    ) -> Vec<Result<String, MpcError>> {
+        let candles = self.get_candles(symbol).await;
+        let enough_candles = candles.len() >= MIN_CANDLES_FOR_SIGNAL;
+        let values = {
+            let builder = self.candle_builder.lock().await;
+            builder.indicator_values(symbol)
+        };
+        let category = self.symbol_category(symbol).await;
+        let regime = self
+            .market_regimes
+            .lock()
+            .await
+            .get(symbol)
+            .map(|r| r.regime);
+        let live_threshold = self.confidence_threshold_for(symbol).await;
+        let time_exit = self.config.time_exit_rule_for(symbol, None);
+        let tradable = validate_symbol(symbol).is_ok() && self.is_whitelisted(symbol);
+        // Sized up front: sizing reads locks ordered before `shadows`
+        let quantity = if enough_candles && tradable {
+            Some(self.calculate_position_size(symbol, price, None).await)
+        } else {
+            None
+        };
+        let now = chrono::Utc::now();
+
+        let mut results = Vec::new();
+        let mut shadows = self.shadows.lock().await;
+        for shadow in shadows.iter_mut().filter(|s| s.serves(symbol, category)) {
+            let name = shadow.group.name.clone();
+            for close in shadow.book.mark(symbol, price, &time_exit, now) {
+                results.push(Ok(format!(
+                    "Shadow {} closed paper position {} due to {} (PnL: {:.4})",
+                    name, close.position_id, close.reason, close.pnl
+                )));
+            }
+            if !enough_candles {
+                continue;
+            }
+
+            let Some(signal) = shadow
+                .combiner
+                .combine_signals_streaming(&candles, &values, regime)
+            else {
+                continue;
+            };
+            let side = match signal.signal {
+                crate::domain::services::strategies::Signal::Buy => PositionSide::Long,
+                crate::domain::services::strategies::Signal::Sell => PositionSide::Short,
+                crate::domain::services::strategies::Signal::Hold => continue,
+            };
+            shadow.book.record_signal();
+
+            let threshold = shadow
+                .group
+                .min_confidence_threshold
+                .unwrap_or(live_threshold);
+            if signal.confidence < threshold {
+                continue;
+            }
+            let quantity = match &quantity {
+                Some(Ok(quantity)) => *quantity,
+                Some(Err(e)) => {
+                    results.push(Err(e.clone()));
+                    continue;
+                }
+                None => {
+                    results.push(Err(MpcError::InvalidInput(format!(
+                        "Symbol '{}' is not in the configured whitelist for trading",
+                        symbol
+                    ))));
+                    continue;
+                }
+            };
+            results.push(
+                match shadow.book.open(symbol, side.clone(), quantity, price, now) {
+                    Ok(position_id) => Ok(format!(
+                        "Shadow {} paper order filled: {} {:.6} {} at {:.4} (confidence: {:.2}) - Position ID: {}",
+                        name, side, quantity, symbol, price.value(), signal.confidence, position_id
+                    )),
+                    Err(reason) => Ok(format!("Shadow {} order skipped: {}", name, reason)),
+                },
+            );
+        }
+        results
+    }
+
+    /// Live results next to each shadow combiner's paper results
+    ///
+    /// Live results cover closed trades attributed to a strategy and every
+    /// open position: in total, and per shadow restricted to the symbols the
+    /// shadow serves, so both sides of a comparison trade the same symbols.
+    pub async fn get_shadow_comparison(&self) -> (PaperSummary, Vec<ShadowReport>) {
+        fn add_closed_trades(total: &mut StrategyMetrics, metrics: &StrategyMetrics) {
+            total.winning_trades += metrics.winning_trades;
+            total.losing_trades += metrics.losing_trades;
+            total.gross_profit += metrics.gross_profit;
+            total.gross_loss += metrics.gross_loss;
+        }
+
+        let mut live = StrategyMetrics::new("live".to_string());
+        {
+            let strategy_metrics = self.strategy_metrics.lock().await;
+            for metrics in strategy_metrics.values() {
+                add_closed_trades(&mut live, metrics);
+            }
         }
+        let positions: Vec<Position> = {
+            let positions = self.open_positions.lock().await;
+            positions.values().cloned().collect()
+        };
+        let live = PaperSummary::new(&live, &positions);
+        let categories = self.symbol_categories.lock().await.clone();
+        let pair_metrics = self.get_symbol_strategy_metrics().await;
+
+        let shadows = self.shadows.lock().await;
+        let reports = shadows
+            .iter()
+            .map(|shadow| {
+                let serves = |symbol: &str| shadow.serves(symbol, categories.get(symbol).copied());
+                let mut served = StrategyMetrics::new("live".to_string());
+                for ((_, symbol), metrics) in &pair_metrics {
+                    if serves(symbol) {
+                        add_closed_trades(&mut served, metrics);
+                    }
+                }
+                let served_positions = positions.iter().filter(|p| serves(&p.symbol));
+
+                let metrics = shadow.book.metrics();
+                ShadowReport {
+                    name: shadow.group.name.clone(),
+                    symbols: shadow.group.symbols.clone(),
+                    categories: shadow.group.categories.clone(),
+                    min_confidence_threshold: shadow.group.min_confidence_threshold,
+                    weights: shadow
+                        .combiner
+                        .get_strategy_names()
+                        .into_iter()
+                        .zip(shadow.combiner.weights().iter().copied())
+                        .collect(),
+                    signals_generated: metrics.signals_generated,
+                    signals_executed: metrics.signals_executed,
+                    results: PaperSummary::new(metrics, shadow.book.positions()),
+                    live: PaperSummary::new(&served, served_positions),
+                }
+            })
+            .collect();
+        (live, reports)
+    }
+
+    /// Whether a symbol is in the configured whitelist of any exchange
+    fn is_whitelisted(&self, symbol: &str) -> bool {
+        let normalized_symbol = TradingConfig::normalize_symbol(symbol);
+        self.config.symbols.values().any(|symbols| {
+            symbols.iter().any(|whitelisted_symbol| {
+                TradingConfig::normalize_symbol(whitelisted_symbol) == normalized_symbol
+            })
+        })
     }
 
     /// Check health of a specific actor
@@ -394,7 +1223,47 @@ impl MpcService {
         );
 
         if candle_count >= MIN_CANDLES_FOR_SIGNAL {
-            let signal = self.generate_trading_signal(&candles).await?;
+            let values = {
+                let builder = self.candle_builder.lock().await;
+                builder.indicator_values(symbol)
+            };
+            let category = self.symbol_category(symbol).await;
+            let reading = self.classify_regime(symbol, &candles).await;
+            let regime = reading.as_ref().map(|r| r.regime);
+            let (mut signal, strategy_names) = {
+                let signal_combiner_guard = self.signal_combiner.read().await;
+                let group_combiners = self.group_combiners.read().await;
+                let combiner = select_group(&group_combiners, symbol, category)
+                    .map(|g| &g.combiner)
+                    .or(signal_combiner_guard.as_ref())
+                    .ok_or(MpcError::SignalCombinerNotInitialized)?;
+                let signal = combiner
+                    .combine_signals_streaming(&candles, &values, regime)
+                    .ok_or(MpcError::SignalCombinerNotInitialized)?;
+                (signal, combiner.get_strategy_names())
+            };
+            if let Some(reading) = reading {
+                signal.indicators.extend([
+                    ("regime_adx".to_string(), reading.adx),
+                    (
+                        "regime_realized_volatility".to_string(),
+                        reading.realized_volatility,
+                    ),
+                    (
+                        "regime_bollinger_bandwidth".to_string(),
+                        reading.bollinger_bandwidth,
+                    ),
+                ]);
+            }
+            if signal.signal != crate::domain::services::strategies::Signal::Hold {
+                let mut pair_metrics = self.symbol_strategy_metrics.lock().await;
+                for name in strategy_names {
+                    pair_metrics
+                        .entry((name.clone(), symbol.to_string()))
+                        .or_insert_with(|| StrategyMetrics::new(name))
+                        .record_signal();
+                }
+            }
             debug!(
                 "Signal generated for {}: {:?} (confidence: {:.3})",
                 symbol, signal.signal, signal.confidence
@@ -409,16 +1278,86 @@ impl MpcService {
         }
     }
 
+    /// Classify and remember the market regime of a symbol
+    ///
+    /// Uses the registry's classifier (defaults without a registry). Returns None
+    /// while the candle history is too short, which leaves weights unchanged.
+    async fn classify_regime(
+        &self,
+        symbol: &str,
+        candles: &[crate::domain::services::indicators::Candle],
+    ) -> Option<RegimeReading> {
+        let classifier = self
+            .strategy_registry
+            .read()
+            .await
+            .as_ref()
+            .map(|r| r.regime_classifier().clone())
+            .unwrap_or_default();
+        let reading = classifier.classify(candles)?;
+
+        let mut regimes = self.market_regimes.lock().await;
+        let previous = regimes.insert(symbol.to_string(), reading.clone());
+        if previous.map(|p| p.regime) != Some(reading.regime) {
+            info!(
+                "Market regime for {} is now {} (ADX {:.1}, volatility {:.5}, bandwidth {:.5})",
+                symbol,
+                reading.regime.as_str(),
+                reading.adx,
+                reading.realized_volatility,
+                reading.bollinger_bandwidth
+            );
+        }
+        Some(reading)
+    }
+
+    /// Latest market regime reading of every classified symbol
+    pub async fn get_market_regimes(&self) -> HashMap<String, RegimeReading> {
+        self.market_regimes.lock().await.clone()
+    }
+
     /// Get aggregated price for a symbol across all exchanges (using normalized symbols)
 
     pub async fn get_aggregated_price(&self, symbol: &str) -> Result<Price, MpcError> {
-        use crate::config::TradingConfig;
         let normalized_symbol = TradingConfig::normalize_symbol(symbol);
+        let prices: Vec<Price> = self
+            .get_venue_prices(symbol)
+            .await?
+            .into_iter()
+            .map(|(_, _, price)| price)
+            .collect();
 
-        let mut prices = Vec::new();
+        if prices.is_empty() {
+            return Err(MpcError::NoPricesAvailable {
+                symbol: symbol.to_string(),
+            });
+        }
 
-        for (exchange, sender) in self.senders.as_ref().iter() {
-            // Get subscriptions for this exchange
+        let aggregated = Self::aggregate_prices(&prices)?;
+        debug!(
+            "Aggregated price calculated {:.2} for {} (based on {} exchanges)",
+            aggregated.value(),
+            normalized_symbol,
+            prices.len()
+        );
+        Ok(aggregated)
+    }
+
+    /// Price of a symbol on every exchange subscribed to it, with the
+    /// exchange's own spelling of the symbol
+    ///
+    /// Exchange symbols are matched on their normalized form, so BTCUSDT on
+    /// Binance and BTC-USD on Coinbase are both prices of BTC-USD.
+    pub async fn get_venue_prices(
+        &self,
+        symbol: &str,
+    ) -> Result<Vec<(Exchange, String, Price)>, MpcError> {
+        let normalized_symbol = TradingConfig::normalize_symbol(symbol);
+
+        let mut prices = Vec::new();
+
+        for (exchange, sender) in self.senders.as_ref().iter() {
+            // Get subscriptions for this exchange
             let (sub_tx, mut sub_rx) = mpsc::channel(1);
             sender
                 .send(ExchangeMessage::GetSubscriptions { reply: sub_tx })
@@ -446,7 +1385,7 @@ impl MpcService {
                                 sub_symbol,
                                 normalized_symbol
                             );
-                            prices.push(price);
+                            prices.push((exchange.clone(), sub_symbol.clone(), price));
                         } else {
                             debug!(
                                 "No price available from {} for {}",
@@ -460,20 +1399,7 @@ impl MpcService {
             }
         }
 
-        if prices.is_empty() {
-            return Err(MpcError::NoPricesAvailable {
-                symbol: symbol.to_string(),
-            });
-        }
-
-        let aggregated = Self::aggregate_prices(&prices)?;
-        debug!(
-            "Aggregated price calculated {:.2} for {} (based on {} exchanges)",
-            aggregated.value(),
-            normalized_symbol,
-            prices.len()
-        );
-        Ok(aggregated)
+        Ok(prices)
     }
 
     /// Get all tracked symbols across all exchanges
@@ -521,6 +1447,114 @@ impl MpcService {
         builder.get_candles(symbol)
     }
 
+    /// Screening inputs of every symbol subscribed on an exchange, keyed by
+    /// normalized symbol
+    ///
+    /// Candles come from the aggregated price feed and bid/ask from the
+    /// exchange's own ticker. Symbols without candles yet are left out; a
+    /// symbol without a quote gets a zero bid and ask, which scores no spread.
+    /// With market discovery, the trade rate and funding come from the
+    /// exchange's market list and the depth from its order book, each fetched
+    /// only when its scorer is weighted.
+    pub async fn get_screening_data(
+        &self,
+        exchange: &Exchange,
+    ) -> HashMap<String, SymbolMarketData> {
+        let mut data = HashMap::new();
+        let Some(sender) = self.senders.as_ref().get(exchange) else {
+            return data;
+        };
+        let needs_listing = self.config.screening_trade_rate_weight > 0.0
+            || self.config.screening_funding_weight > 0.0;
+        let listed: HashMap<String, MarketSnapshot> = match &self.market_discovery {
+            Some(discovery) if needs_listing => match discovery.list_markets(exchange).await {
+                Ok(markets) => markets
+                    .into_iter()
+                    .map(|market| (TradingConfig::normalize_symbol(&market.symbol), market))
+                    .collect(),
+                Err(e) => {
+                    warn!(
+                        "Market list for screening on {} failed: {}",
+                        exchange.name(),
+                        e
+                    );
+                    HashMap::new()
+                }
+            },
+            _ => HashMap::new(),
+        };
+
+        let (sub_tx, mut sub_rx) = mpsc::channel(1);
+        if sender
+            .send(ExchangeMessage::GetSubscriptions { reply: sub_tx })
+            .await
+            .is_err()
+        {
+            return data;
+        }
+        let subscriptions = timeout(CHANNEL_REPLY_TIMEOUT, sub_rx.recv())
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+
+        for sub_symbol in subscriptions {
+            let symbol = TradingConfig::normalize_symbol(&sub_symbol);
+            let candles = self.get_candles(&symbol).await;
+            if candles.is_empty() {
+                continue;
+            }
+
+            let (quote_tx, mut quote_rx) = mpsc::channel(1);
+            let quote = match sender
+                .send(ExchangeMessage::GetBestQuote {
+                    symbol: sub_symbol.clone(),
+                    reply: quote_tx,
+                })
+                .await
+            {
+                Ok(()) => timeout(CHANNEL_REPLY_TIMEOUT, quote_rx.recv())
+                    .await
+                    .ok()
+                    .flatten()
+                    .and_then(|quote| quote.ok()),
+                Err(_) => None,
+            };
+            let (bid, ask) = quote
+                .map(|q| (q.bid.value(), q.ask.value()))
+                .unwrap_or((0.0, 0.0));
+            // Ticker feeds carry the best level only, so depth needs the book
+            let depth = match &self.market_discovery {
+                Some(discovery) if self.config.screening_depth_weight > 0.0 => {
+                    match discovery.order_book(exchange, &sub_symbol).await {
+                        Ok(book) => Some(book),
+                        Err(e) => {
+                            warn!("Order book of {} for screening failed: {}", sub_symbol, e);
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            };
+            let market = listed.get(&symbol);
+
+            data.insert(
+                symbol,
+                SymbolMarketData {
+                    volumes: candles.iter().map(|c| c.volume).collect(),
+                    candles,
+                    bid,
+                    ask,
+                    depth,
+                    trades_per_minute: market.and_then(MarketSnapshot::trades_per_minute),
+                    funding_rate: market.and_then(|m| m.funding_rate),
+                },
+            );
+        }
+
+        data
+    }
+
     /// Place an order on a specific exchange
 
     pub async fn place_order(&self, exchange: &Exchange, order: Order) -> Result<String, MpcError> {
@@ -585,13 +1619,127 @@ impl MpcService {
         }
     }
 
+    /// Get order status and filled quantity from a specific exchange
+    pub async fn get_order_fill(
+        &self,
+        exchange: &Exchange,
+        order_id: &str,
+    ) -> Result<OrderFill, MpcError> {
+        if let Some(sender) = self.senders.as_ref().get(exchange) {
+            let (reply_tx, mut reply_rx) = mpsc::channel(1);
+            let msg = ExchangeMessage::GetOrderFill {
+                order_id: order_id.to_string(),
+                reply: reply_tx,
+            };
+            sender.send(msg).await?;
+            timeout(CHANNEL_REPLY_TIMEOUT, reply_rx.recv())
+                .await
+                .map_err(|_| MpcError::Timeout)?
+                .ok_or(MpcError::NoResponse)?
+                .map_err(|e| MpcError::OrderPlacementFailed(e))
+        } else {
+            Err(MpcError::ActorNotFound(exchange.clone()))
+        }
+    }
+
     /// Store last signal for a symbol (using LRU cache)
 
     pub async fn store_signal(&self, symbol: String, signal: TradingSignal) {
+        if let Some(id) = self.record_signal(&symbol, &signal).await {
+            let superseded = {
+                let mut pending = self.pending_signal_ids.lock().await;
+                pending.insert(symbol.clone(), id)
+            };
+            if let Some(previous) = superseded {
+                self.record_signal_skipped(
+                    previous,
+                    SignalSkipReason::Superseded,
+                    "Replaced by a newer signal before evaluation",
+                )
+                .await;
+            }
+        }
+
         let mut last_signals = self.last_signals.lock().await;
         last_signals.put(symbol, signal); // LRU cache uses .put() instead of .insert()
     }
 
+    /// Persist a generated signal as pending, with the combiner weights behind it
+    ///
+    /// Returns the signal row id, or None without a repository or on failure.
+    pub async fn record_signal(&self, symbol: &str, signal: &TradingSignal) -> Option<i64> {
+        let repository = self.signal_repository.as_ref()?;
+
+        let price = self
+            .candle_builder
+            .lock()
+            .await
+            .get_candles(symbol)
+            .last()
+            .map(|candle| candle.close.value());
+        let weights: serde_json::Map<String, serde_json::Value> = signal
+            .votes
+            .iter()
+            .map(|vote| (vote.strategy.clone(), serde_json::json!(vote.weight)))
+            .collect();
+
+        let record = CreateSignal {
+            symbol: symbol.to_string(),
+            action: format!("{:?}", signal.signal).to_lowercase(),
+            confidence: signal.confidence,
+            price,
+            weights: serde_json::Value::Object(weights),
+            details: signal.explanation(),
+            regime: signal.regime.map(|r| r.as_str().to_string()),
+        };
+
+        match repository.create(record).await {
+            Ok(record) => Some(record.id),
+            Err(e) => {
+                warn!("Failed to record signal for {}: {}", symbol, e);
+                None
+            }
+        }
+    }
+
+    /// Record what became of a persisted signal after an execution attempt
+    pub async fn record_signal_outcome(&self, id: i64, result: &Result<SignalExecution, MpcError>) {
+        let Some(repository) = self.signal_repository.as_ref() else {
+            return;
+        };
+
+        let outcome = match result {
+            Ok(execution) => execution.message().to_string(),
+            Err(e) => e.to_string(),
+        };
+        if let Err(e) = repository
+            .record_outcome(id, signal_skip_reason(result), &outcome)
+            .await
+        {
+            warn!("Failed to record outcome of signal {}: {}", id, e);
+        }
+    }
+
+    async fn record_signal_skipped(&self, id: i64, reason: SignalSkipReason, outcome: &str) {
+        let Some(repository) = self.signal_repository.as_ref() else {
+            return;
+        };
+        if let Err(e) = repository.record_outcome(id, Some(reason), outcome).await {
+            warn!("Failed to record outcome of signal {}: {}", id, e);
+        }
+    }
+
+    /// Query persisted signals (empty without a repository)
+    pub async fn get_signal_history(
+        &self,
+        query: &SignalQuery,
+    ) -> Result<Vec<SignalRecord>, String> {
+        match self.signal_repository.as_ref() {
+            Some(repository) => repository.query(query).await.map_err(|e| e.to_string()),
+            None => Ok(Vec::new()),
+        }
+    }
+
     /// Get all last signals from LRU cache
 
     pub async fn get_all_last_signals(&self) -> HashMap<String, TradingSignal> {
@@ -629,6 +1777,9 @@ impl MpcService {
         )
         .map_err(|e| MpcError::InvalidConfiguration(format!("Failed to create position: {}", e)))?;
 
+        self.record_position_open(&position, "unknown", None, None)
+            .await;
+
         let mut positions = self.open_positions.lock().await;
         positions.insert(position_id.clone(), position);
 
@@ -653,21 +1804,90 @@ impl MpcService {
     }
 
     /// Close a position
-
+    ///
+    /// A leg of a position group closes the whole group.
     pub async fn close_position(&self, position_id: &str) -> Result<(), MpcError> {
+        let group_id = {
+            let positions = self.open_positions.lock().await;
+            positions
+                .get(position_id)
+                .and_then(|position| position.group_id.clone())
+        };
+        match group_id {
+            Some(group_id) => self
+                .close_position_group(&group_id, ExitReason::Manual)
+                .await
+                .map(|_| ()),
+            None => {
+                self.close_position_with_reason(position_id, ExitReason::Manual)
+                    .await
+            }
+        }
+    }
+
+    /// Close a position and record why it was closed
+    ///
+    /// The closing trade is persisted with `reason` as its exit reason when a
+    /// trade repository is configured.
+    pub async fn close_position_with_reason(
+        &self,
+        position_id: &str,
+        reason: ExitReason,
+    ) -> Result<(), MpcError> {
+        self.close_position_by_order(position_id, reason, None)
+            .await
+    }
+
+    /// Close a position, linking the closing trade to the exit order that
+    /// was sent for it
+    async fn close_position_by_order(
+        &self,
+        position_id: &str,
+        reason: ExitReason,
+        exit_order_id: Option<&str>,
+    ) -> Result<(), MpcError> {
         let mut positions = self.open_positions.lock().await;
         if let Some(position) = positions.remove(position_id) {
             let pnl = position.unrealized_pnl().unwrap_or(PnL::zero());
             let entry_value = position.quantity.value() * position.entry_price.value();
 
-            info!("Closed position: {} (PnL: {:?})", position_id, pnl);
+            info!(
+                "Closed position: {} due to {} (PnL: {:?})",
+                position_id, reason, pnl
+            );
 
             // Release lock before updating portfolio
             drop(positions);
 
-            // Update portfolio after closing position
-            self.update_portfolio_after_position_close(entry_value, pnl.value())
+            let realized = self
+                .record_position_close(&position, reason, exit_order_id)
                 .await;
+            self.release_position_bracket(position_id).await;
+            if let Some(group_id) = position.group_id.as_deref() {
+                self.remove_group_leg(group_id, position_id).await;
+            }
+
+            if let Some(strategy) = position.strategy.as_deref() {
+                {
+                    let mut strategy_metrics = self.strategy_metrics.lock().await;
+                    if let Some(metrics) = strategy_metrics.get_mut(strategy) {
+                        metrics.record_trade_outcome(pnl.value());
+                    }
+                }
+                let mut pair_metrics = self.symbol_strategy_metrics.lock().await;
+                pair_metrics
+                    .entry((strategy.to_string(), position.symbol.clone()))
+                    .or_insert_with(|| StrategyMetrics::new(strategy.to_string()))
+                    .record_trade_outcome(pnl.value());
+            }
+
+            // Update portfolio after closing position, net of fees when the
+            // closing trade went through the ledger
+            self.update_portfolio_after_position_close(
+                entry_value,
+                realized.unwrap_or(pnl.value()),
+            )
+            .await;
 
             Ok(())
         } else {
@@ -678,114 +1898,1219 @@ impl MpcService {
         }
     }
 
-    /// Update position prices with current market prices
+    /// Persist a newly opened position and its opening trade
     ///
-    /// This method minimizes lock contention by:
-    /// 1. Collecting symbols without holding the lock
-    /// 2. Fetching prices CONCURRENTLY using futures::join_all (no lock held)
-    /// 3. Updating positions with a short final lock
-    pub async fn update_position_prices(&self) -> Result<(), MpcError> {
-        // Step 1: Collect symbols that need price updates (short lock)
-        let symbols_to_update: Vec<String> = {
-            let positions = self.open_positions.lock().await;
-            positions
-                .values()
-                .map(|p| p.symbol.clone())
-                .collect::<std::collections::HashSet<_>>()
-                .into_iter()
-                .collect()
+    /// No-op without repositories. Failures are logged and never abort trading.
+    async fn record_position_open(
+        &self,
+        position: &Position,
+        exchange: &str,
+        exchange_order_id: Option<&str>,
+        signal: Option<&TradingSignal>,
+    ) {
+        let (Some(positions), Some(trades)) = (
+            self.position_repository.as_ref(),
+            self.trade_repository.as_ref(),
+        ) else {
+            return;
         };
 
-        // Step 2: Fetch all prices CONCURRENTLY (no lock held)
-        // SPEC REQUIREMENT: Use futures::join_all for parallel price fetching
-        let price_futures: Vec<_> = symbols_to_update
-            .iter()
-            .map(|symbol| async move {
-                let price_result = self.get_aggregated_price(symbol).await;
-                (symbol.clone(), price_result)
-            })
-            .collect();
+        let (position_side, trade_side) = match position.side {
+            PositionSide::Long => ("long", "buy"),
+            PositionSide::Short => ("short", "sell"),
+        };
 
-        let price_results = futures_util::future::join_all(price_futures).await;
+        let entry_value = position.entry_price.value() * position.quantity.value();
+        let (fee, fee_estimated) = self
+            .fill_fee(exchange, exchange_order_id, entry_value)
+            .await;
+        let record = CreatePosition {
+            id: position.id.clone(),
+            symbol: position.symbol.clone(),
+            exchange: exchange.to_string(),
+            side: position_side.to_string(),
+            entry_price: position.entry_price.value(),
+            quantity: position.quantity.value(),
+            stop_loss: position.stop_loss_price.map(|p| p.value()),
+            take_profit: position.take_profit_price.map(|p| p.value()),
+            group_id: position.group_id.clone(),
+        };
 
-        // Collect successful price fetches
-        let mut prices = HashMap::new();
-        for (symbol, price_result) in price_results {
-            if let Ok(current_price) = price_result {
-                prices.insert(symbol, current_price);
-            }
+        if let Err(e) = positions.create(record).await {
+            warn!("Failed to persist position {}: {}", position.id, e);
+            return;
         }
 
-        // Step 3: Update positions with fetched prices (short lock)
-        let mut positions = self.open_positions.lock().await;
-        for (symbol, price) in prices {
-            for position in positions.values_mut() {
-                if position.symbol == symbol {
-                    position.update_price(price);
-                }
+        let trade = CreateTrade {
+            id: format!("trade_open_{}", position.id),
+            position_id: Some(position.id.clone()),
+            symbol: position.symbol.clone(),
+            exchange: exchange.to_string(),
+            side: trade_side.to_string(),
+            price: position.entry_price.value(),
+            quantity: position.quantity.value(),
+            fee,
+            fee_estimated,
+            exchange_order_id: exchange_order_id.map(str::to_string),
+            strategy: position
+                .strategy
+                .clone()
+                .unwrap_or_else(|| "SignalCombiner".to_string()),
+            signal_confidence: signal.map(|s| s.confidence),
+            exit_reason: None,
+            signal_details: signal.map(|s| s.explanation().to_string()),
+        };
+
+        match trades.create(trade).await {
+            Ok(record) => {
+                self.enter_in_ledger(&record).await;
             }
+            Err(e) => warn!(
+                "Failed to record opening trade for position {}: {}",
+                position.id, e
+            ),
         }
-
-        Ok(())
-    }
-
-    /// Get all open positions
-
-    pub async fn get_open_positions(&self) -> HashMap<String, Position> {
-        let positions = self.open_positions.lock().await;
-        positions.clone()
     }
 
-    /// Get total unrealized PnL across all positions
-    ///
-    /// Returns the sum of all unrealized PnL across open positions.
-    /// The result can be positive (net profit) or negative (net loss).
-    /// Get total unrealized PnL across all positions
-    ///
-    /// Returns the sum of all unrealized PnL across open positions.
-    /// The result can be positive (net profit) or negative (net loss).
+    /// Persist the closing trade of a position with its exit reason
     ///
-    /// If the total PnL is invalid (NaN/Infinity), logs detailed error information
-    /// and returns zero as a safe fallback.
-    pub async fn get_total_unrealized_pnl(&self) -> PnL {
-        let positions = self.open_positions.lock().await;
-        let mut total_pnl = 0.0;
-        let mut position_pnls = Vec::new();
+    /// The exchange is taken from the persisted position. Returns the PnL the
+    /// closing trade realized in the ledger, net of fees, or `None` when it
+    /// was not recorded (e.g. without repositories).
+    async fn record_position_close(
+        &self,
+        position: &Position,
+        reason: ExitReason,
+        exchange_order_id: Option<&str>,
+    ) -> Option<f64> {
+        let (Some(positions), Some(trades)) = (
+            self.position_repository.as_ref(),
+            self.trade_repository.as_ref(),
+        ) else {
+            return None;
+        };
 
-        for (position_id, position) in positions.iter() {
-            if let Some(pnl) = position.unrealized_pnl() {
-                let pnl_value = pnl.value();
-                position_pnls.push((position_id.clone(), pnl_value));
-                total_pnl += pnl_value;
+        let exit_price = position.current_price.unwrap_or(position.entry_price);
+        let realized_pnl = position.unrealized_pnl().unwrap_or(PnL::zero()).value();
+
+        let exchange = match positions.get(&position.id).await {
+            Ok(Some(record)) => record.exchange,
+            Ok(None) => {
+                warn!(
+                    "Position {} was never persisted, skipping trade record",
+                    position.id
+                );
+                return None;
+            }
+            Err(e) => {
+                warn!("Failed to look up position {}: {}", position.id, e);
+                return None;
             }
+        };
+
+        if let Err(e) = positions
+            .close(&position.id, exit_price.value(), realized_pnl)
+            .await
+        {
+            warn!("Failed to mark position {} closed: {}", position.id, e);
         }
 
-        // PnL can be negative, which is valid for losses
-        match PnL::new(total_pnl) {
-            Ok(pnl) => pnl,
+        let side = match position.side {
+            PositionSide::Long => "sell",
+            PositionSide::Short => "buy",
+        };
+
+        let exit_value = exit_price.value() * position.quantity.value();
+        let (fee, fee_estimated) = self
+            .fill_fee(&exchange, exchange_order_id, exit_value)
+            .await;
+        let trade = CreateTrade {
+            id: format!("trade_close_{}", position.id),
+            position_id: Some(position.id.clone()),
+            symbol: position.symbol.clone(),
+            fee,
+            fee_estimated,
+            exchange,
+            side: side.to_string(),
+            price: exit_price.value(),
+            quantity: position.quantity.value(),
+            exchange_order_id: exchange_order_id.map(str::to_string),
+            strategy: position
+                .strategy
+                .clone()
+                .unwrap_or_else(|| "SignalCombiner".to_string()),
+            signal_confidence: None,
+            exit_reason: Some(reason.as_str().to_string()),
+            signal_details: None,
+        };
+
+        match trades.create(trade).await {
+            Ok(record) => Some(self.enter_in_ledger(&record).await),
             Err(e) => {
-                error!(
-                    "Failed to create PnL from total: {} - Error: {}. \
-                     Position PnLs: {:?}. Using zero PnL as fallback.",
-                    total_pnl, e, position_pnls
+                warn!(
+                    "Failed to record closing trade for position {}: {}",
+                    position.id, e
                 );
-                PnL::zero()
+                None
             }
         }
     }
 
-    /// Fetch portfolio value from all exchanges and update internal state
-    ///
-    /// This fetches the real-time balance from all available exchanges (Coinbase, dYdX, etc.)
-    /// and aggregates them into a total portfolio value in USD.
+    /// Fee of the fill of an order worth `notional` on `exchange`, and
+    /// whether it was estimated
     ///
-    /// # Returns
-    /// Updated total portfolio value in USD
-    pub async fn fetch_and_update_portfolio_from_exchanges(&self) -> Result<f64, MpcError> {
-        use crate::application::actors::trader_actor::TraderMessage;
+    /// The fee the exchange reports for the filled order is used when there
+    /// is one; without an order id, a fill or a reported fee it is estimated
+    /// from the fee schedule.
+    async fn fill_fee(&self, exchange: &str, order_id: Option<&str>, notional: f64) -> (f64, bool) {
+        let reported = match (Exchange::from_name(exchange), order_id) {
+            (Some(venue), Some(order_id)) => match self.get_order_fill(&venue, order_id).await {
+                // An order that has not filled yet reports no fee so far
+                Ok(fill) if fill.filled_quantity.unwrap_or(0.0) > 0.0 => fill.fee,
+                Ok(_) => None,
+                Err(e) => {
+                    debug!("No fill report for order {}: {}", order_id, e);
+                    None
+                }
+            },
+            _ => None,
+        };
+        match reported {
+            Some(fee) => (fee, false),
+            None => (self.estimated_fee(exchange, notional), true),
+        }
+    }
 
-        // Get all traders to query all exchanges
-        let traders = self.traders.lock().await;
+    /// Taker fee of a fill worth `notional` on `exchange` from the configured
+    /// fee schedule, for fills whose exchange does not report the fee
+    fn estimated_fee(&self, exchange: &str, notional: f64) -> f64 {
+        let bps = Exchange::from_name(exchange)
+            .map(|exchange| self.config.arbitrage.fee_for(&exchange))
+            .unwrap_or(self.config.arbitrage.default_fee_bps);
+        notional.abs() * bps / 10_000.0
+    }
+
+    /// Enter a persisted trade in the realized PnL ledger and refresh the
+    /// realized figures of the trading metrics
+    ///
+    /// Returns the net PnL the trade realized.
+    async fn enter_in_ledger(&self, record: &TradeRecord) -> f64 {
+        let Some(fill) = ledger_fill(record) else {
+            warn!(
+                "Trade {} has unknown side '{}', left out of the PnL ledger",
+                record.id, record.side
+            );
+            return 0.0;
+        };
+        let signed_quantity = match fill.side {
+            FillSide::Buy => record.quantity,
+            FillSide::Sell => -record.quantity,
+        };
+        let (realized, gross, totals) = {
+            let mut ledger = self.pnl_ledger.lock().await;
+            let entries = ledger.apply_fill(fill);
+            let realized: f64 = entries.iter().map(|entry| entry.net_pnl).sum();
+            let gross: f64 = entries.iter().map(|entry| entry.gross_pnl).sum();
+            (realized, gross, ledger.totals())
+        };
+        self.trading_metrics.lock().await.sync_realized(&totals);
+        self.note_own_fill(
+            &record.exchange,
+            &record.symbol,
+            signed_quantity,
+            record.price,
+            record.fee,
+            gross,
+        )
+        .await;
+        realized
+    }
+
+    /// Move the expected balances of an exchange by a fill of our own
+    ///
+    /// Spot fills move the base and quote currency of the symbol; perp
+    /// fills settle their realized PnL less the fee in the collateral
+    /// currency. Reconciliation takes the flows with its next run.
+    async fn note_own_fill(
+        &self,
+        exchange: &str,
+        symbol: &str,
+        signed_quantity: f64,
+        price: f64,
+        fee: f64,
+        realized: f64,
+    ) {
+        let Some(exchange) = Exchange::from_name(exchange) else {
+            return;
+        };
+        let normalized = TradingConfig::normalize_symbol(symbol);
+        let mut own_activity = self.own_activity.lock().await;
+        let activity = own_activity.entry(exchange.clone()).or_default();
+        if exchange.is_perpetual() {
+            activity.add_flow(PERP_COLLATERAL, realized - fee);
+        } else if let Some((base, quote)) = normalized.split_once('-') {
+            activity.add_flow(base, signed_quantity);
+            activity.add_flow(quote, -signed_quantity * price - fee);
+        }
+    }
+
+    /// Balance flows of own fills since the last reconciliation, with the
+    /// unrealized PnL of the open positions on each perp venue
+    async fn take_own_activity(&self) -> HashMap<Exchange, OwnActivity> {
+        let mut activity = std::mem::take(&mut *self.own_activity.lock().await);
+        for exchange in [Exchange::Dydx, Exchange::Hyperliquid] {
+            let unrealized: f64 = self
+                .positions_on(&exchange)
+                .await
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Position::unrealized_pnl)
+                .map(|pnl| pnl.value())
+                .sum();
+            if unrealized != 0.0 {
+                activity
+                    .entry(exchange)
+                    .or_default()
+                    .unrealized
+                    .insert(PERP_COLLATERAL.to_string(), unrealized);
+            }
+        }
+        activity
+    }
+
+    /// Enter a funding payment in the realized PnL ledger
+    ///
+    /// The payment is credited to the portfolio and recorded in the funding
+    /// repository so `rebuild_pnl_ledger` replays it after a restart.
+    pub async fn record_funding_payment(
+        &self,
+        payment: FundingPayment,
+    ) -> Result<RealizedEntry, MpcError> {
+        if !payment.amount.is_finite() {
+            return Err(MpcError::InvalidInput(
+                "Funding amount must be finite".to_string(),
+            ));
+        }
+
+        let (entry, totals) = {
+            let mut ledger = self.pnl_ledger.lock().await;
+            let entry = ledger.apply_funding(payment);
+            (entry, ledger.totals())
+        };
+        self.trading_metrics.lock().await.sync_realized(&totals);
+
+        {
+            let mut portfolio_state = self.portfolio_state.lock().await;
+            portfolio_state.available_cash += entry.funding;
+            portfolio_state.total_value += entry.funding;
+        }
+        if let Some(exchange) = Exchange::from_name(&entry.exchange).filter(Exchange::is_perpetual)
+        {
+            self.own_activity
+                .lock()
+                .await
+                .entry(exchange)
+                .or_default()
+                .add_flow(PERP_COLLATERAL, entry.funding);
+        }
+
+        if let Some(funding) = &self.funding_repository {
+            let payment = CreateFundingPayment {
+                exchange: entry.exchange.clone(),
+                symbol: entry.symbol.clone(),
+                strategy: entry.strategy.clone(),
+                amount: entry.funding,
+                paid_at: entry.realized_at,
+            };
+            if let Err(e) = funding.create(payment).await {
+                warn!(
+                    "Failed to persist funding payment on {}: {}",
+                    entry.symbol, e
+                );
+            }
+        }
+
+        info!(
+            "Funding {:+.4} on {} {} credited to {}",
+            entry.funding, entry.exchange, entry.symbol, entry.strategy
+        );
+        Ok(entry)
+    }
+
+    /// Enter the funding payments the funding source settled since the last
+    /// one in the ledger
+    ///
+    /// Pulls from the latest payment already entered for the exchange, or
+    /// from the opening of its oldest lot when none was, and skips payments
+    /// already in the ledger, so a payment also entered through
+    /// `POST /pnl/funding` is not counted twice. Returns the number of
+    /// payments entered. No-op without a funding source.
+    pub async fn sync_funding_payments(&self) -> Result<usize, String> {
+        let Some(source) = &self.funding_source else {
+            return Ok(0);
+        };
+        let exchange = source.exchange();
+        let since = {
+            let ledger = self.pnl_ledger.lock().await;
+            ledger
+                .last_funding_at(exchange.name())
+                .or_else(|| ledger.first_opened_at(exchange.name()))
+        };
+        // Funding only accrues on positions we held
+        let Some(since) = since else {
+            return Ok(0);
+        };
+
+        let mut payments = source.funding_payments(since).await?;
+        payments.sort_by_key(|payment| payment.paid_at);
+        let mut entered = 0;
+        for mut payment in payments {
+            payment.symbol = TradingConfig::normalize_symbol(&payment.symbol);
+            let known = self.pnl_ledger.lock().await.has_funding(
+                &payment.exchange,
+                &payment.symbol,
+                payment.paid_at,
+            );
+            if known {
+                continue;
+            }
+            let symbol = payment.symbol.clone();
+            match self.record_funding_payment(payment).await {
+                Ok(_) => entered += 1,
+                Err(e) => warn!("Skipping funding payment on {}: {}", symbol, e),
+            }
+        }
+        Ok(entered)
+    }
+
+    /// Rebuild the realized PnL ledger from the trades and funding payments
+    /// persisted in the last `pnl_ledger_days`, oldest first
+    ///
+    /// The window reaches back to the opening of the oldest open position so
+    /// its lots are rebuilt. Closing trades of positions opened before the
+    /// window are left out, since their opening lots are not replayed.
+    /// Returns the realized totals, which also replace those of the trading
+    /// metrics. No-op without a trade repository.
+    pub async fn rebuild_pnl_ledger(&self) -> Result<PnlBreakdown, MpcError> {
+        let Some(trades) = &self.trade_repository else {
+            return Ok(self.pnl_ledger.lock().await.totals());
+        };
+
+        let since = self.pnl_ledger_window_start().await?;
+        let records = trades
+            .get_since(since)
+            .await
+            .map_err(|e| MpcError::InvalidConfiguration(format!("Failed to load trades: {}", e)))?;
+        let payments = match &self.funding_repository {
+            Some(funding) => funding.get_since(since).await.map_err(|e| {
+                MpcError::InvalidConfiguration(format!("Failed to load funding payments: {}", e))
+            })?,
+            None => Vec::new(),
+        };
+        let opened: HashSet<&str> = records
+            .iter()
+            .filter(|record| record.exit_reason.is_none())
+            .filter_map(|record| record.position_id.as_deref())
+            .collect();
+
+        // Funding realizes when paid, so it is merged into the trades by time
+        let mut ledger = PnlLedger::new(self.config.pnl_lot_matching);
+        let mut payments = payments.into_iter().map(ledger_funding).peekable();
+        for record in &records {
+            while let Some(payment) = payments.next_if(|p| p.paid_at <= record.executed_at) {
+                ledger.apply_funding(payment);
+            }
+            let closes_earlier_position = record.exit_reason.is_some()
+                && record
+                    .position_id
+                    .as_deref()
+                    .is_some_and(|id| !opened.contains(id));
+            if closes_earlier_position {
+                debug!(
+                    "Skipping trade {} closing a position opened before the ledger window",
+                    record.id
+                );
+                continue;
+            }
+            match ledger_fill(record) {
+                Some(fill) => {
+                    ledger.apply_fill(fill);
+                }
+                None => warn!("Trade {} has unknown side '{}'", record.id, record.side),
+            }
+        }
+        for payment in payments {
+            ledger.apply_funding(payment);
+        }
+
+        let totals = ledger.totals();
+        *self.pnl_ledger.lock().await = ledger;
+        self.trading_metrics.lock().await.sync_realized(&totals);
+        Ok(totals)
+    }
+
+    /// Start of the window `rebuild_pnl_ledger` replays: `pnl_ledger_days`
+    /// ago, or the opening of the oldest open position when earlier
+    async fn pnl_ledger_window_start(&self) -> Result<chrono::DateTime<chrono::Utc>, MpcError> {
+        if self.config.pnl_ledger_days == 0 {
+            return Ok(chrono::DateTime::<chrono::Utc>::UNIX_EPOCH);
+        }
+        let since =
+            chrono::Utc::now() - chrono::Duration::days(i64::from(self.config.pnl_ledger_days));
+        let Some(positions) = &self.position_repository else {
+            return Ok(since);
+        };
+
+        let open = positions.get_open_positions().await.map_err(|e| {
+            MpcError::InvalidConfiguration(format!("Failed to load open positions: {}", e))
+        })?;
+        Ok(open
+            .iter()
+            .map(|position| position.opened_at)
+            .fold(since, |since, opened_at| since.min(opened_at)))
+    }
+
+    /// Realized PnL grouped by `groups`, with the open lots
+    pub async fn get_realized_pnl(&self, groups: &[PnlGroup]) -> PnlReport {
+        self.pnl_ledger.lock().await.report(groups)
+    }
+
+    /// Reload brackets that still have open legs
+    ///
+    /// Returns the number of brackets restored. No-op without a bracket repository.
+    pub async fn restore_brackets(&self) -> Result<usize, MpcError> {
+        let Some(repository) = self.bracket_repository.as_ref() else {
+            return Ok(0);
+        };
+
+        let active = repository.get_active().await.map_err(|e| {
+            MpcError::InvalidConfiguration(format!("Failed to load bracket orders: {}", e))
+        })?;
+
+        let count = active.len();
+        let mut brackets = self.brackets.lock().await;
+        for bracket in active {
+            brackets.insert(bracket.id.clone(), bracket);
+        }
+
+        info!("Restored {} active bracket order(s)", count);
+        Ok(count)
+    }
+
+    /// Rebuild trading state lost on restart before trading resumes
+    ///
+    /// Reloads open positions with their stops and position groups, replays
+    /// the opening trades of the last 24 hours into the hourly and daily trade
+    /// limits and re-queries dYdX orders still marked active. Filled orders no
+    /// recorded trade or bracket accounts for raise a critical alert. The
+    /// summary is written to the audit log.
+    pub async fn recover_trading_state(&self) -> Result<RecoverySummary, MpcError> {
+        let mut summary = RecoverySummary::default();
+
+        if let Some(repository) = &self.position_repository {
+            let records = repository.get_open_positions().await.map_err(|e| {
+                MpcError::InvalidConfiguration(format!("Failed to load open positions: {}", e))
+            })?;
+            for record in records {
+                if self.open_positions.lock().await.contains_key(&record.id) {
+                    continue;
+                }
+                let position = match self.restore_position(&record).await {
+                    Ok(position) => position,
+                    Err(e) => {
+                        warn!("Failed to restore position {}: {}", record.id, e);
+                        continue;
+                    }
+                };
+                let value = position.entry_price.value() * position.quantity.value();
+                if position.stop_loss_price.is_none() {
+                    summary.positions_without_stop.push(position.id.clone());
+                }
+                summary.positions_restored += 1;
+                summary.position_value += value;
+                self.open_positions
+                    .lock()
+                    .await
+                    .insert(position.id.clone(), position);
+                self.update_portfolio_after_position_open(value).await;
+            }
+            summary.groups_restored = self.restore_position_groups(repository.as_ref()).await;
+        }
+
+        if let Some(repository) = &self.trade_repository {
+            let since = chrono::Utc::now() - chrono::Duration::hours(24);
+            let trades = repository.get_since(since).await.map_err(|e| {
+                MpcError::InvalidConfiguration(format!("Failed to load recent trades: {}", e))
+            })?;
+            let mut trade_history = self.trade_history.lock().await;
+            for trade in trades.into_iter().filter(|t| t.exit_reason.is_none()) {
+                let entry = (SystemTime::from(trade.executed_at), trade.symbol);
+                if !trade_history.contains(&entry) {
+                    trade_history.push(entry);
+                    summary.trades_replayed += 1;
+                }
+            }
+            trade_history.sort_by_key(|(timestamp, _)| *timestamp);
+        }
+
+        if let Some(repository) = &self.order_metadata_repository {
+            let orders = repository.get_active_orders().await.map_err(|e| {
+                MpcError::InvalidConfiguration(format!("Failed to load active orders: {}", e))
+            })?;
+            let trader = self.trader_for_exchange(Exchange::Dydx.name()).await;
+            for order in orders {
+                summary.orders_checked += 1;
+                let status = match &trader {
+                    Some(sender) => self
+                        .ask_trader(sender, |reply| TraderMessage::GetOrderStatus {
+                            order_id: order.order_id.clone(),
+                            reply,
+                        })
+                        .await
+                        .unwrap_or(OrderStatus::Unknown),
+                    None => OrderStatus::Unknown,
+                };
+                let updated = match status {
+                    OrderStatus::Filled => {
+                        summary.orders_filled += 1;
+                        if !self.is_fill_tracked(&order.order_id).await {
+                            warn!(
+                                "Order {} ({} {} {}) filled with no local record of it",
+                                order.order_id, order.side, order.quantity, order.symbol
+                            );
+                            summary.untracked_fills.push(order.order_id.clone());
+                        }
+                        repository.update_filled(&order.order_id).await
+                    }
+                    OrderStatus::Cancelled | OrderStatus::Rejected | OrderStatus::Expired => {
+                        summary.orders_closed += 1;
+                        repository.update_expired(&order.order_id).await
+                    }
+                    OrderStatus::Pending | OrderStatus::PartiallyFilled => {
+                        summary.orders_open += 1;
+                        Ok(())
+                    }
+                    OrderStatus::Unknown => {
+                        summary.orders_unverified += 1;
+                        Ok(())
+                    }
+                };
+                if let Err(e) = updated {
+                    warn!("Failed to update order {}: {}", order.order_id, e);
+                }
+            }
+            self.raise_untracked_fill_alert(&summary.untracked_fills)
+                .await;
+        }
+
+        if let Some(audit) = &self.audit_repository {
+            let entry = CreateAuditLog {
+                event_type: "state_recovered".to_string(),
+                exchange: "system".to_string(),
+                symbol: None,
+                details: serde_json::to_value(&summary).unwrap_or_default(),
+            };
+            if let Err(e) = audit.create(entry).await {
+                warn!("Failed to audit state recovery: {}", e);
+            }
+        }
+
+        debug!(
+            "Recovered {} open position(s), {} recent trade(s) and {} active order(s)",
+            summary.positions_restored, summary.trades_replayed, summary.orders_checked
+        );
+        Ok(summary)
+    }
+
+    /// Relink restored legs into the position groups they were opened in
+    ///
+    /// Returns the number of groups rebuilt. A group whose row is missing is
+    /// rebuilt from its legs, without its hedge ratio and entry z-score.
+    async fn restore_position_groups(&self, repository: &dyn PositionRepository) -> usize {
+        let mut legs_by_group: HashMap<String, Vec<Position>> = HashMap::new();
+        {
+            let positions = self.open_positions.lock().await;
+            for position in positions.values() {
+                if let Some(group_id) = &position.group_id {
+                    legs_by_group
+                        .entry(group_id.clone())
+                        .or_default()
+                        .push(position.clone());
+                }
+            }
+        }
+
+        let mut restored = 0;
+        for (group_id, mut legs) in legs_by_group {
+            if self.position_groups.lock().await.contains_key(&group_id) {
+                continue;
+            }
+            legs.sort_by(|a, b| a.entry_time.cmp(&b.entry_time).then(a.id.cmp(&b.id)));
+            let position_ids = legs.iter().map(|leg| leg.id.clone()).collect();
+            let record = match repository.get_group(&group_id).await {
+                Ok(record) => record,
+                Err(e) => {
+                    warn!("Failed to load position group {}: {}", group_id, e);
+                    None
+                }
+            };
+            let group = match record {
+                Some(record) => PositionGroup {
+                    id: record.id,
+                    strategy: record.strategy,
+                    position_ids,
+                    hedge_ratio: record.hedge_ratio,
+                    entry_zscore: record.entry_zscore,
+                    opened_at: record.opened_at,
+                },
+                None => {
+                    warn!(
+                        "Position group {} has no stored row, rebuilding it from its legs",
+                        group_id
+                    );
+                    PositionGroup {
+                        id: group_id.clone(),
+                        strategy: legs[0].strategy.clone().unwrap_or_default(),
+                        position_ids,
+                        hedge_ratio: 0.0,
+                        entry_zscore: 0.0,
+                        opened_at: legs[0].entry_time,
+                    }
+                }
+            };
+            self.position_groups.lock().await.insert(group_id, group);
+            restored += 1;
+        }
+        restored
+    }
+
+    /// Whether a recorded trade or a tracked bracket leg accounts for an order
+    async fn is_fill_tracked(&self, order_id: &str) -> bool {
+        {
+            let brackets = self.brackets.lock().await;
+            let bracket_leg = brackets.values().any(|bracket| {
+                [
+                    bracket.entry.as_ref(),
+                    Some(&bracket.stop_loss),
+                    Some(&bracket.take_profit),
+                ]
+                .into_iter()
+                .flatten()
+                .any(|leg| leg.exchange_order_id.as_deref() == Some(order_id))
+            });
+            if bracket_leg {
+                return true;
+            }
+        }
+        match &self.trade_repository {
+            Some(trades) => match trades.get_by_exchange_order(order_id).await {
+                Ok(records) => !records.is_empty(),
+                Err(e) => {
+                    warn!("Failed to look up trades of order {}: {}", order_id, e);
+                    false
+                }
+            },
+            None => false,
+        }
+    }
+
+    /// Raise a critical alert for fills no local position accounts for
+    ///
+    /// The position they opened or closed on the venue is left to the
+    /// operator rather than guessed at.
+    async fn raise_untracked_fill_alert(&self, order_ids: &[String]) {
+        if order_ids.is_empty() {
+            return;
+        }
+        let message = format!(
+            "{} order(s) filled on {} with no local record: {}",
+            order_ids.len(),
+            Exchange::Dydx.name(),
+            order_ids.join(", ")
+        );
+        error!("🚨 {}", message);
+        self.active_alerts.lock().await.push(SystemAlert {
+            alert_type: AlertType::UntrackedFill(Exchange::Dydx),
+            message,
+            severity: crate::domain::services::metrics::AlertSeverity::Critical,
+            timestamp: SystemTime::now(),
+            resolved: false,
+        });
+    }
+
+    /// Mark a dYdX order filled so recovery does not query it again
+    async fn mark_order_filled(&self, order_id: &str) {
+        let Some(repository) = &self.order_metadata_repository else {
+            return;
+        };
+        if let Err(e) = repository.update_filled(order_id).await {
+            debug!("Failed to mark order {} filled: {}", order_id, e);
+        }
+    }
+
+    /// Position tracked by a persisted open position row
+    ///
+    /// Rows saved without stops get the configured ones.
+    async fn restore_position(&self, record: &PositionRecord) -> Result<Position, String> {
+        let side = match record.side.as_str() {
+            "long" => PositionSide::Long,
+            "short" => PositionSide::Short,
+            other => return Err(format!("unknown side '{}'", other)),
+        };
+        let mut position = Position::new_with_stops(
+            record.id.clone(),
+            record.symbol.clone(),
+            side,
+            Quantity::new(record.quantity).map_err(|e| e.to_string())?,
+            Price::new(record.entry_price).map_err(|e| e.to_string())?,
+            self.config.stop_loss_percentage,
+            self.config.take_profit_percentage,
+        )
+        .map_err(|e| e.to_string())?;
+        position.entry_time = record.opened_at;
+        position.group_id = record.group_id.clone();
+        position.current_price = Price::new(record.current_price)
+            .ok()
+            .filter(|price| price.value() > 0.0);
+        if let Some(stop) = record.stop_loss {
+            position.stop_loss_price = Some(Price::new(stop).map_err(|e| e.to_string())?);
+        }
+        if let Some(target) = record.take_profit {
+            position.take_profit_price = Some(Price::new(target).map_err(|e| e.to_string())?);
+        }
+
+        // The opening trade names the strategy whose exit rules apply
+        if let Some(trades) = &self.trade_repository {
+            if let Ok(Some(trade)) = trades.get(&format!("trade_open_{}", record.id)).await {
+                if trade.strategy != "SignalCombiner" {
+                    position.strategy = Some(trade.strategy);
+                }
+            }
+        }
+        Ok(position)
+    }
+
+    /// Get all tracked brackets, including completed ones
+    pub async fn get_brackets(&self) -> Vec<BracketOrder> {
+        let brackets = self.brackets.lock().await;
+        brackets.values().cloned().collect()
+    }
+
+    /// Link stop-loss and take-profit exits to a freshly opened position
+    ///
+    /// Exits are placed natively when the trader's exchange supports it and
+    /// emulated locally otherwise (or when native placement fails).
+    async fn attach_bracket(
+        &self,
+        position: &Position,
+        exchange: &str,
+        entry_order_id: &str,
+        trader_sender: &mpsc::Sender<TraderMessage>,
+    ) {
+        let (Some(stop), Some(target)) = (position.stop_loss_price, position.take_profit_price)
+        else {
+            return;
+        };
+
+        let exit_side = match position.side {
+            PositionSide::Long => OrderSide::Sell,
+            PositionSide::Short => OrderSide::Buy,
+        };
+
+        let mut bracket = match BracketOrder::oco(
+            format!("bracket_{}", position.id),
+            position.symbol.clone(),
+            exit_side,
+            position.quantity.value(),
+            stop.value(),
+            target.value(),
+        ) {
+            Ok(bracket) => bracket,
+            Err(e) => {
+                warn!("Cannot bracket position {}: {}", position.id, e);
+                return;
+            }
+        };
+        bracket.position_id = Some(position.id.clone());
+        bracket.exchange = exchange.to_string();
+        bracket.link_entry(
+            entry_order_id.to_string(),
+            Some(position.entry_price.value()),
+        );
+        // Positions are opened as soon as the entry order is accepted
+        bracket.record_fill(BracketLegRole::Entry);
+
+        let bracket_for_exchange = bracket.clone();
+        match self
+            .ask_trader(trader_sender, |reply| TraderMessage::PlaceBracketExits {
+                bracket: bracket_for_exchange,
+                reply,
+            })
+            .await
+        {
+            Ok(Some(ids)) => {
+                info!(
+                    "Bracket {} placed natively on {} (SL: {}, TP: {})",
+                    bracket.id, exchange, ids.stop_loss, ids.take_profit
+                );
+                bracket.attach_native_exits(ids);
+            }
+            Ok(None) => {
+                debug!(
+                    "Exchange {} has no native brackets, emulating {}",
+                    exchange, bracket.id
+                );
+            }
+            Err(e) => {
+                warn!(
+                    "Native bracket placement failed for {}: {} - emulating locally",
+                    bracket.id, e
+                );
+            }
+        }
+
+        self.persist_bracket(&bracket).await;
+        let mut brackets = self.brackets.lock().await;
+        brackets.insert(bracket.id.clone(), bracket);
+    }
+
+    /// Active bracket protecting a position
+    async fn bracket_for_position(&self, position_id: &str) -> Option<BracketOrder> {
+        let brackets = self.brackets.lock().await;
+        brackets
+            .values()
+            .find(|b| b.is_active() && b.position_id.as_deref() == Some(position_id))
+            .cloned()
+    }
+
+    /// Store a bracket in memory and in the database
+    async fn update_bracket(&self, bracket: BracketOrder) {
+        self.persist_bracket(&bracket).await;
+        let mut brackets = self.brackets.lock().await;
+        brackets.insert(bracket.id.clone(), bracket);
+    }
+
+    async fn persist_bracket(&self, bracket: &BracketOrder) {
+        if let Some(repository) = self.bracket_repository.as_ref() {
+            if let Err(e) = repository.save(bracket).await {
+                warn!("Failed to persist bracket {}: {}", bracket.id, e);
+            }
+        }
+    }
+
+    /// Cancel exchange orders left over by a bracket
+    async fn cancel_bracket_orders(&self, bracket: &BracketOrder, order_ids: Vec<String>) {
+        if order_ids.is_empty() {
+            return;
+        }
+        let Some(trader_sender) = self.trader_for_exchange(&bracket.exchange).await else {
+            warn!(
+                "No trader on {} to cancel bracket {} orders {:?}",
+                bracket.exchange, bracket.id, order_ids
+            );
+            return;
+        };
+        for order_id in order_ids {
+            let cancel_id = order_id.clone();
+            if let Err(e) = self
+                .ask_trader(&trader_sender, |reply| TraderMessage::CancelOrder {
+                    order_id: cancel_id,
+                    reply,
+                })
+                .await
+            {
+                warn!(
+                    "Failed to cancel bracket {} order {}: {}",
+                    bracket.id, order_id, e
+                );
+            }
+        }
+    }
+
+    /// Poll native exit legs and apply OCO when the exchange reports a fill
+    ///
+    /// A leg that disappears without filling drops the bracket back to local
+    /// emulation so the position stays protected.
+    async fn sync_native_brackets(&self) -> Vec<Result<String, MpcError>> {
+        let mut results = Vec::new();
+        let native: Vec<BracketOrder> = {
+            let brackets = self.brackets.lock().await;
+            brackets
+                .values()
+                .filter(|b| b.is_active() && b.linking == BracketLinking::Native)
+                .cloned()
+                .collect()
+        };
+
+        for mut bracket in native {
+            let Some(trader_sender) = self.trader_for_exchange(&bracket.exchange).await else {
+                continue;
+            };
+
+            let mut filled = None;
+            let mut dropped = false;
+            for leg in [&bracket.stop_loss, &bracket.take_profit] {
+                let Some(order_id) = leg.exchange_order_id.clone() else {
+                    continue;
+                };
+                match self
+                    .ask_trader(&trader_sender, |reply| TraderMessage::GetOrderStatus {
+                        order_id,
+                        reply,
+                    })
+                    .await
+                {
+                    Ok(OrderStatus::Filled) => {
+                        filled = Some(leg.role);
+                        break;
+                    }
+                    Ok(OrderStatus::Cancelled | OrderStatus::Rejected | OrderStatus::Expired) => {
+                        dropped = true;
+                    }
+                    Ok(_) => {}
+                    Err(e) => debug!("Could not poll bracket {} {}: {}", bracket.id, leg.role, e),
+                }
+            }
+
+            if let Some(role) = filled {
+                let to_cancel = bracket.record_fill(role);
+                self.cancel_bracket_orders(&bracket, to_cancel).await;
+                let position_id = bracket.position_id.clone();
+                let exit_order_id = bracket
+                    .leg(role)
+                    .and_then(|leg| leg.exchange_order_id.clone());
+                self.update_bracket(bracket).await;
+
+                if let (Some(position_id), Some(reason)) = (position_id, role.exit_reason()) {
+                    results.push(
+                        self.close_position_by_order(
+                            &position_id,
+                            reason,
+                            exit_order_id.as_deref(),
+                        )
+                        .await
+                        .map(|_| format!("Position {} closed due to {}", position_id, reason)),
+                    );
+                }
+            } else if dropped {
+                warn!(
+                    "Exchange dropped a leg of bracket {}, falling back to local emulation",
+                    bracket.id
+                );
+                let to_cancel = bracket.revert_to_emulated();
+                self.cancel_bracket_orders(&bracket, to_cancel).await;
+                self.update_bracket(bracket).await;
+            }
+        }
+
+        results
+    }
+
+    /// Send the market exit for an emulated bracket leg and record the fill
+    ///
+    /// Returns the exchange order id of the exit.
+    async fn fire_emulated_exit(
+        &self,
+        mut bracket: BracketOrder,
+        role: BracketLegRole,
+    ) -> Result<String, MpcError> {
+        let exit_order = bracket
+            .exit_order(role)
+            .map_err(|e| MpcError::InvalidInput(format!("Invalid bracket exit: {}", e)))?;
+        let trader_sender = self
+            .trader_for_exchange(&bracket.exchange)
+            .await
+            .ok_or_else(|| {
+                MpcError::OrderPlacementFailed(format!(
+                    "No trader available on {} for bracket {}",
+                    bracket.exchange, bracket.id
+                ))
+            })?;
+
+        let exchange_order_id = self
+            .ask_trader(&trader_sender, |reply| TraderMessage::PlaceOrder {
+                order: exit_order,
+                reply,
+            })
+            .await?;
+
+        info!(
+            "Emulated {} fired for bracket {} (order {})",
+            role, bracket.id, exchange_order_id
+        );
+        bracket.mark_working(role, exchange_order_id.clone());
+        bracket.record_fill(role);
+        self.update_bracket(bracket).await;
+        Ok(exchange_order_id)
+    }
+
+    /// Cancel the open legs of a position's bracket once it is closed
+    async fn release_position_bracket(&self, position_id: &str) {
+        if let Some(mut bracket) = self.bracket_for_position(position_id).await {
+            let to_cancel = bracket.cancel_all();
+            self.cancel_bracket_orders(&bracket, to_cancel).await;
+            self.update_bracket(bracket).await;
+        }
+    }
+
+    /// Persist a fill that belongs to no tracked position as a trade and
+    /// enter it in the PnL ledger
+    ///
+    /// Returns the net PnL it realized, or None when it was not recorded
+    /// (e.g. without a trade repository). Failures are logged and never
+    /// abort trading.
+    async fn record_strategy_fill(&self, trade: CreateTrade) -> Option<f64> {
+        let trades = self.trade_repository.as_ref()?;
+        let (strategy, id) = (trade.strategy.clone(), trade.id.clone());
+        match trades.create(trade).await {
+            Ok(record) => Some(self.enter_in_ledger(&record).await),
+            Err(e) => {
+                warn!("Failed to record {} fill {}: {}", strategy, id, e);
+                None
+            }
+        }
+    }
+
+    /// Find a trader currently executing on `exchange` (as stored on positions)
+    async fn trader_for_exchange(&self, exchange: &str) -> Option<mpsc::Sender<TraderMessage>> {
+        let senders: Vec<mpsc::Sender<TraderMessage>> = {
+            let traders = self.traders.lock().await;
+            traders.values().cloned().collect()
+        };
+
+        for sender in senders {
+            if let Some(active) = self.trader_active_exchange(&sender).await {
+                if format!("{:?}", active).to_lowercase() == exchange {
+                    return Some(sender);
+                }
+            }
+        }
+        None
+    }
+
+    /// Send a request to a trader and wait for its reply
+    async fn ask_trader<T>(
+        &self,
+        trader_sender: &mpsc::Sender<TraderMessage>,
+        message: impl FnOnce(mpsc::Sender<Result<T, String>>) -> TraderMessage,
+    ) -> Result<T, MpcError> {
+        let (reply_tx, mut reply_rx) = mpsc::channel(1);
+        trader_sender.send(message(reply_tx)).await?;
+        timeout(CHANNEL_REPLY_TIMEOUT, reply_rx.recv())
+            .await
+            .map_err(|_| MpcError::Timeout)?
+            .ok_or(MpcError::NoResponse)?
+            .map_err(MpcError::OrderPlacementFailed)
+    }
+
+    /// Update position prices with current market prices
+    ///
+    /// This method minimizes lock contention by:
+    /// 1. Collecting symbols without holding the lock
+    /// 2. Fetching prices CONCURRENTLY using futures::join_all (no lock held)
+    /// 3. Updating positions with a short final lock
+    pub async fn update_position_prices(&self) -> Result<(), MpcError> {
+        // Step 1: Collect symbols that need price updates (short lock)
+        let symbols_to_update: Vec<String> = {
+            let positions = self.open_positions.lock().await;
+            positions
+                .values()
+                .map(|p| p.symbol.clone())
+                .collect::<std::collections::HashSet<_>>()
+                .into_iter()
+                .collect()
+        };
+
+        // Step 2: Fetch all prices CONCURRENTLY (no lock held)
+        // SPEC REQUIREMENT: Use futures::join_all for parallel price fetching
+        let price_futures: Vec<_> = symbols_to_update
+            .iter()
+            .map(|symbol| async move {
+                let price_result = self.get_aggregated_price(symbol).await;
+                (symbol.clone(), price_result)
+            })
+            .collect();
+
+        let price_results = futures_util::future::join_all(price_futures).await;
+
+        // Collect successful price fetches
+        let mut prices = HashMap::new();
+        for (symbol, price_result) in price_results {
+            if let Ok(current_price) = price_result {
+                prices.insert(symbol, current_price);
+            }
+        }
+
+        // Step 3: Update positions with fetched prices (short lock)
+        let mut positions = self.open_positions.lock().await;
+        for (symbol, price) in prices {
+            for position in positions.values_mut() {
+                if position.symbol == symbol {
+                    position.update_price(price);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get all open positions
+
+    pub async fn get_open_positions(&self) -> HashMap<String, Position> {
+        let positions = self.open_positions.lock().await;
+        positions.clone()
+    }
+
+    /// Get total unrealized PnL across all positions
+    ///
+    /// Returns the sum of all unrealized PnL across open positions.
+    /// The result can be positive (net profit) or negative (net loss).
+    /// Get total unrealized PnL across all positions
+    ///
+    /// Returns the sum of all unrealized PnL across open positions.
+    /// The result can be positive (net profit) or negative (net loss).
+    ///
+    /// If the total PnL is invalid (NaN/Infinity), logs detailed error information
+    /// and returns zero as a safe fallback.
+    pub async fn get_total_unrealized_pnl(&self) -> PnL {
+        let positions = self.open_positions.lock().await;
+        let mut total_pnl = 0.0;
+        let mut position_pnls = Vec::new();
+
+        for (position_id, position) in positions.iter() {
+            if let Some(pnl) = position.unrealized_pnl() {
+                let pnl_value = pnl.value();
+                position_pnls.push((position_id.clone(), pnl_value));
+                total_pnl += pnl_value;
+            }
+        }
+
+        // PnL can be negative, which is valid for losses
+        match PnL::new(total_pnl) {
+            Ok(pnl) => pnl,
+            Err(e) => {
+                error!(
+                    "Failed to create PnL from total: {} - Error: {}. \
+                     Position PnLs: {:?}. Using zero PnL as fallback.",
+                    total_pnl, e, position_pnls
+                );
+                PnL::zero()
+            }
+        }
+    }
+
+    /// Fetch portfolio value from all exchanges and update internal state
+    ///
+    /// This fetches the real-time balance from all available exchanges (Coinbase, dYdX, etc.)
+    /// and aggregates them into a total portfolio value in USD.
+    ///
+    /// # Returns
+    /// Updated total portfolio value in USD
+    pub async fn fetch_and_update_portfolio_from_exchanges(&self) -> Result<f64, MpcError> {
+        use crate::application::actors::trader_actor::TraderMessage;
+
+        // Get all traders to query all exchanges
+        let traders = self.traders.lock().await;
 
         if traders.is_empty() {
             return Err(MpcError::InvalidConfiguration(
@@ -930,65 +3255,77 @@ impl MpcService {
         );
     }
 
-    /// Calculate position size based on portfolio percentage and current price
-    ///
-    /// # Arguments
-    /// * `_symbol` - The trading symbol (reserved for future risk management per symbol)
-    /// * `current_price` - Current market price for the asset
-    ///
-    /// # Returns
-    /// The quantity to trade, calculated as a percentage of current portfolio value
-    /// Calculate position size based on portfolio percentage and current price
+    /// Calculate position size using the sizing mode configured for a strategy
     ///
     /// # Arguments
-    /// * `_symbol` - The trading symbol (reserved for future risk management per symbol)
+    /// * `symbol` - The trading symbol, used to read candles for ATR and volatility
     /// * `current_price` - Current market price for the asset
+    /// * `strategy` - Strategy behind the order, selecting the sizing mode
     ///
     /// # Returns
-    /// The quantity to trade, calculated as a percentage of current portfolio value
+    /// The quantity to trade. Risk-based modes whose inputs are unavailable
+    /// (no stop, too few candles or closed trades) fall back to the fixed
+    /// portfolio percentage.
     ///
     /// # Errors
     /// Returns an error if:
     /// - Portfolio value is zero or negative
-    /// - Calculation results in overflow
+    /// - The selected mode sizes the position to zero (e.g. Kelly without an edge)
     /// - Quantity is not finite
     pub async fn calculate_position_size(
         &self,
-        _symbol: &str,
+        symbol: &str,
         current_price: Price,
+        strategy: Option<&str>,
     ) -> Result<f64, MpcError> {
         let portfolio_value = self.get_portfolio_value().await;
 
         // Ensure we have positive portfolio value
         if portfolio_value <= 0.0 {
-            return Err(MpcError::InvalidInput(
+            return Err(MpcError::PositionSizingRefused(
                 "Portfolio value is zero or negative. Cannot calculate position size.".to_string(),
             ));
         }
 
         if !portfolio_value.is_finite() {
-            return Err(MpcError::InvalidInput(format!(
+            return Err(MpcError::PositionSizingRefused(format!(
                 "Portfolio value is not finite: {}",
                 portfolio_value
             )));
         }
 
-        // Calculate position value (f64 doesn't have checked_mul, but we validate result)
-        let position_value = portfolio_value * self.config.portfolio_percentage_per_position;
+        let (mode, params) = self.config.sizing_for(strategy);
+        let inputs = self
+            .sizing_inputs(
+                symbol,
+                portfolio_value,
+                current_price.value(),
+                strategy,
+                params.atr_period,
+            )
+            .await;
 
-        if !position_value.is_finite() {
-            return Err(MpcError::InvalidInput(format!(
-                "Position value calculation resulted in non-finite value: {} * {} = {}",
-                portfolio_value, self.config.portfolio_percentage_per_position, position_value
-            )));
-        }
+        let sizer = PositionSizer::new();
+        let sizing = match sizer.target_size(mode, &params, &inputs) {
+            Ok(sizing) => sizing,
+            Err(e) if mode != SizingMode::FixedPercentage => {
+                warn!(
+                    "{} sizing unavailable for {} ({}), falling back to fixed percentage",
+                    mode, symbol, e
+                );
+                sizer
+                    .target_size(SizingMode::FixedPercentage, &params, &inputs)
+                    .map_err(MpcError::PositionSizingRefused)?
+            }
+            Err(e) => return Err(MpcError::PositionSizingRefused(e)),
+        };
+        debug!("Sizing {} with {}: {}", symbol, mode, sizing.reason);
 
-        // Calculate quantity: position_value / current_price
-        let quantity = position_value / current_price.value();
+        let quantity = sizing.quantity;
 
         // Validation: ensure quantity is finite and positive
         if !quantity.is_finite() {
-            return Err(MpcError::InvalidInput(format!(
+            return Err(MpcError::PositionSizingRefused(format!(
                 "Invalid quantity calculated: {} (portfolio_value: {}, price: {})",
                 quantity,
                 portfolio_value,
@@ -996,10 +3333,10 @@ impl MpcService {
             )));
         }
 
-        if quantity < 0.0 {
-            return Err(MpcError::InvalidInput(format!(
-                "Negative quantity calculated: {}",
-                quantity
+        if quantity <= 0.0 {
+            return Err(MpcError::PositionSizingRefused(format!(
+                "Position sized to zero by {} sizing: {}",
+                mode, sizing.reason
             )));
         }
 
@@ -1009,25 +3346,111 @@ impl MpcService {
         Ok(final_quantity)
     }
 
-    /// Check and execute stop-loss and take-profit orders
+    /// Gather the market and performance inputs used by the sizing modes
+    async fn sizing_inputs(
+        &self,
+        symbol: &str,
+        equity: f64,
+        price: f64,
+        strategy: Option<&str>,
+        atr_period: usize,
+    ) -> SizingInputs {
+        let candles = self.get_candles(symbol).await;
+        let mut inputs = SizingInputs::new(equity, price);
+
+        inputs.stop_distance = self.config.stop_loss_percentage.map(|pct| price * pct);
+        inputs.atr = ATR::new(atr_period).calculate(&candles).last().copied();
+        inputs.annualized_volatility =
+            PositionSizer::realized_volatility(&candles, CANDLES_PER_YEAR);
+
+        if let Some(strategy) = strategy {
+            let strategy_metrics = self.strategy_metrics.lock().await;
+            if let Some(metrics) = strategy_metrics.get(strategy) {
+                inputs.win_rate = metrics.win_rate();
+                inputs.payoff_ratio = metrics.payoff_ratio();
+                inputs.closed_trades = metrics.closed_trades();
+            }
+        }
+
+        inputs
+    }
+
+    /// Check and execute stop-loss, take-profit and time-based exits
+    ///
+    /// Price-based stops are evaluated first; positions that survive them are
+    /// checked against the time-exit rule resolved for their symbol and strategy.
+    /// Positions with natively placed bracket exits are left to the exchange and
+    /// only closed here once a leg is reported filled.
 
     pub async fn check_and_execute_stops(&self) -> Vec<Result<String, MpcError>> {
-        let mut results = Vec::new();
+        let mut results = self.sync_native_brackets().await;
         let mut positions_to_close = Vec::new();
+        let now = chrono::Utc::now();
+
+        // Positions whose stops rest on the exchange must not be closed locally on price
+        let natively_protected: Vec<String> = {
+            let brackets = self.brackets.lock().await;
+            brackets
+                .values()
+                .filter(|b| b.is_active() && b.linking == BracketLinking::Native)
+                .filter_map(|b| b.position_id.clone())
+                .collect()
+        };
 
         {
             let positions = self.open_positions.lock().await;
-            for (position_id, position) in positions.iter() {
-                if position.should_stop_loss() {
-                    positions_to_close.push((position_id.clone(), "stop-loss"));
-                } else if position.should_take_profit() {
-                    positions_to_close.push((position_id.clone(), "take-profit"));
+            // Grouped legs are stopped on the combined PnL of their group below
+            for (position_id, position) in positions.iter().filter(|(_, p)| p.group_id.is_none()) {
+                let price_stops_local = !natively_protected.contains(position_id);
+                if price_stops_local && position.should_stop_loss() {
+                    positions_to_close.push((position_id.clone(), ExitReason::StopLoss));
+                } else if price_stops_local && position.should_take_profit() {
+                    positions_to_close.push((position_id.clone(), ExitReason::TakeProfit));
+                } else {
+                    let rule = self
+                        .config
+                        .time_exit_rule_for(&position.symbol, position.strategy.as_deref());
+                    if let Some(reason) = position.time_exit_reason(&rule, now) {
+                        debug!(
+                            "Position {} held for {}s triggers {} exit",
+                            position_id,
+                            position.holding_duration(now).num_seconds(),
+                            reason
+                        );
+                        positions_to_close.push((position_id.clone(), reason));
+                    }
                 }
             }
         }
 
         for (position_id, reason) in positions_to_close {
-            match self.close_position(&position_id).await {
+            // Emulated bracket legs send their exit order before the position is closed
+            let role = match reason {
+                ExitReason::StopLoss => Some(BracketLegRole::StopLoss),
+                ExitReason::TakeProfit => Some(BracketLegRole::TakeProfit),
+                _ => None,
+            };
+            let mut exit_order_id = None;
+            if let (Some(role), Some(bracket)) =
+                (role, self.bracket_for_position(&position_id).await)
+            {
+                match self.fire_emulated_exit(bracket, role).await {
+                    Ok(order_id) => exit_order_id = Some(order_id),
+                    Err(e) => {
+                        warn!(
+                            "Failed to send {} exit for position {}: {}",
+                            role, position_id, e
+                        );
+                        results.push(Err(e));
+                        continue;
+                    }
+                }
+            }
+
+            match self
+                .close_position_by_order(&position_id, reason, exit_order_id.as_deref())
+                .await
+            {
                 Ok(()) => {
                     results.push(Ok(format!(
                         "Position {} closed due to {}",
@@ -1040,6 +3463,24 @@ impl MpcService {
             }
         }
 
+        for (group, legs) in self.get_position_groups().await {
+            let Some(first) = legs.first() else {
+                continue;
+            };
+            let rule = self
+                .config
+                .time_exit_rule_for(&first.symbol, Some(&group.strategy));
+            if let Some(reason) = group.exit_reason(
+                &legs,
+                self.config.stop_loss_percentage,
+                self.config.take_profit_percentage,
+                &rule,
+                now,
+            ) {
+                results.push(self.close_position_group(&group.id, reason).await);
+            }
+        }
+
         results
     }
 
@@ -1049,7 +3490,7 @@ impl MpcService {
         &self,
         symbol: &str,
         signal: &TradingSignal,
-    ) -> Result<String, MpcError> {
+    ) -> Result<SignalExecution, MpcError> {
         use crate::domain::entities::order::{Order, OrderSide, OrderType};
 
         // Validate symbol format
@@ -1058,14 +3499,7 @@ impl MpcService {
         // SPEC REQUIREMENT: Validate symbol against whitelist
         // Check if symbol is in the configured whitelist for any exchange
         let normalized_symbol = crate::config::TradingConfig::normalize_symbol(symbol);
-        let is_whitelisted = self.config.symbols.values().any(|symbols| {
-            symbols.iter().any(|whitelisted_symbol| {
-                crate::config::TradingConfig::normalize_symbol(whitelisted_symbol)
-                    == normalized_symbol
-            })
-        });
-
-        if !is_whitelisted {
+        if !self.is_whitelisted(symbol) {
             warn!(
                 "Symbol '{}' (normalized: '{}') is not in the configured whitelist - order rejected",
                 symbol, normalized_symbol
@@ -1084,6 +3518,7 @@ impl MpcService {
                 "Automated trading is disabled".to_string(),
             ));
         }
+        self.check_reconciliation_pause(symbol).await?;
 
         // SPEC REQUIREMENT: Check trader availability FIRST (before any calculations)
         // This prevents wasting CPU time if no traders are available
@@ -1097,6 +3532,15 @@ impl MpcService {
 
         debug!("✓ Trader '{}' is available for order execution", trader_id);
 
+        // Resolve the strategy behind the selected trader (used for per-strategy exit rules)
+        let trader_strategy = {
+            let order = self.strategy_order.lock().await;
+            order
+                .iter()
+                .find(|name| format!("trader_{}", name.to_lowercase()) == trader_id)
+                .cloned()
+        };
+
         // Check trading limits before proceeding
         self.check_trading_limits().await?;
 
@@ -1109,32 +3553,41 @@ impl MpcService {
                 (OrderSide::Sell, PositionSide::Short)
             }
             crate::domain::services::strategies::Signal::Hold => {
-                return Ok("No order executed - signal is HOLD".to_string());
+                return Ok(SignalExecution::skipped(
+                    SignalSkipReason::Hold,
+                    "No order executed - signal is HOLD".to_string(),
+                ));
             }
         };
 
         // Check confidence BEFORE doing any expensive operations
-        if signal.confidence < self.config.min_confidence_threshold {
+        let min_confidence = self.confidence_threshold_for(symbol).await;
+        if signal.confidence < min_confidence {
             debug!(
                 "Signal confidence {:.3} below threshold {:.3} for {} - order rejected",
-                signal.confidence, self.config.min_confidence_threshold, symbol
+                signal.confidence, min_confidence, symbol
             );
-            return Ok(format!(
-                "Signal confidence {:.2} too low for execution (minimum {:.2})",
-                signal.confidence, self.config.min_confidence_threshold
+            return Ok(SignalExecution::skipped(
+                SignalSkipReason::Confidence,
+                format!(
+                    "Signal confidence {:.2} too low for execution (minimum {:.2})",
+                    signal.confidence, min_confidence
+                ),
             ));
         }
 
         debug!(
             "Signal confidence {:.3} meets threshold {:.3} for {} - proceeding with order",
-            signal.confidence, self.config.min_confidence_threshold, symbol
+            signal.confidence, min_confidence, symbol
         );
 
         // Get current price (for logging purposes)
         let current_price = self.get_aggregated_price(symbol).await?;
 
-        // Calculate position size based on portfolio percentage
-        let quantity = self.calculate_position_size(symbol, current_price).await?;
+        // Calculate position size with the sizing mode configured for the strategy
+        let quantity = self
+            .calculate_position_size(symbol, current_price, trader_strategy.as_deref())
+            .await?;
         let quantity = Quantity::new(quantity).map_err(|e| {
             MpcError::InvalidConfiguration(format!("Invalid quantity calculation: {}", e))
         })?;
@@ -1147,16 +3600,22 @@ impl MpcService {
                 positions.values().filter(|p| p.symbol == symbol).collect();
 
             if symbol_positions.len() >= self.config.max_positions_per_symbol {
-                return Ok(format!(
-                    "Maximum positions per symbol ({}) reached for {}",
-                    self.config.max_positions_per_symbol, symbol
+                return Ok(SignalExecution::skipped(
+                    SignalSkipReason::Limits,
+                    format!(
+                        "Maximum positions per symbol ({}) reached for {}",
+                        self.config.max_positions_per_symbol, symbol
+                    ),
                 ));
             }
 
             if positions.len() >= self.config.max_total_positions {
-                return Ok(format!(
-                    "Maximum total positions ({}) reached",
-                    self.config.max_total_positions
+                return Ok(SignalExecution::skipped(
+                    SignalSkipReason::Limits,
+                    format!(
+                        "Maximum total positions ({}) reached",
+                        self.config.max_total_positions
+                    ),
                 ));
             }
 
@@ -1169,7 +3628,7 @@ impl MpcService {
             let position_id = format!("pos_{}_{}", symbol, timestamp);
 
             // Create actual position with stops
-            let position = Position::new_with_stops(
+            let mut position = Position::new_with_stops(
                 position_id.clone(),
                 symbol.to_string(),
                 position_side.clone(),
@@ -1181,6 +3640,7 @@ impl MpcService {
             .map_err(|e| {
                 MpcError::InvalidConfiguration(format!("Failed to create position: {}", e))
             })?;
+            position.strategy = trader_strategy.clone();
 
             // Insert position atomically while holding lock
             positions.insert(position_id.clone(), position);
@@ -1266,15 +3726,9 @@ impl MpcService {
                     trade_history.retain(|(timestamp, _)| *timestamp >= one_day_ago);
                 }
 
-                // Record strategy execution for all strategies (simplified approach)
+                // Record strategy execution for all strategies serving the symbol (simplified approach)
                 // In a real implementation, we'd track which strategies contributed to the signal
-                let strategy_names = {
-                    let combiner_guard = self.signal_combiner.read().await;
-                    combiner_guard
-                        .as_ref()
-                        .map(|c| c.get_strategy_names())
-                        .unwrap_or_default()
-                };
+                let strategy_names = self.strategies_for_symbol(symbol).await;
 
                 for strategy_name in strategy_names {
                     // Calculate proportional PnL based on strategy weight
@@ -1284,6 +3738,29 @@ impl MpcService {
                         Price::new(0.0).expect("Zero price should always be valid");
                     self.record_strategy_execution(&strategy_name, proportional_pnl)
                         .await;
+                    self.record_symbol_strategy_execution(&strategy_name, symbol, proportional_pnl)
+                        .await;
+                }
+
+                let exchange = self
+                    .trader_active_exchange(&trader_sender)
+                    .await
+                    .map(|exchange| format!("{:?}", exchange).to_lowercase())
+                    .unwrap_or_else(|| "unknown".to_string());
+                let opened_position = {
+                    let positions = self.open_positions.lock().await;
+                    positions.get(&position_id).cloned()
+                };
+                if let Some(opened_position) = opened_position {
+                    self.record_position_open(
+                        &opened_position,
+                        &exchange,
+                        Some(&order_id),
+                        Some(signal),
+                    )
+                    .await;
+                    self.attach_bracket(&opened_position, &exchange, &order_id, &trader_sender)
+                        .await;
                 }
 
                 // Calculate position value for portfolio tracking
@@ -1295,10 +3772,10 @@ impl MpcService {
 
                 info!("ORDER EXECUTED & POSITION OPENED via trader {}: {:?} {} {} (confidence: {:.2}) - Order ID: {}, Position ID: {}, Position Value: ${:.2}",
                       trader_id, order_side, quantity, symbol, signal.confidence, order_id, position_id, position_value);
-                Ok(format!(
+                Ok(SignalExecution::Executed(format!(
                     "Order executed by trader {}: {:?} {} {} - Order ID: {}, Position ID: {}",
                     trader_id, order_side, quantity, symbol, order_id, position_id
-                ))
+                )))
             }
             Err(e) => {
                 // Rollback: Remove the reserved position slot since order execution failed
@@ -1313,6 +3790,24 @@ impl MpcService {
         }
     }
 
+    /// Ask a trader which exchange it currently executes on
+    async fn trader_active_exchange(
+        &self,
+        trader_sender: &mpsc::Sender<TraderMessage>,
+    ) -> Option<Exchange> {
+        let (reply_tx, mut reply_rx) = mpsc::channel(1);
+        trader_sender
+            .send(TraderMessage::GetActiveExchange { reply: reply_tx })
+            .await
+            .ok()?;
+
+        timeout(CHANNEL_REPLY_TIMEOUT, reply_rx.recv())
+            .await
+            .ok()
+            .flatten()
+            .flatten()
+    }
+
     /// Select a trader sender following lock ordering convention
     ///
     /// # Lock Ordering
@@ -1345,7 +3840,7 @@ impl MpcService {
 
     /// Check and execute orders for all symbols with signals
 
-    pub async fn check_and_execute_orders(&self) -> Vec<Result<String, MpcError>> {
+    pub async fn check_and_execute_orders(&self) -> Vec<Result<SignalExecution, MpcError>> {
         let last_signals = self.get_all_last_signals().await;
         let signal_count = last_signals.len();
 
@@ -1385,12 +3880,24 @@ impl MpcService {
                 symbol, signal.confidence
             );
 
-            match self.execute_order_from_signal(&symbol, &signal).await {
-                Ok(msg) => {
+            let result = self.execute_order_from_signal(&symbol, &signal).await;
+            if !is_retryable(&result) {
+                let pending = {
+                    let mut pending = self.pending_signal_ids.lock().await;
+                    pending.remove(&symbol)
+                };
+                if let Some(id) = pending {
+                    self.record_signal_outcome(id, &result).await;
+                }
+            }
+
+            match result {
+                Ok(execution) => {
+                    let msg = execution.message();
                     debug!("✅ Order execution successful for {}: {}", symbol, msg);
 
                     // Mark signal for removal if order was actually executed (not just rejected)
-                    if msg.contains("Order executed") && msg.contains("Order ID") {
+                    if execution.skip_reason().is_none() {
                         symbols_to_clear.push(symbol.clone());
                         successful_executions += 1;
                     } else {
@@ -1399,7 +3906,7 @@ impl MpcService {
                         symbols_to_clear.push(symbol.clone());
                         debug!("🧹 Cleared non-executed signal for {}: {}", symbol, msg);
                     }
-                    results.push(Ok(msg));
+                    results.push(Ok(execution));
                 }
                 Err(e) => {
                     let error_context = format!(
@@ -1409,23 +3916,8 @@ impl MpcService {
                     error!("❌ {}", error_context);
                     failed_executions += 1;
 
-                    // Check if this is a temporary failure that should be retried
-                    let should_retry = match &e {
-                        MpcError::ChannelSendError(_) | MpcError::Timeout => {
-                            // Communication errors - retry later
-                            true
-                        }
-                        MpcError::InvalidConfiguration(msg) if msg.contains("trader") => {
-                            // No trader available - don't retry immediately
-                            false
-                        }
-                        _ => {
-                            // Other errors (validation, limits, etc.) - don't retry
-                            false
-                        }
-                    };
-
-                    if should_retry {
+                    // Temporary (communication) failures are retried later
+                    if matches!(e, MpcError::ChannelSendError(_) | MpcError::Timeout) {
                         retry_later.push((symbol.clone(), signal.clone()));
                         debug!("⏰ Signal for {} queued for retry", symbol);
                     } else {
@@ -1515,7 +4007,7 @@ impl MpcService {
             .count();
 
         if trades_last_hour >= self.config.max_trades_per_hour {
-            return Err(MpcError::InvalidConfiguration(format!(
+            return Err(MpcError::TradeLimitExceeded(format!(
                 "Hourly trade limit exceeded: {} trades in last hour (max: {})",
                 trades_last_hour, self.config.max_trades_per_hour
             )));
@@ -1529,7 +4021,7 @@ impl MpcService {
             .count();
 
         if trades_last_day >= self.config.max_trades_per_day {
-            return Err(MpcError::InvalidConfiguration(format!(
+            return Err(MpcError::TradeLimitExceeded(format!(
                 "Daily trade limit exceeded: {} trades in last 24 hours (max: {})",
                 trades_last_day, self.config.max_trades_per_day
             )));
@@ -1644,35 +4136,154 @@ impl MpcService {
         }
     }
 
-    /// Adjust strategy weights based on performance metrics
+    /// Record an execution of a strategy on a specific symbol
+    pub async fn record_symbol_strategy_execution(
+        &self,
+        strategy_name: &str,
+        symbol: &str,
+        pnl: Price,
+    ) {
+        let mut pair_metrics = self.symbol_strategy_metrics.lock().await;
+        pair_metrics
+            .entry((strategy_name.to_string(), symbol.to_string()))
+            .or_insert_with(|| StrategyMetrics::new(strategy_name.to_string()))
+            .record_execution(pnl);
+    }
 
+    /// Adjust strategy weights based on performance metrics
+    ///
+    /// Every combiner (default and per symbol group) is adjusted from the
+    /// metrics of the strategies it holds, in its own order: by the bandit
+    /// allocator when the registry configures one, otherwise from performance
+    /// scores. The allocator only reallocates after observing new trades and
+    /// keeps weights that would move by less than its `min_change`; its state
+    /// is then persisted and each changed group is written to the audit log.
     pub async fn adjust_strategy_weights(&self) -> Result<(), String> {
-        let strategy_metrics = {
-            let order = {
-                let order_guard = self.strategy_order.lock().await;
-                order_guard.clone()
-            };
-            let metrics_guard = self.strategy_metrics.lock().await;
-            order
-                .iter()
-                .map(|name| {
-                    metrics_guard
-                        .get(name)
-                        .cloned()
-                        .unwrap_or_else(|| StrategyMetrics::new(name.clone()))
-                })
-                .collect::<Vec<_>>()
+        let strategy_metrics = self.get_strategy_metrics().await;
+        let default_group = self
+            .strategy_registry
+            .read()
+            .await
+            .as_ref()
+            .map(|r| r.default_group().name.clone())
+            .unwrap_or_else(|| "default".to_string());
+
+        let (changes, allocator_state) = {
+            let mut signal_combiner_guard = self.signal_combiner.write().await;
+            let mut group_combiners = self.group_combiners.write().await;
+            let mut allocator = self.weight_allocator.lock().await;
+            let stale = allocator.as_mut().map(|allocator| {
+                allocator.observe(&strategy_metrics);
+                allocator.take_stale()
+            });
+            if stale == Some(false) {
+                // No trades closed since the last allocation
+                return Ok(());
+            }
+
+            let mut changes = Vec::new();
+            if let Some(combiner) = signal_combiner_guard.as_mut() {
+                changes.extend(reweight(
+                    &default_group,
+                    combiner,
+                    allocator.as_mut(),
+                    &strategy_metrics,
+                )?);
+            }
+            for group in group_combiners.iter_mut() {
+                changes.extend(reweight(
+                    &group.group.name,
+                    &mut group.combiner,
+                    allocator.as_mut(),
+                    &strategy_metrics,
+                )?);
+            }
+            (changes, allocator.as_ref().map(|a| a.state()))
         };
+        debug!("Strategy weights adjusted based on performance metrics");
+
+        if let (Some(repository), Some(state)) = (&self.allocator_repository, &allocator_state) {
+            match serde_json::to_value(state) {
+                Ok(value) => {
+                    if let Err(e) = repository
+                        .save(WEIGHT_ALLOCATOR_STATE, state.policy.as_str(), &value)
+                        .await
+                    {
+                        warn!("Failed to persist weight allocator state: {}", e);
+                    }
+                }
+                Err(e) => warn!("Failed to serialize weight allocator state: {}", e),
+            }
+        }
 
-        let mut signal_combiner_guard = self.signal_combiner.write().await;
-        if let Some(combiner) = signal_combiner_guard.as_mut() {
-            combiner.adjust_weights(&strategy_metrics)?;
-            debug!("Strategy weights adjusted based on performance metrics");
+        if let Some(audit) = &self.audit_repository {
+            let policy = allocator_state
+                .as_ref()
+                .map_or("performance", |s| s.policy.as_str());
+            for change in changes {
+                let previous: BTreeMap<_, _> =
+                    change.strategies.iter().zip(&change.previous).collect();
+                let weights: BTreeMap<_, _> =
+                    change.strategies.iter().zip(&change.weights).collect();
+                let entry = CreateAuditLog {
+                    event_type: "strategy_weights_changed".to_string(),
+                    exchange: "system".to_string(),
+                    symbol: None,
+                    details: serde_json::json!({
+                        "group": change.group,
+                        "policy": policy,
+                        "previous": previous,
+                        "weights": weights,
+                    }),
+                };
+                if let Err(e) = audit.create(entry).await {
+                    warn!("Failed to audit strategy weight change: {}", e);
+                }
+            }
         }
 
         Ok(())
     }
 
+    /// Resume the weight allocator from its persisted statistics
+    ///
+    /// Call after `apply_strategy_registry`; does nothing without an
+    /// allocator, a repository or saved state.
+    pub async fn restore_weight_allocator(&self) -> Result<(), String> {
+        let Some(repository) = self.allocator_repository.as_ref() else {
+            return Ok(());
+        };
+        let Some(record) = repository
+            .get(WEIGHT_ALLOCATOR_STATE)
+            .await
+            .map_err(|e| format!("Failed to load weight allocator state: {}", e))?
+        else {
+            return Ok(());
+        };
+        let state: AllocatorState = serde_json::from_str(&record.state)
+            .map_err(|e| format!("Invalid weight allocator state: {}", e))?;
+
+        let mut allocator = self.weight_allocator.lock().await;
+        if let Some(allocator) = allocator.as_mut() {
+            info!(
+                "Weight allocator restored: {} strategies learned under {} policy",
+                state.arms.len(),
+                record.policy
+            );
+            allocator.restore(state);
+        }
+        Ok(())
+    }
+
+    /// Statistics of the bandit weight allocator, if one is configured
+    pub async fn get_allocator_state(&self) -> Option<AllocatorState> {
+        self.weight_allocator
+            .lock()
+            .await
+            .as_ref()
+            .map(|a| a.state())
+    }
+
     /// Get current strategy metrics
 
     pub async fn get_strategy_metrics(&self) -> HashMap<String, StrategyMetrics> {
@@ -1680,52 +4291,338 @@ impl MpcService {
         strategy_metrics.clone()
     }
 
+    /// Get strategy metrics per (strategy, symbol) pair
+    pub async fn get_symbol_strategy_metrics(&self) -> HashMap<(String, String), StrategyMetrics> {
+        let pair_metrics = self.symbol_strategy_metrics.lock().await;
+        pair_metrics.clone()
+    }
+
     /// Check for new alerts and update active alerts
 
-    pub async fn check_alerts(&self) -> Vec<SystemAlert> {
-        let trading_metrics = self.trading_metrics.lock().await;
-        let system_health = self.system_health.lock().await;
+    pub async fn check_alerts(&self) -> Vec<SystemAlert> {
+        let trading_metrics = self.trading_metrics.lock().await;
+        let system_health = self.system_health.lock().await;
+
+        let mut new_alerts = Vec::new();
+
+        // Check trading alerts
+        new_alerts.extend(trading_metrics.check_alerts(&self.alert_config));
+
+        // Check system health alerts
+        new_alerts.extend(system_health.check_alerts(&self.alert_config));
+
+        // Filter out already active alerts (avoid duplicates)
+        let mut active_alerts = self.active_alerts.lock().await;
+        let existing_alert_types: std::collections::HashSet<_> = active_alerts
+            .iter()
+            .filter(|alert| !alert.resolved)
+            .map(|alert| std::mem::discriminant(&alert.alert_type))
+            .collect();
+
+        let filtered_new_alerts: Vec<SystemAlert> = new_alerts
+            .into_iter()
+            .filter(|alert| {
+                !existing_alert_types.contains(&std::mem::discriminant(&alert.alert_type))
+            })
+            .collect();
+
+        // Add new alerts to active alerts
+        for alert in &filtered_new_alerts {
+            active_alerts.push(alert.clone());
+        }
+
+        filtered_new_alerts
+    }
+
+    /// Get active alerts
+
+    pub async fn get_active_alerts(&self) -> Vec<SystemAlert> {
+        let active_alerts = self.active_alerts.lock().await;
+        active_alerts
+            .iter()
+            .filter(|alert| !alert.resolved)
+            .cloned()
+            .collect()
+    }
+
+    /// Reconcile balances on every exchange with a client
+    ///
+    /// A critical report raises a reconciliation alert for its exchange, and
+    /// a later report that is no longer critical resolves it.
+    pub async fn run_reconciliation(&self) -> Result<Vec<ReconciliationReport>, String> {
+        let actor = self
+            .reconciliation
+            .as_ref()
+            .ok_or("Reconciliation is not running")?;
+        let activity = self.take_own_activity().await;
+        let (reply_tx, mut reply_rx) = mpsc::channel(1);
+        actor
+            .send(ReconciliationMessage::ReconcileAll {
+                activity,
+                reply: reply_tx,
+            })
+            .await
+            .map_err(|_| "Reconciliation actor stopped".to_string())?;
+        let reports = reply_rx
+            .recv()
+            .await
+            .ok_or("Reconciliation actor dropped the reply")?;
+
+        self.raise_reconciliation_alerts(&reports).await;
+        for report in &reports {
+            self.pause_reconciled_currencies(report).await;
+        }
+        Ok(reports)
+    }
+
+    /// Pause the currencies of reports whose remediation is still open,
+    /// so a restart does not resume trading an operator has not cleared
+    ///
+    /// Returns the number of paused currencies.
+    pub async fn restore_reconciliation_pauses(&self) -> Result<usize, String> {
+        let repository = self
+            .reconciliation_repository
+            .as_ref()
+            .ok_or("Reconciliation audit trail is not configured")?;
+        let open = repository
+            .get_open_remediations()
+            .await
+            .map_err(|e| e.to_string())?;
+        for report in &open {
+            self.pause_reconciled_currencies(report).await;
+        }
+        Ok(self.paused_currencies.lock().await.len())
+    }
+
+    async fn pause_reconciled_currencies(&self, report: &ReconciliationReport) {
+        let Some(remediation) = &report.remediation else {
+            return;
+        };
+        if !remediation.status.is_open() {
+            return;
+        }
+        let mut paused = self.paused_currencies.lock().await;
+        for currency in remediation.paused_currencies() {
+            if paused
+                .insert(currency.to_string(), report.exchange.clone())
+                .is_none()
+            {
+                warn!(
+                    "⏸️  Trading paused on {} until reconciliation {} is resolved",
+                    currency,
+                    report.id()
+                );
+            }
+        }
+    }
+
+    /// Reject symbols whose base or quote currency is paused by reconciliation
+    async fn check_reconciliation_pause(&self, symbol: &str) -> Result<(), MpcError> {
+        let paused = self.paused_currencies.lock().await;
+        if paused.is_empty() {
+            return Ok(());
+        }
+        let normalized = TradingConfig::normalize_symbol(symbol);
+        match normalized
+            .split('-')
+            .find_map(|currency| paused.get(currency).map(|exchange| (currency, exchange)))
+        {
+            Some((currency, exchange)) => Err(MpcError::InvalidConfiguration(format!(
+                "Trading on {} is paused by {} reconciliation",
+                currency,
+                exchange.name()
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Currencies paused by reconciliation, with the exchange that paused each
+    pub async fn get_paused_currencies(&self) -> Vec<(String, Exchange)> {
+        let paused = self.paused_currencies.lock().await;
+        let mut currencies: Vec<(String, Exchange)> = paused
+            .iter()
+            .map(|(currency, exchange)| (currency.clone(), exchange.clone()))
+            .collect();
+        currencies.sort_by(|a, b| a.0.cmp(&b.0));
+        currencies
+    }
+
+    /// Reconciliation reports waiting for an operator, oldest first
+    pub async fn get_open_remediations(&self) -> Result<Vec<ReconciliationReport>, String> {
+        let repository = self
+            .reconciliation_repository
+            .as_ref()
+            .ok_or("Reconciliation audit trail is not configured")?;
+        repository
+            .get_open_remediations()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Append an operator note to a reconciliation report
+    pub async fn annotate_reconciliation(&self, id: &str, note: &str) -> Result<(), String> {
+        if note.trim().is_empty() {
+            return Err("Note must not be empty".to_string());
+        }
+        let repository = self
+            .reconciliation_repository
+            .as_ref()
+            .ok_or("Reconciliation audit trail is not configured")?;
+        repository
+            .annotate_reconciliation(id, note)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Resolve a reconciliation report an operator has looked into
+    ///
+    /// The gaps of the report are adopted into the expected balances and
+    /// trading paused by it resumes. Currencies paused by reports that are
+    /// still open stay paused, and the exchange's alert is resolved once none
+    /// of its reports is open.
+    pub async fn resolve_reconciliation(
+        &self,
+        id: &str,
+        note: Option<&str>,
+    ) -> Result<ReconciliationReport, String> {
+        let repository = self
+            .reconciliation_repository
+            .as_ref()
+            .ok_or("Reconciliation audit trail is not configured")?;
+        let report = repository
+            .resolve_reconciliation(id, note.filter(|note| !note.trim().is_empty()))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if let Some(actor) = &self.reconciliation {
+            actor
+                .send(ReconciliationMessage::Resolve {
+                    report: report.clone(),
+                })
+                .await
+                .map_err(|_| "Reconciliation actor stopped".to_string())?;
+        }
+        // Pauses and alerts of reports still open stay in place
+        let open = repository
+            .get_open_remediations()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !open.iter().any(|other| other.exchange == report.exchange) {
+            let mut active_alerts = self.active_alerts.lock().await;
+            let kind = AlertType::BalanceReconciliation(report.exchange.clone());
+            for alert in active_alerts
+                .iter_mut()
+                .filter(|alert| alert.alert_type == kind)
+            {
+                alert.resolved = true;
+            }
+        }
+        let mut still_paused: HashMap<String, Exchange> = HashMap::new();
+        for other in &open {
+            if let Some(remediation) = &other.remediation {
+                for currency in remediation.paused_currencies() {
+                    still_paused.insert(currency.to_string(), other.exchange.clone());
+                }
+            }
+        }
+        {
+            let mut paused = self.paused_currencies.lock().await;
+            paused.retain(|currency, _| {
+                let resume = !still_paused.contains_key(currency);
+                if resume {
+                    info!("▶️  Trading resumed on {}", currency);
+                }
+                !resume
+            });
+            paused.extend(still_paused);
+        }
+
+        info!("✓ Reconciliation {} resolved", id);
+        Ok(report)
+    }
 
-        let mut new_alerts = Vec::new();
+    /// Latest reconciliation report per exchange
+    pub async fn get_reconciliation_status(&self) -> Result<Vec<ReconciliationReport>, String> {
+        let actor = self
+            .reconciliation
+            .as_ref()
+            .ok_or("Reconciliation is not running")?;
+        let (reply_tx, mut reply_rx) = mpsc::channel(1);
+        actor
+            .send(ReconciliationMessage::GetStatus { reply: reply_tx })
+            .await
+            .map_err(|_| "Reconciliation actor stopped".to_string())?;
+        let status = timeout(CHANNEL_REPLY_TIMEOUT, reply_rx.recv())
+            .await
+            .map_err(|_| "Reconciliation status timed out".to_string())?
+            .ok_or("Reconciliation actor dropped the reply")?;
 
-        // Check trading alerts
-        new_alerts.extend(trading_metrics.check_alerts(&self.alert_config));
+        let mut reports: Vec<ReconciliationReport> = status.into_values().flatten().collect();
+        reports.sort_by(|a, b| a.exchange.name().cmp(b.exchange.name()));
+        Ok(reports)
+    }
 
-        // Check system health alerts
-        new_alerts.extend(system_health.check_alerts(&self.alert_config));
+    /// Persisted reconciliation reports of an exchange over the last `days`, newest first
+    pub async fn get_reconciliation_history(
+        &self,
+        exchange: &Exchange,
+        days: u32,
+    ) -> Result<Vec<ReconciliationReport>, String> {
+        let repository = self
+            .reconciliation_repository
+            .as_ref()
+            .ok_or("Reconciliation audit trail is not configured")?;
+        repository
+            .get_reconciliation_history(exchange, days)
+            .await
+            .map_err(|e| e.to_string())
+    }
 
-        // Filter out already active alerts (avoid duplicates)
+    async fn raise_reconciliation_alerts(&self, reports: &[ReconciliationReport]) {
         let mut active_alerts = self.active_alerts.lock().await;
-        let existing_alert_types: std::collections::HashSet<_> = active_alerts
-            .iter()
-            .filter(|alert| !alert.resolved)
-            .map(|alert| std::mem::discriminant(&alert.alert_type))
-            .collect();
 
-        let filtered_new_alerts: Vec<SystemAlert> = new_alerts
-            .into_iter()
-            .filter(|alert| {
-                !existing_alert_types.contains(&std::mem::discriminant(&alert.alert_type))
-            })
-            .collect();
+        for report in reports {
+            let prefix = format!("Reconciliation {}:", report.exchange.name());
+            let kind = AlertType::BalanceReconciliation(report.exchange.clone());
+            let mut open = active_alerts
+                .iter_mut()
+                .filter(|alert| !alert.resolved && alert.alert_type == kind);
+
+            if report.status != ReconciliationStatus::Critical {
+                for alert in open {
+                    info!("✓ Resolved: {}", alert.message);
+                    alert.resolved = true;
+                }
+                continue;
+            }
+            if open.next().is_some() {
+                continue;
+            }
 
-        // Add new alerts to active alerts
-        for alert in &filtered_new_alerts {
-            active_alerts.push(alert.clone());
+            let message = if report.discrepancies.is_empty() {
+                format!("{} balances could not be fetched", prefix)
+            } else {
+                let currencies: Vec<&str> = report
+                    .discrepancies
+                    .iter()
+                    .map(|discrepancy| discrepancy.currency())
+                    .collect();
+                format!(
+                    "{} {} discrepancies ({})",
+                    prefix,
+                    report.discrepancy_count(),
+                    currencies.join(", ")
+                )
+            };
+            error!("🚨 {}", message);
+            active_alerts.push(SystemAlert {
+                alert_type: kind,
+                message,
+                severity: crate::domain::services::metrics::AlertSeverity::Critical,
+                timestamp: SystemTime::now(),
+                resolved: false,
+            });
         }
-
-        filtered_new_alerts
-    }
-
-    /// Get active alerts
-
-    pub async fn get_active_alerts(&self) -> Vec<SystemAlert> {
-        let active_alerts = self.active_alerts.lock().await;
-        active_alerts
-            .iter()
-            .filter(|alert| !alert.resolved)
-            .cloned()
-            .collect()
     }
 
     /// Get performance profiles
@@ -1738,12 +4635,30 @@ impl MpcService {
     }
 }
 
+#[async_trait::async_trait]
+impl ScreeningDataSource for MpcService {
+    async fn market_data(&self, exchange: &str) -> HashMap<String, SymbolMarketData> {
+        match Exchange::from_name(exchange) {
+            Some(exchange) => self.get_screening_data(&exchange).await,
+            None => HashMap::new(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::test_support::{
+        add_mock_trader, memory_pool, memory_repositories, snapshot, MockExchange, StaticDiscovery,
+    };
     use super::*;
     use crate::domain::services::strategies::{
         ConservativeScalping, FastScalping, MomentumScalping, Strategy,
     };
+    use crate::persistence::repository::{
+        SqliteAllocatorStateRepository, SqliteAuditLogRepository, SqliteBracketOrderRepository,
+        SqliteDydxOrderMetadataRepository, SqliteFundingPaymentRepository,
+        SqlitePositionRepository, SqliteSignalRepository, SqliteTradeRepository,
+    };
 
     #[test]
     fn test_mpc_service_new() {
@@ -1821,84 +4736,974 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_mpc_service_with_mock_actor() {
-        let config = TradingConfig::default();
-        let mut service = MpcService::new(config);
-
-        // Use mock actor for predictable testing
-        let mock_price = crate::domain::value_objects::price::Price::new(55000.0).unwrap();
-        let sender = crate::infrastructure::adapters::exchange_actor::MockExchangeActor::spawn(
-            crate::domain::entities::exchange::Exchange::Binance,
-            mock_price.clone(),
+    async fn test_mpc_service_with_mock_actor() {
+        let config = TradingConfig::default();
+        let mut service = MpcService::new(config);
+
+        // Use mock actor for predictable testing
+        let mock_price = crate::domain::value_objects::price::Price::new(55000.0).unwrap();
+        let sender = crate::infrastructure::adapters::exchange_actor::MockExchangeActor::spawn(
+            crate::domain::entities::exchange::Exchange::Binance,
+            mock_price.clone(),
+        );
+        service
+            .add_actor(crate::domain::entities::exchange::Exchange::Binance, sender)
+            .expect("Failed to add actor");
+
+        // Get price from mock actor
+        let result = service
+            .get_price(
+                &crate::domain::entities::exchange::Exchange::Binance,
+                "BTCUSDT",
+            )
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().value(), 55000.0);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_unsubscribe() {
+        let config = TradingConfig::default();
+        let mut service = MpcService::new(config);
+
+        let mock_price = crate::domain::value_objects::price::Price::new(50000.0).unwrap();
+        let sender = crate::infrastructure::adapters::exchange_actor::MockExchangeActor::spawn(
+            crate::domain::entities::exchange::Exchange::Binance,
+            mock_price,
+        );
+        service
+            .add_actor(crate::domain::entities::exchange::Exchange::Binance, sender)
+            .expect("Failed to add actor");
+
+        // Test subscribe
+        let result = service
+            .subscribe(
+                &crate::domain::entities::exchange::Exchange::Binance,
+                "BTCUSDT",
+            )
+            .await;
+        assert!(result.is_ok());
+
+        // Test unsubscribe
+        let result = service
+            .unsubscribe(
+                &crate::domain::entities::exchange::Exchange::Binance,
+                "BTCUSDT",
+            )
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_subscriptions() {
+        let config = TradingConfig::default();
+        let mut service = MpcService::new(config);
+
+        let mock_price = crate::domain::value_objects::price::Price::new(50000.0).unwrap();
+        let sender = crate::infrastructure::adapters::exchange_actor::MockExchangeActor::spawn(
+            crate::domain::entities::exchange::Exchange::Binance,
+            mock_price,
+        );
+        service
+            .add_actor(crate::domain::entities::exchange::Exchange::Binance, sender)
+            .expect("Failed to add actor");
+
+        // Get subscriptions
+        let result = service
+            .get_subscriptions(&crate::domain::entities::exchange::Exchange::Binance)
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().len(), 0); // Mock actor returns empty list
+    }
+
+    /// Service with a bracket repository, one open BTC long and a bracket on it
+    async fn bracketed_service(
+        exchange: MockExchange,
+    ) -> (MpcService, Arc<SqliteBracketOrderRepository>, String) {
+        let mut service = MpcService::new(TradingConfig::default());
+        let repository = Arc::new(SqliteBracketOrderRepository::new(memory_pool().await));
+        service.set_bracket_repository(repository.clone());
+        let sender = add_mock_trader(&service, Exchange::Coinbase, exchange).await;
+
+        let position_id = service
+            .open_position(
+                "BTC-USD",
+                PositionSide::Long,
+                Quantity::new(0.1).unwrap(),
+                Price::new(50000.0).unwrap(),
+            )
+            .await
+            .unwrap();
+        let position = service.get_open_positions().await[&position_id].clone();
+        service
+            .attach_bracket(&position, "coinbase", "entry-1", &sender)
+            .await;
+
+        (service, repository, position_id)
+    }
+
+    #[tokio::test]
+    async fn test_emulated_bracket_fires_exit_and_cancels_sibling() {
+        let cancelled = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (service, repository, position_id) = bracketed_service(MockExchange {
+            echo_order_ids: true,
+            cancelled: cancelled.clone(),
+            ..Default::default()
+        })
+        .await;
+
+        let bracket_id = format!("bracket_{}", position_id);
+        let stored = repository.get(&bracket_id).await.unwrap().unwrap();
+        assert_eq!(stored.linking, BracketLinking::Emulated);
+
+        {
+            let mut positions = service.open_positions.lock().await;
+            let position = positions.get_mut(&position_id).unwrap();
+            position.update_price(Price::new(47000.0).unwrap());
+        }
+
+        let results = service.check_and_execute_stops().await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].as_ref().unwrap().contains("stop-loss"));
+        assert!(service.get_open_positions().await.is_empty());
+
+        let stored = repository.get(&bracket_id).await.unwrap().unwrap();
+        assert_eq!(stored.filled_exit(), Some(BracketLegRole::StopLoss));
+        assert_eq!(
+            stored.stop_loss.exchange_order_id.as_deref(),
+            Some(format!("ex_{}_stop_loss", bracket_id).as_str())
+        );
+        assert!(repository.get_active().await.unwrap().is_empty());
+        // The take-profit never reached the exchange, so nothing to cancel
+        assert!(cancelled.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_native_bracket_fill_closes_position_and_restores_after_restart() {
+        let cancelled = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (service, repository, position_id) = bracketed_service(MockExchange {
+            echo_order_ids: true,
+            native_brackets: true,
+            filled: vec!["tp-1".to_string()],
+            cancelled: cancelled.clone(),
+            ..Default::default()
+        })
+        .await;
+
+        // A restarted service picks the working legs back up
+        let mut restarted = MpcService::new(TradingConfig::default());
+        restarted.set_bracket_repository(repository.clone());
+        assert_eq!(restarted.restore_brackets().await.unwrap(), 1);
+        let restored = restarted.get_brackets().await;
+        assert_eq!(restored[0].linking, BracketLinking::Native);
+        assert_eq!(
+            restored[0].take_profit.exchange_order_id.as_deref(),
+            Some("tp-1")
+        );
+
+        // Price alone must not close a natively protected position
+        {
+            let mut positions = service.open_positions.lock().await;
+            let position = positions.get_mut(&position_id).unwrap();
+            position.update_price(Price::new(53000.0).unwrap());
+        }
+
+        let results = service.check_and_execute_stops().await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].as_ref().unwrap().contains("take-profit"));
+        assert!(service.get_open_positions().await.is_empty());
+        assert_eq!(*cancelled.lock().unwrap(), vec!["sl-1".to_string()]);
+
+        let stored = repository
+            .get(&format!("bracket_{}", position_id))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.filled_exit(), Some(BracketLegRole::TakeProfit));
+        assert!(!stored.is_active());
+    }
+
+    #[tokio::test]
+    async fn test_restart_recovers_positions_trade_limits_and_orders() {
+        use crate::persistence::models::CreateDydxOrderMetadata;
+
+        let mut config = TradingConfig::default();
+        config.stop_loss_percentage = Some(0.02);
+        config.take_profit_percentage = Some(0.04);
+        let pool = memory_pool().await;
+        let positions = Arc::new(SqlitePositionRepository::new(pool.clone()));
+        let trades = Arc::new(SqliteTradeRepository::new(pool.clone()));
+        let orders = Arc::new(SqliteDydxOrderMetadataRepository::new(pool.clone()));
+        let audit = Arc::new(SqliteAuditLogRepository::new(pool.clone()));
+
+        let mut service = MpcService::new(config.clone());
+        service.set_repositories(positions.clone(), trades.clone());
+        let position_id = service
+            .open_position(
+                "BTC-USD",
+                PositionSide::Long,
+                Quantity::new(0.1).unwrap(),
+                Price::new(50000.0).unwrap(),
+            )
+            .await
+            .unwrap();
+        for (order_id, client_id) in [
+            ("order-filled", 1),
+            ("order-working", 2),
+            ("order-closed", 3),
+        ] {
+            orders
+                .create(CreateDydxOrderMetadata {
+                    order_id: order_id.to_string(),
+                    dydx_order_id: format!("dydx-{}", order_id),
+                    good_until_block: 100,
+                    client_id,
+                    subaccount_number: 0,
+                    order_flags: 64,
+                    clob_pair_id: 0,
+                    symbol: "BTC-USD".to_string(),
+                    side: "buy".to_string(),
+                    quantity: "0.1".to_string(),
+                    price: Some("50000".to_string()),
+                    order_type: "limit".to_string(),
+                })
+                .await
+                .unwrap();
+        }
+        // The exit sent through "order-closed" was recorded before the restart
+        trades
+            .create(CreateTrade {
+                id: "trade_close_earlier".to_string(),
+                position_id: None,
+                symbol: "BTC-USD".to_string(),
+                exchange: "dydx".to_string(),
+                side: "buy".to_string(),
+                price: 50000.0,
+                quantity: 0.1,
+                fee: 0.0,
+                fee_estimated: true,
+                exchange_order_id: Some("order-closed".to_string()),
+                strategy: "SignalCombiner".to_string(),
+                signal_confidence: None,
+                exit_reason: Some("manual".to_string()),
+                signal_details: None,
+            })
+            .await
+            .unwrap();
+
+        // The restarted service starts empty and shares only the database
+        let mut restarted = MpcService::new(config);
+        restarted.set_repositories(positions, trades);
+        restarted.set_order_metadata_repository(orders.clone());
+        restarted.set_weight_repositories(
+            Arc::new(SqliteAllocatorStateRepository::new(pool.clone())),
+            audit.clone(),
+        );
+        add_mock_trader(
+            &restarted,
+            Exchange::Dydx,
+            MockExchange {
+                filled: vec!["order-filled".to_string(), "order-closed".to_string()],
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let summary = restarted.recover_trading_state().await.unwrap();
+        assert_eq!(summary.positions_restored, 1);
+        assert!(summary.positions_without_stop.is_empty());
+        assert_eq!(summary.position_value, 5000.0);
+        assert_eq!(summary.trades_replayed, 1);
+        assert_eq!(summary.orders_checked, 3);
+        assert_eq!(summary.orders_filled, 2);
+        assert_eq!(summary.orders_open, 1);
+        // Nothing local accounts for the first fill, so the operator is alerted
+        assert_eq!(summary.untracked_fills, vec!["order-filled".to_string()]);
+        let alerts = restarted.get_active_alerts().await;
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(
+            alerts[0].alert_type,
+            AlertType::UntrackedFill(Exchange::Dydx)
+        );
+        assert!(alerts[0].message.contains("order-filled"));
+
+        let original = service.get_open_positions().await[&position_id].clone();
+        let restored = restarted.get_open_positions().await[&position_id].clone();
+        assert!(original.stop_loss_price.is_some());
+        assert_eq!(restored.stop_loss_price, original.stop_loss_price);
+        assert_eq!(restored.take_profit_price, original.take_profit_price);
+        assert_eq!(restarted.trade_history.lock().await.len(), 1);
+        assert_eq!(
+            restarted.portfolio_state.lock().await.position_value,
+            5000.0
+        );
+
+        let active: Vec<String> = orders
+            .get_active_orders()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|order| order.order_id)
+            .collect();
+        assert_eq!(active, vec!["order-working".to_string()]);
+
+        let entries = audit
+            .get_by_event_type("state_recovered", 10)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        let details: serde_json::Value = serde_json::from_str(&entries[0].details).unwrap();
+        assert_eq!(details["orders_filled"], 2);
+
+        // Running it again does not double count what is already tracked
+        let again = restarted.recover_trading_state().await.unwrap();
+        assert_eq!(again.positions_restored, 0);
+        assert_eq!(again.trades_replayed, 0);
+        assert_eq!(restarted.get_open_positions().await.len(), 1);
+        assert_eq!(restarted.trade_history.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_realized_pnl_ledger_tracks_fees_funding_and_survives_restart() {
+        use crate::domain::services::pnl_ledger::UNATTRIBUTED;
+
+        let pool = memory_pool().await;
+        let positions = Arc::new(SqlitePositionRepository::new(pool.clone()));
+        let trades = Arc::new(SqliteTradeRepository::new(pool.clone()));
+        let funding = Arc::new(SqliteFundingPaymentRepository::new(pool.clone()));
+
+        let mut service = MpcService::new(TradingConfig::default());
+        service.set_repositories(positions.clone(), trades.clone());
+        service.set_funding_repository(funding.clone());
+        let starting_value = service.portfolio_state.lock().await.total_value;
+
+        // Default 10 bps fees on both fills: +100 - 5.0 - 5.1 and -100 - 3.0 - 3.1
+        for (symbol, side, quantity, entry, exit) in [
+            ("BTC-USD", PositionSide::Long, 0.1, 50000.0, 51000.0),
+            ("ETH-USD", PositionSide::Short, 1.0, 3000.0, 3100.0),
+        ] {
+            let position_id = service
+                .open_position(
+                    symbol,
+                    side,
+                    Quantity::new(quantity).unwrap(),
+                    Price::new(entry).unwrap(),
+                )
+                .await
+                .unwrap();
+            service
+                .open_positions
+                .lock()
+                .await
+                .get_mut(&position_id)
+                .unwrap()
+                .current_price = Some(Price::new(exit).unwrap());
+            service.close_position(&position_id).await.unwrap();
+        }
+
+        let report = service.get_realized_pnl(&[PnlGroup::Symbol]).await;
+        assert!(report.open_lots.is_empty());
+        assert_eq!(report.groups.len(), 2);
+        assert!((report.groups[0].net_pnl - 89.9).abs() < 1e-9);
+        assert!((report.groups[1].net_pnl + 106.1).abs() < 1e-9);
+        assert!((report.total.fees - 16.2).abs() < 1e-9);
+        // No fill reported its fee
+        assert!((report.total.estimated_fees - 16.2).abs() < 1e-9);
+        let metrics = service.get_trading_metrics().await;
+        assert!((metrics.total_realized_pnl.value() + 16.2).abs() < 1e-9);
+        assert_eq!(metrics.winning_trades, 1);
+        assert_eq!(metrics.losing_trades, 1);
+
+        // Funding without an open lot has no strategy to credit
+        let entry = service
+            .record_funding_payment(FundingPayment {
+                symbol: "BTC-USD".to_string(),
+                exchange: "dydx".to_string(),
+                strategy: None,
+                amount: -2.5,
+                paid_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(entry.strategy, UNATTRIBUTED);
+        let total = service.pnl_ledger.lock().await.total_realized();
+        assert!((total + 18.7).abs() < 1e-9);
+        let portfolio_value = service.portfolio_state.lock().await.total_value;
+        assert!((portfolio_value - starting_value - total).abs() < 1e-9);
+
+        // A restarted service rebuilds the same ledger from the database
+        let mut restarted = MpcService::new(TradingConfig::default());
+        restarted.set_repositories(positions, trades);
+        restarted.set_funding_repository(funding);
+        let totals = restarted.rebuild_pnl_ledger().await.unwrap();
+        assert_eq!(totals, service.pnl_ledger.lock().await.totals());
+        let by_strategy = restarted.get_realized_pnl(&[PnlGroup::Strategy]).await;
+        let strategies: Vec<&str> = by_strategy
+            .groups
+            .iter()
+            .map(|group| group.group["strategy"].as_str())
+            .collect();
+        assert_eq!(strategies, vec!["SignalCombiner", UNATTRIBUTED]);
+        let metrics = restarted.get_trading_metrics().await;
+        assert!((metrics.total_realized_pnl.value() + 18.7).abs() < 1e-9);
+
+        // Rebuilding twice does not double count
+        assert_eq!(restarted.rebuild_pnl_ledger().await.unwrap(), totals);
+    }
+
+    #[tokio::test]
+    async fn test_pnl_ledger_rebuild_replays_its_window() {
+        let pool = memory_pool().await;
+        let positions = Arc::new(SqlitePositionRepository::new(pool.clone()));
+        let trades = Arc::new(SqliteTradeRepository::new(pool.clone()));
+        let funding = Arc::new(SqliteFundingPaymentRepository::new(pool.clone()));
+        let mut config = TradingConfig::default();
+        config.pnl_ledger_days = 30;
+
+        let mut service = MpcService::new(config.clone());
+        service.set_repositories(positions.clone(), trades.clone());
+        service.set_funding_repository(funding.clone());
+        let mut ids = Vec::new();
+        for symbol in ["BTC-USD", "ETH-USD", "SOL-USD"] {
+            let position_id = service
+                .open_position(
+                    symbol,
+                    PositionSide::Long,
+                    Quantity::new(1.0).unwrap(),
+                    Price::new(100.0).unwrap(),
+                )
+                .await
+                .unwrap();
+            ids.push(position_id);
+        }
+        for position_id in &ids[..2] {
+            service
+                .open_positions
+                .lock()
+                .await
+                .get_mut(position_id)
+                .unwrap()
+                .current_price = Some(Price::new(110.0).unwrap());
+            service.close_position(position_id).await.unwrap();
+        }
+        let days_ago = |days: i64| chrono::Utc::now() - chrono::Duration::days(days);
+        for (days, amount) in [(60, -4.0), (0, -0.5)] {
+            service
+                .record_funding_payment(FundingPayment {
+                    symbol: "SOL-USD".to_string(),
+                    exchange: "dydx".to_string(),
+                    strategy: None,
+                    amount,
+                    paid_at: days_ago(days),
+                })
+                .await
+                .unwrap();
+        }
+
+        // BTC traded before the window, ETH was opened before it and closed
+        // in it, SOL is still open since before it
+        let backdate = [
+            (format!("trade_open_{}", ids[0]), 60),
+            (format!("trade_close_{}", ids[0]), 59),
+            (format!("trade_open_{}", ids[1]), 60),
+            (format!("trade_open_{}", ids[2]), 45),
+        ];
+        for (trade_id, days) in &backdate {
+            sqlx::query("UPDATE trades SET executed_at = ?1 WHERE id = ?2")
+                .bind(days_ago(*days))
+                .bind(trade_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("UPDATE positions SET opened_at = ?1 WHERE id = ?2")
+            .bind(days_ago(45))
+            .bind(&ids[2])
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut restarted = MpcService::new(config);
+        restarted.set_repositories(positions, trades);
+        restarted.set_funding_repository(funding);
+        let totals = restarted.rebuild_pnl_ledger().await.unwrap();
+        assert_eq!(totals.closed_lots, 0);
+        // Only the payment made in the window
+        assert!((totals.funding + 0.5).abs() < 1e-9);
+        let report = restarted.get_realized_pnl(&[]).await;
+        assert_eq!(report.open_lots.len(), 1);
+        assert_eq!(report.open_lots[0].symbol, "SOL-USD");
+    }
+
+    #[tokio::test]
+    async fn test_reported_fees_and_pulled_funding_enter_the_ledger() {
+        struct StaticFunding(Vec<FundingPayment>);
+
+        #[async_trait::async_trait]
+        impl FundingSource for StaticFunding {
+            fn exchange(&self) -> Exchange {
+                Exchange::Dydx
+            }
+
+            async fn funding_payments(
+                &self,
+                since: chrono::DateTime<chrono::Utc>,
+            ) -> Result<Vec<FundingPayment>, String> {
+                Ok(self
+                    .0
+                    .iter()
+                    .filter(|payment| payment.paid_at >= since)
+                    .cloned()
+                    .collect())
+            }
+        }
+
+        let mut service = MpcService::new(TradingConfig::default());
+        let (_, trades) = memory_repositories(&mut service).await;
+
+        // dYdX reports the fee of the opening order only
+        let (tx, mut rx) = mpsc::channel(8);
+        tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                if let ExchangeMessage::GetOrderFill { order_id, reply } = message {
+                    let _ = reply
+                        .send(Ok(OrderFill {
+                            status: OrderStatus::Filled,
+                            filled_quantity: Some(0.1),
+                            average_price: None,
+                            fee: (order_id == "order-open").then_some(1.25),
+                        }))
+                        .await;
+                }
+            }
+        });
+        service.senders = Arc::new(HashMap::from([(Exchange::Dydx, tx)]));
+
+        let mut position = Position::new(
+            "pos-fees".to_string(),
+            "BTC-USD".to_string(),
+            PositionSide::Long,
+            Quantity::new(0.1).unwrap(),
+            Price::new(50000.0).unwrap(),
+        );
+        service
+            .record_position_open(&position, "dydx", Some("order-open"), None)
+            .await;
+        let opening = trades.get("trade_open_pos-fees").await.unwrap().unwrap();
+        assert_eq!(opening.fee, 1.25);
+        assert!(!opening.fee_estimated);
+
+        position.current_price = Some(Price::new(51000.0).unwrap());
+        service
+            .record_position_close(&position, ExitReason::Manual, Some("order-close"))
+            .await;
+        let closing = trades.get("trade_close_pos-fees").await.unwrap().unwrap();
+        // Default 10 bps of the 5100 exit value
+        assert!((closing.fee - 5.1).abs() < 1e-9);
+        assert!(closing.fee_estimated);
+
+        let report = service.get_realized_pnl(&[]).await;
+        assert!((report.total.fees - 6.35).abs() < 1e-9);
+        assert!((report.total.estimated_fees - 6.35).abs() < 1e-9);
+        assert!(service.pnl_ledger.lock().await.entries()[0].fees_estimated);
+
+        // Funding settled before the position opened is not ours
+        let paid = |hours: i64, amount: f64| FundingPayment {
+            symbol: "BTC-USD".to_string(),
+            exchange: "dydx".to_string(),
+            strategy: None,
+            amount,
+            paid_at: opening.executed_at + chrono::Duration::hours(hours),
+        };
+        service.set_funding_source(Arc::new(StaticFunding(vec![
+            paid(-1, -9.0),
+            paid(1, -0.5),
+            paid(2, 0.25),
+        ])));
+        assert_eq!(service.sync_funding_payments().await.unwrap(), 2);
+        assert_eq!(service.sync_funding_payments().await.unwrap(), 0);
+
+        let report = service.get_realized_pnl(&[]).await;
+        assert!((report.total.funding + 0.25).abs() < 1e-9);
+        assert_eq!(report.total.closed_lots, 1);
+    }
+
+    #[tokio::test]
+    async fn test_time_exit_closes_stale_position_and_records_reason() {
+        use crate::domain::entities::position::TimeExitRule;
+
+        let mut config = TradingConfig::default();
+        config.time_exit = TimeExitRule {
+            max_holding: Some(chrono::Duration::minutes(10)),
+            ..TimeExitRule::disabled()
+        };
+        let mut service = MpcService::new(config);
+        let (_, repository) = memory_repositories(&mut service).await;
+
+        let stale_id = service
+            .open_position(
+                "BTC-USD",
+                PositionSide::Long,
+                Quantity::new(0.1).unwrap(),
+                Price::new(50000.0).unwrap(),
+            )
+            .await
+            .unwrap();
+        let fresh_id = service
+            .open_position(
+                "ETH-USD",
+                PositionSide::Long,
+                Quantity::new(1.0).unwrap(),
+                Price::new(3000.0).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        {
+            let mut positions = service.open_positions.lock().await;
+            let stale = positions.get_mut(&stale_id).unwrap();
+            stale.entry_time = chrono::Utc::now() - chrono::Duration::minutes(11);
+            stale.update_price(Price::new(50010.0).unwrap());
+        }
+
+        let results = service.check_and_execute_stops().await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].as_ref().unwrap().contains("max holding time"));
+
+        let positions = service.get_open_positions().await;
+        assert!(!positions.contains_key(&stale_id));
+        assert!(positions.contains_key(&fresh_id));
+
+        let time_exits = repository
+            .get_by_exit_reason(ExitReason::MaxHoldingTime.as_str(), 10)
+            .await
+            .unwrap();
+        assert_eq!(time_exits.len(), 1);
+        assert_eq!(
+            time_exits[0].position_id.as_deref(),
+            Some(stale_id.as_str())
+        );
+        assert_eq!(time_exits[0].side, "sell");
+        assert_eq!(time_exits[0].price, 50010.0);
+    }
+
+    #[tokio::test]
+    async fn test_opening_trade_stores_signal_explanation() {
+        use crate::domain::services::strategies::{Signal, StrategyVote};
+
+        let mut service = MpcService::new(TradingConfig::default());
+        let (_, repository) = memory_repositories(&mut service).await;
+
+        let position = Position::new(
+            "pos-signal".to_string(),
+            "BTC-USD".to_string(),
+            PositionSide::Long,
+            Quantity::new(0.1).unwrap(),
+            Price::new(50000.0).unwrap(),
         );
+
+        let mut signal = TradingSignal::new(Signal::Buy, 0.72);
+        signal.votes = vec![StrategyVote {
+            strategy: "MomentumScalping".to_string(),
+            signal: Signal::Buy,
+            confidence: 0.9,
+            weight: 0.4,
+        }];
+        signal.indicators.insert("rsi_7".to_string(), 24.5);
         service
-            .add_actor(crate::domain::entities::exchange::Exchange::Binance, sender)
-            .expect("Failed to add actor");
+            .record_position_open(&position, "coinbase", Some("order-1"), Some(&signal))
+            .await;
 
-        // Get price from mock actor
-        let result = service
-            .get_price(
-                &crate::domain::entities::exchange::Exchange::Binance,
-                "BTCUSDT",
+        let trade = repository
+            .get("trade_open_pos-signal")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(trade.signal_confidence, Some(0.72));
+        let details: serde_json::Value =
+            serde_json::from_str(trade.signal_details.as_deref().unwrap()).unwrap();
+        assert_eq!(details["votes"][0]["strategy"], "MomentumScalping");
+        assert_eq!(details["votes"][0]["signal"], "Buy");
+        assert_eq!(details["votes"][0]["weight"], 0.4);
+        assert_eq!(details["indicators"]["rsi_7"], 24.5);
+    }
+
+    #[tokio::test]
+    async fn test_market_regime_is_classified_and_exposed() {
+        use crate::domain::services::indicators::Candle;
+
+        let service = MpcService::new(TradingConfig::default());
+        let short: Vec<Candle> = (0..10)
+            .map(|i| Candle::new(100.0, 100.1, 99.9, 100.0 + i as f64 * 0.01, 1.0).unwrap())
+            .collect();
+        assert!(service.classify_regime("BTC-USD", &short).await.is_none());
+
+        let rising: Vec<Candle> = (0..40)
+            .map(|i| {
+                let close = 100.0 * 1.001_f64.powi(i);
+                Candle::new(close, close + 0.05, close - 0.05, close, 1.0).unwrap()
+            })
+            .collect();
+        let reading = service.classify_regime("BTC-USD", &rising).await.unwrap();
+        assert_eq!(reading.regime, MarketRegime::TrendingUp);
+
+        let regimes = service.get_market_regimes().await;
+        assert_eq!(regimes.len(), 1);
+        assert_eq!(regimes["BTC-USD"].regime, MarketRegime::TrendingUp);
+    }
+
+    #[tokio::test]
+    async fn test_signal_history_tracks_supersede_and_outcome() {
+        use crate::domain::services::strategies::Signal;
+
+        let mut service = MpcService::new(TradingConfig::default());
+        service.set_signal_repository(Arc::new(SqliteSignalRepository::new(memory_pool().await)));
+
+        // Not whitelisted, so the surviving signal is rejected when evaluated
+        service
+            .store_signal("NOPE-USD".to_string(), TradingSignal::new(Signal::Buy, 0.9))
+            .await;
+        service
+            .store_signal(
+                "NOPE-USD".to_string(),
+                TradingSignal::new(Signal::Sell, 0.8),
             )
             .await;
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap().value(), 55000.0);
+        let results = service.check_and_execute_orders().await;
+        assert_eq!(results.len(), 1);
+        assert!(service.pending_signal_ids.lock().await.is_empty());
+
+        let history = service
+            .get_signal_history(&SignalQuery {
+                symbol: Some("NOPE-USD".to_string()),
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].action, "sell");
+        assert_eq!(history[0].skip_reason.as_deref(), Some("rejected"));
+        assert!(history[0].outcome.as_deref().unwrap().contains("whitelist"));
+        assert_eq!(history[1].action, "buy");
+        assert_eq!(history[1].skip_reason.as_deref(), Some("superseded"));
+        assert!(history
+            .iter()
+            .all(|r| !r.executed && r.decided_at.is_some()));
+    }
+
+    #[test]
+    fn test_signal_skip_reason_classification() {
+        let executed = Ok(SignalExecution::Executed(
+            "Order executed by trader t: Buy - Order ID: 1".to_string(),
+        ));
+        let skipped = |reason| {
+            Ok::<_, MpcError>(SignalExecution::skipped(
+                reason,
+                "Signal confidence 0.40 too low for execution".to_string(),
+            ))
+        };
+
+        assert_eq!(signal_skip_reason(&executed), None);
+        // The typed outcome wins over whatever the message says
+        assert_eq!(
+            signal_skip_reason(&skipped(SignalSkipReason::Hold)),
+            Some(SignalSkipReason::Hold)
+        );
+        assert_eq!(
+            signal_skip_reason(&skipped(SignalSkipReason::Limits)),
+            Some(SignalSkipReason::Limits)
+        );
+        assert_eq!(
+            signal_skip_reason(&Err(MpcError::TradeLimitExceeded(
+                "Hourly trade limit exceeded: 10 trades in last hour (max: 10)".to_string()
+            ))),
+            Some(SignalSkipReason::Limits)
+        );
+        assert_eq!(
+            signal_skip_reason(&Err(MpcError::PositionSizingRefused(
+                "Symbol X sized to zero by kelly sizing: no edge".to_string()
+            ))),
+            Some(SignalSkipReason::Risk)
+        );
+        assert_eq!(
+            signal_skip_reason(&Err(MpcError::InvalidInput(
+                "Symbol 'X' is not in the configured whitelist for trading".to_string()
+            ))),
+            Some(SignalSkipReason::Rejected)
+        );
+        assert_eq!(
+            signal_skip_reason(&Err(MpcError::InvalidConfiguration(
+                "Automated trading is disabled".to_string()
+            ))),
+            Some(SignalSkipReason::Rejected)
+        );
+        assert_eq!(
+            signal_skip_reason(&Err(MpcError::OrderPlacementFailed("rejected".into()))),
+            Some(SignalSkipReason::Failed)
+        );
+        assert!(is_retryable(&Err(MpcError::Timeout)));
+        assert!(!is_retryable(&executed));
     }
 
     #[tokio::test]
-    async fn test_subscribe_unsubscribe() {
-        let config = TradingConfig::default();
-        let mut service = MpcService::new(config);
+    async fn test_apply_strategy_registry_installs_group_combiners() {
+        let registry = StrategyRegistry::from_json(
+            r#"{
+                "strategies": [
+                    { "name": "Fast", "type": "fast_scalping" },
+                    { "name": "Momentum", "type": "momentum_scalping" },
+                    { "name": "Conservative", "type": "conservative_scalping" }
+                ],
+                "groups": [
+                    { "name": "alts", "strategies": [
+                        { "strategy": "Fast", "weight": 0.5 },
+                        { "strategy": "Momentum", "weight": 0.5 } ] },
+                    { "name": "majors", "symbols": ["BTCUSDT"], "strategies": [
+                        { "strategy": "Conservative", "weight": 2.0 } ] }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let service = MpcService::new(TradingConfig::default());
+        service.apply_strategy_registry(registry).await.unwrap();
+
+        let (strategies, groups) = service.get_active_strategies().await;
+        assert_eq!(strategies.len(), 3);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "alts");
+        assert!(groups[0].symbols.is_empty());
+        assert_eq!(groups[1].name, "majors");
+        assert_eq!(groups[1].symbols, vec!["BTC-USD"]);
+        assert_eq!(groups[1].weights, vec![("Conservative".to_string(), 2.0)]);
+
+        // Strategies used only by a group are still tracked
+        assert_eq!(
+            *service.strategy_order.lock().await,
+            vec!["Fast", "Momentum", "Conservative"]
+        );
+        assert!(service
+            .get_strategy_metrics()
+            .await
+            .contains_key("Conservative"));
 
-        let mock_price = crate::domain::value_objects::price::Price::new(50000.0).unwrap();
-        let sender = crate::infrastructure::adapters::exchange_actor::MockExchangeActor::spawn(
-            crate::domain::entities::exchange::Exchange::Binance,
-            mock_price,
+        // Each combiner is adjusted from its own strategies
+        for metrics in service.strategy_metrics.lock().await.values_mut() {
+            metrics.performance_score = 0.5;
+        }
+        service.adjust_strategy_weights().await.unwrap();
+        let (_, groups) = service.get_active_strategies().await;
+        assert_eq!(groups[1].weights[0].1, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_strategy_groups_resolve_by_symbol_glob_and_category() {
+        let registry = StrategyRegistry::from_json(
+            r#"{
+                "strategies": [
+                    { "name": "Fast", "type": "fast_scalping" },
+                    { "name": "Momentum", "type": "momentum_scalping" },
+                    { "name": "Conservative", "type": "conservative_scalping" }
+                ],
+                "groups": [
+                    { "name": "default", "strategies": [{ "strategy": "Fast", "weight": 1 }] },
+                    { "name": "usd", "symbols": ["*-USD"], "min_confidence_threshold": 0.6,
+                      "strategies": [{ "strategy": "Momentum", "weight": 1 }] },
+                    { "name": "sol", "symbols": ["SOL/USD"], "min_confidence_threshold": 0.9,
+                      "strategies": [
+                        { "strategy": "Fast", "weight": 0.5 },
+                        { "strategy": "Momentum", "weight": 0.5 } ] },
+                    { "name": "movers", "categories": ["BestCandidate"],
+                      "strategies": [{ "strategy": "Conservative", "weight": 1 }] }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let mut config = TradingConfig::default();
+        config.min_confidence_threshold = 0.7;
+        let service = MpcService::new(config);
+        service.apply_strategy_registry(registry).await.unwrap();
+
+        // Exact symbol (normalized from SOL/USD) beats the glob
+        assert_eq!(
+            service.strategies_for_symbol("SOL-USD").await,
+            vec!["Fast", "Momentum"]
         );
-        service
-            .add_actor(crate::domain::entities::exchange::Exchange::Binance, sender)
-            .expect("Failed to add actor");
+        assert_eq!(service.confidence_threshold_for("SOL-USD").await, 0.9);
+        assert_eq!(
+            service.strategies_for_symbol("ETH-USD").await,
+            vec!["Momentum"]
+        );
+        assert_eq!(service.confidence_threshold_for("ETH-USD").await, 0.6);
 
-        // Test subscribe
-        let result = service
-            .subscribe(
-                &crate::domain::entities::exchange::Exchange::Binance,
-                "BTCUSDT",
-            )
-            .await;
-        assert!(result.is_ok());
+        // Unmatched symbols use the default group and the configured threshold
+        assert_eq!(
+            service.strategies_for_symbol("DOGE-EUR").await,
+            vec!["Fast"]
+        );
+        assert_eq!(service.confidence_threshold_for("DOGE-EUR").await, 0.7);
 
-        // Test unsubscribe
-        let result = service
-            .unsubscribe(
-                &crate::domain::entities::exchange::Exchange::Binance,
-                "BTCUSDT",
+        // A screening category applies once the symbol is screened, below globs
+        let screened = |symbol: &str| {
+            SymbolScreeningResult::new(
+                symbol.to_string(),
+                "coinbase".to_string(),
+                0.9,
+                0.9,
+                0.9,
+                0.9,
             )
+        };
+        service
+            .update_symbol_categories(&[screened("DOGE-EUR"), screened("ETH-USD")])
             .await;
-        assert!(result.is_ok());
+        assert_eq!(
+            service.strategies_for_symbol("DOGE-EUR").await,
+            vec!["Conservative"]
+        );
+        assert_eq!(service.confidence_threshold_for("DOGE-EUR").await, 0.7);
+        assert_eq!(
+            service.strategies_for_symbol("ETH-USD").await,
+            vec!["Momentum"]
+        );
+
+        let (_, groups) = service.get_active_strategies().await;
+        assert_eq!(groups[3].name, "movers");
+        assert_eq!(
+            groups[3].categories,
+            vec![RecommendationCategory::BestCandidate]
+        );
+        assert_eq!(groups[3].min_confidence_threshold, 0.7);
     }
 
     #[tokio::test]
-    async fn test_get_subscriptions() {
-        let config = TradingConfig::default();
-        let mut service = MpcService::new(config);
-
-        let mock_price = crate::domain::value_objects::price::Price::new(50000.0).unwrap();
-        let sender = crate::infrastructure::adapters::exchange_actor::MockExchangeActor::spawn(
-            crate::domain::entities::exchange::Exchange::Binance,
-            mock_price,
-        );
+    async fn test_strategy_metrics_tracked_per_symbol() {
+        let service = MpcService::new(TradingConfig::default());
         service
-            .add_actor(crate::domain::entities::exchange::Exchange::Binance, sender)
-            .expect("Failed to add actor");
+            .apply_strategy_registry(StrategyRegistry::default())
+            .await
+            .unwrap();
 
-        // Get subscriptions
-        let result = service
-            .get_subscriptions(&crate::domain::entities::exchange::Exchange::Binance)
+        let pnl = Price::new(0.0).unwrap();
+        service
+            .record_symbol_strategy_execution("FastScalping", "BTC-USD", pnl)
             .await;
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap().len(), 0); // Mock actor returns empty list
+        service
+            .record_symbol_strategy_execution("FastScalping", "BTC-USD", pnl)
+            .await;
+        service
+            .record_symbol_strategy_execution("FastScalping", "ETH-USD", pnl)
+            .await;
+
+        let pairs = service.get_symbol_strategy_metrics().await;
+        assert_eq!(pairs.len(), 2);
+        let btc = &pairs[&("FastScalping".to_string(), "BTC-USD".to_string())];
+        assert_eq!(btc.signals_executed, 2);
+        assert_eq!(btc.strategy_name, "FastScalping");
+        assert_eq!(
+            pairs[&("FastScalping".to_string(), "ETH-USD".to_string())].signals_executed,
+            1
+        );
     }
 
     #[tokio::test]
@@ -1965,4 +5770,522 @@ mod tests {
             "MomentumScalping weight should be higher than ConservativeScalping"
         );
     }
+
+    #[tokio::test]
+    async fn test_bandit_weights_are_persisted_and_audited() {
+        let registry = StrategyRegistry::from_json(
+            r#"{
+                "strategies": [
+                    { "name": "Winner", "type": "fast_scalping" },
+                    { "name": "Loser", "type": "momentum_scalping" }
+                ],
+                "groups": [{ "name": "default", "strategies": [
+                    { "strategy": "Winner", "weight": 0.5 }, { "strategy": "Loser", "weight": 0.5 } ] }],
+                "allocator": { "policy": "ucb", "floor": 0.1, "cap": 0.8, "exploration": 0.1 }
+            }"#,
+        )
+        .unwrap();
+        let pool = memory_pool().await;
+        let allocator_repository = Arc::new(SqliteAllocatorStateRepository::new(pool.clone()));
+        let audit_repository = Arc::new(SqliteAuditLogRepository::new(pool));
+
+        let mut service = MpcService::new(TradingConfig::default());
+        service.set_weight_repositories(allocator_repository.clone(), audit_repository.clone());
+        service
+            .apply_strategy_registry(registry.clone())
+            .await
+            .unwrap();
+        {
+            let mut metrics = service.strategy_metrics.lock().await;
+            for _ in 0..10 {
+                metrics.get_mut("Winner").unwrap().record_trade_outcome(5.0);
+                metrics.get_mut("Loser").unwrap().record_trade_outcome(-5.0);
+            }
+        }
+        service.adjust_strategy_weights().await.unwrap();
+
+        let weights = service
+            .signal_combiner
+            .read()
+            .await
+            .as_ref()
+            .unwrap()
+            .weights()
+            .to_vec();
+        assert!((weights[0] - 0.8).abs() < 1e-9, "{:?}", weights);
+        assert!((weights[1] - 0.2).abs() < 1e-9, "{:?}", weights);
+
+        let audit = audit_repository
+            .get_by_event_type("strategy_weights_changed", 10)
+            .await
+            .unwrap();
+        assert_eq!(audit.len(), 1);
+        let details: serde_json::Value = serde_json::from_str(&audit[0].details).unwrap();
+        assert_eq!(details["group"], "default");
+        assert_eq!(details["policy"], "ucb");
+        assert_eq!(details["previous"]["Winner"], 0.5);
+
+        // Unchanged weights are not audited again
+        service.adjust_strategy_weights().await.unwrap();
+        assert_eq!(
+            audit_repository
+                .get_by_event_type("strategy_weights_changed", 10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // A restarted service resumes from the persisted statistics
+        let mut restarted = MpcService::new(TradingConfig::default());
+        restarted.set_weight_repositories(allocator_repository, audit_repository);
+        restarted.apply_strategy_registry(registry).await.unwrap();
+        restarted.restore_weight_allocator().await.unwrap();
+        let state = restarted.get_allocator_state().await.unwrap();
+        assert_eq!(state.arms["Winner"].wins, 10);
+        assert_eq!(state.arms["Loser"].losses, 10);
+
+        restarted.adjust_strategy_weights().await.unwrap();
+        let weights = restarted
+            .signal_combiner
+            .read()
+            .await
+            .as_ref()
+            .unwrap()
+            .weights()
+            .to_vec();
+        assert!((weights[0] - 0.8).abs() < 1e-9, "{:?}", weights);
+    }
+
+    #[tokio::test]
+    async fn test_thompson_weights_are_only_audited_when_they_move() {
+        let registry = StrategyRegistry::from_json(
+            r#"{
+                "strategies": [
+                    { "name": "Winner", "type": "fast_scalping" },
+                    { "name": "Loser", "type": "momentum_scalping" }
+                ],
+                "groups": [{ "name": "default", "strategies": [
+                    { "strategy": "Winner", "weight": 0.5 }, { "strategy": "Loser", "weight": 0.5 } ] }],
+                "allocator": { "policy": "thompson", "floor": 0.1, "cap": 0.8 }
+            }"#,
+        )
+        .unwrap();
+        let pool = memory_pool().await;
+        let audit_repository = Arc::new(SqliteAuditLogRepository::new(pool.clone()));
+        let mut service = MpcService::new(TradingConfig::default());
+        service.set_weight_repositories(
+            Arc::new(SqliteAllocatorStateRepository::new(pool)),
+            audit_repository.clone(),
+        );
+        service.apply_strategy_registry(registry).await.unwrap();
+        let metrics = &service.strategy_metrics;
+        let record_trades = move |count: usize| async move {
+            let mut metrics = metrics.lock().await;
+            for _ in 0..count {
+                metrics.get_mut("Winner").unwrap().record_trade_outcome(5.0);
+                metrics.get_mut("Loser").unwrap().record_trade_outcome(-5.0);
+            }
+        };
+        let audit = &audit_repository;
+        let audited = move || async move {
+            audit
+                .get_by_event_type("strategy_weights_changed", 10)
+                .await
+                .unwrap()
+                .len()
+        };
+
+        record_trades(20).await;
+        service.adjust_strategy_weights().await.unwrap();
+        assert_eq!(audited().await, 1);
+
+        // Without new trades nothing is resampled
+        for _ in 0..5 {
+            service.adjust_strategy_weights().await.unwrap();
+        }
+        assert_eq!(audited().await, 1);
+
+        // New trades that leave the weights in place are not audited either
+        record_trades(1).await;
+        service.adjust_strategy_weights().await.unwrap();
+        assert_eq!(audited().await, 1);
+        let weights = service
+            .signal_combiner
+            .read()
+            .await
+            .as_ref()
+            .unwrap()
+            .weights()
+            .to_vec();
+        assert!((weights[0] - 0.8).abs() < 0.02, "{:?}", weights);
+    }
+
+    #[tokio::test]
+    async fn test_shadow_combiner_paper_trades_without_orders() {
+        use crate::domain::services::indicators::Candle;
+        use crate::domain::services::strategies::Signal;
+
+        struct AlwaysBuy;
+        impl Strategy for AlwaysBuy {
+            fn generate_signal(&self, _candles: &[Candle]) -> Option<TradingSignal> {
+                Some(TradingSignal::new(Signal::Buy, 0.9))
+            }
+        }
+
+        let mut config = TradingConfig::default();
+        config.take_profit_percentage = Some(0.02);
+        let mut service = MpcService::new(config);
+        service.candle_builder = Arc::new(Mutex::new(CandleBuilder::new(
+            Duration::from_millis(1),
+            CANDLE_HISTORY_SIZE,
+        )));
+        for i in 0..8 {
+            service
+                .update_candle("BTC-USD".to_string(), Price::new(100.0 + i as f64).unwrap())
+                .await;
+            tokio::time::sleep(Duration::from_millis(3)).await;
+        }
+        assert!(service.get_candles("BTC-USD").await.len() >= MIN_CANDLES_FOR_SIGNAL);
+
+        let group: GroupDefinition = serde_json::from_str(
+            r#"{ "name": "trial", "symbols": ["BTC-USD"],
+                 "strategies": [{ "strategy": "AlwaysBuy", "weight": 1.0 }] }"#,
+        )
+        .unwrap();
+        let combiner = SignalCombiner::new(
+            vec![("AlwaysBuy".to_string(), Box::new(AlwaysBuy) as Box<_>)],
+            vec![1.0],
+        )
+        .unwrap();
+        service.add_shadow_combiner(group, combiner).await;
+
+        // Other symbols are not served by the shadow
+        assert!(service
+            .paper_trade_shadows("ETH-USD", Price::new(10.0).unwrap())
+            .await
+            .is_empty());
+
+        let results = service
+            .paper_trade_shadows("BTC-USD", Price::new(100.0).unwrap())
+            .await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].as_ref().unwrap().contains("paper order filled"));
+        assert!(service.get_open_positions().await.is_empty());
+
+        // One position per symbol, as live
+        let results = service
+            .paper_trade_shadows("BTC-USD", Price::new(101.0).unwrap())
+            .await;
+        assert!(results[0].as_ref().unwrap().contains("per symbol"));
+
+        // Take-profit closes the paper position, then a new one is opened
+        let results = service
+            .paper_trade_shadows("BTC-USD", Price::new(103.0).unwrap())
+            .await;
+        assert!(results[0].as_ref().unwrap().contains("take-profit"));
+        assert!(results[1].as_ref().unwrap().contains("paper order filled"));
+
+        // Live trades on other symbols are left out of the shadow's comparison
+        {
+            let mut pair_metrics = service.symbol_strategy_metrics.lock().await;
+            for (symbol, pnl) in [("BTC-USD", 5.0), ("ETH-USD", -2.0)] {
+                pair_metrics
+                    .entry(("Live".to_string(), symbol.to_string()))
+                    .or_insert_with(|| StrategyMetrics::new("Live".to_string()))
+                    .record_trade_outcome(pnl);
+            }
+        }
+
+        let (live, shadows) = service.get_shadow_comparison().await;
+        assert_eq!(live.closed_trades, 0);
+        assert_eq!(live.open_positions, 0);
+        assert_eq!(shadows[0].live.closed_trades, 1);
+        assert!((shadows[0].live.realized_pnl - 5.0).abs() < 1e-9);
+        assert_eq!(shadows.len(), 1);
+        assert_eq!(shadows[0].name, "trial");
+        assert_eq!(shadows[0].signals_generated, 3);
+        assert_eq!(shadows[0].signals_executed, 2);
+        assert_eq!(shadows[0].results.closed_trades, 1);
+        assert_eq!(shadows[0].results.winning_trades, 1);
+        assert!(shadows[0].results.realized_pnl > 0.0);
+        assert_eq!(shadows[0].results.open_positions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_screening_data_takes_trade_rate_funding_and_book_from_discovery() {
+        use crate::infrastructure::adapters::exchange_actor::BestQuote;
+
+        let mut config = TradingConfig::default();
+        config.screening_depth_weight = 0.1;
+        config.screening_trade_rate_weight = 0.1;
+        config.screening_funding_weight = 0.1;
+        let mut service = MpcService::new(config);
+        service.candle_builder = Arc::new(Mutex::new(CandleBuilder::new(
+            Duration::from_millis(1),
+            CANDLE_HISTORY_SIZE,
+        )));
+        for price in [50000.0, 50010.0] {
+            service
+                .update_candle("BTC-USD".to_string(), Price::new(price).unwrap())
+                .await;
+            tokio::time::sleep(Duration::from_millis(3)).await;
+        }
+
+        let mut btc = snapshot("BTC-USD", 50000.0, 49000.0, 1_000_000_000.0);
+        btc.trades_24h = Some(144_000.0);
+        btc.funding_rate = Some(0.00001);
+        let markets = Arc::new(std::sync::Mutex::new(vec![btc]));
+        service.set_market_discovery(Arc::new(StaticDiscovery(markets)));
+
+        let (tx, mut rx) = mpsc::channel(16);
+        tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                match message {
+                    ExchangeMessage::GetSubscriptions { reply } => {
+                        let _ = reply.send(vec!["BTC-USD".to_string()]).await;
+                    }
+                    ExchangeMessage::GetBestQuote { reply, .. } => {
+                        let _ = reply
+                            .send(Ok(BestQuote {
+                                bid: Price::new(49999.0).unwrap(),
+                                ask: Price::new(50001.0).unwrap(),
+                                bid_size: Some(0.5),
+                                ask_size: Some(0.5),
+                            }))
+                            .await;
+                    }
+                    _ => {}
+                }
+            }
+        });
+        service.senders = Arc::new(HashMap::from([(Exchange::Coinbase, tx)]));
+
+        let data = service.get_screening_data(&Exchange::Coinbase).await;
+        let btc = &data["BTC-USD"];
+        assert_eq!(btc.trades_per_minute, Some(100.0));
+        assert_eq!(btc.funding_rate, Some(0.00001));
+        // The book, not the ticker's best level
+        let depth = btc.depth.as_ref().unwrap();
+        assert_eq!(depth.bids.len(), 2);
+        assert_eq!(depth.asks[1].1, 2.0);
+        assert_eq!(btc.bid, 49999.0);
+    }
+
+    #[tokio::test]
+    async fn test_own_fills_move_expected_balances_of_the_next_reconciliation() {
+        let mut service = MpcService::new(TradingConfig::default());
+        let buy = TradeRecord {
+            id: "trade_1".to_string(),
+            position_id: None,
+            symbol: "ETH-USD".to_string(),
+            exchange: "coinbase".to_string(),
+            side: "buy".to_string(),
+            price: 2000.0,
+            quantity: 0.5,
+            fee: 1.0,
+            fee_estimated: false,
+            exchange_order_id: None,
+            executed_at: chrono::Utc::now(),
+            strategy: "momentum".to_string(),
+            signal_confidence: None,
+            exit_reason: None,
+            signal_details: None,
+            created_at: chrono::Utc::now(),
+        };
+        service.enter_in_ledger(&buy).await;
+        service
+            .record_funding_payment(FundingPayment {
+                symbol: "BTC-USD".to_string(),
+                exchange: "dydx".to_string(),
+                strategy: None,
+                amount: -2.5,
+                paid_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        let (actor_tx, mut actor_rx) = mpsc::channel(4);
+        let (sent_tx, mut sent_rx) = mpsc::channel(4);
+        tokio::spawn(async move {
+            while let Some(ReconciliationMessage::ReconcileAll { activity, reply }) =
+                actor_rx.recv().await
+            {
+                let _ = sent_tx.send(activity).await;
+                let _ = reply.send(Vec::new()).await;
+            }
+        });
+        service.reconciliation = Some(actor_tx);
+
+        service.run_reconciliation().await.unwrap();
+        let activity = sent_rx.recv().await.unwrap();
+        let coinbase = &activity[&Exchange::Coinbase].flows;
+        assert_eq!(coinbase["ETH"], 0.5);
+        assert_eq!(coinbase["USD"], -1001.0);
+        assert_eq!(activity[&Exchange::Dydx].flows["USD"], -2.5);
+
+        // Flows are handed over once
+        service.run_reconciliation().await.unwrap();
+        assert!(sent_rx.recv().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_critical_reconciliation_raises_one_alert_until_resolved() {
+        use crate::domain::services::reconciliation::BalanceDiscrepancy;
+
+        let mut service = MpcService::new(TradingConfig::default());
+        assert!(service.run_reconciliation().await.is_err());
+
+        // Stand-in actor answering each run with the next scripted reports
+        let (actor_tx, mut actor_rx) = mpsc::channel(4);
+        let mut critical = ReconciliationReport::new(Exchange::Dydx);
+        critical.add_discrepancy(BalanceDiscrepancy::Missing {
+            currency: "USD".to_string(),
+            amount: 1000.0,
+        });
+        let mut runs = vec![
+            vec![ReconciliationReport::new(Exchange::Dydx)],
+            vec![critical.clone()],
+            vec![critical],
+        ];
+        tokio::spawn(async move {
+            while let Some(ReconciliationMessage::ReconcileAll { reply, .. }) =
+                actor_rx.recv().await
+            {
+                let _ = reply.send(runs.pop().unwrap_or_default()).await;
+            }
+        });
+        service.reconciliation = Some(actor_tx);
+
+        let reconciliation_alerts = |alerts: Vec<SystemAlert>| -> Vec<String> {
+            alerts
+                .into_iter()
+                .filter(|alert| {
+                    alert.alert_type == AlertType::BalanceReconciliation(Exchange::Dydx)
+                })
+                .map(|alert| alert.message)
+                .collect()
+        };
+
+        service.run_reconciliation().await.unwrap();
+        service.run_reconciliation().await.unwrap();
+        assert_eq!(
+            reconciliation_alerts(service.get_active_alerts().await),
+            vec!["Reconciliation dydx: 1 discrepancies (USD)".to_string()]
+        );
+
+        // A clean position check on the same exchange leaves the balance alert
+        service
+            .raise_position_reconciliation_alert(&PositionReconciliationReport::new(Exchange::Dydx))
+            .await;
+        assert_eq!(
+            reconciliation_alerts(service.get_active_alerts().await).len(),
+            1
+        );
+
+        let reports = service.run_reconciliation().await.unwrap();
+        assert_eq!(reports[0].status, ReconciliationStatus::Ok);
+        assert!(reconciliation_alerts(service.get_active_alerts().await).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_paused_currency_blocks_orders_until_resolved() {
+        use crate::domain::services::reconciliation::{
+            BalanceDiscrepancy, RecoveryStatus, RemediationAction, RemediationPolicy,
+        };
+        use crate::persistence::reconciliation_audit::SqliteReconciliationRepository;
+
+        let repository = Arc::new(SqliteReconciliationRepository::new(memory_pool().await));
+        let mut report = ReconciliationReport::new(Exchange::Coinbase);
+        report.add_discrepancy(BalanceDiscrepancy::Missing {
+            currency: "ETH".to_string(),
+            amount: 2.0,
+        });
+        report.remediation = RemediationPolicy {
+            critical: RemediationAction::PauseTrading,
+            ..Default::default()
+        }
+        .plan(&report);
+        repository.save_reconciliation(&report).await.unwrap();
+
+        let mut service = MpcService::new(TradingConfig::default());
+        let (actor_tx, mut actor_rx) = mpsc::channel(4);
+        service.set_reconciliation(actor_tx, repository);
+
+        // Pauses survive a restart through the audit trail
+        assert_eq!(service.restore_reconciliation_pauses().await.unwrap(), 1);
+        let err = service
+            .check_reconciliation_pause("ETHUSDT")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("ETH is paused by coinbase"));
+        assert!(service.check_reconciliation_pause("BTC-USD").await.is_ok());
+
+        let resolved = service
+            .resolve_reconciliation(&report.id(), Some("exchange was right"))
+            .await
+            .unwrap();
+        assert_eq!(
+            resolved.remediation.unwrap().status,
+            RecoveryStatus::Resolved
+        );
+        assert!(service.check_reconciliation_pause("ETH-USD").await.is_ok());
+        assert!(service.get_open_remediations().await.unwrap().is_empty());
+        // The actor is told to adopt the resolved balances
+        assert!(matches!(
+            actor_rx.recv().await,
+            Some(ReconciliationMessage::Resolve { .. })
+        ));
+        assert!(service
+            .resolve_reconciliation(&report.id(), None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolving_one_report_keeps_the_pauses_of_newer_ones() {
+        use crate::domain::services::reconciliation::{
+            BalanceDiscrepancy, RemediationAction, RemediationPolicy,
+        };
+        use crate::persistence::reconciliation_audit::SqliteReconciliationRepository;
+
+        let repository = Arc::new(SqliteReconciliationRepository::new(memory_pool().await));
+        let paused_report = |currency: &str, seconds_ago: i64| {
+            let mut report = ReconciliationReport::new(Exchange::Coinbase);
+            report.timestamp = chrono::Utc::now() - chrono::Duration::seconds(seconds_ago);
+            report.add_discrepancy(BalanceDiscrepancy::Missing {
+                currency: currency.to_string(),
+                amount: 2.0,
+            });
+            report.remediation = RemediationPolicy {
+                critical: RemediationAction::PauseTrading,
+                ..Default::default()
+            }
+            .plan(&report);
+            report
+        };
+        let older = paused_report("ETH", 20);
+        let newer = paused_report("SOL", 10);
+        repository.save_reconciliation(&older).await.unwrap();
+        repository.save_reconciliation(&newer).await.unwrap();
+
+        let mut service = MpcService::new(TradingConfig::default());
+        let (actor_tx, _actor_rx) = mpsc::channel(4);
+        service.set_reconciliation(actor_tx, repository);
+        assert_eq!(service.restore_reconciliation_pauses().await.unwrap(), 2);
+
+        service
+            .resolve_reconciliation(&older.id(), None)
+            .await
+            .unwrap();
+        assert!(service.check_reconciliation_pause("ETH-USD").await.is_ok());
+        assert!(service.check_reconciliation_pause("SOL-USD").await.is_err());
+        assert_eq!(
+            service.get_paused_currencies().await,
+            vec![("SOL".to_string(), Exchange::Coinbase)]
+        );
+        let open = service.get_open_remediations().await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].id(), newer.id());
+    }
 }
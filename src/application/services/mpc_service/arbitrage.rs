@@ -0,0 +1,581 @@
+//! Cross-exchange arbitrage of the MPC service
+//!
+//! Compares the arbitrage symbols across exchanges and, when execution is
+//! enabled, buys on the cheap venue and sells on the rich one, unwinding
+//! whatever one leg filled beyond the other.
+
+use super::*;
+
+/// Arbitrage attempts kept for `/arbitrage`
+const ARBITRAGE_HISTORY_SIZE: usize = 100;
+
+/// Times an arbitrage leg is polled for its fill before it is cancelled
+const ARBITRAGE_FILL_POLLS: usize = 5;
+
+/// Delay between fill polls of an arbitrage leg
+const ARBITRAGE_FILL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+impl MpcService {
+    /// Compare every arbitrage symbol across exchanges
+    ///
+    /// The widest divergence of each symbol is kept for `/arbitrage`. Those
+    /// clearing the net spread threshold are flagged, and traded when
+    /// execution is enabled and the symbol is out of its cooldown.
+    pub async fn scan_arbitrage(&self) -> Vec<Result<String, MpcError>> {
+        if !self.config.arbitrage_enabled {
+            return Vec::new();
+        }
+        let params = &self.config.arbitrage;
+        let mut results = Vec::new();
+
+        for symbol in self.arbitrage_symbols().await {
+            let venues = match self.get_venue_prices(&symbol).await {
+                Ok(venues) => venues,
+                Err(e) => {
+                    results.push(Err(e));
+                    continue;
+                }
+            };
+            let prices: Vec<(Exchange, f64)> = venues
+                .iter()
+                .map(|(exchange, _, price)| (exchange.clone(), price.value()))
+                .collect();
+            let Some(widest) = best_divergence(&symbol, &prices, params) else {
+                continue;
+            };
+            {
+                let mut divergences = self.price_divergences.lock().await;
+                divergences.insert(symbol.clone(), widest.clone());
+            }
+            if !widest.is_opportunity(params) {
+                continue;
+            }
+            // Only venues with order routing can be traded
+            let routable: Vec<(Exchange, f64)> = prices
+                .iter()
+                .filter(|(exchange, _)| can_route_orders(exchange))
+                .cloned()
+                .collect();
+            let divergence = best_divergence(&symbol, &routable, params)
+                .filter(|divergence| divergence.is_opportunity(params))
+                .unwrap_or(widest);
+
+            let summary = format!(
+                "{}: buy {} @ {:.2}, sell {} @ {:.2}, {:.1} bps net",
+                symbol,
+                Self::get_exchange_name(&divergence.buy_exchange),
+                divergence.buy_price,
+                Self::get_exchange_name(&divergence.sell_exchange),
+                divergence.sell_price,
+                divergence.net_spread_bps
+            );
+            info!("Arbitrage opportunity {}", summary);
+            if !self.config.arbitrage_execute {
+                results.push(Ok(format!("Opportunity {}", summary)));
+                continue;
+            }
+            if !(can_route_orders(&divergence.buy_exchange)
+                && can_route_orders(&divergence.sell_exchange))
+            {
+                results.push(Ok(format!("Opportunity {} (no order routing)", summary)));
+                continue;
+            }
+            if self.in_arbitrage_cooldown(&symbol).await {
+                results.push(Ok(format!("Opportunity {} (cooling down)", summary)));
+                continue;
+            }
+
+            let venue_symbol = |exchange: &Exchange| {
+                venues
+                    .iter()
+                    .find(|(venue, _, _)| venue == exchange)
+                    .map_or_else(
+                        || symbol.clone(),
+                        |(_, venue_symbol, _)| venue_symbol.clone(),
+                    )
+            };
+            let buy_symbol = venue_symbol(&divergence.buy_exchange);
+            let sell_symbol = venue_symbol(&divergence.sell_exchange);
+            results.push(
+                self.execute_arbitrage(&divergence, &buy_symbol, &sell_symbol)
+                    .await
+                    .map(|execution| format!("{:?} {}", execution.outcome, summary)),
+            );
+        }
+        results
+    }
+
+    /// Symbols to compare: the configured list, or every subscribed symbol
+    async fn arbitrage_symbols(&self) -> Vec<String> {
+        if !self.config.arbitrage_symbols.is_empty() {
+            return self.config.arbitrage_symbols.clone();
+        }
+        let mut symbols: Vec<String> = self
+            .get_all_symbols()
+            .await
+            .iter()
+            .map(|symbol| TradingConfig::normalize_symbol(symbol))
+            .collect();
+        symbols.sort();
+        symbols.dedup();
+        symbols
+    }
+
+    /// Whether `symbol` was traded within the arbitrage cooldown
+    async fn in_arbitrage_cooldown(&self, symbol: &str) -> bool {
+        let cooldown = chrono::Duration::seconds(self.config.arbitrage_cooldown_seconds as i64);
+        let executions = self.arbitrage_executions.lock().await;
+        executions
+            .iter()
+            .rev()
+            .find(|execution| execution.symbol == symbol)
+            .is_some_and(|execution| chrono::Utc::now() - execution.executed_at < cooldown)
+    }
+
+    /// Buy on the cheap exchange and sell on the rich one at the same time
+    ///
+    /// `buy_symbol` and `sell_symbol` are the symbol as each exchange spells
+    /// it. Both legs are slippage-protected limit orders sent concurrently,
+    /// then polled for their fills; a leg still open after the fill window is
+    /// cancelled. When the legs filled different quantities the excess is
+    /// reversed on its exchange, so a failed or partial leg never leaves a
+    /// one-sided position behind. Every fill is recorded as a trade of the
+    /// `Arbitrage` strategy, so it reaches the PnL ledger and survives a
+    /// restart.
+    pub async fn execute_arbitrage(
+        &self,
+        divergence: &PriceDivergence,
+        buy_symbol: &str,
+        sell_symbol: &str,
+    ) -> Result<ArbitrageExecution, MpcError> {
+        if !self.config.enable_automated_trading {
+            return Err(MpcError::InvalidConfiguration(
+                "Automated trading is disabled".to_string(),
+            ));
+        }
+        for exchange in [&divergence.buy_exchange, &divergence.sell_exchange] {
+            if !can_route_orders(exchange) {
+                return Err(MpcError::InvalidInput(format!(
+                    "Orders cannot be placed on {}",
+                    Self::get_exchange_name(exchange)
+                )));
+            }
+        }
+        self.check_trading_limits().await?;
+        self.check_reconciliation_pause(&divergence.symbol).await?;
+
+        let quantity = self.config.arbitrage.order_notional / divergence.buy_price;
+        if quantity < MIN_ORDER_QUANTITY {
+            return Err(MpcError::InvalidInput(format!(
+                "Arbitrage quantity {} for {} is below the minimum",
+                quantity, divergence.symbol
+            )));
+        }
+        let slippage = self.config.max_slippage_percent;
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        let order = |side: OrderSide, symbol: &str, limit: f64, quantity: f64| {
+            Order::new(
+                format!(
+                    "arb_{}_{}_{}",
+                    timestamp,
+                    side.to_string().to_lowercase(),
+                    symbol
+                ),
+                symbol.to_string(),
+                side,
+                OrderType::Limit,
+                Some(limit),
+                quantity,
+            )
+            .map_err(|e| MpcError::InvalidConfiguration(format!("Failed to create order: {}", e)))
+        };
+        let buy = order(
+            OrderSide::Buy,
+            buy_symbol,
+            divergence.buy_price * (1.0 + slippage),
+            quantity,
+        )?;
+        let sell = order(
+            OrderSide::Sell,
+            sell_symbol,
+            divergence.sell_price * (1.0 - slippage),
+            quantity,
+        )?;
+
+        let (buy_result, sell_result) = tokio::join!(
+            self.place_order(&divergence.buy_exchange, buy),
+            self.place_order(&divergence.sell_exchange, sell)
+        );
+
+        let mut execution = ArbitrageExecution {
+            symbol: divergence.symbol.clone(),
+            buy_exchange: divergence.buy_exchange.clone(),
+            sell_exchange: divergence.sell_exchange.clone(),
+            quantity,
+            net_spread_bps: divergence.net_spread_bps,
+            buy_order_id: None,
+            sell_order_id: None,
+            buy_filled: 0.0,
+            sell_filled: 0.0,
+            unwind_order_id: None,
+            unwind_filled: 0.0,
+            outcome: ArbitrageOutcome::Filled,
+            errors: Vec::new(),
+            executed_at: chrono::Utc::now(),
+        };
+        match buy_result {
+            Ok(id) => execution.buy_order_id = Some(id),
+            Err(e) => execution.errors.push(format!("buy leg: {}", e)),
+        }
+        match sell_result {
+            Ok(id) => execution.sell_order_id = Some(id),
+            Err(e) => execution.errors.push(format!("sell leg: {}", e)),
+        }
+
+        let (buy_fill, sell_fill) = tokio::join!(
+            self.settle_arbitrage_leg(
+                &divergence.buy_exchange,
+                execution.buy_order_id.as_deref(),
+                quantity
+            ),
+            self.settle_arbitrage_leg(
+                &divergence.sell_exchange,
+                execution.sell_order_id.as_deref(),
+                quantity
+            )
+        );
+        let mut confirmed = true;
+        for (leg, fill) in [("buy", &buy_fill), ("sell", &sell_fill)] {
+            if let Err(e) = fill {
+                confirmed = false;
+                execution.errors.push(format!("{} leg fill: {}", leg, e));
+            }
+        }
+        let (buy_filled, buy_price) = buy_fill.unwrap_or((0.0, None));
+        let (sell_filled, sell_price) = sell_fill.unwrap_or((0.0, None));
+        execution.buy_filled = buy_filled;
+        execution.sell_filled = sell_filled;
+        if let Some(order_id) = &execution.buy_order_id {
+            self.record_arbitrage_fill(
+                &divergence.symbol,
+                &divergence.buy_exchange,
+                OrderSide::Buy,
+                buy_filled,
+                buy_price.unwrap_or(divergence.buy_price),
+                order_id,
+            )
+            .await;
+        }
+        if let Some(order_id) = &execution.sell_order_id {
+            self.record_arbitrage_fill(
+                &divergence.symbol,
+                &divergence.sell_exchange,
+                OrderSide::Sell,
+                sell_filled,
+                sell_price.unwrap_or(divergence.sell_price),
+                order_id,
+            )
+            .await;
+        }
+
+        let excess = buy_filled - sell_filled;
+        if !confirmed {
+            // Reversing a guessed excess could open the exposure it meant to close
+            error!(
+                "Arbitrage on {} has unconfirmed fills, exposure may remain",
+                execution.symbol
+            );
+            execution.outcome = ArbitrageOutcome::UnwindFailed;
+        } else if excess.abs() >= MIN_ORDER_QUANTITY {
+            // Reverse the excess on the exchange where it filled
+            let (exchange, symbol, side, reference, limit) = if excess > 0.0 {
+                (
+                    &divergence.buy_exchange,
+                    buy_symbol,
+                    OrderSide::Sell,
+                    divergence.buy_price,
+                    divergence.buy_price * (1.0 - slippage),
+                )
+            } else {
+                (
+                    &divergence.sell_exchange,
+                    sell_symbol,
+                    OrderSide::Buy,
+                    divergence.sell_price,
+                    divergence.sell_price * (1.0 + slippage),
+                )
+            };
+            let excess = excess.abs();
+            let placed = match order(side.clone(), symbol, limit, excess) {
+                Ok(order) => self.place_order(exchange, order).await,
+                Err(e) => Err(e),
+            };
+            let unwound = match placed {
+                Ok(unwind_id) => {
+                    let fill = self
+                        .settle_arbitrage_leg(exchange, Some(&unwind_id), excess)
+                        .await;
+                    execution.unwind_order_id = Some(unwind_id.clone());
+                    fill.map(|(filled, price)| (unwind_id, filled, price))
+                }
+                Err(e) => Err(e),
+            };
+            match unwound {
+                Ok((unwind_id, filled, price)) => {
+                    execution.unwind_filled = filled;
+                    self.record_arbitrage_fill(
+                        &divergence.symbol,
+                        exchange,
+                        side,
+                        filled,
+                        price.unwrap_or(reference),
+                        &unwind_id,
+                    )
+                    .await;
+                    if excess - filled >= MIN_ORDER_QUANTITY {
+                        error!(
+                            "Arbitrage on {} is one-sided, unwind {} filled {} of {}",
+                            execution.symbol, unwind_id, filled, excess
+                        );
+                        execution
+                            .errors
+                            .push(format!("unwind filled {} of {}", filled, excess));
+                        execution.outcome = ArbitrageOutcome::UnwindFailed;
+                    } else {
+                        warn!(
+                            "Arbitrage on {} was one-sided, unwound {} with order {}",
+                            execution.symbol, excess, unwind_id
+                        );
+                        execution.outcome = ArbitrageOutcome::Unwound;
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Arbitrage on {} is one-sided and could not be unwound: {}",
+                        execution.symbol, e
+                    );
+                    execution.errors.push(format!("unwind: {}", e));
+                    execution.outcome = ArbitrageOutcome::UnwindFailed;
+                }
+            }
+        } else if buy_filled.max(sell_filled) < MIN_ORDER_QUANTITY {
+            execution.outcome = ArbitrageOutcome::Failed;
+        }
+
+        let accepted_legs = [&execution.buy_order_id, &execution.sell_order_id]
+            .iter()
+            .filter(|id| id.is_some())
+            .count();
+        {
+            let mut trade_history = self.trade_history.lock().await;
+            for _ in 0..accepted_legs {
+                trade_history.push((SystemTime::now(), execution.symbol.clone()));
+            }
+        }
+        {
+            let mut executions = self.arbitrage_executions.lock().await;
+            executions.push(execution.clone());
+            let excess = executions.len().saturating_sub(ARBITRAGE_HISTORY_SIZE);
+            executions.drain(..excess);
+        }
+        Ok(execution)
+    }
+
+    /// Wait for an arbitrage leg to fill, cancelling it if it is still open
+    /// after the fill window
+    ///
+    /// Returns the filled quantity and average fill price. A leg that was
+    /// never accepted filled nothing.
+    async fn settle_arbitrage_leg(
+        &self,
+        exchange: &Exchange,
+        order_id: Option<&str>,
+        quantity: f64,
+    ) -> Result<(f64, Option<f64>), MpcError> {
+        let Some(order_id) = order_id else {
+            return Ok((0.0, None));
+        };
+        let settled = |fill: &OrderFill| {
+            let done = matches!(
+                fill.status,
+                OrderStatus::Filled
+                    | OrderStatus::Cancelled
+                    | OrderStatus::Rejected
+                    | OrderStatus::Expired
+            );
+            let filled = match (fill.filled_quantity, &fill.status) {
+                (Some(filled), _) => filled.min(quantity),
+                (None, OrderStatus::Filled) => quantity,
+                (None, _) => 0.0,
+            };
+            done.then_some((filled, fill.average_price))
+        };
+
+        for poll in 0..ARBITRAGE_FILL_POLLS {
+            if poll > 0 {
+                tokio::time::sleep(ARBITRAGE_FILL_POLL_INTERVAL).await;
+            }
+            let fill = self.get_order_fill(exchange, order_id).await?;
+            if let Some(settled) = settled(&fill) {
+                return Ok(settled);
+            }
+        }
+
+        if let Err(e) = self.cancel_order(exchange, order_id).await {
+            warn!("Failed to cancel arbitrage order {}: {}", order_id, e);
+        }
+        let fill = self.get_order_fill(exchange, order_id).await?;
+        settled(&fill).ok_or_else(|| {
+            MpcError::OrderPlacementFailed(format!(
+                "Order {} is still {} after cancelling",
+                order_id, fill.status
+            ))
+        })
+    }
+
+    /// Persist an arbitrage fill as a trade and enter it in the PnL ledger
+    ///
+    /// No-op without a trade repository or for an empty fill.
+    pub(super) async fn record_arbitrage_fill(
+        &self,
+        symbol: &str,
+        exchange: &Exchange,
+        side: OrderSide,
+        quantity: f64,
+        price: f64,
+        order_id: &str,
+    ) {
+        if self.trade_repository.is_none() || quantity <= 0.0 {
+            return;
+        }
+
+        let exchange = exchange.name().to_string();
+        let (fee, fee_estimated) = self
+            .fill_fee(&exchange, Some(order_id), price * quantity)
+            .await;
+        let trade = CreateTrade {
+            id: format!("trade_arb_{}_{}", exchange, order_id),
+            position_id: None,
+            symbol: symbol.to_string(),
+            fee,
+            fee_estimated,
+            exchange,
+            side: side.to_string().to_lowercase(),
+            price,
+            quantity,
+            exchange_order_id: Some(order_id.to_string()),
+            strategy: ARBITRAGE_STRATEGY.to_string(),
+            signal_confidence: None,
+            exit_reason: None,
+            signal_details: None,
+        };
+        self.record_strategy_fill(trade).await;
+    }
+
+    /// Latest divergence of every compared symbol and recent arbitrage attempts
+    pub async fn get_arbitrage_state(&self) -> (Vec<PriceDivergence>, Vec<ArbitrageExecution>) {
+        let executions = {
+            let executions = self.arbitrage_executions.lock().await;
+            executions.clone()
+        };
+        let divergences = {
+            let divergences = self.price_divergences.lock().await;
+            divergences.values().cloned().collect()
+        };
+        (divergences, executions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{memory_repositories, venue_feed};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_arbitrage_trades_routable_venues_and_unwinds_partial_fill() {
+        let mut config = TradingConfig::default();
+        config.arbitrage_enabled = true;
+        config.arbitrage_execute = true;
+        config.arbitrage_symbols = vec!["BTC-USD".to_string()];
+        let mut service = MpcService::new(config);
+        let (_, trades) = memory_repositories(&mut service).await;
+
+        let binance_orders = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let dydx_orders = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let coinbase_orders = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let feed = |symbol: &str, price: f64| {
+            Arc::new(std::sync::Mutex::new(HashMap::from([(
+                symbol.to_string(),
+                price,
+            )])))
+        };
+        service.senders = Arc::new(HashMap::from([
+            // Cheapest, but orders cannot be routed there
+            (
+                Exchange::Binance,
+                venue_feed(
+                    feed("BTCUSDT", 49000.0),
+                    Some(binance_orders.clone()),
+                    Vec::new(),
+                ),
+            ),
+            (
+                Exchange::Dydx,
+                venue_feed(
+                    feed("BTC-USD", 50000.0),
+                    Some(dydx_orders.clone()),
+                    Vec::new(),
+                ),
+            ),
+            // Coinbase quotes 60 bps richer than dYdX but fills a quarter of the sell
+            (
+                Exchange::Coinbase,
+                venue_feed(
+                    feed("BTC-USD", 50300.0),
+                    Some(coinbase_orders.clone()),
+                    vec![0.25],
+                ),
+            ),
+        ]));
+
+        let results = service.scan_arbitrage().await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].as_ref().unwrap().starts_with("Unwound"));
+
+        let (divergences, executions) = service.get_arbitrage_state().await;
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].buy_exchange, Exchange::Binance);
+        assert_eq!(executions.len(), 1);
+        let execution = &executions[0];
+        assert_eq!(execution.buy_exchange, Exchange::Dydx);
+        assert_eq!(execution.sell_exchange, Exchange::Coinbase);
+        assert!((execution.net_spread_bps - 40.0).abs() < 1e-9);
+        assert_eq!(execution.outcome, ArbitrageOutcome::Unwound);
+        assert!((execution.buy_filled - 0.002).abs() < 1e-12);
+        assert!((execution.sell_filled - 0.0005).abs() < 1e-12);
+        assert!((execution.unwind_filled - 0.0015).abs() < 1e-12);
+        assert!(binance_orders.lock().unwrap().is_empty());
+        {
+            // Only the unmatched part of the dYdX buy is sold back there
+            let orders = dydx_orders.lock().unwrap();
+            assert_eq!(orders.len(), 2);
+            assert!(matches!(orders[0].side, OrderSide::Buy));
+            assert!(matches!(orders[1].side, OrderSide::Sell));
+            assert!((orders[1].quantity.value() - 0.0015).abs() < 1e-12);
+        }
+
+        // Every fill is a trade of the arbitrage strategy and in the ledger
+        let recorded = trades.get_recent(10).await.unwrap();
+        assert_eq!(recorded.len(), 3);
+        assert!(recorded.iter().all(|t| t.strategy == ARBITRAGE_STRATEGY));
+        let report = service.get_realized_pnl(&[PnlGroup::Strategy]).await;
+        assert_eq!(report.groups.len(), 1);
+        assert_eq!(report.groups[0].group["strategy"], ARBITRAGE_STRATEGY);
+        assert_eq!(report.open_lots.len(), 2);
+
+        // Still diverging, but the symbol was just traded
+        let results = service.scan_arbitrage().await;
+        assert!(results[0].as_ref().unwrap().contains("cooling down"));
+        assert_eq!(dydx_orders.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,190 @@
+//! Dynamic symbol universe of the MPC service
+//!
+//! Screens the markets listed by the dynamic universe exchanges and
+//! subscribes to the best of them.
+
+use super::*;
+
+impl MpcService {
+    /// Screen every market of the dynamic universe exchanges and adjust
+    /// their subscriptions
+    ///
+    /// Markets are scored from their 24h tickers, on daily scales. The top
+    /// best and good candidates are subscribed; a symbol that stayed out of
+    /// the top for the hysteresis period is unsubscribed unless a position is
+    /// open on it.
+    pub async fn run_dynamic_universe(&self) -> Vec<Result<String, MpcError>> {
+        if !self.config.dynamic_universe_enabled {
+            return Vec::new();
+        }
+        let Some(discovery) = &self.market_discovery else {
+            return Vec::new();
+        };
+        let aggregator = ScalpingPotentialAggregator::for_daily_tickers(&self.config);
+        let mut results = Vec::new();
+
+        for exchange in &self.config.dynamic_universe_exchanges {
+            let exchange_name = Self::get_exchange_name(exchange);
+            let markets = match discovery.list_markets(exchange).await {
+                Ok(markets) => markets,
+                Err(e) => {
+                    results.push(Err(MpcError::AggregationFailed(format!(
+                        "Market discovery on {} failed: {}",
+                        exchange_name, e
+                    ))));
+                    continue;
+                }
+            };
+            let market_data: HashMap<String, SymbolMarketData> = markets
+                .iter()
+                .filter_map(|market| Some((market.symbol.clone(), market.market_data()?)))
+                .collect();
+            let benchmark = SymbolMarketData::benchmark_candles(&market_data);
+            let scored = market_data
+                .iter()
+                .map(|(symbol, data)| {
+                    aggregator.score(
+                        symbol.clone(),
+                        exchange.name().to_string(),
+                        &data.input(benchmark),
+                    )
+                })
+                .collect();
+            let ranked = ScalpingPotentialAggregator::rank_results(scored);
+
+            let held: std::collections::HashSet<String> = {
+                let positions = self.open_positions.lock().await;
+                positions
+                    .values()
+                    .map(|p| TradingConfig::normalize_symbol(&p.symbol))
+                    .collect()
+            };
+            let change = {
+                let mut universes = self.symbol_universes.lock().await;
+                universes
+                    .entry(exchange.clone())
+                    .or_insert_with(|| {
+                        let pinned = self.config.symbols.get(exchange);
+                        SymbolUniverse::new(
+                            self.config.dynamic_universe.clone(),
+                            pinned.map(Vec::as_slice).unwrap_or_default(),
+                        )
+                    })
+                    .update(&ranked, &held, chrono::Utc::now())
+            };
+
+            for symbol in change.subscribe {
+                match self.subscribe(exchange, &symbol).await {
+                    Ok(()) => {
+                        results.push(Ok(format!("Subscribed {} on {}", symbol, exchange_name)))
+                    }
+                    Err(e) => {
+                        let mut universes = self.symbol_universes.lock().await;
+                        if let Some(universe) = universes.get_mut(exchange) {
+                            universe.forget(&symbol);
+                        }
+                        results.push(Err(e));
+                    }
+                }
+            }
+            for symbol in change.unsubscribe {
+                match self.unsubscribe(exchange, &symbol).await {
+                    Ok(()) => {
+                        results.push(Ok(format!("Unsubscribed {} on {}", symbol, exchange_name)))
+                    }
+                    Err(e) => results.push(Err(e)),
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Symbols added to each exchange by the dynamic universe
+    pub async fn get_symbol_universes(&self) -> HashMap<Exchange, Vec<UniverseMember>> {
+        let universes = self.symbol_universes.lock().await;
+        universes
+            .iter()
+            .map(|(exchange, universe)| (exchange.clone(), universe.members()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{snapshot, StaticDiscovery};
+    use super::*;
+    use crate::domain::services::symbol_universe::UniverseParams;
+
+    #[tokio::test]
+    async fn test_dynamic_universe_follows_screening_and_keeps_held_symbols() {
+        let mut config = TradingConfig::default();
+        config.dynamic_universe_enabled = true;
+        config.dynamic_universe_exchanges = vec![Exchange::Coinbase];
+        config.dynamic_universe = UniverseParams {
+            top_n: 2,
+            hysteresis: chrono::Duration::zero(),
+        };
+        config.symbols = HashMap::from([(Exchange::Coinbase, vec!["BTC-USD".to_string()])]);
+        let mut service = MpcService::new(config);
+
+        let markets = Arc::new(std::sync::Mutex::new(vec![
+            snapshot("BTC-USD", 52000.0, 50000.0, 2_000_000_000.0),
+            snapshot("SOL-USD", 110.0, 100.0, 500_000_000.0),
+            snapshot("AVAX-USD", 42.0, 40.0, 300_000_000.0),
+            snapshot("LINK-USD", 15.3, 15.0, 200_000_000.0),
+            snapshot("DUST-USD", 0.01, 0.01, 1_000.0),
+        ]));
+        service.set_market_discovery(Arc::new(StaticDiscovery(markets.clone())));
+
+        let subscribed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (tx, mut rx) = mpsc::channel(16);
+        let feed = subscribed.clone();
+        tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                match message {
+                    ExchangeMessage::Subscribe { symbol, reply } => {
+                        feed.lock().unwrap().push(symbol);
+                        let _ = reply.send(Ok(())).await;
+                    }
+                    ExchangeMessage::Unsubscribe { symbol, reply } => {
+                        feed.lock().unwrap().retain(|s| *s != symbol);
+                        let _ = reply.send(Ok(())).await;
+                    }
+                    _ => {}
+                }
+            }
+        });
+        service.senders = Arc::new(HashMap::from([(Exchange::Coinbase, tx)]));
+
+        // BTC-USD is already configured, so the two next candidates are added
+        let results = service.run_dynamic_universe().await;
+        assert!(results.iter().all(|r| r.is_ok()), "{:?}", results);
+        assert_eq!(*subscribed.lock().unwrap(), vec!["AVAX-USD", "SOL-USD"]);
+
+        // Both fall out of the screening; only the one without a position drops
+        {
+            let mut positions = service.open_positions.lock().await;
+            positions.insert(
+                "pos-sol".to_string(),
+                Position::new(
+                    "pos-sol".to_string(),
+                    "SOL-USD".to_string(),
+                    PositionSide::Long,
+                    Quantity::new(1.0).unwrap(),
+                    Price::new(110.0).unwrap(),
+                ),
+            );
+        }
+        markets.lock().unwrap().retain(|m| m.symbol == "DUST-USD");
+        service.run_dynamic_universe().await;
+        assert_eq!(*subscribed.lock().unwrap(), vec!["SOL-USD"]);
+
+        let universes = service.get_symbol_universes().await;
+        let members: Vec<&str> = universes[&Exchange::Coinbase]
+            .iter()
+            .map(|m| m.symbol.as_str())
+            .collect();
+        assert_eq!(members, vec!["SOL-USD"]);
+    }
+}
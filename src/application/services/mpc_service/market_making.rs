@@ -0,0 +1,709 @@
+//! Market making loop of the MPC service
+//!
+//! Keeps two-sided quotes resting on the active trader's exchange, requotes
+//! them as the price and inventory move, and records their fills.
+
+use super::*;
+
+impl MpcService {
+    /// Quote every market making symbol on the active trader's exchange
+    ///
+    /// Quotes are cancelled rather than refreshed while the exchange's market
+    /// data feed is down or a symbol has no price, so nothing rests on the
+    /// book priced from stale data. They are also pulled while the trade-rate
+    /// limits are reached, and a symbol's quotes while reconciliation pauses
+    /// one of its currencies, like the other strategies' orders.
+    pub async fn run_market_making(&self) -> Vec<Result<String, MpcError>> {
+        if !self.config.market_making_enabled {
+            return Vec::new();
+        }
+        if !self.config.enable_automated_trading {
+            return vec![Err(MpcError::InvalidConfiguration(
+                "Automated trading is disabled".to_string(),
+            ))];
+        }
+        if let Err(e) = self.check_trading_limits().await {
+            let cancelled = self.cancel_quotes(None).await;
+            warn!("{}, cancelled {} quotes", e, cancelled);
+            return vec![Err(e)];
+        }
+        let Some((trader_id, trader_sender)) = self.select_trader_sender().await else {
+            return vec![Err(MpcError::OrderPlacementFailed(
+                "No trader available for market making".to_string(),
+            ))];
+        };
+        let exchange = match self.trader_active_exchange(&trader_sender).await {
+            Some(exchange @ (Exchange::Dydx | Exchange::Coinbase)) => exchange,
+            other => {
+                return vec![Err(MpcError::InvalidConfiguration(format!(
+                    "Market making needs a trader on dYdX or Coinbase, {} is on {:?}",
+                    trader_id, other
+                )))];
+            }
+        };
+
+        if !self.market_data_connected(&exchange).await {
+            let cancelled = self.cancel_quotes(None).await;
+            return vec![Ok(format!(
+                "{} disconnected, cancelled {} quotes",
+                Self::get_exchange_name(&exchange),
+                cancelled
+            ))];
+        }
+
+        let exchange_name = format!("{:?}", exchange).to_lowercase();
+        let mut results = Vec::new();
+        for symbol in &self.config.market_making_symbols {
+            if let Err(e) = self.check_reconciliation_pause(symbol).await {
+                let cancelled = self.cancel_quotes(Some(symbol)).await;
+                warn!("{}, cancelled {} {} quotes", e, cancelled, symbol);
+                results.push(Err(e));
+                continue;
+            }
+            match self.get_aggregated_price(symbol).await {
+                Ok(price) => results.extend(
+                    self.make_market(symbol, price, &exchange_name, &trader_sender)
+                        .await,
+                ),
+                Err(e) => {
+                    let cancelled = self.cancel_quotes(Some(symbol)).await;
+                    warn!(
+                        "No price for {}, cancelled {} quotes: {}",
+                        symbol, cancelled, e
+                    );
+                    results.push(Err(e));
+                }
+            }
+        }
+        results
+    }
+
+    /// Whether the market data feed of `exchange` is up
+    ///
+    /// An exchange without a feed of its own is quoted from the other feeds.
+    async fn market_data_connected(&self, exchange: &Exchange) -> bool {
+        match self.check_actor_health(exchange).await {
+            Ok(healthy) => healthy,
+            Err(MpcError::ActorNotFound(_)) => true,
+            Err(e) => {
+                warn!("Market data health check failed for {:?}: {}", exchange, e);
+                false
+            }
+        }
+    }
+
+    /// Record fills of a symbol's quotes, then requote it around `reference`
+    ///
+    /// Resting quotes are kept until the reference moves past the refresh
+    /// threshold or a fill changes the inventory they were sized for.
+    /// Partial fills are recorded as they happen and once more after a
+    /// cancel, so quantity filled before the cancel took effect is kept.
+    async fn make_market(
+        &self,
+        symbol: &str,
+        reference: Price,
+        exchange: &str,
+        trader_sender: &mpsc::Sender<TraderMessage>,
+    ) -> Vec<Result<String, MpcError>> {
+        let mut results = Vec::new();
+        let mut maker = MarketMaker::new(self.config.market_making.clone());
+        if exchange == "coinbase" {
+            // Spot: only inventory actually held can be offered
+            maker = maker.without_short_selling();
+        }
+        let mut working = {
+            let quotes = self.market_quotes.lock().await;
+            quotes.get(symbol).cloned()
+        };
+        let mut filled = false;
+
+        if let Some(quotes) = working.as_mut() {
+            for slot in [&mut quotes.bid, &mut quotes.ask] {
+                let Some(quote) = slot.as_mut() else {
+                    continue;
+                };
+                let order_id = quote.order_id.clone();
+                match self
+                    .ask_trader(trader_sender, |reply| TraderMessage::GetOrderFill {
+                        order_id,
+                        reply,
+                    })
+                    .await
+                {
+                    Ok(fill) => {
+                        if let Some(result) =
+                            self.record_quote_fill(symbol, exchange, quote, &fill).await
+                        {
+                            filled = true;
+                            results.push(result);
+                        }
+                        match fill.status {
+                            OrderStatus::Filled
+                            | OrderStatus::Cancelled
+                            | OrderStatus::Rejected
+                            | OrderStatus::Expired => {
+                                debug!(
+                                    "{} {} quote {} left the book ({})",
+                                    symbol,
+                                    quote.side.as_str(),
+                                    quote.order_id,
+                                    fill.status
+                                );
+                                *slot = None;
+                            }
+                            _ => {}
+                        }
+                    }
+                    Err(e) => debug!(
+                        "Could not poll {} {} quote {}: {}",
+                        symbol,
+                        quote.side.as_str(),
+                        quote.order_id,
+                        e
+                    ),
+                }
+            }
+        }
+
+        let volatility = {
+            let candles = self.get_candles(symbol).await;
+            let window = maker.params().volatility_window;
+            realized_volatility(&candles[candles.len().saturating_sub(window + 1)..])
+        };
+        let mut inventory = {
+            let portfolio = self.portfolio_manager.lock().await;
+            portfolio.get_inventory(symbol)
+        };
+        let mut target = maker.quote(reference.value(), volatility, inventory);
+
+        let mut filled_on_cancel = false;
+        if let Some(quotes) = working.as_mut() {
+            let fresh = !filled
+                && !maker.needs_refresh(quotes.reference, target.reference)
+                && quotes.bid.is_some() == target.bid.is_some()
+                && quotes.ask.is_some() == target.ask.is_some();
+            if !fresh {
+                for slot in [&mut quotes.bid, &mut quotes.ask] {
+                    let Some(quote) = slot.as_mut() else {
+                        continue;
+                    };
+                    match self
+                        .cancel_quote(symbol, exchange, quote, trader_sender)
+                        .await
+                    {
+                        Ok(fill) => {
+                            if let Some(result) = fill {
+                                filled_on_cancel = true;
+                                results.push(result);
+                            }
+                            *slot = None;
+                        }
+                        Err(e) => warn!(
+                            "Failed to cancel {} quote {}: {}",
+                            symbol, quote.order_id, e
+                        ),
+                    }
+                }
+            }
+
+            if fresh || quotes.working().next().is_some() {
+                // Never stack new quotes on top of ones still resting
+                let mut stored = self.market_quotes.lock().await;
+                stored.insert(symbol.to_string(), quotes.clone());
+                if !fresh {
+                    results.push(Err(MpcError::OrderPlacementFailed(format!(
+                        "Stale {} quotes could not be cancelled",
+                        symbol
+                    ))));
+                }
+                return results;
+            }
+        }
+        if filled_on_cancel {
+            inventory = {
+                let portfolio = self.portfolio_manager.lock().await;
+                portfolio.get_inventory(symbol)
+            };
+            target = maker.quote(reference.value(), volatility, inventory);
+        }
+
+        let mut placed = SymbolQuotes {
+            symbol: symbol.to_string(),
+            exchange: exchange.to_string(),
+            reference: target.reference,
+            spread_bps: target.spread_bps,
+            bid: None,
+            ask: None,
+            updated_at: chrono::Utc::now(),
+        };
+        for quote in target.quotes() {
+            if quote.quantity < MIN_ORDER_QUANTITY {
+                debug!(
+                    "Skipping {} {} quote below minimum quantity: {}",
+                    symbol,
+                    quote.side.as_str(),
+                    quote.quantity
+                );
+                continue;
+            }
+            let order = match Order::new(
+                format!(
+                    "mm_{}_{}_{}",
+                    symbol,
+                    quote.side.as_str(),
+                    chrono::Utc::now().timestamp_millis()
+                ),
+                symbol.to_string(),
+                quote.side.order_side(),
+                OrderType::Limit,
+                Some(quote.price),
+                quote.quantity,
+            )
+            .and_then(Order::post_only)
+            {
+                Ok(order) => order,
+                Err(e) => {
+                    results.push(Err(MpcError::InvalidInput(e)));
+                    continue;
+                }
+            };
+
+            match self
+                .ask_trader(trader_sender, |reply| TraderMessage::PlaceOrder {
+                    order,
+                    reply,
+                })
+                .await
+            {
+                Ok(order_id) => {
+                    let working = Some(WorkingQuote {
+                        order_id,
+                        side: quote.side,
+                        price: quote.price,
+                        quantity: quote.quantity,
+                        filled: 0.0,
+                    });
+                    match quote.side {
+                        QuoteSide::Bid => placed.bid = working,
+                        QuoteSide::Ask => placed.ask = working,
+                    }
+                }
+                Err(e) => results.push(Err(e)),
+            }
+        }
+
+        results.push(Ok(format!(
+            "Quoted {} {} / {} around {:.2} ({:.1} bps, inventory {})",
+            symbol,
+            placed
+                .bid
+                .as_ref()
+                .map_or("-".to_string(), |q| format!("{:.2}", q.price)),
+            placed
+                .ask
+                .as_ref()
+                .map_or("-".to_string(), |q| format!("{:.2}", q.price)),
+            target.reference,
+            target.spread_bps,
+            inventory
+        )));
+        let mut stored = self.market_quotes.lock().await;
+        stored.insert(symbol.to_string(), placed);
+        results
+    }
+
+    /// Cancel resting market making quotes, of one symbol or all of them
+    ///
+    /// Returns how many quotes were cancelled. Quotes whose cancel fails stay
+    /// tracked so a later pass can retry or pick up their fill.
+    pub async fn cancel_quotes(&self, symbol: Option<&str>) -> usize {
+        let targets: Vec<SymbolQuotes> = {
+            let quotes = self.market_quotes.lock().await;
+            quotes
+                .values()
+                .filter(|q| symbol.is_none() || symbol == Some(q.symbol.as_str()))
+                .cloned()
+                .collect()
+        };
+
+        let mut cancelled = 0;
+        for mut quotes in targets {
+            let Some(trader_sender) = self.trader_for_exchange(&quotes.exchange).await else {
+                warn!(
+                    "No trader on {} to cancel {} quotes",
+                    quotes.exchange, quotes.symbol
+                );
+                continue;
+            };
+            for slot in [&mut quotes.bid, &mut quotes.ask] {
+                let Some(quote) = slot.as_mut() else {
+                    continue;
+                };
+                match self
+                    .cancel_quote(&quotes.symbol, &quotes.exchange, quote, &trader_sender)
+                    .await
+                {
+                    Ok(fill) => {
+                        if let Some(result) = fill {
+                            match result {
+                                Ok(message) => info!("{}", message),
+                                Err(e) => warn!("{}", e),
+                            }
+                        }
+                        cancelled += 1;
+                        *slot = None;
+                    }
+                    Err(e) => warn!(
+                        "Failed to cancel {} quote {}: {}",
+                        quotes.symbol, quote.order_id, e
+                    ),
+                }
+            }
+
+            let mut stored = self.market_quotes.lock().await;
+            if quotes.working().next().is_some() {
+                stored.insert(quotes.symbol.clone(), quotes);
+            } else {
+                stored.remove(&quotes.symbol);
+            }
+        }
+
+        if cancelled > 0 {
+            info!("Cancelled {} market making quotes", cancelled);
+        }
+        cancelled
+    }
+
+    /// Cancel a resting quote, then record whatever filled before the cancel
+    ///
+    /// Returns the fill report when part of the quote filled since it was
+    /// last polled.
+    async fn cancel_quote(
+        &self,
+        symbol: &str,
+        exchange: &str,
+        quote: &mut WorkingQuote,
+        trader_sender: &mpsc::Sender<TraderMessage>,
+    ) -> Result<Option<Result<String, MpcError>>, MpcError> {
+        let order_id = quote.order_id.clone();
+        self.ask_trader(trader_sender, |reply| TraderMessage::CancelOrder {
+            order_id,
+            reply,
+        })
+        .await?;
+
+        let order_id = quote.order_id.clone();
+        match self
+            .ask_trader(trader_sender, |reply| TraderMessage::GetOrderFill {
+                order_id,
+                reply,
+            })
+            .await
+        {
+            Ok(fill) => Ok(self.record_quote_fill(symbol, exchange, quote, &fill).await),
+            Err(e) => {
+                warn!(
+                    "Could not poll cancelled {} quote {}: {}",
+                    symbol, quote.order_id, e
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Record the part of a quote that filled since it was last polled
+    ///
+    /// Post-only quotes fill at their limit price. A `Filled` order without
+    /// a reported quantity counts as fully filled. Returns None when nothing
+    /// new filled.
+    async fn record_quote_fill(
+        &self,
+        symbol: &str,
+        exchange: &str,
+        quote: &mut WorkingQuote,
+        fill: &OrderFill,
+    ) -> Option<Result<String, MpcError>> {
+        if fill.status == OrderStatus::Filled {
+            self.mark_order_filled(&quote.order_id).await;
+        }
+        let total = match (fill.status.clone(), fill.filled_quantity) {
+            (_, Some(quantity)) => quantity.min(quote.quantity),
+            (OrderStatus::Filled, None) => quote.quantity,
+            (_, None) => quote.filled,
+        };
+        let delta = total - quote.filled;
+        if delta <= f64::EPSILON {
+            return None;
+        }
+
+        let price = quote.price;
+        let inventory = {
+            let mut portfolio = self.portfolio_manager.lock().await;
+            portfolio
+                .record_fill(symbol, quote.side.signed(delta), price)
+                .map(|_| portfolio.get_inventory(symbol))
+        };
+        quote.filled = total;
+        if inventory.is_ok() {
+            // The reported fee covers every fill of the quote so far
+            let (fee, fee_estimated) = match fill.fee {
+                Some(fee) => (fee * delta / total, false),
+                None => (self.estimated_fee(exchange, delta * price), true),
+            };
+            self.record_strategy_fill(CreateTrade {
+                // Each poll records the quantity filled since the last one
+                id: format!("trade_mm_{}_{}_{}", exchange, quote.order_id, total),
+                position_id: None,
+                symbol: symbol.to_string(),
+                exchange: exchange.to_string(),
+                side: quote.side.order_side().to_string().to_lowercase(),
+                price,
+                quantity: delta,
+                fee,
+                fee_estimated,
+                exchange_order_id: Some(quote.order_id.clone()),
+                strategy: MARKET_MAKING_STRATEGY.to_string(),
+                signal_confidence: None,
+                exit_reason: None,
+                signal_details: None,
+            })
+            .await;
+        }
+        Some(
+            inventory
+                .map(|inventory| {
+                    format!(
+                        "{} {} filled: {} of {} @ {:.2}, inventory {}",
+                        symbol,
+                        quote.side.as_str(),
+                        delta,
+                        quote.quantity,
+                        price,
+                        inventory
+                    )
+                })
+                .map_err(MpcError::InvalidInput),
+        )
+    }
+
+    /// Resting market making quotes and the inventory they built up
+    pub async fn get_market_making_state(&self) -> (Vec<SymbolQuotes>, Vec<Inventory>) {
+        let quotes = {
+            let quotes = self.market_quotes.lock().await;
+            quotes.values().cloned().collect()
+        };
+        let inventory = {
+            let portfolio = self.portfolio_manager.lock().await;
+            portfolio.get_all_inventory()
+        };
+        (quotes, inventory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{add_mock_trader, memory_repositories, MockExchange};
+    use super::*;
+    use crate::domain::services::market_making::MarketMakingParams;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[tokio::test]
+    async fn test_market_making_quotes_skew_with_inventory_and_cancel() {
+        let mut config = TradingConfig::default();
+        config.market_making_enabled = true;
+        config.market_making_symbols = vec!["BTC-USD".to_string()];
+        config.market_making = MarketMakingParams {
+            order_size: 0.01,
+            max_inventory: 0.015,
+            ..Default::default()
+        };
+        let service = MpcService::new(config);
+
+        let placed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let cancelled = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let fill_bids = Arc::new(AtomicBool::new(false));
+        let sender = add_mock_trader(
+            &service,
+            Exchange::Dydx,
+            MockExchange {
+                placed: placed.clone(),
+                cancelled: cancelled.clone(),
+                fill_bids: fill_bids.clone(),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        // No feed for dYdX: quoted from the aggregated price, which is missing here
+        let results = service.run_market_making().await;
+        assert!(matches!(
+            results[0],
+            Err(MpcError::NoPricesAvailable { .. })
+        ));
+
+        let price = |value: f64| Price::new(value).unwrap();
+        service
+            .make_market("BTC-USD", price(50000.0), "dydx", &sender)
+            .await;
+        {
+            let placed = placed.lock().unwrap();
+            assert_eq!(placed.len(), 2);
+            assert!(placed.iter().all(|o| o.post_only));
+            assert_eq!(placed[0].price.unwrap().value(), 49975.0);
+            assert_eq!(placed[1].price.unwrap().value(), 50025.0);
+        }
+
+        // A move below the refresh threshold leaves the quotes alone
+        service
+            .make_market("BTC-USD", price(50010.0), "dydx", &sender)
+            .await;
+        assert_eq!(placed.lock().unwrap().len(), 2);
+        assert!(cancelled.lock().unwrap().is_empty());
+
+        // The bid fills: inventory goes long, the ask is replaced and both skew down
+        fill_bids.store(true, Ordering::SeqCst);
+        let results = service
+            .make_market("BTC-USD", price(50000.0), "dydx", &sender)
+            .await;
+        assert!(results[0].as_ref().unwrap().contains("bid filled"));
+        assert_eq!(*cancelled.lock().unwrap(), vec!["ex_2".to_string()]);
+        {
+            let placed = placed.lock().unwrap();
+            assert_eq!(placed.len(), 4);
+            let (bid, ask) = (&placed[2], &placed[3]);
+            // Only 0.005 of room left under the 0.015 cap
+            assert!((bid.quantity.value() - 0.005).abs() < 1e-12);
+            assert!(bid.price.unwrap().value() < 49975.0);
+            assert!(ask.price.unwrap().value() < 50025.0);
+        }
+        let (quotes, inventory) = service.get_market_making_state().await;
+        assert_eq!(quotes.len(), 1);
+        assert_eq!(inventory.len(), 1);
+        assert!((inventory[0].quantity - 0.01).abs() < 1e-12);
+
+        // Disconnect path: everything resting is pulled
+        fill_bids.store(false, Ordering::SeqCst);
+        assert_eq!(service.cancel_quotes(None).await, 2);
+        assert!(service.get_market_making_state().await.0.is_empty());
+
+        // A symbol paused by reconciliation has its quotes pulled
+        service
+            .make_market("BTC-USD", price(50000.0), "dydx", &sender)
+            .await;
+        service
+            .paused_currencies
+            .lock()
+            .await
+            .insert("BTC".to_string(), Exchange::Dydx);
+        let results = service.run_market_making().await;
+        assert!(matches!(results[0], Err(MpcError::InvalidConfiguration(_))));
+        assert!(service.get_market_making_state().await.0.is_empty());
+        assert_eq!(cancelled.lock().unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_market_making_respects_the_trading_gates() {
+        let mut config = TradingConfig::default();
+        config.market_making_enabled = true;
+        config.market_making_symbols = vec!["BTC-USD".to_string()];
+        config.enable_automated_trading = false;
+        let results = MpcService::new(config.clone()).run_market_making().await;
+        assert!(matches!(
+            results[..],
+            [Err(MpcError::InvalidConfiguration(_))]
+        ));
+
+        config.enable_automated_trading = true;
+        config.max_trades_per_hour = 0;
+        let results = MpcService::new(config).run_market_making().await;
+        assert!(matches!(
+            results[..],
+            [Err(MpcError::TradeLimitExceeded(_))]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_market_making_records_partial_fills_and_quotes_spot_asks_from_inventory() {
+        let mut config = TradingConfig::default();
+        config.market_making_enabled = true;
+        config.market_making_symbols = vec!["BTC-USD".to_string()];
+        config.market_making = MarketMakingParams {
+            order_size: 0.01,
+            max_inventory: 0.015,
+            ..Default::default()
+        };
+        let mut service = MpcService::new(config);
+        let (_, trades) = memory_repositories(&mut service).await;
+
+        let placed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let cancelled = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let partial_fills = Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let sender = add_mock_trader(
+            &service,
+            Exchange::Coinbase,
+            MockExchange {
+                placed: placed.clone(),
+                cancelled: cancelled.clone(),
+                partial_fills: partial_fills.clone(),
+                ..Default::default()
+            },
+        )
+        .await;
+        let price = Price::new(50000.0).unwrap();
+        let inventory = || async {
+            let (_, inventory) = service.get_market_making_state().await;
+            inventory.first().map_or(0.0, |i| i.quantity)
+        };
+
+        // Spot without inventory: nothing to offer, only the bid is quoted
+        service
+            .make_market("BTC-USD", price, "coinbase", &sender)
+            .await;
+        {
+            let placed = placed.lock().unwrap();
+            assert_eq!(placed.len(), 1);
+            assert!(matches!(placed[0].side, OrderSide::Buy));
+        }
+
+        // A partial bid fill is recorded once, although the bid is then cancelled
+        partial_fills
+            .lock()
+            .unwrap()
+            .insert("ex_1".to_string(), 0.004);
+        let results = service
+            .make_market("BTC-USD", price, "coinbase", &sender)
+            .await;
+        assert!(results[0]
+            .as_ref()
+            .unwrap()
+            .contains("bid filled: 0.004 of 0.01"));
+        assert_eq!(*cancelled.lock().unwrap(), vec!["ex_1".to_string()]);
+        assert!((inventory().await - 0.004).abs() < 1e-12);
+        {
+            // The requote offers only what is held
+            let placed = placed.lock().unwrap();
+            assert_eq!(placed.len(), 3);
+            assert!(matches!(placed[2].side, OrderSide::Sell));
+            assert!((placed[2].quantity.value() - 0.004).abs() < 1e-12);
+        }
+
+        // Quantity filled before a cancel landed still reaches the inventory
+        partial_fills
+            .lock()
+            .unwrap()
+            .insert("ex_3".to_string(), 0.001);
+        assert_eq!(service.cancel_quotes(None).await, 2);
+        assert!((inventory().await - 0.003).abs() < 1e-12);
+
+        // Every fill is a trade of the market making strategy and in the ledger
+        let recorded = trades.get_recent(10).await.unwrap();
+        assert_eq!(recorded.len(), 2);
+        assert!(recorded
+            .iter()
+            .all(|t| t.strategy == MARKET_MAKING_STRATEGY && t.fee_estimated));
+        let report = service.get_realized_pnl(&[PnlGroup::Strategy]).await;
+        assert_eq!(report.groups.len(), 1);
+        assert_eq!(report.groups[0].group["strategy"], MARKET_MAKING_STRATEGY);
+        assert_eq!(report.open_lots.len(), 1);
+    }
+}
@@ -0,0 +1,584 @@
+//! Pairs and multi-asset strategies of the MPC service
+//!
+//! Opens the legs of a position group together, unwinding the legs already
+//! sent when one fails, and closes them as a unit.
+
+use super::*;
+
+impl MpcService {
+    /// Evaluate every configured pair and open or close its legs as a unit
+    pub async fn run_pairs_trading(&self) -> Vec<Result<String, MpcError>> {
+        if !self.config.pairs_trading_enabled {
+            return Vec::new();
+        }
+        let mut results = Vec::new();
+        for (base, hedge) in &self.config.pairs_trading_pairs {
+            let strategy = PairsStrategy::new(
+                base.clone(),
+                hedge.clone(),
+                self.config.pairs_trading.clone(),
+            );
+            results.push(
+                self.run_multi_asset_strategy(&strategy, self.config.pairs_trading.notional)
+                    .await,
+            );
+        }
+        results
+    }
+
+    /// Act on a multi-asset strategy's signal for its group of positions
+    ///
+    /// A strategy holds at most one open group. It is opened on `Enter` while
+    /// flat and closed on `Exit` or `Stop`; other signals only report.
+    pub async fn run_multi_asset_strategy(
+        &self,
+        strategy: &dyn MultiAssetStrategy,
+        notional: f64,
+    ) -> Result<String, MpcError> {
+        let name = strategy.name();
+        let mut series = HashMap::new();
+        {
+            let builder = self.candle_builder.lock().await;
+            for symbol in strategy.symbols() {
+                let candles = builder.get_timed_candles(&symbol);
+                series.insert(symbol, candles);
+            }
+        }
+        let Some(signal) = strategy.generate_signal(&series) else {
+            return Ok(format!("{}: not enough candles", name));
+        };
+
+        let open_group = {
+            let groups = self.position_groups.lock().await;
+            groups
+                .values()
+                .find(|group| group.strategy == name)
+                .map(|group| group.id.clone())
+        };
+        match (signal.action, open_group) {
+            (GroupAction::Enter, None) => self.open_position_group(&name, &signal, notional).await,
+            (GroupAction::Exit, Some(group_id)) => {
+                self.close_position_group(&group_id, ExitReason::TakeProfit)
+                    .await
+            }
+            (GroupAction::Stop, Some(group_id)) => {
+                self.close_position_group(&group_id, ExitReason::StopLoss)
+                    .await
+            }
+            (action, _) => Ok(format!(
+                "{}: {:?} at z-score {:.2}",
+                name, action, signal.zscore
+            )),
+        }
+    }
+
+    /// Open every leg of a multi-asset signal, or none of them
+    ///
+    /// Each leg is sized at `notional` times its weight. Legs are ordered one
+    /// after another on the active trader; if one fails, the legs already
+    /// ordered are unwound with opposite orders before the error is returned.
+    pub async fn open_position_group(
+        &self,
+        strategy: &str,
+        signal: &MultiAssetSignal,
+        notional: f64,
+    ) -> Result<String, MpcError> {
+        if !self.config.enable_automated_trading {
+            return Err(MpcError::InvalidConfiguration(
+                "Automated trading is disabled".to_string(),
+            ));
+        }
+        if signal.legs.is_empty() {
+            return Err(MpcError::InvalidInput(format!(
+                "{} signal has no legs to open",
+                strategy
+            )));
+        }
+        for leg in &signal.legs {
+            validate_symbol(&leg.symbol)?;
+            if !self.is_whitelisted(&leg.symbol) {
+                return Err(MpcError::InvalidInput(format!(
+                    "Symbol '{}' is not in the configured whitelist for trading",
+                    leg.symbol
+                )));
+            }
+            self.check_reconciliation_pause(&leg.symbol).await?;
+        }
+
+        let (trader_id, trader_sender) = self.select_trader_sender().await.ok_or_else(|| {
+            MpcError::InvalidConfiguration("No trader available to open position group".to_string())
+        })?;
+        self.check_trading_limits().await?;
+        {
+            let positions = self.open_positions.lock().await;
+            if positions.len() + signal.legs.len() > self.config.max_total_positions {
+                return Ok(format!(
+                    "Maximum total positions ({}) reached",
+                    self.config.max_total_positions
+                ));
+            }
+        }
+
+        // Price and size every leg before anything is sent
+        let mut orders = Vec::new();
+        for leg in &signal.legs {
+            let side = match leg.signal {
+                crate::domain::services::strategies::Signal::Buy => PositionSide::Long,
+                crate::domain::services::strategies::Signal::Sell => PositionSide::Short,
+                crate::domain::services::strategies::Signal::Hold => {
+                    return Err(MpcError::InvalidInput(format!(
+                        "{} leg {} has no direction",
+                        strategy, leg.symbol
+                    )));
+                }
+            };
+            let price = self.get_aggregated_price(&leg.symbol).await?;
+            let quantity = notional * leg.weight / price.value();
+            if quantity < MIN_ORDER_QUANTITY {
+                return Err(MpcError::InvalidInput(format!(
+                    "{} leg {} quantity {} is below the minimum",
+                    strategy, leg.symbol, quantity
+                )));
+            }
+            let quantity = Quantity::new(quantity).map_err(|e| {
+                MpcError::InvalidConfiguration(format!("Invalid quantity calculation: {}", e))
+            })?;
+            orders.push((leg.symbol.clone(), side, quantity, price));
+        }
+
+        let exchange = self
+            .trader_active_exchange(&trader_sender)
+            .await
+            .map(|exchange| format!("{:?}", exchange).to_lowercase())
+            .unwrap_or_else(|| "unknown".to_string());
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        let group_id = format!("grp_{}", timestamp);
+
+        let mut legs: Vec<(Position, String)> = Vec::new();
+        for (symbol, side, quantity, price) in orders {
+            let order_side = match side {
+                PositionSide::Long => OrderSide::Buy,
+                PositionSide::Short => OrderSide::Sell,
+            };
+            match self
+                .send_leg_order(&trader_sender, &symbol, order_side, quantity, price)
+                .await
+            {
+                Ok(order_id) => {
+                    let mut position = Position::new(
+                        format!("pos_{}_{}", symbol, timestamp),
+                        symbol,
+                        side,
+                        quantity,
+                        price,
+                    );
+                    position.strategy = Some(strategy.to_string());
+                    position.group_id = Some(group_id.clone());
+                    legs.push((position, order_id));
+                }
+                Err(e) => {
+                    warn!(
+                        "{} leg {} failed on trader {}, unwinding {} legs: {}",
+                        strategy,
+                        symbol,
+                        trader_id,
+                        legs.len(),
+                        e
+                    );
+                    for (position, _) in &legs {
+                        if let Err(unwind) = self.send_exit_order(&trader_sender, position).await {
+                            error!(
+                                "Failed to unwind {} leg {}: {}",
+                                strategy, position.symbol, unwind
+                            );
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        {
+            let mut trade_history = self.trade_history.lock().await;
+            for (position, _) in &legs {
+                trade_history.push((SystemTime::now(), position.symbol.clone()));
+            }
+        }
+
+        let group = PositionGroup::new(
+            group_id.clone(),
+            strategy.to_string(),
+            legs.iter()
+                .map(|(position, _)| position.id.clone())
+                .collect(),
+            signal.hedge_ratio,
+            signal.zscore,
+        );
+        self.record_group_open(&group).await;
+        for (position, order_id) in &legs {
+            self.record_position_open(position, &exchange, Some(order_id), None)
+                .await;
+        }
+
+        let summary = legs
+            .iter()
+            .map(|(p, _)| format!("{} {} {}", p.side, p.quantity.value(), p.symbol))
+            .collect::<Vec<_>>()
+            .join(", ");
+        {
+            let mut positions = self.open_positions.lock().await;
+            for (position, _) in legs {
+                positions.insert(position.id.clone(), position);
+            }
+        }
+        {
+            let mut groups = self.position_groups.lock().await;
+            groups.insert(group_id.clone(), group);
+        }
+
+        info!(
+            "Opened position group {} for {} at z-score {:.2}: {}",
+            group_id, strategy, signal.zscore, summary
+        );
+        Ok(format!("Opened {} ({}): {}", group_id, strategy, summary))
+    }
+
+    /// Close every leg of a position group
+    ///
+    /// Each leg gets an exit order before it is closed. Legs whose exit order
+    /// fails stay open in the group so a later pass retries them.
+    pub async fn close_position_group(
+        &self,
+        group_id: &str,
+        reason: ExitReason,
+    ) -> Result<String, MpcError> {
+        let group = {
+            let groups = self.position_groups.lock().await;
+            groups.get(group_id).cloned()
+        }
+        .ok_or_else(|| {
+            MpcError::InvalidConfiguration(format!("Position group {} not found", group_id))
+        })?;
+        let legs = self.group_legs(&group).await;
+        let pnl = PositionGroup::unrealized_pnl(&legs);
+
+        let (_, trader_sender) = self.select_trader_sender().await.ok_or_else(|| {
+            MpcError::InvalidConfiguration(format!(
+                "No trader available to close position group {}",
+                group_id
+            ))
+        })?;
+
+        let mut first_error = None;
+        for leg in &legs {
+            let result = match self.send_exit_order(&trader_sender, leg).await {
+                Ok(order_id) => {
+                    self.close_position_by_order(&leg.id, reason, Some(&order_id))
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!(
+                    "Failed to close leg {} of group {}: {}",
+                    leg.id, group_id, e
+                );
+                first_error.get_or_insert(e);
+            }
+        }
+        {
+            // Legs closed elsewhere left the group without going through here
+            let positions = self.open_positions.lock().await;
+            let mut groups = self.position_groups.lock().await;
+            if let Some(group) = groups.get_mut(group_id) {
+                group.position_ids.retain(|id| positions.contains_key(id));
+                if group.position_ids.is_empty() {
+                    groups.remove(group_id);
+                }
+            }
+        }
+
+        if let Some(e) = first_error {
+            return Err(e);
+        }
+        info!(
+            "Closed position group {} ({}) due to {} (PnL: {:.2})",
+            group_id, group.strategy, reason, pnl
+        );
+        Ok(format!(
+            "Position group {} closed due to {} (PnL: {:.2})",
+            group_id, reason, pnl
+        ))
+    }
+
+    /// Open position groups with their legs
+    pub async fn get_position_groups(&self) -> Vec<(PositionGroup, Vec<Position>)> {
+        let groups: Vec<PositionGroup> = {
+            let groups = self.position_groups.lock().await;
+            groups.values().cloned().collect()
+        };
+        let mut result = Vec::with_capacity(groups.len());
+        for group in groups {
+            let legs = self.group_legs(&group).await;
+            result.push((group, legs));
+        }
+        result
+    }
+
+    /// Open legs of a group, in the order they were opened
+    async fn group_legs(&self, group: &PositionGroup) -> Vec<Position> {
+        let positions = self.open_positions.lock().await;
+        group
+            .position_ids
+            .iter()
+            .filter_map(|id| positions.get(id).cloned())
+            .collect()
+    }
+
+    /// Persist a newly opened position group ahead of its legs
+    ///
+    /// No-op without a position repository.
+    async fn record_group_open(&self, group: &PositionGroup) {
+        let Some(positions) = self.position_repository.as_ref() else {
+            return;
+        };
+        let record = PositionGroupRecord {
+            id: group.id.clone(),
+            strategy: group.strategy.clone(),
+            hedge_ratio: group.hedge_ratio,
+            entry_zscore: group.entry_zscore,
+            opened_at: group.opened_at,
+        };
+        if let Err(e) = positions.create_group(record).await {
+            warn!("Failed to persist position group {}: {}", group.id, e);
+        }
+    }
+
+    /// Forget a closed leg, dropping the group once its last leg is gone
+    pub(super) async fn remove_group_leg(&self, group_id: &str, position_id: &str) {
+        let mut groups = self.position_groups.lock().await;
+        if let Some(group) = groups.get_mut(group_id) {
+            group.position_ids.retain(|id| id != position_id);
+            if group.position_ids.is_empty() {
+                groups.remove(group_id);
+            }
+        }
+    }
+
+    /// Send a slippage-protected limit order for one leg of a group
+    async fn send_leg_order(
+        &self,
+        trader_sender: &mpsc::Sender<TraderMessage>,
+        symbol: &str,
+        side: OrderSide,
+        quantity: Quantity,
+        price: Price,
+    ) -> Result<String, MpcError> {
+        let limit = match side {
+            OrderSide::Buy => price.value() * (1.0 + self.config.max_slippage_percent),
+            OrderSide::Sell => price.value() * (1.0 - self.config.max_slippage_percent),
+        };
+        let order = Order::new(
+            format!("order_{}_{}", chrono::Utc::now().timestamp_millis(), symbol),
+            symbol.to_string(),
+            side,
+            OrderType::Limit,
+            Some(limit),
+            quantity.value(),
+        )
+        .map_err(|e| MpcError::InvalidConfiguration(format!("Failed to create order: {}", e)))?;
+
+        self.ask_trader(trader_sender, |reply| TraderMessage::PlaceOrder {
+            order,
+            reply,
+        })
+        .await
+    }
+
+    /// Send the order that flattens `position`, priced at the latest price
+    async fn send_exit_order(
+        &self,
+        trader_sender: &mpsc::Sender<TraderMessage>,
+        position: &Position,
+    ) -> Result<String, MpcError> {
+        let price = match self.get_aggregated_price(&position.symbol).await {
+            Ok(price) => price,
+            Err(_) => position.current_price.unwrap_or(position.entry_price),
+        };
+        let side = match position.side {
+            PositionSide::Long => OrderSide::Sell,
+            PositionSide::Short => OrderSide::Buy,
+        };
+        self.send_leg_order(
+            trader_sender,
+            &position.symbol,
+            side,
+            position.quantity,
+            price,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{
+        add_mock_trader, memory_repositories, venue_feed, MockExchange,
+    };
+    use super::*;
+    use crate::domain::services::pairs_trading::LegSignal;
+
+    #[tokio::test]
+    async fn test_pair_legs_open_stop_and_unwind_as_a_unit() {
+        let mut config = TradingConfig::default();
+        config.stop_loss_percentage = Some(0.02);
+        config.take_profit_percentage = Some(0.10);
+        let mut service = MpcService::new(config.clone());
+        let (positions, trades) = memory_repositories(&mut service).await;
+
+        let prices = Arc::new(std::sync::Mutex::new(HashMap::from([
+            ("ETH-USD".to_string(), 2000.0),
+            ("BTC-USD".to_string(), 50000.0),
+        ])));
+        service.senders = Arc::new(HashMap::from([(
+            Exchange::Coinbase,
+            venue_feed(prices.clone(), None, Vec::new()),
+        )]));
+
+        let placed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let reject = Arc::new(std::sync::Mutex::new(None));
+        add_mock_trader(
+            &service,
+            Exchange::Coinbase,
+            MockExchange {
+                fill_all: true,
+                placed: placed.clone(),
+                reject: reject.clone(),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        // Spread rich: sell ETH, buy 0.8 of its notional in BTC
+        let signal = MultiAssetSignal {
+            action: GroupAction::Enter,
+            legs: vec![
+                LegSignal {
+                    symbol: "ETH-USD".to_string(),
+                    signal: crate::domain::services::strategies::Signal::Sell,
+                    weight: 1.0,
+                },
+                LegSignal {
+                    symbol: "BTC-USD".to_string(),
+                    signal: crate::domain::services::strategies::Signal::Buy,
+                    weight: 0.8,
+                },
+            ],
+            confidence: 0.6,
+            hedge_ratio: 0.8,
+            zscore: 2.4,
+            indicators: BTreeMap::new(),
+        };
+        service
+            .open_position_group("Pairs ETH-USD/BTC-USD", &signal, 100.0)
+            .await
+            .unwrap();
+        {
+            let placed = placed.lock().unwrap();
+            assert_eq!(placed.len(), 2);
+            assert!(matches!(placed[0].side, OrderSide::Sell));
+            assert!((placed[0].quantity.value() - 0.05).abs() < 1e-12);
+            assert!(matches!(placed[1].side, OrderSide::Buy));
+            assert!((placed[1].quantity.value() - 0.0016).abs() < 1e-12);
+        }
+        let groups = service.get_position_groups().await;
+        assert_eq!(groups.len(), 1);
+        let (group, legs) = &groups[0];
+        assert_eq!(legs.len(), 2);
+        assert!(legs
+            .iter()
+            .all(|leg| leg.group_id.as_deref() == Some(group.id.as_str())));
+
+        // A restarted service relinks the legs into the same group
+        let mut restarted = MpcService::new(config);
+        restarted.set_repositories(positions, trades.clone());
+        let summary = restarted.recover_trading_state().await.unwrap();
+        assert_eq!(summary.positions_restored, 2);
+        assert_eq!(summary.groups_restored, 1);
+        let restored = restarted.get_position_groups().await;
+        assert_eq!(restored.len(), 1);
+        let (restored_group, restored_legs) = &restored[0];
+        assert_eq!(restored_group.id, group.id);
+        assert_eq!(restored_group.strategy, "Pairs ETH-USD/BTC-USD");
+        assert_eq!(restored_group.hedge_ratio, 0.8);
+        assert_eq!(restored_group.entry_zscore, 2.4);
+        assert_eq!(restored_group.opened_at, group.opened_at);
+        assert_eq!(restored_legs.len(), 2);
+
+        let set_prices = |eth: f64, btc: f64| {
+            let prices = prices.clone();
+            let service = &service;
+            async move {
+                {
+                    let mut prices = prices.lock().unwrap();
+                    prices.insert("ETH-USD".to_string(), eth);
+                    prices.insert("BTC-USD".to_string(), btc);
+                }
+                let mut positions = service.open_positions.lock().await;
+                for position in positions.values_mut() {
+                    let price = if position.symbol == "ETH-USD" {
+                        eth
+                    } else {
+                        btc
+                    };
+                    position.update_price(Price::new(price).unwrap());
+                }
+            }
+        };
+
+        // The short ETH leg is 5% under water, but the BTC leg covers most of it
+        set_prices(2100.0, 51250.0).await;
+        assert!(service.check_and_execute_stops().await.is_empty());
+        assert_eq!(service.get_open_positions().await.len(), 2);
+
+        // Combined loss of 5 on 180 notional passes the 2% stop: both legs exit
+        set_prices(2100.0, 50000.0).await;
+        let results = service.check_and_execute_stops().await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].as_ref().unwrap().contains("stop-loss"));
+        assert!(service.get_open_positions().await.is_empty());
+        assert!(service.get_position_groups().await.is_empty());
+        {
+            let placed = placed.lock().unwrap();
+            assert_eq!(placed.len(), 4);
+            assert!(matches!(placed[2].side, OrderSide::Buy));
+            assert!(matches!(placed[3].side, OrderSide::Sell));
+        }
+        // The closing trades name the exit orders, so their fills are accounted for
+        for leg in legs {
+            let closing = trades
+                .get(&format!("trade_close_{}", leg.id))
+                .await
+                .unwrap()
+                .unwrap();
+            let exit_order_id = closing.exchange_order_id.unwrap();
+            assert!(service.is_fill_tracked(&exit_order_id).await);
+        }
+
+        // A rejected hedge leg unwinds the leg already sent
+        *reject.lock().unwrap() = Some("BTC-USD".to_string());
+        assert!(service
+            .open_position_group("Pairs ETH-USD/BTC-USD", &signal, 100.0)
+            .await
+            .is_err());
+        {
+            let placed = placed.lock().unwrap();
+            assert_eq!(placed.len(), 6);
+            assert!(matches!(placed[4].side, OrderSide::Sell));
+            assert!(matches!(placed[5].side, OrderSide::Buy));
+            assert_eq!(placed[5].symbol, "ETH-USD");
+        }
+        assert!(service.get_open_positions().await.is_empty());
+        assert!(service.get_position_groups().await.is_empty());
+    }
+}
@@ -0,0 +1,548 @@
+//! Position reconciliation of the MPC service
+//!
+//! Compares dYdX positions and open orders with the venue, adopting orphans
+//! and closing ghosts as the policy allows.
+
+use super::*;
+
+impl MpcService {
+    /// Reconcile dYdX positions and active orders against the venue
+    ///
+    /// Orphans (held only on the venue) are adopted and ghosts (tracked only
+    /// locally) closed when the position reconciliation policy says so;
+    /// otherwise they stay flagged for an operator. Untracked exposure left
+    /// over raises a reconciliation alert. Local state is read before the
+    /// venue's, and a discrepancy is only acted on once the next run finds
+    /// it again, so a trade between the two snapshots is not mistaken for one.
+    pub async fn run_position_reconciliation(
+        &self,
+    ) -> Result<PositionReconciliationReport, String> {
+        let exchange = Exchange::Dydx;
+        let actor = self
+            .reconciliation
+            .as_ref()
+            .ok_or("Reconciliation is not running")?;
+        let local_positions = self.positions_on(&exchange).await?;
+        let strategy_exposure = self.strategy_exposure_on(&exchange).await;
+        // Without order metadata every venue order would look untracked
+        let local_orders = match &self.order_metadata_repository {
+            Some(repository) => Some(
+                repository
+                    .get_active_orders()
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .map(|record| VenueOrder {
+                        order_id: record.order_id,
+                        client_id: record.client_id.to_string(),
+                        symbol: record.symbol,
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            None => None,
+        };
+
+        let (reply_tx, mut reply_rx) = mpsc::channel(1);
+        actor
+            .send(ReconciliationMessage::FetchVenueState {
+                exchange: exchange.clone(),
+                reply: reply_tx,
+            })
+            .await
+            .map_err(|_| "Reconciliation actor stopped".to_string())?;
+        let mut venue = reply_rx
+            .recv()
+            .await
+            .ok_or("Reconciliation actor dropped the reply")?
+            .map_err(|e| e.to_string())?;
+        if local_orders.is_none() {
+            venue.open_orders.clear();
+        }
+
+        let previous = {
+            let reports = self.position_reconciliations.lock().await;
+            reports.get(&exchange).cloned()
+        };
+        let mut report = reconcile_positions(
+            exchange.clone(),
+            &local_positions,
+            &strategy_exposure,
+            &local_orders.unwrap_or_default(),
+            &venue,
+            self.config.reconciliation_threshold_percentage,
+        )
+        .confirmed_by(previous.as_ref());
+
+        let policy = self.config.position_reconciliation;
+        if policy.adopt_orphans {
+            let orphans: Vec<String> = report
+                .orphan_symbols()
+                .into_iter()
+                .map(str::to_string)
+                .collect();
+            for symbol in orphans {
+                let Some((quantity, entry_price)) = report.orphan_position(&symbol) else {
+                    continue;
+                };
+                match self
+                    .open_adopted_position(&exchange, &symbol, quantity, entry_price)
+                    .await
+                {
+                    Ok(_) => report.adopted.push(symbol),
+                    Err(e) => warn!("Failed to adopt {} position: {}", symbol, e),
+                }
+            }
+        }
+        if policy.close_ghosts {
+            let ghosts: Vec<String> = report
+                .ghost_symbols()
+                .into_iter()
+                .map(str::to_string)
+                .collect();
+            for symbol in ghosts {
+                self.close_ghosts(&report.ghost_positions(&symbol)).await;
+                report.closed.push(symbol);
+            }
+            if let Some(repository) = &self.order_metadata_repository {
+                for order_id in report.ghost_orders() {
+                    match repository.update_expired(order_id).await {
+                        Ok(()) => info!("Order {} is no longer open on the venue", order_id),
+                        Err(e) => warn!("Failed to expire order {}: {}", order_id, e),
+                    }
+                }
+            }
+        }
+
+        self.position_reconciliations
+            .lock()
+            .await
+            .insert(exchange, report.clone());
+        self.raise_position_reconciliation_alert(&report).await;
+        Ok(report)
+    }
+
+    /// Latest position reconciliation report per exchange
+    pub async fn get_position_reconciliations(&self) -> Vec<PositionReconciliationReport> {
+        let reports = self.position_reconciliations.lock().await;
+        let mut reports: Vec<PositionReconciliationReport> = reports.values().cloned().collect();
+        reports.sort_by(|a, b| a.exchange.name().cmp(b.exchange.name()));
+        reports
+    }
+
+    /// Track the venue position of an orphan flagged by the latest position
+    /// reconciliation as a local position
+    ///
+    /// Returns the id of the new local position.
+    pub async fn adopt_orphan_position(
+        &self,
+        exchange: &Exchange,
+        symbol: &str,
+    ) -> Result<String, String> {
+        let symbol = TradingConfig::normalize_symbol(symbol);
+        let (quantity, entry_price) = {
+            let reports = self.position_reconciliations.lock().await;
+            let report = reports
+                .get(exchange)
+                .ok_or_else(|| format!("No position reconciliation for {}", exchange.name()))?;
+            if report.adopted.contains(&symbol) {
+                return Err(format!("{} position is already adopted", symbol));
+            }
+            report
+                .orphan_position(&symbol)
+                .ok_or_else(|| format!("No orphan {} position on {}", symbol, exchange.name()))?
+        };
+
+        let position_id = self
+            .open_adopted_position(exchange, &symbol, quantity, entry_price)
+            .await?;
+        self.settle_position_discrepancy(exchange, |report| report.adopted.push(symbol))
+            .await;
+        Ok(position_id)
+    }
+
+    /// Close the local positions on a symbol that the latest position
+    /// reconciliation found flat on the venue
+    ///
+    /// No order is sent; the positions are only closed locally. Returns the
+    /// ids of the closed positions.
+    pub async fn close_ghost_positions(
+        &self,
+        exchange: &Exchange,
+        symbol: &str,
+    ) -> Result<Vec<String>, String> {
+        let symbol = TradingConfig::normalize_symbol(symbol);
+        let position_ids = {
+            let reports = self.position_reconciliations.lock().await;
+            let report = reports
+                .get(exchange)
+                .ok_or_else(|| format!("No position reconciliation for {}", exchange.name()))?;
+            report.ghost_positions(&symbol)
+        };
+        if position_ids.is_empty() {
+            return Err(format!(
+                "No ghost {} positions on {}",
+                symbol,
+                exchange.name()
+            ));
+        }
+
+        let closed = self.close_ghosts(&position_ids).await;
+        self.settle_position_discrepancy(exchange, |report| report.closed.push(symbol))
+            .await;
+        Ok(closed)
+    }
+
+    /// Open positions on an exchange, with normalized symbols
+    ///
+    /// The venue of a position comes from its persisted record, so this
+    /// fails without a position repository or when a record cannot be read.
+    pub(super) async fn positions_on(&self, exchange: &Exchange) -> Result<Vec<Position>, String> {
+        let repository = self
+            .position_repository
+            .as_ref()
+            .ok_or("Positions are not persisted, so their exchange is unknown")?;
+        let positions: Vec<Position> = {
+            let positions = self.open_positions.lock().await;
+            positions.values().cloned().collect()
+        };
+
+        let mut held = Vec::new();
+        for mut position in positions {
+            match repository.get(&position.id).await {
+                Ok(Some(record)) if record.exchange == exchange.name() => {}
+                Ok(_) => continue,
+                Err(e) => return Err(format!("Failed to look up position {}: {}", position.id, e)),
+            }
+            position.symbol = TradingConfig::normalize_symbol(&position.symbol);
+            held.push(position);
+        }
+        Ok(held)
+    }
+
+    /// Net quantity held on an exchange per normalized symbol by the
+    /// strategies that trade without positions, from the open lots of the
+    /// PnL ledger
+    ///
+    /// Arbitrage legs and market making inventory are venue positions we
+    /// hold on purpose, so reconciliation must not adopt them as orphans.
+    async fn strategy_exposure_on(&self, exchange: &Exchange) -> BTreeMap<String, f64> {
+        let ledger = self.pnl_ledger.lock().await;
+        let mut exposure = BTreeMap::new();
+        for lot in ledger.open_lots() {
+            if lot.exchange != exchange.name()
+                || ![ARBITRAGE_STRATEGY, MARKET_MAKING_STRATEGY].contains(&lot.strategy.as_str())
+            {
+                continue;
+            }
+            let quantity = match lot.side {
+                FillSide::Buy => lot.quantity,
+                FillSide::Sell => -lot.quantity,
+            };
+            *exposure
+                .entry(TradingConfig::normalize_symbol(&lot.symbol))
+                .or_insert(0.0) += quantity;
+        }
+        exposure
+    }
+
+    /// Open a local position mirroring a venue position, without placing an order
+    async fn open_adopted_position(
+        &self,
+        exchange: &Exchange,
+        symbol: &str,
+        signed_quantity: f64,
+        entry_price: f64,
+    ) -> Result<String, String> {
+        let side = if signed_quantity < 0.0 {
+            PositionSide::Short
+        } else {
+            PositionSide::Long
+        };
+        let quantity = Quantity::new(signed_quantity.abs()).map_err(|e| e.to_string())?;
+        let entry_price = Price::new(entry_price).map_err(|e| e.to_string())?;
+        let position_id = format!(
+            "pos_{}_adopted_{}",
+            symbol,
+            chrono::Utc::now().timestamp_millis()
+        );
+        let position = Position::new_with_stops(
+            position_id.clone(),
+            symbol.to_string(),
+            side.clone(),
+            quantity,
+            entry_price,
+            self.config.stop_loss_percentage,
+            self.config.take_profit_percentage,
+        )
+        .map_err(|e| e.to_string())?;
+
+        self.record_position_open(&position, exchange.name(), None, None)
+            .await;
+        self.open_positions
+            .lock()
+            .await
+            .insert(position_id.clone(), position);
+
+        warn!(
+            "📥 Adopted {} {} {} @ {} from {}",
+            side,
+            quantity.value(),
+            symbol,
+            entry_price.value(),
+            exchange.name()
+        );
+        Ok(position_id)
+    }
+
+    /// Close positions locally that the venue no longer holds
+    ///
+    /// Returns the ids of the positions that were still open.
+    async fn close_ghosts(&self, position_ids: &[String]) -> Vec<String> {
+        let mut closed = Vec::new();
+        for position_id in position_ids {
+            match self
+                .close_position_with_reason(position_id, ExitReason::Reconciliation)
+                .await
+            {
+                Ok(()) => {
+                    warn!("👻 Closed ghost position {}", position_id);
+                    closed.push(position_id.clone());
+                }
+                Err(e) => debug!("Ghost position {} not closed: {}", position_id, e),
+            }
+        }
+        closed
+    }
+
+    /// Record an operator action on the latest position report of an exchange
+    /// and re-evaluate its alert
+    async fn settle_position_discrepancy(
+        &self,
+        exchange: &Exchange,
+        settle: impl FnOnce(&mut PositionReconciliationReport),
+    ) {
+        let report = {
+            let mut reports = self.position_reconciliations.lock().await;
+            let Some(report) = reports.get_mut(exchange) else {
+                return;
+            };
+            settle(report);
+            report.clone()
+        };
+        self.raise_position_reconciliation_alert(&report).await;
+    }
+
+    /// Raise one alert per exchange while untracked exposure is left over,
+    /// resolving it once every orphan and ghost is settled
+    pub(super) async fn raise_position_reconciliation_alert(
+        &self,
+        report: &PositionReconciliationReport,
+    ) {
+        let prefix = format!("Position reconciliation {}:", report.exchange.name());
+        let symbols: Vec<&str> = report
+            .unresolved()
+            .into_iter()
+            .filter(|discrepancy| discrepancy.severity() == DiscrepancySeverity::Critical)
+            .map(|discrepancy| discrepancy.symbol())
+            .collect();
+
+        let kind = AlertType::PositionReconciliation(report.exchange.clone());
+        let mut active_alerts = self.active_alerts.lock().await;
+        let mut open = active_alerts
+            .iter_mut()
+            .filter(|alert| !alert.resolved && alert.alert_type == kind);
+        if symbols.is_empty() {
+            for alert in open {
+                info!("✓ Resolved: {}", alert.message);
+                alert.resolved = true;
+            }
+            return;
+        }
+        if open.next().is_some() {
+            return;
+        }
+
+        let message = format!(
+            "{} orphan or ghost positions on {}",
+            prefix,
+            symbols.join(", ")
+        );
+        error!("🚨 {}", message);
+        active_alerts.push(SystemAlert {
+            alert_type: kind,
+            message,
+            severity: crate::domain::services::metrics::AlertSeverity::Critical,
+            timestamp: SystemTime::now(),
+            resolved: false,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::memory_repositories;
+    use super::*;
+
+    /// Stand-in reconciliation actor reporting one short BTC perp on dYdX
+    fn venue_with_btc_short() -> mpsc::Sender<ReconciliationMessage> {
+        use crate::domain::services::reconciliation::{VenuePosition, VenueState};
+
+        let (actor_tx, mut actor_rx) = mpsc::channel(4);
+        tokio::spawn(async move {
+            while let Some(ReconciliationMessage::FetchVenueState { reply, .. }) =
+                actor_rx.recv().await
+            {
+                let _ = reply
+                    .send(Ok(VenueState {
+                        positions: vec![VenuePosition {
+                            symbol: "BTC-USD".to_string(),
+                            side: PositionSide::Short,
+                            quantity: 0.2,
+                            entry_price: 50000.0,
+                        }],
+                        open_orders: Vec::new(),
+                    }))
+                    .await;
+            }
+        });
+        actor_tx
+    }
+
+    /// Service reconciling against `venue_with_btc_short`, tracking an ETH
+    /// long on dYdX that the venue no longer holds
+    async fn service_with_eth_ghost(config: TradingConfig) -> (MpcService, String) {
+        let mut service = MpcService::new(config);
+        memory_repositories(&mut service).await;
+        service.reconciliation = Some(venue_with_btc_short());
+
+        // Closed on the venue UI while we still track it
+        let ghost_id = service
+            .open_adopted_position(&Exchange::Dydx, "ETH-USD", 1.0, 3000.0)
+            .await
+            .unwrap();
+        // Positions on other venues are not compared with dYdX
+        service
+            .open_position(
+                "SOL-USD",
+                PositionSide::Long,
+                Quantity::new(5.0).unwrap(),
+                Price::new(150.0).unwrap(),
+            )
+            .await
+            .unwrap();
+        (service, ghost_id)
+    }
+
+    #[tokio::test]
+    async fn test_position_reconciliation_adopts_orphans_and_closes_ghosts() {
+        let mut unpersisted = MpcService::new(TradingConfig::default());
+        assert!(unpersisted.run_position_reconciliation().await.is_err());
+        // Without persisted positions their venue is unknown
+        unpersisted.reconciliation = Some(venue_with_btc_short());
+        assert!(unpersisted.run_position_reconciliation().await.is_err());
+
+        let (service, ghost_id) = service_with_eth_ghost(TradingConfig::default()).await;
+
+        // A first sighting may be a trade between the two snapshots
+        let report = service.run_position_reconciliation().await.unwrap();
+        assert_eq!(report.status, ReconciliationStatus::Ok);
+        assert_eq!(report.pending.len(), 2);
+        assert!(service
+            .adopt_orphan_position(&Exchange::Dydx, "BTC-USD")
+            .await
+            .is_err());
+        assert!(service.get_active_alerts().await.is_empty());
+
+        let report = service.run_position_reconciliation().await.unwrap();
+        assert_eq!(report.status, ReconciliationStatus::Critical);
+        assert_eq!(report.orphan_symbols(), vec!["BTC-USD"]);
+        assert_eq!(report.ghost_positions("ETH-USD"), vec![ghost_id.clone()]);
+        assert_eq!(
+            service
+                .get_active_alerts()
+                .await
+                .iter()
+                .map(|alert| alert.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Position reconciliation dydx: orphan or ghost positions on BTC-USD, ETH-USD"]
+        );
+
+        let adopted_id = service
+            .adopt_orphan_position(&Exchange::Dydx, "BTC-USD")
+            .await
+            .unwrap();
+        assert!(service
+            .adopt_orphan_position(&Exchange::Dydx, "BTC-USD")
+            .await
+            .is_err());
+        assert_eq!(
+            service
+                .close_ghost_positions(&Exchange::Dydx, "ETH-USD")
+                .await
+                .unwrap(),
+            vec![ghost_id]
+        );
+
+        let positions = service.get_open_positions().await;
+        assert_eq!(positions.len(), 2);
+        let adopted = &positions[&adopted_id];
+        assert!(matches!(adopted.side, PositionSide::Short));
+        assert_eq!(adopted.quantity.value(), 0.2);
+        assert!(service.get_active_alerts().await.is_empty());
+
+        // The venue and local state now agree
+        let report = service.run_position_reconciliation().await.unwrap();
+        assert_eq!(report.status, ReconciliationStatus::Ok);
+        assert!(report.pending.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_position_reconciliation_policy_acts_automatically() {
+        let mut config = TradingConfig::default();
+        config.position_reconciliation.adopt_orphans = true;
+        config.position_reconciliation.close_ghosts = true;
+        let (service, _) = service_with_eth_ghost(config).await;
+
+        let report = service.run_position_reconciliation().await.unwrap();
+        assert!(report.adopted.is_empty() && report.closed.is_empty());
+
+        let report = service.run_position_reconciliation().await.unwrap();
+        assert_eq!(report.adopted, vec!["BTC-USD".to_string()]);
+        assert_eq!(report.closed, vec!["ETH-USD".to_string()]);
+        assert!(report.unresolved().is_empty());
+        assert!(service.get_active_alerts().await.is_empty());
+
+        let positions = service.get_open_positions().await;
+        let mut symbols: Vec<&str> = positions
+            .values()
+            .map(|position| position.symbol.as_str())
+            .collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["BTC-USD", "SOL-USD"]);
+    }
+
+    #[tokio::test]
+    async fn test_position_reconciliation_leaves_strategy_exposure_alone() {
+        let mut config = TradingConfig::default();
+        config.position_reconciliation.adopt_orphans = true;
+        let (service, _) = service_with_eth_ghost(config).await;
+        // The venue's BTC short is the leg of an arbitrage
+        service
+            .record_arbitrage_fill(
+                "BTC-USD",
+                &Exchange::Dydx,
+                OrderSide::Sell,
+                0.2,
+                50000.0,
+                "arb_1",
+            )
+            .await;
+
+        service.run_position_reconciliation().await.unwrap();
+        let report = service.run_position_reconciliation().await.unwrap();
+        assert!(report.orphan_symbols().is_empty());
+        assert!(report.adopted.is_empty());
+        assert_eq!(report.ghost_symbols(), vec!["ETH-USD"]);
+        assert_eq!(service.get_open_positions().await.len(), 2);
+    }
+}
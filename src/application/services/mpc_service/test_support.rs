@@ -0,0 +1,294 @@
+//! Fixtures shared by the MPC service tests
+
+use super::*;
+use crate::application::actors::trader_actor::TraderActor;
+use crate::domain::entities::bracket_order::BracketExitIds;
+use crate::domain::entities::trader::Trader;
+use crate::domain::repositories::exchange_client::{
+    Balance, ExchangeClient, ExchangeError, ExchangeResult,
+};
+use crate::domain::services::strategies::FastScalping;
+use crate::persistence::repository::{SqlitePositionRepository, SqliteTradeRepository};
+use crate::persistence::DbPool;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exchange client recording the orders placed and cancelled
+///
+/// Orders are answered with `ex_<n>`, counting from 1, or `ex_<order id>`
+/// with `echo_order_ids`. An order reports filled when listed in `filled`,
+/// with `fill_all`, or when it is a bid and `fill_bids` is set; otherwise it
+/// is pending. Orders in `partial_fills` report that quantity filled instead,
+/// and orders for the `reject` symbol are refused. With `native_brackets` the
+/// exits of a bracket are held on the exchange as `sl-1` and `tp-1`.
+#[derive(Default)]
+pub(super) struct MockExchange {
+    pub echo_order_ids: bool,
+    pub native_brackets: bool,
+    pub fill_all: bool,
+    pub filled: Vec<String>,
+    pub placed: Arc<std::sync::Mutex<Vec<Order>>>,
+    pub cancelled: Arc<std::sync::Mutex<Vec<String>>>,
+    pub fill_bids: Arc<AtomicBool>,
+    pub partial_fills: Arc<std::sync::Mutex<HashMap<String, f64>>>,
+    pub reject: Arc<std::sync::Mutex<Option<String>>>,
+}
+
+impl MockExchange {
+    fn placed_order(&self, order_id: &str) -> Option<Order> {
+        let placed = self.placed.lock().unwrap();
+        if self.echo_order_ids {
+            return placed
+                .iter()
+                .find(|order| format!("ex_{}", order.id) == order_id)
+                .cloned();
+        }
+        let index: usize = order_id.strip_prefix("ex_")?.parse().ok()?;
+        placed.get(index.checked_sub(1)?).cloned()
+    }
+}
+
+#[async_trait::async_trait]
+impl ExchangeClient for MockExchange {
+    fn name(&self) -> &str {
+        "MockExchange"
+    }
+
+    async fn place_order(&self, order: &Order) -> ExchangeResult<String> {
+        if self.reject.lock().unwrap().as_deref() == Some(order.symbol.as_str()) {
+            return Err(ExchangeError::OrderPlacementFailed(format!(
+                "{} rejected",
+                order.symbol
+            )));
+        }
+        let mut placed = self.placed.lock().unwrap();
+        placed.push(order.clone());
+        if self.echo_order_ids {
+            Ok(format!("ex_{}", order.id))
+        } else {
+            Ok(format!("ex_{}", placed.len()))
+        }
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> ExchangeResult<()> {
+        self.cancelled.lock().unwrap().push(order_id.to_string());
+        Ok(())
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> ExchangeResult<OrderStatus> {
+        let bid_filled = self.fill_bids.load(Ordering::SeqCst)
+            && self
+                .placed_order(order_id)
+                .is_some_and(|order| matches!(order.side, OrderSide::Buy));
+        if self.fill_all || bid_filled || self.filled.iter().any(|id| id == order_id) {
+            Ok(OrderStatus::Filled)
+        } else {
+            Ok(OrderStatus::Pending)
+        }
+    }
+
+    async fn get_order_fill(&self, order_id: &str) -> ExchangeResult<OrderFill> {
+        let partial = self.partial_fills.lock().unwrap().get(order_id).copied();
+        let Some(quantity) = partial else {
+            return Ok(OrderFill {
+                status: self.get_order_status(order_id).await?,
+                filled_quantity: None,
+                average_price: None,
+                fee: None,
+            });
+        };
+        let cancelled = self
+            .cancelled
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|id| id == order_id);
+        Ok(OrderFill {
+            status: if cancelled {
+                OrderStatus::Cancelled
+            } else {
+                OrderStatus::PartiallyFilled
+            },
+            filled_quantity: Some(quantity),
+            average_price: None,
+            fee: None,
+        })
+    }
+
+    async fn get_balance(&self, _currency: Option<&str>) -> ExchangeResult<Vec<Balance>> {
+        Ok(Vec::new())
+    }
+
+    fn supports_native_brackets(&self) -> bool {
+        self.native_brackets
+    }
+
+    async fn place_bracket_exits(&self, _bracket: &BracketOrder) -> ExchangeResult<BracketExitIds> {
+        Ok(BracketExitIds {
+            stop_loss: "sl-1".to_string(),
+            take_profit: "tp-1".to_string(),
+        })
+    }
+}
+
+/// Add `trader1` to `service`, executing on `exchange` through `client`
+pub(super) async fn add_mock_trader(
+    service: &MpcService,
+    exchange: Exchange,
+    client: MockExchange,
+) -> mpsc::Sender<TraderMessage> {
+    let mut trader = Trader::new(
+        "trader1".to_string(),
+        Box::new(FastScalping::new()),
+        1.0,
+        0.5,
+    )
+    .unwrap();
+    trader.add_exchange(exchange, Arc::new(client));
+    let sender = TraderActor::spawn(trader);
+    service
+        .add_trader("trader1".to_string(), sender.clone())
+        .await;
+    sender
+}
+
+/// In-memory SQLite database with every migration applied
+pub(super) async fn memory_pool() -> DbPool {
+    crate::persistence::init_database("sqlite::memory:")
+        .await
+        .unwrap()
+}
+
+/// Persist the positions and trades of `service` in a fresh in-memory database
+pub(super) async fn memory_repositories(
+    service: &mut MpcService,
+) -> (Arc<SqlitePositionRepository>, Arc<SqliteTradeRepository>) {
+    let pool = memory_pool().await;
+    let positions = Arc::new(SqlitePositionRepository::new(pool.clone()));
+    let trades = Arc::new(SqliteTradeRepository::new(pool));
+    service.set_repositories(positions.clone(), trades.clone());
+    (positions, trades)
+}
+
+/// Exchange feed answering subscriptions and prices from a shared map
+///
+/// Orders are recorded in `orders` when given and rejected otherwise.
+/// The n-th order reports the n-th of `fill_ratios` of its quantity
+/// filled (all of it past the end), and is cancelled on request.
+pub(super) fn venue_feed(
+    prices: Arc<std::sync::Mutex<HashMap<String, f64>>>,
+    orders: Option<Arc<std::sync::Mutex<Vec<Order>>>>,
+    fill_ratios: Vec<f64>,
+) -> mpsc::Sender<ExchangeMessage> {
+    let (tx, mut rx) = mpsc::channel(16);
+    tokio::spawn(async move {
+        let mut cancelled = std::collections::HashSet::new();
+        while let Some(message) = rx.recv().await {
+            match message {
+                ExchangeMessage::GetSubscriptions { reply } => {
+                    let symbols = prices.lock().unwrap().keys().cloned().collect();
+                    let _ = reply.send(symbols).await;
+                }
+                ExchangeMessage::GetPrice { symbol, reply } => {
+                    let price = prices.lock().unwrap().get(&symbol).copied();
+                    let _ = reply
+                        .send(
+                            price
+                                .map(|p| Price::new(p).unwrap())
+                                .ok_or_else(|| format!("No price for {}", symbol)),
+                        )
+                        .await;
+                }
+                ExchangeMessage::PlaceOrder { order, reply } => {
+                    let result = match &orders {
+                        Some(orders) => {
+                            let mut orders = orders.lock().unwrap();
+                            orders.push(order);
+                            Ok(format!("venue_{}", orders.len()))
+                        }
+                        None => Err("Order rejected".to_string()),
+                    };
+                    let _ = reply.send(result).await;
+                }
+                ExchangeMessage::CancelOrder { order_id, reply } => {
+                    cancelled.insert(order_id);
+                    let _ = reply.send(Ok(())).await;
+                }
+                ExchangeMessage::GetOrderFill { order_id, reply } => {
+                    // Orders placed through a trader are not reported here
+                    let Some(index) = order_id
+                        .strip_prefix("venue_")
+                        .and_then(|index| index.parse::<usize>().ok())
+                    else {
+                        continue;
+                    };
+                    let quantity = orders.as_ref().unwrap().lock().unwrap()[index - 1]
+                        .quantity
+                        .value();
+                    let ratio = fill_ratios.get(index - 1).copied().unwrap_or(1.0);
+                    let status = if ratio >= 1.0 {
+                        OrderStatus::Filled
+                    } else if cancelled.contains(&order_id) {
+                        OrderStatus::Cancelled
+                    } else if ratio > 0.0 {
+                        OrderStatus::PartiallyFilled
+                    } else {
+                        OrderStatus::Pending
+                    };
+                    let _ = reply
+                        .send(Ok(OrderFill {
+                            status,
+                            filled_quantity: Some(quantity * ratio),
+                            average_price: None,
+                            fee: None,
+                        }))
+                        .await;
+                }
+                _ => {}
+            }
+        }
+    });
+    tx
+}
+
+/// Market list served from a shared vector
+pub(super) struct StaticDiscovery(pub Arc<std::sync::Mutex<Vec<MarketSnapshot>>>);
+
+#[async_trait::async_trait]
+impl MarketDiscovery for StaticDiscovery {
+    async fn list_markets(&self, _exchange: &Exchange) -> Result<Vec<MarketSnapshot>, String> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+
+    /// Two levels around the listed price, 1 and 2 units deep
+    async fn order_book(
+        &self,
+        _exchange: &Exchange,
+        symbol: &str,
+    ) -> Result<crate::domain::services::screening::OrderBookDepth, String> {
+        let markets = self.0.lock().unwrap();
+        let market = markets
+            .iter()
+            .find(|m| m.symbol == symbol)
+            .ok_or_else(|| format!("{} not listed", symbol))?;
+        Ok(crate::domain::services::screening::OrderBookDepth {
+            bids: vec![(market.price * 0.9999, 1.0), (market.price * 0.999, 2.0)],
+            asks: vec![(market.price * 1.0001, 1.0), (market.price * 1.001, 2.0)],
+        })
+    }
+}
+
+/// Market with a 24h range around its price and open
+pub(super) fn snapshot(symbol: &str, price: f64, open: f64, quote_volume: f64) -> MarketSnapshot {
+    MarketSnapshot {
+        symbol: symbol.to_string(),
+        price,
+        open_24h: open,
+        high_24h: price.max(open) * 1.02,
+        low_24h: price.min(open) * 0.98,
+        quote_volume_24h: quote_volume,
+        bid: Some(price * 0.9999),
+        ask: Some(price * 1.0001),
+        trades_24h: None,
+        funding_rate: None,
+    }
+}
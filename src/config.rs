@@ -1,4 +1,14 @@
 use crate::domain::entities::exchange::Exchange;
+use crate::domain::entities::position::TimeExitRule;
+use crate::domain::services::arbitrage::ArbitrageParams;
+use crate::domain::services::market_making::MarketMakingParams;
+use crate::domain::services::pairs_trading::PairsParams;
+use crate::domain::services::pnl_ledger::LotMatching;
+use crate::domain::services::reconciliation::{
+    PositionReconciliationPolicy, RemediationAction, RemediationPolicy,
+};
+use crate::domain::services::symbol_universe::UniverseParams;
+use crate::domain::value_objects::position_sizing::{SizingMode, SizingParams};
 use std::collections::HashMap;
 
 /// Configuration for symbols to track on each exchange
@@ -17,8 +27,42 @@ pub struct TradingConfig {
     pub max_trades_per_day: usize,              // Limite de trades par jour
     pub max_slippage_percent: f64, // Maximum slippage allowed on orders (e.g., 0.002 = 0.2%)
 
+    // Time-based exit configuration
+    pub time_exit: TimeExitRule, // Default rule applied to every position
+    pub time_exit_by_symbol: HashMap<String, TimeExitRule>, // Overrides keyed by normalized symbol
+    pub time_exit_by_strategy: HashMap<String, TimeExitRule>, // Overrides keyed by strategy name
+
+    // Position sizing configuration
+    pub sizing_mode: SizingMode, // Default sizing algorithm
+    pub sizing_mode_by_strategy: HashMap<String, SizingMode>, // Overrides keyed by strategy name
+    pub sizing_params: SizingParams, // Risk, ATR, volatility and Kelly tunables
+
+    // Strategy registry file (JSON); built-in strategies are used when unset
+    pub strategy_config_path: Option<String>,
+
+    // Market making configuration
+    pub market_making_enabled: bool, // Quote two-sided post-only orders on market_making_symbols
+    pub market_making_symbols: Vec<String>, // Normalized symbols to quote
+    pub market_making_interval_seconds: u64, // How often quotes are checked and refreshed
+    pub market_making: MarketMakingParams, // Spread, skew, size and refresh settings
+
+    // Pairs trading configuration
+    pub pairs_trading_enabled: bool, // Trade the spread of pairs_trading_pairs
+    pub pairs_trading_pairs: Vec<(String, String)>, // (base, hedge) normalized symbol pairs
+    pub pairs_trading_interval_seconds: u64, // How often pair spreads are evaluated
+    pub pairs_trading: PairsParams,  // Lookback, z-score thresholds and notional
+
+    // Cross-exchange arbitrage configuration
+    pub arbitrage_enabled: bool, // Compare symbol prices across exchanges
+    pub arbitrage_execute: bool, // Trade divergences instead of only flagging them
+    pub arbitrage_symbols: Vec<String>, // Normalized symbols to compare, empty for all subscribed
+    pub arbitrage_interval_seconds: u64, // How often prices are compared
+    pub arbitrage_cooldown_seconds: u64, // Minimum time between trades of one symbol
+    pub arbitrage: ArbitrageParams, // Threshold, fees, perp basis and leg notional
+
     // Symbol screening configuration
     pub screening_enabled: bool, // Enable symbol screening for scalping
+    pub screening_exchange: Exchange, // Exchange whose subscribed symbols are screened
     pub screening_interval_seconds: u64, // Interval between screening runs (seconds)
     pub screening_cache_ttl_seconds: u64, // Cache TTL for screening results (seconds)
     pub screening_score_threshold: f64, // Minimum overall score to consider (0.0-1.0)
@@ -26,6 +70,17 @@ pub struct TradingConfig {
     pub screening_volume_weight: f64, // Weight for volume in score aggregation
     pub screening_spread_weight: f64, // Weight for spread in score aggregation
     pub screening_momentum_weight: f64, // Weight for momentum in score aggregation
+    pub screening_depth_weight: f64, // Weight for book depth near the mid (0 = off)
+    pub screening_depth_bps: f64, // Band around the mid counted as depth, in bps
+    pub screening_trade_rate_weight: f64, // Weight for trades per minute (0 = off)
+    pub screening_funding_weight: f64, // Weight for low perp funding (0 = off)
+    pub screening_correlation_weight: f64, // Weight for low correlation to BTC (0 = off)
+
+    // Dynamic symbol universe configuration
+    pub dynamic_universe_enabled: bool, // Subscribe to top screened markets beyond `symbols`
+    pub dynamic_universe_exchanges: Vec<Exchange>, // Exchanges whose market lists are screened
+    pub dynamic_universe_interval_seconds: u64, // How often market lists are screened
+    pub dynamic_universe: UniverseParams, // Top N and hysteresis before a symbol is dropped
 
     // Portfolio reconciliation configuration
     pub reconciliation_enabled: bool, // Enable portfolio reconciliation
@@ -33,6 +88,13 @@ pub struct TradingConfig {
     pub reconciliation_threshold_percentage: f64, // Threshold for flagging discrepancies (percentage)
     pub reconciliation_timeout_milliseconds: u64, // API call timeout (milliseconds)
     pub reconciliation_max_retries: u32,          // Maximum number of retries on failure
+    pub reconciliation_remediation: RemediationPolicy, // Action per discrepancy severity
+    pub position_reconciliation: PositionReconciliationPolicy, // Automatic adoption of orphans and closing of ghosts
+
+    // Realized PnL accounting
+    pub pnl_lot_matching: LotMatching, // Which open lots a closing fill consumes first
+    pub pnl_ledger_days: u32, // Days of trades and funding replayed into the ledger at startup, 0 for all
+    pub funding_sync_interval_seconds: u64, // How often dYdX funding payments are pulled into the ledger
 }
 
 impl TradingConfig {
@@ -109,8 +171,38 @@ impl TradingConfig {
             max_trades_per_day: 50,                  // 50 trades par jour max
             max_slippage_percent: 0.002,             // 0.2% maximum slippage protection
 
+            // Time-based exits are disabled unless configured
+            time_exit: TimeExitRule::disabled(),
+            time_exit_by_symbol: HashMap::new(),
+            time_exit_by_strategy: HashMap::new(),
+
+            // Fixed percentage sizing unless a risk-based mode is configured
+            sizing_mode: SizingMode::FixedPercentage,
+            sizing_mode_by_strategy: HashMap::new(),
+            sizing_params: SizingParams::default(),
+
+            // Built-in strategy set unless a registry file is configured
+            strategy_config_path: None,
+
+            // Market making is opt-in
+            market_making_enabled: false,
+            market_making_symbols: Vec::new(),
+            market_making_interval_seconds: 5,
+            market_making: MarketMakingParams::default(),
+            pairs_trading_enabled: false,
+            pairs_trading_pairs: Vec::new(),
+            pairs_trading_interval_seconds: 60,
+            pairs_trading: PairsParams::default(),
+            arbitrage_enabled: false,
+            arbitrage_execute: false,
+            arbitrage_symbols: Vec::new(),
+            arbitrage_interval_seconds: 5,
+            arbitrage_cooldown_seconds: 60,
+            arbitrage: ArbitrageParams::default(),
+
             // Symbol screening defaults
             screening_enabled: true,
+            screening_exchange: Exchange::Coinbase,
             screening_interval_seconds: 60, // Screen every 60 seconds
             screening_cache_ttl_seconds: 300, // 5 minute cache TTL
             screening_score_threshold: 0.50, // Minimum 0.50 score
@@ -118,6 +210,17 @@ impl TradingConfig {
             screening_volume_weight: 0.3,   // 30% weight
             screening_spread_weight: 0.2,   // 20% weight
             screening_momentum_weight: 0.2, // 20% weight
+            screening_depth_weight: 0.0,
+            screening_depth_bps: 10.0,
+            screening_trade_rate_weight: 0.0,
+            screening_funding_weight: 0.0,
+            screening_correlation_weight: 0.0,
+
+            // Dynamic symbol universe defaults
+            dynamic_universe_enabled: false,
+            dynamic_universe_exchanges: vec![Exchange::Dydx],
+            dynamic_universe_interval_seconds: 300,
+            dynamic_universe: UniverseParams::default(),
 
             // Portfolio reconciliation defaults
             reconciliation_enabled: true,
@@ -125,6 +228,12 @@ impl TradingConfig {
             reconciliation_threshold_percentage: 0.01, // 1% threshold
             reconciliation_timeout_milliseconds: 10000, // 10 second timeout
             reconciliation_max_retries: 3,        // 3 retries
+            reconciliation_remediation: RemediationPolicy::default(),
+            position_reconciliation: PositionReconciliationPolicy::default(),
+
+            pnl_lot_matching: LotMatching::Fifo,
+            pnl_ledger_days: 365,
+            funding_sync_interval_seconds: 3600, // dYdX settles funding hourly
         }
     }
 
@@ -228,12 +337,339 @@ impl TradingConfig {
             }
         }
 
+        // Time-based exit configuration from environment
+        if let Ok(max_holding) = std::env::var("MAX_HOLDING_MINUTES") {
+            if let Ok(value) = max_holding.parse::<i64>() {
+                if value > 0 && value <= 7 * 24 * 60 {
+                    config.time_exit.max_holding = Some(chrono::Duration::minutes(value));
+                }
+            }
+        }
+
+        if let Ok(no_progress) = std::env::var("NO_PROGRESS_MINUTES") {
+            if let Ok(value) = no_progress.parse::<i64>() {
+                if value > 0 && value <= 7 * 24 * 60 {
+                    config.time_exit.no_progress_after = Some(chrono::Duration::minutes(value));
+                }
+            }
+        }
+
+        if let Ok(min_progress) = std::env::var("NO_PROGRESS_MIN_PERCENTAGE") {
+            if let Ok(value) = min_progress.parse::<f64>() {
+                if (0.0..=0.1).contains(&value) {
+                    config.time_exit.min_progress_pct = value;
+                }
+            }
+        }
+
+        if let Ok(overrides) = std::env::var("TIME_EXIT_BY_SYMBOL") {
+            config.time_exit_by_symbol =
+                Self::parse_time_exit_overrides(&overrides, &config.time_exit)
+                    .into_iter()
+                    .map(|(symbol, rule)| (Self::normalize_symbol(&symbol), rule))
+                    .collect();
+        }
+
+        if let Ok(overrides) = std::env::var("TIME_EXIT_BY_STRATEGY") {
+            config.time_exit_by_strategy =
+                Self::parse_time_exit_overrides(&overrides, &config.time_exit);
+        }
+
+        // Position sizing configuration from environment
+        if let Ok(mode) = std::env::var("POSITION_SIZING_MODE") {
+            match SizingMode::parse(&mode) {
+                Ok(mode) => config.sizing_mode = mode,
+                Err(e) => tracing::warn!("Ignoring POSITION_SIZING_MODE: {}", e),
+            }
+        }
+
+        if let Ok(overrides) = std::env::var("POSITION_SIZING_BY_STRATEGY") {
+            config.sizing_mode_by_strategy = Self::parse_sizing_mode_overrides(&overrides);
+        }
+
+        if let Ok(risk) = std::env::var("RISK_PER_TRADE") {
+            if let Ok(value) = risk.parse::<f64>() {
+                if (0.0005..=0.05).contains(&value) {
+                    config.sizing_params.risk_per_trade = value;
+                }
+            }
+        }
+
+        if let Ok(multiple) = std::env::var("ATR_STOP_MULTIPLE") {
+            if let Ok(value) = multiple.parse::<f64>() {
+                if (0.5..=10.0).contains(&value) {
+                    config.sizing_params.atr_multiple = value;
+                }
+            }
+        }
+
+        if let Ok(period) = std::env::var("ATR_PERIOD") {
+            if let Ok(value) = period.parse::<usize>() {
+                if (2..=100).contains(&value) {
+                    config.sizing_params.atr_period = value;
+                }
+            }
+        }
+
+        if let Ok(target) = std::env::var("TARGET_ANNUAL_VOLATILITY") {
+            if let Ok(value) = target.parse::<f64>() {
+                if (0.01..=2.0).contains(&value) {
+                    config.sizing_params.target_annual_volatility = value;
+                }
+            }
+        }
+
+        if let Ok(fraction) = std::env::var("KELLY_FRACTION") {
+            if let Ok(value) = fraction.parse::<f64>() {
+                if (0.05..=1.0).contains(&value) {
+                    config.sizing_params.kelly_fraction = value;
+                }
+            }
+        }
+
+        if let Ok(cap) = std::env::var("KELLY_CAP") {
+            if let Ok(value) = cap.parse::<f64>() {
+                if (0.005..=0.5).contains(&value) {
+                    config.sizing_params.kelly_cap = value;
+                }
+            }
+        }
+
+        if let Ok(min_trades) = std::env::var("KELLY_MIN_TRADES") {
+            if let Ok(value) = min_trades.parse::<u32>() {
+                if (1..=1000).contains(&value) {
+                    config.sizing_params.kelly_min_trades = value;
+                }
+            }
+        }
+
+        if let Ok(max_fraction) = std::env::var("MAX_POSITION_FRACTION") {
+            if let Ok(value) = max_fraction.parse::<f64>() {
+                if (0.01..=1.0).contains(&value) {
+                    config.sizing_params.max_position_fraction = value;
+                }
+            }
+        }
+
+        if let Ok(path) = std::env::var("STRATEGY_CONFIG_PATH") {
+            if !path.trim().is_empty() {
+                config.strategy_config_path = Some(path.trim().to_string());
+            }
+        }
+
+        // Market making configuration from environment
+        if let Ok(enabled) = std::env::var("MARKET_MAKING_ENABLED") {
+            config.market_making_enabled = enabled.to_lowercase() == "true" || enabled == "1";
+        }
+
+        if let Ok(symbols) = std::env::var("MARKET_MAKING_SYMBOLS") {
+            config.market_making_symbols = symbols
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(Self::normalize_symbol)
+                .collect();
+        }
+
+        if let Ok(interval) = std::env::var("MARKET_MAKING_INTERVAL_SECONDS") {
+            if let Ok(value) = interval.parse::<u64>() {
+                if (1..=300).contains(&value) {
+                    config.market_making_interval_seconds = value;
+                }
+            }
+        }
+
+        let mut params = config.market_making.clone();
+        for (name, field) in [
+            ("MARKET_MAKING_BASE_SPREAD_BPS", &mut params.base_spread_bps),
+            ("MARKET_MAKING_MIN_SPREAD_BPS", &mut params.min_spread_bps),
+            ("MARKET_MAKING_MAX_SPREAD_BPS", &mut params.max_spread_bps),
+            (
+                "MARKET_MAKING_VOLATILITY_MULTIPLIER",
+                &mut params.volatility_multiplier,
+            ),
+            ("MARKET_MAKING_ORDER_SIZE", &mut params.order_size),
+            ("MARKET_MAKING_MAX_INVENTORY", &mut params.max_inventory),
+            ("MARKET_MAKING_INVENTORY_SKEW", &mut params.inventory_skew),
+            (
+                "MARKET_MAKING_REFRESH_BPS",
+                &mut params.refresh_threshold_bps,
+            ),
+            ("MARKET_MAKING_TICK_SIZE", &mut params.tick_size),
+        ] {
+            if let Ok(value) = std::env::var(name) {
+                match value.parse::<f64>() {
+                    Ok(value) => *field = value,
+                    Err(e) => tracing::warn!("Failed to parse {} '{}': {}", name, value, e),
+                }
+            }
+        }
+        match params.validate() {
+            Ok(()) => config.market_making = params,
+            Err(e) => tracing::warn!("Ignoring market making settings: {}", e),
+        }
+
+        // Pairs trading configuration from environment
+        if let Ok(enabled) = std::env::var("PAIRS_TRADING_ENABLED") {
+            config.pairs_trading_enabled = enabled.to_lowercase() == "true" || enabled == "1";
+        }
+
+        if let Ok(pairs) = std::env::var("PAIRS_TRADING_PAIRS") {
+            config.pairs_trading_pairs = pairs
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .filter_map(|pair| match pair.split_once(':') {
+                    Some((base, hedge))
+                        if !base.trim().is_empty()
+                            && !hedge.trim().is_empty()
+                            && base.trim() != hedge.trim() =>
+                    {
+                        Some((
+                            Self::normalize_symbol(base.trim()),
+                            Self::normalize_symbol(hedge.trim()),
+                        ))
+                    }
+                    _ => {
+                        tracing::warn!("Ignoring pair '{}', expected BASE:HEDGE", pair);
+                        None
+                    }
+                })
+                .collect();
+        }
+
+        if let Ok(interval) = std::env::var("PAIRS_TRADING_INTERVAL_SECONDS") {
+            if let Ok(value) = interval.parse::<u64>() {
+                if (1..=3600).contains(&value) {
+                    config.pairs_trading_interval_seconds = value;
+                }
+            }
+        }
+
+        let mut params = config.pairs_trading.clone();
+        if let Ok(value) = std::env::var("PAIRS_TRADING_LOOKBACK") {
+            match value.parse::<usize>() {
+                Ok(lookback) => params.lookback = lookback,
+                Err(e) => {
+                    tracing::warn!("Failed to parse PAIRS_TRADING_LOOKBACK '{}': {}", value, e)
+                }
+            }
+        }
+        for (name, field) in [
+            ("PAIRS_TRADING_ENTRY_ZSCORE", &mut params.entry_zscore),
+            ("PAIRS_TRADING_EXIT_ZSCORE", &mut params.exit_zscore),
+            ("PAIRS_TRADING_STOP_ZSCORE", &mut params.stop_zscore),
+            ("PAIRS_TRADING_NOTIONAL", &mut params.notional),
+        ] {
+            if let Ok(value) = std::env::var(name) {
+                match value.parse::<f64>() {
+                    Ok(value) => *field = value,
+                    Err(e) => tracing::warn!("Failed to parse {} '{}': {}", name, value, e),
+                }
+            }
+        }
+        match params.validate() {
+            Ok(()) => config.pairs_trading = params,
+            Err(e) => tracing::warn!("Ignoring pairs trading settings: {}", e),
+        }
+
+        // Cross-exchange arbitrage configuration from environment
+        if let Ok(enabled) = std::env::var("ARBITRAGE_ENABLED") {
+            config.arbitrage_enabled = enabled.to_lowercase() == "true" || enabled == "1";
+        }
+
+        if let Ok(execute) = std::env::var("ARBITRAGE_EXECUTE") {
+            config.arbitrage_execute = execute.to_lowercase() == "true" || execute == "1";
+        }
+
+        if let Ok(symbols) = std::env::var("ARBITRAGE_SYMBOLS") {
+            config.arbitrage_symbols = symbols
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(Self::normalize_symbol)
+                .collect();
+        }
+
+        if let Ok(interval) = std::env::var("ARBITRAGE_INTERVAL_SECONDS") {
+            if let Ok(value) = interval.parse::<u64>() {
+                if (1..=300).contains(&value) {
+                    config.arbitrage_interval_seconds = value;
+                }
+            }
+        }
+
+        if let Ok(cooldown) = std::env::var("ARBITRAGE_COOLDOWN_SECONDS") {
+            if let Ok(value) = cooldown.parse::<u64>() {
+                config.arbitrage_cooldown_seconds = value;
+            }
+        }
+
+        let mut params = config.arbitrage.clone();
+        for (name, field) in [
+            (
+                "ARBITRAGE_MIN_NET_SPREAD_BPS",
+                &mut params.min_net_spread_bps,
+            ),
+            ("ARBITRAGE_DEFAULT_FEE_BPS", &mut params.default_fee_bps),
+            ("ARBITRAGE_PERP_BASIS_BPS", &mut params.perp_basis_bps),
+            ("ARBITRAGE_ORDER_NOTIONAL", &mut params.order_notional),
+        ] {
+            if let Ok(value) = std::env::var(name) {
+                match value.parse::<f64>() {
+                    Ok(value) => *field = value,
+                    Err(e) => tracing::warn!("Failed to parse {} '{}': {}", name, value, e),
+                }
+            }
+        }
+        // Format: "coinbase:60,dydx:5"
+        if let Ok(fees) = std::env::var("ARBITRAGE_FEES_BPS") {
+            for entry in fees.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                let parsed = entry.split_once(':').and_then(|(exchange, fee)| {
+                    Some((
+                        Exchange::from_name(exchange)?,
+                        fee.trim().parse::<f64>().ok()?,
+                    ))
+                });
+                match parsed {
+                    Some((exchange, fee)) => {
+                        params.fee_bps.insert(exchange, fee);
+                    }
+                    None => {
+                        tracing::warn!("Ignoring arbitrage fee '{}', expected exchange:bps", entry)
+                    }
+                }
+            }
+        }
+        match params.validate() {
+            Ok(()) => config.arbitrage = params,
+            Err(e) => tracing::warn!("Ignoring arbitrage settings: {}", e),
+        }
+
         // Symbol screening configuration from environment
         if let Ok(screening_enabled) = std::env::var("SCREENING_ENABLED") {
             config.screening_enabled =
                 screening_enabled.to_lowercase() == "true" || screening_enabled == "1";
         }
 
+        if let Ok(screening_exchange) = std::env::var("SCREENING_EXCHANGE") {
+            match Exchange::from_name(&screening_exchange) {
+                Some(exchange) => {
+                    if !exchange.has_best_quote() {
+                        tracing::warn!(
+                            "SCREENING_EXCHANGE '{}' publishes no best bid/ask, so every symbol scores no spread",
+                            screening_exchange
+                        );
+                    }
+                    config.screening_exchange = exchange;
+                }
+                None => tracing::warn!(
+                    "Ignoring unknown SCREENING_EXCHANGE '{}'",
+                    screening_exchange
+                ),
+            }
+        }
+
         if let Ok(screening_interval) = std::env::var("SCREENING_INTERVAL_SECONDS") {
             if let Ok(value) = screening_interval.parse::<u64>() {
                 if value >= 10 && value <= 3600 {
@@ -290,6 +726,92 @@ impl TradingConfig {
             }
         }
 
+        for (name, field) in [
+            ("SCREENING_DEPTH_WEIGHT", &mut config.screening_depth_weight),
+            (
+                "SCREENING_TRADE_RATE_WEIGHT",
+                &mut config.screening_trade_rate_weight,
+            ),
+            (
+                "SCREENING_FUNDING_WEIGHT",
+                &mut config.screening_funding_weight,
+            ),
+            (
+                "SCREENING_CORRELATION_WEIGHT",
+                &mut config.screening_correlation_weight,
+            ),
+        ] {
+            if let Ok(value) = std::env::var(name) {
+                match value.parse::<f64>() {
+                    Ok(value) if (0.0..=1.0).contains(&value) => *field = value,
+                    _ => tracing::warn!("Ignoring {} '{}': expected 0.0-1.0", name, value),
+                }
+            }
+        }
+
+        if let Ok(depth_bps) = std::env::var("SCREENING_DEPTH_BPS") {
+            match depth_bps.parse::<f64>() {
+                Ok(value) if value > 0.0 && value <= 1000.0 => config.screening_depth_bps = value,
+                _ => tracing::warn!(
+                    "Ignoring SCREENING_DEPTH_BPS '{}': expected 0-1000",
+                    depth_bps
+                ),
+            }
+        }
+
+        // Dynamic symbol universe configuration from environment
+        if let Ok(enabled) = std::env::var("DYNAMIC_UNIVERSE_ENABLED") {
+            config.dynamic_universe_enabled = enabled.to_lowercase() == "true" || enabled == "1";
+        }
+
+        if let Ok(exchanges) = std::env::var("DYNAMIC_UNIVERSE_EXCHANGES") {
+            let mut parsed = Vec::new();
+            for name in exchanges
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+            {
+                match Exchange::from_name(name) {
+                    Some(exchange) if !parsed.contains(&exchange) => parsed.push(exchange),
+                    Some(_) => {}
+                    None => tracing::warn!("Ignoring unknown universe exchange '{}'", name),
+                }
+            }
+            config.dynamic_universe_exchanges = parsed;
+        }
+
+        if let Ok(interval) = std::env::var("DYNAMIC_UNIVERSE_INTERVAL_SECONDS") {
+            if let Ok(value) = interval.parse::<u64>() {
+                if (60..=86400).contains(&value) {
+                    config.dynamic_universe_interval_seconds = value;
+                }
+            }
+        }
+
+        let mut params = config.dynamic_universe.clone();
+        if let Ok(top_n) = std::env::var("DYNAMIC_UNIVERSE_TOP_N") {
+            match top_n.parse::<usize>() {
+                Ok(value) => params.top_n = value,
+                Err(e) => {
+                    tracing::warn!("Failed to parse DYNAMIC_UNIVERSE_TOP_N '{}': {}", top_n, e)
+                }
+            }
+        }
+        if let Ok(minutes) = std::env::var("DYNAMIC_UNIVERSE_HYSTERESIS_MINUTES") {
+            match minutes.parse::<i64>() {
+                Ok(value) => params.hysteresis = chrono::Duration::minutes(value),
+                Err(e) => tracing::warn!(
+                    "Failed to parse DYNAMIC_UNIVERSE_HYSTERESIS_MINUTES '{}': {}",
+                    minutes,
+                    e
+                ),
+            }
+        }
+        match params.validate() {
+            Ok(()) => config.dynamic_universe = params,
+            Err(e) => tracing::warn!("Ignoring dynamic universe settings: {}", e),
+        }
+
         // Portfolio reconciliation configuration from environment
         if let Ok(reconciliation_enabled) = std::env::var("RECONCILIATION_ENABLED") {
             config.reconciliation_enabled =
@@ -328,9 +850,169 @@ impl TradingConfig {
             }
         }
 
+        for (name, action) in [
+            (
+                "RECONCILIATION_REMEDIATION_MINOR",
+                &mut config.reconciliation_remediation.minor,
+            ),
+            (
+                "RECONCILIATION_REMEDIATION_MAJOR",
+                &mut config.reconciliation_remediation.major,
+            ),
+            (
+                "RECONCILIATION_REMEDIATION_CRITICAL",
+                &mut config.reconciliation_remediation.critical,
+            ),
+        ] {
+            if let Ok(value) = std::env::var(name) {
+                match RemediationAction::from_name(&value) {
+                    Some(parsed) => *action = parsed,
+                    None => tracing::warn!(
+                        "Ignoring {}='{}': expected adopt, pause or acknowledge",
+                        name,
+                        value
+                    ),
+                }
+            }
+        }
+
+        if let Ok(value) = std::env::var("RECONCILIATION_ADOPT_ORPHAN_POSITIONS") {
+            config.position_reconciliation.adopt_orphans =
+                value.to_lowercase() == "true" || value == "1";
+        }
+        if let Ok(value) = std::env::var("RECONCILIATION_CLOSE_GHOST_POSITIONS") {
+            config.position_reconciliation.close_ghosts =
+                value.to_lowercase() == "true" || value == "1";
+        }
+
+        if let Ok(value) = std::env::var("PNL_LOT_MATCHING") {
+            match LotMatching::from_name(&value) {
+                Some(matching) => config.pnl_lot_matching = matching,
+                None => tracing::warn!(
+                    "Ignoring PNL_LOT_MATCHING='{}': expected fifo or lifo",
+                    value
+                ),
+            }
+        }
+
+        if let Ok(value) = std::env::var("PNL_LEDGER_DAYS") {
+            if let Ok(value) = value.parse::<u32>() {
+                config.pnl_ledger_days = value;
+            }
+        }
+
+        if let Ok(value) = std::env::var("FUNDING_SYNC_INTERVAL_SECONDS") {
+            if let Ok(value) = value.parse::<u64>() {
+                if (60..=86400).contains(&value) {
+                    config.funding_sync_interval_seconds = value;
+                }
+            }
+        }
+
         config
     }
 
+    /// Parse time-exit overrides of the form `KEY:max_minutes[:no_progress_minutes],...`
+    ///
+    /// A value of 0 disables that part of the rule. `min_progress_pct` is inherited
+    /// from `base`. Malformed entries are skipped with a warning.
+    pub fn parse_time_exit_overrides(
+        spec: &str,
+        base: &TimeExitRule,
+    ) -> HashMap<String, TimeExitRule> {
+        let to_duration = |minutes: i64| (minutes > 0).then(|| chrono::Duration::minutes(minutes));
+        let mut overrides = HashMap::new();
+
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let parts: Vec<&str> = entry.split(':').map(str::trim).collect();
+            let parsed = match parts.as_slice() {
+                [key, max] => max.parse::<i64>().ok().map(|max| (key, max, None)),
+                [key, max, no_progress] => match (max.parse::<i64>(), no_progress.parse::<i64>()) {
+                    (Ok(max), Ok(no_progress)) => Some((key, max, Some(no_progress))),
+                    _ => None,
+                },
+                _ => None,
+            };
+
+            match parsed {
+                Some((key, max, no_progress)) if !key.is_empty() => {
+                    overrides.insert(
+                        key.to_string(),
+                        TimeExitRule {
+                            max_holding: to_duration(max),
+                            no_progress_after: match no_progress {
+                                Some(minutes) => to_duration(minutes),
+                                None => base.no_progress_after,
+                            },
+                            min_progress_pct: base.min_progress_pct,
+                        },
+                    );
+                }
+                _ => {
+                    tracing::warn!("Ignoring malformed time-exit override '{}'", entry);
+                }
+            }
+        }
+
+        overrides
+    }
+
+    /// Resolve the time-exit rule for a position
+    ///
+    /// Symbol overrides win over strategy overrides, which win over the default rule.
+    pub fn time_exit_rule_for(&self, symbol: &str, strategy: Option<&str>) -> TimeExitRule {
+        if let Some(rule) = self
+            .time_exit_by_symbol
+            .get(&Self::normalize_symbol(symbol))
+        {
+            return *rule;
+        }
+
+        if let Some(rule) = strategy.and_then(|name| self.time_exit_by_strategy.get(name)) {
+            return *rule;
+        }
+
+        self.time_exit
+    }
+
+    /// Parse per-strategy sizing modes of the form `Strategy:mode,...`
+    ///
+    /// Malformed entries and unknown modes are skipped with a warning.
+    pub fn parse_sizing_mode_overrides(spec: &str) -> HashMap<String, SizingMode> {
+        let mut overrides = HashMap::new();
+
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match entry.split_once(':') {
+                Some((key, mode)) if !key.trim().is_empty() => match SizingMode::parse(mode) {
+                    Ok(mode) => {
+                        overrides.insert(key.trim().to_string(), mode);
+                    }
+                    Err(e) => tracing::warn!("Ignoring sizing override '{}': {}", entry, e),
+                },
+                _ => tracing::warn!("Ignoring malformed sizing override '{}'", entry),
+            }
+        }
+
+        overrides
+    }
+
+    /// Resolve the sizing mode and parameters for a strategy
+    ///
+    /// Strategy overrides win over the default mode. The fixed-percentage share
+    /// always follows `portfolio_percentage_per_position`.
+    pub fn sizing_for(&self, strategy: Option<&str>) -> (SizingMode, SizingParams) {
+        let mode = strategy
+            .and_then(|name| self.sizing_mode_by_strategy.get(name))
+            .copied()
+            .unwrap_or(self.sizing_mode);
+        let params = SizingParams {
+            portfolio_fraction: self.portfolio_percentage_per_position,
+            ..self.sizing_params.clone()
+        };
+
+        (mode, params)
+    }
+
     /// Get all unique normalized symbols (BTC-USD format)
 
     pub fn get_normalized_symbols(&self) -> Vec<String> {
@@ -393,6 +1075,82 @@ mod tests {
         assert_eq!(TradingConfig::normalize_symbol("ETH"), "ETH-USD");
     }
 
+    #[test]
+    fn test_parse_time_exit_overrides() {
+        let base = TimeExitRule {
+            max_holding: Some(chrono::Duration::minutes(60)),
+            no_progress_after: Some(chrono::Duration::minutes(20)),
+            min_progress_pct: 0.001,
+        };
+        let overrides = TradingConfig::parse_time_exit_overrides(
+            "FastScalping:10:3, Momentum:0, bad, X:y",
+            &base,
+        );
+
+        assert_eq!(overrides.len(), 2);
+        let fast = overrides.get("FastScalping").unwrap();
+        assert_eq!(fast.max_holding, Some(chrono::Duration::minutes(10)));
+        assert_eq!(fast.no_progress_after, Some(chrono::Duration::minutes(3)));
+        assert_eq!(fast.min_progress_pct, 0.001);
+
+        let momentum = overrides.get("Momentum").unwrap();
+        assert_eq!(momentum.max_holding, None);
+        assert_eq!(momentum.no_progress_after, base.no_progress_after);
+    }
+
+    #[test]
+    fn test_time_exit_rule_resolution() {
+        let mut config = TradingConfig::default();
+        config.time_exit.max_holding = Some(chrono::Duration::minutes(60));
+        config.time_exit_by_strategy.insert(
+            "FastScalping".to_string(),
+            TimeExitRule {
+                max_holding: Some(chrono::Duration::minutes(10)),
+                ..TimeExitRule::disabled()
+            },
+        );
+        config.time_exit_by_symbol.insert(
+            "SOL-USD".to_string(),
+            TimeExitRule {
+                max_holding: Some(chrono::Duration::minutes(5)),
+                ..TimeExitRule::disabled()
+            },
+        );
+
+        let minutes = |rule: TimeExitRule| rule.max_holding.map(|d| d.num_minutes());
+        assert_eq!(
+            minutes(config.time_exit_rule_for("BTC-USD", None)),
+            Some(60)
+        );
+        assert_eq!(
+            minutes(config.time_exit_rule_for("BTC-USD", Some("FastScalping"))),
+            Some(10)
+        );
+        assert_eq!(
+            minutes(config.time_exit_rule_for("SOL/USD", Some("FastScalping"))),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn test_sizing_mode_resolution() {
+        let mut config = TradingConfig::default();
+        config.sizing_mode_by_strategy = TradingConfig::parse_sizing_mode_overrides(
+            "FastScalping:atr, RSI:kelly, Bad:martingale, :x",
+        );
+
+        assert_eq!(config.sizing_mode_by_strategy.len(), 2);
+        assert_eq!(config.sizing_for(None).0, SizingMode::FixedPercentage);
+        assert_eq!(
+            config.sizing_for(Some("FastScalping")).0,
+            SizingMode::AtrScaled
+        );
+        assert_eq!(config.sizing_for(Some("RSI")).0, SizingMode::Kelly);
+
+        config.portfolio_percentage_per_position = 0.05;
+        assert_eq!(config.sizing_for(None).1.portfolio_fraction, 0.05);
+    }
+
     #[test]
     fn test_get_normalized_symbols() {
         let config = TradingConfig::default();
@@ -0,0 +1,667 @@
+//! Bracket and OCO Orders
+//!
+//! A bracket links an entry `Order` to a stop-loss and a take-profit exit leg.
+//! An OCO ("one cancels the other") is the same structure without an entry leg,
+//! used to protect a position that is already open.
+//!
+//! Exit legs are either placed natively on the exchange (dYdX conditional
+//! orders, Coinbase `trigger_bracket_gtc`) or emulated locally: the legs stay
+//! `Pending` and the service fires a market exit when the price crosses a
+//! trigger. In both cases a fill on one exit leg cancels its sibling.
+
+use crate::domain::entities::order::{Order, OrderSide, OrderType};
+use crate::domain::entities::position::ExitReason;
+use chrono::{DateTime, Utc};
+
+/// Role of a leg inside a bracket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BracketLegRole {
+    Entry,
+    StopLoss,
+    TakeProfit,
+}
+
+impl BracketLegRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BracketLegRole::Entry => "entry",
+            BracketLegRole::StopLoss => "stop_loss",
+            BracketLegRole::TakeProfit => "take_profit",
+        }
+    }
+
+    /// Exit reason recorded when this leg closes the position
+    pub fn exit_reason(&self) -> Option<ExitReason> {
+        match self {
+            BracketLegRole::Entry => None,
+            BracketLegRole::StopLoss => Some(ExitReason::StopLoss),
+            BracketLegRole::TakeProfit => Some(ExitReason::TakeProfit),
+        }
+    }
+}
+
+/// Separates the exchange order id from the role in a per-leg order id
+const LEG_ID_SEPARATOR: char = '#';
+
+/// Id of one exit leg of a bracket the exchange holds as a single order
+pub fn leg_order_id(order_id: &str, role: BracketLegRole) -> String {
+    format!("{}{}{}", order_id, LEG_ID_SEPARATOR, role.as_str())
+}
+
+/// Exchange order id and leg role of an id built by `leg_order_id`
+///
+/// Ids of ordinary orders come back unchanged with no role.
+pub fn split_leg_order_id(id: &str) -> (&str, Option<BracketLegRole>) {
+    let Some((order_id, role)) = id.rsplit_once(LEG_ID_SEPARATOR) else {
+        return (id, None);
+    };
+    let role = match role {
+        "stop_loss" => BracketLegRole::StopLoss,
+        "take_profit" => BracketLegRole::TakeProfit,
+        _ => return (id, None),
+    };
+    (order_id, Some(role))
+}
+
+impl std::fmt::Display for BracketLegRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Lifecycle state of a single leg
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BracketLegState {
+    /// Not yet on the exchange (emulated legs stay here until triggered)
+    Pending,
+    /// Resting on the exchange
+    Working,
+    Filled,
+    Cancelled,
+    Rejected,
+}
+
+impl BracketLegState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BracketLegState::Pending => "pending",
+            BracketLegState::Working => "working",
+            BracketLegState::Filled => "filled",
+            BracketLegState::Cancelled => "cancelled",
+            BracketLegState::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "pending" => Ok(BracketLegState::Pending),
+            "working" => Ok(BracketLegState::Working),
+            "filled" => Ok(BracketLegState::Filled),
+            "cancelled" => Ok(BracketLegState::Cancelled),
+            "rejected" => Ok(BracketLegState::Rejected),
+            other => Err(format!("Unknown bracket leg state: {}", other)),
+        }
+    }
+
+    /// Whether the leg can no longer change
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            BracketLegState::Filled | BracketLegState::Cancelled | BracketLegState::Rejected
+        )
+    }
+}
+
+/// How the exit legs are linked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BracketLinking {
+    /// The exchange holds both exit legs and enforces OCO itself
+    Native,
+    /// Exit legs are watched locally and sent as market orders when triggered
+    Emulated,
+}
+
+impl BracketLinking {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BracketLinking::Native => "native",
+            BracketLinking::Emulated => "emulated",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "native" => Ok(BracketLinking::Native),
+            "emulated" => Ok(BracketLinking::Emulated),
+            other => Err(format!("Unknown bracket linking: {}", other)),
+        }
+    }
+}
+
+/// A single leg of a bracket
+#[derive(Debug, Clone)]
+pub struct BracketLeg {
+    pub role: BracketLegRole,
+    /// Trigger price for exit legs, limit price (if any) for the entry leg
+    pub price: Option<f64>,
+    pub exchange_order_id: Option<String>,
+    pub state: BracketLegState,
+}
+
+impl BracketLeg {
+    fn new(role: BracketLegRole, price: Option<f64>) -> Self {
+        Self {
+            role,
+            price,
+            exchange_order_id: None,
+            state: BracketLegState::Pending,
+        }
+    }
+}
+
+/// Exchange order ids returned when exit legs are placed natively
+///
+/// Exchanges that model a bracket as a single order (Coinbase) return
+/// per-leg ids of that order (see `single_order`), so each leg reports its
+/// own fill.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BracketExitIds {
+    pub stop_loss: String,
+    pub take_profit: String,
+}
+
+impl BracketExitIds {
+    /// Per-leg ids of a bracket held as the single order `order_id`
+    pub fn single_order(order_id: &str) -> Self {
+        Self {
+            stop_loss: leg_order_id(order_id, BracketLegRole::StopLoss),
+            take_profit: leg_order_id(order_id, BracketLegRole::TakeProfit),
+        }
+    }
+}
+
+/// Entry order linked to stop-loss and take-profit exits
+#[derive(Debug, Clone)]
+pub struct BracketOrder {
+    pub id: String,
+    pub position_id: Option<String>,
+    pub symbol: String,
+    pub exchange: String,
+    /// Side of the exit legs (opposite of the entry side)
+    pub exit_side: OrderSide,
+    pub quantity: f64,
+    pub linking: BracketLinking,
+    pub entry: Option<BracketLeg>,
+    pub stop_loss: BracketLeg,
+    pub take_profit: BracketLeg,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BracketOrder {
+    /// Create a bracket around an entry order
+    ///
+    /// # Errors
+    /// Returns an error if the stop and take-profit are not on opposite sides
+    /// of the entry price (or of each other for market entries).
+    pub fn bracket(
+        id: String,
+        entry: &Order,
+        stop_price: f64,
+        take_profit_price: f64,
+    ) -> Result<Self, String> {
+        let exit_side = match entry.side {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        };
+        let entry_price = entry.price.map(|p| p.value());
+        Self::validate_prices(&exit_side, entry_price, stop_price, take_profit_price)?;
+
+        let mut bracket = Self::build(
+            id,
+            entry.symbol.clone(),
+            exit_side,
+            entry.quantity.value(),
+            stop_price,
+            take_profit_price,
+        );
+        bracket.entry = Some(BracketLeg::new(BracketLegRole::Entry, entry_price));
+        Ok(bracket)
+    }
+
+    /// Create an OCO pair protecting an existing position
+    ///
+    /// `exit_side` is the side that closes the position (`Sell` for longs).
+    pub fn oco(
+        id: String,
+        symbol: String,
+        exit_side: OrderSide,
+        quantity: f64,
+        stop_price: f64,
+        take_profit_price: f64,
+    ) -> Result<Self, String> {
+        if !quantity.is_finite() || quantity <= 0.0 {
+            return Err("OCO quantity must be positive".to_string());
+        }
+        Self::validate_prices(&exit_side, None, stop_price, take_profit_price)?;
+        Ok(Self::build(
+            id,
+            symbol,
+            exit_side,
+            quantity,
+            stop_price,
+            take_profit_price,
+        ))
+    }
+
+    fn build(
+        id: String,
+        symbol: String,
+        exit_side: OrderSide,
+        quantity: f64,
+        stop_price: f64,
+        take_profit_price: f64,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id,
+            position_id: None,
+            symbol,
+            exchange: "unknown".to_string(),
+            exit_side,
+            quantity,
+            linking: BracketLinking::Emulated,
+            entry: None,
+            stop_loss: BracketLeg::new(BracketLegRole::StopLoss, Some(stop_price)),
+            take_profit: BracketLeg::new(BracketLegRole::TakeProfit, Some(take_profit_price)),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn validate_prices(
+        exit_side: &OrderSide,
+        entry_price: Option<f64>,
+        stop_price: f64,
+        take_profit_price: f64,
+    ) -> Result<(), String> {
+        if !stop_price.is_finite()
+            || !take_profit_price.is_finite()
+            || stop_price <= 0.0
+            || take_profit_price <= 0.0
+        {
+            return Err("Bracket trigger prices must be positive".to_string());
+        }
+        // A long position exits with a sell: stop below, target above
+        let (low, high) = match exit_side {
+            OrderSide::Sell => (stop_price, take_profit_price),
+            OrderSide::Buy => (take_profit_price, stop_price),
+        };
+        if low >= high {
+            return Err(format!(
+                "Stop-loss {} and take-profit {} are on the wrong side of each other",
+                stop_price, take_profit_price
+            ));
+        }
+        if let Some(entry) = entry_price {
+            if entry <= low || entry >= high {
+                return Err(format!(
+                    "Entry price {} must lie between stop-loss {} and take-profit {}",
+                    entry, stop_price, take_profit_price
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn leg(&self, role: BracketLegRole) -> Option<&BracketLeg> {
+        match role {
+            BracketLegRole::Entry => self.entry.as_ref(),
+            BracketLegRole::StopLoss => Some(&self.stop_loss),
+            BracketLegRole::TakeProfit => Some(&self.take_profit),
+        }
+    }
+
+    fn leg_mut(&mut self, role: BracketLegRole) -> Option<&mut BracketLeg> {
+        match role {
+            BracketLegRole::Entry => self.entry.as_mut(),
+            BracketLegRole::StopLoss => Some(&mut self.stop_loss),
+            BracketLegRole::TakeProfit => Some(&mut self.take_profit),
+        }
+    }
+
+    /// Exit legs are armed once the entry is filled (or there is no entry)
+    pub fn is_entry_filled(&self) -> bool {
+        self.entry
+            .as_ref()
+            .map_or(true, |leg| leg.state == BracketLegState::Filled)
+    }
+
+    /// Whether the bracket still needs monitoring
+    pub fn is_active(&self) -> bool {
+        if let Some(entry) = &self.entry {
+            if entry.state.is_terminal() && entry.state != BracketLegState::Filled {
+                return false;
+            }
+        }
+        !self.stop_loss.state.is_terminal() || !self.take_profit.state.is_terminal()
+    }
+
+    /// Exit leg that closed the bracket, if any
+    pub fn filled_exit(&self) -> Option<BracketLegRole> {
+        [&self.stop_loss, &self.take_profit]
+            .into_iter()
+            .find(|leg| leg.state == BracketLegState::Filled)
+            .map(|leg| leg.role)
+    }
+
+    /// Link the entry order that opened the position an OCO protects
+    pub fn link_entry(&mut self, exchange_order_id: String, price: Option<f64>) {
+        let mut leg = BracketLeg::new(BracketLegRole::Entry, price);
+        leg.exchange_order_id = Some(exchange_order_id);
+        leg.state = BracketLegState::Working;
+        self.entry = Some(leg);
+        self.updated_at = Utc::now();
+    }
+
+    /// Record that a leg is resting on the exchange
+    pub fn mark_working(&mut self, role: BracketLegRole, exchange_order_id: String) {
+        if let Some(leg) = self.leg_mut(role) {
+            leg.exchange_order_id = Some(exchange_order_id);
+            leg.state = BracketLegState::Working;
+        }
+        self.updated_at = Utc::now();
+    }
+
+    /// Switch to native linking with the ids returned by the exchange
+    pub fn attach_native_exits(&mut self, ids: BracketExitIds) {
+        self.linking = BracketLinking::Native;
+        self.mark_working(BracketLegRole::StopLoss, ids.stop_loss);
+        self.mark_working(BracketLegRole::TakeProfit, ids.take_profit);
+    }
+
+    /// Record a fill and apply the OCO rule
+    ///
+    /// Filling an exit leg cancels its sibling. Returns the exchange order ids
+    /// that must be cancelled on the exchange; a sibling on the same exchange
+    /// order as the filled leg (single-order native brackets) needs no cancel.
+    pub fn record_fill(&mut self, role: BracketLegRole) -> Vec<String> {
+        let filled_id = match self.leg_mut(role) {
+            Some(leg) => {
+                leg.state = BracketLegState::Filled;
+                leg.exchange_order_id
+                    .as_deref()
+                    .map(|id| split_leg_order_id(id).0.to_string())
+            }
+            None => return Vec::new(),
+        };
+        self.updated_at = Utc::now();
+
+        let sibling = match role {
+            BracketLegRole::Entry => return Vec::new(),
+            BracketLegRole::StopLoss => &mut self.take_profit,
+            BracketLegRole::TakeProfit => &mut self.stop_loss,
+        };
+
+        let mut to_cancel = Vec::new();
+        if !sibling.state.is_terminal() {
+            if sibling.state == BracketLegState::Working {
+                if let Some(id) = &sibling.exchange_order_id {
+                    let order_id = split_leg_order_id(id).0;
+                    if filled_id.as_deref() != Some(order_id) {
+                        to_cancel.push(order_id.to_string());
+                    }
+                }
+            }
+            sibling.state = BracketLegState::Cancelled;
+        }
+        to_cancel
+    }
+
+    /// Cancel every open leg, returning the distinct exchange orders to cancel
+    pub fn cancel_all(&mut self) -> Vec<String> {
+        let mut to_cancel: Vec<String> = Vec::new();
+        let legs = self
+            .entry
+            .iter_mut()
+            .chain([&mut self.stop_loss, &mut self.take_profit]);
+        for leg in legs {
+            if leg.state.is_terminal() {
+                continue;
+            }
+            if leg.state == BracketLegState::Working {
+                if let Some(id) = &leg.exchange_order_id {
+                    let order_id = split_leg_order_id(id).0.to_string();
+                    if !to_cancel.contains(&order_id) {
+                        to_cancel.push(order_id);
+                    }
+                }
+            }
+            leg.state = BracketLegState::Cancelled;
+        }
+        self.updated_at = Utc::now();
+        to_cancel
+    }
+
+    /// Fall back to local emulation after the exchange dropped a native exit
+    ///
+    /// Both exits return to `Pending`. Returns the exchange ids of legs still
+    /// resting on the exchange, which must be cancelled.
+    pub fn revert_to_emulated(&mut self) -> Vec<String> {
+        let mut to_cancel: Vec<String> = Vec::new();
+        for leg in [&mut self.stop_loss, &mut self.take_profit] {
+            if let Some(id) = leg.exchange_order_id.take() {
+                let order_id = split_leg_order_id(&id).0.to_string();
+                if leg.state == BracketLegState::Working && !to_cancel.contains(&order_id) {
+                    to_cancel.push(order_id);
+                }
+            }
+            leg.state = BracketLegState::Pending;
+        }
+        self.linking = BracketLinking::Emulated;
+        self.updated_at = Utc::now();
+        to_cancel
+    }
+
+    /// Exit leg an emulated bracket should fire at `price`
+    ///
+    /// Always `None` for native brackets, before the entry fills, and once an
+    /// exit has already completed.
+    pub fn triggered_exit(&self, price: f64) -> Option<BracketLegRole> {
+        if self.linking != BracketLinking::Emulated || !self.is_entry_filled() {
+            return None;
+        }
+        if self.stop_loss.state.is_terminal() || self.take_profit.state.is_terminal() {
+            return None;
+        }
+        let stop = self.stop_loss.price?;
+        let target = self.take_profit.price?;
+        let (stop_hit, target_hit) = match self.exit_side {
+            OrderSide::Sell => (price <= stop, price >= target),
+            OrderSide::Buy => (price >= stop, price <= target),
+        };
+        if stop_hit {
+            Some(BracketLegRole::StopLoss)
+        } else if target_hit {
+            Some(BracketLegRole::TakeProfit)
+        } else {
+            None
+        }
+    }
+
+    /// Market order closing the position for an emulated exit leg
+    pub fn exit_order(&self, role: BracketLegRole) -> Result<Order, String> {
+        if role == BracketLegRole::Entry {
+            return Err("Entry leg is not an exit".to_string());
+        }
+        Order::new(
+            format!("{}_{}", self.id, role.as_str()),
+            self.symbol.clone(),
+            self.exit_side.clone(),
+            OrderType::Market,
+            None,
+            self.quantity,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long_bracket() -> BracketOrder {
+        let entry = Order::new(
+            "entry-1".to_string(),
+            "BTC-USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit,
+            Some(50000.0),
+            0.1,
+        )
+        .unwrap();
+        BracketOrder::bracket("br-1".to_string(), &entry, 49000.0, 52000.0).unwrap()
+    }
+
+    #[test]
+    fn test_bracket_validates_price_ordering() {
+        let entry = Order::new(
+            "entry-1".to_string(),
+            "BTC-USD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit,
+            Some(50000.0),
+            0.1,
+        )
+        .unwrap();
+        assert!(BracketOrder::bracket("b".to_string(), &entry, 52000.0, 49000.0).is_err());
+        assert!(BracketOrder::bracket("b".to_string(), &entry, 50500.0, 52000.0).is_err());
+
+        let short = BracketOrder::oco(
+            "o".to_string(),
+            "ETH-USD".to_string(),
+            OrderSide::Buy,
+            1.0,
+            3100.0,
+            2900.0,
+        );
+        assert!(short.is_ok());
+        assert!(matches!(short.unwrap().exit_side, OrderSide::Buy));
+    }
+
+    #[test]
+    fn test_exits_armed_only_after_entry_fill() {
+        let mut bracket = long_bracket();
+        assert_eq!(bracket.exit_side.to_string(), "SELL");
+        assert!(!bracket.is_entry_filled());
+        assert_eq!(bracket.triggered_exit(48000.0), None);
+
+        bracket.record_fill(BracketLegRole::Entry);
+        assert!(bracket.is_entry_filled());
+        assert_eq!(
+            bracket.triggered_exit(48900.0),
+            Some(BracketLegRole::StopLoss)
+        );
+        assert_eq!(
+            bracket.triggered_exit(52100.0),
+            Some(BracketLegRole::TakeProfit)
+        );
+        assert_eq!(bracket.triggered_exit(50000.0), None);
+    }
+
+    #[test]
+    fn test_fill_cancels_sibling_leg() {
+        let mut bracket = long_bracket();
+        bracket.record_fill(BracketLegRole::Entry);
+        bracket.attach_native_exits(BracketExitIds {
+            stop_loss: "sl-1".to_string(),
+            take_profit: "tp-1".to_string(),
+        });
+
+        let to_cancel = bracket.record_fill(BracketLegRole::TakeProfit);
+        assert_eq!(to_cancel, vec!["sl-1".to_string()]);
+        assert_eq!(bracket.stop_loss.state, BracketLegState::Cancelled);
+        assert_eq!(bracket.filled_exit(), Some(BracketLegRole::TakeProfit));
+        assert!(!bracket.is_active());
+    }
+
+    #[test]
+    fn test_single_order_native_bracket_needs_no_sibling_cancel() {
+        let mut bracket = BracketOrder::oco(
+            "oco-1".to_string(),
+            "BTC-USD".to_string(),
+            OrderSide::Sell,
+            0.1,
+            49000.0,
+            52000.0,
+        )
+        .unwrap();
+        bracket.attach_native_exits(BracketExitIds::single_order("cb-1"));
+        assert_ne!(
+            bracket.stop_loss.exchange_order_id,
+            bracket.take_profit.exchange_order_id
+        );
+
+        bracket.link_entry("entry-9".to_string(), Some(50000.0));
+        assert!(!bracket.is_entry_filled());
+        bracket.record_fill(BracketLegRole::Entry);
+
+        assert!(bracket.record_fill(BracketLegRole::StopLoss).is_empty());
+        assert_eq!(bracket.take_profit.state, BracketLegState::Cancelled);
+        assert_eq!(bracket.triggered_exit(40000.0), None);
+    }
+
+    #[test]
+    fn test_leg_order_ids_round_trip() {
+        let ids = BracketExitIds::single_order("cb-1");
+        assert_eq!(
+            split_leg_order_id(&ids.take_profit),
+            ("cb-1", Some(BracketLegRole::TakeProfit))
+        );
+        assert_eq!(
+            split_leg_order_id(&ids.stop_loss),
+            ("cb-1", Some(BracketLegRole::StopLoss))
+        );
+        assert_eq!(split_leg_order_id("order#7"), ("order#7", None));
+    }
+
+    #[test]
+    fn test_cancel_all_deduplicates_exchange_ids() {
+        let mut bracket = long_bracket();
+        bracket.mark_working(BracketLegRole::Entry, "entry-x".to_string());
+        bracket.attach_native_exits(BracketExitIds::single_order("cb-1"));
+
+        let to_cancel = bracket.cancel_all();
+        assert_eq!(to_cancel, vec!["entry-x".to_string(), "cb-1".to_string()]);
+        assert!(!bracket.is_active());
+    }
+
+    #[test]
+    fn test_revert_to_emulated_rearms_exits() {
+        let mut bracket = long_bracket();
+        bracket.record_fill(BracketLegRole::Entry);
+        bracket.attach_native_exits(BracketExitIds {
+            stop_loss: "sl-1".to_string(),
+            take_profit: "tp-1".to_string(),
+        });
+        bracket.stop_loss.state = BracketLegState::Cancelled;
+
+        assert_eq!(bracket.revert_to_emulated(), vec!["tp-1".to_string()]);
+        assert_eq!(bracket.linking, BracketLinking::Emulated);
+        assert!(bracket.is_active());
+        assert_eq!(
+            bracket.triggered_exit(48000.0),
+            Some(BracketLegRole::StopLoss)
+        );
+    }
+
+    #[test]
+    fn test_exit_order_uses_exit_side_and_quantity() {
+        let bracket = long_bracket();
+        let order = bracket.exit_order(BracketLegRole::StopLoss).unwrap();
+        assert_eq!(order.id, "br-1_stop_loss");
+        assert!(matches!(order.side, OrderSide::Sell));
+        assert!(matches!(order.order_type, OrderType::Market));
+        assert_eq!(order.quantity.value(), 0.1);
+        assert!(bracket.exit_order(BracketLegRole::Entry).is_err());
+    }
+}
@@ -19,6 +19,31 @@ impl Exchange {
             Exchange::Kraken => "kraken",
         }
     }
+
+    /// Parse a lowercase exchange name as returned by `name`
+    pub fn from_name(name: &str) -> Option<Exchange> {
+        match name.trim().to_lowercase().as_str() {
+            "dydx" => Some(Exchange::Dydx),
+            "hyperliquid" => Some(Exchange::Hyperliquid),
+            "coinbase" => Some(Exchange::Coinbase),
+            "binance" => Some(Exchange::Binance),
+            "kraken" => Some(Exchange::Kraken),
+            _ => None,
+        }
+    }
+
+    /// Whether the venue trades perpetual futures rather than spot
+    pub fn is_perpetual(&self) -> bool {
+        matches!(self, Exchange::Dydx | Exchange::Hyperliquid)
+    }
+
+    /// Whether the venue's ticker feed carries a best bid and ask
+    pub fn has_best_quote(&self) -> bool {
+        matches!(
+            self,
+            Exchange::Coinbase | Exchange::Binance | Exchange::Kraken
+        )
+    }
 }
 
 #[cfg(test)]
@@ -36,6 +61,13 @@ mod tests {
         assert_eq!(Exchange::Dydx.name(), "dydx");
     }
 
+    #[test]
+    fn test_only_order_book_tickers_have_best_quote() {
+        assert!(Exchange::Coinbase.has_best_quote());
+        assert!(!Exchange::Dydx.has_best_quote());
+        assert!(!Exchange::Hyperliquid.has_best_quote());
+    }
+
     #[test]
     fn test_exchange_name_binance() {
         assert_eq!(Exchange::Binance.name(), "binance");
@@ -45,4 +77,19 @@ mod tests {
     fn test_exchange_name_kraken() {
         assert_eq!(Exchange::Kraken.name(), "kraken");
     }
+
+    #[test]
+    fn test_exchange_from_name_round_trips() {
+        for exchange in [
+            Exchange::Dydx,
+            Exchange::Hyperliquid,
+            Exchange::Coinbase,
+            Exchange::Binance,
+            Exchange::Kraken,
+        ] {
+            assert_eq!(Exchange::from_name(exchange.name()), Some(exchange));
+        }
+        assert_eq!(Exchange::from_name(" Coinbase "), Some(Exchange::Coinbase));
+        assert_eq!(Exchange::from_name("ftx"), None);
+    }
 }
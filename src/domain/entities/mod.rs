@@ -1,8 +1,10 @@
 pub mod balance;
+pub mod bracket_order;
 pub mod exchange;
 pub mod leverage;
 pub mod order;
 pub mod position;
+pub mod position_group;
 pub mod symbol_screening;
 pub mod trader;
 
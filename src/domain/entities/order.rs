@@ -29,6 +29,7 @@ pub struct Order {
     pub order_type: OrderType,
     pub price: Option<Price>,
     pub quantity: Quantity,
+    pub post_only: bool, // Limit order rejected by the exchange rather than taking liquidity
 }
 
 impl Order {
@@ -59,9 +60,19 @@ impl Order {
             order_type,
             price,
             quantity,
+            post_only: false,
         })
     }
 
+    /// Make this limit order maker-only
+    pub fn post_only(mut self) -> Result<Self, String> {
+        if !matches!(self.order_type, OrderType::Limit) {
+            return Err("Only limit orders can be post-only".to_string());
+        }
+        self.post_only = true;
+        Ok(self)
+    }
+
     pub fn total_value(&self) -> Option<Price> {
         self.price
             .and_then(|p| p.multiply(self.quantity.value()).ok())
@@ -152,6 +163,32 @@ mod tests {
         assert_eq!(total.unwrap().value(), 5000.0);
     }
 
+    #[test]
+    fn test_post_only_requires_limit_order() {
+        let limit = Order::new(
+            "404".to_string(),
+            "BTCUSD".to_string(),
+            OrderSide::Buy,
+            OrderType::Limit,
+            Some(50000.0),
+            0.1,
+        )
+        .unwrap();
+        assert!(!limit.post_only);
+        assert!(limit.post_only().unwrap().post_only);
+
+        let market = Order::new(
+            "405".to_string(),
+            "BTCUSD".to_string(),
+            OrderSide::Buy,
+            OrderType::Market,
+            None,
+            0.1,
+        )
+        .unwrap();
+        assert!(market.post_only().is_err());
+    }
+
     #[test]
     fn test_order_total_value_market() {
         let order = Order::new(
@@ -1,6 +1,6 @@
 use crate::domain::errors::ValidationError;
 use crate::domain::value_objects::{pnl::PnL, price::Price, quantity::Quantity};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 
 #[derive(Debug, Clone)]
 pub enum PositionSide {
@@ -17,6 +17,77 @@ impl std::fmt::Display for PositionSide {
     }
 }
 
+/// Why a position was closed
+///
+/// Persisted with the closing trade so that exits can be analysed per reason.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitReason {
+    StopLoss,
+    TakeProfit,
+    /// Position was held longer than the configured maximum holding period
+    MaxHoldingTime,
+    /// Position did not move in our favour within the configured window
+    NoProgress,
+    Manual,
+    /// Venue no longer held the position, closed locally by reconciliation
+    Reconciliation,
+}
+
+impl ExitReason {
+    /// Stable identifier stored in the `trades.exit_reason` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitReason::StopLoss => "stop_loss",
+            ExitReason::TakeProfit => "take_profit",
+            ExitReason::MaxHoldingTime => "max_holding_time",
+            ExitReason::NoProgress => "no_progress",
+            ExitReason::Manual => "manual",
+            ExitReason::Reconciliation => "reconciliation",
+        }
+    }
+
+    /// Whether this exit was triggered by elapsed time rather than price
+    pub fn is_time_based(&self) -> bool {
+        matches!(self, ExitReason::MaxHoldingTime | ExitReason::NoProgress)
+    }
+}
+
+impl std::fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExitReason::StopLoss => write!(f, "stop-loss"),
+            ExitReason::TakeProfit => write!(f, "take-profit"),
+            ExitReason::MaxHoldingTime => write!(f, "max holding time"),
+            ExitReason::NoProgress => write!(f, "no progress"),
+            ExitReason::Manual => write!(f, "manual close"),
+            ExitReason::Reconciliation => write!(f, "reconciliation"),
+        }
+    }
+}
+
+/// Time-based exit rule for a position
+///
+/// - `max_holding`: close unconditionally once the position is older than this
+/// - `no_progress_after`: close once the position is older than this and its
+///   return is still below `min_progress_pct` (e.g. 0.001 = 0.1%)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TimeExitRule {
+    pub max_holding: Option<Duration>,
+    pub no_progress_after: Option<Duration>,
+    pub min_progress_pct: f64,
+}
+
+impl TimeExitRule {
+    /// Rule that never triggers
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_holding.is_some() || self.no_progress_after.is_some()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Position {
     pub id: String,
@@ -28,6 +99,10 @@ pub struct Position {
     pub current_price: Option<Price>,
     pub stop_loss_price: Option<Price>,
     pub take_profit_price: Option<Price>,
+    /// Strategy whose trader opened the position (used for per-strategy exit rules)
+    pub strategy: Option<String>,
+    /// Group of linked legs the position was opened in, closed together
+    pub group_id: Option<String>,
 }
 
 impl Position {
@@ -48,6 +123,8 @@ impl Position {
             current_price: None,
             stop_loss_price: None,
             take_profit_price: None,
+            strategy: None,
+            group_id: None,
         }
     }
 
@@ -124,6 +201,48 @@ impl Position {
         }
     }
 
+    /// How long the position has been open at `now`
+    pub fn holding_duration(&self, now: DateTime<Utc>) -> Duration {
+        now.signed_duration_since(self.entry_time)
+    }
+
+    /// Return since entry as a fraction of the entry price, signed by side
+    ///
+    /// Returns None if current price hasn't been set yet.
+    pub fn return_pct(&self) -> Option<f64> {
+        self.current_price.map(|current_price| {
+            let change =
+                (current_price.value() - self.entry_price.value()) / self.entry_price.value();
+            match self.side {
+                PositionSide::Long => change,
+                PositionSide::Short => -change,
+            }
+        })
+    }
+
+    /// Check whether a time-based exit rule is met at `now`
+    ///
+    /// The maximum holding period takes precedence over the no-progress check.
+    /// A position without a current price is treated as having made no progress.
+    pub fn time_exit_reason(&self, rule: &TimeExitRule, now: DateTime<Utc>) -> Option<ExitReason> {
+        let held = self.holding_duration(now);
+
+        if let Some(max_holding) = rule.max_holding {
+            if held >= max_holding {
+                return Some(ExitReason::MaxHoldingTime);
+            }
+        }
+
+        if let Some(no_progress_after) = rule.no_progress_after {
+            if held >= no_progress_after && self.return_pct().unwrap_or(0.0) < rule.min_progress_pct
+            {
+                return Some(ExitReason::NoProgress);
+            }
+        }
+
+        None
+    }
+
     pub fn set_stop_loss_percentage(&mut self, percentage: f64) -> Result<(), ValidationError> {
         let sl_price = match self.side {
             PositionSide::Long => Price::new(self.entry_price.value() * (1.0 - percentage))?,
@@ -296,4 +415,80 @@ mod tests {
         position.update_price(Price::new(45000.0).unwrap());
         assert!(position.should_take_profit());
     }
+
+    #[test]
+    fn test_position_max_holding_time_exit() {
+        let mut position = Position::new(
+            "pos_1".to_string(),
+            "BTC-USD".to_string(),
+            PositionSide::Long,
+            Quantity::new(1.0).unwrap(),
+            Price::new(50000.0).unwrap(),
+        );
+        position.update_price(Price::new(51000.0).unwrap());
+
+        let rule = TimeExitRule {
+            max_holding: Some(Duration::minutes(15)),
+            ..TimeExitRule::disabled()
+        };
+        let now = position.entry_time;
+
+        assert_eq!(
+            position.time_exit_reason(&rule, now + Duration::minutes(14)),
+            None
+        );
+        assert_eq!(
+            position.time_exit_reason(&rule, now + Duration::minutes(15)),
+            Some(ExitReason::MaxHoldingTime)
+        );
+        assert_eq!(
+            position.time_exit_reason(&TimeExitRule::disabled(), now + Duration::days(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_position_no_progress_exit() {
+        let mut position = Position::new(
+            "pos_1".to_string(),
+            "BTC-USD".to_string(),
+            PositionSide::Short,
+            Quantity::new(1.0).unwrap(),
+            Price::new(50000.0).unwrap(),
+        );
+        let rule = TimeExitRule {
+            max_holding: None,
+            no_progress_after: Some(Duration::minutes(5)),
+            min_progress_pct: 0.002,
+        };
+        let later = position.entry_time + Duration::minutes(6);
+
+        // Short moved against us - no progress
+        position.update_price(Price::new(50010.0).unwrap());
+        assert_eq!(
+            position.time_exit_reason(&rule, later),
+            Some(ExitReason::NoProgress)
+        );
+
+        // Short in profit by 0.4% - keep it
+        position.update_price(Price::new(49800.0).unwrap());
+        assert!((position.return_pct().unwrap() - 0.004).abs() < 1e-9);
+        assert_eq!(position.time_exit_reason(&rule, later), None);
+
+        // Not yet past the window
+        position.update_price(Price::new(50010.0).unwrap());
+        assert_eq!(
+            position.time_exit_reason(&rule, position.entry_time + Duration::minutes(4)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_exit_reason_identifiers() {
+        assert_eq!(ExitReason::MaxHoldingTime.as_str(), "max_holding_time");
+        assert_eq!(ExitReason::NoProgress.as_str(), "no_progress");
+        assert!(ExitReason::NoProgress.is_time_based());
+        assert!(!ExitReason::StopLoss.is_time_based());
+        assert_eq!(ExitReason::StopLoss.to_string(), "stop-loss");
+    }
 }
@@ -0,0 +1,216 @@
+use crate::domain::entities::position::{ExitReason, Position, PositionSide, TimeExitRule};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Positions opened together by a multi-asset strategy
+///
+/// The legs of a group are opened and closed as a unit. Stops apply to the
+/// combined PnL of the legs rather than to each leg, since a hedged leg is
+/// expected to lose whenever the other one gains.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PositionGroup {
+    pub id: String,
+    /// Multi-asset strategy that opened the group
+    pub strategy: String,
+    pub position_ids: Vec<String>,
+    /// Hedge quantity of the second leg per unit of the first, in log-price terms
+    pub hedge_ratio: f64,
+    /// Spread z-score the group was opened at
+    pub entry_zscore: f64,
+    pub opened_at: DateTime<Utc>,
+}
+
+impl PositionGroup {
+    pub fn new(
+        id: String,
+        strategy: String,
+        position_ids: Vec<String>,
+        hedge_ratio: f64,
+        entry_zscore: f64,
+    ) -> Self {
+        Self {
+            id,
+            strategy,
+            position_ids,
+            hedge_ratio,
+            entry_zscore,
+            opened_at: Utc::now(),
+        }
+    }
+
+    /// Combined unrealized PnL of the legs; legs without a price count as flat
+    pub fn unrealized_pnl(legs: &[Position]) -> f64 {
+        legs.iter()
+            .filter_map(|leg| leg.unrealized_pnl())
+            .map(|pnl| pnl.value())
+            .sum()
+    }
+
+    /// Combined PnL as a fraction of the gross notional of the legs at entry
+    pub fn return_pct(legs: &[Position]) -> f64 {
+        let gross: f64 = legs
+            .iter()
+            .map(|leg| leg.quantity.value() * leg.entry_price.value())
+            .sum();
+        if gross > 0.0 {
+            Self::unrealized_pnl(legs) / gross
+        } else {
+            0.0
+        }
+    }
+
+    /// Net signed notional of the legs at entry (long minus short)
+    pub fn net_exposure(legs: &[Position]) -> f64 {
+        legs.iter()
+            .map(|leg| {
+                let notional = leg.quantity.value() * leg.entry_price.value();
+                match leg.side {
+                    PositionSide::Long => notional,
+                    PositionSide::Short => -notional,
+                }
+            })
+            .sum()
+    }
+
+    /// Check the group's combined return against stop-loss, take-profit and
+    /// time exit rules at `now`
+    ///
+    /// Percentages are fractions of the gross entry notional. The holding
+    /// period is measured from when the group was opened.
+    pub fn exit_reason(
+        &self,
+        legs: &[Position],
+        stop_loss_pct: Option<f64>,
+        take_profit_pct: Option<f64>,
+        rule: &TimeExitRule,
+        now: DateTime<Utc>,
+    ) -> Option<ExitReason> {
+        let priced = !legs.is_empty() && legs.iter().all(|leg| leg.current_price.is_some());
+        let combined = Self::return_pct(legs);
+
+        if priced {
+            if stop_loss_pct.is_some_and(|sl| combined <= -sl) {
+                return Some(ExitReason::StopLoss);
+            }
+            if take_profit_pct.is_some_and(|tp| combined >= tp) {
+                return Some(ExitReason::TakeProfit);
+            }
+        }
+
+        let held = now.signed_duration_since(self.opened_at);
+        if rule.max_holding.is_some_and(|max| held >= max) {
+            return Some(ExitReason::MaxHoldingTime);
+        }
+        if rule
+            .no_progress_after
+            .is_some_and(|after| held >= after && combined < rule.min_progress_pct)
+        {
+            return Some(ExitReason::NoProgress);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_objects::{price::Price, quantity::Quantity};
+    use chrono::Duration;
+
+    fn leg(id: &str, side: PositionSide, quantity: f64, entry: f64, current: f64) -> Position {
+        let mut position = Position::new(
+            id.to_string(),
+            format!("{}-USD", id),
+            side,
+            Quantity::new(quantity).unwrap(),
+            Price::new(entry).unwrap(),
+        );
+        position.update_price(Price::new(current).unwrap());
+        position
+    }
+
+    fn group() -> PositionGroup {
+        PositionGroup::new(
+            "grp_1".to_string(),
+            "pairs".to_string(),
+            vec!["ETH".to_string(), "BTC".to_string()],
+            1.0,
+            2.5,
+        )
+    }
+
+    #[test]
+    fn test_combined_pnl_nets_the_legs() {
+        // Short ETH loses 10, long BTC gains 20
+        let legs = [
+            leg("ETH", PositionSide::Short, 1.0, 1000.0, 1010.0),
+            leg("BTC", PositionSide::Long, 0.02, 50000.0, 51000.0),
+        ];
+        assert!((PositionGroup::unrealized_pnl(&legs) - 10.0).abs() < 1e-9);
+        assert!((PositionGroup::return_pct(&legs) - 0.005).abs() < 1e-9);
+        assert!(PositionGroup::net_exposure(&legs).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stops_use_combined_return() {
+        let group = group();
+        let now = Utc::now();
+        let rule = TimeExitRule::disabled();
+
+        // One leg is down 5% but the hedge covers it
+        let hedged = [
+            leg("ETH", PositionSide::Long, 1.0, 1000.0, 950.0),
+            leg("BTC", PositionSide::Short, 0.02, 50000.0, 47600.0),
+        ];
+        assert_eq!(
+            group.exit_reason(&hedged, Some(0.02), Some(0.05), &rule, now),
+            None
+        );
+
+        let losing = [
+            leg("ETH", PositionSide::Long, 1.0, 1000.0, 950.0),
+            leg("BTC", PositionSide::Short, 0.02, 50000.0, 50500.0),
+        ];
+        assert_eq!(
+            group.exit_reason(&losing, Some(0.02), Some(0.05), &rule, now),
+            Some(ExitReason::StopLoss)
+        );
+
+        let winning = [
+            leg("ETH", PositionSide::Long, 1.0, 1000.0, 1100.0),
+            leg("BTC", PositionSide::Short, 0.02, 50000.0, 50000.0),
+        ];
+        assert_eq!(
+            group.exit_reason(&winning, Some(0.02), Some(0.04), &rule, now),
+            Some(ExitReason::TakeProfit)
+        );
+    }
+
+    #[test]
+    fn test_time_exit_uses_group_age() {
+        let group = group();
+        let legs = [
+            leg("ETH", PositionSide::Long, 1.0, 1000.0, 1000.0),
+            leg("BTC", PositionSide::Short, 0.02, 50000.0, 50000.0),
+        ];
+        let rule = TimeExitRule {
+            max_holding: Some(Duration::hours(1)),
+            ..Default::default()
+        };
+        assert_eq!(
+            group.exit_reason(&legs, None, None, &rule, group.opened_at),
+            None
+        );
+        assert_eq!(
+            group.exit_reason(
+                &legs,
+                None,
+                None,
+                &rule,
+                group.opened_at + Duration::hours(2)
+            ),
+            Some(ExitReason::MaxHoldingTime)
+        );
+    }
+}
@@ -1,8 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Recommendation category for a symbol's scalping potential
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RecommendationCategory {
     /// Score >= 0.75 - Excellent scalping potential
     BestCandidate,
@@ -40,8 +41,12 @@ pub struct SymbolScreeningResult {
     pub spread_score: f64,
     /// Momentum score [0.0, 1.0]
     pub momentum_score: f64,
+    /// Every component score [0.0, 1.0] by scorer name, including the four
+    /// above
+    #[serde(default)]
+    pub component_scores: BTreeMap<String, f64>,
     /// Overall scalping potential score [0.0, 1.0]
-    /// Formula: 0.3 * volatility + 0.3 * volume + 0.2 * spread + 0.2 * momentum
+    /// Default formula: 0.3 * volatility + 0.3 * volume + 0.2 * spread + 0.2 * momentum
     pub overall_score: f64,
     /// Recommendation category based on overall_score
     pub recommendation: RecommendationCategory,
@@ -86,18 +91,55 @@ impl SymbolScreeningResult {
         let overall_score =
             0.3 * volatility_score + 0.3 * volume_score + 0.2 * spread_score + 0.2 * momentum_score;
 
-        let recommendation = RecommendationCategory::from_score(overall_score);
+        let component_scores = BTreeMap::from([
+            ("volatility".to_string(), volatility_score),
+            ("volume".to_string(), volume_score),
+            ("spread".to_string(), spread_score),
+            ("momentum".to_string(), momentum_score),
+        ]);
+
+        Self::from_components(symbol, exchange, component_scores, overall_score)
+    }
+
+    /// Create a result from named component scores and their weighted score
+    ///
+    /// The volatility, volume, spread and momentum fields are read from the
+    /// components of the same name, zero when absent.
+    ///
+    /// # Panics
+    /// Panics if any score is outside [0.0, 1.0]
+    pub fn from_components(
+        symbol: String,
+        exchange: String,
+        component_scores: BTreeMap<String, f64>,
+        overall_score: f64,
+    ) -> Self {
+        for (name, score) in &component_scores {
+            assert!(
+                (0.0..=1.0).contains(score),
+                "{}_score must be in [0.0, 1.0], got {}",
+                name,
+                score
+            );
+        }
+        assert!(
+            (0.0..=1.0).contains(&overall_score),
+            "overall_score must be in [0.0, 1.0], got {}",
+            overall_score
+        );
+        let component = |name: &str| component_scores.get(name).copied().unwrap_or(0.0);
 
         SymbolScreeningResult {
             symbol,
             exchange,
-            volatility_score,
-            volume_score,
-            spread_score,
-            momentum_score,
+            volatility_score: component("volatility"),
+            volume_score: component("volume"),
+            spread_score: component("spread"),
+            momentum_score: component("momentum"),
             overall_score,
-            recommendation,
+            recommendation: RecommendationCategory::from_score(overall_score),
             screened_at: Utc::now(),
+            component_scores,
         }
     }
 
@@ -211,6 +253,34 @@ mod tests {
         assert_eq!(result.recommendation, RecommendationCategory::Avoid);
     }
 
+    #[test]
+    fn test_result_from_components() {
+        let components =
+            BTreeMap::from([("volume".to_string(), 0.8), ("funding".to_string(), 0.4)]);
+        let result = SymbolScreeningResult::from_components(
+            "ETH-USD".to_string(),
+            "dydx".to_string(),
+            components,
+            0.65,
+        );
+
+        assert_eq!(result.volume_score, 0.8);
+        assert_eq!(result.volatility_score, 0.0);
+        assert_eq!(result.component_scores["funding"], 0.4);
+        assert_eq!(result.recommendation, RecommendationCategory::GoodCandidate);
+    }
+
+    #[test]
+    #[should_panic(expected = "funding_score must be in [0.0, 1.0]")]
+    fn test_component_score_validation() {
+        SymbolScreeningResult::from_components(
+            "BTC-USD".to_string(),
+            "dydx".to_string(),
+            BTreeMap::from([("funding".to_string(), 1.2)]),
+            0.5,
+        );
+    }
+
     #[test]
     #[should_panic(expected = "volatility_score must be in [0.0, 1.0]")]
     fn test_volatility_score_validation_too_high() {
@@ -11,9 +11,10 @@
 //! - Position tracking
 //! - Risk management
 
+use crate::domain::entities::bracket_order::{BracketExitIds, BracketOrder};
 use crate::domain::entities::exchange::Exchange;
 use crate::domain::entities::order::{Order, OrderSide, OrderType};
-use crate::domain::repositories::exchange_client::ExchangeClient;
+use crate::domain::repositories::exchange_client::{ExchangeClient, OrderFill, OrderStatus};
 use crate::domain::services::strategies::{Signal, Strategy, TradingSignal};
 use crate::domain::value_objects::price::Price;
 use std::collections::HashMap;
@@ -300,6 +301,86 @@ impl Trader {
         })
     }
 
+    /// Place bracket exits natively on the active exchange
+    ///
+    /// Returns `Ok(None)` when the active exchange cannot link exits itself,
+    /// in which case the caller emulates them.
+    pub async fn place_bracket_exits(
+        &self,
+        bracket: &BracketOrder,
+    ) -> Result<Option<BracketExitIds>, String> {
+        let (exchange, client) = self.active_client()?;
+        if !client.supports_native_brackets() {
+            return Ok(None);
+        }
+
+        client
+            .place_bracket_exits(bracket)
+            .await
+            .map(Some)
+            .map_err(|e| {
+                let error_msg = format!(
+                    "Failed to place bracket {} on {}: {}",
+                    bracket.id,
+                    exchange.name(),
+                    e
+                );
+                error!("{}", error_msg);
+                error_msg
+            })
+    }
+
+    /// Cancel an order on the active exchange
+    pub async fn cancel_order(&self, order_id: &str) -> Result<(), String> {
+        let (exchange, client) = self.active_client()?;
+        client.cancel_order(order_id).await.map_err(|e| {
+            format!(
+                "Failed to cancel {} on {}: {}",
+                order_id,
+                exchange.name(),
+                e
+            )
+        })
+    }
+
+    /// Get the status of an order on the active exchange
+    pub async fn get_order_status(&self, order_id: &str) -> Result<OrderStatus, String> {
+        let (exchange, client) = self.active_client()?;
+        client.get_order_status(order_id).await.map_err(|e| {
+            format!(
+                "Failed to get status of {} on {}: {}",
+                order_id,
+                exchange.name(),
+                e
+            )
+        })
+    }
+
+    /// Get the status and filled quantity of an order on the active exchange
+    pub async fn get_order_fill(&self, order_id: &str) -> Result<OrderFill, String> {
+        let (exchange, client) = self.active_client()?;
+        client.get_order_fill(order_id).await.map_err(|e| {
+            format!(
+                "Failed to get fill of {} on {}: {}",
+                order_id,
+                exchange.name(),
+                e
+            )
+        })
+    }
+
+    fn active_client(&self) -> Result<(&Exchange, &Arc<dyn ExchangeClient>), String> {
+        let exchange = self
+            .active_exchange
+            .as_ref()
+            .ok_or("No active exchange set for trader")?;
+        let client = self
+            .exchange_clients
+            .get(exchange)
+            .ok_or_else(|| format!("Exchange client not found for {:?}", exchange))?;
+        Ok((exchange, client))
+    }
+
     /// Check if all exchange clients are healthy
     pub async fn check_health(&self) -> HashMap<Exchange, bool> {
         let mut health_status = HashMap::new();
@@ -429,6 +510,36 @@ mod tests {
         assert_eq!(trader.get_active_exchange(), Some(&Exchange::Binance));
     }
 
+    #[tokio::test]
+    async fn test_place_bracket_exits_without_native_support() {
+        let strategy = Box::new(FastScalping::new());
+        let mut trader = Trader::new("trader1".to_string(), strategy, 100.0, 0.7).unwrap();
+        trader.add_exchange(
+            Exchange::Binance,
+            Arc::new(MockExchangeClient {
+                name: "MockExchange".to_string(),
+                should_fail: false,
+            }),
+        );
+
+        let bracket = BracketOrder::oco(
+            "oco-1".to_string(),
+            "BTC-USD".to_string(),
+            OrderSide::Sell,
+            0.1,
+            49000.0,
+            52000.0,
+        )
+        .unwrap();
+
+        // The caller falls back to local emulation
+        assert_eq!(trader.place_bracket_exits(&bracket).await.unwrap(), None);
+        assert_eq!(
+            trader.get_order_status("mock_order_id").await.unwrap(),
+            OrderStatus::Filled
+        );
+    }
+
     #[tokio::test]
     async fn test_execute_signal() {
         let strategy = Box::new(FastScalping::new());
@@ -444,6 +555,7 @@ mod tests {
         let signal = TradingSignal {
             signal: Signal::Buy,
             confidence: 0.8,
+            ..Default::default()
         };
 
         let price = Price::new(50000.0).unwrap();
@@ -468,6 +580,7 @@ mod tests {
         let signal = TradingSignal {
             signal: Signal::Buy,
             confidence: 0.5, // Below threshold
+            ..Default::default()
         };
 
         let price = Price::new(50000.0).unwrap();
@@ -492,6 +605,7 @@ mod tests {
         let signal = TradingSignal {
             signal: Signal::Hold,
             confidence: 0.9,
+            ..Default::default()
         };
 
         let price = Price::new(50000.0).unwrap();
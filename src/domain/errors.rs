@@ -181,6 +181,12 @@ pub enum MpcError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    #[error("Trade limit reached: {0}")]
+    TradeLimitExceeded(String),
+
+    #[error("Position sizing refused: {0}")]
+    PositionSizingRefused(String),
+
     #[error("Timeout waiting for response")]
     Timeout,
 }
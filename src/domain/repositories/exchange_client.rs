@@ -10,6 +10,7 @@
 //! - Allows traders to work with multiple exchanges
 //! - Simplifies adding new exchange support
 
+use crate::domain::entities::bracket_order::{BracketExitIds, BracketOrder};
 use crate::domain::entities::order::Order;
 use async_trait::async_trait;
 
@@ -101,6 +102,19 @@ impl std::fmt::Display for OrderStatus {
     }
 }
 
+/// Status of an order together with how much of it has filled
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderFill {
+    pub status: OrderStatus,
+    /// Filled quantity in base units, when the exchange reports it
+    pub filled_quantity: Option<f64>,
+    /// Average fill price, when the exchange reports it
+    pub average_price: Option<f64>,
+    /// Fees charged on the filled quantity in quote currency, when the
+    /// exchange reports them
+    pub fee: Option<f64>,
+}
+
 /// Exchange client trait providing common interface for all exchanges
 #[async_trait]
 pub trait ExchangeClient: Send + Sync {
@@ -131,6 +145,22 @@ pub trait ExchangeClient: Send + Sync {
     /// The current status of the order
     async fn get_order_status(&self, order_id: &str) -> ExchangeResult<OrderStatus>;
 
+    /// Get the status of an order together with its filled quantity
+    ///
+    /// The default only knows the status; exchanges that report fill
+    /// quantities override it.
+    ///
+    /// # Arguments
+    /// * `order_id` - The exchange-assigned order ID
+    async fn get_order_fill(&self, order_id: &str) -> ExchangeResult<OrderFill> {
+        Ok(OrderFill {
+            status: self.get_order_status(order_id).await?,
+            filled_quantity: None,
+            average_price: None,
+            fee: None,
+        })
+    }
+
     /// Get account balance
     ///
     /// # Arguments
@@ -140,6 +170,28 @@ pub trait ExchangeClient: Send + Sync {
     /// List of balances
     async fn get_balance(&self, currency: Option<&str>) -> ExchangeResult<Vec<Balance>>;
 
+    /// Whether this exchange can hold linked stop-loss/take-profit exits itself
+    ///
+    /// When false, callers emulate bracket exits locally.
+    fn supports_native_brackets(&self) -> bool {
+        false
+    }
+
+    /// Place the stop-loss and take-profit exits of a bracket as linked orders
+    ///
+    /// # Arguments
+    /// * `bracket` - The bracket whose exit legs should be placed
+    ///
+    /// # Returns
+    /// The exchange-assigned order IDs of both exit legs
+    async fn place_bracket_exits(&self, bracket: &BracketOrder) -> ExchangeResult<BracketExitIds> {
+        Err(ExchangeError::InvalidOrder(format!(
+            "{} does not support native bracket orders ({})",
+            self.name(),
+            bracket.id
+        )))
+    }
+
     /// Check if the exchange client is healthy and connected
     async fn is_healthy(&self) -> bool {
         // Default implementation - can be overridden
@@ -0,0 +1,19 @@
+//! Funding Source Trait
+//!
+//! Reads the funding payments an exchange settled on our perpetual
+//! positions, so the realized PnL ledger takes them without manual entry.
+
+use crate::domain::entities::exchange::Exchange;
+use crate::domain::services::pnl_ledger::FundingPayment;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+#[async_trait]
+pub trait FundingSource: Send + Sync {
+    /// Exchange the payments are settled on
+    fn exchange(&self) -> Exchange;
+
+    /// Payments settled at or after `since`, in the exchange's own symbol
+    /// spelling and credited to no strategy in particular
+    async fn funding_payments(&self, since: DateTime<Utc>) -> Result<Vec<FundingPayment>, String>;
+}
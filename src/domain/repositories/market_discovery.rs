@@ -0,0 +1,21 @@
+//! Market Discovery Trait
+//!
+//! Lists every market an exchange trades, with its 24h ticker, so symbols can
+//! be screened before they are subscribed, and reads the order books the
+//! WebSocket tickers do not carry.
+
+use crate::domain::entities::exchange::Exchange;
+use crate::domain::services::screening::OrderBookDepth;
+use crate::domain::services::symbol_universe::MarketSnapshot;
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait MarketDiscovery: Send + Sync {
+    /// Active markets of `exchange`, in the exchange's own symbol spelling
+    async fn list_markets(&self, exchange: &Exchange) -> Result<Vec<MarketSnapshot>, String>;
+
+    /// Resting levels near the top of the book of `symbol`, in the
+    /// exchange's own spelling
+    async fn order_book(&self, exchange: &Exchange, symbol: &str)
+        -> Result<OrderBookDepth, String>;
+}
@@ -1 +1,3 @@
 pub mod exchange_client;
+pub mod funding_source;
+pub mod market_discovery;
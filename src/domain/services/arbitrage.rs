@@ -0,0 +1,295 @@
+//! Cross-exchange arbitrage
+//!
+//! Compares the price of one normalized symbol across venues and finds the
+//! pair to buy low and sell high. Both legs trade against inventory already
+//! held on each venue, so nothing is withdrawn or transferred and no transfer
+//! cost applies. What remains between two venues is their taker fees and, for
+//! perpetual venues, the basis: perps trade at a premium or discount to spot
+//! that does not converge within the trade. Perp prices are brought back to
+//! spot terms before comparing, so a standing basis is not mistaken for a
+//! divergence. Divergences are flagged across every quoting venue, but only
+//! venues with order routing are traded.
+
+use crate::domain::entities::exchange::Exchange;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Fee, basis and sizing settings of the arbitrage detector
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArbitrageParams {
+    /// Spread left after fees and basis, in basis points, to flag a divergence
+    pub min_net_spread_bps: f64,
+    /// Taker fee of venues without an entry in `fee_bps`
+    pub default_fee_bps: f64,
+    pub fee_bps: HashMap<Exchange, f64>,
+    /// Expected premium of perpetual venues over spot, in basis points
+    pub perp_basis_bps: f64,
+    /// Quote currency notional of each leg
+    pub order_notional: f64,
+}
+
+impl Default for ArbitrageParams {
+    fn default() -> Self {
+        Self {
+            min_net_spread_bps: 10.0,
+            default_fee_bps: 10.0,
+            fee_bps: HashMap::new(),
+            perp_basis_bps: 0.0,
+            order_notional: 100.0,
+        }
+    }
+}
+
+impl ArbitrageParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.min_net_spread_bps > 0.0 && self.min_net_spread_bps.is_finite()) {
+            return Err(format!(
+                "min_net_spread_bps ({}) must be positive",
+                self.min_net_spread_bps
+            ));
+        }
+        if !(self.order_notional > 0.0 && self.order_notional.is_finite()) {
+            return Err(format!(
+                "order_notional ({}) must be positive",
+                self.order_notional
+            ));
+        }
+        for (venue, fee) in std::iter::once(("default", self.default_fee_bps))
+            .chain(self.fee_bps.iter().map(|(ex, fee)| (ex.name(), *fee)))
+        {
+            if !(fee >= 0.0 && fee.is_finite()) {
+                return Err(format!("{} fee ({} bps) must not be negative", venue, fee));
+            }
+        }
+        if !self.perp_basis_bps.is_finite() || self.perp_basis_bps.abs() >= 10_000.0 {
+            return Err(format!(
+                "perp_basis_bps ({}) must be below 10000 in magnitude",
+                self.perp_basis_bps
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn fee_for(&self, exchange: &Exchange) -> f64 {
+        self.fee_bps
+            .get(exchange)
+            .copied()
+            .unwrap_or(self.default_fee_bps)
+    }
+
+    /// Price of `exchange` in spot terms, with the perp basis taken out
+    pub fn spot_equivalent(&self, exchange: &Exchange, price: f64) -> f64 {
+        if exchange.is_perpetual() {
+            price / (1.0 + self.perp_basis_bps / 10_000.0)
+        } else {
+            price
+        }
+    }
+}
+
+/// Widest buy-low / sell-high pair of venues for one symbol
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriceDivergence {
+    pub symbol: String,
+    pub buy_exchange: Exchange,
+    pub sell_exchange: Exchange,
+    pub buy_price: f64,
+    pub sell_price: f64,
+    /// Raw spread between the two venues
+    pub gross_spread_bps: f64,
+    /// Part of the gross spread explained by perp basis
+    pub basis_bps: f64,
+    /// Taker fees of both legs
+    pub fees_bps: f64,
+    /// Gross spread less basis and fees
+    pub net_spread_bps: f64,
+    /// Number of venues quoting the symbol
+    pub venues: usize,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl PriceDivergence {
+    pub fn is_opportunity(&self, params: &ArbitrageParams) -> bool {
+        self.net_spread_bps >= params.min_net_spread_bps
+    }
+}
+
+/// Venues whose exchange actor can place, poll and cancel orders
+pub fn can_route_orders(exchange: &Exchange) -> bool {
+    matches!(exchange, Exchange::Dydx | Exchange::Coinbase)
+}
+
+/// Find the venue pair with the widest net spread for `symbol`
+///
+/// Returns None with fewer than two priced venues. The result may have a
+/// negative net spread; check `is_opportunity` before trading it.
+pub fn best_divergence(
+    symbol: &str,
+    prices: &[(Exchange, f64)],
+    params: &ArbitrageParams,
+) -> Option<PriceDivergence> {
+    let priced: Vec<&(Exchange, f64)> = prices.iter().filter(|(_, p)| *p > 0.0).collect();
+    let mut best: Option<PriceDivergence> = None;
+
+    for (buy_exchange, buy_price) in &priced {
+        for (sell_exchange, sell_price) in &priced {
+            if buy_exchange == sell_exchange {
+                continue;
+            }
+            let gross_spread_bps = (sell_price - buy_price) / buy_price * 10_000.0;
+            let fair_buy = params.spot_equivalent(buy_exchange, *buy_price);
+            let fair_sell = params.spot_equivalent(sell_exchange, *sell_price);
+            let fair_spread_bps = (fair_sell - fair_buy) / fair_buy * 10_000.0;
+            let fees_bps = params.fee_for(buy_exchange) + params.fee_for(sell_exchange);
+            let net_spread_bps = fair_spread_bps - fees_bps;
+
+            if best
+                .as_ref()
+                .is_none_or(|b| net_spread_bps > b.net_spread_bps)
+            {
+                best = Some(PriceDivergence {
+                    symbol: symbol.to_string(),
+                    buy_exchange: buy_exchange.clone(),
+                    sell_exchange: sell_exchange.clone(),
+                    buy_price: *buy_price,
+                    sell_price: *sell_price,
+                    gross_spread_bps,
+                    basis_bps: gross_spread_bps - fair_spread_bps,
+                    fees_bps,
+                    net_spread_bps,
+                    venues: priced.len(),
+                    detected_at: Utc::now(),
+                });
+            }
+        }
+    }
+
+    best
+}
+
+/// How both legs of an arbitrage ended up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArbitrageOutcome {
+    /// Both legs filled the same quantity
+    Filled,
+    /// The legs filled different quantities and the excess was reversed
+    Unwound,
+    /// The excess could not be reversed, or a fill could not be confirmed;
+    /// exposure may remain
+    UnwindFailed,
+    /// Neither leg filled
+    Failed,
+}
+
+/// Record of one attempt to trade a divergence
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArbitrageExecution {
+    pub symbol: String,
+    pub buy_exchange: Exchange,
+    pub sell_exchange: Exchange,
+    /// Quantity ordered on each leg
+    pub quantity: f64,
+    pub net_spread_bps: f64,
+    pub buy_order_id: Option<String>,
+    pub sell_order_id: Option<String>,
+    /// Quantity each leg filled before it was cancelled
+    pub buy_filled: f64,
+    pub sell_filled: f64,
+    pub unwind_order_id: Option<String>,
+    pub unwind_filled: f64,
+    pub outcome: ArbitrageOutcome,
+    pub errors: Vec<String>,
+    pub executed_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> ArbitrageParams {
+        ArbitrageParams {
+            fee_bps: HashMap::from([(Exchange::Coinbase, 20.0), (Exchange::Dydx, 5.0)]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_widest_pair_net_of_fees() {
+        let prices = [
+            (Exchange::Binance, 50000.0),
+            (Exchange::Coinbase, 50300.0),
+            (Exchange::Kraken, 50150.0),
+        ];
+        let divergence = best_divergence("BTC-USD", &prices, &params()).unwrap();
+        assert_eq!(divergence.buy_exchange, Exchange::Binance);
+        assert_eq!(divergence.sell_exchange, Exchange::Coinbase);
+        assert!((divergence.gross_spread_bps - 60.0).abs() < 1e-9);
+        assert_eq!(divergence.fees_bps, 30.0);
+        assert!((divergence.net_spread_bps - 30.0).abs() < 1e-9);
+        assert_eq!(divergence.venues, 3);
+        assert!(divergence.is_opportunity(&params()));
+    }
+
+    #[test]
+    fn test_fees_can_eat_the_spread() {
+        let prices = [(Exchange::Binance, 50000.0), (Exchange::Coinbase, 50100.0)];
+        let divergence = best_divergence("BTC-USD", &prices, &params()).unwrap();
+        assert!((divergence.net_spread_bps + 10.0).abs() < 1e-9);
+        assert!(!divergence.is_opportunity(&params()));
+
+        assert!(best_divergence("BTC-USD", &prices[..1], &params()).is_none());
+    }
+
+    #[test]
+    fn test_perp_basis_is_not_a_divergence() {
+        let params = ArbitrageParams {
+            perp_basis_bps: 40.0,
+            ..params()
+        };
+        // dYdX trades 44 bps over spot, 40 of them its basis
+        let prices = [(Exchange::Binance, 50000.0), (Exchange::Dydx, 50220.0)];
+        let divergence = best_divergence("BTC-USD", &prices, &params).unwrap();
+        assert_eq!(divergence.buy_exchange, Exchange::Binance);
+        assert!((divergence.gross_spread_bps - 44.0).abs() < 1e-9);
+        assert!((divergence.basis_bps - 40.0).abs() < 0.1);
+        assert!(!divergence.is_opportunity(&params));
+
+        // Perp below spot despite its premium: buy the perp, sell spot
+        let prices = [(Exchange::Binance, 50200.0), (Exchange::Dydx, 50000.0)];
+        let divergence = best_divergence("BTC-USD", &prices, &params).unwrap();
+        assert_eq!(divergence.buy_exchange, Exchange::Dydx);
+        assert!(divergence.net_spread_bps > params.min_net_spread_bps);
+    }
+
+    #[test]
+    fn test_orders_route_to_dydx_and_coinbase_only() {
+        assert!(can_route_orders(&Exchange::Dydx));
+        assert!(can_route_orders(&Exchange::Coinbase));
+        assert!(!can_route_orders(&Exchange::Binance));
+        assert!(!can_route_orders(&Exchange::Hyperliquid));
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(ArbitrageParams::default().validate().is_ok());
+        let invalid = [
+            ArbitrageParams {
+                min_net_spread_bps: 0.0,
+                ..Default::default()
+            },
+            ArbitrageParams {
+                fee_bps: HashMap::from([(Exchange::Kraken, -1.0)]),
+                ..Default::default()
+            },
+            ArbitrageParams {
+                order_notional: f64::NAN,
+                ..Default::default()
+            },
+        ];
+        for params in invalid {
+            assert!(params.validate().is_err(), "{:?}", params);
+        }
+    }
+}
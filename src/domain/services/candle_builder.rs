@@ -1,7 +1,10 @@
 use crate::domain::services::indicators::Candle;
+use crate::domain::services::streaming_indicators::{
+    IndicatorBank, IndicatorSpec, IndicatorValues,
+};
 use crate::domain::value_objects::price::Price;
 use std::collections::{HashMap, VecDeque};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Price update with timestamp
 #[derive(Debug, Clone)]
@@ -22,6 +25,12 @@ pub struct CandleBuilder {
     price_updates: HashMap<String, VecDeque<PriceUpdate>>,
     /// Completed candles per symbol
     candles: HashMap<String, VecDeque<Candle>>,
+    /// Window start of each completed candle, on the window grid
+    candle_starts: HashMap<String, VecDeque<SystemTime>>,
+    /// Streaming indicators maintained for every symbol
+    indicator_specs: Vec<IndicatorSpec>,
+    /// Streaming indicator state per symbol, updated as candles complete
+    indicators: HashMap<String, IndicatorBank>,
 }
 
 impl CandleBuilder {
@@ -36,9 +45,42 @@ impl CandleBuilder {
             max_price_updates,
             price_updates: HashMap::new(),
             candles: HashMap::new(),
+            candle_starts: HashMap::new(),
+            indicator_specs: Vec::new(),
+            indicators: HashMap::new(),
         }
     }
 
+    /// Maintain streaming indicators for every symbol
+    ///
+    /// New indicators are warmed up on the candle history already held for
+    /// each symbol. Specs that are already registered are ignored.
+    pub fn register_indicators(&mut self, specs: &[IndicatorSpec]) {
+        for spec in specs {
+            if self.indicator_specs.contains(spec) {
+                continue;
+            }
+            self.indicator_specs.push(*spec);
+
+            for (symbol, bank) in self.indicators.iter_mut() {
+                let history: Vec<Candle> = self
+                    .candles
+                    .get(symbol)
+                    .map(|deque| deque.iter().cloned().collect())
+                    .unwrap_or_default();
+                bank.register(*spec, &history);
+            }
+        }
+    }
+
+    /// Current streaming indicator readings for a symbol
+    pub fn indicator_values(&self, symbol: &str) -> IndicatorValues {
+        self.indicators
+            .get(symbol)
+            .map(|bank| bank.values())
+            .unwrap_or_default()
+    }
+
     /// Add a price update for a symbol
     pub fn add_price(&mut self, symbol: String, price: Price) {
         let update = PriceUpdate {
@@ -89,16 +131,37 @@ impl CandleBuilder {
 
                     if !window_updates.is_empty() {
                         if let Some(candle) = Self::build_candle_from_updates(&window_updates) {
-                            self.candles
+                            let specs = &self.indicator_specs;
+                            let bank =
+                                self.indicators
+                                    .entry(symbol.to_string())
+                                    .or_insert_with(|| {
+                                        let mut bank = IndicatorBank::new();
+                                        for spec in specs {
+                                            bank.register(*spec, &[]);
+                                        }
+                                        bank
+                                    });
+                            bank.update(&candle);
+
+                            let candle_history = self
+                                .candles
                                 .entry(symbol.to_string())
-                                .or_insert_with(VecDeque::new)
-                                .push_back(candle);
-
-                            // Trim to max history
-                            if let Some(candle_history) = self.candles.get_mut(symbol) {
-                                while candle_history.len() > self.max_history {
-                                    candle_history.pop_front();
+                                .or_insert_with(VecDeque::new);
+                            candle_history.push_back(candle);
+                            let starts = self
+                                .candle_starts
+                                .entry(symbol.to_string())
+                                .or_insert_with(VecDeque::new);
+                            starts
+                                .push_back(Self::grid_start(first_timestamp, self.window_duration));
+
+                            // Trim to max history; window indicators drop the same candles
+                            while candle_history.len() > self.max_history {
+                                if let Some(oldest) = candle_history.pop_front() {
+                                    bank.evict(&oldest);
                                 }
+                                starts.pop_front();
                             }
                         }
                     }
@@ -107,6 +170,21 @@ impl CandleBuilder {
         }
     }
 
+    /// Start of the grid window `timestamp` falls in
+    ///
+    /// Candle windows open at a symbol's first update, so they are floored
+    /// to multiples of the window duration to compare across symbols. Window
+    /// starts of one symbol are more than a window apart, so their grid
+    /// starts stay distinct.
+    fn grid_start(timestamp: SystemTime, window: Duration) -> SystemTime {
+        let window_ms = window.as_millis().max(1);
+        let since_epoch = timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        UNIX_EPOCH + Duration::from_millis((since_epoch - since_epoch % window_ms) as u64)
+    }
+
     /// Build a candle from price updates
     fn build_candle_from_updates(updates: &[PriceUpdate]) -> Option<Candle> {
         if updates.is_empty() {
@@ -138,6 +216,20 @@ impl CandleBuilder {
             .unwrap_or_default()
     }
 
+    /// Get candles for a symbol with the grid start of each candle's window
+    ///
+    /// Series of different symbols can be joined on the start time.
+    pub fn get_timed_candles(&self, symbol: &str) -> Vec<(SystemTime, Candle)> {
+        match (self.candle_starts.get(symbol), self.candles.get(symbol)) {
+            (Some(starts), Some(candles)) => starts
+                .iter()
+                .copied()
+                .zip(candles.iter().cloned())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
     /// Get number of candles for a symbol
 
     pub fn candle_count(&self, symbol: &str) -> usize {
@@ -152,6 +244,8 @@ impl CandleBuilder {
     pub fn clear_symbol(&mut self, symbol: &str) {
         self.price_updates.remove(symbol);
         self.candles.remove(symbol);
+        self.candle_starts.remove(symbol);
+        self.indicators.remove(symbol);
     }
 
     /// Get all tracked symbols
@@ -194,6 +288,11 @@ impl CandleBuilder {
             .retain(|symbol, _| active_symbols.contains(symbol));
         self.candles
             .retain(|symbol, candles| !candles.is_empty() && active_symbols.contains(symbol));
+        let candles = &self.candles;
+        self.candle_starts
+            .retain(|symbol, _| candles.contains_key(symbol));
+        self.indicators
+            .retain(|symbol, _| candles.contains_key(symbol));
     }
 }
 
@@ -315,4 +414,139 @@ mod tests {
         assert!(builder.price_updates.contains_key("ETH-USD"));
         assert!(!builder.price_updates.contains_key("BTC-USD"));
     }
+
+    #[test]
+    fn test_candle_starts_follow_the_window_grid() {
+        let minute = Duration::from_secs(60);
+        let at = |secs: u64| UNIX_EPOCH + Duration::from_secs(secs);
+        assert_eq!(CandleBuilder::grid_start(at(130), minute), at(120));
+        assert_eq!(CandleBuilder::grid_start(at(180), minute), at(180));
+
+        let mut builder = CandleBuilder::new(Duration::ZERO, 5);
+        for i in 0..8 {
+            builder.add_price("BTC-USD".to_string(), Price::new(100.0 + i as f64).unwrap());
+        }
+        let timed = builder.get_timed_candles("BTC-USD");
+        let candles = builder.get_candles("BTC-USD");
+        assert_eq!(timed.len(), candles.len());
+        assert_eq!(
+            timed.last().unwrap().1.close.value(),
+            candles.last().unwrap().close.value()
+        );
+        assert!(timed.windows(2).all(|w| w[0].0 <= w[1].0));
+
+        builder.clear_symbol("BTC-USD");
+        assert!(builder.get_timed_candles("BTC-USD").is_empty());
+    }
+
+    #[test]
+    fn test_streaming_indicators_follow_candles() {
+        use crate::domain::services::indicators::{Indicator, EMA, RSI};
+
+        // A zero window closes a candle on every price update
+        let mut builder = CandleBuilder::new(Duration::ZERO, 100);
+        builder.register_indicators(&[IndicatorSpec::Ema(3)]);
+
+        for i in 0..20 {
+            let price = Price::new(100.0 + (i % 5) as f64).unwrap();
+            builder.add_price("BTC-USD".to_string(), price);
+        }
+
+        // Registering later warms up on the existing history
+        builder.register_indicators(&[IndicatorSpec::Ema(3), IndicatorSpec::Rsi(5)]);
+
+        let candles = builder.get_candles("BTC-USD");
+        let values = builder.indicator_values("BTC-USD");
+        assert!(!candles.is_empty());
+        assert_eq!(
+            values.value(&IndicatorSpec::Ema(3)),
+            EMA::new(3).calculate(&candles).last().copied()
+        );
+        let rsi = values.value(&IndicatorSpec::Rsi(5)).unwrap();
+        let expected = *RSI::new(5).calculate(&candles).last().unwrap();
+        assert!((rsi - expected).abs() < 1e-9);
+
+        builder.clear_symbol("BTC-USD");
+        assert!(builder.indicator_values("BTC-USD").is_empty());
+    }
+
+    #[test]
+    fn test_streaming_indicators_follow_trimmed_history() {
+        use crate::domain::services::indicators::{
+            BollingerBands, Indicator, StochasticOscillator, EMA, MACD, RSI, VWAP,
+        };
+
+        let batch = |spec: &IndicatorSpec, candles: &[Candle]| -> Option<f64> {
+            match *spec {
+                IndicatorSpec::Ema(p) => EMA::new(p).calculate(candles).last().copied(),
+                IndicatorSpec::Rsi(p) => RSI::new(p).calculate(candles).last().copied(),
+                IndicatorSpec::Macd(f, s, g) => {
+                    MACD::new(f, s, g).calculate(candles).last().copied()
+                }
+                IndicatorSpec::Bollinger(p, k) => BollingerBands::new(p, k)
+                    .calculate_detailed(candles)
+                    .middle
+                    .last()
+                    .copied(),
+                IndicatorSpec::Stochastic(k, d) => StochasticOscillator::new(k, d)
+                    .calculate(candles)
+                    .last()
+                    .copied(),
+                IndicatorSpec::Vwap => VWAP.calculate(candles).last().copied(),
+            }
+        };
+        let early = [
+            IndicatorSpec::Ema(4),
+            IndicatorSpec::Rsi(5),
+            IndicatorSpec::Macd(3, 6, 3),
+            IndicatorSpec::Bollinger(5, 2.0),
+            IndicatorSpec::Stochastic(4, 3),
+            IndicatorSpec::Vwap,
+        ];
+        let late = IndicatorSpec::Ema(6);
+
+        // A zero window closes a candle on every price update
+        let mut builder = CandleBuilder::new(Duration::ZERO, 12);
+        builder.register_indicators(&early);
+        // Every candle closed, and where the late indicator's stream starts
+        let mut stream: Vec<Candle> = Vec::new();
+        let mut late_start = 0;
+        for i in 0..40 {
+            let x = i as f64;
+            let price = Price::new(100.0 + (x * 0.7).sin() * 5.0 + x * 0.3).unwrap();
+            builder.add_price("BTC-USD".to_string(), price);
+            stream.push(builder.get_candles("BTC-USD").last().unwrap().clone());
+            if i == 25 {
+                // Registered long after trimming started
+                builder.register_indicators(&[late]);
+                late_start = stream.len() - builder.get_candles("BTC-USD").len();
+            }
+
+            let candles = builder.get_candles("BTC-USD");
+            assert!(candles.len() <= 12);
+            let values = builder.indicator_values("BTC-USD");
+            let registered = if i >= 25 { &[late][..] } else { &[] };
+            for spec in early.iter().chain(registered) {
+                // EMA and MACD run over every candle fed, the rest over the window
+                let series = if *spec == late {
+                    &stream[late_start..]
+                } else if matches!(spec, IndicatorSpec::Ema(_) | IndicatorSpec::Macd(..)) {
+                    &stream[..]
+                } else {
+                    &candles[..]
+                };
+                match (values.value(spec), batch(spec, series)) {
+                    (Some(streamed), Some(expected)) => assert!(
+                        (streamed - expected).abs() <= 1e-9 * expected.abs().max(1.0),
+                        "{:?} after {} prices: {} vs {}",
+                        spec,
+                        i + 1,
+                        streamed,
+                        expected
+                    ),
+                    (streamed, expected) => assert_eq!(streamed, expected, "{:?}", spec),
+                }
+            }
+        }
+    }
 }
@@ -25,6 +25,123 @@ pub trait Indicator {
     fn calculate(&self, candles: &[Candle]) -> Vec<f64>;
 }
 
+/// Sum of floats held exactly as non-overlapping partials (Shewchuk)
+///
+/// `value` rounds the exact sum once, so it only depends on which terms were
+/// added and removed, not on their order. Window sums of the batch and
+/// streaming indicators go through it, which keeps both identical however
+/// the streaming window got to its current candles.
+#[derive(Debug, Clone, Default)]
+pub struct ExactSum {
+    /// Partials in increasing magnitude, none overlapping
+    partials: Vec<f64>,
+}
+
+impl ExactSum {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, value: f64) {
+        let mut x = value;
+        self.partials.retain_mut(|partial| {
+            let mut y = *partial;
+            if x.abs() < y.abs() {
+                std::mem::swap(&mut x, &mut y);
+            }
+            let hi = x + y;
+            let lo = y - (hi - x);
+            x = hi;
+            *partial = lo;
+            lo != 0.0
+        });
+        if x != 0.0 {
+            self.partials.push(x);
+        }
+    }
+
+    pub fn sub(&mut self, value: f64) {
+        self.add(-value);
+    }
+
+    /// Add the exact product `a * b`
+    pub fn add_product(&mut self, a: f64, b: f64) {
+        let product = a * b;
+        self.add(product);
+        self.add(a.mul_add(b, -product));
+    }
+
+    /// The exact sum rounded to the nearest float, ties to even
+    pub fn value(&self) -> f64 {
+        let partials = &self.partials;
+        let Some(&last) = partials.last() else {
+            return 0.0;
+        };
+        let mut n = partials.len() - 1;
+        let mut hi = last;
+        let mut lo = 0.0;
+        while n > 0 {
+            let x = hi;
+            n -= 1;
+            let y = partials[n];
+            hi = x + y;
+            lo = y - (hi - x);
+            if lo != 0.0 {
+                break;
+            }
+        }
+        // Half-way cases rounded across the remaining partials
+        if n > 0 && ((lo < 0.0 && partials[n - 1] < 0.0) || (lo > 0.0 && partials[n - 1] > 0.0)) {
+            let y = lo * 2.0;
+            let x = hi + y;
+            if y == x - hi {
+                hi = x;
+            }
+        }
+        hi
+    }
+}
+
+/// Mean and population variance of `count` values from the exact sums of
+/// the values and of their squares
+///
+/// The squared deviations from the rounded mean are summed exactly and
+/// rounded once.
+pub fn window_moments(sum: &ExactSum, squares: &ExactSum, count: usize) -> (f64, f64) {
+    let n = count as f64;
+    let mean = sum.value() / n;
+    // Σ(x - mean)² = Σx² - 2·mean·Σx + n·mean²
+    let mut deviations = squares.clone();
+    for &partial in &sum.partials {
+        deviations.add_product(-2.0 * mean, partial);
+    }
+    let scaled = n * mean;
+    deviations.add_product(scaled, mean);
+    deviations.add_product(n.mul_add(mean, -scaled), mean);
+    (mean, deviations.value().max(0.0) / n)
+}
+
+/// Mean and population variance of the closes of a window
+fn close_moments(slice: &[Candle]) -> (f64, f64) {
+    let mut sum = ExactSum::new();
+    let mut squares = ExactSum::new();
+    for candle in slice {
+        let close = candle.close.value();
+        sum.add(close);
+        squares.add_product(close, close);
+    }
+    window_moments(&sum, &squares, slice.len())
+}
+
+/// Exactly rounded sum of a slice
+fn exact_sum(values: &[f64]) -> f64 {
+    let mut sum = ExactSum::new();
+    for &value in values {
+        sum.add(value);
+    }
+    sum.value()
+}
+
 pub struct EMA {
     pub period: usize,
 }
@@ -121,8 +238,8 @@ impl Indicator for RSI {
         for i in self.period..=gains.len() {
             let start_idx = i - self.period;
             let end_idx = i - 1;
-            let avg_gain = gains[start_idx..=end_idx].iter().sum::<f64>() / self.period as f64;
-            let avg_loss = losses[start_idx..=end_idx].iter().sum::<f64>() / self.period as f64;
+            let avg_gain = exact_sum(&gains[start_idx..=end_idx]) / self.period as f64;
+            let avg_loss = exact_sum(&losses[start_idx..=end_idx]) / self.period as f64;
             let rs = if avg_loss == 0.0 {
                 100.0
             } else {
@@ -170,13 +287,7 @@ impl BollingerBands {
         for i in self.period..=candles.len() {
             let start_idx = i - self.period;
             let end_idx = i - 1;
-            let slice = &candles[start_idx..=end_idx];
-            let sma = slice.iter().map(|c| c.close.value()).sum::<f64>() / self.period as f64;
-            let variance = slice
-                .iter()
-                .map(|c| (c.close.value() - sma).powi(2))
-                .sum::<f64>()
-                / self.period as f64;
+            let (sma, variance) = close_moments(&candles[start_idx..=end_idx]);
             let std = variance.sqrt();
 
             upper.push(sma + self.std_dev * std);
@@ -202,13 +313,7 @@ impl Indicator for BollingerBands {
         for i in self.period..=candles.len() {
             let start_idx = i - self.period;
             let end_idx = i - 1;
-            let slice = &candles[start_idx..=end_idx];
-            let sma = slice.iter().map(|c| c.close.value()).sum::<f64>() / self.period as f64;
-            let variance = slice
-                .iter()
-                .map(|c| (c.close.value() - sma).powi(2))
-                .sum::<f64>()
-                / self.period as f64;
+            let (sma, variance) = close_moments(&candles[start_idx..=end_idx]);
             let std = variance.sqrt();
             bands.push(sma + self.std_dev * std); // Upper band
             bands.push(sma); // Middle (SMA)
@@ -241,11 +346,18 @@ impl Indicator for MACD {
         let slow_ema = EMA::new(self.slow_period);
         let fast_values = fast_ema.calculate(candles);
         let slow_values = slow_ema.calculate(candles);
+        if fast_values.is_empty() || slow_values.is_empty() {
+            return vec![];
+        }
 
-        let macd_line: Vec<f64> = fast_values
-            .iter()
-            .zip(slow_values.iter())
-            .map(|(f, s)| f - s)
+        // EMA values start at candle min(period, len) - 1, so zipping both lines
+        // would subtract a slow EMA from a fast EMA taken `slow - fast` candles
+        // earlier. Pair them on the same candle; the MACD line starts once the
+        // slower EMA is seeded.
+        let fast_offset = self.fast_period.min(candles.len()) - 1;
+        let slow_offset = self.slow_period.min(candles.len()) - 1;
+        let macd_line: Vec<f64> = (fast_offset.max(slow_offset)..candles.len())
+            .map(|i| fast_values[i - fast_offset] - slow_values[i - slow_offset])
             .collect();
 
         let signal_ema = EMA::new(self.signal_period);
@@ -303,7 +415,7 @@ impl Indicator for StochasticOscillator {
         for i in self.d_period..=k_values.len() {
             let start_idx = i - self.d_period;
             let end_idx = i - 1;
-            let sum: f64 = k_values[start_idx..=end_idx].iter().sum();
+            let sum = exact_sum(&k_values[start_idx..=end_idx]);
             d_values.push(sum / self.d_period as f64);
         }
 
@@ -316,18 +428,19 @@ pub struct VWAP;
 impl Indicator for VWAP {
     fn calculate(&self, candles: &[Candle]) -> Vec<f64> {
         let mut vwap_values = Vec::new();
-        let mut cumulative_volume = 0.0;
-        let mut cumulative_volume_price = 0.0;
+        let mut cumulative_volume = ExactSum::new();
+        let mut cumulative_volume_price = ExactSum::new();
 
         for candle in candles {
             let typical_price =
                 (candle.high.value() + candle.low.value() + candle.close.value()) / 3.0;
-            cumulative_volume += candle.volume;
-            cumulative_volume_price += typical_price * candle.volume;
+            cumulative_volume.add(candle.volume);
+            cumulative_volume_price.add_product(typical_price, candle.volume);
 
             // Handle division by zero when cumulative volume is zero
-            let vwap = if cumulative_volume > f64::EPSILON {
-                cumulative_volume_price / cumulative_volume
+            let volume = cumulative_volume.value();
+            let vwap = if volume > f64::EPSILON {
+                cumulative_volume_price.value() / volume
             } else {
                 typical_price // Use typical price if no volume
             };
@@ -338,6 +451,539 @@ impl Indicator for VWAP {
     }
 }
 
+/// Highest high and lowest low of a candle window
+fn high_low(slice: &[Candle]) -> (f64, f64) {
+    slice
+        .iter()
+        .fold((f64::NEG_INFINITY, f64::INFINITY), |(hi, lo), c| {
+            (hi.max(c.high.value()), lo.min(c.low.value()))
+        })
+}
+
+/// True range of each candle after the first
+fn true_ranges(candles: &[Candle]) -> Vec<f64> {
+    candles
+        .windows(2)
+        .map(|w| {
+            let (prev_close, high, low) = (w[0].close.value(), w[1].high.value(), w[1].low.value());
+            (high - low)
+                .max((high - prev_close).abs())
+                .max((low - prev_close).abs())
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct AtrValues {
+    /// True range of every candle after the first
+    pub true_range: Vec<f64>,
+    /// Wilder-smoothed ATR, first value at candle index `period`
+    pub atr: Vec<f64>,
+}
+
+/// Average True Range with Wilder smoothing
+pub struct ATR {
+    pub period: usize,
+}
+
+impl ATR {
+    pub fn new(period: usize) -> Self {
+        ATR { period }
+    }
+
+    /// Calculate ATR and return the underlying true ranges as well
+    pub fn calculate_detailed(&self, candles: &[Candle]) -> AtrValues {
+        let true_range = true_ranges(candles);
+        if self.period == 0 || true_range.len() < self.period {
+            return AtrValues {
+                true_range,
+                atr: vec![],
+            };
+        }
+
+        let mut atr = true_range[..self.period].iter().sum::<f64>() / self.period as f64;
+        let mut atr_values = vec![atr];
+        for tr in &true_range[self.period..] {
+            atr = (atr * (self.period as f64 - 1.0) + tr) / self.period as f64;
+            atr_values.push(atr);
+        }
+
+        AtrValues {
+            true_range,
+            atr: atr_values,
+        }
+    }
+}
+
+impl Indicator for ATR {
+    fn calculate(&self, candles: &[Candle]) -> Vec<f64> {
+        self.calculate_detailed(candles).atr
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AdxValues {
+    /// +DI, first value at candle index `period`
+    pub plus_di: Vec<f64>,
+    /// -DI, aligned with `plus_di`
+    pub minus_di: Vec<f64>,
+    /// ADX, first value at candle index `2 * period - 1`
+    pub adx: Vec<f64>,
+}
+
+/// Average Directional Index with the directional movement indicators (Wilder)
+pub struct ADX {
+    pub period: usize,
+}
+
+impl ADX {
+    pub fn new(period: usize) -> Self {
+        ADX { period }
+    }
+
+    /// Calculate ADX together with +DI and -DI
+    pub fn calculate_detailed(&self, candles: &[Candle]) -> AdxValues {
+        let mut values = AdxValues {
+            plus_di: vec![],
+            minus_di: vec![],
+            adx: vec![],
+        };
+        if self.period == 0 || candles.len() < self.period + 1 {
+            return values;
+        }
+
+        let tr = true_ranges(candles);
+        let (plus_dm, minus_dm): (Vec<f64>, Vec<f64>) = candles
+            .windows(2)
+            .map(|w| {
+                let up = w[1].high.value() - w[0].high.value();
+                let down = w[0].low.value() - w[1].low.value();
+                (
+                    if up > down && up > 0.0 { up } else { 0.0 },
+                    if down > up && down > 0.0 { down } else { 0.0 },
+                )
+            })
+            .unzip();
+
+        let n = self.period as f64;
+        let mut smoothed_tr: f64 = tr[..self.period].iter().sum();
+        let mut smoothed_plus: f64 = plus_dm[..self.period].iter().sum();
+        let mut smoothed_minus: f64 = minus_dm[..self.period].iter().sum();
+        let mut dx = Vec::new();
+
+        for i in self.period..=tr.len() {
+            if i > self.period {
+                smoothed_tr = smoothed_tr - smoothed_tr / n + tr[i - 1];
+                smoothed_plus = smoothed_plus - smoothed_plus / n + plus_dm[i - 1];
+                smoothed_minus = smoothed_minus - smoothed_minus / n + minus_dm[i - 1];
+            }
+
+            let (plus_di, minus_di) = if smoothed_tr > f64::EPSILON {
+                (
+                    100.0 * smoothed_plus / smoothed_tr,
+                    100.0 * smoothed_minus / smoothed_tr,
+                )
+            } else {
+                (0.0, 0.0)
+            };
+            let di_sum = plus_di + minus_di;
+            dx.push(if di_sum > f64::EPSILON {
+                100.0 * (plus_di - minus_di).abs() / di_sum
+            } else {
+                0.0
+            });
+            values.plus_di.push(plus_di);
+            values.minus_di.push(minus_di);
+        }
+
+        if dx.len() >= self.period {
+            let mut adx = dx[..self.period].iter().sum::<f64>() / n;
+            values.adx.push(adx);
+            for value in &dx[self.period..] {
+                adx = (adx * (n - 1.0) + value) / n;
+                values.adx.push(adx);
+            }
+        }
+
+        values
+    }
+}
+
+impl Indicator for ADX {
+    fn calculate(&self, candles: &[Candle]) -> Vec<f64> {
+        self.calculate_detailed(candles).adx
+    }
+}
+
+/// On-Balance Volume, starting at zero on the first candle
+pub struct OBV;
+
+impl Indicator for OBV {
+    fn calculate(&self, candles: &[Candle]) -> Vec<f64> {
+        let mut obv_values = Vec::with_capacity(candles.len());
+        let mut obv = 0.0;
+
+        for (i, candle) in candles.iter().enumerate() {
+            if i > 0 {
+                let prev_close = candles[i - 1].close.value();
+                let close = candle.close.value();
+                if close > prev_close {
+                    obv += candle.volume;
+                } else if close < prev_close {
+                    obv -= candle.volume;
+                }
+            }
+            obv_values.push(obv);
+        }
+
+        obv_values
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChannelValues {
+    pub upper: Vec<f64>,
+    pub middle: Vec<f64>,
+    pub lower: Vec<f64>,
+}
+
+impl ChannelValues {
+    fn empty() -> Self {
+        ChannelValues {
+            upper: vec![],
+            middle: vec![],
+            lower: vec![],
+        }
+    }
+
+    /// Flatten into `[upper, middle, lower, ...]` like `BollingerBands::calculate`
+    fn interleaved(&self) -> Vec<f64> {
+        self.upper
+            .iter()
+            .zip(&self.middle)
+            .zip(&self.lower)
+            .flat_map(|((u, m), l)| [*u, *m, *l])
+            .collect()
+    }
+}
+
+/// Keltner Channels: EMA of closes with ATR-based bands
+pub struct KeltnerChannels {
+    pub ema_period: usize,
+    pub atr_period: usize,
+    pub multiplier: f64,
+}
+
+impl KeltnerChannels {
+    pub fn new(ema_period: usize, atr_period: usize, multiplier: f64) -> Self {
+        KeltnerChannels {
+            ema_period,
+            atr_period,
+            multiplier,
+        }
+    }
+
+    /// Calculate the channel lines, first value where both EMA and ATR exist
+    pub fn calculate_detailed(&self, candles: &[Candle]) -> ChannelValues {
+        if self.ema_period == 0 || self.atr_period == 0 || candles.len() < self.ema_period {
+            return ChannelValues::empty();
+        }
+
+        // EMA values start at candle index ema_period - 1, ATR at atr_period
+        let ema = EMA::new(self.ema_period).calculate(candles);
+        let atr = ATR::new(self.atr_period).calculate(candles);
+        let start = (self.ema_period - 1).max(self.atr_period);
+
+        let mut values = ChannelValues::empty();
+        for i in start..candles.len() {
+            let middle = ema[i + 1 - self.ema_period];
+            let band = self.multiplier * atr[i - self.atr_period];
+            values.upper.push(middle + band);
+            values.middle.push(middle);
+            values.lower.push(middle - band);
+        }
+
+        values
+    }
+}
+
+impl Indicator for KeltnerChannels {
+    fn calculate(&self, candles: &[Candle]) -> Vec<f64> {
+        self.calculate_detailed(candles).interleaved()
+    }
+}
+
+/// Donchian Channels: highest high and lowest low over a window
+pub struct DonchianChannels {
+    pub period: usize,
+}
+
+impl DonchianChannels {
+    pub fn new(period: usize) -> Self {
+        DonchianChannels { period }
+    }
+
+    /// Calculate the channel lines, first value at candle index `period - 1`
+    pub fn calculate_detailed(&self, candles: &[Candle]) -> ChannelValues {
+        if self.period == 0 || candles.len() < self.period {
+            return ChannelValues::empty();
+        }
+
+        let mut values = ChannelValues::empty();
+        for window in candles.windows(self.period) {
+            let (highest, lowest) = high_low(window);
+            values.upper.push(highest);
+            values.middle.push((highest + lowest) / 2.0);
+            values.lower.push(lowest);
+        }
+
+        values
+    }
+}
+
+impl Indicator for DonchianChannels {
+    fn calculate(&self, candles: &[Candle]) -> Vec<f64> {
+        self.calculate_detailed(candles).interleaved()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SuperTrendValues {
+    /// Active band (support in uptrends, resistance in downtrends)
+    pub supertrend: Vec<f64>,
+    /// True while the trend is up, aligned with `supertrend`
+    pub uptrend: Vec<bool>,
+}
+
+/// SuperTrend: ATR bands around the candle midpoint that flip with the trend
+pub struct SuperTrend {
+    pub period: usize,
+    pub multiplier: f64,
+}
+
+impl SuperTrend {
+    pub fn new(period: usize, multiplier: f64) -> Self {
+        SuperTrend { period, multiplier }
+    }
+
+    /// Calculate the SuperTrend line and direction, first value at candle index `period`
+    pub fn calculate_detailed(&self, candles: &[Candle]) -> SuperTrendValues {
+        let mut values = SuperTrendValues {
+            supertrend: vec![],
+            uptrend: vec![],
+        };
+        let atr = ATR::new(self.period).calculate(candles);
+        if atr.is_empty() {
+            return values;
+        }
+
+        let mut final_upper = f64::INFINITY;
+        let mut final_lower = f64::NEG_INFINITY;
+        let mut uptrend = true;
+
+        for (offset, atr_value) in atr.iter().enumerate() {
+            let i = self.period + offset;
+            let candle = &candles[i];
+            let prev_close = candles[i - 1].close.value();
+            let mid = (candle.high.value() + candle.low.value()) / 2.0;
+            let basic_upper = mid + self.multiplier * atr_value;
+            let basic_lower = mid - self.multiplier * atr_value;
+
+            final_upper = if basic_upper < final_upper || prev_close > final_upper {
+                basic_upper
+            } else {
+                final_upper
+            };
+            final_lower = if basic_lower > final_lower || prev_close < final_lower {
+                basic_lower
+            } else {
+                final_lower
+            };
+
+            let close = candle.close.value();
+            if offset == 0 {
+                uptrend = close >= mid;
+            } else if uptrend && close < final_lower {
+                uptrend = false;
+            } else if !uptrend && close > final_upper {
+                uptrend = true;
+            }
+
+            values
+                .supertrend
+                .push(if uptrend { final_lower } else { final_upper });
+            values.uptrend.push(uptrend);
+        }
+
+        values
+    }
+}
+
+impl Indicator for SuperTrend {
+    fn calculate(&self, candles: &[Candle]) -> Vec<f64> {
+        self.calculate_detailed(candles).supertrend
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IchimokuValues {
+    /// Conversion line, first value at candle index `tenkan_period - 1`
+    pub tenkan_sen: Vec<f64>,
+    /// Base line, first value at candle index `kijun_period - 1`
+    pub kijun_sen: Vec<f64>,
+    /// Leading span A as computed at each candle (plotted `kijun_period` ahead),
+    /// aligned with `kijun_sen`
+    pub senkou_span_a: Vec<f64>,
+    /// Leading span B as computed at each candle (plotted `kijun_period` ahead),
+    /// first value at candle index `senkou_b_period - 1`
+    pub senkou_span_b: Vec<f64>,
+    /// Lagging span: closes plotted `kijun_period` back
+    pub chikou_span: Vec<f64>,
+}
+
+/// Ichimoku Kinko Hyo (defaults 9/26/52)
+pub struct Ichimoku {
+    pub tenkan_period: usize,
+    pub kijun_period: usize,
+    pub senkou_b_period: usize,
+}
+
+impl Ichimoku {
+    pub fn new(tenkan_period: usize, kijun_period: usize, senkou_b_period: usize) -> Self {
+        Ichimoku {
+            tenkan_period,
+            kijun_period,
+            senkou_b_period,
+        }
+    }
+
+    fn midpoints(candles: &[Candle], period: usize) -> Vec<f64> {
+        if period == 0 || candles.len() < period {
+            return vec![];
+        }
+        candles
+            .windows(period)
+            .map(|window| {
+                let (highest, lowest) = high_low(window);
+                (highest + lowest) / 2.0
+            })
+            .collect()
+    }
+
+    /// Calculate all five Ichimoku lines
+    pub fn calculate_detailed(&self, candles: &[Candle]) -> IchimokuValues {
+        let tenkan_sen = Self::midpoints(candles, self.tenkan_period);
+        let kijun_sen = Self::midpoints(candles, self.kijun_period);
+        let senkou_span_b = Self::midpoints(candles, self.senkou_b_period);
+
+        // Align the tenkan-sen with the (shorter) kijun-sen before averaging
+        let senkou_span_a = if kijun_sen.is_empty() || tenkan_sen.len() < kijun_sen.len() {
+            vec![]
+        } else {
+            let skip = tenkan_sen.len() - kijun_sen.len();
+            tenkan_sen[skip..]
+                .iter()
+                .zip(&kijun_sen)
+                .map(|(tenkan, kijun)| (tenkan + kijun) / 2.0)
+                .collect()
+        };
+
+        IchimokuValues {
+            tenkan_sen,
+            kijun_sen,
+            senkou_span_a,
+            senkou_span_b,
+            chikou_span: candles.iter().map(|c| c.close.value()).collect(),
+        }
+    }
+}
+
+impl Default for Ichimoku {
+    fn default() -> Self {
+        Self::new(9, 26, 52)
+    }
+}
+
+impl Indicator for Ichimoku {
+    /// Returns the conversion line; use `calculate_detailed` for the others
+    fn calculate(&self, candles: &[Candle]) -> Vec<f64> {
+        Self::midpoints(candles, self.tenkan_period)
+    }
+}
+
+/// Commodity Channel Index
+pub struct CCI {
+    pub period: usize,
+}
+
+impl CCI {
+    pub fn new(period: usize) -> Self {
+        CCI { period }
+    }
+}
+
+impl Indicator for CCI {
+    fn calculate(&self, candles: &[Candle]) -> Vec<f64> {
+        if self.period == 0 || candles.len() < self.period {
+            return vec![];
+        }
+
+        let typical: Vec<f64> = candles
+            .iter()
+            .map(|c| (c.high.value() + c.low.value() + c.close.value()) / 3.0)
+            .collect();
+
+        typical
+            .windows(self.period)
+            .map(|window| {
+                let sma = window.iter().sum::<f64>() / self.period as f64;
+                let mean_deviation =
+                    window.iter().map(|tp| (tp - sma).abs()).sum::<f64>() / self.period as f64;
+                let current = window[self.period - 1];
+                // Flat window: no deviation to scale by
+                if mean_deviation > f64::EPSILON {
+                    (current - sma) / (0.015 * mean_deviation)
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
+
+/// Williams %R, ranging from -100 (at the low) to 0 (at the high)
+pub struct WilliamsR {
+    pub period: usize,
+}
+
+impl WilliamsR {
+    pub fn new(period: usize) -> Self {
+        WilliamsR { period }
+    }
+}
+
+impl Indicator for WilliamsR {
+    fn calculate(&self, candles: &[Candle]) -> Vec<f64> {
+        if self.period == 0 || candles.len() < self.period {
+            return vec![];
+        }
+
+        candles
+            .windows(self.period)
+            .map(|window| {
+                let (highest, lowest) = high_low(window);
+                let close = window[self.period - 1].close.value();
+                let range = highest - lowest;
+                if range > f64::EPSILON {
+                    -100.0 * (highest - close) / range
+                } else {
+                    -50.0 // Neutral value when no price movement
+                }
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -356,6 +1002,43 @@ mod tests {
         assert!(values[0] > 100.0);
     }
 
+    #[test]
+    fn test_exact_sum_ignores_order_and_removed_terms() {
+        let values = [1e16, 0.1, -1e16, 0.2, 3.3, -0.7, 1e-3];
+        let mut forward = ExactSum::new();
+        for value in values {
+            forward.add(value);
+        }
+        let mut backward = ExactSum::new();
+        for value in values.iter().rev() {
+            backward.add(*value);
+        }
+        assert_eq!(forward.value(), backward.value());
+        assert_eq!(forward.value(), 2.901);
+
+        // Adding and removing a term leaves no rounding behind
+        let mut sum = ExactSum::new();
+        sum.add(0.1);
+        sum.add(123456.789);
+        sum.sub(123456.789);
+        assert_eq!(sum.value(), 0.1);
+        sum.sub(0.1);
+        assert_eq!(sum.value(), 0.0);
+    }
+
+    #[test]
+    fn test_window_moments_of_constant_values_have_no_variance() {
+        let mut sum = ExactSum::new();
+        let mut squares = ExactSum::new();
+        for _ in 0..7 {
+            sum.add(0.1);
+            squares.add_product(0.1, 0.1);
+        }
+        let (mean, variance) = window_moments(&sum, &squares, 7);
+        assert_eq!(mean, 0.1);
+        assert_eq!(variance, 0.0);
+    }
+
     #[test]
     fn test_rsi_calculation() {
         let candles = vec![
@@ -371,6 +1054,28 @@ mod tests {
         assert!(values[0] >= 0.0 && values[0] <= 100.0);
     }
 
+    #[test]
+    fn test_macd_pairs_emas_on_the_same_candle() {
+        let candles: Vec<Candle> = (0..12)
+            .map(|i| {
+                let close = 100.0 + (i * i) as f64;
+                Candle::new(close, close + 1.0, close - 1.0, close, 1000.0).unwrap()
+            })
+            .collect();
+        let fast = EMA::new(3).calculate(&candles);
+        let slow = EMA::new(6).calculate(&candles);
+
+        // A one-period signal EMA returns the MACD line itself
+        let macd = MACD::new(3, 6, 1).calculate(&candles);
+
+        // The line starts at candle 5, where the slow EMA is seeded
+        assert_eq!(macd.len(), candles.len() - 5);
+        for (j, value) in macd.iter().enumerate() {
+            // Fast EMA values start at candle 2, slow ones at candle 5
+            assert_eq!(*value, fast[j + 3] - slow[j]);
+        }
+    }
+
     #[test]
     fn test_vwap_calculation() {
         let candles = vec![
@@ -382,4 +1087,195 @@ mod tests {
         assert_eq!(values.len(), 2);
         assert!(values[1] > values[0]); // Should increase with volume
     }
+
+    #[test]
+    fn test_atr_wilder_smoothing() {
+        let candles = vec![
+            Candle::new(10.0, 11.0, 9.0, 10.0, 1.0).unwrap(),
+            Candle::new(10.0, 12.0, 10.0, 11.0, 1.0).unwrap(), // TR = 2
+            Candle::new(11.0, 11.5, 10.5, 11.0, 1.0).unwrap(), // TR = 1
+            Candle::new(11.0, 14.0, 11.0, 13.0, 1.0).unwrap(), // TR = 3
+        ];
+        let atr = ATR::new(2).calculate(&candles);
+
+        assert_eq!(atr.len(), 2);
+        assert!((atr[0] - 1.5).abs() < 1e-9);
+        assert!((atr[1] - 2.25).abs() < 1e-9);
+        assert!(ATR::new(5).calculate(&candles).is_empty());
+    }
+
+    /// Reference dataset (high, low, close, volume); expected values below were
+    /// computed independently with the textbook formulas
+    fn reference_candles() -> Vec<Candle> {
+        [
+            (48.70, 47.79, 48.16, 1000.0),
+            (48.72, 48.14, 48.61, 1200.0),
+            (48.90, 48.39, 48.75, 900.0),
+            (48.87, 48.37, 48.63, 1500.0),
+            (48.82, 48.24, 48.74, 1100.0),
+            (49.05, 48.64, 49.03, 1300.0),
+            (49.20, 48.94, 49.07, 800.0),
+            (49.35, 48.86, 49.32, 1700.0),
+            (49.92, 49.50, 49.91, 2100.0),
+            (50.19, 49.87, 50.13, 1600.0),
+            (50.12, 49.20, 49.53, 1900.0),
+            (49.66, 48.90, 49.50, 1400.0),
+            (49.88, 49.43, 49.75, 1000.0),
+            (50.19, 49.73, 50.03, 1250.0),
+            (50.36, 49.26, 50.31, 1800.0),
+            (50.57, 50.09, 50.52, 1700.0),
+            (50.65, 50.30, 50.41, 1100.0),
+            (50.43, 49.21, 49.34, 2300.0),
+            (49.63, 48.98, 49.37, 1500.0),
+            (50.33, 49.61, 50.23, 1600.0),
+            (50.29, 49.20, 49.24, 2000.0),
+            (50.17, 49.43, 49.93, 1200.0),
+            (49.32, 48.08, 48.43, 2600.0),
+            (48.50, 47.64, 48.18, 2400.0),
+            (48.32, 41.55, 46.57, 5000.0),
+            (46.80, 44.28, 45.41, 3100.0),
+            (47.80, 47.31, 47.77, 1800.0),
+            (48.39, 47.20, 47.72, 1500.0),
+            (48.66, 47.90, 48.62, 1400.0),
+            (48.79, 47.73, 47.85, 1300.0),
+        ]
+        .iter()
+        .map(|&(high, low, close, volume)| Candle::new(close, high, low, close, volume).unwrap())
+        .collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_atr_reference_values() {
+        let values = ATR::new(14).calculate_detailed(&reference_candles());
+
+        assert_eq!(values.true_range.len(), 29);
+        assert_eq!(values.atr.len(), 16);
+        assert_close(values.atr[0], 0.567857142857);
+        assert_close(values.atr[15], 1.307982853379);
+    }
+
+    #[test]
+    fn test_adx_reference_values() {
+        let values = ADX::new(14).calculate_detailed(&reference_candles());
+
+        assert_eq!(values.plus_di.len(), 16);
+        assert_eq!(values.minus_di.len(), 16);
+        assert_eq!(values.adx.len(), 3);
+        assert_close(values.plus_di[0], 26.415094339623);
+        assert_close(values.minus_di[0], 20.0);
+        assert_close(values.plus_di[15], 14.687860206533);
+        assert_close(values.minus_di[15], 36.824812960494);
+        assert_close(values.adx[0], 28.597874845256);
+        assert_close(values.adx[2], 30.508814954000);
+    }
+
+    #[test]
+    fn test_obv_reference_values() {
+        let values = OBV.calculate(&reference_candles());
+
+        assert_eq!(values.len(), 30);
+        assert_eq!(values[0], 0.0);
+        assert_close(values[9], 9200.0);
+        assert_close(values[29], -2150.0);
+    }
+
+    #[test]
+    fn test_keltner_reference_values() {
+        let keltner = KeltnerChannels::new(20, 10, 2.0);
+        let values = keltner.calculate_detailed(&reference_candles());
+
+        assert_eq!(values.middle.len(), 11);
+        assert_close(values.upper[0], 50.776301335524);
+        assert_close(values.middle[0], 49.467);
+        assert_close(values.lower[0], 48.157698664476);
+        assert_close(values.upper[10], 51.394763972547);
+        assert_close(values.middle[10], 48.452696692593);
+        assert_close(values.lower[10], 45.510629412640);
+        assert_eq!(keltner.calculate(&reference_candles()).len(), 33);
+    }
+
+    #[test]
+    fn test_donchian_reference_values() {
+        let values = DonchianChannels::new(20).calculate_detailed(&reference_candles());
+
+        assert_eq!(values.upper.len(), 11);
+        assert_close(values.upper[0], 50.65);
+        assert_close(values.middle[0], 49.22);
+        assert_close(values.lower[0], 47.79);
+        assert_close(values.upper[10], 50.65);
+        assert_close(values.middle[10], 46.1);
+        assert_close(values.lower[10], 41.55);
+    }
+
+    #[test]
+    fn test_supertrend_reference_values() {
+        let values = SuperTrend::new(5, 1.0).calculate_detailed(&reference_candles());
+
+        assert_eq!(values.supertrend.len(), 25);
+        assert_close(values.supertrend[0], 48.329);
+        assert_close(values.supertrend[3], 49.214128);
+        assert_close(values.supertrend[14], 49.227664290138);
+        assert_close(values.supertrend[24], 46.587570711623);
+
+        // Trend flips down at 5, up at 8, down at 12, up at 14, down at 17, up at 21
+        let flips: Vec<usize> = values
+            .uptrend
+            .windows(2)
+            .enumerate()
+            .filter(|(_, w)| w[0] != w[1])
+            .map(|(i, _)| i + 1)
+            .collect();
+        assert_eq!(flips, vec![5, 8, 12, 14, 17, 21]);
+    }
+
+    #[test]
+    fn test_ichimoku_reference_values() {
+        let candles = reference_candles();
+        let values = Ichimoku::default().calculate_detailed(&candles);
+
+        assert_eq!(values.tenkan_sen.len(), 22);
+        assert_eq!(values.kijun_sen.len(), 5);
+        assert_eq!(values.senkou_span_a.len(), 5);
+        assert!(values.senkou_span_b.is_empty());
+        assert_eq!(values.chikou_span.len(), 30);
+        assert_close(values.tenkan_sen[0], 48.855);
+        assert_close(values.tenkan_sen[21], 45.86);
+        assert_close(values.kijun_sen[0], 46.1);
+        assert_close(values.senkou_span_a[4], 45.98);
+
+        let short = Ichimoku::new(3, 5, 7).calculate_detailed(&candles);
+        assert_eq!(short.senkou_span_a.len(), 26);
+        assert_eq!(short.senkou_span_b.len(), 24);
+        assert_close(short.senkou_span_a[25], 47.265);
+        assert_close(short.senkou_span_b[0], 48.495);
+        assert_close(short.senkou_span_b[23], 45.17);
+    }
+
+    #[test]
+    fn test_cci_reference_values() {
+        let values = CCI::new(20).calculate(&reference_candles());
+
+        assert_eq!(values.len(), 11);
+        assert_close(values[0], 77.358677358677);
+        assert_close(values[10], -44.028833041107);
+    }
+
+    #[test]
+    fn test_williams_r_reference_values() {
+        let values = WilliamsR::new(14).calculate(&reference_candles());
+
+        assert_eq!(values.len(), 17);
+        assert_close(values[0], -6.666666666667);
+        assert_close(values[16], -30.769230769231);
+        assert!(values.iter().all(|v| (-100.0..=0.0).contains(v)));
+    }
 }
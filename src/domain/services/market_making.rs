@@ -0,0 +1,371 @@
+//! Market making
+//!
+//! Prices two-sided post-only quotes around a reference price (the mid, or
+//! the microprice when top-of-book sizes are known). The quoted spread is a
+//! base spread plus a multiple of realized volatility, clamped to a range.
+//! Quotes are skewed against inventory: a long book shifts both quotes down so
+//! the ask is more likely to fill, a short book shifts them up. Each side is
+//! sized so a fill can never take inventory beyond `max_inventory`, and a side
+//! whose cap is reached is not quoted at all. Without short selling (spot
+//! venues) the ask is limited to the inventory actually held.
+
+use crate::domain::entities::order::OrderSide;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Spread, sizing and refresh settings of the market maker
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketMakingParams {
+    /// Spread quoted in a market without volatility, in basis points
+    pub base_spread_bps: f64,
+    pub min_spread_bps: f64,
+    pub max_spread_bps: f64,
+    /// Basis points of spread added per basis point of realized volatility
+    pub volatility_multiplier: f64,
+    /// Candles used to measure realized volatility
+    pub volatility_window: usize,
+    /// Base quantity of each quote
+    pub order_size: f64,
+    /// Largest absolute inventory the quotes may build up
+    pub max_inventory: f64,
+    /// Fraction of the half spread the reference shifts at full inventory (0-1)
+    pub inventory_skew: f64,
+    /// Reference move, in basis points, after which quotes are replaced
+    pub refresh_threshold_bps: f64,
+    /// Price increment; bids are rounded down and asks up to it
+    pub tick_size: f64,
+}
+
+impl Default for MarketMakingParams {
+    fn default() -> Self {
+        Self {
+            base_spread_bps: 10.0,
+            min_spread_bps: 4.0,
+            max_spread_bps: 100.0,
+            volatility_multiplier: 2.0,
+            volatility_window: 20,
+            order_size: 0.001,
+            max_inventory: 0.01,
+            inventory_skew: 1.0,
+            refresh_threshold_bps: 5.0,
+            tick_size: 0.01,
+        }
+    }
+}
+
+impl MarketMakingParams {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("base_spread_bps", self.base_spread_bps),
+            ("min_spread_bps", self.min_spread_bps),
+            ("max_spread_bps", self.max_spread_bps),
+            ("order_size", self.order_size),
+            ("max_inventory", self.max_inventory),
+            ("refresh_threshold_bps", self.refresh_threshold_bps),
+            ("tick_size", self.tick_size),
+        ] {
+            if !(value > 0.0 && value.is_finite()) {
+                return Err(format!("{} ({}) must be positive", name, value));
+            }
+        }
+        if self.min_spread_bps > self.max_spread_bps {
+            return Err(format!(
+                "min_spread_bps ({}) must not exceed max_spread_bps ({})",
+                self.min_spread_bps, self.max_spread_bps
+            ));
+        }
+        if !(self.volatility_multiplier >= 0.0) {
+            return Err(format!(
+                "volatility_multiplier ({}) must not be negative",
+                self.volatility_multiplier
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.inventory_skew) {
+            return Err(format!(
+                "inventory_skew ({}) must be between 0 and 1",
+                self.inventory_skew
+            ));
+        }
+        if self.volatility_window < 2 {
+            return Err(format!(
+                "volatility_window ({}) must be at least 2",
+                self.volatility_window
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Size-weighted mid of the best bid and ask
+///
+/// Leans towards the side with less size, where the next trade is more
+/// likely to move the price. Falls back to the plain mid without size.
+pub fn microprice(bid: f64, bid_size: f64, ask: f64, ask_size: f64) -> f64 {
+    let total = bid_size + ask_size;
+    if total > 0.0 {
+        (bid * ask_size + ask * bid_size) / total
+    } else {
+        (bid + ask) / 2.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteSide {
+    Bid,
+    Ask,
+}
+
+impl QuoteSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuoteSide::Bid => "bid",
+            QuoteSide::Ask => "ask",
+        }
+    }
+
+    pub fn order_side(&self) -> OrderSide {
+        match self {
+            QuoteSide::Bid => OrderSide::Buy,
+            QuoteSide::Ask => OrderSide::Sell,
+        }
+    }
+
+    /// Inventory change when a quote of `quantity` on this side fills
+    pub fn signed(&self, quantity: f64) -> f64 {
+        match self {
+            QuoteSide::Bid => quantity,
+            QuoteSide::Ask => -quantity,
+        }
+    }
+}
+
+/// One side of a two-sided quote
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    pub side: QuoteSide,
+    pub price: f64,
+    pub quantity: f64,
+}
+
+/// Bid and ask for a reference price; a side is None at its inventory cap
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteSet {
+    pub reference: f64,
+    pub spread_bps: f64,
+    pub bid: Option<Quote>,
+    pub ask: Option<Quote>,
+}
+
+impl QuoteSet {
+    pub fn quotes(&self) -> impl Iterator<Item = &Quote> {
+        self.bid.iter().chain(self.ask.iter())
+    }
+}
+
+/// A quote resting on an exchange
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkingQuote {
+    pub order_id: String, // Exchange-assigned
+    pub side: QuoteSide,
+    pub price: f64,
+    pub quantity: f64,
+    /// Quantity already recorded in the inventory
+    pub filled: f64,
+}
+
+/// Quotes resting for one symbol and the reference they were priced from
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SymbolQuotes {
+    pub symbol: String,
+    pub exchange: String,
+    pub reference: f64,
+    pub spread_bps: f64,
+    pub bid: Option<WorkingQuote>,
+    pub ask: Option<WorkingQuote>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SymbolQuotes {
+    pub fn working(&self) -> impl Iterator<Item = &WorkingQuote> {
+        self.bid.iter().chain(self.ask.iter())
+    }
+}
+
+pub struct MarketMaker {
+    params: MarketMakingParams,
+    allow_short: bool,
+}
+
+impl MarketMaker {
+    pub fn new(params: MarketMakingParams) -> Self {
+        Self {
+            params,
+            allow_short: true,
+        }
+    }
+
+    /// Only offer inventory that is held, for venues that cannot sell short
+    pub fn without_short_selling(mut self) -> Self {
+        self.allow_short = false;
+        self
+    }
+
+    pub fn params(&self) -> &MarketMakingParams {
+        &self.params
+    }
+
+    /// Spread in basis points for a realized volatility (per-candle log return)
+    pub fn spread_bps(&self, volatility: f64) -> f64 {
+        let spread =
+            self.params.base_spread_bps + self.params.volatility_multiplier * volatility * 10_000.0;
+        spread.clamp(self.params.min_spread_bps, self.params.max_spread_bps)
+    }
+
+    /// Quote both sides of `reference` given volatility and current inventory
+    pub fn quote(&self, reference: f64, volatility: f64, inventory: f64) -> QuoteSet {
+        let spread_bps = self.spread_bps(volatility);
+        let half_spread = reference * spread_bps / 20_000.0;
+        let fill = (inventory / self.params.max_inventory).clamp(-1.0, 1.0);
+        let skew = self.params.inventory_skew.clamp(0.0, 1.0);
+        let center = reference - fill * skew * half_spread;
+        let tick = self.params.tick_size;
+
+        let bid_room = self.params.max_inventory - inventory;
+        let ask_room = if self.allow_short {
+            self.params.max_inventory + inventory
+        } else {
+            inventory.min(self.params.max_inventory + inventory)
+        };
+        // The epsilon keeps prices already on a tick from moving a whole tick
+        let bid_price = ((center - half_spread) / tick + 1e-9).floor() * tick;
+        let ask_price = ((center + half_spread) / tick - 1e-9).ceil() * tick;
+
+        QuoteSet {
+            reference,
+            spread_bps,
+            bid: (bid_room > 0.0 && bid_price > 0.0).then(|| Quote {
+                side: QuoteSide::Bid,
+                price: bid_price,
+                quantity: self.params.order_size.min(bid_room),
+            }),
+            ask: (ask_room > 0.0).then(|| Quote {
+                side: QuoteSide::Ask,
+                price: ask_price,
+                quantity: self.params.order_size.min(ask_room),
+            }),
+        }
+    }
+
+    /// Whether quotes priced from `quoted` are stale at `reference`
+    pub fn needs_refresh(&self, quoted: f64, reference: f64) -> bool {
+        quoted <= 0.0
+            || (reference - quoted).abs() / quoted * 10_000.0 >= self.params.refresh_threshold_bps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn maker() -> MarketMaker {
+        MarketMaker::new(MarketMakingParams {
+            order_size: 1.0,
+            max_inventory: 2.0,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_flat_book_quotes_symmetric_spread() {
+        let quotes = maker().quote(1000.0, 0.0, 0.0);
+        assert_eq!(quotes.spread_bps, 10.0);
+        let bid = quotes.bid.unwrap();
+        let ask = quotes.ask.unwrap();
+        assert!((bid.price - 999.5).abs() < 1e-9);
+        assert!((ask.price - 1000.5).abs() < 1e-9);
+        assert_eq!((bid.quantity, ask.quantity), (1.0, 1.0));
+    }
+
+    #[test]
+    fn test_spread_widens_with_volatility_within_bounds() {
+        let maker = maker();
+        assert_eq!(maker.spread_bps(0.001), 30.0);
+        assert_eq!(maker.spread_bps(0.1), 100.0);
+        let quiet = maker.quote(1000.0, 0.0, 0.0);
+        let volatile = maker.quote(1000.0, 0.001, 0.0);
+        assert!(volatile.bid.unwrap().price < quiet.bid.unwrap().price);
+        assert!(volatile.ask.unwrap().price > quiet.ask.unwrap().price);
+    }
+
+    #[test]
+    fn test_inventory_skews_quotes_and_caps_sides() {
+        let maker = maker();
+        // Long half the cap: both quotes move down by a quarter spread
+        let long = maker.quote(1000.0, 0.0, 1.0);
+        assert!((long.bid.as_ref().unwrap().price - 999.25).abs() < 1e-9);
+        assert!((long.ask.as_ref().unwrap().price - 1000.25).abs() < 1e-9);
+
+        // A bid fill may only take inventory up to the cap
+        let near_cap = maker.quote(1000.0, 0.0, 1.5);
+        assert_eq!(near_cap.bid.unwrap().quantity, 0.5);
+        let at_cap = maker.quote(1000.0, 0.0, 2.0);
+        assert!(at_cap.bid.is_none());
+        assert_eq!(at_cap.ask.unwrap().quantity, 1.0);
+
+        let short = maker.quote(1000.0, 0.0, -2.0);
+        assert!(short.ask.is_none());
+        assert!(short.bid.unwrap().price > 999.5);
+    }
+
+    #[test]
+    fn test_spot_asks_are_limited_to_held_inventory() {
+        let maker = maker().without_short_selling();
+        let flat = maker.quote(1000.0, 0.0, 0.0);
+        assert!(flat.ask.is_none());
+        assert_eq!(flat.bid.unwrap().quantity, 1.0);
+
+        let long = maker.quote(1000.0, 0.0, 0.4);
+        assert_eq!(long.ask.unwrap().quantity, 0.4);
+        assert_eq!(maker.quote(1000.0, 0.0, 1.5).ask.unwrap().quantity, 1.0);
+    }
+
+    #[test]
+    fn test_refresh_threshold_and_microprice() {
+        let maker = maker();
+        assert!(!maker.needs_refresh(1000.0, 1000.4));
+        assert!(maker.needs_refresh(1000.0, 1000.6));
+        assert!(maker.needs_refresh(1000.0, 999.4));
+
+        // Thin ask side pulls the microprice towards the ask
+        assert!((microprice(99.0, 3.0, 101.0, 1.0) - 100.5).abs() < 1e-9);
+        assert_eq!(microprice(99.0, 0.0, 101.0, 0.0), 100.0);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(MarketMakingParams::default().validate().is_ok());
+        let invalid = [
+            MarketMakingParams {
+                max_inventory: 0.0,
+                ..Default::default()
+            },
+            MarketMakingParams {
+                min_spread_bps: 50.0,
+                max_spread_bps: 20.0,
+                ..Default::default()
+            },
+            MarketMakingParams {
+                inventory_skew: -1.0,
+                ..Default::default()
+            },
+            MarketMakingParams {
+                inventory_skew: 1.5,
+                ..Default::default()
+            },
+        ];
+        for params in invalid {
+            assert!(params.validate().is_err(), "{:?}", params);
+        }
+    }
+}
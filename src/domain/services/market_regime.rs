@@ -0,0 +1,259 @@
+//! Market regime classification
+//!
+//! Labels the current market of a symbol from its candle history so the
+//! signal combiner can enable, disable or reweight strategies per regime.
+//! Volatility takes precedence over trend: a market whose realized volatility
+//! exceeds `high_volatility` is `HighVolatility` whatever its direction. Below
+//! that, an ADX at or above `trend_threshold` is a trend in the direction of
+//! the dominant directional indicator, a Bollinger bandwidth below
+//! `low_bandwidth` is a `LowVolatility` squeeze, and anything else is `Ranging`.
+
+use crate::domain::services::indicators::{BollingerBands, Candle, ADX};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketRegime {
+    TrendingUp,
+    TrendingDown,
+    Ranging,
+    HighVolatility,
+    LowVolatility,
+}
+
+impl MarketRegime {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarketRegime::TrendingUp => "trending_up",
+            MarketRegime::TrendingDown => "trending_down",
+            MarketRegime::Ranging => "ranging",
+            MarketRegime::HighVolatility => "high_volatility",
+            MarketRegime::LowVolatility => "low_volatility",
+        }
+    }
+}
+
+/// A regime label with the measurements it was derived from
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RegimeReading {
+    pub regime: MarketRegime,
+    pub adx: f64,
+    pub plus_di: f64,
+    pub minus_di: f64,
+    /// Standard deviation of per-candle log returns
+    pub realized_volatility: f64,
+    /// Bollinger band width relative to the middle band
+    pub bollinger_bandwidth: f64,
+}
+
+/// Thresholds and lookbacks of the regime classifier
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegimeClassifier {
+    pub adx_period: usize,
+    pub bollinger_period: usize,
+    pub bollinger_std_dev: f64,
+    pub volatility_window: usize,
+    /// ADX at or above which the market is trending
+    pub trend_threshold: f64,
+    /// Realized volatility above which the market is highly volatile
+    pub high_volatility: f64,
+    /// Bollinger bandwidth below which the market is quiet
+    pub low_bandwidth: f64,
+}
+
+impl Default for RegimeClassifier {
+    /// Tuned for the 10 second candles built by `CandleBuilder`
+    fn default() -> Self {
+        Self {
+            adx_period: 14,
+            bollinger_period: 20,
+            bollinger_std_dev: 2.0,
+            volatility_window: 20,
+            trend_threshold: 25.0,
+            high_volatility: 0.004,
+            low_bandwidth: 0.002,
+        }
+    }
+}
+
+impl RegimeClassifier {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.adx_period == 0 || self.bollinger_period < 2 || self.volatility_window < 2 {
+            return Err(format!(
+                "adx_period ({}) must be positive, bollinger_period ({}) and volatility_window ({}) at least 2",
+                self.adx_period, self.bollinger_period, self.volatility_window
+            ));
+        }
+        if !(0.0..=100.0).contains(&self.trend_threshold) {
+            return Err(format!(
+                "trend_threshold ({}) must be within 0-100",
+                self.trend_threshold
+            ));
+        }
+        for (name, value) in [
+            ("bollinger_std_dev", self.bollinger_std_dev),
+            ("high_volatility", self.high_volatility),
+            ("low_bandwidth", self.low_bandwidth),
+        ] {
+            if !(value > 0.0 && value.is_finite()) {
+                return Err(format!("{} ({}) must be positive", name, value));
+            }
+        }
+        Ok(())
+    }
+
+    /// Candles needed before a regime can be classified
+    pub fn min_candles(&self) -> usize {
+        (2 * self.adx_period)
+            .max(self.bollinger_period)
+            .max(self.volatility_window + 1)
+    }
+
+    /// Classify the latest candle; None until `min_candles` are available
+    pub fn classify(&self, candles: &[Candle]) -> Option<RegimeReading> {
+        if candles.len() < self.min_candles() {
+            return None;
+        }
+
+        let dmi = ADX::new(self.adx_period).calculate_detailed(candles);
+        let adx = *dmi.adx.last()?;
+        let plus_di = *dmi.plus_di.last()?;
+        let minus_di = *dmi.minus_di.last()?;
+
+        let bands = BollingerBands::new(self.bollinger_period, self.bollinger_std_dev)
+            .calculate_detailed(candles);
+        let middle = *bands.middle.last()?;
+        let bollinger_bandwidth = if middle > 0.0 {
+            (bands.upper.last()? - bands.lower.last()?) / middle
+        } else {
+            0.0
+        };
+
+        let realized_volatility =
+            realized_volatility(&candles[candles.len() - self.volatility_window - 1..]);
+
+        let regime = if realized_volatility > self.high_volatility {
+            MarketRegime::HighVolatility
+        } else if adx >= self.trend_threshold {
+            if plus_di >= minus_di {
+                MarketRegime::TrendingUp
+            } else {
+                MarketRegime::TrendingDown
+            }
+        } else if bollinger_bandwidth < self.low_bandwidth {
+            MarketRegime::LowVolatility
+        } else {
+            MarketRegime::Ranging
+        };
+
+        Some(RegimeReading {
+            regime,
+            adx,
+            plus_di,
+            minus_di,
+            realized_volatility,
+            bollinger_bandwidth,
+        })
+    }
+}
+
+/// Population standard deviation of the log returns between consecutive closes
+pub fn realized_volatility(candles: &[Candle]) -> f64 {
+    let returns: Vec<f64> = candles
+        .windows(2)
+        .map(|w| (w[1].close.value() / w[0].close.value()).ln())
+        .collect();
+    if returns.is_empty() {
+        return 0.0;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len() as f64;
+    variance.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Candles around `close` with a fixed high/low spread
+    fn candle(close: f64, spread: f64) -> Candle {
+        Candle::new(close, close + spread, close - spread, close, 1000.0).unwrap()
+    }
+
+    fn series(closes: impl Iterator<Item = f64>, spread: f64) -> Vec<Candle> {
+        closes.map(|c| candle(c, spread)).collect()
+    }
+
+    #[test]
+    fn test_needs_enough_candles() {
+        let classifier = RegimeClassifier::default();
+        assert_eq!(classifier.min_candles(), 28);
+        let candles = series((0..27).map(|i| 100.0 + i as f64 * 0.01), 0.05);
+        assert!(classifier.classify(&candles).is_none());
+    }
+
+    #[test]
+    fn test_steady_climb_is_trending_up() {
+        let candles = series((0..40).map(|i| 100.0 * 1.001_f64.powi(i)), 0.05);
+        let reading = RegimeClassifier::default().classify(&candles).unwrap();
+        assert_eq!(reading.regime, MarketRegime::TrendingUp);
+        assert!(reading.adx > 25.0);
+        assert!(reading.plus_di > reading.minus_di);
+    }
+
+    #[test]
+    fn test_steady_decline_is_trending_down() {
+        let candles = series((0..40).map(|i| 100.0 * 0.999_f64.powi(i)), 0.05);
+        let reading = RegimeClassifier::default().classify(&candles).unwrap();
+        assert_eq!(reading.regime, MarketRegime::TrendingDown);
+    }
+
+    #[test]
+    fn test_oscillation_is_ranging() {
+        let candles = series((0..40).map(|i| if i % 2 == 0 { 100.0 } else { 100.3 }), 0.2);
+        let reading = RegimeClassifier::default().classify(&candles).unwrap();
+        assert_eq!(reading.regime, MarketRegime::Ranging);
+        assert!(reading.adx < 25.0);
+    }
+
+    #[test]
+    fn test_tight_oscillation_is_low_volatility() {
+        let candles = series(
+            (0..40).map(|i| if i % 2 == 0 { 100.0 } else { 100.01 }),
+            0.01,
+        );
+        let reading = RegimeClassifier::default().classify(&candles).unwrap();
+        assert_eq!(reading.regime, MarketRegime::LowVolatility);
+    }
+
+    #[test]
+    fn test_wide_swings_are_high_volatility() {
+        let candles = series((0..40).map(|i| if i % 2 == 0 { 100.0 } else { 101.0 }), 0.5);
+        let reading = RegimeClassifier::default().classify(&candles).unwrap();
+        assert_eq!(reading.regime, MarketRegime::HighVolatility);
+        assert!(reading.realized_volatility > 0.004);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(RegimeClassifier::default().validate().is_ok());
+        let invalid = [
+            RegimeClassifier {
+                adx_period: 0,
+                ..Default::default()
+            },
+            RegimeClassifier {
+                trend_threshold: 120.0,
+                ..Default::default()
+            },
+            RegimeClassifier {
+                high_volatility: 0.0,
+                ..Default::default()
+            },
+        ];
+        for classifier in invalid {
+            assert!(classifier.validate().is_err(), "{:?}", classifier);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::domain::entities::exchange::Exchange;
+use crate::domain::services::pnl_ledger::PnlBreakdown;
 use crate::domain::value_objects::pnl::PnL;
 use crate::domain::value_objects::price::Price;
 use std::collections::HashMap;
@@ -119,6 +121,38 @@ impl TradingMetrics {
         self.last_updated = SystemTime::now();
     }
 
+    /// Take realized PnL and closed trade counts from the realized PnL ledger
+    ///
+    /// Replaces what `record_trade` accumulated for them; volume and latency
+    /// are left as they are.
+    pub fn sync_realized(&mut self, totals: &PnlBreakdown) {
+        self.total_realized_pnl = PnL::new(totals.net_pnl).unwrap_or(self.total_realized_pnl);
+        self.winning_trades = totals.winning;
+        self.losing_trades = totals.losing;
+        self.total_trades = totals.closed_lots;
+        self.win_rate = if totals.closed_lots > 0 {
+            (totals.winning as f64 / totals.closed_lots as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        let average =
+            |sum: f64, count: u32| Price::new(if count > 0 { sum / count as f64 } else { 0.0 });
+        if let Ok(avg_win) = average(totals.gross_profit, totals.winning) {
+            self.avg_win = avg_win;
+        }
+        if let Ok(avg_loss) = average(totals.gross_loss, totals.losing) {
+            self.avg_loss = avg_loss;
+        }
+        self.profit_factor = if totals.gross_loss > 0.0 {
+            totals.gross_profit / totals.gross_loss
+        } else {
+            0.0
+        };
+
+        self.last_updated = SystemTime::now();
+    }
+
     /// Update unrealized PnL
     pub fn update_unrealized_pnl(&mut self, unrealized_pnl: PnL) {
         self.total_unrealized_pnl = unrealized_pnl;
@@ -179,6 +213,14 @@ pub struct StrategyMetrics {
     pub current_weight: f64,
     /// Performance score (0.0 to 1.0)
     pub performance_score: f64,
+    /// Closed trades with a positive PnL
+    pub winning_trades: u32,
+    /// Closed trades with a zero or negative PnL
+    pub losing_trades: u32,
+    /// Sum of winning trade PnL
+    pub gross_profit: f64,
+    /// Sum of losing trade PnL, as a positive amount
+    pub gross_loss: f64,
     /// Last update timestamp
     pub last_updated: SystemTime,
 }
@@ -193,6 +235,10 @@ impl StrategyMetrics {
             strategy_pnl: Price::new(0.0).unwrap(),
             current_weight: 0.0,
             performance_score: 0.5, // Start neutral
+            winning_trades: 0,
+            losing_trades: 0,
+            gross_profit: 0.0,
+            gross_loss: 0.0,
             last_updated: SystemTime::now(),
         }
     }
@@ -211,6 +257,39 @@ impl StrategyMetrics {
         self.last_updated = SystemTime::now();
     }
 
+    /// Record the realized PnL of a closed trade attributed to this strategy
+    pub fn record_trade_outcome(&mut self, pnl: f64) {
+        if pnl > 0.0 {
+            self.winning_trades += 1;
+            self.gross_profit += pnl;
+        } else {
+            self.losing_trades += 1;
+            self.gross_loss += pnl.abs();
+        }
+        self.last_updated = SystemTime::now();
+    }
+
+    /// Number of closed trades recorded through `record_trade_outcome`
+    pub fn closed_trades(&self) -> u32 {
+        self.winning_trades + self.losing_trades
+    }
+
+    /// Share of closed trades that were winners, if any trade closed
+    pub fn win_rate(&self) -> Option<f64> {
+        let total = self.closed_trades();
+        (total > 0).then(|| self.winning_trades as f64 / total as f64)
+    }
+
+    /// Average win divided by average loss, if both are known
+    pub fn payoff_ratio(&self) -> Option<f64> {
+        if self.winning_trades == 0 || self.losing_trades == 0 || self.gross_loss <= 0.0 {
+            return None;
+        }
+        let avg_win = self.gross_profit / self.winning_trades as f64;
+        let avg_loss = self.gross_loss / self.losing_trades as f64;
+        Some(avg_win / avg_loss)
+    }
+
     pub fn update_weight(&mut self, new_weight: f64) {
         self.current_weight = new_weight;
         self.last_updated = SystemTime::now();
@@ -432,7 +511,7 @@ pub struct SystemHealthMetrics {
 }
 
 /// Alert types for different monitoring scenarios
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AlertType {
     HighDrawdown,
     LowWinRate,
@@ -441,6 +520,12 @@ pub enum AlertType {
     HighMemoryUsage,
     HighCpuUsage,
     SystemUnhealthy,
+    /// Balances on the exchange differ from the expected ones
+    BalanceReconciliation(Exchange),
+    /// Positions on the exchange are not tracked, or tracked ones are gone
+    PositionReconciliation(Exchange),
+    /// Orders filled on the exchange that nothing local accounts for
+    UntrackedFill(Exchange),
 }
 
 /// System alert with details
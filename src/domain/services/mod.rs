@@ -1,11 +1,17 @@
+pub mod arbitrage;
 pub mod balance_manager;
 pub mod candle_builder;
 pub mod circuit_breaker;
 pub mod indicators;
 pub mod leverage_calculator;
 pub mod lock_validator;
+pub mod market_making;
+pub mod market_regime;
 pub mod metrics;
 pub mod order_executor;
+pub mod pairs_trading;
+pub mod paper_trading;
+pub mod pnl_ledger;
 pub mod portfolio_manager;
 pub mod portfolio_reconciliation;
 pub mod position_manager;
@@ -13,8 +19,12 @@ pub mod position_sizer;
 pub mod reconciliation;
 pub mod screening;
 pub mod strategies;
+pub mod strategy_registry;
+pub mod streaming_indicators;
 pub mod symbol_screening;
+pub mod symbol_universe;
 pub mod trade_execution_error;
+pub mod weight_allocator;
 
 #[cfg(test)]
 pub mod position_validation_tests;
@@ -17,7 +17,9 @@ use crate::domain::services::metrics::{PerformanceProfiler, TradingMetrics};
 use crate::domain::services::position_manager::{PositionLimits, PositionManager, PositionResult};
 use crate::domain::services::position_sizer::PositionSizer;
 use crate::domain::services::trade_execution_error::TradeExecutionError;
-use crate::domain::value_objects::position_sizing::PositionSizingRequest;
+use crate::domain::value_objects::position_sizing::{
+    PositionSizingRequest, SizingInputs, SizingMode, SizingParams,
+};
 use crate::domain::value_objects::{price::Price, quantity::Quantity};
 
 /// Order execution configuration
@@ -86,6 +88,9 @@ pub struct OrderExecutor {
     balance_manager: Option<Arc<BalanceManager>>,
     leverage_calculator: Option<Arc<LeverageCalculator>>,
     position_sizer: PositionSizer,
+    sizing_mode: SizingMode,
+    sizing_params: SizingParams,
+    sizing_inputs: HashMap<String, SizingInputs>,
 }
 
 impl OrderExecutor {
@@ -112,6 +117,9 @@ impl OrderExecutor {
             balance_manager: None,
             leverage_calculator: None,
             position_sizer: PositionSizer::new(),
+            sizing_mode: SizingMode::FixedPercentage,
+            sizing_params: SizingParams::default(),
+            sizing_inputs: HashMap::new(),
         }
     }
 
@@ -140,6 +148,9 @@ impl OrderExecutor {
             balance_manager: Some(balance_manager),
             leverage_calculator: Some(leverage_calculator),
             position_sizer: PositionSizer::new(),
+            sizing_mode: SizingMode::FixedPercentage,
+            sizing_params: SizingParams::default(),
+            sizing_inputs: HashMap::new(),
         }
     }
 
@@ -253,11 +264,19 @@ impl OrderExecutor {
         let current_price = self.get_current_price(symbol)?;
 
         // Calculate position size
-        let position_size = self.calculate_position_size(
-            self.portfolio_value,
-            current_price.value(),
-            self.config.portfolio_percentage,
-        )?;
+        let position_size = match self.sizing_mode {
+            SizingMode::FixedPercentage => self.calculate_position_size(
+                self.portfolio_value,
+                current_price.value(),
+                self.config.portfolio_percentage,
+            )?,
+            _ => {
+                let mut inputs = self.sizing_inputs.get(symbol).cloned().unwrap_or_default();
+                inputs.equity = self.portfolio_value;
+                inputs.price = current_price.value();
+                self.calculate_risk_position_size(&inputs)?
+            }
+        };
 
         // Check minimum quantity
         if position_size < self.config.min_quantity {
@@ -413,6 +432,54 @@ impl OrderExecutor {
         Ok(quantity)
     }
 
+    /// Select the sizing mode used for new orders
+    ///
+    /// `params.portfolio_fraction` is overridden by the configured
+    /// `portfolio_percentage` so fixed sizing stays consistent.
+    pub fn set_sizing_mode(&mut self, mode: SizingMode, params: SizingParams) {
+        self.sizing_mode = mode;
+        self.sizing_params = SizingParams {
+            portfolio_fraction: self.config.portfolio_percentage,
+            ..params
+        };
+    }
+
+    /// Provide stop distance, ATR, volatility or win-rate inputs for a symbol
+    pub fn update_sizing_inputs(&mut self, symbol: &str, inputs: SizingInputs) {
+        self.sizing_inputs.insert(symbol.to_string(), inputs);
+    }
+
+    /// Calculate position size with the configured sizing mode
+    ///
+    /// Falls back to the fixed portfolio percentage when the inputs the mode
+    /// depends on are missing.
+    pub fn calculate_risk_position_size(&self, inputs: &SizingInputs) -> Result<f64, String> {
+        let sizing = self
+            .position_sizer
+            .target_size(self.sizing_mode, &self.sizing_params, inputs)
+            .or_else(|e| {
+                tracing::warn!(
+                    "{} sizing unavailable ({}), falling back to fixed percentage",
+                    self.sizing_mode,
+                    e
+                );
+                self.position_sizer.target_size(
+                    SizingMode::FixedPercentage,
+                    &self.sizing_params,
+                    inputs,
+                )
+            })?;
+
+        if sizing.quantity < self.config.min_quantity {
+            return Err(format!(
+                "Calculated quantity {:.8} is below minimum {:.8} ({})",
+                sizing.quantity, self.config.min_quantity, sizing.reason
+            ));
+        }
+
+        Ok(sizing.quantity)
+    }
+
     /// Validate execution constraints (balance, position limits) - sync version
     fn validate_execution_constraints_sync(
         &self,
@@ -989,6 +1056,36 @@ mod tests {
         assert_eq!(size, 0.01);
     }
 
+    #[test]
+    fn test_calculate_risk_position_size_atr_with_fallback() {
+        let config = OrderExecutorConfig {
+            confidence_threshold: 0.5,
+            symbols: vec!["BTC-USD".to_string()],
+            traders: vec!["trader1".to_string()],
+            max_per_hour: 10,
+            max_per_day: 50,
+            portfolio_percentage: 0.05,
+            slippage_pct: 0.02,
+            min_quantity: 0.0001,
+            max_retry_attempts: 3,
+            retry_delay_ms: 1000,
+        };
+
+        let mut executor = OrderExecutor::new_with_config(config);
+        executor.set_sizing_mode(SizingMode::AtrScaled, SizingParams::default());
+
+        // Without ATR the fixed 5% applies: 10000 * 0.05 / 50000 = 0.01
+        let inputs = SizingInputs::new(10000.0, 50000.0);
+        let size = executor.calculate_risk_position_size(&inputs).unwrap();
+        assert!((size - 0.01).abs() < 1e-12);
+
+        // Risk 1% (100 USD) over 2 ATR of 500 = 0.1 BTC, capped at 25% of equity
+        let mut inputs = SizingInputs::new(10000.0, 50000.0);
+        inputs.atr = Some(500.0);
+        let size = executor.calculate_risk_position_size(&inputs).unwrap();
+        assert!((size - 0.05).abs() < 1e-12);
+    }
+
     #[test]
     fn test_slippage_protection_buy() {
         let config = OrderExecutorConfig {
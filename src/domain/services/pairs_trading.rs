@@ -0,0 +1,482 @@
+//! Pairs trading
+//!
+//! Multi-asset strategies see the candles of several symbols at once and emit
+//! linked leg signals that are opened and closed as a unit. The pairs strategy
+//! joins the candles of a base and a hedge symbol on their window start and
+//! regresses the base log price on the hedge log price over a lookback window
+//! of joined candles. The slope is the hedge ratio and the residual is
+//! the spread. When the spread's z-score stretches past the entry threshold,
+//! the rich leg is sold and the cheap leg bought, sized so that a common move
+//! of both symbols nets out. The pair is closed once the spread reverts
+//! inside the exit threshold, or stopped out if it keeps diverging.
+
+use crate::domain::services::indicators::Candle;
+use crate::domain::services::strategies::Signal;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::time::SystemTime;
+
+/// Candles of one symbol with the start of each candle's window
+pub type CandleSeries = Vec<(SystemTime, Candle)>;
+
+/// Lookback, thresholds and sizing of the pairs strategy
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairsParams {
+    /// Candles used to estimate the hedge ratio and the spread distribution
+    pub lookback: usize,
+    /// Absolute z-score at which a pair is opened
+    pub entry_zscore: f64,
+    /// Absolute z-score at or below which an open pair is closed
+    pub exit_zscore: f64,
+    /// Absolute z-score at which an open pair is stopped out
+    pub stop_zscore: f64,
+    /// Quote currency notional of the base leg; the hedge leg is scaled by the hedge ratio
+    pub notional: f64,
+}
+
+impl Default for PairsParams {
+    fn default() -> Self {
+        Self {
+            lookback: 60,
+            entry_zscore: 2.0,
+            exit_zscore: 0.5,
+            stop_zscore: 4.0,
+            notional: 100.0,
+        }
+    }
+}
+
+impl PairsParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.lookback < 10 {
+            return Err(format!(
+                "lookback ({}) must be at least 10 candles",
+                self.lookback
+            ));
+        }
+        if !(self.notional > 0.0 && self.notional.is_finite()) {
+            return Err(format!("notional ({}) must be positive", self.notional));
+        }
+        if !(0.0 <= self.exit_zscore
+            && self.exit_zscore < self.entry_zscore
+            && self.entry_zscore < self.stop_zscore)
+        {
+            return Err(format!(
+                "z-scores must satisfy 0 <= exit ({}) < entry ({}) < stop ({})",
+                self.exit_zscore, self.entry_zscore, self.stop_zscore
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What a multi-asset strategy wants done with its group of positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupAction {
+    /// Open the legs if the group is flat
+    Enter,
+    /// Close an open group, the trade worked out
+    Exit,
+    /// Close an open group, the trade went against us
+    Stop,
+    Hold,
+}
+
+/// One leg of a linked multi-asset signal
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LegSignal {
+    pub symbol: String,
+    pub signal: Signal,
+    /// Notional of this leg relative to the strategy's base notional
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MultiAssetSignal {
+    pub action: GroupAction,
+    /// Legs to open on `Enter`; empty for the other actions
+    pub legs: Vec<LegSignal>,
+    pub confidence: f64,
+    pub hedge_ratio: f64,
+    pub zscore: f64,
+    pub indicators: BTreeMap<String, f64>,
+}
+
+/// Strategy that trades several symbols as one position
+///
+/// Unlike `Strategy`, which sees one symbol's candles, a multi-asset strategy
+/// receives the candle series of every symbol it names in `symbols`. The
+/// series are not aligned; strategies join them on the window start.
+pub trait MultiAssetStrategy: Send + Sync {
+    /// Unique name, also used as the strategy of the positions it opens
+    fn name(&self) -> String;
+    fn symbols(&self) -> Vec<String>;
+    /// None while any series is too short to judge
+    fn generate_signal(&self, series: &HashMap<String, CandleSeries>) -> Option<MultiAssetSignal>;
+}
+
+/// Regression of the base log price on the hedge log price
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpreadEstimate {
+    pub hedge_ratio: f64,
+    pub intercept: f64,
+    /// Latest residual of the regression
+    pub spread: f64,
+    /// Latest residual in standard deviations of the window's residuals
+    pub zscore: f64,
+}
+
+/// Estimate the hedge ratio and spread z-score of two aligned price series
+///
+/// Returns None for series of different or too short length, non-positive
+/// prices, or a hedge series without variance.
+pub fn estimate_spread(base: &[f64], hedge: &[f64]) -> Option<SpreadEstimate> {
+    if base.len() != hedge.len() || base.len() < 3 {
+        return None;
+    }
+    if base.iter().chain(hedge).any(|p| *p <= 0.0) {
+        return None;
+    }
+    let y: Vec<f64> = base.iter().map(|p| p.ln()).collect();
+    let x: Vec<f64> = hedge.iter().map(|p| p.ln()).collect();
+    let n = y.len() as f64;
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
+    let var_x = x.iter().map(|v| (v - mean_x).powi(2)).sum::<f64>();
+    if var_x <= f64::EPSILON {
+        return None;
+    }
+    let cov = x
+        .iter()
+        .zip(&y)
+        .map(|(xv, yv)| (xv - mean_x) * (yv - mean_y))
+        .sum::<f64>();
+    let hedge_ratio = cov / var_x;
+    let intercept = mean_y - hedge_ratio * mean_x;
+
+    let residuals: Vec<f64> = x
+        .iter()
+        .zip(&y)
+        .map(|(xv, yv)| yv - intercept - hedge_ratio * xv)
+        .collect();
+    // Residuals of a least-squares fit have zero mean
+    let std = (residuals.iter().map(|r| r * r).sum::<f64>() / n).sqrt();
+    let spread = *residuals.last()?;
+    let zscore = if std > f64::EPSILON {
+        spread / std
+    } else {
+        0.0
+    };
+
+    Some(SpreadEstimate {
+        hedge_ratio,
+        intercept,
+        spread,
+        zscore,
+    })
+}
+
+/// Mean-reversion trade on the spread between two correlated symbols
+pub struct PairsStrategy {
+    base: String,
+    hedge: String,
+    params: PairsParams,
+}
+
+impl PairsStrategy {
+    pub fn new(base: String, hedge: String, params: PairsParams) -> Self {
+        Self {
+            base,
+            hedge,
+            params,
+        }
+    }
+
+    /// Base and hedge closes of the last `lookback` candles both symbols have
+    ///
+    /// Candles are paired by window start; a candle missing on either side
+    /// drops that period from both series.
+    fn aligned_closes(
+        &self,
+        series: &HashMap<String, CandleSeries>,
+    ) -> Option<(Vec<f64>, Vec<f64>)> {
+        let hedge: HashMap<SystemTime, f64> = series
+            .get(&self.hedge)?
+            .iter()
+            .map(|(start, candle)| (*start, candle.close.value()))
+            .collect();
+        let joined: Vec<(f64, f64)> = series
+            .get(&self.base)?
+            .iter()
+            .filter_map(|(start, candle)| {
+                hedge
+                    .get(start)
+                    .map(|hedge_close| (candle.close.value(), *hedge_close))
+            })
+            .collect();
+        if joined.len() < self.params.lookback {
+            return None;
+        }
+        Some(
+            joined[joined.len() - self.params.lookback..]
+                .iter()
+                .copied()
+                .unzip(),
+        )
+    }
+}
+
+impl MultiAssetStrategy for PairsStrategy {
+    fn name(&self) -> String {
+        format!("Pairs {}/{}", self.base, self.hedge)
+    }
+
+    fn symbols(&self) -> Vec<String> {
+        vec![self.base.clone(), self.hedge.clone()]
+    }
+
+    fn generate_signal(&self, series: &HashMap<String, CandleSeries>) -> Option<MultiAssetSignal> {
+        let (base, hedge) = self.aligned_closes(series)?;
+        let estimate = estimate_spread(&base, &hedge)?;
+        let z = estimate.zscore;
+        let stretch = z.abs();
+
+        // A negative hedge ratio means the symbols don't move together
+        let action = if estimate.hedge_ratio <= 0.0 || stretch >= self.params.stop_zscore {
+            GroupAction::Stop
+        } else if stretch >= self.params.entry_zscore {
+            GroupAction::Enter
+        } else if stretch <= self.params.exit_zscore {
+            GroupAction::Exit
+        } else {
+            GroupAction::Hold
+        };
+
+        let legs = if action == GroupAction::Enter {
+            // Spread rich (z > 0): the base is expensive relative to the hedge
+            let (base_signal, hedge_signal) = if z > 0.0 {
+                (Signal::Sell, Signal::Buy)
+            } else {
+                (Signal::Buy, Signal::Sell)
+            };
+            vec![
+                LegSignal {
+                    symbol: self.base.clone(),
+                    signal: base_signal,
+                    weight: 1.0,
+                },
+                LegSignal {
+                    symbol: self.hedge.clone(),
+                    signal: hedge_signal,
+                    weight: estimate.hedge_ratio,
+                },
+            ]
+        } else {
+            Vec::new()
+        };
+
+        let mut indicators = BTreeMap::new();
+        indicators.insert("hedge_ratio".to_string(), estimate.hedge_ratio);
+        indicators.insert("spread".to_string(), estimate.spread);
+        indicators.insert("zscore".to_string(), z);
+
+        Some(MultiAssetSignal {
+            action,
+            legs,
+            confidence: (stretch / self.params.stop_zscore).min(1.0),
+            hedge_ratio: estimate.hedge_ratio,
+            zscore: z,
+            indicators,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candles(closes: &[f64]) -> CandleSeries {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (minute(i), Candle::new(*c, *c, *c, *c, 1.0).unwrap()))
+            .collect()
+    }
+
+    fn minute(i: usize) -> SystemTime {
+        std::time::UNIX_EPOCH + std::time::Duration::from_secs(60 * i as u64)
+    }
+
+    /// Hedge series oscillating around a trend, base tracking it at `ratio`
+    fn cointegrated(n: usize, ratio: f64, last_shock: f64) -> (Vec<f64>, Vec<f64>) {
+        let hedge: Vec<f64> = (0..n)
+            .map(|i| 50000.0 * (1.0 + 0.001 * i as f64 + 0.01 * (i as f64 * 0.7).sin()))
+            .collect();
+        let mut base: Vec<f64> = hedge
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                let noise = 0.001 * (i as f64 * 1.3).cos();
+                (0.02f64.ln() + ratio * h.ln() + noise).exp()
+            })
+            .collect();
+        if let Some(last) = base.last_mut() {
+            *last *= 1.0 + last_shock;
+        }
+        (base, hedge)
+    }
+
+    fn series(base: &[f64], hedge: &[f64]) -> HashMap<String, CandleSeries> {
+        HashMap::from([
+            ("ETH-USD".to_string(), candles(base)),
+            ("BTC-USD".to_string(), candles(hedge)),
+        ])
+    }
+
+    fn strategy() -> PairsStrategy {
+        PairsStrategy::new(
+            "ETH-USD".to_string(),
+            "BTC-USD".to_string(),
+            PairsParams {
+                lookback: 40,
+                stop_zscore: 8.0,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_hedge_ratio_recovers_the_relationship() {
+        let (base, hedge) = cointegrated(40, 0.8, 0.0);
+        let estimate = estimate_spread(&base, &hedge).unwrap();
+        assert!((estimate.hedge_ratio - 0.8).abs() < 0.05, "{:?}", estimate);
+        assert!(estimate.zscore.abs() < 2.0);
+
+        assert!(estimate_spread(&base, &hedge[1..]).is_none());
+        assert!(estimate_spread(&[1.0, 2.0, 3.0], &[5.0, 5.0, 5.0]).is_none());
+    }
+
+    #[test]
+    fn test_rich_spread_sells_base_and_buys_hedge() {
+        let (base, hedge) = cointegrated(50, 1.0, 0.01);
+        let signal = strategy().generate_signal(&series(&base, &hedge)).unwrap();
+        assert_eq!(signal.action, GroupAction::Enter);
+        assert!(signal.zscore > 2.0);
+        assert_eq!(signal.legs.len(), 2);
+        assert_eq!(signal.legs[0].symbol, "ETH-USD");
+        assert_eq!(signal.legs[0].signal, Signal::Sell);
+        assert_eq!(signal.legs[1].symbol, "BTC-USD");
+        assert_eq!(signal.legs[1].signal, Signal::Buy);
+        assert!((signal.legs[1].weight - signal.hedge_ratio).abs() < 1e-12);
+
+        let (base, hedge) = cointegrated(50, 1.0, -0.01);
+        let signal = strategy().generate_signal(&series(&base, &hedge)).unwrap();
+        assert_eq!(signal.legs[0].signal, Signal::Buy);
+        assert_eq!(signal.legs[1].signal, Signal::Sell);
+    }
+
+    #[test]
+    fn test_series_are_joined_on_candle_start() {
+        let strategy = strategy();
+        let (base, hedge) = cointegrated(50, 1.0, 0.01);
+        let aligned = strategy.generate_signal(&series(&base, &hedge)).unwrap();
+
+        // The hedge feed missed two early candles; joining keeps periods paired
+        let mut gappy = series(&base, &hedge);
+        gappy
+            .get_mut("BTC-USD")
+            .unwrap()
+            .retain(|(start, _)| *start != minute(3) && *start != minute(4));
+        let joined = strategy.generate_signal(&gappy).unwrap();
+        assert!((joined.zscore - aligned.zscore).abs() < 1e-12);
+        assert_eq!(joined.action, GroupAction::Enter);
+
+        // A gap inside the lookback leaves that period out of both legs
+        let mut gappy = series(&base, &hedge);
+        gappy
+            .get_mut("BTC-USD")
+            .unwrap()
+            .retain(|(start, _)| *start != minute(30));
+        let joined = strategy.generate_signal(&gappy).unwrap();
+        let keep = |v: &[f64]| -> Vec<f64> {
+            v.iter()
+                .enumerate()
+                .filter(|(i, _)| *i != 30)
+                .map(|(_, p)| *p)
+                .collect()
+        };
+        let (base_kept, hedge_kept) = (keep(&base), keep(&hedge));
+        let expected = estimate_spread(
+            &base_kept[base_kept.len() - 40..],
+            &hedge_kept[hedge_kept.len() - 40..],
+        )
+        .unwrap();
+        assert!((joined.zscore - expected.zscore).abs() < 1e-12);
+
+        // Too few shared candles to judge
+        let mut shifted = series(&base, &hedge);
+        for (start, _) in shifted.get_mut("BTC-USD").unwrap().iter_mut() {
+            *start += std::time::Duration::from_secs(60 * 20);
+        }
+        assert!(strategy.generate_signal(&shifted).is_none());
+    }
+
+    #[test]
+    fn test_exit_stop_and_short_history() {
+        let strategy = strategy();
+        let (base, hedge) = cointegrated(50, 1.0, 0.0);
+        let signal = strategy.generate_signal(&series(&base, &hedge)).unwrap();
+        assert!(matches!(
+            signal.action,
+            GroupAction::Exit | GroupAction::Hold
+        ));
+        assert!(signal.legs.is_empty());
+
+        // A lone outlier stays under sqrt(lookback - 1) deviations, so stop at 4
+        let tight = PairsStrategy::new(
+            "ETH-USD".to_string(),
+            "BTC-USD".to_string(),
+            PairsParams {
+                lookback: 40,
+                ..Default::default()
+            },
+        );
+        let (base, hedge) = cointegrated(50, 1.0, 0.5);
+        let signal = tight.generate_signal(&series(&base, &hedge)).unwrap();
+        assert_eq!(signal.action, GroupAction::Stop);
+        assert!(signal.legs.is_empty());
+
+        let (base, hedge) = cointegrated(20, 1.0, 0.0);
+        assert!(strategy.generate_signal(&series(&base, &hedge)).is_none());
+        let mut missing = series(&base, &hedge);
+        missing.remove("BTC-USD");
+        assert!(strategy.generate_signal(&missing).is_none());
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(PairsParams::default().validate().is_ok());
+        let invalid = [
+            PairsParams {
+                lookback: 5,
+                ..Default::default()
+            },
+            PairsParams {
+                exit_zscore: 2.5,
+                ..Default::default()
+            },
+            PairsParams {
+                stop_zscore: 1.5,
+                ..Default::default()
+            },
+            PairsParams {
+                notional: 0.0,
+                ..Default::default()
+            },
+        ];
+        for params in invalid {
+            assert!(params.validate().is_err(), "{:?}", params);
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! Paper trading
+//!
+//! Simulated account used by shadow strategies. Orders fill in full at the
+//! price they are placed at, positions carry the live stop-loss, take-profit
+//! and time-exit rules, and the book applies the live position and trade-rate
+//! limits to its own positions and fills. Results are recorded in a
+//! `StrategyMetrics` so they compare directly with live strategies.
+
+use crate::domain::entities::position::{ExitReason, Position, PositionSide, TimeExitRule};
+use crate::domain::services::metrics::StrategyMetrics;
+use crate::domain::value_objects::price::Price;
+use crate::domain::value_objects::quantity::Quantity;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Risk limits and exit rules applied to simulated orders
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaperLimits {
+    pub max_positions_per_symbol: usize,
+    pub max_total_positions: usize,
+    pub max_trades_per_hour: usize,
+    pub max_trades_per_day: usize,
+    pub stop_loss_percentage: Option<f64>,
+    pub take_profit_percentage: Option<f64>,
+}
+
+/// A simulated position closed by the book
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaperClose {
+    pub position_id: String,
+    pub symbol: String,
+    pub reason: ExitReason,
+    pub pnl: f64,
+}
+
+/// Simulated positions and results of one shadow combiner
+#[derive(Debug, Clone)]
+pub struct PaperBook {
+    limits: PaperLimits,
+    positions: HashMap<String, Position>,
+    fills: Vec<DateTime<Utc>>,
+    metrics: StrategyMetrics,
+    next_id: u64,
+}
+
+impl PaperBook {
+    pub fn new(name: &str, limits: PaperLimits) -> Self {
+        Self {
+            limits,
+            positions: HashMap::new(),
+            fills: Vec::new(),
+            metrics: StrategyMetrics::new(name.to_string()),
+            next_id: 1,
+        }
+    }
+
+    pub fn metrics(&self) -> &StrategyMetrics {
+        &self.metrics
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = &Position> {
+        self.positions.values()
+    }
+
+    /// Count a directional signal from the shadow combiner
+    pub fn record_signal(&mut self) {
+        self.metrics.record_signal();
+    }
+
+    /// Open a simulated position at `price`, subject to the book's limits
+    ///
+    /// Returns the position id, or why the order was refused.
+    pub fn open(
+        &mut self,
+        symbol: &str,
+        side: PositionSide,
+        quantity: f64,
+        price: Price,
+        now: DateTime<Utc>,
+    ) -> Result<String, String> {
+        self.fills.retain(|t| now - *t < Duration::days(1));
+        let last_hour = self
+            .fills
+            .iter()
+            .filter(|t| now - **t < Duration::hours(1))
+            .count();
+        if last_hour >= self.limits.max_trades_per_hour {
+            return Err(format!(
+                "Hourly trade limit exceeded: {} trades in last hour (max: {})",
+                last_hour, self.limits.max_trades_per_hour
+            ));
+        }
+        if self.fills.len() >= self.limits.max_trades_per_day {
+            return Err(format!(
+                "Daily trade limit exceeded: {} trades in last 24 hours (max: {})",
+                self.fills.len(),
+                self.limits.max_trades_per_day
+            ));
+        }
+
+        let for_symbol = self
+            .positions
+            .values()
+            .filter(|p| p.symbol == symbol)
+            .count();
+        if for_symbol >= self.limits.max_positions_per_symbol {
+            return Err(format!(
+                "Maximum positions per symbol ({}) reached for {}",
+                self.limits.max_positions_per_symbol, symbol
+            ));
+        }
+        if self.positions.len() >= self.limits.max_total_positions {
+            return Err(format!(
+                "Maximum total positions ({}) reached",
+                self.limits.max_total_positions
+            ));
+        }
+
+        let quantity = Quantity::new(quantity).map_err(|e| format!("Invalid quantity: {}", e))?;
+        let id = format!("paper_{}_{}", symbol, self.next_id);
+        let mut position = Position::new_with_stops(
+            id.clone(),
+            symbol.to_string(),
+            side,
+            quantity,
+            price,
+            self.limits.stop_loss_percentage,
+            self.limits.take_profit_percentage,
+        )
+        .map_err(|e| format!("Failed to create position: {}", e))?;
+        position.entry_time = now;
+        position.update_price(price);
+
+        self.next_id += 1;
+        self.fills.push(now);
+        self.positions.insert(id.clone(), position);
+        // Safe: 0.0 is always a valid price
+        self.metrics
+            .record_execution(Price::new(0.0).expect("Zero price should always be valid"));
+        Ok(id)
+    }
+
+    /// Mark a symbol's positions to `price` and close those hitting their
+    /// stop-loss, take-profit or time exit
+    pub fn mark(
+        &mut self,
+        symbol: &str,
+        price: Price,
+        time_exit: &TimeExitRule,
+        now: DateTime<Utc>,
+    ) -> Vec<PaperClose> {
+        let mut exits = Vec::new();
+        for (id, position) in self.positions.iter_mut() {
+            if position.symbol != symbol {
+                continue;
+            }
+            position.update_price(price);
+            let reason = if position.should_stop_loss() {
+                Some(ExitReason::StopLoss)
+            } else if position.should_take_profit() {
+                Some(ExitReason::TakeProfit)
+            } else {
+                position.time_exit_reason(time_exit, now)
+            };
+            if let Some(reason) = reason {
+                exits.push((id.clone(), reason));
+            }
+        }
+
+        exits
+            .into_iter()
+            .filter_map(|(id, reason)| {
+                let position = self.positions.remove(&id)?;
+                let pnl = position.unrealized_pnl().map_or(0.0, |p| p.value());
+                self.metrics.record_trade_outcome(pnl);
+                Some(PaperClose {
+                    position_id: id,
+                    symbol: position.symbol,
+                    reason,
+                    pnl,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Trading results of a live or shadow strategy set, for side-by-side comparison
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PaperSummary {
+    pub closed_trades: u32,
+    pub winning_trades: u32,
+    pub losing_trades: u32,
+    pub win_rate: Option<f64>,
+    pub payoff_ratio: Option<f64>,
+    pub realized_pnl: f64,
+    pub open_positions: usize,
+    pub unrealized_pnl: f64,
+}
+
+impl PaperSummary {
+    pub fn new<'a>(
+        metrics: &StrategyMetrics,
+        positions: impl IntoIterator<Item = &'a Position>,
+    ) -> Self {
+        let (open_positions, unrealized_pnl) =
+            positions
+                .into_iter()
+                .fold((0, 0.0), |(count, pnl), position| {
+                    (
+                        count + 1,
+                        pnl + position.unrealized_pnl().map_or(0.0, |p| p.value()),
+                    )
+                });
+        Self {
+            closed_trades: metrics.closed_trades(),
+            winning_trades: metrics.winning_trades,
+            losing_trades: metrics.losing_trades,
+            win_rate: metrics.win_rate(),
+            payoff_ratio: metrics.payoff_ratio(),
+            realized_pnl: metrics.gross_profit - metrics.gross_loss,
+            open_positions,
+            unrealized_pnl,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> PaperLimits {
+        PaperLimits {
+            max_positions_per_symbol: 1,
+            max_total_positions: 2,
+            max_trades_per_hour: 3,
+            max_trades_per_day: 10,
+            stop_loss_percentage: Some(0.01),
+            take_profit_percentage: Some(0.02),
+        }
+    }
+
+    fn price(value: f64) -> Price {
+        Price::new(value).unwrap()
+    }
+
+    #[test]
+    fn test_positions_close_on_stops_and_record_results() {
+        let mut book = PaperBook::new("shadow", limits());
+        let now = Utc::now();
+        book.open("BTC-USD", PositionSide::Long, 0.5, price(100.0), now)
+            .unwrap();
+        book.open("ETH-USD", PositionSide::Short, 2.0, price(50.0), now)
+            .unwrap();
+        assert_eq!(book.metrics().signals_executed, 2);
+
+        let rule = TimeExitRule::disabled();
+        assert!(book.mark("BTC-USD", price(100.5), &rule, now).is_empty());
+        let closed = book.mark("BTC-USD", price(102.5), &rule, now);
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].reason, ExitReason::TakeProfit);
+        assert!((closed[0].pnl - 1.25).abs() < 1e-9);
+
+        // Short stopped out when the price rises
+        let closed = book.mark("ETH-USD", price(50.6), &rule, now);
+        assert_eq!(closed[0].reason, ExitReason::StopLoss);
+        assert!((closed[0].pnl + 1.2).abs() < 1e-9);
+
+        let summary = PaperSummary::new(book.metrics(), book.positions());
+        assert_eq!(summary.closed_trades, 2);
+        assert_eq!(summary.win_rate, Some(0.5));
+        assert!((summary.realized_pnl - 0.05).abs() < 1e-9);
+        assert_eq!(summary.open_positions, 0);
+    }
+
+    #[test]
+    fn test_limits_refuse_orders() {
+        let mut book = PaperBook::new("shadow", limits());
+        let now = Utc::now();
+        book.open("BTC-USD", PositionSide::Long, 1.0, price(100.0), now)
+            .unwrap();
+        assert!(book
+            .open("BTC-USD", PositionSide::Short, 1.0, price(100.0), now)
+            .unwrap_err()
+            .contains("per symbol"));
+        book.open("ETH-USD", PositionSide::Long, 1.0, price(10.0), now)
+            .unwrap();
+        assert!(book
+            .open("SOL-USD", PositionSide::Long, 1.0, price(10.0), now)
+            .unwrap_err()
+            .contains("total positions"));
+
+        // Close both, then hit the hourly fill limit
+        let rule = TimeExitRule::disabled();
+        book.mark("BTC-USD", price(50.0), &rule, now);
+        book.mark("ETH-USD", price(5.0), &rule, now);
+        book.open("SOL-USD", PositionSide::Long, 1.0, price(10.0), now)
+            .unwrap();
+        assert!(book
+            .open("ADA-USD", PositionSide::Long, 1.0, price(1.0), now)
+            .unwrap_err()
+            .contains("Hourly trade limit"));
+        // An hour later the fills have aged out
+        assert!(book
+            .open(
+                "ADA-USD",
+                PositionSide::Long,
+                1.0,
+                price(1.0),
+                now + Duration::minutes(61)
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_time_exit_closes_stale_positions() {
+        let mut book = PaperBook::new("shadow", limits());
+        let opened = Utc::now();
+        book.open("BTC-USD", PositionSide::Long, 1.0, price(100.0), opened)
+            .unwrap();
+        let rule = TimeExitRule {
+            max_holding: Some(Duration::minutes(30)),
+            ..TimeExitRule::disabled()
+        };
+        assert!(book
+            .mark(
+                "BTC-USD",
+                price(100.1),
+                &rule,
+                opened + Duration::minutes(10)
+            )
+            .is_empty());
+        let closed = book.mark(
+            "BTC-USD",
+            price(100.1),
+            &rule,
+            opened + Duration::minutes(31),
+        );
+        assert_eq!(closed[0].reason, ExitReason::MaxHoldingTime);
+        assert_eq!(book.metrics().winning_trades, 1);
+    }
+}
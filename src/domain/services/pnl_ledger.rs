@@ -0,0 +1,766 @@
+//! Realized PnL ledger
+//!
+//! Ties the buy and sell fills of a symbol on one exchange into lots. A fill
+//! against the open lots of the opposite direction closes them, oldest first
+//! (FIFO) or newest first (LIFO), and whatever quantity is left opens a new
+//! lot. Each closed slice realizes its price difference less the fees of both
+//! fills, in proportion to the quantity matched; entries whose fees came from
+//! the fee schedule rather than the fill reports are marked as estimated.
+//! Funding payments realize on the day they are paid. Results are attributed to the strategy that opened
+//! the lot, and can be broken down by strategy, symbol, exchange and day.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+/// Quantities below this are treated as fully matched
+const QUANTITY_EPSILON: f64 = 1e-12;
+
+/// Strategy credited with funding paid while no lot is open
+pub const UNATTRIBUTED: &str = "unattributed";
+
+/// Which open lots a closing fill consumes first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LotMatching {
+    #[default]
+    Fifo,
+    Lifo,
+}
+
+impl LotMatching {
+    /// Parse `fifo` or `lifo`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "fifo" => Some(Self::Fifo),
+            "lifo" => Some(Self::Lifo),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FillSide {
+    Buy,
+    Sell,
+}
+
+impl FillSide {
+    /// Parse `buy` or `sell`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "buy" => Some(Self::Buy),
+            "sell" => Some(Self::Sell),
+            _ => None,
+        }
+    }
+}
+
+/// An executed trade entered in the ledger
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    pub id: String,
+    pub symbol: String,
+    pub exchange: String,
+    pub strategy: String,
+    pub side: FillSide,
+    pub price: f64,
+    pub quantity: f64,
+    pub fee: f64,
+    /// Fee taken from the fee schedule because the fill did not report one
+    pub fee_estimated: bool,
+    pub executed_at: DateTime<Utc>,
+}
+
+/// Funding paid (negative) or received (positive) on a perpetual position
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundingPayment {
+    pub symbol: String,
+    pub exchange: String,
+    /// Strategy to credit; the strategy of the oldest open lot when `None`
+    pub strategy: Option<String>,
+    pub amount: f64,
+    pub paid_at: DateTime<Utc>,
+}
+
+/// Open quantity of a fill not yet matched by an opposite fill
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Lot {
+    pub fill_id: String,
+    pub symbol: String,
+    pub exchange: String,
+    pub strategy: String,
+    pub side: FillSide,
+    pub price: f64,
+    pub quantity: f64,
+    /// Share of the opening fee carried by the remaining quantity
+    pub fee: f64,
+    pub fee_estimated: bool,
+    pub opened_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    /// A lot, or part of one, closed by an opposite fill
+    Trade,
+    Funding,
+}
+
+/// A realized result of the ledger
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RealizedEntry {
+    pub kind: EntryKind,
+    pub symbol: String,
+    pub exchange: String,
+    pub strategy: String,
+    /// Fill that opened the lot (trades only)
+    pub open_fill_id: Option<String>,
+    /// Fill that closed the lot (trades only)
+    pub close_fill_id: Option<String>,
+    /// Direction of the closed lot (trades only)
+    pub side: Option<FillSide>,
+    pub quantity: f64,
+    pub entry_price: Option<f64>,
+    pub exit_price: Option<f64>,
+    pub opened_at: Option<DateTime<Utc>>,
+    pub realized_at: DateTime<Utc>,
+    pub gross_pnl: f64,
+    pub fees: f64,
+    /// Either fee was estimated from the fee schedule (trades only)
+    pub fees_estimated: bool,
+    pub funding: f64,
+    pub net_pnl: f64,
+}
+
+/// Dimension realized results are grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PnlGroup {
+    Strategy,
+    Symbol,
+    Exchange,
+    Day,
+}
+
+impl PnlGroup {
+    /// Parse `strategy`, `symbol`, `exchange` or `day`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "strategy" => Some(Self::Strategy),
+            "symbol" => Some(Self::Symbol),
+            "exchange" => Some(Self::Exchange),
+            "day" => Some(Self::Day),
+            _ => None,
+        }
+    }
+
+    /// Parse a comma-separated list such as `strategy,day`
+    pub fn parse_list(spec: &str) -> Result<Vec<Self>, String> {
+        let mut groups = Vec::new();
+        for name in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let group = Self::from_name(name).ok_or_else(|| {
+                format!(
+                    "Unknown group '{}', expected strategy, symbol, exchange or day",
+                    name
+                )
+            })?;
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+        Ok(groups)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Strategy => "strategy",
+            Self::Symbol => "symbol",
+            Self::Exchange => "exchange",
+            Self::Day => "day",
+        }
+    }
+
+    fn key_of(&self, entry: &RealizedEntry) -> String {
+        match self {
+            Self::Strategy => entry.strategy.clone(),
+            Self::Symbol => entry.symbol.clone(),
+            Self::Exchange => entry.exchange.clone(),
+            Self::Day => entry.realized_at.date_naive().to_string(),
+        }
+    }
+}
+
+/// Realized results of one group
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PnlBreakdown {
+    /// Value of each grouping dimension, empty for the overall total
+    pub group: BTreeMap<String, String>,
+    pub gross_pnl: f64,
+    pub fees: f64,
+    /// Fees of the entries whose fees were estimated
+    pub estimated_fees: f64,
+    pub funding: f64,
+    pub net_pnl: f64,
+    /// Closed lot slices
+    pub closed_lots: u32,
+    pub winning: u32,
+    pub losing: u32,
+    /// Closed lot slices netting exactly zero, neither winning nor losing
+    pub break_even: u32,
+    /// Sum of winning net PnL
+    pub gross_profit: f64,
+    /// Sum of losing net PnL, as a positive amount
+    pub gross_loss: f64,
+}
+
+impl PnlBreakdown {
+    fn add(&mut self, entry: &RealizedEntry) {
+        self.gross_pnl += entry.gross_pnl;
+        self.fees += entry.fees;
+        if entry.fees_estimated {
+            self.estimated_fees += entry.fees;
+        }
+        self.funding += entry.funding;
+        self.net_pnl += entry.net_pnl;
+        if entry.kind == EntryKind::Trade {
+            self.closed_lots += 1;
+            if entry.net_pnl > 0.0 {
+                self.winning += 1;
+                self.gross_profit += entry.net_pnl;
+            } else if entry.net_pnl < 0.0 {
+                self.losing += 1;
+                self.gross_loss += entry.net_pnl.abs();
+            } else {
+                self.break_even += 1;
+            }
+        }
+    }
+}
+
+/// Realized results and open lots of the ledger
+#[derive(Debug, Clone, Serialize)]
+pub struct PnlReport {
+    pub lot_matching: LotMatching,
+    pub group_by: Vec<PnlGroup>,
+    pub groups: Vec<PnlBreakdown>,
+    pub total: PnlBreakdown,
+    pub open_lots: Vec<Lot>,
+}
+
+/// Lot book and realized results of every symbol and exchange
+#[derive(Debug, Clone, Default)]
+pub struct PnlLedger {
+    matching: LotMatching,
+    /// Open lots per (exchange, symbol), oldest first, all in one direction
+    books: HashMap<(String, String), VecDeque<Lot>>,
+    entries: Vec<RealizedEntry>,
+    fill_ids: HashSet<String>,
+    /// Funding entered per (exchange, symbol, paid_at)
+    funding_paid: HashSet<(String, String, DateTime<Utc>)>,
+    /// Latest funding payment entered per exchange
+    last_funding: HashMap<String, DateTime<Utc>>,
+}
+
+impl PnlLedger {
+    pub fn new(matching: LotMatching) -> Self {
+        Self {
+            matching,
+            ..Default::default()
+        }
+    }
+
+    pub fn matching(&self) -> LotMatching {
+        self.matching
+    }
+
+    /// Enter a fill, closing opposite lots before opening a new one
+    ///
+    /// Returns the entries realized by the fill. A fill whose id was already
+    /// entered is ignored, so replaying trade history is safe.
+    pub fn apply_fill(&mut self, fill: Fill) -> Vec<RealizedEntry> {
+        if fill.quantity <= QUANTITY_EPSILON || !self.fill_ids.insert(fill.id.clone()) {
+            return Vec::new();
+        }
+
+        let book = self
+            .books
+            .entry((fill.exchange.clone(), fill.symbol.clone()))
+            .or_default();
+        let mut remaining = fill.quantity;
+        let mut realized = Vec::new();
+
+        while remaining > QUANTITY_EPSILON {
+            let lot = match self.matching {
+                LotMatching::Fifo => book.front_mut(),
+                LotMatching::Lifo => book.back_mut(),
+            };
+            let Some(lot) = lot.filter(|lot| lot.side != fill.side) else {
+                break;
+            };
+
+            let matched = remaining.min(lot.quantity);
+            let entry_fee = lot.fee * matched / lot.quantity;
+            let exit_fee = fill.fee * matched / fill.quantity;
+            let gross_pnl = match lot.side {
+                FillSide::Buy => (fill.price - lot.price) * matched,
+                FillSide::Sell => (lot.price - fill.price) * matched,
+            };
+            let fees = entry_fee + exit_fee;
+            realized.push(RealizedEntry {
+                kind: EntryKind::Trade,
+                symbol: fill.symbol.clone(),
+                exchange: fill.exchange.clone(),
+                strategy: lot.strategy.clone(),
+                open_fill_id: Some(lot.fill_id.clone()),
+                close_fill_id: Some(fill.id.clone()),
+                side: Some(lot.side),
+                quantity: matched,
+                entry_price: Some(lot.price),
+                exit_price: Some(fill.price),
+                opened_at: Some(lot.opened_at),
+                realized_at: fill.executed_at,
+                gross_pnl,
+                fees,
+                fees_estimated: lot.fee_estimated || fill.fee_estimated,
+                funding: 0.0,
+                net_pnl: gross_pnl - fees,
+            });
+
+            lot.quantity -= matched;
+            lot.fee -= entry_fee;
+            remaining -= matched;
+            if lot.quantity <= QUANTITY_EPSILON {
+                match self.matching {
+                    LotMatching::Fifo => book.pop_front(),
+                    LotMatching::Lifo => book.pop_back(),
+                };
+            }
+        }
+
+        if remaining > QUANTITY_EPSILON {
+            book.push_back(Lot {
+                fill_id: fill.id.clone(),
+                symbol: fill.symbol.clone(),
+                exchange: fill.exchange.clone(),
+                strategy: fill.strategy.clone(),
+                side: fill.side,
+                price: fill.price,
+                quantity: remaining,
+                fee: fill.fee * remaining / fill.quantity,
+                fee_estimated: fill.fee_estimated,
+                opened_at: fill.executed_at,
+            });
+        }
+
+        self.entries.extend(realized.iter().cloned());
+        realized
+    }
+
+    /// Enter a funding payment, credited to the strategy of the oldest open
+    /// lot unless the payment names one
+    pub fn apply_funding(&mut self, payment: FundingPayment) -> RealizedEntry {
+        let strategy = payment.strategy.clone().unwrap_or_else(|| {
+            self.books
+                .get(&(payment.exchange.clone(), payment.symbol.clone()))
+                .and_then(|book| book.front())
+                .map(|lot| lot.strategy.clone())
+                .unwrap_or_else(|| UNATTRIBUTED.to_string())
+        });
+
+        self.funding_paid.insert((
+            payment.exchange.clone(),
+            payment.symbol.clone(),
+            payment.paid_at,
+        ));
+        let last = self
+            .last_funding
+            .entry(payment.exchange.clone())
+            .or_insert(payment.paid_at);
+        *last = (*last).max(payment.paid_at);
+
+        let entry = RealizedEntry {
+            kind: EntryKind::Funding,
+            symbol: payment.symbol,
+            exchange: payment.exchange,
+            strategy,
+            open_fill_id: None,
+            close_fill_id: None,
+            side: None,
+            quantity: 0.0,
+            entry_price: None,
+            exit_price: None,
+            opened_at: None,
+            realized_at: payment.paid_at,
+            gross_pnl: 0.0,
+            fees: 0.0,
+            fees_estimated: false,
+            funding: payment.amount,
+            net_pnl: payment.amount,
+        };
+        self.entries.push(entry.clone());
+        entry
+    }
+
+    /// Whether a funding payment on `symbol` paid at `paid_at` was entered
+    pub fn has_funding(&self, exchange: &str, symbol: &str, paid_at: DateTime<Utc>) -> bool {
+        self.funding_paid
+            .contains(&(exchange.to_string(), symbol.to_string(), paid_at))
+    }
+
+    /// When the latest funding payment entered for `exchange` was paid
+    pub fn last_funding_at(&self, exchange: &str) -> Option<DateTime<Utc>> {
+        self.last_funding.get(exchange).copied()
+    }
+
+    /// When the oldest lot entered for `exchange` was opened, closed or not
+    pub fn first_opened_at(&self, exchange: &str) -> Option<DateTime<Utc>> {
+        let closed = self
+            .entries
+            .iter()
+            .filter(|entry| entry.exchange == exchange)
+            .filter_map(|entry| entry.opened_at);
+        let open = self
+            .books
+            .values()
+            .flatten()
+            .filter(|lot| lot.exchange == exchange)
+            .map(|lot| lot.opened_at);
+        closed.chain(open).min()
+    }
+
+    /// Realized entries in the order they were entered
+    pub fn entries(&self) -> &[RealizedEntry] {
+        &self.entries
+    }
+
+    /// Lots still open, per exchange and symbol
+    pub fn open_lots(&self) -> Vec<&Lot> {
+        let mut lots: Vec<&Lot> = self.books.values().flatten().collect();
+        lots.sort_by(|a, b| {
+            (&a.exchange, &a.symbol, a.opened_at).cmp(&(&b.exchange, &b.symbol, b.opened_at))
+        });
+        lots
+    }
+
+    /// Realized results over every entry
+    pub fn totals(&self) -> PnlBreakdown {
+        let mut totals = PnlBreakdown::default();
+        for entry in &self.entries {
+            totals.add(entry);
+        }
+        totals
+    }
+
+    /// Net realized PnL over every entry
+    pub fn total_realized(&self) -> f64 {
+        self.entries.iter().map(|entry| entry.net_pnl).sum()
+    }
+
+    /// Realized results grouped by `groups`, sorted by group values
+    ///
+    /// No groups yields the overall total as a single breakdown.
+    pub fn breakdown(&self, groups: &[PnlGroup]) -> Vec<PnlBreakdown> {
+        let mut grouped: BTreeMap<Vec<String>, PnlBreakdown> = BTreeMap::new();
+        for entry in &self.entries {
+            let key: Vec<String> = groups.iter().map(|group| group.key_of(entry)).collect();
+            grouped
+                .entry(key)
+                .or_insert_with_key(|key| PnlBreakdown {
+                    group: groups
+                        .iter()
+                        .map(|group| group.as_str().to_string())
+                        .zip(key.iter().cloned())
+                        .collect(),
+                    ..Default::default()
+                })
+                .add(entry);
+        }
+        grouped.into_values().collect()
+    }
+
+    /// Breakdown by `groups` with the overall total and the open lots
+    pub fn report(&self, groups: &[PnlGroup]) -> PnlReport {
+        PnlReport {
+            lot_matching: self.matching,
+            group_by: groups.to_vec(),
+            groups: self.breakdown(groups),
+            total: self.totals(),
+            open_lots: self.open_lots().into_iter().cloned().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap()
+    }
+
+    /// Fee-free BTC-USD fill of the Momentum strategy on dYdX
+    fn fill(
+        id: &str,
+        side: FillSide,
+        price: f64,
+        quantity: f64,
+        executed_at: DateTime<Utc>,
+    ) -> Fill {
+        Fill {
+            id: id.to_string(),
+            symbol: "BTC-USD".to_string(),
+            exchange: "dydx".to_string(),
+            strategy: "Momentum".to_string(),
+            side,
+            price,
+            quantity,
+            fee: 0.0,
+            fee_estimated: false,
+            executed_at,
+        }
+    }
+
+    fn rsi(fill: Fill) -> Fill {
+        Fill {
+            strategy: "RSI".to_string(),
+            ..fill
+        }
+    }
+
+    fn with_fee(fill: Fill, fee: f64) -> Fill {
+        Fill { fee, ..fill }
+    }
+
+    fn with_estimated_fee(fill: Fill, fee: f64) -> Fill {
+        Fill {
+            fee,
+            fee_estimated: true,
+            ..fill
+        }
+    }
+
+    #[test]
+    fn test_fifo_closes_oldest_lot_first_with_fees_pro_rata() {
+        let mut ledger = PnlLedger::new(LotMatching::Fifo);
+        assert!(ledger
+            .apply_fill(with_fee(
+                fill("b1", FillSide::Buy, 100.0, 1.0, at(1, 9)),
+                1.0
+            ))
+            .is_empty());
+        ledger.apply_fill(rsi(with_fee(
+            fill("b2", FillSide::Buy, 110.0, 1.0, at(1, 10)),
+            1.0,
+        )));
+
+        // Selling 1.5 closes all of b1 and half of b2
+        let realized = ledger.apply_fill(rsi(with_fee(
+            fill("s1", FillSide::Sell, 120.0, 1.5, at(2, 9)),
+            3.0,
+        )));
+        assert_eq!(realized.len(), 2);
+        assert_eq!(realized[0].open_fill_id.as_deref(), Some("b1"));
+        assert_eq!(realized[0].strategy, "Momentum");
+        assert_eq!(realized[0].gross_pnl, 20.0);
+        assert!((realized[0].fees - 3.0).abs() < 1e-9); // 1.0 entry + 2.0 of the exit fee
+        assert_eq!(realized[1].open_fill_id.as_deref(), Some("b2"));
+        assert_eq!(realized[1].strategy, "RSI");
+        assert_eq!(realized[1].quantity, 0.5);
+        assert!((realized[1].gross_pnl - 5.0).abs() < 1e-9);
+        assert!((realized[1].fees - 1.5).abs() < 1e-9); // 0.5 entry + 1.0 exit
+
+        let open = ledger.open_lots();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].fill_id, "b2");
+        assert!((open[0].quantity - 0.5).abs() < 1e-9);
+        assert!((open[0].fee - 0.5).abs() < 1e-9);
+        assert!((ledger.total_realized() - 20.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lifo_closes_newest_lot_first() {
+        let mut ledger = PnlLedger::new(LotMatching::Lifo);
+        ledger.apply_fill(fill("b1", FillSide::Buy, 100.0, 1.0, at(1, 9)));
+        ledger.apply_fill(rsi(fill("b2", FillSide::Buy, 110.0, 1.0, at(1, 10))));
+
+        let realized = ledger.apply_fill(fill("s1", FillSide::Sell, 120.0, 1.0, at(2, 9)));
+        assert_eq!(realized.len(), 1);
+        assert_eq!(realized[0].open_fill_id.as_deref(), Some("b2"));
+        assert_eq!(realized[0].strategy, "RSI");
+        assert_eq!(realized[0].net_pnl, 10.0);
+        assert_eq!(ledger.open_lots()[0].fill_id, "b1");
+    }
+
+    #[test]
+    fn test_fill_larger_than_open_lots_flips_into_a_short_lot() {
+        let mut ledger = PnlLedger::new(LotMatching::Fifo);
+        ledger.apply_fill(fill("b1", FillSide::Buy, 100.0, 1.0, at(1, 9)));
+        let realized = ledger.apply_fill(fill("s1", FillSide::Sell, 90.0, 3.0, at(1, 10)));
+        assert_eq!(realized.len(), 1);
+        assert_eq!(realized[0].net_pnl, -10.0);
+
+        let open = ledger.open_lots();
+        assert_eq!(open[0].side, FillSide::Sell);
+        assert_eq!(open[0].quantity, 2.0);
+
+        // A short lot gains when bought back lower
+        let realized = ledger.apply_fill(fill("b2", FillSide::Buy, 80.0, 2.0, at(1, 11)));
+        assert_eq!(realized[0].side, Some(FillSide::Sell));
+        assert_eq!(realized[0].net_pnl, 20.0);
+        assert!(ledger.open_lots().is_empty());
+    }
+
+    #[test]
+    fn test_estimated_fees_mark_the_entries_they_enter() {
+        let mut ledger = PnlLedger::new(LotMatching::Fifo);
+        ledger.apply_fill(with_estimated_fee(
+            fill("b1", FillSide::Buy, 100.0, 2.0, at(1, 9)),
+            2.0,
+        ));
+        assert!(ledger.open_lots()[0].fee_estimated);
+
+        // Closing half with a reported fee still carries the estimated entry fee
+        let realized = ledger.apply_fill(with_fee(
+            fill("s1", FillSide::Sell, 110.0, 1.0, at(1, 10)),
+            0.5,
+        ));
+        assert!(realized[0].fees_estimated);
+        ledger.apply_fill(with_fee(
+            fill("b2", FillSide::Buy, 100.0, 1.0, at(1, 11)),
+            0.5,
+        ));
+        ledger.apply_fill(with_fee(
+            fill("s2", FillSide::Sell, 110.0, 2.0, at(1, 12)),
+            1.0,
+        ));
+
+        let entries = ledger.entries();
+        assert_eq!(entries.len(), 3);
+        assert!(entries[1].fees_estimated); // Rest of b1
+        assert!(!entries[2].fees_estimated); // b2, both fees reported
+        let totals = ledger.totals();
+        assert!((totals.fees - 4.0).abs() < 1e-9);
+        assert!((totals.estimated_fees - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_replayed_fills_are_ignored() {
+        let mut ledger = PnlLedger::new(LotMatching::Fifo);
+        let buy = fill("b1", FillSide::Buy, 100.0, 1.0, at(1, 9));
+        let sell = fill("s1", FillSide::Sell, 105.0, 1.0, at(1, 10));
+        ledger.apply_fill(buy.clone());
+        ledger.apply_fill(sell.clone());
+        assert!(ledger.apply_fill(buy).is_empty());
+        assert!(ledger.apply_fill(sell).is_empty());
+        assert_eq!(ledger.entries().len(), 1);
+        assert!(ledger.open_lots().is_empty());
+    }
+
+    #[test]
+    fn test_funding_is_credited_to_the_open_lot_strategy() {
+        let mut ledger = PnlLedger::new(LotMatching::Fifo);
+        ledger.apply_fill(fill("b1", FillSide::Buy, 100.0, 1.0, at(1, 9)));
+
+        let payment = |strategy: Option<&str>, symbol: &str| FundingPayment {
+            symbol: symbol.to_string(),
+            exchange: "dydx".to_string(),
+            strategy: strategy.map(str::to_string),
+            amount: -0.25,
+            paid_at: at(1, 12),
+        };
+        let credited = ledger.apply_funding(payment(None, "BTC-USD"));
+        assert_eq!(credited.strategy, "Momentum");
+        let credited = ledger.apply_funding(payment(Some("RSI"), "BTC-USD"));
+        assert_eq!(credited.strategy, "RSI");
+        let credited = ledger.apply_funding(payment(None, "ETH-USD"));
+        assert_eq!(credited.strategy, UNATTRIBUTED);
+
+        let totals = ledger.totals();
+        assert_eq!(totals.funding, -0.75);
+        assert_eq!(totals.net_pnl, -0.75);
+        assert_eq!(totals.closed_lots, 0);
+
+        assert!(ledger.has_funding("dydx", "ETH-USD", at(1, 12)));
+        assert!(!ledger.has_funding("dydx", "ETH-USD", at(1, 13)));
+        assert!(!ledger.has_funding("hyperliquid", "ETH-USD", at(1, 12)));
+        // A late, older payment does not move the latest one back
+        ledger.apply_funding(FundingPayment {
+            paid_at: at(1, 8),
+            ..payment(None, "SOL-USD")
+        });
+        assert_eq!(ledger.last_funding_at("dydx"), Some(at(1, 12)));
+        assert_eq!(ledger.last_funding_at("hyperliquid"), None);
+        assert_eq!(ledger.first_opened_at("dydx"), Some(at(1, 9)));
+        assert_eq!(ledger.first_opened_at("hyperliquid"), None);
+    }
+
+    #[test]
+    fn test_breakdown_by_strategy_and_day() {
+        let mut ledger = PnlLedger::new(LotMatching::Fifo);
+        for trade in [
+            with_fee(fill("b1", FillSide::Buy, 100.0, 1.0, at(1, 9)), 0.5),
+            with_fee(fill("s1", FillSide::Sell, 110.0, 1.0, at(1, 10)), 0.5),
+            rsi(with_fee(
+                fill("b2", FillSide::Buy, 100.0, 1.0, at(1, 11)),
+                0.5,
+            )),
+            rsi(with_fee(
+                fill("s2", FillSide::Sell, 95.0, 1.0, at(2, 9)),
+                0.5,
+            )),
+            fill("b3", FillSide::Buy, 100.0, 1.0, at(2, 10)),
+            fill("s3", FillSide::Sell, 104.0, 1.0, at(2, 11)),
+        ] {
+            ledger.apply_fill(trade);
+        }
+
+        let groups = PnlGroup::parse_list("strategy, day").unwrap();
+        let breakdown = ledger.breakdown(&groups);
+        assert_eq!(breakdown.len(), 3);
+        assert_eq!(breakdown[0].group["strategy"], "Momentum");
+        assert_eq!(breakdown[0].group["day"], "2025-03-01");
+        assert_eq!(breakdown[0].net_pnl, 9.0);
+        assert_eq!(breakdown[0].fees, 1.0);
+        assert_eq!(breakdown[1].group["day"], "2025-03-02");
+        assert_eq!(breakdown[1].net_pnl, 4.0);
+        assert_eq!(breakdown[2].group["strategy"], "RSI");
+        assert_eq!(breakdown[2].net_pnl, -6.0);
+        assert_eq!(breakdown[2].losing, 1);
+
+        let by_exchange = ledger.breakdown(&[PnlGroup::Exchange]);
+        assert_eq!(by_exchange.len(), 1);
+        assert_eq!(by_exchange[0].net_pnl, 7.0);
+        assert_eq!(by_exchange[0].closed_lots, 3);
+        assert_eq!(by_exchange[0].gross_profit, 13.0);
+        assert_eq!(by_exchange[0].gross_loss, 6.0);
+
+        let total = ledger.breakdown(&[]);
+        assert!(total[0].group.is_empty());
+        assert_eq!(total[0], ledger.totals());
+
+        assert!(PnlGroup::parse_list("strategy,week").is_err());
+    }
+
+    #[test]
+    fn test_break_even_lots_are_neither_winning_nor_losing() {
+        let mut ledger = PnlLedger::new(LotMatching::Fifo);
+        for trade in [
+            fill("b1", FillSide::Buy, 100.0, 1.0, at(1, 9)),
+            fill("s1", FillSide::Sell, 100.0, 1.0, at(1, 10)),
+            fill("b2", FillSide::Buy, 100.0, 1.0, at(1, 11)),
+            with_fee(fill("s2", FillSide::Sell, 100.0, 1.0, at(1, 12)), 0.5),
+        ] {
+            ledger.apply_fill(trade);
+        }
+
+        let totals = ledger.totals();
+        assert_eq!(totals.closed_lots, 2);
+        assert_eq!(totals.break_even, 1);
+        assert_eq!(totals.winning, 0);
+        assert_eq!(totals.losing, 1);
+        assert_eq!(totals.gross_loss, 0.5);
+    }
+}
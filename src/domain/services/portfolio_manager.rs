@@ -1,5 +1,6 @@
 //! PortfolioManager - ACID-compliant portfolio state management
 
+use serde::Serialize;
 use std::collections::HashMap;
 use std::time::SystemTime;
 
@@ -13,12 +14,23 @@ pub struct Position {
     pub current_price: Option<f64>,
 }
 
+/// Net quantity held in a symbol through fills rather than positions
+///
+/// Negative quantities are short inventory.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Inventory {
+    pub symbol: String,
+    pub quantity: f64,
+    pub mark_price: f64, // Price of the latest fill
+}
+
 /// ACID-compliant portfolio manager
 pub struct PortfolioManager {
     initial_value: f64,
     total_value: f64,
     available_cash: f64,
     positions: HashMap<String, Position>,
+    inventory: HashMap<String, Inventory>,
     max_total_positions: usize,
     max_per_symbol: usize,
     max_exposure: f64,
@@ -32,6 +44,7 @@ impl PortfolioManager {
             total_value: initial_value,
             available_cash: initial_value,
             positions: HashMap::new(),
+            inventory: HashMap::new(),
             max_total_positions: 5,
             max_per_symbol: 3,
             max_exposure: 0.8,
@@ -156,6 +169,48 @@ impl PortfolioManager {
         Ok(pnl)
     }
 
+    /// Record a fill against a symbol's inventory
+    ///
+    /// `quantity` is positive for buys and negative for sells. The fill has
+    /// already happened on the exchange, so it is applied without limit
+    /// checks; inventory is marked to the fill price and the mark-to-market
+    /// change is booked into the total value.
+    pub fn record_fill(&mut self, symbol: &str, quantity: f64, price: f64) -> Result<(), String> {
+        if !(quantity.is_finite() && price.is_finite() && price > 0.0) {
+            return Err(format!(
+                "Invalid fill for {}: quantity {} at {}",
+                symbol, quantity, price
+            ));
+        }
+
+        let inventory = self
+            .inventory
+            .entry(symbol.to_string())
+            .or_insert_with(|| Inventory {
+                symbol: symbol.to_string(),
+                quantity: 0.0,
+                mark_price: price,
+            });
+        self.total_value += inventory.quantity * (price - inventory.mark_price);
+        self.available_cash -= quantity * price;
+        inventory.quantity += quantity;
+        inventory.mark_price = price;
+        if inventory.quantity.abs() < 1e-12 {
+            self.inventory.remove(symbol);
+        }
+        Ok(())
+    }
+
+    /// Net inventory held in a symbol (0.0 when flat)
+    pub fn get_inventory(&self, symbol: &str) -> f64 {
+        self.inventory.get(symbol).map_or(0.0, |i| i.quantity)
+    }
+
+    /// Inventory of every symbol that is not flat
+    pub fn get_all_inventory(&self) -> Vec<Inventory> {
+        self.inventory.values().cloned().collect()
+    }
+
     /// Validate all portfolio invariants
     pub fn validate_invariants(&self) -> Result<(), String> {
         // Invariant 1: available_cash >= 0
@@ -246,12 +301,19 @@ impl PortfolioManager {
         self.available_cash
     }
 
-    /// Get position value
+    /// Get position value, including inventory marked at its latest fill
     pub fn get_position_value(&self) -> f64 {
-        self.positions
+        let positions: f64 = self
+            .positions
             .values()
             .map(|p| p.quantity * p.current_price.unwrap_or(p.entry_price))
-            .sum()
+            .sum();
+        let inventory: f64 = self
+            .inventory
+            .values()
+            .map(|i| i.quantity * i.mark_price)
+            .sum();
+        positions + inventory
     }
 
     /// Get all open positions
@@ -300,6 +362,7 @@ impl PortfolioManager {
         self.total_value = self.initial_value;
         self.available_cash = self.initial_value;
         self.positions.clear();
+        self.inventory.clear();
     }
 }
 
@@ -331,6 +394,27 @@ mod tests {
         assert_eq!(pm.get_position_count(), 1);
     }
 
+    #[test]
+    fn test_fills_track_signed_inventory() {
+        let mut pm = PortfolioManager::new(10000.0);
+        pm.record_fill("BTC-USD", 0.1, 50000.0).unwrap();
+        pm.record_fill("BTC-USD", 0.05, 50100.0).unwrap();
+        assert!((pm.get_inventory("BTC-USD") - 0.15).abs() < 1e-12);
+        // The first 0.1 is marked up by 100
+        assert!((pm.get_total_value() - 10010.0).abs() < 1e-6);
+        assert!(pm.validate_consistency());
+
+        pm.record_fill("BTC-USD", -0.2, 50000.0).unwrap();
+        assert!((pm.get_inventory("BTC-USD") + 0.05).abs() < 1e-12);
+        pm.record_fill("BTC-USD", 0.05, 50000.0).unwrap();
+        assert_eq!(pm.get_inventory("BTC-USD"), 0.0);
+        assert!(pm.get_all_inventory().is_empty());
+        // Bought 0.1 @ 50000 and 0.05 @ 50100, sold 0.2 @ 50000, covered 0.05 @ 50000
+        assert!((pm.get_total_value() - 9995.0).abs() < 1e-6);
+        assert!((pm.get_available_cash() - 9995.0).abs() < 1e-6);
+        assert!(pm.record_fill("BTC-USD", 0.1, 0.0).is_err());
+    }
+
     #[test]
     fn test_insufficient_balance() {
         let mut pm = PortfolioManager::new(1000.0);
@@ -4,6 +4,7 @@
 //! It provides comprehensive reconciliation capabilities with audit trails.
 
 use crate::domain::entities::exchange::Exchange;
+use crate::domain::services::reconciliation::Remediation;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
@@ -11,7 +12,7 @@ use std::fmt;
 use std::time::Duration;
 
 /// Portfolio balance structure
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Balance {
     pub currency: String,
     pub amount: f64,
@@ -62,6 +63,11 @@ impl Portfolio {
     pub fn add_balance(&mut self, currency: String, amount: f64) {
         self.balances.insert(currency, amount);
     }
+
+    /// Add `delta` to the balance of a currency, starting from zero
+    pub fn adjust_balance(&mut self, currency: &str, delta: f64) {
+        *self.balances.entry(currency.to_string()).or_insert(0.0) += delta;
+    }
 }
 
 impl Default for Portfolio {
@@ -70,6 +76,23 @@ impl Default for Portfolio {
     }
 }
 
+/// Balance changes the bot caused on an exchange since the last reconciliation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OwnActivity {
+    /// Net change per currency from recorded fills, fees and funding
+    pub flows: HashMap<String, f64>,
+    /// Unrealized PnL of the bot's open perp positions per collateral
+    /// currency, which perp venues include in their equity
+    pub unrealized: HashMap<String, f64>,
+}
+
+impl OwnActivity {
+    /// Add `delta` to the flow of a currency
+    pub fn add_flow(&mut self, currency: &str, delta: f64) {
+        *self.flows.entry(currency.to_string()).or_insert(0.0) += delta;
+    }
+}
+
 /// Balance discrepancy types
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum BalanceDiscrepancy {
@@ -113,10 +136,90 @@ impl BalanceDiscrepancy {
             Self::Precision { .. } => DiscrepancySeverity::Ok,
         }
     }
+
+    /// Exchange balance less the local balance
+    pub fn gap(&self) -> f64 {
+        match self {
+            Self::Missing { amount, .. } => -amount,
+            Self::Mismatch {
+                local, exchange, ..
+            } => exchange - local,
+            Self::Precision { .. } => 0.0,
+        }
+    }
+}
+
+/// Compare expected balances with exchange balances in both directions
+///
+/// Gaps below `threshold_percentage` of the exchange balance are ignored,
+/// and so are currencies only one side holds a dust amount of. A currency
+/// only the exchange holds is a mismatch against a local balance of zero.
+pub fn compare_balances(
+    local: &Portfolio,
+    exchange: &ExchangeBalances,
+    config: &ReconciliationConfig,
+) -> Vec<BalanceDiscrepancy> {
+    let mut discrepancies = Vec::new();
+
+    // Check local balances against exchange
+    for (currency, local_balance) in &local.balances {
+        match exchange.get_balance(currency) {
+            Some(exchange_balance) => {
+                let diff = (local_balance - exchange_balance.amount).abs();
+                if diff > 0.0 {
+                    let difference_percentage = if exchange_balance.amount != 0.0 {
+                        diff / exchange_balance.amount.abs() * 100.0
+                    } else {
+                        100.0
+                    };
+
+                    if difference_percentage >= config.threshold_percentage * 100.0
+                        && diff > config.precision_tolerance
+                    {
+                        discrepancies.push(BalanceDiscrepancy::Mismatch {
+                            currency: currency.clone(),
+                            local: *local_balance,
+                            exchange: exchange_balance.amount,
+                            diff,
+                        });
+                    } else if diff <= config.precision_tolerance {
+                        discrepancies.push(BalanceDiscrepancy::Precision {
+                            currency: currency.clone(),
+                            tolerance: config.precision_tolerance,
+                        });
+                    }
+                }
+            }
+            None if local_balance.abs() > config.precision_tolerance => {
+                discrepancies.push(BalanceDiscrepancy::Missing {
+                    currency: currency.clone(),
+                    amount: *local_balance,
+                });
+            }
+            None => {}
+        }
+    }
+
+    // Check exchange balances the local side does not know about
+    for (currency, exchange_balance) in &exchange.balances {
+        if local.balances.contains_key(currency)
+            || exchange_balance.amount.abs() <= config.precision_tolerance
+        {
+            continue;
+        }
+        discrepancies.push(BalanceDiscrepancy::Mismatch {
+            currency: currency.clone(),
+            local: 0.0,
+            exchange: exchange_balance.amount,
+            diff: exchange_balance.amount.abs(),
+        });
+    }
+
+    discrepancies
 }
 
 /// Discrepancy severity levels
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum DiscrepancySeverity {
     Ok,
     Minor,
@@ -172,6 +275,9 @@ pub struct ReconciliationReport {
     pub status: ReconciliationStatus,
     pub local_balances: Vec<Balance>,
     pub exchange_balances: HashMap<String, Balance>,
+    /// Steps taken on the discrepancies, None when none were needed
+    pub remediation: Option<Remediation>,
+    pub operator_notes: Option<String>,
 }
 
 impl ReconciliationReport {
@@ -183,9 +289,20 @@ impl ReconciliationReport {
             status: ReconciliationStatus::Ok,
             local_balances: Vec::new(),
             exchange_balances: HashMap::new(),
+            remediation: None,
+            operator_notes: None,
         }
     }
 
+    /// Key of the report in the reconciliation audit trail
+    pub fn id(&self) -> String {
+        format!(
+            "rec_{}_{}",
+            self.exchange.name(),
+            self.timestamp.timestamp_millis()
+        )
+    }
+
     pub fn add_discrepancy(&mut self, discrepancy: BalanceDiscrepancy) {
         let severity = discrepancy.severity();
         self.discrepancies.push(discrepancy);
@@ -5,8 +5,14 @@
 //! - Portfolio exposure limits
 //! - Order size constraints (min/max)
 //! - Price precision
+//!
+//! Risk-based modes (fixed fractional risk, ATR-scaled, volatility target and
+//! capped Kelly) decide how much to buy before those limits are applied.
 
-use crate::domain::value_objects::position_sizing::{PositionSizingRequest, PositionSizingResult};
+use crate::domain::services::indicators::Candle;
+use crate::domain::value_objects::position_sizing::{
+    PositionSizingRequest, PositionSizingResult, SizingInputs, SizingMode, SizingParams,
+};
 
 /// PositionSizer service for calculating optimal position sizes
 #[derive(Debug, Clone)]
@@ -83,6 +89,155 @@ impl PositionSizer {
         PositionSizingResult::new(final_quantity, final_notional_value, final_margin_used, reason)
     }
 
+    /// Compute the unconstrained target size for a sizing mode
+    ///
+    /// The result is unlevered (`margin_used` equals the notional value) and is
+    /// meant to be passed through [`PositionSizer::size_position`] or the
+    /// caller's own limits afterwards. Risk-based modes never exceed
+    /// `params.max_position_fraction` of equity.
+    ///
+    /// # Errors
+    /// Returns an error when equity or price are not positive, or when the
+    /// input the mode depends on (stop distance, ATR, volatility, win rate) is
+    /// missing or invalid.
+    pub fn target_size(
+        &self,
+        mode: SizingMode,
+        params: &SizingParams,
+        inputs: &SizingInputs,
+    ) -> Result<PositionSizingResult, String> {
+        if !inputs.equity.is_finite() || inputs.equity <= 0.0 {
+            return Err("equity must be positive".to_string());
+        }
+        if !inputs.price.is_finite() || inputs.price <= 0.0 {
+            return Err("price must be positive".to_string());
+        }
+
+        let (notional, reason) = match mode {
+            SizingMode::FixedPercentage => (
+                inputs.equity * params.portfolio_fraction,
+                format!("Fixed {:.2}% of equity", params.portfolio_fraction * 100.0),
+            ),
+            SizingMode::FixedFractionalRisk => {
+                let stop_distance = positive(inputs.stop_distance, "stop distance")?;
+                let quantity = inputs.equity * params.risk_per_trade / stop_distance;
+                (
+                    quantity * inputs.price,
+                    format!(
+                        "Risking {:.2}% of equity over a stop distance of {:.4}",
+                        params.risk_per_trade * 100.0,
+                        stop_distance
+                    ),
+                )
+            }
+            SizingMode::AtrScaled => {
+                let atr = positive(inputs.atr, "ATR")?;
+                let stop_distance = atr * params.atr_multiple;
+                if stop_distance <= 0.0 {
+                    return Err("ATR multiple must be positive".to_string());
+                }
+                let quantity = inputs.equity * params.risk_per_trade / stop_distance;
+                (
+                    quantity * inputs.price,
+                    format!(
+                        "Risking {:.2}% of equity over {:.1} ATR ({:.4})",
+                        params.risk_per_trade * 100.0,
+                        params.atr_multiple,
+                        stop_distance
+                    ),
+                )
+            }
+            SizingMode::VolatilityTarget => {
+                let volatility = positive(inputs.annualized_volatility, "volatility")?;
+                (
+                    inputs.equity * params.target_annual_volatility / volatility,
+                    format!(
+                        "Targeting {:.1}% annualised volatility (realised {:.1}%)",
+                        params.target_annual_volatility * 100.0,
+                        volatility * 100.0
+                    ),
+                )
+            }
+            SizingMode::Kelly => {
+                if inputs.closed_trades < params.kelly_min_trades {
+                    return Err(format!(
+                        "Kelly sizing needs {} closed trades, have {}",
+                        params.kelly_min_trades, inputs.closed_trades
+                    ));
+                }
+                let win_rate = inputs
+                    .win_rate
+                    .filter(|p| (0.0..=1.0).contains(p))
+                    .ok_or_else(|| "win rate is missing or outside [0, 1]".to_string())?;
+                let payoff = positive(inputs.payoff_ratio, "payoff ratio")?;
+                let full_kelly = win_rate - (1.0 - win_rate) / payoff;
+                let fraction = (full_kelly * params.kelly_fraction)
+                    .min(params.kelly_cap)
+                    .max(0.0);
+                (
+                    inputs.equity * fraction,
+                    format!(
+                        "Kelly {:.2}% of equity (full Kelly {:.2}%, win rate {:.1}%, payoff {:.2})",
+                        fraction * 100.0,
+                        full_kelly * 100.0,
+                        win_rate * 100.0,
+                        payoff
+                    ),
+                )
+            }
+        };
+
+        let (notional, reason) = if mode != SizingMode::FixedPercentage
+            && notional > inputs.equity * params.max_position_fraction
+        {
+            (
+                inputs.equity * params.max_position_fraction,
+                format!(
+                    "{}; capped at {:.1}% of equity",
+                    reason,
+                    params.max_position_fraction * 100.0
+                ),
+            )
+        } else {
+            (notional, reason)
+        };
+
+        if !notional.is_finite() || notional < 0.0 {
+            return Err(format!("invalid notional value computed: {}", notional));
+        }
+
+        PositionSizingResult::new(notional / inputs.price, notional, notional, reason)
+    }
+
+    /// Annualised volatility of close-to-close log returns
+    ///
+    /// # Arguments
+    /// * `candles` - Candle history, oldest first
+    /// * `periods_per_year` - Number of candles in a year at the candle interval
+    ///
+    /// # Returns
+    /// `None` when fewer than three candles are available
+    pub fn realized_volatility(candles: &[Candle], periods_per_year: f64) -> Option<f64> {
+        if candles.len() < 3 || periods_per_year <= 0.0 {
+            return None;
+        }
+
+        let returns: Vec<f64> = candles
+            .windows(2)
+            .map(|w| (w[1].close.value() / w[0].close.value()).ln())
+            .filter(|r| r.is_finite())
+            .collect();
+        if returns.len() < 2 {
+            return None;
+        }
+
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance =
+            returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+
+        Some(variance.sqrt() * periods_per_year.sqrt())
+    }
+
     /// Calculate if the available balance is sufficient for a desired notional value
     ///
     /// # Arguments
@@ -139,6 +294,15 @@ impl PositionSizer {
     }
 }
 
+/// Extract a strictly positive, finite input or describe why it is unusable
+fn positive(value: Option<f64>, name: &str) -> Result<f64, String> {
+    match value {
+        Some(v) if v.is_finite() && v > 0.0 => Ok(v),
+        Some(v) => Err(format!("{} must be positive, got {}", name, v)),
+        None => Err(format!("{} is not available", name)),
+    }
+}
+
 impl Default for PositionSizer {
     fn default() -> Self {
         Self::new()
@@ -388,4 +552,123 @@ mod tests {
         let result = sizer.size_position(&req).unwrap();
         assert!(!result.reason.is_empty());
     }
+
+    fn params() -> SizingParams {
+        SizingParams::default()
+    }
+
+    fn inputs() -> SizingInputs {
+        SizingInputs::new(10000.0, 100.0)
+    }
+
+    #[test]
+    fn test_target_size_fixed_percentage() {
+        let sizer = PositionSizer::new();
+        let result = sizer
+            .target_size(SizingMode::FixedPercentage, &params(), &inputs())
+            .unwrap();
+
+        // 2% of 10000 = 200 USD at 100 = 2 units
+        assert!((result.quantity - 2.0).abs() < 1e-9);
+        assert!((result.notional_value - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_target_size_fixed_fractional_risk() {
+        let sizer = PositionSizer::new();
+        let mut inputs = inputs();
+        inputs.stop_distance = Some(5.0);
+
+        let result = sizer
+            .target_size(SizingMode::FixedFractionalRisk, &params(), &inputs)
+            .unwrap();
+
+        // Risk 1% = 100 USD over a 5 USD stop = 20 units, 2000 USD notional
+        assert!((result.quantity - 20.0).abs() < 1e-9);
+
+        inputs.stop_distance = None;
+        assert!(sizer
+            .target_size(SizingMode::FixedFractionalRisk, &params(), &inputs)
+            .is_err());
+    }
+
+    #[test]
+    fn test_target_size_atr_scaled() {
+        let sizer = PositionSizer::new();
+        let mut inputs = inputs();
+        inputs.atr = Some(2.5);
+
+        let result = sizer
+            .target_size(SizingMode::AtrScaled, &params(), &inputs)
+            .unwrap();
+
+        // Stop = 2 * 2.5 = 5, so the same 20 units as a 5 USD fixed stop
+        assert!((result.quantity - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_target_size_volatility_target_is_capped() {
+        let sizer = PositionSizer::new();
+        let mut inputs = inputs();
+        inputs.annualized_volatility = Some(0.8);
+
+        let result = sizer
+            .target_size(SizingMode::VolatilityTarget, &params(), &inputs)
+            .unwrap();
+        // 20% / 80% = 25% of equity = 2500 USD
+        assert!((result.notional_value - 2500.0).abs() < 1e-9);
+
+        inputs.annualized_volatility = Some(0.1);
+        let result = sizer
+            .target_size(SizingMode::VolatilityTarget, &params(), &inputs)
+            .unwrap();
+        // 200% of equity requested, capped at 25%
+        assert!((result.notional_value - 2500.0).abs() < 1e-9);
+        assert!(result.reason.contains("capped"));
+    }
+
+    #[test]
+    fn test_target_size_kelly() {
+        let sizer = PositionSizer::new();
+        let params = params();
+        let mut inputs = inputs();
+        inputs.win_rate = Some(0.55);
+        inputs.payoff_ratio = Some(1.5);
+        inputs.closed_trades = 10;
+
+        // Not enough history yet
+        let result = sizer.target_size(SizingMode::Kelly, &params, &inputs);
+        assert!(result.is_err());
+
+        inputs.closed_trades = 40;
+        let result = sizer
+            .target_size(SizingMode::Kelly, &params, &inputs)
+            .unwrap();
+        // Full Kelly = 0.55 - 0.45 / 1.5 = 0.25, half Kelly 12.5% capped at 10%
+        assert!((result.notional_value - 1000.0).abs() < 1e-9);
+
+        // A losing edge sizes to zero
+        inputs.win_rate = Some(0.3);
+        let result = sizer
+            .target_size(SizingMode::Kelly, &params, &inputs)
+            .unwrap();
+        assert_eq!(result.quantity, 0.0);
+    }
+
+    #[test]
+    fn test_realized_volatility() {
+        let flat: Vec<Candle> = (0..10)
+            .map(|_| Candle::new(100.0, 100.0, 100.0, 100.0, 1.0).unwrap())
+            .collect();
+        assert_eq!(PositionSizer::realized_volatility(&flat, 365.0), Some(0.0));
+
+        let moving: Vec<Candle> = [100.0, 101.0, 99.0, 102.0, 100.0]
+            .iter()
+            .map(|&p| Candle::new(p, p, p, p, 1.0).unwrap())
+            .collect();
+        let vol = PositionSizer::realized_volatility(&moving, 365.0).unwrap();
+        assert!(vol > 0.0);
+
+        assert!(PositionSizer::realized_volatility(&moving[..2], 365.0).is_none());
+    }
 }
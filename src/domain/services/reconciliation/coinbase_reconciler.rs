@@ -10,11 +10,23 @@ use std::sync::Arc;
 pub struct CoinbaseReconciler {
     client: Arc<CoinbaseClient>,
     config: ReconciliationConfig,
+    /// Balances we expect to hold on the exchange
+    local: Portfolio,
 }
 
 impl CoinbaseReconciler {
     pub fn new(client: Arc<CoinbaseClient>, config: ReconciliationConfig) -> Self {
-        Self { client, config }
+        Self {
+            client,
+            config,
+            local: Portfolio::new(),
+        }
+    }
+
+    /// Reconcile against these expected balances instead of none
+    pub fn with_local_balances(mut self, local: Portfolio) -> Self {
+        self.local = local;
+        self
     }
 }
 
@@ -52,47 +64,7 @@ impl PortfolioReconciliationService for CoinbaseReconciler {
         local: &Portfolio,
         exchange: &ExchangeBalances,
     ) -> Vec<BalanceDiscrepancy> {
-        let mut discrepancies = Vec::new();
-
-        // Check local balances against exchange
-        for (currency, local_balance) in &local.balances {
-            match exchange.get_balance(currency) {
-                Some(exchange_balance) => {
-                    let diff = (local_balance - exchange_balance.amount).abs();
-                    if diff > 0.0 {
-                        let difference_percentage = if exchange_balance.amount != 0.0 {
-                            (local_balance - exchange_balance.amount).abs()
-                                / exchange_balance.amount.abs()
-                                * 100.0
-                        } else {
-                            100.0
-                        };
-
-                        if difference_percentage >= self.config.threshold_percentage * 100.0 {
-                            discrepancies.push(BalanceDiscrepancy::Mismatch {
-                                currency: currency.clone(),
-                                local: *local_balance,
-                                exchange: exchange_balance.amount,
-                                diff,
-                            });
-                        } else if diff <= self.config.precision_tolerance {
-                            discrepancies.push(BalanceDiscrepancy::Precision {
-                                currency: currency.clone(),
-                                tolerance: self.config.precision_tolerance,
-                            });
-                        }
-                    }
-                }
-                None => {
-                    discrepancies.push(BalanceDiscrepancy::Missing {
-                        currency: currency.clone(),
-                        amount: *local_balance,
-                    });
-                }
-            }
-        }
-
-        discrepancies
+        compare_balances(local, exchange, &self.config)
     }
 
     fn generate_report(
@@ -113,14 +85,12 @@ impl PortfolioReconciliationService for CoinbaseReconciler {
     ) -> Result<ReconciliationReport, ReconciliationError> {
         let exchange_balances = self.fetch_exchange_balances(&exchange).await?;
 
-        // For now, create an empty local portfolio - in real implementation this would come from position manager
-        let local_portfolio = Portfolio::new();
-
-        let discrepancies = self.detect_discrepancies(&local_portfolio, &exchange_balances);
+        let discrepancies = self.detect_discrepancies(&self.local, &exchange_balances);
         let mut report = self.generate_report(discrepancies, exchange);
 
         // Convert balances to Balance structs for the report
-        report.local_balances = local_portfolio
+        report.local_balances = self
+            .local
             .balances
             .iter()
             .map(|(currency, amount)| Balance {
@@ -1,20 +1,97 @@
 //! dYdX-specific balance reconciler
 
 use crate::domain::entities::exchange::Exchange;
+use crate::domain::entities::position::PositionSide;
 use crate::domain::services::portfolio_reconciliation::*;
+use crate::domain::services::reconciliation::positions::{VenueOrder, VenuePosition, VenueState};
 use crate::infrastructure::dydx_client::DydxClient;
 use async_trait::async_trait;
 use std::sync::Arc;
 
+/// Margin figure reported next to equity; it moves with the margin of open
+/// positions rather than with fills, so it is reported but not reconciled
+const FREE_COLLATERAL: &str = "FREE_COLLATERAL";
+
 /// dYdX reconciler implementation
 pub struct DydxReconciler {
     client: Arc<DydxClient>,
     config: ReconciliationConfig,
+    /// Balances we expect to hold on the exchange
+    local: Portfolio,
 }
 
 impl DydxReconciler {
     pub fn new(client: Arc<DydxClient>, config: ReconciliationConfig) -> Self {
-        Self { client, config }
+        Self {
+            client,
+            config,
+            local: Portfolio::new(),
+        }
+    }
+
+    /// Reconcile against these expected balances instead of none
+    pub fn with_local_balances(mut self, local: Portfolio) -> Self {
+        self.local = local;
+        self
+    }
+
+    /// Fetch the open perp positions and open orders of the subaccount
+    pub async fn fetch_venue_state(&self) -> Result<VenueState, ReconciliationError> {
+        let positions = self
+            .client
+            .get_perpetual_positions()
+            .await
+            .map_err(ReconciliationError::ApiError)?;
+        let orders = self
+            .client
+            .get_open_orders()
+            .await
+            .map_err(ReconciliationError::ApiError)?;
+
+        let mut state = VenueState::default();
+        for position in positions {
+            let size = position.size.parse::<f64>().map_err(|e| {
+                ReconciliationError::ParseError(format!(
+                    "Invalid size '{}' for {}: {}",
+                    position.size, position.market, e
+                ))
+            })?;
+            if size == 0.0 {
+                continue;
+            }
+            let side = match position.side.to_uppercase().as_str() {
+                "SHORT" => PositionSide::Short,
+                _ if size < 0.0 => PositionSide::Short,
+                _ => PositionSide::Long,
+            };
+            let entry_price = position
+                .entry_price
+                .parse::<f64>()
+                .ok()
+                .filter(|price| *price > 0.0)
+                .ok_or_else(|| {
+                    ReconciliationError::ParseError(format!(
+                        "Invalid entry price '{}' for {}",
+                        position.entry_price, position.market
+                    ))
+                })?;
+            state.positions.push(VenuePosition {
+                symbol: position.market,
+                side,
+                quantity: size.abs(),
+                entry_price,
+            });
+        }
+        state.open_orders = orders
+            .into_iter()
+            .map(|order| VenueOrder {
+                order_id: order.id,
+                client_id: order.client_id,
+                symbol: order.ticker,
+            })
+            .collect();
+
+        Ok(state)
     }
 }
 
@@ -40,7 +117,7 @@ impl PortfolioReconciliationService for DydxReconciler {
                         balances.add_balance("USD".to_string(), equity); // dYdX uses USD as base
                     }
                     if let Ok(free_collateral) = subaccount.free_collateral.parse::<f64>() {
-                        balances.add_balance("FREE_COLLATERAL".to_string(), free_collateral);
+                        balances.add_balance(FREE_COLLATERAL.to_string(), free_collateral);
                     }
                 }
 
@@ -57,47 +134,10 @@ impl PortfolioReconciliationService for DydxReconciler {
         local: &Portfolio,
         exchange: &ExchangeBalances,
     ) -> Vec<BalanceDiscrepancy> {
-        let mut discrepancies = Vec::new();
-
-        // Check local balances against exchange
-        for (currency, local_balance) in &local.balances {
-            match exchange.get_balance(currency) {
-                Some(exchange_balance) => {
-                    let diff = (local_balance - exchange_balance.amount).abs();
-                    if diff > 0.0 {
-                        let difference_percentage = if exchange_balance.amount != 0.0 {
-                            (local_balance - exchange_balance.amount).abs()
-                                / exchange_balance.amount.abs()
-                                * 100.0
-                        } else {
-                            100.0
-                        };
-
-                        if difference_percentage >= self.config.threshold_percentage * 100.0 {
-                            discrepancies.push(BalanceDiscrepancy::Mismatch {
-                                currency: currency.clone(),
-                                local: *local_balance,
-                                exchange: exchange_balance.amount,
-                                diff,
-                            });
-                        } else if diff <= self.config.precision_tolerance {
-                            discrepancies.push(BalanceDiscrepancy::Precision {
-                                currency: currency.clone(),
-                                tolerance: self.config.precision_tolerance,
-                            });
-                        }
-                    }
-                }
-                None => {
-                    discrepancies.push(BalanceDiscrepancy::Missing {
-                        currency: currency.clone(),
-                        amount: *local_balance,
-                    });
-                }
-            }
-        }
-
-        discrepancies
+        compare_balances(local, exchange, &self.config)
+            .into_iter()
+            .filter(|discrepancy| discrepancy.currency() != FREE_COLLATERAL)
+            .collect()
     }
 
     fn generate_report(
@@ -118,14 +158,12 @@ impl PortfolioReconciliationService for DydxReconciler {
     ) -> Result<ReconciliationReport, ReconciliationError> {
         let exchange_balances = self.fetch_exchange_balances(&exchange).await?;
 
-        // For now, create an empty local portfolio - in real implementation this would come from position manager
-        let local_portfolio = Portfolio::new();
-
-        let discrepancies = self.detect_discrepancies(&local_portfolio, &exchange_balances);
+        let discrepancies = self.detect_discrepancies(&self.local, &exchange_balances);
         let mut report = self.generate_report(discrepancies, exchange);
 
         // Convert balances to Balance structs for the report
-        report.local_balances = local_portfolio
+        report.local_balances = self
+            .local
             .balances
             .iter()
             .map(|(currency, amount)| Balance {
@@ -6,6 +6,8 @@
 pub mod coinbase_reconciler;
 pub mod dydx_reconciler;
 pub mod models;
+pub mod positions;
+pub mod remediation;
 
 // Re-export reconcilers
 pub use coinbase_reconciler::CoinbaseReconciler;
@@ -14,3 +16,5 @@ pub use dydx_reconciler::DydxReconciler;
 // Re-export all types from parent portfolio_reconciliation module
 pub use super::portfolio_reconciliation::*;
 pub use models::*;
+pub use positions::*;
+pub use remediation::*;
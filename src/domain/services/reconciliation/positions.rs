@@ -0,0 +1,600 @@
+//! Position-level reconciliation
+//!
+//! Balances alone miss a position closed on the venue's UI or an order that
+//! filled while the bot was down. This compares the positions and active
+//! orders we track with the perp positions and open orders the venue reports.
+//! Perp venues hold one net position per market, so local positions are
+//! netted per symbol before comparing, together with the exposure of
+//! strategies that trade without positions (arbitrage legs, market making
+//! inventory). A trade landing between the local and
+//! the venue snapshot looks like a discrepancy, so one is only reported once
+//! it persists into the next run.
+
+use super::super::portfolio_reconciliation::{DiscrepancySeverity, ReconciliationStatus};
+use crate::domain::entities::exchange::Exchange;
+use crate::domain::entities::position::{Position, PositionSide};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Perp position held on the venue
+#[derive(Debug, Clone)]
+pub struct VenuePosition {
+    pub symbol: String,
+    pub side: PositionSide,
+    pub quantity: f64,
+    pub entry_price: f64,
+}
+
+impl VenuePosition {
+    /// Quantity, negative for shorts
+    pub fn signed_quantity(&self) -> f64 {
+        match self.side {
+            PositionSide::Long => self.quantity,
+            PositionSide::Short => -self.quantity,
+        }
+    }
+}
+
+/// Order open on the venue, or active in our order metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct VenueOrder {
+    pub order_id: String,
+    pub client_id: String,
+    pub symbol: String,
+}
+
+/// Positions and open orders the venue reports
+#[derive(Debug, Clone, Default)]
+pub struct VenueState {
+    pub positions: Vec<VenuePosition>,
+    pub open_orders: Vec<VenueOrder>,
+}
+
+/// Difference between local and venue positions or orders
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PositionDiscrepancy {
+    /// Tracked locally but flat on the venue, e.g. closed on the venue UI
+    GhostPosition {
+        symbol: String,
+        position_ids: Vec<String>,
+        local_quantity: f64,
+    },
+    /// Held on the venue but not tracked locally, e.g. filled while we were down
+    OrphanPosition {
+        symbol: String,
+        exchange_quantity: f64,
+        entry_price: f64,
+    },
+    /// Held on both sides with a different net size or side
+    QuantityMismatch {
+        symbol: String,
+        position_ids: Vec<String>,
+        local_quantity: f64,
+        exchange_quantity: f64,
+    },
+    /// Active locally but no longer open on the venue
+    GhostOrder {
+        order_id: String,
+        client_id: String,
+        symbol: String,
+    },
+    /// Open on the venue but not tracked locally
+    OrphanOrder {
+        order_id: String,
+        client_id: String,
+        symbol: String,
+    },
+}
+
+impl PositionDiscrepancy {
+    pub fn symbol(&self) -> &str {
+        match self {
+            Self::GhostPosition { symbol, .. }
+            | Self::OrphanPosition { symbol, .. }
+            | Self::QuantityMismatch { symbol, .. }
+            | Self::GhostOrder { symbol, .. }
+            | Self::OrphanOrder { symbol, .. } => symbol,
+        }
+    }
+
+    /// Untracked exposure is critical; an order we still think is active
+    /// has most likely filled or expired and is minor
+    pub fn severity(&self) -> DiscrepancySeverity {
+        match self {
+            Self::GhostPosition { .. } | Self::OrphanPosition { .. } => {
+                DiscrepancySeverity::Critical
+            }
+            Self::QuantityMismatch { .. } | Self::OrphanOrder { .. } => DiscrepancySeverity::Major,
+            Self::GhostOrder { .. } => DiscrepancySeverity::Minor,
+        }
+    }
+
+    /// Whether both are the same kind of discrepancy on the same symbol,
+    /// or on the same order
+    fn same_as(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::GhostOrder { client_id: a, .. }, Self::GhostOrder { client_id: b, .. })
+            | (Self::OrphanOrder { client_id: a, .. }, Self::OrphanOrder { client_id: b, .. }) => {
+                a == b
+            }
+            _ => {
+                std::mem::discriminant(self) == std::mem::discriminant(other)
+                    && self.symbol() == other.symbol()
+            }
+        }
+    }
+}
+
+/// What to do automatically about orphans and ghosts
+///
+/// Both are off by default, leaving discrepancies to an operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PositionReconciliationPolicy {
+    /// Track venue positions we do not know about as local positions
+    pub adopt_orphans: bool,
+    /// Close local positions and orders the venue no longer holds
+    pub close_ghosts: bool,
+}
+
+/// Result of reconciling positions and orders on one exchange
+#[derive(Debug, Clone)]
+pub struct PositionReconciliationReport {
+    pub exchange: Exchange,
+    pub timestamp: DateTime<Utc>,
+    pub discrepancies: Vec<PositionDiscrepancy>,
+    pub status: ReconciliationStatus,
+    /// Symbols whose orphan was adopted as a local position
+    pub adopted: Vec<String>,
+    /// Symbols whose ghost positions were closed locally
+    pub closed: Vec<String>,
+    /// Discrepancies seen for the first time, reported if the next run
+    /// finds them again
+    pub pending: Vec<PositionDiscrepancy>,
+}
+
+impl PositionReconciliationReport {
+    pub fn new(exchange: Exchange) -> Self {
+        Self {
+            exchange,
+            timestamp: Utc::now(),
+            discrepancies: Vec::new(),
+            status: ReconciliationStatus::Ok,
+            adopted: Vec::new(),
+            closed: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Keep the discrepancies the previous run also found, moving the
+    /// others to `pending`
+    pub fn confirmed_by(self, previous: Option<&PositionReconciliationReport>) -> Self {
+        let seen = |discrepancy: &PositionDiscrepancy| {
+            previous.is_some_and(|previous| {
+                previous
+                    .discrepancies
+                    .iter()
+                    .chain(&previous.pending)
+                    .any(|earlier| earlier.same_as(discrepancy))
+            })
+        };
+        let mut report = Self::new(self.exchange);
+        report.timestamp = self.timestamp;
+        for discrepancy in self.discrepancies {
+            if seen(&discrepancy) {
+                report.add_discrepancy(discrepancy);
+            } else {
+                report.pending.push(discrepancy);
+            }
+        }
+        report
+    }
+
+    pub fn add_discrepancy(&mut self, discrepancy: PositionDiscrepancy) {
+        let level = |status: ReconciliationStatus| match status {
+            ReconciliationStatus::Ok => 0,
+            ReconciliationStatus::Minor => 1,
+            ReconciliationStatus::Major => 2,
+            ReconciliationStatus::Critical => 3,
+        };
+        let status = match discrepancy.severity() {
+            DiscrepancySeverity::Ok => ReconciliationStatus::Ok,
+            DiscrepancySeverity::Minor => ReconciliationStatus::Minor,
+            DiscrepancySeverity::Major => ReconciliationStatus::Major,
+            DiscrepancySeverity::Critical => ReconciliationStatus::Critical,
+        };
+        if level(status) > level(self.status) {
+            self.status = status;
+        }
+        self.discrepancies.push(discrepancy);
+    }
+
+    /// Discrepancies not settled by adopting an orphan or closing a ghost
+    pub fn unresolved(&self) -> Vec<&PositionDiscrepancy> {
+        self.discrepancies
+            .iter()
+            .filter(|discrepancy| match discrepancy {
+                PositionDiscrepancy::OrphanPosition { symbol, .. } => {
+                    !self.adopted.contains(symbol)
+                }
+                PositionDiscrepancy::GhostPosition { symbol, .. } => !self.closed.contains(symbol),
+                _ => true,
+            })
+            .collect()
+    }
+
+    /// Venue position not tracked locally on a symbol, as (net quantity, entry price)
+    pub fn orphan_position(&self, symbol: &str) -> Option<(f64, f64)> {
+        self.discrepancies
+            .iter()
+            .find_map(|discrepancy| match discrepancy {
+                PositionDiscrepancy::OrphanPosition {
+                    symbol: orphan,
+                    exchange_quantity,
+                    entry_price,
+                } if orphan == symbol => Some((*exchange_quantity, *entry_price)),
+                _ => None,
+            })
+    }
+
+    /// Local positions on a symbol the venue no longer holds
+    pub fn ghost_positions(&self, symbol: &str) -> Vec<String> {
+        self.discrepancies
+            .iter()
+            .filter_map(|discrepancy| match discrepancy {
+                PositionDiscrepancy::GhostPosition {
+                    symbol: ghost,
+                    position_ids,
+                    ..
+                } if ghost == symbol => Some(position_ids.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Local orders the venue no longer holds open
+    pub fn ghost_orders(&self) -> Vec<&str> {
+        self.discrepancies
+            .iter()
+            .filter_map(|discrepancy| match discrepancy {
+                PositionDiscrepancy::GhostOrder { order_id, .. } => Some(order_id.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Symbols with a venue position not tracked locally
+    pub fn orphan_symbols(&self) -> Vec<&str> {
+        self.discrepancies
+            .iter()
+            .filter(|discrepancy| matches!(discrepancy, PositionDiscrepancy::OrphanPosition { .. }))
+            .map(|discrepancy| discrepancy.symbol())
+            .collect()
+    }
+
+    /// Symbols with local positions the venue no longer holds
+    pub fn ghost_symbols(&self) -> Vec<&str> {
+        self.discrepancies
+            .iter()
+            .filter(|discrepancy| matches!(discrepancy, PositionDiscrepancy::GhostPosition { .. }))
+            .map(|discrepancy| discrepancy.symbol())
+            .collect()
+    }
+}
+
+/// Compare local positions and active orders with the venue
+///
+/// `strategy_exposure` is the signed net quantity per symbol held by
+/// strategies that keep no positions; it counts as tracked, so it is never
+/// reported as an orphan, and its symbols only have ghost positions when
+/// positions are open on them. Net sizes on a symbol mismatch when they
+/// differ by more than `threshold` (e.g. 0.01 = 1%) of the larger one.
+/// Orders are matched on client id.
+pub fn reconcile_positions(
+    exchange: Exchange,
+    local_positions: &[Position],
+    strategy_exposure: &BTreeMap<String, f64>,
+    local_orders: &[VenueOrder],
+    venue: &VenueState,
+    threshold: f64,
+) -> PositionReconciliationReport {
+    let mut report = PositionReconciliationReport::new(exchange);
+
+    // symbol -> (local ids, local net, strategy exposure, (venue net, venue entry))
+    let mut symbols: BTreeMap<&str, (Vec<String>, f64, bool, Option<(f64, f64)>)> = BTreeMap::new();
+    for (symbol, quantity) in strategy_exposure {
+        let entry = symbols.entry(symbol.as_str()).or_default();
+        entry.1 += quantity;
+        entry.2 = true;
+    }
+    for position in local_positions {
+        let quantity = match position.side {
+            PositionSide::Long => position.quantity.value(),
+            PositionSide::Short => -position.quantity.value(),
+        };
+        let entry = symbols.entry(position.symbol.as_str()).or_default();
+        entry.0.push(position.id.clone());
+        entry.1 += quantity;
+    }
+    for position in &venue.positions {
+        let entry = symbols.entry(position.symbol.as_str()).or_default();
+        let (net, _) = entry.3.get_or_insert((0.0, position.entry_price));
+        *net += position.signed_quantity();
+    }
+
+    for (symbol, (position_ids, local_quantity, strategy_held, exchange)) in symbols {
+        let symbol = symbol.to_string();
+        let tracked = !position_ids.is_empty() || strategy_held;
+        match exchange {
+            None if !tracked || local_quantity == 0.0 => {}
+            // Strategy exposure the venue no longer holds has no position to close
+            None if position_ids.is_empty() => {
+                report.add_discrepancy(PositionDiscrepancy::QuantityMismatch {
+                    symbol,
+                    position_ids,
+                    local_quantity,
+                    exchange_quantity: 0.0,
+                })
+            }
+            None => report.add_discrepancy(PositionDiscrepancy::GhostPosition {
+                symbol,
+                position_ids,
+                local_quantity,
+            }),
+            Some((exchange_quantity, entry_price)) if !tracked => {
+                report.add_discrepancy(PositionDiscrepancy::OrphanPosition {
+                    symbol,
+                    exchange_quantity,
+                    entry_price,
+                })
+            }
+            Some((exchange_quantity, _)) => {
+                let diff = (local_quantity - exchange_quantity).abs();
+                if diff > threshold * local_quantity.abs().max(exchange_quantity.abs()) {
+                    report.add_discrepancy(PositionDiscrepancy::QuantityMismatch {
+                        symbol,
+                        position_ids,
+                        local_quantity,
+                        exchange_quantity,
+                    });
+                }
+            }
+        }
+    }
+
+    let venue_ids: HashSet<&str> = venue
+        .open_orders
+        .iter()
+        .map(|order| order.client_id.as_str())
+        .collect();
+    let local_ids: HashSet<&str> = local_orders
+        .iter()
+        .map(|order| order.client_id.as_str())
+        .collect();
+    for order in local_orders {
+        if !venue_ids.contains(order.client_id.as_str()) {
+            report.add_discrepancy(PositionDiscrepancy::GhostOrder {
+                order_id: order.order_id.clone(),
+                client_id: order.client_id.clone(),
+                symbol: order.symbol.clone(),
+            });
+        }
+    }
+    for order in &venue.open_orders {
+        if !local_ids.contains(order.client_id.as_str()) {
+            report.add_discrepancy(PositionDiscrepancy::OrphanOrder {
+                order_id: order.order_id.clone(),
+                client_id: order.client_id.clone(),
+                symbol: order.symbol.clone(),
+            });
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_objects::{price::Price, quantity::Quantity};
+
+    fn position(id: &str, symbol: &str, side: PositionSide, quantity: f64) -> Position {
+        Position::new(
+            id.to_string(),
+            symbol.to_string(),
+            side,
+            Quantity::new(quantity).unwrap(),
+            Price::new(100.0).unwrap(),
+        )
+    }
+
+    fn order(client_id: &str, symbol: &str) -> VenueOrder {
+        VenueOrder {
+            order_id: format!("order_{}", client_id),
+            client_id: client_id.to_string(),
+            symbol: symbol.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_flags_orphans_and_ghosts_on_both_sides() {
+        let local = vec![
+            position("pos_btc", "BTC-USD", PositionSide::Long, 0.5),
+            position("pos_eth", "ETH-USD", PositionSide::Long, 2.0),
+            position("pos_sol_1", "SOL-USD", PositionSide::Short, 10.0),
+            position("pos_sol_2", "SOL-USD", PositionSide::Short, 5.0),
+        ];
+        let venue = VenueState {
+            positions: vec![
+                VenuePosition {
+                    symbol: "BTC-USD".to_string(),
+                    side: PositionSide::Long,
+                    quantity: 0.5,
+                    entry_price: 50000.0,
+                },
+                VenuePosition {
+                    symbol: "SOL-USD".to_string(),
+                    side: PositionSide::Short,
+                    quantity: 10.0,
+                    entry_price: 150.0,
+                },
+                VenuePosition {
+                    symbol: "AVAX-USD".to_string(),
+                    side: PositionSide::Short,
+                    quantity: 3.0,
+                    entry_price: 30.0,
+                },
+            ],
+            open_orders: vec![order("1", "BTC-USD"), order("3", "ETH-USD")],
+        };
+        let local_orders = vec![order("1", "BTC-USD"), order("2", "SOL-USD")];
+
+        let report = reconcile_positions(
+            Exchange::Dydx,
+            &local,
+            &BTreeMap::new(),
+            &local_orders,
+            &venue,
+            0.01,
+        );
+
+        assert_eq!(report.status, ReconciliationStatus::Critical);
+        assert_eq!(report.orphan_symbols(), vec!["AVAX-USD"]);
+        assert_eq!(report.orphan_position("AVAX-USD"), Some((-3.0, 30.0)));
+        assert_eq!(report.ghost_symbols(), vec!["ETH-USD"]);
+        assert_eq!(report.ghost_positions("ETH-USD"), vec!["pos_eth"]);
+        assert!(report
+            .discrepancies
+            .contains(&PositionDiscrepancy::QuantityMismatch {
+                symbol: "SOL-USD".to_string(),
+                position_ids: vec!["pos_sol_1".to_string(), "pos_sol_2".to_string()],
+                local_quantity: -15.0,
+                exchange_quantity: -10.0,
+            }));
+        assert_eq!(report.ghost_orders(), vec!["order_2"]);
+        assert!(report
+            .discrepancies
+            .contains(&PositionDiscrepancy::OrphanOrder {
+                order_id: "order_3".to_string(),
+                client_id: "3".to_string(),
+                symbol: "ETH-USD".to_string(),
+            }));
+        assert_eq!(report.discrepancies.len(), 5);
+
+        let mut report = report;
+        report.adopted.push("AVAX-USD".to_string());
+        report.closed.push("ETH-USD".to_string());
+        assert!(report
+            .unresolved()
+            .iter()
+            .all(|discrepancy| discrepancy.severity() != DiscrepancySeverity::Critical));
+    }
+
+    #[test]
+    fn test_discrepancies_are_reported_once_they_persist() {
+        let local = vec![position("pos_eth", "ETH-USD", PositionSide::Long, 2.0)];
+        let venue = VenueState::default();
+        let first =
+            reconcile_positions(Exchange::Dydx, &local, &BTreeMap::new(), &[], &venue, 0.01);
+        assert_eq!(first.discrepancies.len(), 1);
+
+        // A position closed between the two snapshots is not reported yet
+        let first = first.confirmed_by(None);
+        assert_eq!(first.status, ReconciliationStatus::Ok);
+        assert!(first.discrepancies.is_empty());
+        assert_eq!(first.pending.len(), 1);
+
+        let second =
+            reconcile_positions(Exchange::Dydx, &local, &BTreeMap::new(), &[], &venue, 0.01)
+                .confirmed_by(Some(&first));
+        assert_eq!(second.status, ReconciliationStatus::Critical);
+        assert_eq!(second.ghost_symbols(), vec!["ETH-USD"]);
+        assert!(second.pending.is_empty());
+
+        // Once gone, nothing carries over
+        let third = reconcile_positions(Exchange::Dydx, &[], &BTreeMap::new(), &[], &venue, 0.01)
+            .confirmed_by(Some(&second));
+        assert!(third.discrepancies.is_empty() && third.pending.is_empty());
+    }
+
+    #[test]
+    fn test_matching_state_within_threshold_is_ok() {
+        let local = vec![position("pos_btc", "BTC-USD", PositionSide::Long, 1.0)];
+        let venue = VenueState {
+            positions: vec![VenuePosition {
+                symbol: "BTC-USD".to_string(),
+                side: PositionSide::Long,
+                quantity: 0.995,
+                entry_price: 50000.0,
+            }],
+            open_orders: vec![order("7", "BTC-USD")],
+        };
+
+        let report = reconcile_positions(
+            Exchange::Dydx,
+            &local,
+            &BTreeMap::new(),
+            &[order("7", "BTC-USD")],
+            &venue,
+            0.01,
+        );
+        assert_eq!(report.status, ReconciliationStatus::Ok);
+        assert!(report.discrepancies.is_empty());
+
+        // A flipped side is always a mismatch
+        let mut flipped = venue.clone();
+        flipped.positions[0].side = PositionSide::Short;
+        let report = reconcile_positions(
+            Exchange::Dydx,
+            &local,
+            &BTreeMap::new(),
+            &[order("7", "BTC-USD")],
+            &flipped,
+            0.01,
+        );
+        assert_eq!(report.status, ReconciliationStatus::Major);
+    }
+
+    #[test]
+    fn test_strategy_exposure_counts_as_tracked() {
+        let local = vec![position("pos_btc", "BTC-USD", PositionSide::Long, 1.0)];
+        let exposure = BTreeMap::from([
+            ("BTC-USD".to_string(), -0.4),
+            ("ETH-USD".to_string(), 2.0),
+            ("SOL-USD".to_string(), 5.0),
+        ]);
+        let venue = VenueState {
+            positions: vec![
+                VenuePosition {
+                    symbol: "BTC-USD".to_string(),
+                    side: PositionSide::Long,
+                    quantity: 0.6,
+                    entry_price: 50000.0,
+                },
+                VenuePosition {
+                    symbol: "ETH-USD".to_string(),
+                    side: PositionSide::Long,
+                    quantity: 2.0,
+                    entry_price: 3000.0,
+                },
+            ],
+            open_orders: Vec::new(),
+        };
+
+        let report = reconcile_positions(Exchange::Dydx, &local, &exposure, &[], &venue, 0.01);
+        assert!(report.orphan_symbols().is_empty());
+        assert!(report.ghost_symbols().is_empty());
+        // Exposure the venue no longer holds is a mismatch, not a ghost to close
+        assert_eq!(
+            report.discrepancies,
+            vec![PositionDiscrepancy::QuantityMismatch {
+                symbol: "SOL-USD".to_string(),
+                position_ids: Vec::new(),
+                local_quantity: 5.0,
+                exchange_quantity: 0.0,
+            }]
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Remediation of reconciliation discrepancies
+//!
+//! A policy maps each discrepancy severity to an action. Applying it to a
+//! report yields the steps taken, which are written to the audit row.
+
+use super::super::portfolio_reconciliation::{
+    DiscrepancySeverity, Portfolio, ReconciliationReport,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What to do about a discrepancy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemediationAction {
+    /// Take the exchange balance as the local balance
+    AdoptExchangeBalance,
+    /// Stop opening positions on symbols of the currency until resolved
+    PauseTrading,
+    /// Keep the discrepancy open until an operator resolves it
+    RequireAcknowledgement,
+}
+
+impl RemediationAction {
+    /// Parse `adopt`, `pause` or `acknowledge`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "adopt" => Some(Self::AdoptExchangeBalance),
+            "pause" => Some(Self::PauseTrading),
+            "acknowledge" => Some(Self::RequireAcknowledgement),
+            _ => None,
+        }
+    }
+}
+
+/// Action taken per discrepancy severity
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemediationPolicy {
+    pub minor: RemediationAction,
+    pub major: RemediationAction,
+    pub critical: RemediationAction,
+}
+
+impl Default for RemediationPolicy {
+    fn default() -> Self {
+        Self {
+            minor: RemediationAction::AdoptExchangeBalance,
+            major: RemediationAction::RequireAcknowledgement,
+            critical: RemediationAction::RequireAcknowledgement,
+        }
+    }
+}
+
+impl RemediationPolicy {
+    /// Action for a severity; discrepancies within tolerance need none
+    pub fn action_for(&self, severity: DiscrepancySeverity) -> Option<RemediationAction> {
+        match severity {
+            DiscrepancySeverity::Ok => None,
+            DiscrepancySeverity::Minor => Some(self.minor),
+            DiscrepancySeverity::Major => Some(self.major),
+            DiscrepancySeverity::Critical => Some(self.critical),
+        }
+    }
+
+    /// Steps for every discrepancy of a report, None when nothing needs doing
+    pub fn plan(&self, report: &ReconciliationReport) -> Option<Remediation> {
+        let steps: Vec<RemediationStep> = report
+            .discrepancies
+            .iter()
+            .filter_map(|discrepancy| {
+                let severity = discrepancy.severity();
+                let action = self.action_for(severity)?;
+                let currency = discrepancy.currency().to_string();
+                Some(RemediationStep {
+                    exchange_amount: report
+                        .exchange_balances
+                        .get(&currency)
+                        .map(|balance| balance.amount),
+                    gap: Some(discrepancy.gap()),
+                    currency,
+                    severity,
+                    action,
+                })
+            })
+            .collect();
+        Remediation::from_steps(steps)
+    }
+}
+
+/// Action taken on one discrepancy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemediationStep {
+    pub currency: String,
+    pub severity: DiscrepancySeverity,
+    pub action: RemediationAction,
+    /// Exchange balance at the time, None when the exchange has none
+    pub exchange_amount: Option<f64>,
+    /// Exchange balance less the expected balance at the time; None in
+    /// audit rows written before gaps were recorded
+    #[serde(default)]
+    pub gap: Option<f64>,
+}
+
+/// State of a remediation in the audit trail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryStatus {
+    /// Every step adopted the exchange balance, nothing left open
+    Adopted,
+    /// Trading is paused on at least one currency
+    Paused,
+    /// Waiting for an operator
+    AwaitingAcknowledgement,
+    /// An operator resolved it and the exchange balances were adopted
+    Resolved,
+}
+
+impl RecoveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Adopted => "adopted",
+            Self::Paused => "paused",
+            Self::AwaitingAcknowledgement => "awaiting_acknowledgement",
+            Self::Resolved => "resolved",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "adopted" => Some(Self::Adopted),
+            "paused" => Some(Self::Paused),
+            "awaiting_acknowledgement" => Some(Self::AwaitingAcknowledgement),
+            "resolved" => Some(Self::Resolved),
+            _ => None,
+        }
+    }
+
+    /// Whether an operator still has to resolve it
+    pub fn is_open(&self) -> bool {
+        matches!(self, Self::Paused | Self::AwaitingAcknowledgement)
+    }
+}
+
+impl fmt::Display for RecoveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Steps taken on the discrepancies of one report
+#[derive(Debug, Clone, PartialEq)]
+pub struct Remediation {
+    pub status: RecoveryStatus,
+    pub steps: Vec<RemediationStep>,
+}
+
+impl Remediation {
+    /// Remediation taking these steps, None when there are none
+    pub fn from_steps(steps: Vec<RemediationStep>) -> Option<Self> {
+        if steps.is_empty() {
+            return None;
+        }
+
+        let has = |action| steps.iter().any(|step| step.action == action);
+        let status = if has(RemediationAction::PauseTrading) {
+            RecoveryStatus::Paused
+        } else if has(RemediationAction::RequireAcknowledgement) {
+            RecoveryStatus::AwaitingAcknowledgement
+        } else {
+            RecoveryStatus::Adopted
+        };
+        Some(Remediation { status, steps })
+    }
+
+    /// Close the gaps this remediation adopts in the local balances
+    ///
+    /// The gap is added rather than the exchange balance copied, so own
+    /// fills recorded since the report are kept. Once resolved, every step
+    /// is adopted.
+    pub fn apply_adoptions(&self, local: &mut Portfolio) {
+        for step in &self.steps {
+            if self.status != RecoveryStatus::Resolved
+                && step.action != RemediationAction::AdoptExchangeBalance
+            {
+                continue;
+            }
+            match (step.exchange_amount, step.gap) {
+                (None, _) => {
+                    local.balances.remove(&step.currency);
+                }
+                (Some(_), Some(gap)) => local.adjust_balance(&step.currency, gap),
+                (Some(amount), None) => local.add_balance(step.currency.clone(), amount),
+            }
+        }
+    }
+
+    /// Currencies this remediation pauses trading on
+    pub fn paused_currencies(&self) -> Vec<&str> {
+        self.steps
+            .iter()
+            .filter(|step| step.action == RemediationAction::PauseTrading)
+            .map(|step| step.currency.as_str())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::exchange::Exchange;
+    use crate::domain::services::portfolio_reconciliation::{Balance, BalanceDiscrepancy};
+
+    fn report() -> ReconciliationReport {
+        let mut report = ReconciliationReport::new(Exchange::Coinbase);
+        for (currency, local, exchange) in [("ETH", 10.0, 9.9), ("USD", 1000.0, 1050.0)] {
+            report.add_discrepancy(BalanceDiscrepancy::Mismatch {
+                currency: currency.to_string(),
+                local,
+                exchange,
+                diff: (local - exchange).abs(),
+            });
+            report.exchange_balances.insert(
+                currency.to_string(),
+                Balance {
+                    currency: currency.to_string(),
+                    amount: exchange,
+                },
+            );
+        }
+        report.add_discrepancy(BalanceDiscrepancy::Missing {
+            currency: "SOL".to_string(),
+            amount: 3.0,
+        });
+        report
+    }
+
+    #[test]
+    fn test_policy_picks_action_per_severity() {
+        let policy = RemediationPolicy {
+            minor: RemediationAction::AdoptExchangeBalance,
+            major: RemediationAction::PauseTrading,
+            critical: RemediationAction::RequireAcknowledgement,
+        };
+        let remediation = policy.plan(&report()).unwrap();
+
+        let actions: Vec<(&str, RemediationAction)> = remediation
+            .steps
+            .iter()
+            .map(|step| (step.currency.as_str(), step.action))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ("ETH", RemediationAction::AdoptExchangeBalance),
+                ("USD", RemediationAction::PauseTrading),
+                ("SOL", RemediationAction::RequireAcknowledgement),
+            ]
+        );
+        assert_eq!(remediation.status, RecoveryStatus::Paused);
+        assert_eq!(remediation.paused_currencies(), vec!["USD"]);
+        assert!(policy
+            .plan(&ReconciliationReport::new(Exchange::Coinbase))
+            .is_none());
+    }
+
+    #[test]
+    fn test_adoptions_update_local_balances() {
+        let policy = RemediationPolicy::default();
+        let mut remediation = policy.plan(&report()).unwrap();
+        assert_eq!(remediation.status, RecoveryStatus::AwaitingAcknowledgement);
+
+        let mut local = Portfolio::new();
+        local.add_balance("ETH".to_string(), 10.0);
+        local.add_balance("USD".to_string(), 1000.0);
+        local.add_balance("SOL".to_string(), 3.0);
+
+        // Only the minor ETH difference is adopted until an operator resolves
+        remediation.apply_adoptions(&mut local);
+        assert!((local.balances["ETH"] - 9.9).abs() < 1e-9);
+        assert_eq!(local.balances["USD"], 1000.0);
+
+        // A fill of our own before the resolution is kept
+        local.adjust_balance("USD", -200.0);
+        remediation.status = RecoveryStatus::Resolved;
+        remediation.apply_adoptions(&mut local);
+        assert_eq!(local.balances["USD"], 850.0);
+        assert!(!local.balances.contains_key("SOL"));
+    }
+}
@@ -1,29 +1,31 @@
+use std::collections::BTreeMap;
 use tracing::{debug, warn};
 
+use crate::config::TradingConfig;
 use crate::domain::entities::symbol_screening::SymbolScreeningResult;
 use crate::domain::services::indicators::Candle;
 use crate::domain::services::screening::{
-    MomentumScoreCalculator, SimpleMomentumCalculator, SimpleSpreadCalculator,
-    SimpleVolatilityCalculator, SimpleVolumeCalculator, SpreadScoreCalculator,
-    VolatilityScoreCalculator, VolumeScoreCalculator,
+    CorrelationScorer, DepthScorer, FundingScorer, ScreeningInput, ScreeningScorer,
+    SimpleMomentumCalculator, SimpleSpreadCalculator, SimpleVolatilityCalculator,
+    SimpleVolumeCalculator, TradeRateScorer,
 };
 
 /// Aggregates all individual scores into overall scalping potential score
+///
+/// The overall score is the weighted mean of the scorers that could score
+/// the symbol, so a scorer without input (no funding on a spot market, no
+/// book on a venue without one) neither helps nor hurts it.
 pub struct ScalpingPotentialAggregator {
-    volatility_calc: SimpleVolatilityCalculator,
-    volume_calc: SimpleVolumeCalculator,
-    spread_calc: SimpleSpreadCalculator,
-    momentum_calc: SimpleMomentumCalculator,
+    scorers: Vec<(Box<dyn ScreeningScorer>, f64)>,
 }
 
 impl Default for ScalpingPotentialAggregator {
     fn default() -> Self {
-        ScalpingPotentialAggregator {
-            volatility_calc: SimpleVolatilityCalculator::default(),
-            volume_calc: SimpleVolumeCalculator::default(),
-            spread_calc: SimpleSpreadCalculator::default(),
-            momentum_calc: SimpleMomentumCalculator::default(),
-        }
+        ScalpingPotentialAggregator::empty()
+            .with_scorer(SimpleVolatilityCalculator::default(), 0.3)
+            .with_scorer(SimpleVolumeCalculator::default(), 0.3)
+            .with_scorer(SimpleSpreadCalculator::default(), 0.2)
+            .with_scorer(SimpleMomentumCalculator::default(), 0.2)
     }
 }
 
@@ -32,7 +34,101 @@ impl ScalpingPotentialAggregator {
         Self::default()
     }
 
-    /// Calculate scalping potential for a symbol
+    /// Aggregator without scorers
+    pub fn empty() -> Self {
+        ScalpingPotentialAggregator {
+            scorers: Vec::new(),
+        }
+    }
+
+    /// Aggregator with every built-in scorer weighted from the configuration
+    ///
+    /// Scorers with a zero weight are left out.
+    pub fn from_config(config: &TradingConfig) -> Self {
+        Self::weighted(
+            config,
+            SimpleVolatilityCalculator::default(),
+            SimpleVolumeCalculator::default(),
+        )
+    }
+
+    /// Aggregator for markets known only by their 24h ticker
+    ///
+    /// Volatility and volume are scaled for a day's range and quote volume
+    /// instead of a live candle's. Momentum and BTC correlation, which need a
+    /// candle history, and depth, which needs a book, are left out.
+    pub fn for_daily_tickers(config: &TradingConfig) -> Self {
+        let mut aggregator = Self::weighted(
+            config,
+            SimpleVolatilityCalculator::daily(),
+            SimpleVolumeCalculator::daily(),
+        );
+        aggregator.scorers.retain(|(scorer, _)| {
+            !matches!(scorer.name(), "momentum" | "btc_correlation" | "depth")
+        });
+        aggregator
+    }
+
+    fn weighted(
+        config: &TradingConfig,
+        volatility: SimpleVolatilityCalculator,
+        volume: SimpleVolumeCalculator,
+    ) -> Self {
+        let weighted: [(Box<dyn ScreeningScorer>, f64); 8] = [
+            (Box::new(volatility), config.screening_volatility_weight),
+            (Box::new(volume), config.screening_volume_weight),
+            (
+                Box::new(SimpleSpreadCalculator::default()),
+                config.screening_spread_weight,
+            ),
+            (
+                Box::new(SimpleMomentumCalculator::default()),
+                config.screening_momentum_weight,
+            ),
+            (
+                Box::new(DepthScorer {
+                    within_bps: config.screening_depth_bps,
+                    ..DepthScorer::default()
+                }),
+                config.screening_depth_weight,
+            ),
+            (
+                Box::new(TradeRateScorer::default()),
+                config.screening_trade_rate_weight,
+            ),
+            (
+                Box::new(FundingScorer::default()),
+                config.screening_funding_weight,
+            ),
+            (
+                Box::new(CorrelationScorer::default()),
+                config.screening_correlation_weight,
+            ),
+        ];
+        ScalpingPotentialAggregator {
+            scorers: weighted
+                .into_iter()
+                .filter(|(_, weight)| *weight > 0.0)
+                .collect(),
+        }
+    }
+
+    /// Add a scorer, replacing any scorer of the same name
+    pub fn with_scorer(mut self, scorer: impl ScreeningScorer + 'static, weight: f64) -> Self {
+        self.scorers.retain(|(s, _)| s.name() != scorer.name());
+        self.scorers.push((Box::new(scorer), weight.max(0.0)));
+        self
+    }
+
+    /// Names and weights of the scorers, in evaluation order
+    pub fn weights(&self) -> Vec<(String, f64)> {
+        self.scorers
+            .iter()
+            .map(|(scorer, weight)| (scorer.name().to_string(), *weight))
+            .collect()
+    }
+
+    /// Calculate scalping potential for a symbol from candles and quote
     ///
     /// # Arguments
     /// * `symbol` - Trading symbol (e.g., "BTC-USD")
@@ -52,78 +148,71 @@ impl ScalpingPotentialAggregator {
         volumes: &[f64],
         bid: f64,
         ask: f64,
+    ) -> SymbolScreeningResult {
+        self.score(
+            symbol,
+            exchange,
+            &ScreeningInput::new(candles, volumes, bid, ask),
+        )
+    }
+
+    /// Calculate scalping potential for a symbol from any available input
+    pub fn score(
+        &self,
+        symbol: String,
+        exchange: String,
+        input: &ScreeningInput,
     ) -> SymbolScreeningResult {
         debug!(
             symbol = %symbol,
             exchange = %exchange,
-            candle_count = candles.len(),
-            volume_count = volumes.len(),
+            candle_count = input.candles.len(),
+            volume_count = input.volumes.len(),
             "Starting scalping potential calculation"
         );
 
-        let volatility_score = if candles.is_empty() {
-            debug!(symbol = %symbol, "No candle data available, volatility_score = 0.0");
-            0.0
-        } else {
-            let last = &candles[candles.len() - 1];
-            let score = self.volatility_calc.calculate(
-                last.high.value(),
-                last.low.value(),
-                last.close.value(),
-            );
+        let mut component_scores = BTreeMap::new();
+        let mut weighted_sum = 0.0;
+        let mut total_weight = 0.0;
+        for (scorer, weight) in &self.scorers {
+            let Some(score) = scorer.score(input) else {
+                debug!(symbol = %symbol, scorer = scorer.name(), "No input, not scored");
+                continue;
+            };
+            if !score.is_finite() {
+                warn!(symbol = %symbol, scorer = scorer.name(), "Non-finite score ignored");
+                continue;
+            }
+            let score = score.clamp(0.0, 1.0);
             debug!(
                 symbol = %symbol,
-                high = last.high.value(),
-                low = last.low.value(),
-                close = last.close.value(),
-                volatility_score = score,
-                "Calculated volatility score"
+                scorer = scorer.name(),
+                score = score,
+                weight = *weight,
+                "Calculated component score"
             );
-            score
+            weighted_sum += weight * score;
+            total_weight += weight;
+            component_scores.insert(scorer.name().to_string(), score);
+        }
+        let overall_score = if total_weight > 0.0 {
+            (weighted_sum / total_weight).clamp(0.0, 1.0)
+        } else {
+            0.0
         };
 
-        let volume_score = self.volume_calc.calculate(volumes);
-        debug!(
-            symbol = %symbol,
-            volume_count = volumes.len(),
-            volume_score = volume_score,
-            "Calculated volume score"
-        );
-
-        let spread_score = self.spread_calc.calculate(bid, ask);
-        debug!(
-            symbol = %symbol,
-            bid = bid,
-            ask = ask,
-            spread_score = spread_score,
-            "Calculated spread score"
-        );
-
-        let momentum_score = self.momentum_calc.calculate(candles);
-        debug!(
-            symbol = %symbol,
-            candle_count = candles.len(),
-            momentum_score = momentum_score,
-            "Calculated momentum score"
-        );
-
-        let result = SymbolScreeningResult::new(
+        let result = SymbolScreeningResult::from_components(
             symbol.clone(),
             exchange.clone(),
-            volatility_score,
-            volume_score,
-            spread_score,
-            momentum_score,
+            component_scores,
+            overall_score,
         );
 
         debug!(
             symbol = %symbol,
             exchange = %exchange,
             overall_score = result.overall_score,
-            volatility_score = volatility_score,
-            volume_score = volume_score,
-            spread_score = spread_score,
-            momentum_score = momentum_score,
+            components = ?result.component_scores,
             recommendation = ?result.recommendation,
             "Completed scalping potential calculation"
         );
@@ -181,12 +270,21 @@ mod tests {
 
     #[test]
     fn test_scalping_score_aggregation_with_known_inputs() {
-        let mut agg = ScalpingPotentialAggregator::new();
-
         // Override calculators with fixed values for testing
-        agg.volatility_calc.max_volatility_range_percent = 1.0;
-        agg.volume_calc.max_volume = 1.0;
-        agg.spread_calc.max_spread_percent = 1.0;
+        let agg = ScalpingPotentialAggregator::new()
+            .with_scorer(
+                SimpleVolatilityCalculator {
+                    max_volatility_range_percent: 1.0,
+                },
+                0.3,
+            )
+            .with_scorer(SimpleVolumeCalculator { max_volume: 1.0 }, 0.3)
+            .with_scorer(
+                SimpleSpreadCalculator {
+                    max_spread_percent: 1.0,
+                },
+                0.2,
+            );
 
         let candles = vec![Candle::new(100.0, 100.5, 99.5, 100.0, 1.0).unwrap()];
         let volumes = vec![1.0];
@@ -207,6 +305,115 @@ mod tests {
         assert!(result.momentum_score >= 0.0);
     }
 
+    #[test]
+    fn test_default_weights_match_the_fixed_formula() {
+        let agg = ScalpingPotentialAggregator::new();
+        let candles = vec![Candle::new(100.0, 101.0, 99.0, 100.5, 1000.0).unwrap()];
+        let result = agg.calculate(
+            "TEST-USD".to_string(),
+            "test".to_string(),
+            &candles,
+            &[500_000.0],
+            100.0,
+            100.1,
+        );
+
+        let fixed = SymbolScreeningResult::new(
+            "TEST-USD".to_string(),
+            "test".to_string(),
+            result.volatility_score,
+            result.volume_score,
+            result.spread_score,
+            result.momentum_score,
+        );
+        assert!((result.overall_score - fixed.overall_score).abs() < 1e-12);
+        assert_eq!(result.component_scores.len(), 4);
+    }
+
+    /// Scorer returning a fixed score, or nothing
+    struct Fixed(&'static str, Option<f64>);
+
+    impl ScreeningScorer for Fixed {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn score(&self, _input: &ScreeningInput) -> Option<f64> {
+            self.1
+        }
+    }
+
+    #[test]
+    fn test_custom_scorers_and_missing_inputs() {
+        let agg = ScalpingPotentialAggregator::empty()
+            .with_scorer(Fixed("a", Some(0.9)), 3.0)
+            .with_scorer(Fixed("b", Some(0.5)), 1.0)
+            .with_scorer(Fixed("funding", None), 4.0);
+        let result = agg.score(
+            "TEST-USD".to_string(),
+            "test".to_string(),
+            &ScreeningInput::new(&[], &[], 0.0, 0.0),
+        );
+
+        // The unscored component's weight is left out: (3*0.9 + 0.5) / 4
+        assert!((result.overall_score - 0.8).abs() < 1e-12);
+        assert_eq!(result.component_scores.get("a"), Some(&0.9));
+        assert!(!result.component_scores.contains_key("funding"));
+        assert_eq!(result.volatility_score, 0.0);
+
+        // Same name replaces the scorer
+        let agg = agg.with_scorer(Fixed("a", Some(0.1)), 1.0);
+        assert_eq!(agg.weights().len(), 3);
+        assert_eq!(agg.weights()[2], ("a".to_string(), 1.0));
+    }
+
+    #[test]
+    fn test_from_config_skips_zero_weights() {
+        let mut config = TradingConfig::default();
+        config.screening_funding_weight = 0.1;
+        let agg = ScalpingPotentialAggregator::from_config(&config);
+        let names: Vec<String> = agg.weights().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(
+            names,
+            vec!["volatility", "volume", "spread", "momentum", "funding"]
+        );
+    }
+
+    #[test]
+    fn test_daily_tickers_are_scored_on_a_daily_scale() {
+        let mut config = TradingConfig::default();
+        config.screening_depth_weight = 0.1;
+        config.screening_correlation_weight = 0.1;
+        let agg = ScalpingPotentialAggregator::for_daily_tickers(&config);
+        let names: Vec<String> = agg.weights().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["volatility", "volume", "spread"]);
+
+        // A 4% day on 20M of quote volume is far from the top of either scale
+        let candles = vec![Candle::new(100.0, 102.0, 98.0, 101.0, 20_000_000.0).unwrap()];
+        let daily = agg.calculate(
+            "TEST-USD".to_string(),
+            "test".to_string(),
+            &candles,
+            &[20_000_000.0],
+            100.0,
+            100.01,
+        );
+        assert!((daily.volatility_score - 0.4 / 1.01).abs() < 1e-9);
+        assert!((daily.volume_score - 0.2).abs() < 1e-9);
+
+        // The live scales would saturate on the same ticker
+        let live = ScalpingPotentialAggregator::from_config(&config).calculate(
+            "TEST-USD".to_string(),
+            "test".to_string(),
+            &candles,
+            &[20_000_000.0],
+            100.0,
+            100.01,
+        );
+        assert_eq!(live.volume_score, 1.0);
+        assert!(live.volatility_score > daily.volatility_score);
+    }
+
     #[test]
     fn test_ranking_multiple_symbols_by_score() {
         let results = vec![
@@ -0,0 +1,130 @@
+use crate::domain::services::indicators::Candle;
+use crate::domain::services::screening::scorer::{ScreeningInput, ScreeningScorer};
+
+/// Scores how independently a symbol moves from BTC
+///
+/// Most of the market follows BTC, so a book of highly correlated symbols
+/// is one position in disguise. Score is 1 - |correlation| of close-to-close
+/// returns over the most recent candles both series have.
+pub struct CorrelationScorer {
+    /// Returns compared, newest first
+    pub lookback: usize,
+    /// Fewer overlapping returns than this are not scored
+    pub min_returns: usize,
+}
+
+impl Default for CorrelationScorer {
+    fn default() -> Self {
+        CorrelationScorer {
+            lookback: 60,
+            min_returns: 10,
+        }
+    }
+}
+
+impl CorrelationScorer {
+    fn returns(candles: &[Candle]) -> Vec<f64> {
+        candles
+            .windows(2)
+            .map(|pair| pair[1].close.value() / pair[0].close.value() - 1.0)
+            .collect()
+    }
+
+    /// Pearson correlation, None when either series is flat
+    fn correlation(a: &[f64], b: &[f64]) -> Option<f64> {
+        let n = a.len() as f64;
+        let mean_a = a.iter().sum::<f64>() / n;
+        let mean_b = b.iter().sum::<f64>() / n;
+        let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+        for (x, y) in a.iter().zip(b) {
+            cov += (x - mean_a) * (y - mean_b);
+            var_a += (x - mean_a).powi(2);
+            var_b += (y - mean_b).powi(2);
+        }
+        if var_a <= f64::EPSILON || var_b <= f64::EPSILON {
+            return None;
+        }
+        Some(cov / (var_a * var_b).sqrt())
+    }
+}
+
+impl ScreeningScorer for CorrelationScorer {
+    fn name(&self) -> &str {
+        "btc_correlation"
+    }
+
+    fn score(&self, input: &ScreeningInput) -> Option<f64> {
+        let symbol = Self::returns(input.candles);
+        let benchmark = Self::returns(input.benchmark?);
+        let n = symbol.len().min(benchmark.len()).min(self.lookback);
+        if n < self.min_returns.max(2) {
+            return None;
+        }
+        let correlation = Self::correlation(
+            &symbol[symbol.len() - n..],
+            &benchmark[benchmark.len() - n..],
+        )?;
+        Some((1.0 - correlation.abs()).clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candles(closes: &[f64]) -> Vec<Candle> {
+        closes
+            .iter()
+            .map(|c| Candle::new(*c, *c, *c, *c, 1.0).unwrap())
+            .collect()
+    }
+
+    fn zigzag(base: f64, step: f64, len: usize) -> Vec<f64> {
+        (0..len)
+            .map(|i| base + if i % 2 == 0 { 0.0 } else { step })
+            .collect()
+    }
+
+    #[test]
+    fn test_correlated_symbol_scores_low() {
+        let scorer = CorrelationScorer::default();
+        let btc = candles(&zigzag(50_000.0, 500.0, 20));
+        let follower = candles(&zigzag(100.0, 1.0, 20));
+        let contrarian = candles(&zigzag(101.0, -1.0, 20));
+
+        let mut input = ScreeningInput::new(&follower, &[], 100.0, 100.1);
+        input.benchmark = Some(&btc);
+        assert!(scorer.score(&input).unwrap() < 0.01);
+
+        // Moving against BTC is as much a BTC bet as moving with it
+        input.candles = &contrarian;
+        assert!(scorer.score(&input).unwrap() < 0.01);
+    }
+
+    #[test]
+    fn test_independent_symbol_scores_high() {
+        let scorer = CorrelationScorer::default();
+        let btc = candles(&zigzag(50_000.0, 500.0, 21));
+        // Moves every other candle of BTC's swing, uncorrelated with it
+        let closes: Vec<f64> = (0..21)
+            .map(|i| 100.0 + if (i / 2) % 2 == 0 { 0.0 } else { 1.0 })
+            .collect();
+        let independent = candles(&closes);
+
+        let mut input = ScreeningInput::new(&independent, &[], 100.0, 100.1);
+        input.benchmark = Some(&btc);
+        assert!(scorer.score(&input).unwrap() > 0.9);
+    }
+
+    #[test]
+    fn test_short_or_missing_history_is_not_scored() {
+        let scorer = CorrelationScorer::default();
+        let btc = candles(&zigzag(50_000.0, 500.0, 5));
+        let symbol = candles(&zigzag(100.0, 1.0, 30));
+
+        let mut input = ScreeningInput::new(&symbol, &[], 100.0, 100.1);
+        assert_eq!(scorer.score(&input), None);
+        input.benchmark = Some(&btc);
+        assert_eq!(scorer.score(&input), None);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::domain::services::screening::scorer::{ScreeningInput, ScreeningScorer};
+
+/// Scores the notional resting within a band around the mid price
+///
+/// The thinner side counts, since a scalp has to get out as well as in.
+pub struct DepthScorer {
+    /// Half-width of the band around the mid, in basis points
+    pub within_bps: f64,
+    /// Notional per side that scores 1.0
+    pub target_notional: f64,
+}
+
+impl Default for DepthScorer {
+    fn default() -> Self {
+        DepthScorer {
+            within_bps: 10.0,
+            target_notional: 100_000.0,
+        }
+    }
+}
+
+impl ScreeningScorer for DepthScorer {
+    fn name(&self) -> &str {
+        "depth"
+    }
+
+    fn score(&self, input: &ScreeningInput) -> Option<f64> {
+        let depth = input.depth?;
+        if input.bid <= 0.0 || input.ask < input.bid {
+            return None;
+        }
+        let mid = (input.bid + input.ask) / 2.0;
+        let band = mid * self.within_bps / 10_000.0;
+
+        let notional = |levels: &[(f64, f64)]| -> f64 {
+            levels
+                .iter()
+                .filter(|(price, _)| (price - mid).abs() <= band)
+                .map(|(price, quantity)| price * quantity)
+                .sum()
+        };
+        let thinner = notional(&depth.bids).min(notional(&depth.asks));
+
+        Some((thinner / self.target_notional).clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::screening::scorer::OrderBookDepth;
+
+    #[test]
+    fn test_depth_counts_the_thinner_side_within_the_band() {
+        let scorer = DepthScorer::default();
+        let depth = OrderBookDepth {
+            bids: vec![(99.95, 500.0), (99.0, 10_000.0)], // Second level is 100 bps away
+            asks: vec![(100.05, 1_000.0)],
+        };
+        let mut input = ScreeningInput::new(&[], &[], 99.95, 100.05);
+        input.depth = Some(&depth);
+
+        let score = scorer.score(&input).unwrap();
+        assert!((score - 99.95 * 500.0 / 100_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_depth_without_book_is_not_scored() {
+        let scorer = DepthScorer::default();
+        assert_eq!(
+            scorer.score(&ScreeningInput::new(&[], &[], 100.0, 100.1)),
+            None
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use crate::domain::services::screening::scorer::{ScreeningInput, ScreeningScorer};
+
+/// Scores the carrying cost of a perpetual market
+///
+/// A scalp holds either side, so the sign of the funding does not matter;
+/// a rate near zero costs nothing to hold while a large one eats the edge.
+/// Spot markets have no funding and are not scored.
+pub struct FundingScorer {
+    /// Absolute hourly rate that scores 0.0
+    pub max_hourly_rate: f64,
+}
+
+impl Default for FundingScorer {
+    fn default() -> Self {
+        FundingScorer {
+            max_hourly_rate: 0.0001, // 0.01% an hour, about 88% a year
+        }
+    }
+}
+
+impl ScreeningScorer for FundingScorer {
+    fn name(&self) -> &str {
+        "funding"
+    }
+
+    fn score(&self, input: &ScreeningInput) -> Option<f64> {
+        let rate = input.funding_rate.filter(|r| r.is_finite())?;
+        Some((1.0 - rate.abs() / self.max_hourly_rate).clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_funding_score_ignores_sign() {
+        let scorer = FundingScorer::default();
+        let mut input = ScreeningInput::new(&[], &[], 100.0, 100.1);
+        assert_eq!(scorer.score(&input), None);
+
+        input.funding_rate = Some(0.0);
+        assert_eq!(scorer.score(&input), Some(1.0));
+
+        input.funding_rate = Some(0.000025);
+        let long_pays = scorer.score(&input).unwrap();
+        input.funding_rate = Some(-0.000025);
+        assert_eq!(scorer.score(&input), Some(long_pays));
+        assert!((long_pays - 0.75).abs() < 1e-9);
+
+        input.funding_rate = Some(0.001);
+        assert_eq!(scorer.score(&input), Some(0.0));
+    }
+}
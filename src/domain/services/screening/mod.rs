@@ -1,13 +1,23 @@
 pub mod aggregator;
+pub mod correlation;
+pub mod depth;
+pub mod funding;
 pub mod momentum;
 pub mod score_calculator;
+pub mod scorer;
 pub mod spread;
+pub mod trade_rate;
 pub mod volatility;
 pub mod volume;
 
 pub use aggregator::ScalpingPotentialAggregator;
+pub use correlation::CorrelationScorer;
+pub use depth::DepthScorer;
+pub use funding::FundingScorer;
 pub use momentum::{MomentumScoreCalculator, SimpleMomentumCalculator};
 pub use score_calculator::ScoreCalculator;
+pub use scorer::{OrderBookDepth, ScreeningInput, ScreeningScorer};
 pub use spread::{SimpleSpreadCalculator, SpreadScoreCalculator};
+pub use trade_rate::TradeRateScorer;
 pub use volatility::{SimpleVolatilityCalculator, VolatilityScoreCalculator};
 pub use volume::{SimpleVolumeCalculator, VolumeScoreCalculator};
@@ -1,4 +1,5 @@
 use crate::domain::services::indicators::{Candle, Indicator, MACD, RSI};
+use crate::domain::services::screening::scorer::{ScreeningInput, ScreeningScorer};
 
 /// Calculates momentum score from multiple technical indicators
 pub trait MomentumScoreCalculator {
@@ -97,6 +98,16 @@ impl MomentumScoreCalculator for SimpleMomentumCalculator {
     }
 }
 
+impl ScreeningScorer for SimpleMomentumCalculator {
+    fn name(&self) -> &str {
+        "momentum"
+    }
+
+    fn score(&self, input: &ScreeningInput) -> Option<f64> {
+        Some(MomentumScoreCalculator::calculate(self, input.candles))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
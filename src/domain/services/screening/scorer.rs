@@ -0,0 +1,54 @@
+use crate::domain::services::indicators::Candle;
+
+/// Resting liquidity near the top of the book as (price, quantity) levels
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderBookDepth {
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+/// Market data a scorer may draw on
+///
+/// Only candles, volumes and the quote are always present; the rest depends
+/// on what the venue publishes.
+#[derive(Debug, Clone, Copy)]
+pub struct ScreeningInput<'a> {
+    pub candles: &'a [Candle],
+    pub volumes: &'a [f64],
+    pub bid: f64,
+    pub ask: f64,
+    pub depth: Option<&'a OrderBookDepth>,
+    pub trades_per_minute: Option<f64>,
+    /// Hourly funding rate of a perpetual market
+    pub funding_rate: Option<f64>,
+    /// Candles of the benchmark (BTC) over the same period
+    pub benchmark: Option<&'a [Candle]>,
+}
+
+impl<'a> ScreeningInput<'a> {
+    /// Input with candles, volumes and quote only
+    pub fn new(candles: &'a [Candle], volumes: &'a [f64], bid: f64, ask: f64) -> Self {
+        ScreeningInput {
+            candles,
+            volumes,
+            bid,
+            ask,
+            depth: None,
+            trades_per_minute: None,
+            funding_rate: None,
+            benchmark: None,
+        }
+    }
+}
+
+/// One component of the screening score
+///
+/// Scores are in [0.0, 1.0], higher being better for scalping. A scorer
+/// returns None when its input is missing, and the aggregator then leaves
+/// its weight out rather than counting it as a zero.
+pub trait ScreeningScorer: Send + Sync {
+    /// Key of the component in `SymbolScreeningResult::component_scores`
+    fn name(&self) -> &str;
+
+    fn score(&self, input: &ScreeningInput) -> Option<f64>;
+}
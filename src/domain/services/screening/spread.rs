@@ -1,3 +1,5 @@
+use crate::domain::services::screening::scorer::{ScreeningInput, ScreeningScorer};
+
 /// Calculates spread score from bid-ask data
 pub trait SpreadScoreCalculator {
     /// Calculate spread score from bid and ask prices
@@ -36,6 +38,16 @@ impl SpreadScoreCalculator for SimpleSpreadCalculator {
     }
 }
 
+impl ScreeningScorer for SimpleSpreadCalculator {
+    fn name(&self) -> &str {
+        "spread"
+    }
+
+    fn score(&self, input: &ScreeningInput) -> Option<f64> {
+        Some(SpreadScoreCalculator::calculate(self, input.bid, input.ask))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
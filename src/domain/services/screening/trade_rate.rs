@@ -0,0 +1,44 @@
+use crate::domain::services::screening::scorer::{ScreeningInput, ScreeningScorer};
+
+/// Scores how often the market trades; busy markets fill scalps quickly
+pub struct TradeRateScorer {
+    /// Trades per minute that score 1.0
+    pub max_trades_per_minute: f64,
+}
+
+impl Default for TradeRateScorer {
+    fn default() -> Self {
+        TradeRateScorer {
+            max_trades_per_minute: 60.0, // One trade a second
+        }
+    }
+}
+
+impl ScreeningScorer for TradeRateScorer {
+    fn name(&self) -> &str {
+        "trade_rate"
+    }
+
+    fn score(&self, input: &ScreeningInput) -> Option<f64> {
+        let rate = input.trades_per_minute.filter(|r| r.is_finite())?;
+        Some((rate / self.max_trades_per_minute).clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trade_rate_score() {
+        let scorer = TradeRateScorer::default();
+        let mut input = ScreeningInput::new(&[], &[], 100.0, 100.1);
+        assert_eq!(scorer.score(&input), None);
+
+        input.trades_per_minute = Some(15.0);
+        assert_eq!(scorer.score(&input), Some(0.25));
+
+        input.trades_per_minute = Some(600.0);
+        assert_eq!(scorer.score(&input), Some(1.0));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::domain::services::screening::scorer::{ScreeningInput, ScreeningScorer};
+
 /// Calculates volatility score based on price range in candles
 pub trait VolatilityScoreCalculator {
     /// Calculate volatility score from candle data
@@ -19,6 +21,17 @@ impl Default for SimpleVolatilityCalculator {
     }
 }
 
+impl SimpleVolatilityCalculator {
+    /// Calculator for a single 24h candle, where a 10% range is "high"
+    ///
+    /// The default scale suits the short candles of the live feed.
+    pub fn daily() -> Self {
+        SimpleVolatilityCalculator {
+            max_volatility_range_percent: 10.0,
+        }
+    }
+}
+
 impl VolatilityScoreCalculator for SimpleVolatilityCalculator {
     fn calculate(&self, high: f64, low: f64, close: f64) -> f64 {
         if close <= 0.0 || low < 0.0 || high < low {
@@ -34,6 +47,24 @@ impl VolatilityScoreCalculator for SimpleVolatilityCalculator {
     }
 }
 
+/// Scores the range of the latest candle, zero without candles
+impl ScreeningScorer for SimpleVolatilityCalculator {
+    fn name(&self) -> &str {
+        "volatility"
+    }
+
+    fn score(&self, input: &ScreeningInput) -> Option<f64> {
+        Some(input.candles.last().map_or(0.0, |last| {
+            VolatilityScoreCalculator::calculate(
+                self,
+                last.high.value(),
+                last.low.value(),
+                last.close.value(),
+            )
+        }))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,6 +115,15 @@ mod tests {
         assert_eq!(score, 1.0);
     }
 
+    #[test]
+    fn test_daily_scale_is_wider_than_intraday() {
+        let intraday = SimpleVolatilityCalculator::default();
+        let daily = SimpleVolatilityCalculator::daily();
+        // A 6% day is ordinary, a 6% intraday candle is extreme
+        assert_eq!(intraday.calculate(103.0, 97.0, 100.0), 1.0);
+        assert!((daily.calculate(103.0, 97.0, 100.0) - 0.6).abs() < 1e-9);
+    }
+
     #[test]
     fn test_volatility_score_typical_market() {
         let calc = SimpleVolatilityCalculator::default();
@@ -1,3 +1,5 @@
+use crate::domain::services::screening::scorer::{ScreeningInput, ScreeningScorer};
+
 /// Calculates volume score based on recent trading volumes
 pub trait VolumeScoreCalculator {
     /// Calculate volume score from volumes
@@ -19,6 +21,17 @@ impl Default for SimpleVolumeCalculator {
     }
 }
 
+impl SimpleVolumeCalculator {
+    /// Calculator for 24h quote volume, where 100M is "high volume"
+    ///
+    /// The default scale suits the short candles of the live feed.
+    pub fn daily() -> Self {
+        SimpleVolumeCalculator {
+            max_volume: 100_000_000.0,
+        }
+    }
+}
+
 impl VolumeScoreCalculator for SimpleVolumeCalculator {
     fn calculate(&self, volumes: &[f64]) -> f64 {
         if volumes.is_empty() {
@@ -39,6 +52,16 @@ impl VolumeScoreCalculator for SimpleVolumeCalculator {
     }
 }
 
+impl ScreeningScorer for SimpleVolumeCalculator {
+    fn name(&self) -> &str {
+        "volume"
+    }
+
+    fn score(&self, input: &ScreeningInput) -> Option<f64> {
+        Some(VolumeScoreCalculator::calculate(self, input.volumes))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
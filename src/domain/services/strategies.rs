@@ -1,22 +1,85 @@
 use crate::domain::services::indicators::{
     BollingerBands, Candle, Indicator, StochasticOscillator, EMA, MACD, RSI, VWAP,
 };
+use crate::domain::services::market_regime::MarketRegime;
+use crate::domain::services::streaming_indicators::{
+    IndicatorReading, IndicatorSpec, IndicatorValues,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
 pub enum Signal {
     Buy,
     Sell,
+    #[default]
     Hold,
 }
 
-#[derive(Debug, Clone)]
+/// One strategy's contribution to a combined signal
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StrategyVote {
+    pub strategy: String,
+    pub signal: Signal,
+    pub confidence: f64,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct TradingSignal {
     pub signal: Signal,
     pub confidence: f64, // 0.0 to 1.0
+    /// Votes behind a combined signal; empty for a single strategy's signal
+    pub votes: Vec<StrategyVote>,
+    /// Indicator readings the decision was based on, e.g. `rsi_7`
+    pub indicators: BTreeMap<String, f64>,
+    /// Market regime the votes were weighted for, if it was known
+    pub regime: Option<MarketRegime>,
+}
+
+impl TradingSignal {
+    pub fn new(signal: Signal, confidence: f64) -> Self {
+        Self {
+            signal,
+            confidence,
+            ..Default::default()
+        }
+    }
+
+    fn with_indicators<const N: usize>(mut self, readings: [(String, f64); N]) -> Self {
+        self.indicators.extend(readings);
+        self
+    }
+
+    /// Votes, indicator readings and regime as JSON, as stored with trades
+    pub fn explanation(&self) -> serde_json::Value {
+        serde_json::json!({
+            "votes": self.votes,
+            "indicators": self.indicators,
+            "regime": self.regime,
+        })
+    }
 }
 
 pub trait Strategy {
     fn generate_signal(&self, candles: &[Candle]) -> Option<TradingSignal>;
+
+    /// Streaming indicators read by `generate_signal_streaming`
+    fn indicators(&self) -> Vec<IndicatorSpec> {
+        Vec::new()
+    }
+
+    /// Generate a signal from streaming indicator readings maintained by the
+    /// `CandleBuilder` instead of recomputing indicators over `candles`
+    ///
+    /// Defaults to `generate_signal`, which is also used when a reading is missing.
+    fn generate_signal_streaming(
+        &self,
+        candles: &[Candle],
+        _values: &IndicatorValues,
+    ) -> Option<TradingSignal> {
+        self.generate_signal(candles)
+    }
 }
 
 pub struct FastScalping {
@@ -31,6 +94,20 @@ impl FastScalping {
             ema_long: EMA::new(5),  // Reduced from 10 for faster signals
         }
     }
+
+    fn decide(&self, last_short: f64, last_long: f64) -> TradingSignal {
+        let signal = if last_short > last_long {
+            TradingSignal::new(Signal::Buy, 0.8)
+        } else if last_short < last_long {
+            TradingSignal::new(Signal::Sell, 0.8)
+        } else {
+            TradingSignal::new(Signal::Hold, 0.5)
+        };
+        signal.with_indicators([
+            (format!("ema_{}", self.ema_short.period), last_short),
+            (format!("ema_{}", self.ema_long.period), last_long),
+        ])
+    }
 }
 
 impl Strategy for FastScalping {
@@ -44,21 +121,30 @@ impl Strategy for FastScalping {
         let last_short = *short_ema.last()?;
         let last_long = *long_ema.last()?;
 
-        if last_short > last_long {
-            Some(TradingSignal {
-                signal: Signal::Buy,
-                confidence: 0.8,
-            })
-        } else if last_short < last_long {
-            Some(TradingSignal {
-                signal: Signal::Sell,
-                confidence: 0.8,
-            })
-        } else {
-            Some(TradingSignal {
-                signal: Signal::Hold,
-                confidence: 0.5,
-            })
+        Some(self.decide(last_short, last_long))
+    }
+
+    fn indicators(&self) -> Vec<IndicatorSpec> {
+        vec![
+            IndicatorSpec::Ema(self.ema_short.period),
+            IndicatorSpec::Ema(self.ema_long.period),
+        ]
+    }
+
+    fn generate_signal_streaming(
+        &self,
+        candles: &[Candle],
+        values: &IndicatorValues,
+    ) -> Option<TradingSignal> {
+        if candles.len() < 10 {
+            return None;
+        }
+        match (
+            values.value(&IndicatorSpec::Ema(self.ema_short.period)),
+            values.value(&IndicatorSpec::Ema(self.ema_long.period)),
+        ) {
+            (Some(last_short), Some(last_long)) => Some(self.decide(last_short, last_long)),
+            _ => self.generate_signal(candles),
         }
     }
 }
@@ -66,6 +152,8 @@ impl Strategy for FastScalping {
 pub struct MomentumScalping {
     pub rsi: RSI,
     pub macd: MACD,
+    pub rsi_oversold: f64,
+    pub rsi_overbought: f64,
 }
 
 impl MomentumScalping {
@@ -73,8 +161,38 @@ impl MomentumScalping {
         MomentumScalping {
             rsi: RSI::new(7),          // Reduced from 14 for faster signals with 10s candles
             macd: MACD::new(6, 13, 5), // Reduced from (12, 26, 9) for faster signals
+            rsi_oversold: 30.0,
+            rsi_overbought: 70.0,
         }
     }
+
+    fn macd_spec(&self) -> IndicatorSpec {
+        IndicatorSpec::Macd(
+            self.macd.fast_period,
+            self.macd.slow_period,
+            self.macd.signal_period,
+        )
+    }
+
+    fn decide(&self, last_rsi: f64, last_macd: f64) -> TradingSignal {
+        let signal = if last_rsi < self.rsi_oversold && last_macd > 0.0 {
+            TradingSignal::new(Signal::Buy, 0.9)
+        } else if last_rsi > self.rsi_overbought && last_macd < 0.0 {
+            TradingSignal::new(Signal::Sell, 0.9)
+        } else {
+            TradingSignal::new(Signal::Hold, 0.5)
+        };
+        signal.with_indicators([
+            (format!("rsi_{}", self.rsi.period), last_rsi),
+            (
+                format!(
+                    "macd_{}_{}_{}",
+                    self.macd.fast_period, self.macd.slow_period, self.macd.signal_period
+                ),
+                last_macd,
+            ),
+        ])
+    }
 }
 
 impl Strategy for MomentumScalping {
@@ -89,25 +207,27 @@ impl Strategy for MomentumScalping {
         let last_rsi = *rsi_values.last()?;
         let last_macd = *macd_values.last()?;
 
-        let mut confidence = 0.5;
-
-        if last_rsi < 30.0 && last_macd > 0.0 {
-            confidence = 0.9;
-            Some(TradingSignal {
-                signal: Signal::Buy,
-                confidence,
-            })
-        } else if last_rsi > 70.0 && last_macd < 0.0 {
-            confidence = 0.9;
-            Some(TradingSignal {
-                signal: Signal::Sell,
-                confidence,
-            })
-        } else {
-            Some(TradingSignal {
-                signal: Signal::Hold,
-                confidence,
-            })
+        Some(self.decide(last_rsi, last_macd))
+    }
+
+    fn indicators(&self) -> Vec<IndicatorSpec> {
+        vec![IndicatorSpec::Rsi(self.rsi.period), self.macd_spec()]
+    }
+
+    fn generate_signal_streaming(
+        &self,
+        candles: &[Candle],
+        values: &IndicatorValues,
+    ) -> Option<TradingSignal> {
+        if candles.len() < 13 {
+            return None;
+        }
+        match (
+            values.value(&IndicatorSpec::Rsi(self.rsi.period)),
+            values.value(&self.macd_spec()),
+        ) {
+            (Some(last_rsi), Some(last_macd)) => Some(self.decide(last_rsi, last_macd)),
+            _ => self.generate_signal(candles),
         }
     }
 }
@@ -116,6 +236,8 @@ pub struct ConservativeScalping {
     pub bollinger: BollingerBands,
     pub stoch: StochasticOscillator,
     pub vwap: VWAP,
+    pub stoch_oversold: f64,
+    pub stoch_overbought: f64,
 }
 
 impl ConservativeScalping {
@@ -124,8 +246,52 @@ impl ConservativeScalping {
             bollinger: BollingerBands::new(10, 2.0), // Reduced from 20 for faster signals with 10s candles
             stoch: StochasticOscillator::new(7, 3), // Reduced k_period from 14, d_period stays at 3
             vwap: VWAP,
+            stoch_oversold: 20.0,
+            stoch_overbought: 80.0,
         }
     }
+
+    fn bollinger_spec(&self) -> IndicatorSpec {
+        IndicatorSpec::Bollinger(self.bollinger.period, self.bollinger.std_dev)
+    }
+
+    fn stoch_spec(&self) -> IndicatorSpec {
+        IndicatorSpec::Stochastic(self.stoch.k_period, self.stoch.d_period)
+    }
+
+    fn decide(
+        &self,
+        last_close: f64,
+        last_bb_upper: f64,
+        last_bb_lower: f64,
+        last_stoch: f64,
+        last_vwap: f64,
+    ) -> TradingSignal {
+        let signal = if last_close < last_bb_lower
+            && last_stoch < self.stoch_oversold
+            && last_close < last_vwap
+        {
+            TradingSignal::new(Signal::Buy, 0.7)
+        } else if last_close > last_bb_upper
+            && last_stoch > self.stoch_overbought
+            && last_close > last_vwap
+        {
+            TradingSignal::new(Signal::Sell, 0.7)
+        } else {
+            TradingSignal::new(Signal::Hold, 0.6)
+        };
+        let period = self.bollinger.period;
+        signal.with_indicators([
+            ("close".to_string(), last_close),
+            (format!("bb_upper_{}", period), last_bb_upper),
+            (format!("bb_lower_{}", period), last_bb_lower),
+            (
+                format!("stoch_{}_{}", self.stoch.k_period, self.stoch.d_period),
+                last_stoch,
+            ),
+            ("vwap".to_string(), last_vwap),
+        ])
+    }
 }
 
 impl Strategy for ConservativeScalping {
@@ -144,25 +310,48 @@ impl Strategy for ConservativeScalping {
         let last_stoch = *stoch_values.last()?;
         let last_vwap = *vwap_values.last()?;
 
-        let mut confidence = 0.6;
-
-        if last_close < last_bb_lower && last_stoch < 20.0 && last_close < last_vwap {
-            confidence = 0.7;
-            Some(TradingSignal {
-                signal: Signal::Buy,
-                confidence,
-            })
-        } else if last_close > last_bb_upper && last_stoch > 80.0 && last_close > last_vwap {
-            confidence = 0.7;
-            Some(TradingSignal {
-                signal: Signal::Sell,
-                confidence,
-            })
-        } else {
-            Some(TradingSignal {
-                signal: Signal::Hold,
-                confidence,
-            })
+        Some(self.decide(
+            last_close,
+            last_bb_upper,
+            last_bb_lower,
+            last_stoch,
+            last_vwap,
+        ))
+    }
+
+    fn indicators(&self) -> Vec<IndicatorSpec> {
+        vec![
+            self.bollinger_spec(),
+            self.stoch_spec(),
+            IndicatorSpec::Vwap,
+        ]
+    }
+
+    fn generate_signal_streaming(
+        &self,
+        candles: &[Candle],
+        values: &IndicatorValues,
+    ) -> Option<TradingSignal> {
+        if candles.len() < 10 {
+            return None;
+        }
+        match (
+            values.get(&self.bollinger_spec()),
+            values.value(&self.stoch_spec()),
+            values.value(&IndicatorSpec::Vwap),
+        ) {
+            (
+                Some(IndicatorReading::Bands { upper, lower, .. }),
+                Some(last_stoch),
+                Some(last_vwap),
+            ) => Some(self.decide(
+                candles.last()?.close.value(),
+                upper,
+                lower,
+                last_stoch,
+                last_vwap,
+            )),
+            _ => self.generate_signal(candles),
         }
     }
 }
@@ -171,6 +360,8 @@ pub struct SignalCombiner {
     pub strategies: Vec<Box<dyn Strategy + Send + Sync>>,
     pub strategy_names: Vec<String>,
     pub weights: Vec<f64>,
+    /// Per regime weight multipliers aligned with `strategies`; 0.0 disables a strategy
+    pub regime_multipliers: HashMap<MarketRegime, Vec<f64>>,
 }
 
 impl SignalCombiner {
@@ -197,22 +388,105 @@ impl SignalCombiner {
             strategies: strategy_instances,
             strategy_names,
             weights,
+            regime_multipliers: HashMap::new(),
         })
     }
 
+    /// Scale strategy weights while `regime` is active
+    ///
+    /// Strategies not listed keep their weight; a multiplier of 0.0 disables one.
+    pub fn set_regime_multipliers(
+        &mut self,
+        regime: MarketRegime,
+        multipliers: &BTreeMap<String, f64>,
+    ) -> Result<(), String> {
+        let mut aligned = vec![1.0; self.strategies.len()];
+        for (name, &multiplier) in multipliers {
+            let index = self
+                .strategy_names
+                .iter()
+                .position(|n| n == name)
+                .ok_or_else(|| {
+                    format!("unknown strategy '{}' for regime {}", name, regime.as_str())
+                })?;
+            if !(multiplier >= 0.0 && multiplier.is_finite()) {
+                return Err(format!(
+                    "multiplier for '{}' in regime {} must be zero or positive",
+                    name,
+                    regime.as_str()
+                ));
+            }
+            aligned[index] = multiplier;
+        }
+        self.regime_multipliers.insert(regime, aligned);
+        Ok(())
+    }
+
     pub fn combine_signals(&self, candles: &[Candle]) -> Option<TradingSignal> {
+        self.combine_with(None, |strategy| strategy.generate_signal(candles))
+    }
+
+    /// Combine signals computed from streaming indicator readings, weighted for
+    /// the market regime when it is known
+    pub fn combine_signals_streaming(
+        &self,
+        candles: &[Candle],
+        values: &IndicatorValues,
+        regime: Option<MarketRegime>,
+    ) -> Option<TradingSignal> {
+        self.combine_with(regime, |strategy| {
+            strategy.generate_signal_streaming(candles, values)
+        })
+    }
+
+    /// Streaming indicators needed by all strategies, without duplicates
+    pub fn required_indicators(&self) -> Vec<IndicatorSpec> {
+        let mut specs: Vec<IndicatorSpec> = Vec::new();
+        for spec in self.strategies.iter().flat_map(|s| s.indicators()) {
+            if !specs.contains(&spec) {
+                specs.push(spec);
+            }
+        }
+        specs
+    }
+
+    fn combine_with<F>(&self, regime: Option<MarketRegime>, generate: F) -> Option<TradingSignal>
+    where
+        F: Fn(&(dyn Strategy + Send + Sync)) -> Option<TradingSignal>,
+    {
         let mut buy_score = 0.0;
         let mut sell_score = 0.0;
         let mut total_weight = 0.0;
-
-        for (strategy, &weight) in self.strategies.iter().zip(&self.weights) {
-            if let Some(signal) = strategy.generate_signal(candles) {
+        let mut votes = Vec::new();
+        let mut indicators = BTreeMap::new();
+
+        let multipliers = regime.and_then(|r| self.regime_multipliers.get(&r));
+
+        for (i, ((strategy, name), &weight)) in self
+            .strategies
+            .iter()
+            .zip(&self.strategy_names)
+            .zip(&self.weights)
+            .enumerate()
+        {
+            let weight = weight * multipliers.map_or(1.0, |m| m[i]);
+            if weight == 0.0 {
+                continue; // Disabled in this regime
+            }
+            if let Some(signal) = generate(strategy.as_ref()) {
                 match signal.signal {
                     Signal::Buy => buy_score += signal.confidence * weight,
                     Signal::Sell => sell_score += signal.confidence * weight,
                     Signal::Hold => {} // Hold doesn't affect score
                 }
                 total_weight += weight;
+                votes.push(StrategyVote {
+                    strategy: name.clone(),
+                    signal: signal.signal,
+                    confidence: signal.confidence,
+                    weight,
+                });
+                indicators.extend(signal.indicators);
             }
         }
 
@@ -223,22 +497,17 @@ impl SignalCombiner {
         let buy_confidence = buy_score / total_weight;
         let sell_confidence = sell_score / total_weight;
 
-        if buy_confidence > sell_confidence && buy_confidence > 0.5 {
-            Some(TradingSignal {
-                signal: Signal::Buy,
-                confidence: buy_confidence,
-            })
+        let mut combined = if buy_confidence > sell_confidence && buy_confidence > 0.5 {
+            TradingSignal::new(Signal::Buy, buy_confidence)
         } else if sell_confidence > buy_confidence && sell_confidence > 0.5 {
-            Some(TradingSignal {
-                signal: Signal::Sell,
-                confidence: sell_confidence,
-            })
+            TradingSignal::new(Signal::Sell, sell_confidence)
         } else {
-            Some(TradingSignal {
-                signal: Signal::Hold,
-                confidence: 0.5,
-            })
-        }
+            TradingSignal::new(Signal::Hold, 0.5)
+        };
+        combined.votes = votes;
+        combined.indicators = indicators;
+        combined.regime = regime;
+        Some(combined)
     }
 
     /// Adjust strategy weights based on performance metrics
@@ -291,6 +560,27 @@ impl SignalCombiner {
         Ok(())
     }
 
+    /// Replace the weights, e.g. with a `WeightAllocator` allocation
+    pub fn set_weights(&mut self, weights: Vec<f64>) -> Result<(), String> {
+        if weights.len() != self.strategies.len() {
+            return Err(format!(
+                "Weights count ({}) must match strategies count ({})",
+                weights.len(),
+                self.strategies.len()
+            ));
+        }
+        if weights.iter().any(|w| !(w.is_finite() && *w >= 0.0))
+            || weights.iter().sum::<f64>() <= 0.0
+        {
+            return Err(format!(
+                "Weights must be non-negative with a positive sum: {:?}",
+                weights
+            ));
+        }
+        self.weights = weights;
+        Ok(())
+    }
+
     /// Get current strategy names for metrics tracking
     pub fn get_strategy_names(&self) -> Vec<String> {
         self.strategy_names.clone()
@@ -388,4 +678,167 @@ mod tests {
         let signal = combined_signal.unwrap();
         assert!(signal.confidence >= 0.0 && signal.confidence <= 1.0);
     }
+
+    #[test]
+    fn test_combined_signal_explains_votes_and_indicators() {
+        let strategies = vec![
+            (
+                "FastScalping".to_string(),
+                Box::new(FastScalping::new()) as Box<dyn Strategy + Send + Sync>,
+            ),
+            (
+                "MomentumScalping".to_string(),
+                Box::new(MomentumScalping::new()) as Box<dyn Strategy + Send + Sync>,
+            ),
+            (
+                "ConservativeScalping".to_string(),
+                Box::new(ConservativeScalping::new()) as Box<dyn Strategy + Send + Sync>,
+            ),
+        ];
+        let combiner = SignalCombiner::new(strategies, vec![0.5, 0.3, 0.2]).unwrap();
+        let candles = create_test_candles();
+        let signal = combiner.combine_signals(&candles).unwrap();
+
+        let voters: Vec<&str> = signal.votes.iter().map(|v| v.strategy.as_str()).collect();
+        assert_eq!(
+            voters,
+            vec!["FastScalping", "MomentumScalping", "ConservativeScalping"]
+        );
+        assert_eq!(signal.votes[0].weight, 0.5);
+        // Rising closes: the short EMA leads the long one
+        assert_eq!(signal.votes[0].signal, Signal::Buy);
+        assert_eq!(signal.votes[0].confidence, 0.8);
+
+        let ema_short = EMA::new(3).calculate(&candles);
+        assert_eq!(signal.indicators["ema_3"], *ema_short.last().unwrap());
+        for key in [
+            "ema_5",
+            "rsi_7",
+            "macd_6_13_5",
+            "bb_upper_10",
+            "bb_lower_10",
+            "stoch_7_3",
+            "vwap",
+            "close",
+        ] {
+            assert!(signal.indicators.contains_key(key), "missing {}", key);
+        }
+
+        let explanation = signal.explanation();
+        assert_eq!(explanation["votes"].as_array().unwrap().len(), 3);
+        assert_eq!(explanation["indicators"]["close"], 165.0);
+    }
+
+    #[test]
+    fn test_regime_multipliers_reweight_and_disable_strategies() {
+        use crate::domain::services::streaming_indicators::IndicatorBank;
+
+        let strategies = vec![
+            (
+                "FastScalping".to_string(),
+                Box::new(FastScalping::new()) as Box<dyn Strategy + Send + Sync>,
+            ),
+            (
+                "MomentumScalping".to_string(),
+                Box::new(MomentumScalping::new()) as Box<dyn Strategy + Send + Sync>,
+            ),
+        ];
+        let mut combiner = SignalCombiner::new(strategies, vec![0.6, 0.4]).unwrap();
+        combiner
+            .set_regime_multipliers(
+                MarketRegime::Ranging,
+                &BTreeMap::from([("MomentumScalping".to_string(), 0.0)]),
+            )
+            .unwrap();
+        combiner
+            .set_regime_multipliers(
+                MarketRegime::TrendingUp,
+                &BTreeMap::from([("FastScalping".to_string(), 0.5)]),
+            )
+            .unwrap();
+        assert!(combiner
+            .set_regime_multipliers(
+                MarketRegime::Ranging,
+                &BTreeMap::from([("Unknown".to_string(), 1.0)]),
+            )
+            .is_err());
+        assert!(combiner
+            .set_regime_multipliers(
+                MarketRegime::Ranging,
+                &BTreeMap::from([("FastScalping".to_string(), -1.0)]),
+            )
+            .is_err());
+
+        let candles = create_test_candles();
+        let mut bank = IndicatorBank::new();
+        for spec in combiner.required_indicators() {
+            bank.register(spec, &candles);
+        }
+        let values = bank.values();
+
+        let unknown = combiner
+            .combine_signals_streaming(&candles, &values, None)
+            .unwrap();
+        assert_eq!(unknown.votes.len(), 2);
+        assert_eq!(unknown.regime, None);
+
+        let ranging = combiner
+            .combine_signals_streaming(&candles, &values, Some(MarketRegime::Ranging))
+            .unwrap();
+        let voters: Vec<&str> = ranging.votes.iter().map(|v| v.strategy.as_str()).collect();
+        assert_eq!(voters, vec!["FastScalping"]);
+        assert_eq!(ranging.regime, Some(MarketRegime::Ranging));
+        assert_eq!(ranging.explanation()["regime"], "ranging");
+
+        let trending = combiner
+            .combine_signals_streaming(&candles, &values, Some(MarketRegime::TrendingUp))
+            .unwrap();
+        assert_eq!(trending.votes[0].weight, 0.3);
+        assert_eq!(trending.votes[1].weight, 0.4);
+
+        // Regimes without rules keep the base weights
+        let quiet = combiner
+            .combine_signals_streaming(&candles, &values, Some(MarketRegime::LowVolatility))
+            .unwrap();
+        assert_eq!(quiet.votes[0].weight, 0.6);
+    }
+
+    #[test]
+    fn test_streaming_signals_match_batch() {
+        use crate::domain::services::streaming_indicators::IndicatorBank;
+
+        let strategies = vec![
+            (
+                "FastScalping".to_string(),
+                Box::new(FastScalping::new()) as Box<dyn Strategy + Send + Sync>,
+            ),
+            (
+                "MomentumScalping".to_string(),
+                Box::new(MomentumScalping::new()) as Box<dyn Strategy + Send + Sync>,
+            ),
+            (
+                "ConservativeScalping".to_string(),
+                Box::new(ConservativeScalping::new()) as Box<dyn Strategy + Send + Sync>,
+            ),
+        ];
+        let combiner = SignalCombiner::new(strategies, vec![0.4, 0.3, 0.3]).unwrap();
+        assert_eq!(combiner.required_indicators().len(), 7);
+
+        let mut bank = IndicatorBank::new();
+        for spec in combiner.required_indicators() {
+            bank.register(spec, &[]);
+        }
+
+        let candles = create_test_candles();
+        for (i, candle) in candles.iter().enumerate() {
+            bank.update(candle);
+            let history = &candles[..=i];
+            let values = bank.values();
+            for strategy in &combiner.strategies {
+                let streamed = strategy.generate_signal_streaming(history, &values);
+                let batch = strategy.generate_signal(history);
+                assert_eq!(streamed.map(|s| s.signal), batch.map(|s| s.signal));
+            }
+        }
+    }
 }
@@ -0,0 +1,1004 @@
+//! Strategy registry
+//!
+//! Builds strategies by name from a JSON configuration file instead of
+//! hardcoding them at startup. Each strategy has a type and typed parameters
+//! that are validated before anything is constructed, and groups map symbols,
+//! symbol globs or screening categories to their own strategy set, weights and
+//! confidence threshold.
+//!
+//! ```json
+//! {
+//!   "strategies": [
+//!     { "name": "FastScalping", "type": "fast_scalping", "params": { "ema_short": 3, "ema_long": 5 } },
+//!     { "name": "MomentumScalping", "type": "momentum_scalping" }
+//!   ],
+//!   "groups": [
+//!     { "name": "default", "strategies": [
+//!         { "strategy": "FastScalping", "weight": 0.6 },
+//!         { "strategy": "MomentumScalping", "weight": 0.4 } ] },
+//!     { "name": "majors", "symbols": ["BTC-USD", "ETH-*"], "min_confidence_threshold": 0.6,
+//!       "strategies": [ { "strategy": "FastScalping", "weight": 1.0 } ] },
+//!     { "name": "movers", "categories": ["BestCandidate"], "strategies": [
+//!         { "strategy": "MomentumScalping", "weight": 1.0 } ],
+//!       "regimes": { "ranging": { "MomentumScalping": 0.0 } } }
+//!   ],
+//!   "regime": { "trend_threshold": 25.0, "high_volatility": 0.004 },
+//!   "allocator": { "policy": "thompson", "floor": 0.05, "cap": 0.7,
+//!     "limits": { "MomentumScalping": { "cap": 0.5 } } },
+//!   "shadows": [
+//!     { "name": "momentum-heavy", "strategies": [
+//!         { "strategy": "FastScalping", "weight": 0.2 },
+//!         { "strategy": "MomentumScalping", "weight": 0.8 } ] }
+//!   ]
+//! }
+//! ```
+//!
+//! Omitted parameters take the values the strategies use by default. The group
+//! without symbols or categories is the default group and serves every other
+//! symbol. A symbol listed exactly wins over a glob (`*`, `?`), which wins over
+//! its current screening category; groups without a threshold use the default
+//! group's, then `MIN_CONFIDENCE_THRESHOLD`.
+//!
+//! `regimes` multiplies a group's weights while a symbol is in a given market
+//! regime (see `market_regime`); a multiplier of 0.0 disables the strategy and
+//! unlisted strategies keep their weight. `regime` tunes the classifier.
+//!
+//! `allocator` selects how weights adapt to realized trade returns (see
+//! `weight_allocator`): `performance` (default), `thompson` or `ucb`, with a
+//! floor and cap on each strategy's share of its group's weight.
+//!
+//! `shadows` are combiners that paper-trade next to the live ones (see
+//! `paper_trading`). They are written like groups; a shadow without symbols or
+//! categories runs on every symbol.
+
+use crate::domain::entities::symbol_screening::RecommendationCategory;
+use crate::domain::services::indicators::{
+    BollingerBands, StochasticOscillator, EMA, MACD, RSI, VWAP,
+};
+use crate::domain::services::market_regime::{MarketRegime, RegimeClassifier};
+use crate::domain::services::strategies::{
+    ConservativeScalping, FastScalping, MomentumScalping, SignalCombiner, Strategy,
+};
+use crate::domain::services::weight_allocator::AllocatorConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Raw registry file contents
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegistryConfig {
+    pub strategies: Vec<StrategyDefinition>,
+    pub groups: Vec<GroupDefinition>,
+    #[serde(default)]
+    pub regime: RegimeClassifier,
+    #[serde(default)]
+    pub allocator: AllocatorConfig,
+    #[serde(default)]
+    pub shadows: Vec<GroupDefinition>,
+}
+
+/// A named strategy instance; `params` is checked against `kind`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StrategyDefinition {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub params: serde_json::Value,
+}
+
+/// A symbol group and the weighted strategies that serve it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroupDefinition {
+    pub name: String,
+    /// Exact symbols or globs such as `*-USD`
+    #[serde(default)]
+    pub symbols: Vec<String>,
+    /// Screening categories whose symbols use this group
+    #[serde(default)]
+    pub categories: Vec<RecommendationCategory>,
+    pub strategies: Vec<GroupMember>,
+    #[serde(default)]
+    pub min_confidence_threshold: Option<f64>,
+    /// Weight multipliers per market regime and strategy
+    #[serde(default)]
+    pub regimes: BTreeMap<MarketRegime, BTreeMap<String, f64>>,
+}
+
+/// How a symbol was matched to a group; lower ranks take precedence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GroupMatch {
+    Symbol,
+    Glob,
+    Category,
+}
+
+impl GroupDefinition {
+    /// The default group lists neither symbols nor categories
+    pub fn is_default(&self) -> bool {
+        self.symbols.is_empty() && self.categories.is_empty()
+    }
+
+    /// Best way this group matches a symbol with an optional screening category
+    pub fn matches(
+        &self,
+        symbol: &str,
+        category: Option<RecommendationCategory>,
+    ) -> Option<GroupMatch> {
+        let mut best = None;
+        for pattern in &self.symbols {
+            if !is_glob(pattern) {
+                if pattern.eq_ignore_ascii_case(symbol) {
+                    return Some(GroupMatch::Symbol);
+                }
+            } else if glob_match(pattern, symbol) {
+                best = Some(GroupMatch::Glob);
+            }
+        }
+        if best.is_none() && category.is_some_and(|c| self.categories.contains(&c)) {
+            best = Some(GroupMatch::Category);
+        }
+        best
+    }
+}
+
+/// Check a group's strategy references, weights, threshold and regime rules
+fn validate_members(group: &GroupDefinition, strategies: &[StrategySpec]) -> Result<(), String> {
+    if group.strategies.is_empty() {
+        return Err(format!("group '{}' has no strategies", group.name));
+    }
+    let mut members = HashSet::new();
+    for member in &group.strategies {
+        if !strategies.iter().any(|s| s.name == member.strategy) {
+            return Err(format!(
+                "group '{}' references unknown strategy '{}'",
+                group.name, member.strategy
+            ));
+        }
+        if !members.insert(member.strategy.as_str()) {
+            return Err(format!(
+                "group '{}' lists strategy '{}' twice",
+                group.name, member.strategy
+            ));
+        }
+        if !(member.weight > 0.0 && member.weight.is_finite()) {
+            return Err(format!(
+                "group '{}': weight for '{}' must be positive",
+                group.name, member.strategy
+            ));
+        }
+    }
+    if let Some(threshold) = group.min_confidence_threshold {
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(format!(
+                "group '{}': min_confidence_threshold ({}) must be between 0.0 and 1.0",
+                group.name, threshold
+            ));
+        }
+    }
+    for (regime, multipliers) in &group.regimes {
+        for (strategy, &multiplier) in multipliers {
+            if !members.contains(strategy.as_str()) {
+                return Err(format!(
+                    "group '{}': regime {} references strategy '{}' outside the group",
+                    group.name,
+                    regime.as_str(),
+                    strategy
+                ));
+            }
+            if !(multiplier >= 0.0 && multiplier.is_finite()) {
+                return Err(format!(
+                    "group '{}': multiplier for '{}' in regime {} must be zero or positive",
+                    group.name,
+                    strategy,
+                    regime.as_str()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// Case-insensitive glob match supporting `*` (any run) and `?` (one character)
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_ascii_uppercase().chars().collect();
+    let text: Vec<char> = text.to_ascii_uppercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroupMember {
+    pub strategy: String,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FastScalpingParams {
+    pub ema_short: usize,
+    pub ema_long: usize,
+}
+
+impl Default for FastScalpingParams {
+    fn default() -> Self {
+        Self {
+            ema_short: 3,
+            ema_long: 5,
+        }
+    }
+}
+
+impl FastScalpingParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.ema_short == 0 || self.ema_short >= self.ema_long {
+            return Err(format!(
+                "ema_short ({}) must be positive and below ema_long ({})",
+                self.ema_short, self.ema_long
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MomentumScalpingParams {
+    pub rsi_period: usize,
+    pub macd_fast: usize,
+    pub macd_slow: usize,
+    pub macd_signal: usize,
+    pub rsi_oversold: f64,
+    pub rsi_overbought: f64,
+}
+
+impl Default for MomentumScalpingParams {
+    fn default() -> Self {
+        Self {
+            rsi_period: 7,
+            macd_fast: 6,
+            macd_slow: 13,
+            macd_signal: 5,
+            rsi_oversold: 30.0,
+            rsi_overbought: 70.0,
+        }
+    }
+}
+
+impl MomentumScalpingParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rsi_period == 0 || self.macd_signal == 0 {
+            return Err("rsi_period and macd_signal must be positive".to_string());
+        }
+        if self.macd_fast == 0 || self.macd_fast >= self.macd_slow {
+            return Err(format!(
+                "macd_fast ({}) must be positive and below macd_slow ({})",
+                self.macd_fast, self.macd_slow
+            ));
+        }
+        validate_band("rsi", self.rsi_oversold, self.rsi_overbought)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConservativeScalpingParams {
+    pub bollinger_period: usize,
+    pub bollinger_std_dev: f64,
+    pub stoch_k_period: usize,
+    pub stoch_d_period: usize,
+    pub stoch_oversold: f64,
+    pub stoch_overbought: f64,
+}
+
+impl Default for ConservativeScalpingParams {
+    fn default() -> Self {
+        Self {
+            bollinger_period: 10,
+            bollinger_std_dev: 2.0,
+            stoch_k_period: 7,
+            stoch_d_period: 3,
+            stoch_oversold: 20.0,
+            stoch_overbought: 80.0,
+        }
+    }
+}
+
+impl ConservativeScalpingParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.bollinger_period < 2 {
+            return Err(format!(
+                "bollinger_period ({}) must be at least 2",
+                self.bollinger_period
+            ));
+        }
+        if !(self.bollinger_std_dev > 0.0 && self.bollinger_std_dev.is_finite()) {
+            return Err(format!(
+                "bollinger_std_dev ({}) must be positive",
+                self.bollinger_std_dev
+            ));
+        }
+        if self.stoch_k_period == 0 || self.stoch_d_period == 0 {
+            return Err("stoch_k_period and stoch_d_period must be positive".to_string());
+        }
+        validate_band("stoch", self.stoch_oversold, self.stoch_overbought)
+    }
+}
+
+/// Oscillator thresholds must sit inside 0-100 with oversold below overbought
+fn validate_band(prefix: &str, oversold: f64, overbought: f64) -> Result<(), String> {
+    if !(0.0..=100.0).contains(&oversold)
+        || !(0.0..=100.0).contains(&overbought)
+        || oversold >= overbought
+    {
+        return Err(format!(
+            "{prefix}_oversold ({}) and {prefix}_overbought ({}) must be within 0-100 with oversold below overbought",
+            oversold, overbought
+        ));
+    }
+    Ok(())
+}
+
+/// Validated parameters for each supported strategy type
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "params", rename_all = "snake_case")]
+pub enum StrategyParams {
+    FastScalping(FastScalpingParams),
+    MomentumScalping(MomentumScalpingParams),
+    ConservativeScalping(ConservativeScalpingParams),
+}
+
+impl StrategyParams {
+    /// Parse and validate `params` for a strategy `kind`; missing params use defaults
+    pub fn parse(kind: &str, params: &serde_json::Value) -> Result<Self, String> {
+        let params = if params.is_null() {
+            serde_json::Value::Object(Default::default())
+        } else {
+            params.clone()
+        };
+        let parsed = match kind {
+            "fast_scalping" => StrategyParams::FastScalping(
+                serde_json::from_value(params).map_err(|e| e.to_string())?,
+            ),
+            "momentum_scalping" => StrategyParams::MomentumScalping(
+                serde_json::from_value(params).map_err(|e| e.to_string())?,
+            ),
+            "conservative_scalping" => StrategyParams::ConservativeScalping(
+                serde_json::from_value(params).map_err(|e| e.to_string())?,
+            ),
+            other => {
+                return Err(format!(
+                    "unknown strategy type '{}' (expected fast_scalping, momentum_scalping or conservative_scalping)",
+                    other
+                ))
+            }
+        };
+        parsed.validate()?;
+        Ok(parsed)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            StrategyParams::FastScalping(p) => p.validate(),
+            StrategyParams::MomentumScalping(p) => p.validate(),
+            StrategyParams::ConservativeScalping(p) => p.validate(),
+        }
+    }
+
+    /// Construct a strategy instance from these parameters
+    pub fn build(&self) -> Box<dyn Strategy + Send + Sync> {
+        match self {
+            StrategyParams::FastScalping(p) => Box::new(FastScalping {
+                ema_short: EMA::new(p.ema_short),
+                ema_long: EMA::new(p.ema_long),
+            }),
+            StrategyParams::MomentumScalping(p) => Box::new(MomentumScalping {
+                rsi: RSI::new(p.rsi_period),
+                macd: MACD::new(p.macd_fast, p.macd_slow, p.macd_signal),
+                rsi_oversold: p.rsi_oversold,
+                rsi_overbought: p.rsi_overbought,
+            }),
+            StrategyParams::ConservativeScalping(p) => Box::new(ConservativeScalping {
+                bollinger: BollingerBands::new(p.bollinger_period, p.bollinger_std_dev),
+                stoch: StochasticOscillator::new(p.stoch_k_period, p.stoch_d_period),
+                vwap: VWAP,
+                stoch_oversold: p.stoch_oversold,
+                stoch_overbought: p.stoch_overbought,
+            }),
+        }
+    }
+}
+
+/// A registered strategy with its validated parameters
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StrategySpec {
+    pub name: String,
+    #[serde(flatten)]
+    pub params: StrategyParams,
+}
+
+/// Validated set of strategies and the symbol groups that use them
+#[derive(Debug, Clone)]
+pub struct StrategyRegistry {
+    strategies: Vec<StrategySpec>,
+    groups: Vec<GroupDefinition>,
+    regime: RegimeClassifier,
+    allocator: AllocatorConfig,
+    shadows: Vec<GroupDefinition>,
+}
+
+impl Default for StrategyRegistry {
+    /// The three scalping strategies with their default parameters, weighted
+    /// 0.4 / 0.4 / 0.2 in a single default group. Momentum is disabled while
+    /// ranging, the Bollinger/stochastic mean reversion while trending, and
+    /// the EMA crossover is halved in high volatility.
+    fn default() -> Self {
+        let strategies = vec![
+            StrategySpec {
+                name: "FastScalping".to_string(),
+                params: StrategyParams::FastScalping(FastScalpingParams::default()),
+            },
+            StrategySpec {
+                name: "MomentumScalping".to_string(),
+                params: StrategyParams::MomentumScalping(MomentumScalpingParams::default()),
+            },
+            StrategySpec {
+                name: "ConservativeScalping".to_string(),
+                params: StrategyParams::ConservativeScalping(ConservativeScalpingParams::default()),
+            },
+        ];
+        let members = [0.4, 0.4, 0.2]
+            .iter()
+            .zip(&strategies)
+            .map(|(&weight, spec)| GroupMember {
+                strategy: spec.name.clone(),
+                weight,
+            })
+            .collect();
+        Self {
+            strategies,
+            groups: vec![GroupDefinition {
+                name: "default".to_string(),
+                symbols: Vec::new(),
+                categories: Vec::new(),
+                strategies: members,
+                min_confidence_threshold: None,
+                regimes: BTreeMap::from([
+                    (
+                        MarketRegime::Ranging,
+                        BTreeMap::from([("MomentumScalping".to_string(), 0.0)]),
+                    ),
+                    (
+                        MarketRegime::TrendingUp,
+                        BTreeMap::from([("ConservativeScalping".to_string(), 0.0)]),
+                    ),
+                    (
+                        MarketRegime::TrendingDown,
+                        BTreeMap::from([("ConservativeScalping".to_string(), 0.0)]),
+                    ),
+                    (
+                        MarketRegime::HighVolatility,
+                        BTreeMap::from([("FastScalping".to_string(), 0.5)]),
+                    ),
+                ]),
+            }],
+            regime: RegimeClassifier::default(),
+            allocator: AllocatorConfig::default(),
+            shadows: Vec::new(),
+        }
+    }
+}
+
+impl StrategyRegistry {
+    /// Read and validate a registry file
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read strategy config '{}': {}", path, e))?;
+        Self::from_json(&contents).map_err(|e| format!("Invalid strategy config '{}': {}", path, e))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let config: RegistryConfig = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        Self::from_config(config)
+    }
+
+    /// Validate strategy definitions, groups and shadows
+    ///
+    /// Strategy names must be unique, every group and shadow must reference
+    /// registered strategies with positive weights, exactly one group has no
+    /// symbols or categories (the default group), and an exact symbol, glob or
+    /// category belongs to at most one group.
+    pub fn from_config(config: RegistryConfig) -> Result<Self, String> {
+        if config.strategies.is_empty() {
+            return Err("at least one strategy is required".to_string());
+        }
+
+        let mut strategies = Vec::with_capacity(config.strategies.len());
+        for definition in &config.strategies {
+            if definition.name.trim().is_empty() {
+                return Err("strategy names must not be empty".to_string());
+            }
+            if strategies
+                .iter()
+                .any(|s: &StrategySpec| s.name == definition.name)
+            {
+                return Err(format!("duplicate strategy name '{}'", definition.name));
+            }
+            let params = StrategyParams::parse(&definition.kind, &definition.params)
+                .map_err(|e| format!("strategy '{}': {}", definition.name, e))?;
+            strategies.push(StrategySpec {
+                name: definition.name.clone(),
+                params,
+            });
+        }
+
+        let mut group_names = HashSet::new();
+        let mut grouped_symbols = HashSet::new();
+        let mut grouped_categories = HashSet::new();
+        let mut default_groups = 0;
+        for group in &config.groups {
+            if !group_names.insert(group.name.as_str()) {
+                return Err(format!("duplicate group name '{}'", group.name));
+            }
+            validate_members(group, &strategies)?;
+            if group.is_default() {
+                default_groups += 1;
+            }
+            for symbol in &group.symbols {
+                if symbol.trim().is_empty() {
+                    return Err(format!("group '{}' has an empty symbol", group.name));
+                }
+                if !grouped_symbols.insert(symbol.to_uppercase()) {
+                    return Err(format!(
+                        "symbol '{}' is assigned to more than one group",
+                        symbol
+                    ));
+                }
+            }
+            for category in &group.categories {
+                if !grouped_categories.insert(*category) {
+                    return Err(format!(
+                        "category {:?} is assigned to more than one group",
+                        category
+                    ));
+                }
+            }
+        }
+        if default_groups != 1 {
+            return Err(format!(
+                "exactly one group without symbols or categories is required as the default, found {}",
+                default_groups
+            ));
+        }
+
+        config
+            .regime
+            .validate()
+            .map_err(|e| format!("regime: {}", e))?;
+
+        let strategy_names: Vec<String> = strategies.iter().map(|s| s.name.clone()).collect();
+        config
+            .allocator
+            .validate(&strategy_names)
+            .map_err(|e| format!("allocator: {}", e))?;
+
+        let mut shadow_names = HashSet::new();
+        for shadow in &config.shadows {
+            if !shadow_names.insert(shadow.name.as_str()) {
+                return Err(format!("duplicate shadow name '{}'", shadow.name));
+            }
+            validate_members(shadow, &strategies).map_err(|e| format!("shadow {}", e))?;
+        }
+
+        Ok(Self {
+            strategies,
+            groups: config.groups,
+            regime: config.regime,
+            allocator: config.allocator,
+            shadows: config.shadows,
+        })
+    }
+
+    pub fn strategies(&self) -> &[StrategySpec] {
+        &self.strategies
+    }
+
+    pub fn groups(&self) -> &[GroupDefinition] {
+        &self.groups
+    }
+
+    /// Classifier that labels each symbol's market regime
+    pub fn regime_classifier(&self) -> &RegimeClassifier {
+        &self.regime
+    }
+
+    /// How strategy weights adapt to realized trade returns
+    pub fn allocator_config(&self) -> &AllocatorConfig {
+        &self.allocator
+    }
+
+    /// Combiners that paper-trade alongside the live ones
+    pub fn shadows(&self) -> &[GroupDefinition] {
+        &self.shadows
+    }
+
+    /// Registered strategy names in definition order
+    pub fn strategy_names(&self) -> Vec<String> {
+        self.strategies.iter().map(|s| s.name.clone()).collect()
+    }
+
+    /// The group serving symbols not matched by any other group
+    pub fn default_group(&self) -> &GroupDefinition {
+        self.groups
+            .iter()
+            .find(|g| g.is_default())
+            .expect("validated registry always has a default group")
+    }
+
+    /// Construct a registered strategy by name
+    pub fn build_strategy(&self, name: &str) -> Result<Box<dyn Strategy + Send + Sync>, String> {
+        self.strategies
+            .iter()
+            .find(|s| s.name == name)
+            .map(|s| s.params.build())
+            .ok_or_else(|| format!("unknown strategy '{}'", name))
+    }
+
+    /// Construct the weighted combiner for a group
+    pub fn build_combiner(&self, group: &GroupDefinition) -> Result<SignalCombiner, String> {
+        let mut strategies = Vec::with_capacity(group.strategies.len());
+        let mut weights = Vec::with_capacity(group.strategies.len());
+        for member in &group.strategies {
+            strategies.push((
+                member.strategy.clone(),
+                self.build_strategy(&member.strategy)?,
+            ));
+            weights.push(member.weight);
+        }
+        let mut combiner = SignalCombiner::new(strategies, weights)?;
+        for (regime, multipliers) in &group.regimes {
+            combiner.set_regime_multipliers(*regime, multipliers)?;
+        }
+        Ok(combiner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::weight_allocator::AllocatorPolicy;
+
+    const CONFIG: &str = r#"{
+        "strategies": [
+            { "name": "Fast", "type": "fast_scalping", "params": { "ema_short": 4, "ema_long": 9 } },
+            { "name": "Momentum", "type": "momentum_scalping", "params": { "rsi_oversold": 25 } },
+            { "name": "Conservative", "type": "conservative_scalping" }
+        ],
+        "groups": [
+            { "name": "default", "strategies": [
+                { "strategy": "Fast", "weight": 0.5 },
+                { "strategy": "Momentum", "weight": 0.3 },
+                { "strategy": "Conservative", "weight": 0.2 } ] },
+            { "name": "majors", "symbols": ["BTC-USD", "ETH-USD"], "strategies": [
+                { "strategy": "Fast", "weight": 1.0 } ] }
+        ]
+    }"#;
+
+    #[test]
+    fn test_parses_typed_params_with_defaults() {
+        let registry = StrategyRegistry::from_json(CONFIG).unwrap();
+        assert_eq!(
+            registry.strategy_names(),
+            vec!["Fast", "Momentum", "Conservative"]
+        );
+        assert_eq!(
+            registry.strategies()[0].params,
+            StrategyParams::FastScalping(FastScalpingParams {
+                ema_short: 4,
+                ema_long: 9
+            })
+        );
+        match &registry.strategies()[1].params {
+            StrategyParams::MomentumScalping(p) => {
+                assert_eq!(p.rsi_oversold, 25.0);
+                assert_eq!(p.rsi_overbought, 70.0);
+                assert_eq!(p.rsi_period, 7);
+            }
+            other => panic!("unexpected params {:?}", other),
+        }
+        assert_eq!(
+            registry.strategies()[2].params,
+            StrategyParams::ConservativeScalping(ConservativeScalpingParams::default())
+        );
+    }
+
+    #[test]
+    fn test_builds_group_combiners() {
+        let registry = StrategyRegistry::from_json(CONFIG).unwrap();
+        let default = registry.build_combiner(registry.default_group()).unwrap();
+        assert_eq!(default.get_strategy_names().len(), 3);
+        assert_eq!(default.weights(), &[0.5, 0.3, 0.2]);
+
+        let majors = registry.build_combiner(&registry.groups()[1]).unwrap();
+        assert_eq!(majors.get_strategy_names(), vec!["Fast"]);
+        assert_eq!(
+            majors.required_indicators(),
+            vec![
+                crate::domain::services::streaming_indicators::IndicatorSpec::Ema(4),
+                crate::domain::services::streaming_indicators::IndicatorSpec::Ema(9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_registry_matches_builtin_setup() {
+        let registry = StrategyRegistry::default();
+        assert_eq!(
+            registry.strategy_names(),
+            vec!["FastScalping", "MomentumScalping", "ConservativeScalping"]
+        );
+        let combiner = registry.build_combiner(registry.default_group()).unwrap();
+        assert_eq!(combiner.weights(), &[0.4, 0.4, 0.2]);
+        assert_eq!(
+            combiner.regime_multipliers[&MarketRegime::Ranging],
+            vec![1.0, 0.0, 1.0]
+        );
+        assert_eq!(registry.regime_classifier(), &RegimeClassifier::default());
+    }
+
+    #[test]
+    fn test_regime_rules_are_parsed_and_applied() {
+        let registry = StrategyRegistry::from_json(
+            r#"{
+                "strategies": [
+                    { "name": "A", "type": "fast_scalping" },
+                    { "name": "B", "type": "momentum_scalping" }
+                ],
+                "groups": [
+                    { "name": "default",
+                      "strategies": [{ "strategy": "A", "weight": 0.5 }, { "strategy": "B", "weight": 0.5 }],
+                      "regimes": { "trending_down": { "A": 0.0, "B": 2.0 }, "low_volatility": { "B": 0.5 } } }
+                ],
+                "regime": { "trend_threshold": 30.0 }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(registry.regime_classifier().trend_threshold, 30.0);
+        assert_eq!(registry.regime_classifier().adx_period, 14);
+
+        let combiner = registry.build_combiner(registry.default_group()).unwrap();
+        assert_eq!(
+            combiner.regime_multipliers[&MarketRegime::TrendingDown],
+            vec![0.0, 2.0]
+        );
+        assert_eq!(
+            combiner.regime_multipliers[&MarketRegime::LowVolatility],
+            vec![1.0, 0.5]
+        );
+        assert!(!combiner
+            .regime_multipliers
+            .contains_key(&MarketRegime::Ranging));
+    }
+
+    #[test]
+    fn test_allocator_section_is_parsed() {
+        assert_eq!(
+            StrategyRegistry::default().allocator_config().policy,
+            AllocatorPolicy::Performance
+        );
+        let registry = StrategyRegistry::from_json(
+            r#"{
+                "strategies": [{ "name": "A", "type": "fast_scalping" }],
+                "groups": [{ "name": "default", "strategies": [{ "strategy": "A", "weight": 1.0 }] }],
+                "allocator": { "policy": "thompson", "cap": 0.6, "limits": { "A": { "floor": 0.2 } } }
+            }"#,
+        )
+        .unwrap();
+        let allocator = registry.allocator_config();
+        assert_eq!(allocator.policy, AllocatorPolicy::Thompson);
+        assert_eq!(allocator.limits_for("A"), (0.2, 0.6));
+        assert_eq!(allocator.samples, 1000);
+    }
+
+    #[test]
+    fn test_shadows_are_parsed() {
+        assert!(StrategyRegistry::default().shadows().is_empty());
+        let registry = StrategyRegistry::from_json(
+            r#"{
+                "strategies": [{ "name": "A", "type": "fast_scalping" }, { "name": "B", "type": "momentum_scalping" }],
+                "groups": [{ "name": "default", "strategies": [{ "strategy": "A", "weight": 1.0 }] }],
+                "shadows": [{ "name": "trial", "min_confidence_threshold": 0.4,
+                              "strategies": [{ "strategy": "A", "weight": 0.3 }, { "strategy": "B", "weight": 0.7 }] }]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(registry.shadows().len(), 1);
+        let shadow = &registry.shadows()[0];
+        assert!(shadow.is_default()); // Runs on every symbol
+        let combiner = registry.build_combiner(shadow).unwrap();
+        assert_eq!(combiner.weights(), &[0.3, 0.7]);
+    }
+
+    #[test]
+    fn test_rejects_invalid_configs() {
+        let cases = [
+            // unknown type
+            r#"{"strategies":[{"name":"A","type":"grid"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}]}]}"#,
+            // misspelled parameter
+            r#"{"strategies":[{"name":"A","type":"fast_scalping","params":{"ema_shrt":3}}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}]}]}"#,
+            // inverted periods
+            r#"{"strategies":[{"name":"A","type":"fast_scalping","params":{"ema_short":9,"ema_long":5}}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}]}]}"#,
+            // inverted thresholds
+            r#"{"strategies":[{"name":"A","type":"momentum_scalping","params":{"rsi_oversold":80}}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}]}]}"#,
+            // duplicate name
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"},{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}]}]}"#,
+            // unknown strategy in group
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"B","weight":1}]}]}"#,
+            // non-positive weight
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":0}]}]}"#,
+            // no default group
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","symbols":["BTC-USD"],"strategies":[{"strategy":"A","weight":1}]}]}"#,
+            // threshold out of range
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","min_confidence_threshold":1.5,"strategies":[{"strategy":"A","weight":1}]}]}"#,
+            // category in two groups
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}]},
+                          {"name":"x","categories":["Avoid"],"strategies":[{"strategy":"A","weight":1}]},
+                          {"name":"y","categories":["Avoid"],"strategies":[{"strategy":"A","weight":1}]}]}"#,
+            // symbol in two groups
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}]},
+                          {"name":"x","symbols":["BTC-USD"],"strategies":[{"strategy":"A","weight":1}]},
+                          {"name":"y","symbols":["btc-usd"],"strategies":[{"strategy":"A","weight":1}]}]}"#,
+            // unknown regime
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}],"regimes":{"sideways":{"A":0}}}]}"#,
+            // regime rule for a strategy outside the group
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"},{"name":"B","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}],"regimes":{"ranging":{"B":0}}}]}"#,
+            // negative regime multiplier
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}],"regimes":{"ranging":{"A":-1}}}]}"#,
+            // invalid classifier settings
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}]}],
+                "regime":{"adx_period":0}}"#,
+            // unknown allocator policy
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}]}],
+                "allocator":{"policy":"greedy"}}"#,
+            // allocator floor above cap
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}]}],
+                "allocator":{"floor":0.5,"cap":0.4}}"#,
+            // allocator limits for an unknown strategy
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}]}],
+                "allocator":{"limits":{"B":{"cap":0.5}}}}"#,
+            // shadow referencing an unknown strategy
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}]}],
+                "shadows":[{"name":"s","strategies":[{"strategy":"B","weight":1}]}]}"#,
+            // duplicate shadow name
+            r#"{"strategies":[{"name":"A","type":"fast_scalping"}],
+                "groups":[{"name":"d","strategies":[{"strategy":"A","weight":1}]}],
+                "shadows":[{"name":"s","strategies":[{"strategy":"A","weight":1}]},
+                           {"name":"s","strategies":[{"strategy":"A","weight":1}]}]}"#,
+        ];
+        for case in cases {
+            assert!(
+                StrategyRegistry::from_json(case).is_err(),
+                "expected rejection: {}",
+                case
+            );
+        }
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*-USD", "SOL-USD"));
+        assert!(glob_match("btc-*", "BTC-USD"));
+        assert!(glob_match("?TC-USD", "BTC-USD"));
+        assert!(glob_match("*", "ANY"));
+        assert!(glob_match("A*B*C", "AxxBxxBxxC"));
+        assert!(!glob_match("*-EUR", "BTC-USD"));
+        assert!(!glob_match("?-USD", "BTC-USD"));
+        assert!(!glob_match("BTC", "BTC-USD"));
+    }
+
+    #[test]
+    fn test_group_match_precedence() {
+        let registry = StrategyRegistry::from_json(
+            r#"{
+                "strategies": [{ "name": "A", "type": "fast_scalping" }],
+                "groups": [
+                    { "name": "default", "min_confidence_threshold": 0.8,
+                      "strategies": [{ "strategy": "A", "weight": 1 }] },
+                    { "name": "movers", "categories": ["BestCandidate"],
+                      "strategies": [{ "strategy": "A", "weight": 1 }] },
+                    { "name": "usd", "symbols": ["*-USD"], "min_confidence_threshold": 0.6,
+                      "strategies": [{ "strategy": "A", "weight": 1 }] },
+                    { "name": "btc", "symbols": ["BTC-USD"],
+                      "strategies": [{ "strategy": "A", "weight": 1 }] }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let groups = registry.groups();
+        let best = Some(RecommendationCategory::BestCandidate);
+
+        assert!(groups[0].is_default());
+        assert_eq!(groups[0].matches("BTC-USD", best), None);
+        assert_eq!(
+            groups[1].matches("DOGE-EUR", best),
+            Some(GroupMatch::Category)
+        );
+        assert_eq!(groups[1].matches("DOGE-EUR", None), None);
+        assert_eq!(groups[2].matches("SOL-USD", best), Some(GroupMatch::Glob));
+        assert_eq!(groups[3].matches("btc-usd", None), Some(GroupMatch::Symbol));
+        assert!(GroupMatch::Symbol < GroupMatch::Glob && GroupMatch::Glob < GroupMatch::Category);
+        assert_eq!(groups[2].min_confidence_threshold, Some(0.6));
+        assert_eq!(groups[3].min_confidence_threshold, None);
+    }
+
+    #[test]
+    fn test_built_strategy_uses_configured_thresholds() {
+        use crate::domain::services::indicators::Candle;
+        use crate::domain::services::strategies::Signal;
+
+        // A long rally then a pullback: RSI(7) near 48 while MACD stays positive
+        let candles: Vec<Candle> = (0..20)
+            .map(|i| 100.0 + i as f64 * 5.0)
+            .chain((1..=4).map(|j| 195.0 - j as f64 * 4.0))
+            .map(|close| Candle::new(close, close + 1.0, close - 1.0, close, 1000.0).unwrap())
+            .collect();
+
+        let default = StrategyParams::parse("momentum_scalping", &serde_json::Value::Null)
+            .unwrap()
+            .build();
+        let loose = StrategyParams::parse(
+            "momentum_scalping",
+            &serde_json::json!({ "rsi_oversold": 50.0, "rsi_overbought": 80.0 }),
+        )
+        .unwrap()
+        .build();
+
+        assert_eq!(
+            default.generate_signal(&candles).unwrap().signal,
+            Signal::Hold
+        );
+        assert_eq!(loose.generate_signal(&candles).unwrap().signal, Signal::Buy);
+    }
+}
@@ -0,0 +1,849 @@
+//! Stateful streaming versions of the batch indicators
+//!
+//! Each streaming indicator consumes one completed candle at a time and keeps
+//! just enough state to produce its next value, so strategies no longer have to
+//! recompute the whole candle history on every tick. Window indicators (RSI,
+//! Bollinger, Stochastic, VWAP) cover the candles fed minus those evicted and
+//! are identical to what the batch indicator returns for that series at every
+//! step. `CandleBuilder` evicts the candles it trims from its history, so both
+//! always see the same series.
+//!
+//! EMA and MACD ignore evictions: their recurrence keeps running over every
+//! candle fed, and they match the batch indicator over that whole stream. An
+//! evicted candle's weight has already decayed by `(1 - α)^n` after `n` later
+//! candles, whereas dropping it from the series would re-anchor the seed and
+//! move every later point.
+//!
+//! Window sums are `ExactSum`s, shared with the batch indicators: a running
+//! float sum would carry the rounding of every value that entered and left
+//! it, while an exact sum rounds only once, whichever path led to the window.
+//! Updates and evictions take constant time.
+
+use crate::domain::services::indicators::{window_moments, Candle, ExactSum};
+use std::collections::VecDeque;
+
+/// Indicator updated incrementally from completed candles
+pub trait StreamingIndicator: Send + Sync {
+    /// Feed the next completed candle and return the latest primary value
+    fn update(&mut self, candle: &Candle) -> Option<f64>;
+
+    /// Drop the oldest candle fed, which the caller passes back
+    fn evict(&mut self, candle: &Candle);
+
+    /// Full reading after the last update, if the indicator is warmed up
+    fn reading(&self) -> Option<IndicatorReading>;
+}
+
+/// Current output of a streaming indicator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IndicatorReading {
+    /// Single-line indicators (EMA, RSI, VWAP)
+    Value(f64),
+    /// MACD line, signal line and histogram
+    Macd {
+        macd: f64,
+        signal: f64,
+        histogram: f64,
+    },
+    /// Bollinger upper, middle and lower bands
+    Bands { upper: f64, middle: f64, lower: f64 },
+    /// Stochastic %K and %D
+    Stochastic { k: f64, d: f64 },
+}
+
+impl IndicatorReading {
+    /// Primary value: the middle band for Bollinger, otherwise the last value
+    /// of the batch `calculate` (signal line for MACD, %D for Stochastic)
+    pub fn value(&self) -> f64 {
+        match *self {
+            IndicatorReading::Value(value) => value,
+            IndicatorReading::Macd { signal, .. } => signal,
+            IndicatorReading::Bands { middle, .. } => middle,
+            IndicatorReading::Stochastic { d, .. } => d,
+        }
+    }
+}
+
+/// Streaming exponential moving average
+///
+/// Before `period` values the value is the mean of the values seen so far,
+/// then it is seeded with their SMA, like `EMA::calculate`. Evictions leave
+/// the recurrence running over every value fed.
+#[derive(Debug, Clone)]
+pub struct StreamingEma {
+    period: usize,
+    multiplier: f64,
+    /// Values fed
+    count: usize,
+    /// Sum of the first `period` values fed
+    seed_sum: f64,
+    value: Option<f64>,
+}
+
+impl StreamingEma {
+    pub fn new(period: usize) -> Self {
+        StreamingEma {
+            period,
+            multiplier: 2.0 / (period as f64 + 1.0),
+            count: 0,
+            seed_sum: 0.0,
+            value: None,
+        }
+    }
+
+    /// Feed a raw value instead of a candle close
+    pub fn update_value(&mut self, value: f64) -> Option<f64> {
+        if self.period == 0 {
+            return None;
+        }
+
+        self.count += 1;
+        let ema = if self.count <= self.period {
+            self.seed_sum += value;
+            self.seed_sum / self.count as f64
+        } else {
+            // A value always exists once more than `period` values were fed
+            let ema = self.value.unwrap_or(value);
+            (value - ema) * self.multiplier + ema
+        };
+        self.value = Some(ema);
+        self.value
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+impl StreamingIndicator for StreamingEma {
+    fn update(&mut self, candle: &Candle) -> Option<f64> {
+        self.update_value(candle.close.value())
+    }
+
+    fn evict(&mut self, _candle: &Candle) {}
+
+    fn reading(&self) -> Option<IndicatorReading> {
+        self.value.map(IndicatorReading::Value)
+    }
+}
+
+/// Streaming RSI over the simple average of the last `period` changes
+///
+/// Gains and losses are kept as exact sums over the window of changes.
+#[derive(Debug, Clone)]
+pub struct StreamingRsi {
+    period: usize,
+    /// Candles held
+    count: usize,
+    prev_close: Option<f64>,
+    /// (gain, loss) of the last `period` changes
+    changes: VecDeque<(f64, f64)>,
+    gain_sum: ExactSum,
+    loss_sum: ExactSum,
+}
+
+impl StreamingRsi {
+    pub fn new(period: usize) -> Self {
+        StreamingRsi {
+            period,
+            count: 0,
+            prev_close: None,
+            changes: VecDeque::with_capacity(period + 1),
+            gain_sum: ExactSum::new(),
+            loss_sum: ExactSum::new(),
+        }
+    }
+
+    fn pop_change(&mut self) {
+        let Some((gain, loss)) = self.changes.pop_front() else {
+            return;
+        };
+        self.gain_sum.sub(gain);
+        self.loss_sum.sub(loss);
+    }
+
+    fn value(&self) -> Option<f64> {
+        if self.period == 0 || self.count < self.period + 1 {
+            return None;
+        }
+        let avg_gain = self.gain_sum.value() / self.period as f64;
+        let avg_loss = self.loss_sum.value() / self.period as f64;
+        let rs = if avg_loss == 0.0 {
+            100.0
+        } else {
+            avg_gain / avg_loss
+        };
+        Some(100.0 - (100.0 / (1.0 + rs)))
+    }
+}
+
+impl StreamingIndicator for StreamingRsi {
+    fn update(&mut self, candle: &Candle) -> Option<f64> {
+        let close = candle.close.value();
+        self.count += 1;
+        let prev_close = self.prev_close.replace(close);
+        if self.period == 0 {
+            return None;
+        }
+        let prev_close = prev_close?;
+
+        let change = close - prev_close;
+        let (gain, loss) = if change > 0.0 {
+            (change, 0.0)
+        } else {
+            (0.0, change.abs())
+        };
+        self.changes.push_back((gain, loss));
+        self.gain_sum.add(gain);
+        self.loss_sum.add(loss);
+        if self.changes.len() > self.period {
+            self.pop_change();
+        }
+        self.value()
+    }
+
+    fn evict(&mut self, _candle: &Candle) {
+        self.count = self.count.saturating_sub(1);
+        if self.count == 0 {
+            self.prev_close = None;
+        }
+        while self.changes.len() > self.count.saturating_sub(1) {
+            self.pop_change();
+        }
+    }
+
+    fn reading(&self) -> Option<IndicatorReading> {
+        self.value().map(IndicatorReading::Value)
+    }
+}
+
+/// Streaming MACD
+///
+/// Until the slower EMA has a full period of candles the MACD line is reported
+/// as-is; from then on it feeds the signal EMA, mirroring `MACD::calculate`.
+/// Like the EMAs it is built on, it runs over every candle fed.
+#[derive(Debug, Clone)]
+pub struct StreamingMacd {
+    warmup: usize,
+    signal_period: usize,
+    /// Closes fed
+    count: usize,
+    fast: StreamingEma,
+    slow: StreamingEma,
+    signal: StreamingEma,
+    reading: Option<IndicatorReading>,
+}
+
+impl StreamingMacd {
+    pub fn new(fast_period: usize, slow_period: usize, signal_period: usize) -> Self {
+        StreamingMacd {
+            warmup: fast_period.max(slow_period),
+            signal_period,
+            count: 0,
+            fast: StreamingEma::new(fast_period),
+            slow: StreamingEma::new(slow_period),
+            signal: StreamingEma::new(signal_period),
+            reading: None,
+        }
+    }
+
+    fn update_close(&mut self, close: f64) -> Option<f64> {
+        self.count += 1;
+        let fast = self.fast.update_value(close);
+        let slow = self.slow.update_value(close);
+        if self.signal_period == 0 {
+            return None;
+        }
+        let macd = fast? - slow?;
+
+        let signal = if self.count < self.warmup {
+            macd
+        } else {
+            self.signal.update_value(macd)?
+        };
+        self.reading = Some(IndicatorReading::Macd {
+            macd,
+            signal,
+            histogram: macd - signal,
+        });
+        Some(signal)
+    }
+}
+
+impl StreamingIndicator for StreamingMacd {
+    fn update(&mut self, candle: &Candle) -> Option<f64> {
+        self.update_close(candle.close.value())
+    }
+
+    fn evict(&mut self, _candle: &Candle) {}
+
+    fn reading(&self) -> Option<IndicatorReading> {
+        self.reading
+    }
+}
+
+/// Streaming Bollinger Bands over a window of `period` closes
+///
+/// Exact sums of the closes and of their squares are updated as closes enter
+/// and leave the window, instead of summing the window again.
+#[derive(Debug, Clone)]
+pub struct StreamingBollinger {
+    period: usize,
+    std_dev: f64,
+    /// Candles held
+    count: usize,
+    closes: VecDeque<f64>,
+    sum: ExactSum,
+    squares: ExactSum,
+}
+
+impl StreamingBollinger {
+    pub fn new(period: usize, std_dev: f64) -> Self {
+        StreamingBollinger {
+            period,
+            std_dev,
+            count: 0,
+            closes: VecDeque::with_capacity(period + 1),
+            sum: ExactSum::new(),
+            squares: ExactSum::new(),
+        }
+    }
+
+    fn pop_close(&mut self) {
+        let Some(close) = self.closes.pop_front() else {
+            return;
+        };
+        self.sum.sub(close);
+        self.squares.add_product(-close, close);
+    }
+
+    fn bands(&self) -> Option<IndicatorReading> {
+        if self.period == 0 || self.closes.len() < self.period {
+            return None;
+        }
+        let (mean, variance) = window_moments(&self.sum, &self.squares, self.period);
+        let std = variance.sqrt();
+        Some(IndicatorReading::Bands {
+            upper: mean + self.std_dev * std,
+            middle: mean,
+            lower: mean - self.std_dev * std,
+        })
+    }
+}
+
+impl StreamingIndicator for StreamingBollinger {
+    fn update(&mut self, candle: &Candle) -> Option<f64> {
+        self.count += 1;
+        if self.period == 0 {
+            return None;
+        }
+        let close = candle.close.value();
+        self.closes.push_back(close);
+        self.sum.add(close);
+        self.squares.add_product(close, close);
+        if self.closes.len() > self.period {
+            self.pop_close();
+        }
+        self.bands().map(|bands| bands.value())
+    }
+
+    fn evict(&mut self, _candle: &Candle) {
+        self.count = self.count.saturating_sub(1);
+        while self.closes.len() > self.count {
+            self.pop_close();
+        }
+    }
+
+    fn reading(&self) -> Option<IndicatorReading> {
+        self.bands()
+    }
+}
+
+/// Streaming Stochastic Oscillator (%K over `k_period`, %D as its SMA)
+///
+/// The highest high and lowest low of the window come from monotonic queues,
+/// and %D from an exact sum of the last `d_period` %K values.
+#[derive(Debug, Clone)]
+pub struct StreamingStochastic {
+    k_period: usize,
+    d_period: usize,
+    /// Candles fed so far, used as candle positions
+    fed: usize,
+    /// Candles held
+    count: usize,
+    /// (position, high) with decreasing highs
+    highs: VecDeque<(usize, f64)>,
+    /// (position, low) with increasing lows
+    lows: VecDeque<(usize, f64)>,
+    k_values: VecDeque<f64>,
+    k_sum: ExactSum,
+}
+
+impl StreamingStochastic {
+    pub fn new(k_period: usize, d_period: usize) -> Self {
+        StreamingStochastic {
+            k_period,
+            d_period,
+            fed: 0,
+            count: 0,
+            highs: VecDeque::new(),
+            lows: VecDeque::new(),
+            k_values: VecDeque::with_capacity(d_period + 1),
+            k_sum: ExactSum::new(),
+        }
+    }
+
+    /// Drop extremes older than the window and %K values whose window
+    /// reaches past the candles held
+    fn trim(&mut self) {
+        let start = self.fed - self.k_period.min(self.count);
+        while self.highs.front().is_some_and(|(at, _)| *at < start) {
+            self.highs.pop_front();
+        }
+        while self.lows.front().is_some_and(|(at, _)| *at < start) {
+            self.lows.pop_front();
+        }
+        let valid_k = (self.count + 1).saturating_sub(self.k_period);
+        while self.k_values.len() > self.d_period.min(valid_k) {
+            if let Some(k) = self.k_values.pop_front() {
+                self.k_sum.sub(k);
+            }
+        }
+    }
+
+    fn oscillator(&self) -> Option<IndicatorReading> {
+        if self.d_period == 0 || self.k_values.len() < self.d_period {
+            return None;
+        }
+        Some(IndicatorReading::Stochastic {
+            k: *self.k_values.back()?,
+            d: self.k_sum.value() / self.d_period as f64,
+        })
+    }
+}
+
+impl StreamingIndicator for StreamingStochastic {
+    fn update(&mut self, candle: &Candle) -> Option<f64> {
+        if self.k_period == 0 || self.d_period == 0 {
+            return None;
+        }
+        let (high, low) = (candle.high.value(), candle.low.value());
+        let position = self.fed;
+        self.fed += 1;
+        self.count += 1;
+        while self.highs.back().is_some_and(|(_, h)| *h <= high) {
+            self.highs.pop_back();
+        }
+        self.highs.push_back((position, high));
+        while self.lows.back().is_some_and(|(_, l)| *l >= low) {
+            self.lows.pop_back();
+        }
+        self.lows.push_back((position, low));
+        self.trim();
+        if self.count < self.k_period {
+            return None;
+        }
+
+        let highest = self.highs.front()?.1;
+        let lowest = self.lows.front()?.1;
+        let range = highest - lowest;
+        let k = if range > f64::EPSILON {
+            100.0 * (candle.close.value() - lowest) / range
+        } else {
+            50.0 // Neutral value when no price movement
+        };
+
+        self.k_values.push_back(k);
+        self.k_sum.add(k);
+        self.trim();
+        self.oscillator().map(|reading| reading.value())
+    }
+
+    fn evict(&mut self, _candle: &Candle) {
+        if self.k_period == 0 || self.d_period == 0 {
+            return;
+        }
+        self.count = self.count.saturating_sub(1);
+        self.trim();
+    }
+
+    fn reading(&self) -> Option<IndicatorReading> {
+        self.oscillator()
+    }
+}
+
+/// Streaming VWAP anchored at the oldest candle held
+#[derive(Debug, Clone, Default)]
+pub struct StreamingVwap {
+    /// Candles held
+    count: usize,
+    cumulative_volume: ExactSum,
+    cumulative_volume_price: ExactSum,
+    /// Typical price of the newest candle, the VWAP while no volume is held
+    last_typical_price: f64,
+    value: Option<f64>,
+}
+
+impl StreamingVwap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn typical_price(candle: &Candle) -> f64 {
+        (candle.high.value() + candle.low.value() + candle.close.value()) / 3.0
+    }
+
+    fn vwap(&self) -> f64 {
+        // Handle division by zero when cumulative volume is zero
+        let volume = self.cumulative_volume.value();
+        if volume > f64::EPSILON {
+            self.cumulative_volume_price.value() / volume
+        } else {
+            self.last_typical_price // Use typical price if no volume
+        }
+    }
+}
+
+impl StreamingIndicator for StreamingVwap {
+    fn update(&mut self, candle: &Candle) -> Option<f64> {
+        let typical_price = Self::typical_price(candle);
+        self.count += 1;
+        self.cumulative_volume.add(candle.volume);
+        self.cumulative_volume_price
+            .add_product(typical_price, candle.volume);
+        self.last_typical_price = typical_price;
+        self.value = Some(self.vwap());
+        self.value
+    }
+
+    fn evict(&mut self, candle: &Candle) {
+        self.count = self.count.saturating_sub(1);
+        self.cumulative_volume.sub(candle.volume);
+        self.cumulative_volume_price
+            .add_product(-Self::typical_price(candle), candle.volume);
+        self.value = (self.count > 0).then(|| self.vwap());
+    }
+
+    fn reading(&self) -> Option<IndicatorReading> {
+        self.value.map(IndicatorReading::Value)
+    }
+}
+
+/// Identifies a streaming indicator and its parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IndicatorSpec {
+    Ema(usize),
+    Rsi(usize),
+    Macd(usize, usize, usize),
+    Bollinger(usize, f64),
+    Stochastic(usize, usize),
+    Vwap,
+}
+
+impl IndicatorSpec {
+    /// Create a fresh streaming indicator for this spec
+    pub fn build(&self) -> Box<dyn StreamingIndicator> {
+        match *self {
+            IndicatorSpec::Ema(period) => Box::new(StreamingEma::new(period)),
+            IndicatorSpec::Rsi(period) => Box::new(StreamingRsi::new(period)),
+            IndicatorSpec::Macd(fast, slow, signal) => {
+                Box::new(StreamingMacd::new(fast, slow, signal))
+            }
+            IndicatorSpec::Bollinger(period, std_dev) => {
+                Box::new(StreamingBollinger::new(period, std_dev))
+            }
+            IndicatorSpec::Stochastic(k, d) => Box::new(StreamingStochastic::new(k, d)),
+            IndicatorSpec::Vwap => Box::new(StreamingVwap::new()),
+        }
+    }
+}
+
+/// Snapshot of the current readings for one symbol
+#[derive(Debug, Clone, Default)]
+pub struct IndicatorValues {
+    readings: Vec<(IndicatorSpec, IndicatorReading)>,
+}
+
+impl IndicatorValues {
+    /// Reading for a spec, if it is registered and warmed up
+    pub fn get(&self, spec: &IndicatorSpec) -> Option<IndicatorReading> {
+        self.readings
+            .iter()
+            .find(|(s, _)| s == spec)
+            .map(|(_, reading)| *reading)
+    }
+
+    /// Primary value for a spec
+    pub fn value(&self, spec: &IndicatorSpec) -> Option<f64> {
+        self.get(spec).map(|reading| reading.value())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.readings.is_empty()
+    }
+}
+
+/// The set of streaming indicators maintained for one symbol
+#[derive(Default)]
+pub struct IndicatorBank {
+    indicators: Vec<(IndicatorSpec, Box<dyn StreamingIndicator>)>,
+}
+
+impl IndicatorBank {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an indicator, warming it up on `history`; no-op if already present
+    pub fn register(&mut self, spec: IndicatorSpec, history: &[Candle]) {
+        if self.indicators.iter().any(|(s, _)| *s == spec) {
+            return;
+        }
+        let mut indicator = spec.build();
+        for candle in history {
+            indicator.update(candle);
+        }
+        self.indicators.push((spec, indicator));
+    }
+
+    /// Feed a completed candle to every indicator
+    pub fn update(&mut self, candle: &Candle) {
+        for (_, indicator) in &mut self.indicators {
+            indicator.update(candle);
+        }
+    }
+
+    /// Drop the oldest candle from every indicator
+    pub fn evict(&mut self, candle: &Candle) {
+        for (_, indicator) in &mut self.indicators {
+            indicator.evict(candle);
+        }
+    }
+
+    /// Current readings of the warmed-up indicators
+    pub fn values(&self) -> IndicatorValues {
+        IndicatorValues {
+            readings: self
+                .indicators
+                .iter()
+                .filter_map(|(spec, indicator)| indicator.reading().map(|r| (*spec, r)))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::indicators::{
+        BollingerBands, Indicator, StochasticOscillator, EMA, MACD, RSI, VWAP,
+    };
+
+    fn candles() -> Vec<Candle> {
+        (0..60)
+            .map(|i| {
+                let x = i as f64;
+                let close = 100.0 + (x * 0.7).sin() * 5.0 + x * 0.1;
+                let high = close + 1.0 + (x * 1.3).cos().abs();
+                let low = close - 1.0 - (x * 0.9).sin().abs();
+                Candle::new(close, high, low, close, 10.0 + (i % 7) as f64).unwrap()
+            })
+            .collect()
+    }
+
+    fn assert_same(streamed: Option<f64>, expected: Option<f64>, at: usize) {
+        assert_eq!(streamed, expected, "mismatch at candle {}", at);
+    }
+
+    fn bollinger_reading(candles: &[Candle]) -> Option<IndicatorReading> {
+        let bands = BollingerBands::new(10, 2.0).calculate_detailed(candles);
+        bands.upper.last().map(|&upper| IndicatorReading::Bands {
+            upper,
+            middle: *bands.middle.last().unwrap(),
+            lower: *bands.lower.last().unwrap(),
+        })
+    }
+
+    /// Feed candles one by one and compare with the batch result on each prefix
+    fn assert_matches_batch(
+        mut streaming: impl StreamingIndicator,
+        batch: impl Fn(&[Candle]) -> Option<f64>,
+    ) {
+        let candles = candles();
+        for (i, candle) in candles.iter().enumerate() {
+            let streamed = streaming.update(candle);
+            assert_same(streamed, batch(&candles[..=i]), i);
+        }
+    }
+
+    /// Keep only the last `window` candles and compare with the batch result
+    /// on that window after every update
+    fn assert_windowed_matches_batch(
+        mut streaming: impl StreamingIndicator,
+        window: usize,
+        batch: impl Fn(&[Candle]) -> Option<f64>,
+    ) {
+        let candles = candles();
+        for (i, candle) in candles.iter().enumerate() {
+            streaming.update(candle);
+            let start = (i + 1).saturating_sub(window);
+            if start > 0 {
+                streaming.evict(&candles[start - 1]);
+            }
+            let streamed = streaming.reading().map(|reading| reading.value());
+            assert_same(streamed, batch(&candles[start..=i]), i);
+        }
+    }
+
+    #[test]
+    fn test_streaming_ema_matches_batch() {
+        assert_matches_batch(StreamingEma::new(5), |c| {
+            EMA::new(5).calculate(c).last().copied()
+        });
+    }
+
+    #[test]
+    fn test_streaming_rsi_matches_batch() {
+        assert_matches_batch(StreamingRsi::new(7), |c| {
+            RSI::new(7).calculate(c).last().copied()
+        });
+    }
+
+    #[test]
+    fn test_streaming_macd_matches_batch() {
+        assert_matches_batch(StreamingMacd::new(6, 13, 5), |c| {
+            MACD::new(6, 13, 5).calculate(c).last().copied()
+        });
+    }
+
+    #[test]
+    fn test_streaming_bollinger_matches_batch() {
+        let candles = candles();
+        let mut streaming = StreamingBollinger::new(10, 2.0);
+        for (i, candle) in candles.iter().enumerate() {
+            streaming.update(candle);
+            assert_eq!(
+                streaming.reading(),
+                bollinger_reading(&candles[..=i]),
+                "mismatch at candle {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_windowed_bollinger_bands_match_batch() {
+        let candles = candles();
+        let mut streaming = StreamingBollinger::new(10, 2.0);
+        for (i, candle) in candles.iter().enumerate() {
+            streaming.update(candle);
+            let start = (i + 1).saturating_sub(12);
+            if start > 0 {
+                streaming.evict(&candles[start - 1]);
+            }
+            assert_eq!(
+                streaming.reading(),
+                bollinger_reading(&candles[start..=i]),
+                "mismatch at candle {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_windowed_vwap_without_volume_reads_the_newest_typical_price() {
+        let candles = vec![
+            Candle::new(100.0, 101.0, 99.0, 100.0, 5.0).unwrap(),
+            Candle::new(102.0, 104.0, 101.0, 103.0, 0.0).unwrap(),
+        ];
+        let mut streaming = StreamingVwap::new();
+        streaming.update(&candles[0]);
+        streaming.update(&candles[1]);
+        streaming.evict(&candles[0]);
+        assert_same(
+            streaming.reading().map(|reading| reading.value()),
+            VWAP.calculate(&candles[1..]).last().copied(),
+            1,
+        );
+    }
+
+    #[test]
+    fn test_streaming_stochastic_matches_batch() {
+        assert_matches_batch(StreamingStochastic::new(7, 3), |c| {
+            StochasticOscillator::new(7, 3).calculate(c).last().copied()
+        });
+    }
+
+    #[test]
+    fn test_streaming_vwap_matches_batch() {
+        assert_matches_batch(StreamingVwap::new(), |c| VWAP.calculate(c).last().copied());
+    }
+
+    #[test]
+    fn test_ema_and_macd_run_over_evicted_candles() {
+        let candles = candles();
+        let mut ema = StreamingEma::new(5);
+        let mut macd = StreamingMacd::new(6, 13, 5);
+        for (i, candle) in candles.iter().enumerate() {
+            ema.update(candle);
+            macd.update(candle);
+            if i >= 3 {
+                ema.evict(&candles[i - 3]);
+                macd.evict(&candles[i - 3]);
+            }
+            let fed = &candles[..=i];
+            assert_same(
+                ema.reading().map(|reading| reading.value()),
+                EMA::new(5).calculate(fed).last().copied(),
+                i,
+            );
+            assert_same(
+                macd.reading().map(|reading| reading.value()),
+                MACD::new(6, 13, 5).calculate(fed).last().copied(),
+                i,
+            );
+        }
+    }
+
+    #[test]
+    fn test_evicted_candles_leave_the_window() {
+        assert_windowed_matches_batch(StreamingRsi::new(7), 12, |c| {
+            RSI::new(7).calculate(c).last().copied()
+        });
+        assert_windowed_matches_batch(StreamingBollinger::new(10, 2.0), 12, |c| {
+            let bands = BollingerBands::new(10, 2.0).calculate_detailed(c);
+            bands.middle.last().copied()
+        });
+        assert_windowed_matches_batch(StreamingStochastic::new(7, 3), 9, |c| {
+            StochasticOscillator::new(7, 3).calculate(c).last().copied()
+        });
+        assert_windowed_matches_batch(StreamingVwap::new(), 12, |c| {
+            VWAP.calculate(c).last().copied()
+        });
+    }
+
+    #[test]
+    fn test_indicator_bank_warm_up_and_snapshot() {
+        let candles = candles();
+        let mut bank = IndicatorBank::new();
+        bank.register(IndicatorSpec::Ema(5), &candles[..30]);
+        bank.register(IndicatorSpec::Ema(5), &[]); // duplicate is ignored
+        bank.register(IndicatorSpec::Rsi(7), &candles[..30]);
+        for candle in &candles[30..] {
+            bank.update(candle);
+        }
+
+        let values = bank.values();
+        assert_same(
+            values.value(&IndicatorSpec::Ema(5)),
+            EMA::new(5).calculate(&candles).last().copied(),
+            candles.len(),
+        );
+        assert_same(
+            values.value(&IndicatorSpec::Rsi(7)),
+            RSI::new(7).calculate(&candles).last().copied(),
+            candles.len(),
+        );
+        assert!(values.get(&IndicatorSpec::Vwap).is_none());
+    }
+}
@@ -4,9 +4,15 @@ use std::time::{Duration, SystemTime};
 use tokio::sync::RwLock;
 use tracing::{debug, info};
 
+use crate::config::TradingConfig;
 use crate::domain::entities::symbol_screening::SymbolScreeningResult;
 use crate::domain::services::indicators::Candle;
-use crate::domain::services::screening::ScalpingPotentialAggregator;
+use crate::domain::services::screening::{
+    OrderBookDepth, ScalpingPotentialAggregator, ScreeningInput,
+};
+
+/// Symbol whose candles the correlation scorer compares against
+const BENCHMARK_SYMBOL: &str = "BTC-USD";
 
 /// Cache performance statistics
 #[derive(Clone, Debug, Default)]
@@ -29,12 +35,44 @@ impl CacheStats {
 }
 
 /// Market data for a symbol
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct SymbolMarketData {
     pub candles: Vec<Candle>,
     pub volumes: Vec<f64>,
     pub bid: f64,
     pub ask: f64,
+    /// Book levels near the top, when the venue publishes sizes
+    pub depth: Option<OrderBookDepth>,
+    pub trades_per_minute: Option<f64>,
+    /// Hourly funding rate, perpetual markets only
+    pub funding_rate: Option<f64>,
+}
+
+impl SymbolMarketData {
+    /// Scorer input over this data, compared against `benchmark` candles
+    pub fn input<'a>(&'a self, benchmark: Option<&'a [Candle]>) -> ScreeningInput<'a> {
+        ScreeningInput {
+            candles: &self.candles,
+            volumes: &self.volumes,
+            bid: self.bid,
+            ask: self.ask,
+            depth: self.depth.as_ref(),
+            trades_per_minute: self.trades_per_minute,
+            funding_rate: self.funding_rate,
+            benchmark,
+        }
+    }
+
+    /// Candles of the benchmark symbol among `symbols_data`, if screened
+    pub fn benchmark_candles<'a, I>(symbols_data: I) -> Option<&'a [Candle]>
+    where
+        I: IntoIterator<Item = (&'a String, &'a SymbolMarketData)>,
+    {
+        symbols_data
+            .into_iter()
+            .find(|(symbol, _)| TradingConfig::normalize_symbol(symbol) == BENCHMARK_SYMBOL)
+            .map(|(_, data)| data.candles.as_slice())
+    }
 }
 
 /// Cached screening result with timestamp
@@ -66,12 +104,34 @@ impl SymbolScreeningService {
         Self::new(Duration::from_secs(300)) // 5 minutes default
     }
 
+    pub fn with_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    pub fn with_aggregator(mut self, aggregator: ScalpingPotentialAggregator) -> Self {
+        self.aggregator = aggregator;
+        self
+    }
+
     /// Screen a single symbol
     pub async fn screen_symbol(
         &self,
         symbol: String,
         exchange: String,
         market_data: SymbolMarketData,
+    ) -> SymbolScreeningResult {
+        self.screen_against(symbol, exchange, &market_data, None)
+            .await
+    }
+
+    /// Screen a symbol, scoring correlation against `benchmark` candles
+    async fn screen_against(
+        &self,
+        symbol: String,
+        exchange: String,
+        market_data: &SymbolMarketData,
+        benchmark: Option<&[Candle]>,
     ) -> SymbolScreeningResult {
         let cache_key = format!("{}:{}", exchange, symbol);
 
@@ -106,13 +166,10 @@ impl SymbolScreeningService {
         );
 
         // Calculate fresh result
-        let result = self.aggregator.calculate(
+        let result = self.aggregator.score(
             symbol.clone(),
             exchange.clone(),
-            &market_data.candles,
-            &market_data.volumes,
-            market_data.bid,
-            market_data.ask,
+            &market_data.input(benchmark),
         );
 
         // Cache result
@@ -127,12 +184,13 @@ impl SymbolScreeningService {
             );
         }
 
+        let cache_size = self.cache.read().await.len();
         info!(
             symbol = %symbol,
             exchange = %exchange,
             overall_score = result.overall_score,
             recommendation = ?result.recommendation,
-            cache_size = self.cache.read().await.len(),
+            cache_size = cache_size,
             "Screening result calculated and cached"
         );
 
@@ -153,10 +211,11 @@ impl SymbolScreeningService {
         );
 
         let mut results = Vec::new();
+        let benchmark = SymbolMarketData::benchmark_candles(&symbols_data);
 
-        for (symbol, market_data) in symbols_data {
+        for (symbol, market_data) in &symbols_data {
             let result = self
-                .screen_symbol(symbol, exchange.clone(), market_data)
+                .screen_against(symbol.clone(), exchange.clone(), market_data, benchmark)
                 .await;
             results.push(result);
         }
@@ -223,6 +282,7 @@ mod tests {
             volumes: vec![500_000.0],
             bid: 100.0,
             ask: 100.1,
+            ..Default::default()
         };
 
         // First call - cache miss
@@ -259,6 +319,7 @@ mod tests {
             volumes: vec![500_000.0],
             bid: 100.0,
             ask: 100.1,
+            ..Default::default()
         };
 
         // Generate some cache activity
@@ -292,6 +353,7 @@ mod tests {
             volumes: vec![500_000.0],
             bid: 100.0,
             ask: 100.1,
+            ..Default::default()
         };
 
         let result = service
@@ -316,6 +378,7 @@ mod tests {
                     volumes: vec![500_000.0],
                     bid: 100.0,
                     ask: 100.1,
+                    ..Default::default()
                 },
             );
         }
@@ -340,6 +403,7 @@ mod tests {
             volumes: vec![500_000.0],
             bid: 100.0,
             ask: 100.1,
+            ..Default::default()
         };
 
         let result1 = service
@@ -368,6 +432,7 @@ mod tests {
             volumes: vec![500_000.0],
             bid: 100.0,
             ask: 100.1,
+            ..Default::default()
         };
 
         let result1 = service
@@ -399,6 +464,7 @@ mod tests {
             volumes: vec![],
             bid: 0.0,
             ask: 0.0,
+            ..Default::default()
         };
 
         let result = service
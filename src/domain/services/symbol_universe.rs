@@ -0,0 +1,349 @@
+//! Screening-driven symbol universe
+//!
+//! Instead of a fixed symbol list, the universe of an exchange follows its
+//! screening ranking: the top `BestCandidate` and `GoodCandidate` markets are
+//! subscribed, and a subscribed market is dropped only after it has stayed out
+//! of the top for the hysteresis period, so symbols hovering around the cut
+//! are not subscribed and unsubscribed on every run. Symbols from the static
+//! configuration and symbols with open positions are never dropped.
+
+use crate::config::TradingConfig;
+use crate::domain::entities::symbol_screening::{RecommendationCategory, SymbolScreeningResult};
+use crate::domain::services::indicators::Candle;
+use crate::domain::services::symbol_screening::SymbolMarketData;
+use crate::domain::value_objects::price::Price;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// 24h ticker of one market as listed by its exchange
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketSnapshot {
+    /// Symbol in the exchange's own spelling, as subscribed
+    pub symbol: String,
+    pub price: f64,
+    pub open_24h: f64,
+    pub high_24h: f64,
+    pub low_24h: f64,
+    /// Traded notional over 24h in the quote currency
+    pub quote_volume_24h: f64,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    /// Number of trades over 24h
+    pub trades_24h: Option<f64>,
+    /// Hourly funding rate of a perpetual market
+    pub funding_rate: Option<f64>,
+}
+
+impl MarketSnapshot {
+    /// Screening inputs of the snapshot: one 24h candle and its volume,
+    /// with the trade rate and funding when listed
+    ///
+    /// The candle spans a day, so it is to be scored with
+    /// `ScalpingPotentialAggregator::for_daily_tickers`, not with the scales
+    /// of the live candles. Returns None for a market without a usable price.
+    /// A market without a top of book gets a zero bid and ask, which scores
+    /// no spread.
+    pub fn market_data(&self) -> Option<SymbolMarketData> {
+        if self.price <= 0.0 || !self.price.is_finite() {
+            return None;
+        }
+        // Venues without a 24h range or open report zero for them
+        let open = if self.open_24h > 0.0 {
+            self.open_24h
+        } else {
+            self.price
+        };
+        let range = [open, self.price, self.high_24h, self.low_24h];
+        let priced = range.iter().copied().filter(|p| *p > 0.0);
+        let high = priced.clone().fold(f64::MIN, f64::max);
+        let low = priced.fold(f64::MAX, f64::min);
+        let candle = Candle {
+            open: Price::new(open).ok()?,
+            high: Price::new(high).ok()?,
+            low: Price::new(low).ok()?,
+            close: Price::new(self.price).ok()?,
+            volume: self.quote_volume_24h,
+        };
+        Some(SymbolMarketData {
+            candles: vec![candle],
+            volumes: vec![self.quote_volume_24h],
+            bid: self.bid.unwrap_or(0.0),
+            ask: self.ask.unwrap_or(0.0),
+            depth: None,
+            trades_per_minute: self.trades_per_minute(),
+            funding_rate: self.funding_rate,
+        })
+    }
+
+    /// Average trade rate over the 24h, when the trade count is listed
+    pub fn trades_per_minute(&self) -> Option<f64> {
+        self.trades_24h.map(|trades| trades / (24.0 * 60.0))
+    }
+}
+
+/// Size and hysteresis of a dynamic universe
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniverseParams {
+    /// Best and good candidates subscribed per exchange
+    pub top_n: usize,
+    /// Time a subscribed symbol may stay out of the top before it is dropped
+    pub hysteresis: Duration,
+}
+
+impl Default for UniverseParams {
+    fn default() -> Self {
+        Self {
+            top_n: 5,
+            hysteresis: Duration::minutes(30),
+        }
+    }
+}
+
+impl UniverseParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.top_n == 0 {
+            return Err("top_n must be at least 1".to_string());
+        }
+        if self.hysteresis < Duration::zero() {
+            return Err(format!(
+                "hysteresis ({} min) must not be negative",
+                self.hysteresis.num_minutes()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Subscriptions to add and remove after a screening run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UniverseChange {
+    pub subscribe: Vec<String>,
+    pub unsubscribe: Vec<String>,
+}
+
+/// Symbol added to a universe by screening
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UniverseMember {
+    pub symbol: String,
+    pub added_at: DateTime<Utc>,
+    /// Last run that ranked the symbol in the top
+    pub last_qualified: DateTime<Utc>,
+}
+
+/// Screening-driven symbols of one exchange
+#[derive(Debug, Clone)]
+pub struct SymbolUniverse {
+    params: UniverseParams,
+    /// Normalized symbols of the static configuration, never added or dropped
+    pinned: HashSet<String>,
+    members: HashMap<String, UniverseMember>,
+}
+
+impl SymbolUniverse {
+    pub fn new(params: UniverseParams, pinned: &[String]) -> Self {
+        Self {
+            params,
+            pinned: pinned
+                .iter()
+                .map(|s| TradingConfig::normalize_symbol(s))
+                .collect(),
+            members: HashMap::new(),
+        }
+    }
+
+    /// Apply a ranking, best first, and return the subscriptions to change
+    ///
+    /// `held` holds the normalized symbols with open positions; they stay
+    /// subscribed however long they have been out of the top.
+    pub fn update(
+        &mut self,
+        ranked: &[SymbolScreeningResult],
+        held: &HashSet<String>,
+        now: DateTime<Utc>,
+    ) -> UniverseChange {
+        let mut change = UniverseChange::default();
+
+        let qualified: Vec<&str> = ranked
+            .iter()
+            .filter(|r| {
+                matches!(
+                    r.recommendation,
+                    RecommendationCategory::BestCandidate | RecommendationCategory::GoodCandidate
+                )
+            })
+            .map(|r| r.symbol.as_str())
+            .filter(|s| !self.pinned.contains(&TradingConfig::normalize_symbol(s)))
+            .take(self.params.top_n)
+            .collect();
+
+        for symbol in &qualified {
+            match self.members.get_mut(*symbol) {
+                Some(member) => member.last_qualified = now,
+                None => {
+                    self.members.insert(
+                        symbol.to_string(),
+                        UniverseMember {
+                            symbol: symbol.to_string(),
+                            added_at: now,
+                            last_qualified: now,
+                        },
+                    );
+                    change.subscribe.push(symbol.to_string());
+                }
+            }
+        }
+
+        let hysteresis = self.params.hysteresis;
+        self.members.retain(|symbol, member| {
+            let decayed = member.last_qualified < now
+                && now.signed_duration_since(member.last_qualified) >= hysteresis
+                && !held.contains(&TradingConfig::normalize_symbol(symbol));
+            if decayed {
+                change.unsubscribe.push(symbol.clone());
+            }
+            !decayed
+        });
+        change.unsubscribe.sort();
+
+        change
+    }
+
+    /// Drop a symbol whose subscription failed so a later run retries it
+    pub fn forget(&mut self, symbol: &str) {
+        self.members.remove(symbol);
+    }
+
+    /// Symbols added by screening, most recently qualified first
+    pub fn members(&self) -> Vec<UniverseMember> {
+        let mut members: Vec<UniverseMember> = self.members.values().cloned().collect();
+        members.sort_by(|a, b| {
+            b.last_qualified
+                .cmp(&a.last_qualified)
+                .then(a.symbol.cmp(&b.symbol))
+        });
+        members
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranked(symbols: &[(&str, f64)]) -> Vec<SymbolScreeningResult> {
+        symbols
+            .iter()
+            .map(|(symbol, score)| {
+                SymbolScreeningResult::new(
+                    symbol.to_string(),
+                    "coinbase".to_string(),
+                    *score,
+                    *score,
+                    *score,
+                    *score,
+                )
+            })
+            .collect()
+    }
+
+    fn universe() -> SymbolUniverse {
+        SymbolUniverse::new(
+            UniverseParams {
+                top_n: 2,
+                hysteresis: Duration::minutes(30),
+            },
+            &["BTC-USD".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_top_candidates_are_subscribed_except_pinned() {
+        let mut universe = universe();
+        let now = Utc::now();
+        let change = universe.update(
+            &ranked(&[
+                ("BTC-USD", 0.9),
+                ("SOL-USD", 0.8),
+                ("AVAX-USD", 0.7),
+                ("LINK-USD", 0.65),
+                ("DOGE-USD", 0.3),
+            ]),
+            &HashSet::new(),
+            now,
+        );
+        assert_eq!(change.subscribe, vec!["SOL-USD", "AVAX-USD"]);
+        assert!(change.unsubscribe.is_empty());
+
+        // Fair candidates and worse never enter, however few better ones exist
+        let mut universe = SymbolUniverse::new(UniverseParams::default(), &[]);
+        let change = universe.update(&ranked(&[("DOGE-USD", 0.55)]), &HashSet::new(), now);
+        assert!(change.subscribe.is_empty());
+    }
+
+    #[test]
+    fn test_decayed_symbols_drop_after_hysteresis() {
+        let mut universe = universe();
+        let start = Utc::now();
+        universe.update(
+            &ranked(&[("SOL-USD", 0.8), ("AVAX-USD", 0.7)]),
+            &HashSet::new(),
+            start,
+        );
+
+        // AVAX falls out of the top but is kept within the hysteresis period
+        let fallen = ranked(&[("SOL-USD", 0.8), ("LINK-USD", 0.75), ("AVAX-USD", 0.4)]);
+        let change = universe.update(&fallen, &HashSet::new(), start + Duration::minutes(10));
+        assert_eq!(change.subscribe, vec!["LINK-USD"]);
+        assert!(change.unsubscribe.is_empty());
+
+        let change = universe.update(&fallen, &HashSet::new(), start + Duration::minutes(30));
+        assert!(change.subscribe.is_empty());
+        assert_eq!(change.unsubscribe, vec!["AVAX-USD"]);
+        let members: Vec<String> = universe.members().into_iter().map(|m| m.symbol).collect();
+        assert_eq!(members, vec!["LINK-USD", "SOL-USD"]);
+    }
+
+    #[test]
+    fn test_symbols_with_open_positions_are_kept() {
+        let mut universe = universe();
+        let start = Utc::now();
+        universe.update(&ranked(&[("SOLUSDT", 0.8)]), &HashSet::new(), start);
+
+        let held = HashSet::from(["SOL-USD".to_string()]);
+        let later = start + Duration::hours(5);
+        let change = universe.update(&ranked(&[("SOLUSDT", 0.2)]), &held, later);
+        assert!(change.unsubscribe.is_empty());
+
+        // Once the position is closed the symbol drops on the next run
+        let change = universe.update(&ranked(&[("SOLUSDT", 0.2)]), &HashSet::new(), later);
+        assert_eq!(change.unsubscribe, vec!["SOLUSDT"]);
+    }
+
+    #[test]
+    fn test_snapshot_market_data() {
+        let snapshot = MarketSnapshot {
+            symbol: "SOL-USD".to_string(),
+            price: 105.0,
+            open_24h: 100.0,
+            high_24h: 0.0, // Venue without a 24h range
+            low_24h: 0.0,
+            quote_volume_24h: 2_000_000.0,
+            bid: Some(104.95),
+            ask: None,
+            trades_24h: Some(2880.0),
+            funding_rate: None,
+        };
+        let data = snapshot.market_data().unwrap();
+        assert_eq!(data.candles[0].high.value(), 105.0);
+        assert_eq!(data.candles[0].low.value(), 100.0);
+        assert_eq!(data.volumes, vec![2_000_000.0]);
+        assert_eq!(data.ask, 0.0);
+        assert_eq!(data.trades_per_minute, Some(2.0));
+
+        let unpriced = MarketSnapshot {
+            price: 0.0,
+            ..snapshot
+        };
+        assert!(unpriced.market_data().is_none());
+    }
+}
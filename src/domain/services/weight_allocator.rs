@@ -0,0 +1,697 @@
+//! Online strategy weight allocation
+//!
+//! Treats every strategy as an arm of a multi-armed bandit whose rewards are
+//! the realized returns of its closed trades, taken from `StrategyMetrics`.
+//! The allocator keeps its own per-strategy statistics, fed with the metric
+//! deltas since the previous observation, so they can be persisted and
+//! restored across restarts while the in-memory metrics start from zero.
+//!
+//! Policies:
+//! - `thompson`: Beta-Bernoulli Thompson sampling on winning vs losing trades;
+//!   a strategy's weight is the share of posterior draws in which it is best.
+//! - `ucb`: UCB1 on the mean return per trade, normalized across the group,
+//!   with an exploration bonus that favours rarely traded strategies.
+//! - `performance`: no bandit; `SignalCombiner::adjust_weights` keeps its
+//!   smoothed `performance_score` normalization.
+//!
+//! Bandit weights are normalized per group, then bounded by the configured
+//! floors and caps with the remainder redistributed among the other strategies.
+//! Allocations are made from the arm statistics alone; the `performance`
+//! policy keeps its own minimum weight and ignores the floors and caps.
+//!
+//! Groups are only reallocated once new trades were observed, and a new
+//! allocation replaces the current weights only when some weight moves by at
+//! least `min_change`, so Thompson sampling noise is neither applied nor
+//! persisted and audited on every run.
+
+use crate::domain::services::metrics::StrategyMetrics;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AllocatorPolicy {
+    #[default]
+    Performance,
+    Thompson,
+    Ucb,
+}
+
+impl AllocatorPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AllocatorPolicy::Performance => "performance",
+            AllocatorPolicy::Thompson => "thompson",
+            AllocatorPolicy::Ucb => "ucb",
+        }
+    }
+}
+
+/// Per-strategy overrides of the default floor and cap
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StrategyWeightLimits {
+    #[serde(default)]
+    pub floor: Option<f64>,
+    #[serde(default)]
+    pub cap: Option<f64>,
+}
+
+/// Allocator section of the strategy registry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AllocatorConfig {
+    pub policy: AllocatorPolicy,
+    /// Minimum share of a group's weight for any strategy
+    pub floor: f64,
+    /// Maximum share of a group's weight for any strategy
+    pub cap: f64,
+    /// Floors and caps of individual strategies
+    pub limits: BTreeMap<String, StrategyWeightLimits>,
+    /// UCB exploration coefficient
+    pub exploration: f64,
+    /// Posterior draws per Thompson allocation
+    pub samples: usize,
+    /// Smallest weight move for a new allocation to replace a group's weights
+    pub min_change: f64,
+}
+
+impl Default for AllocatorConfig {
+    fn default() -> Self {
+        Self {
+            policy: AllocatorPolicy::Performance,
+            floor: 0.05,
+            cap: 1.0,
+            limits: BTreeMap::new(),
+            exploration: 1.0,
+            samples: 1000,
+            min_change: 0.02,
+        }
+    }
+}
+
+impl AllocatorConfig {
+    /// Check bounds and that overrides name registered strategies
+    pub fn validate(&self, strategy_names: &[String]) -> Result<(), String> {
+        let check = |label: &str, floor: f64, cap: f64| {
+            if !(0.0..=1.0).contains(&floor) || !(0.0..=1.0).contains(&cap) || floor > cap {
+                return Err(format!(
+                    "{}: floor ({}) and cap ({}) must be within 0-1 with floor at most cap",
+                    label, floor, cap
+                ));
+            }
+            Ok(())
+        };
+        check("allocator", self.floor, self.cap)?;
+        for (name, limits) in &self.limits {
+            if !strategy_names.contains(name) {
+                return Err(format!("allocator limits for unknown strategy '{}'", name));
+            }
+            check(
+                &format!("allocator limits for '{}'", name),
+                limits.floor.unwrap_or(self.floor),
+                limits.cap.unwrap_or(self.cap),
+            )?;
+        }
+        if !(self.exploration >= 0.0 && self.exploration.is_finite()) {
+            return Err(format!(
+                "allocator exploration ({}) must be zero or positive",
+                self.exploration
+            ));
+        }
+        if self.samples == 0 {
+            return Err("allocator samples must be positive".to_string());
+        }
+        if !(0.0..1.0).contains(&self.min_change) {
+            return Err(format!(
+                "allocator min_change ({}) must be within 0-1",
+                self.min_change
+            ));
+        }
+        Ok(())
+    }
+
+    /// Effective floor and cap of a strategy
+    pub fn limits_for(&self, strategy: &str) -> (f64, f64) {
+        let limits = self.limits.get(strategy);
+        (
+            limits.and_then(|l| l.floor).unwrap_or(self.floor),
+            limits.and_then(|l| l.cap).unwrap_or(self.cap),
+        )
+    }
+}
+
+/// Closed-trade statistics of one strategy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArmStats {
+    pub trades: u32,
+    pub wins: u32,
+    pub losses: u32,
+    /// Sum of realized trade PnL
+    pub total_return: f64,
+    /// Metric counters at the last observation; not persisted since the
+    /// metrics restart from zero with the process
+    #[serde(skip)]
+    seen_wins: u32,
+    #[serde(skip)]
+    seen_losses: u32,
+    #[serde(skip)]
+    seen_profit: f64,
+    #[serde(skip)]
+    seen_loss: f64,
+}
+
+impl ArmStats {
+    pub fn mean_return(&self) -> Option<f64> {
+        (self.trades > 0).then(|| self.total_return / self.trades as f64)
+    }
+
+    /// Fold in the trades closed since the last observation, returning
+    /// whether there were any
+    ///
+    /// Counters below the last seen values mean the metrics were reset (e.g.
+    /// after a restart), so everything they hold is new.
+    fn observe(&mut self, metrics: &StrategyMetrics) -> bool {
+        if metrics.winning_trades < self.seen_wins || metrics.losing_trades < self.seen_losses {
+            self.reset_baseline();
+        }
+        let new_wins = metrics.winning_trades - self.seen_wins;
+        let new_losses = metrics.losing_trades - self.seen_losses;
+        if new_wins + new_losses == 0 {
+            return false;
+        }
+        self.trades += new_wins + new_losses;
+        self.wins += new_wins;
+        self.losses += new_losses;
+        self.total_return +=
+            (metrics.gross_profit - self.seen_profit) - (metrics.gross_loss - self.seen_loss);
+        self.seen_wins = metrics.winning_trades;
+        self.seen_losses = metrics.losing_trades;
+        self.seen_profit = metrics.gross_profit;
+        self.seen_loss = metrics.gross_loss;
+        true
+    }
+
+    fn reset_baseline(&mut self) {
+        self.seen_wins = 0;
+        self.seen_losses = 0;
+        self.seen_profit = 0.0;
+        self.seen_loss = 0.0;
+    }
+}
+
+/// Scores each strategy of a group; weights are proportional to the scores
+pub trait AllocationPolicy: Send + Sync {
+    fn scores(&mut self, arms: &[ArmStats]) -> Vec<f64>;
+}
+
+/// Probability matching on Beta(wins + 1, losses + 1) posteriors
+pub struct ThompsonSampling {
+    samples: usize,
+    rng: StdRng,
+}
+
+impl ThompsonSampling {
+    pub fn new(samples: usize) -> Self {
+        Self {
+            samples,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn with_seed(samples: usize, seed: u64) -> Self {
+        Self {
+            samples,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl AllocationPolicy for ThompsonSampling {
+    fn scores(&mut self, arms: &[ArmStats]) -> Vec<f64> {
+        let mut best_counts = vec![0.0; arms.len()];
+        for _ in 0..self.samples {
+            let draws: Vec<f64> = arms
+                .iter()
+                .map(|arm| {
+                    sample_beta(
+                        &mut self.rng,
+                        arm.wins as f64 + 1.0,
+                        arm.losses as f64 + 1.0,
+                    )
+                })
+                .collect();
+            if let Some(best) = draws
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(i, _)| i)
+            {
+                best_counts[best] += 1.0;
+            }
+        }
+        best_counts
+    }
+}
+
+/// UCB1 on mean trade return normalized to 0-1 across the group
+pub struct Ucb1 {
+    exploration: f64,
+}
+
+impl Ucb1 {
+    pub fn new(exploration: f64) -> Self {
+        Self { exploration }
+    }
+}
+
+impl AllocationPolicy for Ucb1 {
+    fn scores(&mut self, arms: &[ArmStats]) -> Vec<f64> {
+        let total_trades: u32 = arms.iter().map(|a| a.trades).sum();
+        let means: Vec<f64> = arms.iter().filter_map(|a| a.mean_return()).collect();
+        let lowest = means.iter().cloned().fold(f64::INFINITY, f64::min);
+        let highest = means.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let log_total = ((total_trades.max(1)) as f64).ln();
+
+        arms.iter()
+            .map(|arm| match arm.mean_return() {
+                // Never traded: optimistic mean and the largest bonus
+                None => 1.0 + self.exploration * (2.0 * (log_total + 1.0)).sqrt(),
+                Some(mean) => {
+                    let normalized = if highest - lowest > f64::EPSILON {
+                        (mean - lowest) / (highest - lowest)
+                    } else {
+                        0.5
+                    };
+                    normalized + self.exploration * (2.0 * log_total / arm.trades as f64).sqrt()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Persisted allocator state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AllocatorState {
+    pub policy: AllocatorPolicy,
+    pub arms: BTreeMap<String, ArmStats>,
+}
+
+/// Bandit allocator shared by every signal combiner
+pub struct WeightAllocator {
+    config: AllocatorConfig,
+    policy: Box<dyn AllocationPolicy>,
+    arms: BTreeMap<String, ArmStats>,
+    /// Statistics changed since the groups were last reallocated
+    stale: bool,
+}
+
+impl WeightAllocator {
+    /// Allocator for a bandit policy; None for `performance`, which keeps
+    /// `SignalCombiner::adjust_weights`
+    pub fn from_config(config: AllocatorConfig) -> Option<Self> {
+        let policy: Box<dyn AllocationPolicy> = match config.policy {
+            AllocatorPolicy::Performance => return None,
+            AllocatorPolicy::Thompson => Box::new(ThompsonSampling::new(config.samples)),
+            AllocatorPolicy::Ucb => Box::new(Ucb1::new(config.exploration)),
+        };
+        Some(Self::with_policy(config, policy))
+    }
+
+    pub fn with_policy(config: AllocatorConfig, policy: Box<dyn AllocationPolicy>) -> Self {
+        Self {
+            config,
+            policy,
+            arms: BTreeMap::new(),
+            stale: true,
+        }
+    }
+
+    pub fn arms(&self) -> &BTreeMap<String, ArmStats> {
+        &self.arms
+    }
+
+    pub fn min_change(&self) -> f64 {
+        self.config.min_change
+    }
+
+    /// Update arm statistics from the current strategy metrics
+    pub fn observe(&mut self, metrics: &HashMap<String, StrategyMetrics>) {
+        for (name, metrics) in metrics {
+            if self.arms.entry(name.clone()).or_default().observe(metrics) {
+                self.stale = true;
+            }
+        }
+    }
+
+    /// Whether the statistics changed since the last call, i.e. the groups
+    /// should be reallocated and the state persisted
+    pub fn take_stale(&mut self) -> bool {
+        std::mem::take(&mut self.stale)
+    }
+
+    /// Target weights for the strategies of one group, in order, summing to 1
+    pub fn allocate(&mut self, strategies: &[String]) -> Result<Vec<f64>, String> {
+        if strategies.is_empty() {
+            return Err("cannot allocate weights without strategies".to_string());
+        }
+        let arms: Vec<ArmStats> = strategies
+            .iter()
+            .map(|name| self.arms.get(name).cloned().unwrap_or_default())
+            .collect();
+        let scores = self.policy.scores(&arms);
+
+        let (floors, caps): (Vec<f64>, Vec<f64>) = strategies
+            .iter()
+            .map(|name| self.config.limits_for(name))
+            .unzip();
+        Ok(apply_limits(&scores, &floors, &caps))
+    }
+
+    pub fn state(&self) -> AllocatorState {
+        AllocatorState {
+            policy: self.config.policy,
+            arms: self.arms.clone(),
+        }
+    }
+
+    /// Resume from persisted statistics (kept when the policy changed)
+    pub fn restore(&mut self, state: AllocatorState) {
+        self.arms = state.arms;
+        self.stale = true;
+    }
+}
+
+/// Normalize scores to weights within per-strategy floors and caps
+///
+/// Strategies pushed past a bound are pinned to it and the remaining weight is
+/// shared among the others in proportion to their scores. Bounds a group
+/// cannot meet (floors summing above 1, caps below 1) are scaled to fit, so a
+/// single-strategy group always keeps its full weight.
+pub fn apply_limits(scores: &[f64], floors: &[f64], caps: &[f64]) -> Vec<f64> {
+    let n = scores.len();
+    let floor_sum: f64 = floors.iter().sum();
+    let floors: Vec<f64> = if floor_sum > 1.0 {
+        floors.iter().map(|f| f / floor_sum).collect()
+    } else {
+        floors.to_vec()
+    };
+    let cap_sum: f64 = caps.iter().sum();
+    let caps: Vec<f64> = if cap_sum < 1.0 {
+        caps.iter()
+            .zip(&floors)
+            .map(|(c, f)| if cap_sum > 0.0 { c / cap_sum } else { 1.0 }.max(*f))
+            .collect()
+    } else {
+        caps.to_vec()
+    };
+
+    let scores: Vec<f64> = scores
+        .iter()
+        .map(|s| if s.is_finite() { s.max(0.0) } else { 0.0 })
+        .collect();
+    let mut pinned: Vec<Option<f64>> = vec![None; n];
+    loop {
+        let remaining = 1.0 - pinned.iter().flatten().sum::<f64>();
+        let free: Vec<usize> = (0..n).filter(|&i| pinned[i].is_none()).collect();
+        if free.is_empty() {
+            break;
+        }
+        let free_score: f64 = free.iter().map(|&i| scores[i]).sum();
+        let share = |i: usize| {
+            if free_score > 0.0 {
+                remaining * scores[i] / free_score
+            } else {
+                remaining / free.len() as f64
+            }
+        };
+
+        // Caps first: capping frees weight for the others, while pinning a
+        // floor only takes weight away, so neither undoes the other
+        let over: Vec<usize> = free
+            .iter()
+            .copied()
+            .filter(|&i| share(i) > caps[i])
+            .collect();
+        let under: Vec<usize> = free
+            .iter()
+            .copied()
+            .filter(|&i| share(i) < floors[i])
+            .collect();
+        if !over.is_empty() {
+            for i in over {
+                pinned[i] = Some(caps[i]);
+            }
+        } else if !under.is_empty() {
+            for i in under {
+                pinned[i] = Some(floors[i]);
+            }
+        } else {
+            for &i in &free {
+                pinned[i] = Some(share(i));
+            }
+            break;
+        }
+    }
+
+    let weights: Vec<f64> = pinned.into_iter().map(|w| w.unwrap_or(0.0)).collect();
+    let total: f64 = weights.iter().sum();
+    if total > 0.0 && (total - 1.0).abs() > 1e-9 {
+        weights.iter().map(|w| w / total).collect()
+    } else {
+        weights
+    }
+}
+
+/// Beta(alpha, beta) draw from two gamma draws
+fn sample_beta(rng: &mut StdRng, alpha: f64, beta: f64) -> f64 {
+    let x = sample_gamma(rng, alpha);
+    let y = sample_gamma(rng, beta);
+    if x + y > 0.0 {
+        x / (x + y)
+    } else {
+        0.5
+    }
+}
+
+/// Gamma(shape, 1) draw (Marsaglia and Tsang), for shape >= 1
+fn sample_gamma(rng: &mut StdRng, shape: f64) -> f64 {
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let x = sample_standard_normal(rng);
+        let v = (1.0 + c * x).powi(3);
+        if v <= 0.0 {
+            continue;
+        }
+        let u: f64 = rng.gen();
+        if u.ln() < 0.5 * x * x + d - d * v + d * v.ln() {
+            return d * v;
+        }
+    }
+}
+
+/// Standard normal draw (Box-Muller)
+fn sample_standard_normal(rng: &mut StdRng) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(wins: u32, losses: u32, pnl_per_trade: f64) -> StrategyMetrics {
+        let mut metrics = StrategyMetrics::new("test".to_string());
+        for _ in 0..wins {
+            metrics.record_trade_outcome(pnl_per_trade.abs());
+        }
+        for _ in 0..losses {
+            metrics.record_trade_outcome(-pnl_per_trade.abs());
+        }
+        metrics
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    fn allocator(config: AllocatorConfig, policy: Box<dyn AllocationPolicy>) -> WeightAllocator {
+        WeightAllocator::with_policy(config, policy)
+    }
+
+    #[test]
+    fn test_limits_pin_and_redistribute() {
+        let weights = apply_limits(&[8.0, 1.0, 1.0], &[0.1; 3], &[0.6; 3]);
+        assert!((weights[0] - 0.6).abs() < 1e-9);
+        assert!((weights[1] - 0.2).abs() < 1e-9);
+        assert!((weights[2] - 0.2).abs() < 1e-9);
+
+        let weights = apply_limits(&[1.0, 0.0], &[0.05, 0.05], &[0.7, 0.7]);
+        assert!((weights[0] - 0.7).abs() < 1e-9);
+        assert!((weights[1] - 0.3).abs() < 1e-9);
+
+        // Without any score the weight is shared equally
+        assert_eq!(
+            apply_limits(&[0.0, 0.0], &[0.0; 2], &[1.0; 2]),
+            vec![0.5, 0.5]
+        );
+    }
+
+    #[test]
+    fn test_unsatisfiable_limits_are_scaled() {
+        // A single strategy keeps everything despite its cap
+        assert_eq!(apply_limits(&[1.0], &[0.05], &[0.5]), vec![1.0]);
+        // Floors summing above 1 are shrunk to fit
+        let weights = apply_limits(&[1.0, 0.0], &[0.8, 0.4], &[1.0, 1.0]);
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(weights[1] > 0.3);
+    }
+
+    #[test]
+    fn test_observe_accumulates_deltas_across_metric_resets() {
+        let mut allocator = allocator(AllocatorConfig::default(), Box::new(Ucb1::new(1.0)));
+        let mut current = HashMap::from([("A".to_string(), metrics(2, 1, 1.0))]);
+        allocator.observe(&current);
+        allocator.observe(&current); // Nothing new
+        assert_eq!(allocator.arms()["A"].trades, 3);
+        assert_eq!(allocator.arms()["A"].wins, 2);
+        assert!((allocator.arms()["A"].total_return - 1.0).abs() < 1e-9);
+
+        current.insert("A".to_string(), metrics(3, 1, 1.0));
+        allocator.observe(&current);
+        assert_eq!(allocator.arms()["A"].wins, 3);
+        assert_eq!(allocator.arms()["A"].trades, 4);
+
+        // Restored statistics plus a fresh process's metrics
+        let state = allocator.state();
+        let mut restarted = WeightAllocator::from_config(AllocatorConfig {
+            policy: AllocatorPolicy::Ucb,
+            ..Default::default()
+        })
+        .unwrap();
+        restarted.restore(serde_json::from_value(serde_json::to_value(&state).unwrap()).unwrap());
+        restarted.observe(&HashMap::from([("A".to_string(), metrics(0, 1, 2.0))]));
+        assert_eq!(restarted.arms()["A"].trades, 5);
+        assert_eq!(restarted.arms()["A"].losses, 2);
+        assert!((restarted.arms()["A"].total_return - 0.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_thompson_favours_the_winning_strategy() {
+        let mut allocator = allocator(
+            AllocatorConfig::default(),
+            Box::new(ThompsonSampling::with_seed(2000, 7)),
+        );
+        allocator.observe(&HashMap::from([
+            ("Good".to_string(), metrics(30, 10, 1.0)),
+            ("Bad".to_string(), metrics(10, 30, 1.0)),
+        ]));
+        let weights = allocator.allocate(&names(&["Good", "Bad", "New"])).unwrap();
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(weights[0] > weights[2], "{:?}", weights);
+        // The losing strategy keeps its floor so it can still be re-evaluated
+        assert!((weights[1] - 0.05).abs() < 1e-9, "{:?}", weights);
+        // The untried strategy is explored
+        assert!(weights[2] > 0.05, "{:?}", weights);
+    }
+
+    #[test]
+    fn test_only_new_trades_mark_the_allocator_stale() {
+        let mut allocator = allocator(AllocatorConfig::default(), Box::new(Ucb1::new(1.0)));
+        assert!(allocator.take_stale()); // A fresh allocator has never allocated
+        assert!(!allocator.take_stale());
+
+        let current = HashMap::from([("A".to_string(), metrics(2, 1, 1.0))]);
+        allocator.observe(&current);
+        assert!(allocator.take_stale());
+        allocator.observe(&current); // Nothing new
+        assert!(!allocator.take_stale());
+
+        allocator.restore(allocator.state());
+        assert!(allocator.take_stale());
+    }
+
+    #[test]
+    fn test_ucb_explores_untried_and_ranks_by_return() {
+        let mut allocator = allocator(
+            AllocatorConfig {
+                floor: 0.0,
+                ..Default::default()
+            },
+            Box::new(Ucb1::new(0.5)),
+        );
+        allocator.observe(&HashMap::from([
+            ("Big".to_string(), metrics(5, 5, 10.0)),
+            ("Small".to_string(), metrics(8, 2, 1.0)),
+        ]));
+        // Same win rate but returns decide: Big nets 0, Small nets +6
+        let weights = allocator
+            .allocate(&names(&["Big", "Small", "New"]))
+            .unwrap();
+        assert!(weights[1] > weights[0], "{:?}", weights);
+        assert!(weights[2] > weights[1], "{:?}", weights);
+    }
+
+    #[test]
+    fn test_per_strategy_limits_override_defaults() {
+        let config = AllocatorConfig {
+            policy: AllocatorPolicy::Ucb,
+            limits: BTreeMap::from([(
+                "Capped".to_string(),
+                StrategyWeightLimits {
+                    floor: None,
+                    cap: Some(0.3),
+                },
+            )]),
+            ..Default::default()
+        };
+        let mut allocator = WeightAllocator::from_config(config).unwrap();
+        allocator.observe(&HashMap::from([
+            ("Capped".to_string(), metrics(20, 0, 1.0)),
+            ("Other".to_string(), metrics(0, 20, 1.0)),
+        ]));
+        let weights = allocator.allocate(&names(&["Capped", "Other"])).unwrap();
+        assert!((weights[0] - 0.3).abs() < 1e-9, "{:?}", weights);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let strategies = names(&["A"]);
+        assert!(AllocatorConfig::default().validate(&strategies).is_ok());
+        assert!(WeightAllocator::from_config(AllocatorConfig::default()).is_none());
+        let invalid = [
+            AllocatorConfig {
+                floor: -0.1,
+                ..Default::default()
+            },
+            AllocatorConfig {
+                floor: 0.5,
+                cap: 0.4,
+                ..Default::default()
+            },
+            AllocatorConfig {
+                limits: BTreeMap::from([("B".to_string(), StrategyWeightLimits::default())]),
+                ..Default::default()
+            },
+            AllocatorConfig {
+                exploration: -1.0,
+                ..Default::default()
+            },
+            AllocatorConfig {
+                samples: 0,
+                ..Default::default()
+            },
+            AllocatorConfig {
+                min_change: 1.0,
+                ..Default::default()
+            },
+        ];
+        for config in invalid {
+            assert!(config.validate(&strategies).is_err(), "{:?}", config);
+        }
+    }
+}
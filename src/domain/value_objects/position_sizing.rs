@@ -176,6 +176,124 @@ impl PositionSizingResult {
     }
 }
 
+/// Algorithm used to size a new position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SizingMode {
+    /// Fixed share of the portfolio per position (legacy behaviour)
+    #[default]
+    FixedPercentage,
+    /// Risk a fixed fraction of equity given the distance to the stop
+    FixedFractionalRisk,
+    /// Risk a fixed fraction of equity with the stop placed at a multiple of ATR
+    AtrScaled,
+    /// Scale the position so its annualised volatility matches a target
+    VolatilityTarget,
+    /// Fractional Kelly bet derived from the strategy's win rate and payoff ratio
+    Kelly,
+}
+
+impl SizingMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SizingMode::FixedPercentage => "fixed",
+            SizingMode::FixedFractionalRisk => "fixed_risk",
+            SizingMode::AtrScaled => "atr",
+            SizingMode::VolatilityTarget => "vol_target",
+            SizingMode::Kelly => "kelly",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "fixed" | "fixed_percentage" => Ok(SizingMode::FixedPercentage),
+            "fixed_risk" | "fixed_fractional" => Ok(SizingMode::FixedFractionalRisk),
+            "atr" | "atr_scaled" => Ok(SizingMode::AtrScaled),
+            "vol_target" | "volatility_target" => Ok(SizingMode::VolatilityTarget),
+            "kelly" => Ok(SizingMode::Kelly),
+            other => Err(format!("unknown sizing mode '{}'", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for SizingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Tunables shared by the sizing modes
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizingParams {
+    /// Share of equity per position in `FixedPercentage` mode
+    pub portfolio_fraction: f64,
+    /// Share of equity lost if the stop is hit (risk-based modes)
+    pub risk_per_trade: f64,
+    /// Stop distance expressed in ATRs (`AtrScaled`)
+    pub atr_multiple: f64,
+    /// Candles used to compute the ATR
+    pub atr_period: usize,
+    /// Annualised volatility targeted per position (`VolatilityTarget`)
+    pub target_annual_volatility: f64,
+    /// Fraction of the full Kelly bet actually taken
+    pub kelly_fraction: f64,
+    /// Upper bound on the Kelly share of equity
+    pub kelly_cap: f64,
+    /// Closed trades required before Kelly sizing is trusted
+    pub kelly_min_trades: u32,
+    /// Upper bound on the notional of any risk-based position, as a share of equity
+    pub max_position_fraction: f64,
+}
+
+impl Default for SizingParams {
+    fn default() -> Self {
+        Self {
+            portfolio_fraction: 0.02,
+            risk_per_trade: 0.01,
+            atr_multiple: 2.0,
+            atr_period: 14,
+            target_annual_volatility: 0.2,
+            kelly_fraction: 0.5,
+            kelly_cap: 0.1,
+            kelly_min_trades: 20,
+            max_position_fraction: 0.25,
+        }
+    }
+}
+
+/// Market and performance inputs available when sizing a position
+///
+/// Only `equity` and `price` are required; each mode fails when the
+/// optional input it depends on is missing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SizingInputs {
+    /// Portfolio equity
+    pub equity: f64,
+    /// Expected entry price
+    pub price: f64,
+    /// Absolute distance between entry and stop
+    pub stop_distance: Option<f64>,
+    /// Average true range of the symbol
+    pub atr: Option<f64>,
+    /// Realised annualised volatility of the symbol
+    pub annualized_volatility: Option<f64>,
+    /// Strategy win rate in [0, 1]
+    pub win_rate: Option<f64>,
+    /// Average win divided by average loss
+    pub payoff_ratio: Option<f64>,
+    /// Number of closed trades behind `win_rate` and `payoff_ratio`
+    pub closed_trades: u32,
+}
+
+impl SizingInputs {
+    pub fn new(equity: f64, price: f64) -> Self {
+        Self {
+            equity,
+            price,
+            ..Self::default()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
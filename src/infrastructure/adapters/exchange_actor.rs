@@ -1,5 +1,6 @@
 use crate::domain::entities::exchange::Exchange;
 use crate::domain::entities::order::Order;
+use crate::domain::repositories::exchange_client::{ExchangeClient, OrderFill};
 use crate::domain::services::circuit_breaker::{
     CircuitBreaker, CircuitBreakerConfig, CircuitState,
 };
@@ -25,12 +26,26 @@ pub enum SubscriptionCommand {
     Unsubscribe(String),
 }
 
+/// Best bid and ask of a symbol from the exchange's ticker feed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BestQuote {
+    pub bid: Price,
+    pub ask: Price,
+    /// Quantities resting at the best bid and ask, when the feed reports them
+    pub bid_size: Option<f64>,
+    pub ask_size: Option<f64>,
+}
+
 #[derive(Debug)]
 pub enum ExchangeMessage {
     GetPrice {
         symbol: String,
         reply: mpsc::Sender<Result<Price, String>>,
     },
+    GetBestQuote {
+        symbol: String,
+        reply: mpsc::Sender<Result<BestQuote, String>>,
+    },
     Subscribe {
         symbol: String,
         reply: mpsc::Sender<Result<(), String>>,
@@ -60,12 +75,18 @@ pub enum ExchangeMessage {
         order_id: String,
         reply: mpsc::Sender<Result<String, String>>,
     },
+    /// Order status together with the filled quantity
+    GetOrderFill {
+        order_id: String,
+        reply: mpsc::Sender<Result<OrderFill, String>>,
+    },
     Shutdown,
 }
 
 pub struct ExchangeActor {
     pub exchange: Exchange,
     pub prices: Arc<Mutex<HashMap<String, Price>>>,
+    pub quotes: Arc<Mutex<HashMap<String, BestQuote>>>,
     pub subscriptions: Arc<Mutex<HashSet<String>>>,
     pub shutdown_tx: broadcast::Sender<()>,
     pub subscription_tx: mpsc::Sender<SubscriptionCommand>,
@@ -102,6 +123,7 @@ impl ActivityTracker {
 impl ExchangeActor {
     pub fn spawn(exchange: Exchange) -> mpsc::Sender<ExchangeMessage> {
         let prices = Arc::new(Mutex::new(HashMap::new()));
+        let quotes = Arc::new(Mutex::new(HashMap::new()));
         let subscriptions = Arc::new(Mutex::new(HashSet::new()));
         let (tx, rx) = mpsc::channel(100);
         let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
@@ -225,6 +247,7 @@ impl ExchangeActor {
         let actor = Self {
             exchange,
             prices: prices.clone(),
+            quotes: quotes.clone(),
             subscriptions: subscriptions.clone(),
             shutdown_tx: shutdown_tx.clone(),
             subscription_tx: subscription_tx.clone(),
@@ -244,6 +267,7 @@ impl ExchangeActor {
             Self::run_websocket(
                 exchange_clone,
                 prices,
+                quotes,
                 subscriptions,
                 subscription_rx,
                 shutdown_rx,
@@ -285,6 +309,16 @@ impl ExchangeActor {
                         warn!("Failed to send reply: {:?}", e);
                     }
                 }
+                ExchangeMessage::GetBestQuote { symbol, reply } => {
+                    let quotes = self.quotes.lock().await;
+                    let result = quotes
+                        .get(&symbol)
+                        .copied()
+                        .ok_or_else(|| format!("No bid/ask available for symbol: {}", symbol));
+                    if let Err(e) = reply.send(result).await {
+                        warn!("Failed to send reply: {:?}", e);
+                    }
+                }
                 ExchangeMessage::Subscribe { symbol, reply } => {
                     info!(
                         "Subscribe request for {}: {}",
@@ -476,6 +510,45 @@ impl ExchangeActor {
                         warn!("Failed to send reply: {:?}", e);
                     }
                 }
+                ExchangeMessage::GetOrderFill { order_id, reply } => {
+                    debug!(
+                        "Get order fill request for {}: {}",
+                        Self::get_exchange_name(&self.exchange),
+                        order_id
+                    );
+
+                    let result = match &self.exchange {
+                        Exchange::Dydx => {
+                            if let Some(client) = &self.dydx_client {
+                                client.get_order_fill(&order_id).await
+                            } else {
+                                Err("dYdX client not initialized - check DYDX_MNEMONIC".to_string())
+                            }
+                        }
+                        Exchange::Coinbase => {
+                            // Try Advanced Trade client first, fallback to Pro client
+                            if let Some(client) = &self.coinbase_advanced_client {
+                                ExchangeClient::get_order_fill(client, &order_id)
+                                    .await
+                                    .map_err(|e| e.to_string())
+                            } else if let Some(client) = &self.coinbase_client {
+                                ExchangeClient::get_order_fill(client, &order_id)
+                                    .await
+                                    .map_err(|e| e.to_string())
+                            } else {
+                                Err("Coinbase client not initialized - check COINBASE_CLOUD_API_KEY/COINBASE_API_KEY".to_string())
+                            }
+                        }
+                        _ => Err(format!(
+                            "Order status not implemented for {:?}",
+                            self.exchange
+                        )),
+                    };
+
+                    if let Err(e) = reply.send(result).await {
+                        warn!("Failed to send reply: {:?}", e);
+                    }
+                }
                 ExchangeMessage::Shutdown => {
                     info!(
                         "Shutdown signal received for exchange: {}",
@@ -497,6 +570,7 @@ impl ExchangeActor {
     async fn run_websocket(
         exchange: Exchange,
         prices: Arc<Mutex<HashMap<String, Price>>>,
+        quotes: Arc<Mutex<HashMap<String, BestQuote>>>,
         subscriptions: Arc<Mutex<HashSet<String>>>,
         mut subscription_rx: mpsc::Receiver<SubscriptionCommand>,
         mut shutdown_rx: broadcast::Receiver<()>,
@@ -514,7 +588,7 @@ impl ExchangeActor {
             );
 
             tokio::select! {
-                result = Self::try_websocket_connection(&exchange, &prices, &subscriptions, &mut subscription_rx, &activity_tracker) => {
+                result = Self::try_websocket_connection(&exchange, &prices, &quotes, &subscriptions, &mut subscription_rx, &activity_tracker) => {
                     match result {
                         Ok(()) => {
                             info!("WebSocket connection ended normally for {}, reconnecting...", Self::get_exchange_name(&exchange));
@@ -547,6 +621,7 @@ impl ExchangeActor {
     async fn try_websocket_connection(
         exchange: &Exchange,
         prices: &Arc<Mutex<HashMap<String, Price>>>,
+        quotes: &Arc<Mutex<HashMap<String, BestQuote>>>,
         subscriptions: &Arc<Mutex<HashSet<String>>>,
         subscription_rx: &mut mpsc::Receiver<SubscriptionCommand>,
         activity_tracker: &ActivityTracker,
@@ -596,6 +671,9 @@ impl ExchangeActor {
                                     debug!("Prix mis à jour pour {} {}: {:.2}", Self::get_exchange_name(exchange), symbol, price.value());
                                     activity_tracker.mark_active().await;
                                 }
+                                if let Some((symbol, quote)) = Self::parse_best_quote(exchange, &data) {
+                                    quotes.lock().await.insert(symbol, quote);
+                                }
                             } else {
                                 warn!("Received invalid JSON from {:?}: {}", exchange, text);
                             }
@@ -638,8 +716,9 @@ impl ExchangeActor {
                                     .map_err(|e| format!("Failed to send unsubscribe message: {}", e))?;
                                 info!("Unsubscribed from {} {}: {}", Self::get_exchange_name(exchange), symbol, msg);
                             }
-                            // Remove from prices map
+                            // Remove from prices and quotes maps
                             prices.lock().await.remove(&symbol);
+                            quotes.lock().await.remove(&symbol);
                             activity_tracker.mark_active().await;
                         }
                     }
@@ -768,6 +847,63 @@ impl ExchangeActor {
         }
     }
 
+    /// Parse the best bid and ask from a ticker message
+    ///
+    /// dYdX market and Hyperliquid mid feeds carry no top of book, so those
+    /// exchanges never report a quote (see `Exchange::has_best_quote`).
+    fn parse_best_quote(
+        exchange: &Exchange,
+        data: &serde_json::Value,
+    ) -> Option<(String, BestQuote)> {
+        let (symbol, bid, ask, bid_size, ask_size) = match exchange {
+            Exchange::Binance => (
+                data["s"].as_str()?,
+                &data["b"],
+                &data["a"],
+                &data["B"],
+                &data["A"],
+            ),
+            Exchange::Coinbase => (
+                data["product_id"].as_str()?,
+                &data["best_bid"],
+                &data["best_ask"],
+                &data["best_bid_quantity"],
+                &data["best_ask_quantity"],
+            ),
+            Exchange::Kraken => {
+                let arr = data.as_array().filter(|arr| arr.len() >= 4)?;
+                (
+                    arr[3].as_str()?,
+                    &arr[1]["b"][0],
+                    &arr[1]["a"][0],
+                    &arr[1]["b"][2],
+                    &arr[1]["a"][2],
+                )
+            }
+            Exchange::Dydx | Exchange::Hyperliquid => return None,
+        };
+        let bid = Price::new(bid.as_str()?.parse::<f64>().ok()?).ok()?;
+        let ask = Price::new(ask.as_str()?.parse::<f64>().ok()?).ok()?;
+        if ask.value() < bid.value() {
+            return None;
+        }
+        let size = |value: &serde_json::Value| {
+            value
+                .as_str()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|q| *q >= 0.0)
+        };
+        Some((
+            symbol.to_string(),
+            BestQuote {
+                bid,
+                ask,
+                bid_size: size(bid_size),
+                ask_size: size(ask_size),
+            },
+        ))
+    }
+
     fn parse_price(exchange: &Exchange, data: &serde_json::Value) -> Option<Price> {
         match exchange {
             Exchange::Binance => data["c"]
@@ -949,6 +1085,16 @@ impl MockExchangeActor {
                         warn!("Failed to send reply: {:?}", e);
                     }
                 }
+                ExchangeMessage::GetBestQuote { symbol: _, reply } => {
+                    let _ = reply
+                        .send(Ok(BestQuote {
+                            bid: self.mock_price,
+                            ask: self.mock_price,
+                            bid_size: None,
+                            ask_size: None,
+                        }))
+                        .await;
+                }
                 ExchangeMessage::Subscribe { symbol: _, reply } => {
                     let _ = reply.send(Ok(())).await;
                 }
@@ -973,6 +1119,17 @@ impl MockExchangeActor {
                 ExchangeMessage::GetOrderStatus { order_id: _, reply } => {
                     let _ = reply.send(Ok("FILLED".to_string())).await;
                 }
+                ExchangeMessage::GetOrderFill { order_id: _, reply } => {
+                    let _ = reply
+                        .send(Ok(OrderFill {
+                            status:
+                                crate::domain::repositories::exchange_client::OrderStatus::Filled,
+                            filled_quantity: None,
+                            average_price: None,
+                            fee: None,
+                        }))
+                        .await;
+                }
                 ExchangeMessage::Shutdown => {
                     break;
                 }
@@ -1120,6 +1277,34 @@ mod tests {
         assert_eq!(price.value(), 53000.0);
     }
 
+    #[test]
+    fn test_parse_best_quote() {
+        let data = json!({"s": "BTCUSDT", "c": "50000.00", "b": "49999.50", "B": "1.25", "a": "50000.50", "A": "0.80"});
+        let (symbol, quote) = ExchangeActor::parse_best_quote(&Exchange::Binance, &data).unwrap();
+        assert_eq!(symbol, "BTCUSDT");
+        assert_eq!(quote.bid.value(), 49999.5);
+        assert_eq!(quote.ask.value(), 50000.5);
+        assert_eq!(quote.bid_size, Some(1.25));
+        assert_eq!(quote.ask_size, Some(0.8));
+
+        let data = json!({"product_id": "BTC-USD", "price": "53000.00", "best_bid": "52999.99", "best_ask": "53000.01"});
+        let (_, quote) = ExchangeActor::parse_best_quote(&Exchange::Coinbase, &data).unwrap();
+        assert_eq!(quote.ask.value(), 53000.01);
+        assert_eq!(quote.bid_size, None);
+
+        let data = json!([42, {"a": ["50001.0", 1, "1.0"], "b": ["49999.0", 2, "2.0"], "c": ["50000.0", "0.1"]}, "ticker", "XBT/USD"]);
+        let (symbol, quote) = ExchangeActor::parse_best_quote(&Exchange::Kraken, &data).unwrap();
+        assert_eq!(symbol, "XBT/USD");
+        assert_eq!(quote.bid.value(), 49999.0);
+        assert_eq!(quote.bid_size, Some(2.0));
+
+        // Crossed books and feeds without a top of book report nothing
+        let data = json!({"s": "BTCUSDT", "b": "50001.00", "a": "50000.00"});
+        assert!(ExchangeActor::parse_best_quote(&Exchange::Binance, &data).is_none());
+        let data = json!({"contents": {"markets": {"BTC-USD": {"oraclePrice": "51000.00"}}}});
+        assert!(ExchangeActor::parse_best_quote(&Exchange::Dydx, &data).is_none());
+    }
+
     #[tokio::test]
     async fn test_activity_tracker_detects_staleness() {
         let tracker = ActivityTracker::new();
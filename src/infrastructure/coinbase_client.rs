@@ -213,7 +213,7 @@ impl CoinbaseClient {
             r#type: order_type,
             time_in_force: Some("GTC".to_string()), // Good 'Til Canceled
             cancel_after: None,
-            post_only: Some(false),
+            post_only: Some(order.post_only),
         })
     }
 
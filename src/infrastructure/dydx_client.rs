@@ -24,7 +24,12 @@
 //!
 //! **DO NOT USE THIS CODE FOR REAL TRADING WITHOUT FIXING THE SIGNING MECHANISM**
 
+use crate::domain::entities::exchange::Exchange;
 use crate::domain::entities::order::{Order, OrderSide, OrderType};
+use crate::domain::repositories::funding_source::FundingSource;
+use crate::domain::services::pnl_ledger::FundingPayment;
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
 use ethers::signers::{coins_bip39::English, LocalWallet, MnemonicBuilder, Signer};
 use ethers::types::{Address, Signature, H256};
 use ethers::utils::keccak256;
@@ -104,6 +109,75 @@ pub struct DydxSubaccount {
     pub free_collateral: String,
 }
 
+/// Indexer response listing the perpetual positions of a subaccount
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DydxPerpetualPositions {
+    pub positions: Vec<DydxPerpetualPosition>,
+}
+
+/// Open perpetual position as reported by the indexer
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DydxPerpetualPosition {
+    pub market: String,
+    pub side: String,
+    /// Signed size, negative for shorts
+    pub size: String,
+    pub entry_price: String,
+    pub status: String,
+}
+
+/// Order as reported by the indexer
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DydxIndexerOrder {
+    pub id: String,
+    pub client_id: String,
+    pub ticker: String,
+    pub side: String,
+    pub size: String,
+    pub status: String,
+}
+
+/// Indexer response listing the funding payments of a subaccount
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DydxFundingPayments {
+    pub funding_payments: Vec<DydxFundingPayment>,
+}
+
+/// Funding settled on a perpetual position as reported by the indexer
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DydxFundingPayment {
+    pub created_at: String,
+    pub ticker: String,
+    /// Signed amount in USDC, negative when paid
+    pub payment: String,
+}
+
+impl DydxFundingPayment {
+    /// Ledger payment of this settlement, credited to no strategy in particular
+    pub fn to_payment(&self) -> Result<FundingPayment, String> {
+        let amount = self.payment.parse::<f64>().map_err(|e| {
+            format!(
+                "Invalid funding payment '{}' on {}: {}",
+                self.payment, self.ticker, e
+            )
+        })?;
+        let paid_at = DateTime::parse_from_rfc3339(&self.created_at)
+            .map_err(|e| format!("Invalid funding time '{}': {}", self.created_at, e))?
+            .with_timezone(&Utc);
+        Ok(FundingPayment {
+            symbol: self.ticker.clone(),
+            exchange: Exchange::Dydx.name().to_string(),
+            strategy: None,
+            amount,
+            paid_at,
+        })
+    }
+}
+
 /// dYdX client for API interactions
 pub struct DydxClient {
     client: Client,
@@ -192,6 +266,92 @@ impl DydxClient {
         Ok(account)
     }
 
+    /// Get the open perpetual positions of the first subaccount from the indexer
+    pub async fn get_perpetual_positions(&self) -> Result<Vec<DydxPerpetualPosition>, String> {
+        let url = format!(
+            "{}/v4/perpetualPositions?address={:?}&subaccountNumber=0&status=OPEN",
+            self.config.indexer_base,
+            self.wallet.address()
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to get perpetual positions: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("API error: {}", response.status()));
+        }
+
+        let positions: DydxPerpetualPositions = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse perpetual positions response: {}", e))?;
+
+        Ok(positions.positions)
+    }
+
+    /// Get the open orders of the first subaccount from the indexer
+    pub async fn get_open_orders(&self) -> Result<Vec<DydxIndexerOrder>, String> {
+        let url = format!(
+            "{}/v4/orders?address={:?}&subaccountNumber=0&status=OPEN",
+            self.config.indexer_base,
+            self.wallet.address()
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to get open orders: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("API error: {}", response.status()));
+        }
+
+        let orders: Vec<DydxIndexerOrder> = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse open orders response: {}", e))?;
+
+        Ok(orders)
+    }
+
+    /// Get the funding payments of the first subaccount settled at or after
+    /// `after_or_at` from the indexer
+    pub async fn get_funding_payments(
+        &self,
+        after_or_at: DateTime<Utc>,
+    ) -> Result<Vec<DydxFundingPayment>, String> {
+        let url = format!(
+            "{}/v4/fundingPayments?address={:?}&subaccountNumber=0&afterOrAt={}",
+            self.config.indexer_base,
+            self.wallet.address(),
+            after_or_at.to_rfc3339_opts(SecondsFormat::Millis, true)
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to get funding payments: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("API error: {}", response.status()));
+        }
+
+        let payments: DydxFundingPayments = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse funding payments response: {}", e))?;
+
+        Ok(payments.funding_payments)
+    }
+
     /// Convert our Order to dYdX API format
     pub async fn convert_order(&self, order: &Order) -> Result<DydxOrder, String> {
         let market = self.normalize_market(&order.symbol)?;
@@ -240,7 +400,7 @@ impl DydxClient {
             client_id,
             signature: format!("0x{}", hex::encode(signature.to_vec())),
             reduce_only: false,
-            post_only: false,
+            post_only: order.post_only,
         })
     }
 
@@ -379,6 +539,21 @@ impl DydxClient {
     }
 }
 
+#[async_trait]
+impl FundingSource for DydxClient {
+    fn exchange(&self) -> Exchange {
+        Exchange::Dydx
+    }
+
+    async fn funding_payments(&self, since: DateTime<Utc>) -> Result<Vec<FundingPayment>, String> {
+        self.get_funding_payments(since)
+            .await?
+            .iter()
+            .map(DydxFundingPayment::to_payment)
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -392,6 +567,34 @@ mod tests {
         assert_eq!(config.network_id, 1);
     }
 
+    #[test]
+    fn test_funding_payment_from_indexer() {
+        let payments: DydxFundingPayments = serde_json::from_value(serde_json::json!({
+            "fundingPayments": [{
+                "createdAt": "2025-03-01T12:00:00.000Z",
+                "createdAtHeight": "1000",
+                "ticker": "BTC-USD",
+                "payment": "-0.125",
+                "rate": "0.00001"
+            }]
+        }))
+        .unwrap();
+
+        let payment = payments.funding_payments[0].to_payment().unwrap();
+        assert_eq!(payment.symbol, "BTC-USD");
+        assert_eq!(payment.exchange, "dydx");
+        assert_eq!(payment.amount, -0.125);
+        assert_eq!(payment.paid_at.to_rfc3339(), "2025-03-01T12:00:00+00:00");
+        assert!(payment.strategy.is_none());
+
+        let malformed = DydxFundingPayment {
+            created_at: "2025-03-01T12:00:00.000Z".to_string(),
+            ticker: "BTC-USD".to_string(),
+            payment: "n/a".to_string(),
+        };
+        assert!(malformed.to_payment().is_err());
+    }
+
     #[test]
     fn test_normalize_market() {
         let config = DydxConfig::default();
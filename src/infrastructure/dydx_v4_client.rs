@@ -8,20 +8,23 @@
 //! - Uses official v4-client-rs from https://github.com/dydxprotocol/v4-clients
 //! - Proper Cosmos SDK transaction signing with protobuf encoding
 //! - Market and limit order support
+//! - Conditional stop-loss / take-profit orders for bracket exits
 //! - Order cancellation and status checking
 //! - Account and subaccount management
 
+use crate::domain::entities::bracket_order::{BracketExitIds, BracketLegRole, BracketOrder};
 use crate::domain::entities::order::{Order, OrderSide, OrderType};
 use crate::domain::repositories::exchange_client::{
-    Balance, ExchangeClient, ExchangeError, ExchangeResult, OrderStatus,
+    Balance, ExchangeClient, ExchangeError, ExchangeResult, OrderFill, OrderStatus,
 };
 use crate::persistence::models::{CreateDydxOrderMetadata, DydxOrderMetadataRecord};
 use crate::persistence::repository::DydxOrderMetadataRepository;
 use async_trait::async_trait;
 use base64::{self, Engine};
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use dydx::config::ClientConfig;
-use dydx::indexer::{Height, IndexerClient, Ticker};
+use dydx::indexer::{GetFillsOpts, Height, IndexerClient, Ticker};
 use dydx::node::{
     Account, NodeClient, OrderBuilder, OrderSide as DydxOrderSide, Subaccount, Wallet,
 };
@@ -35,8 +38,20 @@ use tokio::sync::Mutex;
 use tracing::{error, info, warn};
 use zeroize::Zeroizing;
 
+/// Order flags value dYdX assigns to conditional orders
+const ORDER_FLAGS_CONDITIONAL: u32 = 32;
+
+/// How long conditional bracket exits stay on the book
+const CONDITIONAL_ORDER_TTL_DAYS: i64 = 28;
+
+/// Fills read per indexer page when looking up the fees of an order
+const FILL_PAGE_SIZE: u32 = 100;
+
+/// Most fill pages read when looking up the fees of an order
+const MAX_FILL_PAGES: u32 = 20;
+
 /// Global metadata repository for order cancellation support
-static METADATA_REPO: OnceCell<Arc<DydxOrderMetadataRepository>> = OnceCell::new();
+static METADATA_REPO: OnceCell<Arc<dyn DydxOrderMetadataRepository>> = OnceCell::new();
 
 /// dYdX v4 client for API interactions using official client
 pub struct DydxV4Client {
@@ -51,14 +66,14 @@ pub struct DydxV4Client {
 impl DydxV4Client {
     /// Set the global metadata repository for order cancellation support
     /// This must be called before using dYdX clients for order placement
-    pub fn set_metadata_repository(repo: Arc<DydxOrderMetadataRepository>) {
+    pub fn set_metadata_repository(repo: Arc<dyn DydxOrderMetadataRepository>) {
         METADATA_REPO.set(repo).unwrap_or_else(|_| {
             warn!("dYdX metadata repository already set, ignoring new value");
         });
     }
 
     /// Get the global metadata repository
-    fn get_metadata_repository() -> Option<&'static Arc<DydxOrderMetadataRepository>> {
+    fn get_metadata_repository() -> Option<&'static Arc<dyn DydxOrderMetadataRepository>> {
         METADATA_REPO.get()
     }
 }
@@ -193,14 +208,14 @@ impl DydxV4Client {
         let (order_id, dydx_order) = match order.order_type {
             OrderType::Market => {
                 // For market orders, we use a slippage protection price
-                let slippage_price =
-                    order
-                        .price
-                        .map(|p| p.value() as u64)
-                        .unwrap_or(match order.side {
-                            OrderSide::Buy => u64::MAX, // Buy at any price
-                            OrderSide::Sell => 0,       // Sell at any price
-                        });
+                let slippage_price = match order.price {
+                    Some(p) => BigDecimal::from_str(&p.value().to_string())
+                        .map_err(|e| format!("Failed to parse price: {}", e))?,
+                    None => match order.side {
+                        OrderSide::Buy => BigDecimal::from(u64::MAX), // Buy at any price
+                        OrderSide::Sell => BigDecimal::from(0),       // Sell at any price
+                    },
+                };
 
                 order_builder
                     .market(side, size)
@@ -212,12 +227,20 @@ impl DydxV4Client {
                     .map_err(|e| format!("Failed to build market order: {:?}", e))?
             }
             OrderType::Limit => {
+                // Sub-dollar quotes must keep their decimals
                 let price = order.price.ok_or("Limit order must have price")?.value();
+                let price = BigDecimal::from_str(&price.to_string())
+                    .map_err(|e| format!("Failed to parse price: {}", e))?;
+                let time_in_force = if order.post_only {
+                    TimeInForce::PostOnly // Rejected instead of crossing the book
+                } else {
+                    TimeInForce::Unspecified // GTC by default
+                };
 
                 order_builder
-                    .limit(side, price as u64, size)
+                    .limit(side, price, size)
                     .reduce_only(false)
-                    .time_in_force(TimeInForce::Unspecified) // GTC by default
+                    .time_in_force(time_in_force)
                     .until(good_until_block.clone())
                     .build(dydx::indexer::AnyId)
                     .map_err(|e| format!("Failed to build limit order: {:?}", e))?
@@ -305,6 +328,172 @@ impl DydxV4Client {
         Ok(order_id_string)
     }
 
+    /// Place the exits of a bracket as conditional orders
+    ///
+    /// Both legs are reduce-only stop-market / take-profit-market orders. dYdX
+    /// does not cancel one when the other fills, so the caller must cancel the
+    /// surviving leg.
+    pub async fn place_conditional_exits(
+        &self,
+        bracket: &BracketOrder,
+    ) -> Result<BracketExitIds, String> {
+        let stop_loss = self
+            .place_conditional_exit(bracket, BracketLegRole::StopLoss)
+            .await?;
+
+        let take_profit = match self
+            .place_conditional_exit(bracket, BracketLegRole::TakeProfit)
+            .await
+        {
+            Ok(order_id) => order_id,
+            Err(e) => {
+                // Don't leave an unpaired stop behind
+                if let Err(cancel_err) = self.cancel_order(&stop_loss).await {
+                    error!(
+                        "Failed to cancel stop-loss {} after take-profit placement failed: {}",
+                        stop_loss, cancel_err
+                    );
+                }
+                return Err(e);
+            }
+        };
+
+        Ok(BracketExitIds {
+            stop_loss,
+            take_profit,
+        })
+    }
+
+    /// Trigger and worst execution price of a conditional exit leg
+    ///
+    /// Both keep their decimals, so sub-dollar markets trigger at the
+    /// bracket's price instead of 0.
+    fn conditional_exit_prices(
+        bracket: &BracketOrder,
+        role: BracketLegRole,
+    ) -> Result<(BigDecimal, BigDecimal), String> {
+        let trigger_price = bracket
+            .leg(role)
+            .and_then(|leg| leg.price)
+            .ok_or_else(|| format!("Bracket {} has no {} trigger price", bracket.id, role))?;
+        let trigger_price = BigDecimal::from_str(&trigger_price.to_string())
+            .map_err(|e| format!("Failed to parse trigger price: {}", e))?;
+
+        // Once triggered the leg executes like a market order
+        let worst_price = match bracket.exit_side {
+            OrderSide::Buy => BigDecimal::from(u64::MAX),
+            OrderSide::Sell => BigDecimal::from(0),
+        };
+        Ok((trigger_price, worst_price))
+    }
+
+    /// Place a single conditional exit leg and store its metadata
+    async fn place_conditional_exit(
+        &self,
+        bracket: &BracketOrder,
+        role: BracketLegRole,
+    ) -> Result<String, String> {
+        let (trigger_price, worst_price) = Self::conditional_exit_prices(bracket, role)?;
+
+        let ticker = Ticker(bracket.symbol.clone());
+        let market = self
+            .indexer_client
+            .markets()
+            .get_perpetual_market(&ticker)
+            .await
+            .map_err(|e| format!("Failed to get market {}: {:?}", bracket.symbol, e))?;
+
+        let subaccount = self.get_subaccount().await?;
+
+        let side = match bracket.exit_side {
+            OrderSide::Buy => DydxOrderSide::Buy,
+            OrderSide::Sell => DydxOrderSide::Sell,
+        };
+
+        let size = BigDecimal::from_str(&bracket.quantity.to_string())
+            .map_err(|e| format!("Failed to parse quantity: {}", e))?;
+
+        // Conditional orders are stateful and expire by block time, not height
+        let good_until_time = Utc::now() + chrono::Duration::days(CONDITIONAL_ORDER_TTL_DAYS);
+
+        let order_builder = OrderBuilder::new(market, subaccount);
+        let order_builder = match role {
+            BracketLegRole::StopLoss => {
+                order_builder.stop_market(side, worst_price, trigger_price.clone(), size)
+            }
+            _ => order_builder.take_profit_market(side, worst_price, trigger_price.clone(), size),
+        };
+
+        let (order_id, dydx_order) = order_builder
+            .reduce_only(true)
+            .until(good_until_time)
+            .build(dydx::indexer::AnyId)
+            .map_err(|e| format!("Failed to build {} order: {:?}", role, e))?;
+
+        let mut account = self.account.lock().await;
+        let mut node_client = self.node_client.lock().await;
+
+        let refreshed_account = self
+            .wallet
+            .account(0, &mut *node_client)
+            .await
+            .map_err(|e| format!("Failed to refresh account before order: {:?}", e))?;
+        *account = refreshed_account;
+
+        let tx_hash = node_client
+            .place_order(&mut *account, dydx_order)
+            .await
+            .map_err(|e| format!("Failed to place {} order: {:?}", role, e))?;
+
+        drop(node_client);
+        drop(account);
+
+        let order_id_string = format!("{:?}", order_id);
+        info!(
+            "dYdX v4 {} order placed for bracket {} - TxHash: {:?}, OrderID: {}",
+            role, bracket.id, tx_hash, order_id_string
+        );
+
+        let subaccount = self.get_subaccount().await?;
+        let order_id_bytes = order_id.encode_to_vec();
+        let dydx_order_id_b64 = base64::engine::general_purpose::STANDARD.encode(&order_id_bytes);
+
+        let metadata = CreateDydxOrderMetadata {
+            order_id: order_id_string.clone(),
+            dydx_order_id: dydx_order_id_b64,
+            // Conditional orders store their expiry as a unix timestamp
+            good_until_block: good_until_time.timestamp(),
+            client_id: order_id.client_id as i64,
+            subaccount_number: subaccount.number.value() as i32,
+            order_flags: order_id.order_flags as i32,
+            clob_pair_id: order_id.clob_pair_id as i32,
+            symbol: bracket.symbol.clone(),
+            side: match bracket.exit_side {
+                OrderSide::Buy => "buy".to_string(),
+                OrderSide::Sell => "sell".to_string(),
+            },
+            quantity: bracket.quantity.to_string(),
+            price: Some(trigger_price.to_string()),
+            order_type: match role {
+                BracketLegRole::StopLoss => "stop_market".to_string(),
+                _ => "take_profit_market".to_string(),
+            },
+        };
+
+        if let Some(metadata_repo) = Self::get_metadata_repository() {
+            if let Err(e) = metadata_repo.create(metadata).await {
+                error!(
+                    "Failed to store order metadata for {}: {}",
+                    order_id_string, e
+                );
+            }
+        } else {
+            warn!("dYdX metadata repository not set - order cancellation will not be available for {}", order_id_string);
+        }
+
+        Ok(order_id_string)
+    }
+
     /// Reconstruct OrderId from stored metadata
     async fn reconstruct_order_id(
         &self,
@@ -358,16 +547,22 @@ impl DydxV4Client {
             return Err(format!("Order {} is already {}", order_id, metadata.status));
         }
 
-        // Check if order has expired
-        let current_block = self
-            .node_client
-            .lock()
-            .await
-            .latest_block_height()
-            .await
-            .map_err(|e| format!("Failed to get current block height: {:?}", e))?;
+        // Conditional orders expire by block time; short-term orders by height
+        let is_conditional = metadata.order_flags as u32 == ORDER_FLAGS_CONDITIONAL;
+        let has_expired = if is_conditional {
+            Utc::now().timestamp() >= metadata.good_until_block
+        } else {
+            let current_block = self
+                .node_client
+                .lock()
+                .await
+                .latest_block_height()
+                .await
+                .map_err(|e| format!("Failed to get current block height: {:?}", e))?;
+            current_block.0 as u64 >= metadata.good_until_block as u64
+        };
 
-        if current_block.0 as u64 >= metadata.good_until_block as u64 {
+        if has_expired {
             // Mark as expired in database
             metadata_repo
                 .update_expired(order_id)
@@ -385,17 +580,24 @@ impl DydxV4Client {
             .await
             .map_err(|e| format!("Failed to reconstruct OrderId: {}", e))?;
 
-        // Reconstruct the good_until_block
-        let good_until_block = Height(metadata.good_until_block as u32);
-
         // Perform the cancellation
         let mut account = self.account.lock().await;
         let mut node_client = self.node_client.lock().await;
 
-        let tx_hash = node_client
-            .cancel_order(&mut *account, reconstructed_order_id, good_until_block)
-            .await
-            .map_err(|e| format!("Failed to cancel order on dYdX: {:?}", e))?;
+        let tx_hash = if is_conditional {
+            let good_until_time = DateTime::<Utc>::from_timestamp(metadata.good_until_block, 0)
+                .ok_or_else(|| format!("Invalid expiry time stored for order {}", order_id))?;
+            node_client
+                .cancel_order(&mut *account, reconstructed_order_id, good_until_time)
+                .await
+        } else {
+            // Reconstruct the good_until_block
+            let good_until_block = Height(metadata.good_until_block as u32);
+            node_client
+                .cancel_order(&mut *account, reconstructed_order_id, good_until_block)
+                .await
+        }
+        .map_err(|e| format!("Failed to cancel order on dYdX: {:?}", e))?;
 
         // Update the metadata to mark as cancelled
         metadata_repo
@@ -435,6 +637,95 @@ impl DydxV4Client {
         Ok("NOT_FOUND".to_string())
     }
 
+    /// Get order status, filled quantity and fees from dYdX v4 indexer
+    ///
+    /// An open order with a non-zero `totalFilled` is reported as
+    /// `PartiallyFilled`. Fees are summed over the fills of the order.
+    pub async fn get_order_fill(&self, order_id: &str) -> Result<OrderFill, String> {
+        let subaccount = self.get_subaccount().await?;
+
+        let orders = self
+            .indexer_client
+            .accounts()
+            .get_subaccount_orders(&subaccount, None)
+            .await
+            .map_err(|e| format!("Failed to get orders: {:?}", e))?;
+
+        for order in orders {
+            let current_order_id = format!("{:?}", order.id);
+            let client_id_str = format!("{:?}", order.client_id);
+            if current_order_id == order_id || client_id_str == order_id {
+                let filled_quantity = order.total_filled.to_string().parse::<f64>().ok();
+                let fee = match filled_quantity {
+                    Some(quantity) if quantity > 0.0 => {
+                        self.order_fees(&subaccount, &current_order_id).await
+                    }
+                    _ => None,
+                };
+                return Ok(OrderFill {
+                    status: Self::fill_status(&format!("{:?}", order.status), filled_quantity),
+                    filled_quantity,
+                    average_price: None,
+                    fee,
+                });
+            }
+        }
+
+        Ok(OrderFill {
+            status: OrderStatus::Unknown,
+            filled_quantity: None,
+            average_price: None,
+            fee: None,
+        })
+    }
+
+    /// Total fee of the fills of an order, `None` when the indexer lists none
+    ///
+    /// Pages through the subaccount fills, newest first, until a page after
+    /// the order's fills holds none of them or the history ends, reading at
+    /// most `MAX_FILL_PAGES`. Maker rebates are negative fees.
+    async fn order_fees(&self, subaccount: &Subaccount, order_id: &str) -> Option<f64> {
+        let mut fees: Vec<f64> = Vec::new();
+        for page in 1..=MAX_FILL_PAGES {
+            let opts = GetFillsOpts {
+                limit: Some(FILL_PAGE_SIZE),
+                page: Some(page),
+                ..Default::default()
+            };
+            let fills = match self
+                .indexer_client
+                .accounts()
+                .get_subaccount_fills(subaccount, Some(opts))
+                .await
+            {
+                Ok(fills) => fills,
+                Err(e) => {
+                    warn!("Failed to get fills of order {}: {:?}", order_id, e);
+                    return None;
+                }
+            };
+
+            let found = fees.len();
+            fees.extend(
+                fills
+                    .iter()
+                    .filter(|fill| {
+                        fill.order_id
+                            .as_ref()
+                            .is_some_and(|id| format!("{:?}", id) == order_id)
+                    })
+                    .filter_map(|fill| fill.fee.to_string().parse::<f64>().ok()),
+            );
+            // An order fills over a short span, so a page without its fills
+            // after one with them means every fill was read
+            let passed_order = found > 0 && fees.len() == found;
+            if passed_order || fills.len() < FILL_PAGE_SIZE as usize {
+                break;
+            }
+        }
+        (!fees.is_empty()).then(|| fees.iter().sum())
+    }
+
     /// Get account information
     pub async fn get_account_info(&self) -> Result<AccountInfo, String> {
         let account = self.account.lock().await;
@@ -445,10 +736,20 @@ impl DydxV4Client {
         })
     }
 
+    /// Order status taking the filled quantity of an open order into account
+    fn fill_status(status_str: &str, filled_quantity: Option<f64>) -> OrderStatus {
+        match Self::parse_order_status(status_str) {
+            OrderStatus::Pending if filled_quantity.is_some_and(|q| q > 0.0) => {
+                OrderStatus::PartiallyFilled
+            }
+            status => status,
+        }
+    }
+
     /// Helper to convert dYdX order status string to our OrderStatus enum
     fn parse_order_status(status_str: &str) -> OrderStatus {
         match status_str.to_uppercase().as_str() {
-            "OPEN" | "PENDING" | "BEST_EFFORT_OPENED" => OrderStatus::Pending,
+            "OPEN" | "PENDING" | "BEST_EFFORT_OPENED" | "UNTRIGGERED" => OrderStatus::Pending,
             "FILLED" | "BEST_EFFORT_FILLED" => OrderStatus::Filled,
             "PARTIALLY_FILLED" => OrderStatus::PartiallyFilled,
             "CANCELLED" | "BEST_EFFORT_CANCELLED" => OrderStatus::Cancelled,
@@ -480,6 +781,16 @@ impl ExchangeClient for DydxV4Client {
             .map_err(|e| ExchangeError::OrderPlacementFailed(e))
     }
 
+    fn supports_native_brackets(&self) -> bool {
+        true
+    }
+
+    async fn place_bracket_exits(&self, bracket: &BracketOrder) -> ExchangeResult<BracketExitIds> {
+        DydxV4Client::place_conditional_exits(self, bracket)
+            .await
+            .map_err(|e| ExchangeError::OrderPlacementFailed(e))
+    }
+
     async fn cancel_order(&self, order_id: &str) -> ExchangeResult<()> {
         // Use the existing cancel_order implementation
         DydxV4Client::cancel_order(self, order_id)
@@ -496,6 +807,12 @@ impl ExchangeClient for DydxV4Client {
         Ok(Self::parse_order_status(&status_str))
     }
 
+    async fn get_order_fill(&self, order_id: &str) -> ExchangeResult<OrderFill> {
+        DydxV4Client::get_order_fill(self, order_id)
+            .await
+            .map_err(|e| ExchangeError::OrderStatusFailed(e))
+    }
+
     async fn get_balance(&self, currency: Option<&str>) -> ExchangeResult<Vec<Balance>> {
         // Get subaccount to access balance info
         let subaccount = self
@@ -555,6 +872,28 @@ mod tests {
         assert_eq!(info.sequence, 456);
     }
 
+    #[test]
+    fn test_conditional_exit_prices_keep_decimals() {
+        let bracket = BracketOrder::oco(
+            "oco-1".to_string(),
+            "DOGE-USD".to_string(),
+            OrderSide::Sell,
+            100.0,
+            0.4567,
+            0.5123,
+        )
+        .unwrap();
+
+        let (trigger, worst) =
+            DydxV4Client::conditional_exit_prices(&bracket, BracketLegRole::StopLoss).unwrap();
+        assert_eq!(trigger, BigDecimal::from_str("0.4567").unwrap());
+        assert_eq!(worst, BigDecimal::from(0));
+
+        let (trigger, _) =
+            DydxV4Client::conditional_exit_prices(&bracket, BracketLegRole::TakeProfit).unwrap();
+        assert_eq!(trigger, BigDecimal::from_str("0.5123").unwrap());
+    }
+
     #[test]
     fn test_parse_order_status() {
         assert_eq!(
@@ -573,6 +912,10 @@ mod tests {
             DydxV4Client::parse_order_status("PARTIALLY_FILLED"),
             OrderStatus::PartiallyFilled
         );
+        assert_eq!(
+            DydxV4Client::parse_order_status("UNTRIGGERED"),
+            OrderStatus::Pending
+        );
         assert_eq!(
             DydxV4Client::parse_order_status("NOT_FOUND"),
             OrderStatus::Unknown
@@ -583,6 +926,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_open_order_with_fills_is_partially_filled() {
+        assert_eq!(
+            DydxV4Client::fill_status("OPEN", Some(0.5)),
+            OrderStatus::PartiallyFilled
+        );
+        assert_eq!(
+            DydxV4Client::fill_status("OPEN", Some(0.0)),
+            OrderStatus::Pending
+        );
+        assert_eq!(
+            DydxV4Client::fill_status("CANCELLED", Some(0.5)),
+            OrderStatus::Cancelled
+        );
+    }
+
     #[test]
     fn test_metadata_repository_not_set() {
         // Test that get_metadata_repository returns None when not set
@@ -8,6 +8,7 @@ use crate::domain::entities::exchange::Exchange;
 use crate::domain::repositories::exchange_client::ExchangeClient;
 use crate::infrastructure::coinbase_advanced_client::CoinbaseAdvancedClient;
 use crate::infrastructure::coinbase_client::CoinbaseClient;
+use crate::infrastructure::dydx_client::{DydxClient, DydxConfig};
 use crate::infrastructure::dydx_v4_client::DydxV4Client;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -127,6 +128,48 @@ impl ExchangeClientFactory {
         }
     }
 
+    /// Create the account clients the reconciliation actor reads balances from
+    ///
+    /// Coinbase accounts come from the Exchange API (`COINBASE_API_KEY`),
+    /// dYdX subaccounts from the indexer (`DYDX_MNEMONIC`). Either is None
+    /// when its credentials are missing.
+    pub fn create_reconciliation_clients() -> (Option<Arc<CoinbaseClient>>, Option<Arc<DydxClient>>)
+    {
+        let coinbase = match (
+            std::env::var("COINBASE_API_KEY"),
+            std::env::var("COINBASE_API_SECRET"),
+        ) {
+            (Ok(api_key), Ok(api_secret)) => {
+                let passphrase = std::env::var("COINBASE_PASSPHRASE").ok();
+                match CoinbaseClient::new(&api_key, &api_secret, passphrase.as_deref()) {
+                    Ok(client) => Some(Arc::new(client)),
+                    Err(e) => {
+                        error!("✗ Failed to create Coinbase reconciliation client: {}", e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        (coinbase, Self::create_dydx_account_client())
+    }
+
+    /// Create the indexer client of the dYdX subaccount (`DYDX_MNEMONIC`),
+    /// which reconciliation and funding payments read from
+    ///
+    /// None when the mnemonic is missing.
+    pub fn create_dydx_account_client() -> Option<Arc<DydxClient>> {
+        let mnemonic = std::env::var("DYDX_MNEMONIC").ok()?;
+        match DydxClient::new(&mnemonic, DydxConfig::default()) {
+            Ok(client) => Some(Arc::new(client)),
+            Err(e) => {
+                error!("✗ Failed to create dYdX account client: {}", e);
+                None
+            }
+        }
+    }
+
     /// Create a specific exchange client
     ///
     /// # Arguments
@@ -0,0 +1,422 @@
+//! Market discovery over the exchanges' public REST APIs
+//!
+//! One request per exchange lists every market with its 24h ticker (two for
+//! Kraken, whose ticker does not carry the WebSocket pair names), and one
+//! request per symbol reads its order book. No credentials are needed. Only
+//! markets quoted in USD (USDT on Binance) are listed, since those are the
+//! only ones the symbol normalization handles.
+
+use crate::domain::entities::exchange::Exchange;
+use crate::domain::repositories::market_discovery::MarketDiscovery;
+use crate::domain::services::screening::OrderBookDepth;
+use crate::domain::services::symbol_universe::MarketSnapshot;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+const BINANCE_TICKERS_URL: &str = "https://api.binance.com/api/v3/ticker/24hr";
+const COINBASE_PRODUCTS_URL: &str = "https://api.coinbase.com/api/v3/brokerage/market/products";
+const DYDX_MARKETS_URL: &str = "https://indexer.dydx.trade/v4/perpetualMarkets";
+const HYPERLIQUID_INFO_URL: &str = "https://api.hyperliquid.xyz/info";
+const KRAKEN_PAIRS_URL: &str = "https://api.kraken.com/0/public/AssetPairs";
+const KRAKEN_TICKER_URL: &str = "https://api.kraken.com/0/public/Ticker";
+const BINANCE_DEPTH_URL: &str = "https://api.binance.com/api/v3/depth";
+const COINBASE_BOOK_URL: &str = "https://api.coinbase.com/api/v3/brokerage/market/product_book";
+const DYDX_BOOK_URL: &str = "https://indexer.dydx.trade/v4/orderbooks/perpetualMarket";
+const KRAKEN_DEPTH_URL: &str = "https://api.kraken.com/0/public/Depth";
+/// Levels requested per side of a book
+const BOOK_LEVELS: usize = 100;
+
+pub struct MarketDiscoveryClient {
+    http: Client,
+}
+
+impl MarketDiscoveryClient {
+    pub fn new() -> Result<Self, String> {
+        let http = Client::builder()
+            .user_agent("NZEZA-Trading-Bot/0.1.0")
+            .timeout(Duration::from_secs(15))
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        Ok(Self { http })
+    }
+
+    async fn get_json(&self, url: &str) -> Result<Value, String> {
+        self.http
+            .get(url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("GET {} failed: {}", url, e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid JSON from {}: {}", url, e))
+    }
+
+    async fn post_hyperliquid(&self, request: Value) -> Result<Value, String> {
+        self.http
+            .post(HYPERLIQUID_INFO_URL)
+            .json(&request)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("POST {} failed: {}", HYPERLIQUID_INFO_URL, e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid JSON from {}: {}", HYPERLIQUID_INFO_URL, e))
+    }
+}
+
+#[async_trait]
+impl MarketDiscovery for MarketDiscoveryClient {
+    async fn list_markets(&self, exchange: &Exchange) -> Result<Vec<MarketSnapshot>, String> {
+        match exchange {
+            Exchange::Binance => Ok(parse_binance(&self.get_json(BINANCE_TICKERS_URL).await?)),
+            Exchange::Coinbase => Ok(parse_coinbase(&self.get_json(COINBASE_PRODUCTS_URL).await?)),
+            Exchange::Dydx => Ok(parse_dydx(&self.get_json(DYDX_MARKETS_URL).await?)),
+            Exchange::Hyperliquid => {
+                let body = self
+                    .post_hyperliquid(json!({"type": "metaAndAssetCtxs"}))
+                    .await?;
+                Ok(parse_hyperliquid(&body))
+            }
+            Exchange::Kraken => {
+                let pairs = self.get_json(KRAKEN_PAIRS_URL).await?;
+                let tickers = self.get_json(KRAKEN_TICKER_URL).await?;
+                Ok(parse_kraken(&pairs, &tickers))
+            }
+        }
+    }
+
+    async fn order_book(
+        &self,
+        exchange: &Exchange,
+        symbol: &str,
+    ) -> Result<OrderBookDepth, String> {
+        let body = match exchange {
+            Exchange::Binance => {
+                let url = format!(
+                    "{}?symbol={}&limit={}",
+                    BINANCE_DEPTH_URL,
+                    symbol.to_uppercase(),
+                    BOOK_LEVELS
+                );
+                self.get_json(&url).await?
+            }
+            Exchange::Coinbase => {
+                let url = format!(
+                    "{}?product_id={}&limit={}",
+                    COINBASE_BOOK_URL, symbol, BOOK_LEVELS
+                );
+                self.get_json(&url).await?["pricebook"].take()
+            }
+            Exchange::Dydx => {
+                self.get_json(&format!("{}/{}", DYDX_BOOK_URL, symbol))
+                    .await?
+            }
+            Exchange::Hyperliquid => {
+                let body = self
+                    .post_hyperliquid(json!({"type": "l2Book", "coin": symbol}))
+                    .await?;
+                json!({"bids": body["levels"][0], "asks": body["levels"][1]})
+            }
+            Exchange::Kraken => {
+                // The REST pair name is the WebSocket name without its slash
+                let url = format!(
+                    "{}?pair={}&count={}",
+                    KRAKEN_DEPTH_URL,
+                    symbol.replace('/', ""),
+                    BOOK_LEVELS
+                );
+                let body = self.get_json(&url).await?;
+                body["result"]
+                    .as_object()
+                    .and_then(|result| result.values().next().cloned())
+                    .unwrap_or(Value::Null)
+            }
+        };
+        let book = parse_book(&body);
+        if book.bids.is_empty() && book.asks.is_empty() {
+            return Err(format!(
+                "Empty order book for {} on {}",
+                symbol,
+                exchange.name()
+            ));
+        }
+        Ok(book)
+    }
+}
+
+/// Bids and asks of a book body, whose levels are `[price, size, ..]`
+/// arrays (Binance, Kraken) or `{price, size}` / `{px, sz}` objects
+fn parse_book(body: &Value) -> OrderBookDepth {
+    let levels = |side: &Value| -> Vec<(f64, f64)> {
+        side.as_array()
+            .into_iter()
+            .flatten()
+            .map(|level| {
+                if level.is_array() {
+                    (num(&level[0]), num(&level[1]))
+                } else if level.get("px").is_some() {
+                    (num(&level["px"]), num(&level["sz"]))
+                } else {
+                    (num(&level["price"]), num(&level["size"]))
+                }
+            })
+            .filter(|(price, size)| *price > 0.0 && *size > 0.0)
+            .collect()
+    };
+    OrderBookDepth {
+        bids: levels(&body["bids"]),
+        asks: levels(&body["asks"]),
+    }
+}
+
+/// Number from a JSON string or number, zero when missing
+fn num(value: &Value) -> f64 {
+    value
+        .as_str()
+        .and_then(|s| s.parse::<f64>().ok())
+        .or_else(|| value.as_f64())
+        .unwrap_or(0.0)
+}
+
+fn positive(value: &Value) -> Option<f64> {
+    Some(num(value)).filter(|v| *v > 0.0)
+}
+
+fn parse_binance(body: &Value) -> Vec<MarketSnapshot> {
+    body.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|t| {
+            let symbol = t["symbol"].as_str()?;
+            if !symbol.ends_with("USDT") {
+                return None;
+            }
+            Some(MarketSnapshot {
+                symbol: symbol.to_string(),
+                price: num(&t["lastPrice"]),
+                open_24h: num(&t["openPrice"]),
+                high_24h: num(&t["highPrice"]),
+                low_24h: num(&t["lowPrice"]),
+                quote_volume_24h: num(&t["quoteVolume"]),
+                bid: positive(&t["bidPrice"]),
+                ask: positive(&t["askPrice"]),
+                trades_24h: positive(&t["count"]),
+                funding_rate: None,
+            })
+        })
+        .collect()
+}
+
+/// Coinbase lists the 24h change but no range or book; the open is derived
+/// from the change
+fn parse_coinbase(body: &Value) -> Vec<MarketSnapshot> {
+    body["products"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|p| {
+            p["quote_currency_id"] == "USD"
+                && p["status"] == "online"
+                && !p["trading_disabled"].as_bool().unwrap_or(false)
+        })
+        .filter_map(|p| {
+            let price = num(&p["price"]);
+            let change_pct = num(&p["price_percentage_change_24h"]);
+            Some(MarketSnapshot {
+                symbol: p["product_id"].as_str()?.to_string(),
+                price,
+                open_24h: price / (1.0 + change_pct / 100.0),
+                high_24h: 0.0,
+                low_24h: 0.0,
+                quote_volume_24h: num(&p["approximate_quote_24h_volume"]),
+                bid: None,
+                ask: None,
+                trades_24h: None,
+                funding_rate: None,
+            })
+        })
+        .collect()
+}
+
+fn parse_dydx(body: &Value) -> Vec<MarketSnapshot> {
+    body["markets"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(_, m)| m["status"] == "ACTIVE")
+        .map(|(ticker, m)| {
+            let price = num(&m["oraclePrice"]);
+            MarketSnapshot {
+                symbol: ticker.clone(),
+                price,
+                open_24h: price - num(&m["priceChange24H"]),
+                high_24h: 0.0,
+                low_24h: 0.0,
+                quote_volume_24h: num(&m["volume24H"]),
+                bid: None,
+                ask: None,
+                trades_24h: positive(&m["trades24H"]),
+                funding_rate: m["nextFundingRate"]
+                    .as_str()
+                    .and_then(|r| r.parse::<f64>().ok()),
+            }
+        })
+        .collect()
+}
+
+/// The meta universe and the asset contexts are parallel arrays
+fn parse_hyperliquid(body: &Value) -> Vec<MarketSnapshot> {
+    let universe = body[0]["universe"].as_array().cloned().unwrap_or_default();
+    let contexts = body[1].as_array().cloned().unwrap_or_default();
+    universe
+        .iter()
+        .zip(contexts.iter())
+        .filter(|(asset, _)| !asset["isDelisted"].as_bool().unwrap_or(false))
+        .filter_map(|(asset, ctx)| {
+            Some(MarketSnapshot {
+                symbol: asset["name"].as_str()?.to_string(),
+                price: num(&ctx["markPx"]),
+                open_24h: num(&ctx["prevDayPx"]),
+                high_24h: 0.0,
+                low_24h: 0.0,
+                quote_volume_24h: num(&ctx["dayNtlVlm"]),
+                bid: positive(&ctx["impactPxs"][0]),
+                ask: positive(&ctx["impactPxs"][1]),
+                trades_24h: None,
+                funding_rate: ctx["funding"].as_str().and_then(|r| r.parse::<f64>().ok()),
+            })
+        })
+        .collect()
+}
+
+/// Ticker entries are keyed by REST pair name; the WebSocket name comes from
+/// the asset pairs. Volumes are in the base asset.
+fn parse_kraken(pairs: &Value, tickers: &Value) -> Vec<MarketSnapshot> {
+    pairs["result"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, pair)| {
+            let wsname = pair["wsname"].as_str()?;
+            if !wsname.ends_with("/USD") || pair["status"].as_str().unwrap_or("online") != "online"
+            {
+                return None;
+            }
+            let t = tickers["result"].get(name)?;
+            let price = num(&t["c"][0]);
+            Some(MarketSnapshot {
+                symbol: wsname.to_string(),
+                price,
+                open_24h: num(&t["o"]),
+                high_24h: num(&t["h"][1]),
+                low_24h: num(&t["l"][1]),
+                quote_volume_24h: num(&t["v"][1]) * price,
+                bid: positive(&t["b"][0]),
+                ask: positive(&t["a"][0]),
+                trades_24h: positive(&t["t"][1]),
+                funding_rate: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_binance_keeps_usdt_markets() {
+        let body = json!([
+            {"symbol": "BTCUSDT", "lastPrice": "50000", "openPrice": "49000", "highPrice": "51000",
+             "lowPrice": "48500", "quoteVolume": "1200000000", "bidPrice": "49999.9", "askPrice": "50000.1",
+             "count": 1440000},
+            {"symbol": "ETHBTC", "lastPrice": "0.05"}
+        ]);
+        let markets = parse_binance(&body);
+        assert_eq!(markets.len(), 1);
+        assert_eq!(markets[0].symbol, "BTCUSDT");
+        assert_eq!(markets[0].high_24h, 51000.0);
+        assert_eq!(markets[0].bid, Some(49999.9));
+        assert_eq!(markets[0].trades_24h, Some(1_440_000.0));
+    }
+
+    #[test]
+    fn test_parse_venues_without_a_book() {
+        let coinbase = json!({"products": [
+            {"product_id": "SOL-USD", "price": "110", "price_percentage_change_24h": "10",
+             "approximate_quote_24h_volume": "5000000", "quote_currency_id": "USD",
+             "status": "online", "trading_disabled": false},
+            {"product_id": "SOL-EUR", "price": "100", "quote_currency_id": "EUR", "status": "online"},
+            {"product_id": "OLD-USD", "price": "1", "quote_currency_id": "USD", "status": "delisted"}
+        ]});
+        let markets = parse_coinbase(&coinbase);
+        assert_eq!(markets.len(), 1);
+        assert!((markets[0].open_24h - 100.0).abs() < 1e-9);
+        assert_eq!(markets[0].bid, None);
+
+        let dydx = json!({"markets": {
+            "BTC-USD": {"status": "ACTIVE", "oraclePrice": "50000", "priceChange24H": "-500", "volume24H": "900000000",
+                        "trades24H": 250000, "nextFundingRate": "-0.0000125"},
+            "LUNA-USD": {"status": "FINAL_SETTLEMENT", "oraclePrice": "0.1"}
+        }});
+        let markets = parse_dydx(&dydx);
+        assert_eq!(markets.len(), 1);
+        assert_eq!(markets[0].open_24h, 50500.0);
+        assert_eq!(markets[0].funding_rate, Some(-0.0000125));
+
+        let hyperliquid = json!([
+            {"universe": [{"name": "BTC"}, {"name": "OLD", "isDelisted": true}]},
+            [{"markPx": "50000", "prevDayPx": "49000", "dayNtlVlm": "1000000", "impactPxs": ["49995", "50005"],
+              "funding": "0.0000125"},
+             {"markPx": "1"}]
+        ]);
+        let markets = parse_hyperliquid(&hyperliquid);
+        assert_eq!(markets.len(), 1);
+        assert_eq!(markets[0].symbol, "BTC");
+        assert_eq!(markets[0].ask, Some(50005.0));
+        assert_eq!(markets[0].funding_rate, Some(0.0000125));
+    }
+
+    #[test]
+    fn test_parse_book_level_shapes() {
+        let binance =
+            json!({"bids": [["49999.5", "2.0"], ["49999.0", "0"]], "asks": [["50000.5", "1.5"]]});
+        let book = parse_book(&binance);
+        assert_eq!(book.bids, vec![(49999.5, 2.0)]);
+        assert_eq!(book.asks, vec![(50000.5, 1.5)]);
+
+        let dydx = json!({"bids": [{"price": "49999", "size": "0.5"}], "asks": [{"price": "50001", "size": "0.25"}]});
+        let book = parse_book(&dydx);
+        assert_eq!(book.bids, vec![(49999.0, 0.5)]);
+        assert_eq!(book.asks, vec![(50001.0, 0.25)]);
+
+        let hyperliquid = json!({"bids": [{"px": "49998", "sz": "3", "n": 2}], "asks": []});
+        let book = parse_book(&hyperliquid);
+        assert_eq!(book.bids, vec![(49998.0, 3.0)]);
+        assert!(book.asks.is_empty());
+    }
+
+    #[test]
+    fn test_parse_kraken_uses_websocket_names() {
+        let pairs = json!({"result": {
+            "XXBTZUSD": {"wsname": "XBT/USD", "status": "online"},
+            "XXBTZEUR": {"wsname": "XBT/EUR", "status": "online"}
+        }});
+        let tickers = json!({"result": {
+            "XXBTZUSD": {"a": ["50001.0", "1", "1.0"], "b": ["49999.0", "1", "1.0"], "c": ["50000.0", "0.1"],
+                         "v": ["100", "200"], "h": ["50500", "51000"], "l": ["49500", "49000"], "o": "49800",
+                         "t": [1200, 3000]},
+            "XXBTZEUR": {"c": ["46000.0", "0.1"]}
+        }});
+        let markets = parse_kraken(&pairs, &tickers);
+        assert_eq!(markets.len(), 1);
+        assert_eq!(markets[0].symbol, "XBT/USD");
+        assert_eq!(markets[0].quote_volume_24h, 10_000_000.0);
+        assert_eq!(markets[0].low_24h, 49000.0);
+        assert_eq!(markets[0].trades_24h, Some(3000.0));
+    }
+}
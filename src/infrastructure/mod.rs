@@ -4,3 +4,4 @@ pub mod coinbase_client;
 pub mod dydx_client;
 pub mod dydx_v4_client;
 pub mod exchange_client_factory;
+pub mod market_discovery;
@@ -6,21 +6,28 @@ mod infrastructure;
 mod persistence;
 mod rate_limit;
 mod task_runner;
+use crate::application::actors::reconciliation_actor::ReconciliationActor;
+use crate::application::actors::screening_actor::{
+    ScreeningActor, ScreeningMessage, ScreeningResults,
+};
 use crate::application::actors::trader_actor::TraderActor;
-use crate::application::services::mpc_service::MpcService;
+use crate::application::handlers::screening_handler::{self, ScreeningState};
+use crate::application::services::mpc_service::{MpcService, SignalExecution};
 use crate::domain::entities::exchange::Exchange;
 use crate::domain::entities::trader::Trader;
-use crate::domain::services::strategies::{
-    ConservativeScalping, FastScalping, MomentumScalping, SignalCombiner, Strategy,
+use crate::domain::services::reconciliation::{
+    PositionReconciliationReport, ReconciliationConfig, ReconciliationReport, ReconciliationStatus,
 };
+use crate::domain::services::screening::ScalpingPotentialAggregator;
+use crate::domain::services::strategy_registry::StrategyRegistry;
 use crate::infrastructure::adapters::exchange_actor::ExchangeActor;
 use crate::infrastructure::exchange_client_factory::ExchangeClientFactory;
-use crate::persistence::repository::DydxOrderMetadataRepository;
-use crate::persistence::{init_database, DatabaseConfig};
+use crate::infrastructure::market_discovery::MarketDiscoveryClient;
+use crate::persistence::{open_repositories, DatabaseConfig};
 use axum::extract::ws::{Message, WebSocket};
 use axum::response::Response;
 use axum::{
-    extract::{Path, State, WebSocketUpgrade},
+    extract::{FromRef, Path, Query, State, WebSocketUpgrade},
     middleware,
     routing::{delete, get, post},
     Json, Router,
@@ -30,7 +37,7 @@ use std::io::ErrorKind;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -38,6 +45,13 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 struct AppState {
     mpc_service: std::sync::Arc<MpcService>,
     metrics_tx: broadcast::Sender<String>,
+    screening: ScreeningState,
+}
+
+impl FromRef<AppState> for ScreeningState {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.screening.clone()
+    }
 }
 
 fn get_exchange_name(exchange: &Exchange) -> &'static str {
@@ -69,6 +83,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    // `--migrate-only` brings the database schema up to date and exits
+    if std::env::args().any(|arg| arg == "--migrate-only") {
+        let db_config = DatabaseConfig::from_env();
+        match open_repositories(&db_config.url, &db_config.instance_id)
+            .await?
+            .sqlite
+        {
+            Some(db_pool) => {
+                let version = crate::persistence::migrations::current_version(&db_pool).await?;
+                info!(
+                    "Database schema at version {}, exiting (--migrate-only)",
+                    version
+                );
+            }
+            None => info!("Database schema up to date, exiting (--migrate-only)"),
+        }
+        return Ok(());
+    }
+
     info!("MPC Trading Server démarrage avec acteurs et stratégies...");
     info!("Échanges supportés: Binance, dYdX, Hyperliquid, Coinbase, Kraken");
     info!("Stratégies: Fast Scalping, Momentum Scalping, Conservative Scalping");
@@ -85,13 +118,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Initialize database
     let db_config = DatabaseConfig::from_env();
-    let db_pool = init_database(&db_config.url).await?;
-    let dydx_metadata_repo = DydxOrderMetadataRepository::new(db_pool.clone());
+    let repositories = open_repositories(&db_config.url, &db_config.instance_id).await?;
     info!("Database initialized successfully");
 
     // Set global metadata repository for dYdX order cancellation
     use crate::infrastructure::dydx_v4_client::DydxV4Client;
-    DydxV4Client::set_metadata_repository(Arc::new(dydx_metadata_repo));
+    DydxV4Client::set_metadata_repository(repositories.order_metadata.clone());
 
     // ⚠️ WARNING: dYdX v4 integration has known issues
     // The current implementation uses Ethereum (EIP-712) signing instead of Cosmos SDK signing.
@@ -152,12 +184,73 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "  Portfolio % per position: {:.2}%",
         config.portfolio_percentage_per_position * 100.0
     );
+    if let Some(max_holding) = config.time_exit.max_holding {
+        info!("  Max holding time: {} min", max_holding.num_minutes());
+    }
+    if let Some(no_progress) = config.time_exit.no_progress_after {
+        info!(
+            "  No-progress exit: {} min (min progress {:.2}%)",
+            no_progress.num_minutes(),
+            config.time_exit.min_progress_pct * 100.0
+        );
+    }
     info!("  Max trades per hour: {}", config.max_trades_per_hour);
     info!("  Max trades per day: {}", config.max_trades_per_day);
 
     // Create MPC service with initial config (will be updated later if needed)
     // Note: Config will be updated after checking balance and trader availability
     let mut mpc_service = MpcService::new(config.clone());
+    mpc_service.set_repositories(repositories.positions.clone(), repositories.trades.clone());
+    mpc_service.set_funding_repository(repositories.funding.clone());
+    mpc_service.set_bracket_repository(repositories.brackets.clone());
+    mpc_service.set_signal_repository(repositories.signals.clone());
+    mpc_service.set_weight_repositories(repositories.allocator.clone(), repositories.audit.clone());
+    mpc_service.set_order_metadata_repository(repositories.order_metadata.clone());
+    if let Some(dydx_client) = ExchangeClientFactory::create_dydx_account_client() {
+        mpc_service.set_funding_source(dydx_client);
+    }
+    if config.reconciliation_enabled {
+        let (coinbase_client, dydx_client) = ExchangeClientFactory::create_reconciliation_clients();
+        if coinbase_client.is_none() && dydx_client.is_none() {
+            warn!("⚠️  Reconciliation enabled but no Coinbase or dYdX account credentials found");
+        } else {
+            let repository = repositories.reconciliation.clone();
+            let reconciliation_config = ReconciliationConfig {
+                timeout_milliseconds: config.reconciliation_timeout_milliseconds,
+                threshold_percentage: config.reconciliation_threshold_percentage,
+                max_retries: config.reconciliation_max_retries,
+                ..Default::default()
+            };
+            let actor =
+                ReconciliationActor::new(coinbase_client, dydx_client, reconciliation_config)
+                    .with_repository(repository.clone())
+                    .with_remediation_policy(config.reconciliation_remediation.clone())
+                    .start();
+            mpc_service.set_reconciliation(actor, repository);
+            match mpc_service.restore_reconciliation_pauses().await {
+                Ok(0) => {}
+                Ok(paused) => warn!(
+                    "⏸️  {} currencies stay paused until their reconciliation is resolved",
+                    paused
+                ),
+                Err(e) => warn!("⚠️  Failed to restore reconciliation pauses: {}", e),
+            }
+        }
+    }
+    // Screening reads trade rates, funding and books through discovery too
+    let screening_discovery = config.screening_enabled
+        && (config.screening_depth_weight > 0.0
+            || config.screening_trade_rate_weight > 0.0
+            || config.screening_funding_weight > 0.0);
+    if config.dynamic_universe_enabled || screening_discovery {
+        match MarketDiscoveryClient::new() {
+            Ok(client) => mpc_service.set_market_discovery(Arc::new(client)),
+            Err(e) => warn!("⚠️  Market discovery unavailable: {}", e),
+        }
+    }
+    if let Err(e) = mpc_service.restore_brackets().await {
+        warn!("⚠️  Failed to restore bracket orders: {}", e);
+    }
     mpc_service.add_actor(Exchange::Binance, binance_sender);
     mpc_service.add_actor(Exchange::Dydx, dydx_sender);
     mpc_service.add_actor(Exchange::Hyperliquid, hyperliquid_sender);
@@ -228,47 +321,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    // Initialize signal combiner with strategies
-    let strategies = vec![
-        (
-            "FastScalping".to_string(),
-            Box::new(FastScalping::new()) as Box<dyn Strategy + Send + Sync>,
-        ),
-        (
-            "MomentumScalping".to_string(),
-            Box::new(MomentumScalping::new()) as Box<dyn Strategy + Send + Sync>,
-        ),
-        (
-            "ConservativeScalping".to_string(),
-            Box::new(ConservativeScalping::new()) as Box<dyn Strategy + Send + Sync>,
-        ),
-    ];
-    let weights = vec![0.4, 0.4, 0.2];
-    let combiner =
-        SignalCombiner::new(strategies, weights).expect("Failed to create signal combiner");
-    mpc_service.set_signal_combiner(combiner).await;
+    // Initialize strategies and signal combiners from the registry
+    let registry = match &config.strategy_config_path {
+        Some(path) => {
+            info!("Loading strategy registry from {}", path);
+            StrategyRegistry::load(path)?
+        }
+        None => StrategyRegistry::default(),
+    };
+    mpc_service
+        .apply_strategy_registry(registry.clone())
+        .await?;
+    if let Err(e) = mpc_service.restore_weight_allocator().await {
+        warn!("Starting weight allocation from scratch: {}", e);
+    }
 
     // Create and spawn traders with exchange clients
     if !exchange_clients.is_empty() {
         info!("Creating traders with available exchange clients...");
 
-        // Create one trader per strategy for now
-        let trader_strategies = vec![
-            (
-                "FastScalping",
-                Box::new(FastScalping::new()) as Box<dyn Strategy + Send + Sync>,
-            ),
-            (
-                "MomentumScalping",
-                Box::new(MomentumScalping::new()) as Box<dyn Strategy + Send + Sync>,
-            ),
-            (
-                "ConservativeScalping",
-                Box::new(ConservativeScalping::new()) as Box<dyn Strategy + Send + Sync>,
-            ),
-        ];
-
-        for (strategy_name, strategy) in trader_strategies {
+        // Create one trader per registered strategy
+        for strategy_name in registry.strategy_names() {
+            let strategy = registry.build_strategy(&strategy_name)?;
             let trader_id = format!("trader_{}", strategy_name.to_lowercase());
 
             match Trader::new(
@@ -407,6 +481,37 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     mpc_service.config = config.clone();
     info!("✓ MpcService configuration updated with final trading status");
 
+    // Reload what the previous run left open before any signal can trade
+    match mpc_service.recover_trading_state().await {
+        Ok(summary) => {
+            info!(
+                "♻️  Recovered {} open position(s) worth ${:.2}, replayed {} trade(s) into the limits",
+                summary.positions_restored, summary.position_value, summary.trades_replayed
+            );
+            if summary.orders_checked > 0 {
+                info!(
+                    "♻️  Active orders: {} filled, {} closed, {} open, {} unverified",
+                    summary.orders_filled,
+                    summary.orders_closed,
+                    summary.orders_open,
+                    summary.orders_unverified
+                );
+            }
+            for position_id in &summary.positions_without_stop {
+                warn!("⚠️  Restored position {} has no stop-loss", position_id);
+            }
+        }
+        Err(e) => warn!("⚠️  Failed to recover trading state: {}", e),
+    }
+
+    match mpc_service.rebuild_pnl_ledger().await {
+        Ok(totals) => info!(
+            "📒 Realized PnL ledger rebuilt: ${:.2} net over {} closed lot(s), ${:.2} fees, ${:.2} funding",
+            totals.net_pnl, totals.closed_lots, totals.fees, totals.funding
+        ),
+        Err(e) => warn!("⚠️  Failed to rebuild the realized PnL ledger: {}", e),
+    }
+
     for (exchange, symbols) in &config.symbols {
         info!(
             "Souscription à {} symboles sur {}",
@@ -434,9 +539,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let (metrics_tx, _) = broadcast::channel::<String>(100);
     let metrics_tx_clone = metrics_tx.clone();
 
+    // Screening tables are created by the schema migrations
+    let screening_repo = Some(repositories.screening.clone());
+    let screening_state = ScreeningState::new(
+        config.screening_exchange.name().to_string(),
+        screening_repo.clone(),
+    );
+
     let app_state = AppState {
         mpc_service: std::sync::Arc::new(mpc_service),
         metrics_tx: metrics_tx_clone,
+        screening: screening_state,
     };
 
     // Spawn supervision task
@@ -493,6 +606,113 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         portfolio_refresh_task(app_state_clone, Duration::from_secs(60)).await;
     });
 
+    // Spawn market making task
+    if config.market_making_enabled {
+        info!(
+            "Market making enabled on {:?}",
+            config.market_making_symbols
+        );
+        let app_state_clone = app_state.clone();
+        let interval = Duration::from_secs(config.market_making_interval_seconds);
+        tokio::spawn(async move {
+            market_making_task(app_state_clone, interval).await;
+        });
+    }
+
+    // Spawn pairs trading task
+    if config.pairs_trading_enabled {
+        info!("Pairs trading enabled on {:?}", config.pairs_trading_pairs);
+        let app_state_clone = app_state.clone();
+        let interval = Duration::from_secs(config.pairs_trading_interval_seconds);
+        tokio::spawn(async move {
+            pairs_trading_task(app_state_clone, interval).await;
+        });
+    }
+
+    // Spawn cross-exchange arbitrage task
+    if config.arbitrage_enabled {
+        info!(
+            "Arbitrage scanning enabled (execution {})",
+            if config.arbitrage_execute {
+                "on"
+            } else {
+                "off"
+            }
+        );
+        let app_state_clone = app_state.clone();
+        let interval = Duration::from_secs(config.arbitrage_interval_seconds);
+        tokio::spawn(async move {
+            arbitrage_task(app_state_clone, interval).await;
+        });
+    }
+
+    // Spawn symbol screening actor
+    if config.screening_enabled {
+        info!(
+            "Symbol screening enabled on {} every {}s",
+            get_exchange_name(&config.screening_exchange),
+            config.screening_interval_seconds
+        );
+        let (results_tx, results_rx) = mpsc::channel::<ScreeningResults>(100);
+        let mut actor = ScreeningActor::new(
+            config.screening_exchange.name().to_string(),
+            Duration::from_secs(config.screening_interval_seconds),
+            results_tx,
+        )
+        .with_cache_ttl(Duration::from_secs(config.screening_cache_ttl_seconds))
+        .with_aggregator(ScalpingPotentialAggregator::from_config(&config))
+        .with_data_source(app_state.mpc_service.clone());
+        if let Some(repo) = screening_repo {
+            actor = actor.with_repository(repo);
+        }
+        let (msg_tx, msg_rx) = mpsc::channel::<ScreeningMessage>(50);
+        tokio::spawn(actor.run(msg_rx));
+
+        let app_state_clone = app_state.clone();
+        tokio::spawn(async move {
+            screening_results_task(app_state_clone, results_rx, msg_tx).await;
+        });
+    }
+
+    // Spawn dynamic symbol universe task
+    if config.dynamic_universe_enabled {
+        info!(
+            "Dynamic symbol universe enabled on {:?} (top {})",
+            config.dynamic_universe_exchanges, config.dynamic_universe.top_n
+        );
+        let app_state_clone = app_state.clone();
+        let interval = Duration::from_secs(config.dynamic_universe_interval_seconds);
+        tokio::spawn(async move {
+            dynamic_universe_task(app_state_clone, interval).await;
+        });
+    }
+
+    // Spawn balance reconciliation task
+    if app_state.mpc_service.reconciliation.is_some() {
+        info!(
+            "Balance reconciliation every {}s",
+            config.reconciliation_interval_seconds
+        );
+        let app_state_clone = app_state.clone();
+        let interval = Duration::from_secs(config.reconciliation_interval_seconds);
+        tokio::spawn(async move {
+            reconciliation_task(app_state_clone, interval).await;
+        });
+    }
+
+    // Spawn funding payment sync task
+    if app_state.mpc_service.funding_source.is_some() {
+        info!(
+            "dYdX funding payments synced every {}s",
+            config.funding_sync_interval_seconds
+        );
+        let app_state_clone = app_state.clone();
+        let interval = Duration::from_secs(config.funding_sync_interval_seconds);
+        tokio::spawn(async move {
+            funding_sync_task(app_state_clone, interval).await;
+        });
+    }
+
     // Public routes (no authentication required)
     let public_routes = Router::new()
         .route(
@@ -510,6 +730,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/prices", get(get_all_prices))
         .route("/prices/:symbol", get(get_symbol_price))
         .route("/signals", get(get_all_signals))
+        .route("/signals/history", get(get_signal_history))
         .route("/signals/:symbol", get(get_symbol_signal))
         .route("/orders/execute", post(execute_pending_orders))
         .route("/orders/:symbol/execute", post(execute_symbol_order))
@@ -526,9 +747,54 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .route("/positions", get(get_positions))
         .route("/positions/pnl", get(get_total_pnl))
+        .route("/positions/groups", get(get_position_groups))
+        .route("/pnl/realized", get(get_realized_pnl))
+        .route("/pnl/funding", post(record_funding_payment))
         .route("/portfolio", get(get_portfolio))
         .route("/portfolio/refresh", post(refresh_portfolio))
         .route("/config", get(get_config))
+        .route("/strategies", get(get_strategies))
+        .route("/strategies/metrics", get(get_symbol_strategy_metrics))
+        .route("/strategies/shadow", get(get_shadow_strategies))
+        .route("/regimes", get(get_market_regimes))
+        .route("/market-making", get(get_market_making))
+        .route("/arbitrage", get(get_arbitrage))
+        .route("/screening", get(screening_handler::get_screening_results))
+        .route("/universe", get(get_symbol_universe))
+        .route("/reconciliation", get(get_reconciliation))
+        .route("/reconciliation/run", post(run_reconciliation))
+        .route(
+            "/reconciliation/:exchange/history",
+            get(get_reconciliation_history),
+        )
+        .route(
+            "/reconciliation/audit/:id/annotate",
+            post(annotate_reconciliation),
+        )
+        .route(
+            "/reconciliation/audit/:id/resolve",
+            post(resolve_reconciliation),
+        )
+        .route(
+            "/reconciliation/positions",
+            get(get_position_reconciliation),
+        )
+        .route(
+            "/reconciliation/positions/run",
+            post(run_position_reconciliation),
+        )
+        .route(
+            "/reconciliation/positions/:exchange/:symbol/adopt",
+            post(adopt_orphan_position),
+        )
+        .route(
+            "/reconciliation/positions/:exchange/:symbol/close",
+            post(close_ghost_positions),
+        )
+        .route(
+            "/screening/:symbol",
+            get(screening_handler::get_symbol_screening_details),
+        )
         .route("/alerts", get(get_alerts))
         .route("/performance", get(get_performance_profiles))
         .route_layer(middleware::from_fn(move |req, next| {
@@ -616,6 +882,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("Server shutting down gracefully...");
 
+    // Pull resting quotes before the exchange connections go away
+    if app_state.mpc_service.config.market_making_enabled {
+        app_state.mpc_service.cancel_quotes(None).await;
+    }
+
     // Shutdown all actors
     app_state.mpc_service.shutdown().await;
 
@@ -743,6 +1014,18 @@ async fn signal_generation_task(app_state: AppState) {
                     debug!("✓ Signal stored for {} in LRU cache", normalized_symbol);
                 }
 
+                // Paper-trade the shadow combiners on the same candles
+                for result in app_state
+                    .mpc_service
+                    .run_shadow_strategies(&normalized_symbol)
+                    .await
+                {
+                    match result {
+                        Ok(msg) => debug!("{}", msg),
+                        Err(e) => debug!("Shadow trading skipped for {}: {}", normalized_symbol, e),
+                    }
+                }
+
                 // Update position prices and metrics
                 if let Err(e) = app_state.mpc_service.update_position_prices().await {
                     debug!("Failed to update position prices: {}", e);
@@ -791,12 +1074,7 @@ async fn order_execution_task(app_state: AppState) {
         // Count only ACTUAL orders executed (not Hold signals or low confidence rejections)
         let successful_orders = results
             .iter()
-            .filter(|r| {
-                r.as_ref()
-                    .ok()
-                    .map(|msg| msg.contains("Order executed") && msg.contains("Order ID"))
-                    .unwrap_or(false)
-            })
+            .filter(|r| matches!(r, Ok(SignalExecution::Executed(_))))
             .count();
 
         let failed_orders = results.iter().filter(|r| r.is_err()).count();
@@ -838,12 +1116,7 @@ async fn order_execution_task_iteration(app_state: AppState) -> Result<(), Strin
     // Count only ACTUAL orders executed (not Hold signals or low confidence rejections)
     let successful_orders = results
         .iter()
-        .filter(|r| {
-            r.as_ref()
-                .ok()
-                .map(|msg| msg.contains("Order executed") && msg.contains("Order ID"))
-                .unwrap_or(false)
-        })
+        .filter(|r| matches!(r, Ok(SignalExecution::Executed(_))))
         .count();
 
     let failed_orders = results.iter().filter(|r| r.is_err()).count();
@@ -979,7 +1252,10 @@ async fn get_symbol_signal(
             "symbol": symbol,
             "normalized_symbol": normalized_symbol,
             "signal": format!("{:?}", signal.signal),
-            "confidence": signal.confidence
+            "confidence": signal.confidence,
+            "votes": signal.votes,
+            "indicators": signal.indicators,
+            "regime": signal.regime
         })),
         Err(e) => Json(serde_json::json!({
             "error": format!("Failed to generate signal: {}", e)
@@ -987,6 +1263,74 @@ async fn get_symbol_signal(
     }
 }
 
+/// Query parameters of `/signals/history`
+#[derive(Debug, serde::Deserialize)]
+struct SignalHistoryParams {
+    symbol: Option<String>,
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+    limit: Option<i64>,
+}
+
+/// Get persisted signals, newest first, optionally filtered by symbol and time range
+async fn get_signal_history(
+    State(app_state): State<AppState>,
+    Query(params): Query<SignalHistoryParams>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    let query = crate::persistence::models::SignalQuery {
+        symbol: params
+            .symbol
+            .map(|symbol| crate::config::TradingConfig::normalize_symbol(&symbol)),
+        from: params.from,
+        to: params.to,
+        limit: params.limit.unwrap_or(100).clamp(1, 1000),
+    };
+
+    let records = app_state
+        .mpc_service
+        .get_signal_history(&query)
+        .await
+        .map_err(|e| {
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Failed to query signals: {}", e)})),
+            )
+        })?;
+
+    let signals: Vec<serde_json::Value> = records
+        .iter()
+        .map(|record| {
+            serde_json::json!({
+                "id": record.id,
+                "symbol": record.symbol,
+                "timestamp": record.generated_at.to_rfc3339(),
+                "action": record.action,
+                "confidence": record.confidence,
+                "price": record.price,
+                "weights": serde_json::from_str::<serde_json::Value>(&record.weights)
+                    .unwrap_or_default(),
+                "details": serde_json::from_str::<serde_json::Value>(&record.details)
+                    .unwrap_or_default(),
+                "status": match (record.decided_at, record.executed) {
+                    (None, _) => "pending",
+                    (Some(_), true) => "executed",
+                    (Some(_), false) => "skipped",
+                },
+                "executed": record.executed,
+                "skip_reason": record.skip_reason,
+                "outcome": record.outcome,
+                "regime": record.regime,
+                "decided_at": record.decided_at.map(|t| t.to_rfc3339())
+            })
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "signals": signals,
+        "count": signals.len()
+    })))
+}
+
 /// Execute pending orders for all symbols
 async fn execute_pending_orders(State(app_state): State<AppState>) -> Json<serde_json::Value> {
     let results = app_state.mpc_service.check_and_execute_orders().await;
@@ -994,7 +1338,7 @@ async fn execute_pending_orders(State(app_state): State<AppState>) -> Json<serde
     let successful: Vec<String> = results
         .iter()
         .filter_map(|r| r.as_ref().ok())
-        .cloned()
+        .map(|execution| execution.message().to_string())
         .collect();
 
     let failed: Vec<String> = results
@@ -1024,18 +1368,31 @@ async fn execute_symbol_order(
         .await
     {
         Ok(signal) => {
-            match app_state
+            let signal_id = app_state
+                .mpc_service
+                .record_signal(&normalized_symbol, &signal)
+                .await;
+            let result = app_state
                 .mpc_service
                 .execute_order_from_signal(&normalized_symbol, &signal)
-                .await
-            {
-                Ok(msg) => Json(serde_json::json!({
+                .await;
+            if let Some(id) = signal_id {
+                app_state
+                    .mpc_service
+                    .record_signal_outcome(id, &result)
+                    .await;
+            }
+
+            match result {
+                Ok(execution) => Json(serde_json::json!({
                     "success": true,
                     "symbol": symbol,
                     "normalized_symbol": normalized_symbol,
                     "signal": format!("{:?}", signal.signal),
                     "confidence": signal.confidence,
-                    "message": msg
+                    "executed": execution.skip_reason().is_none(),
+                    "skip_reason": execution.skip_reason().map(|reason| reason.as_str()),
+                    "message": execution.message()
                 })),
                 Err(e) => Json(serde_json::json!({
                     "success": false,
@@ -1300,7 +1657,8 @@ async fn get_positions(State(app_state): State<AppState>) -> Json<serde_json::Va
                     "unrealized_pnl": position.unrealized_pnl().map(|p| p.value()),
                     "entry_time": position.entry_time.to_rfc3339(),
                     "stop_loss_price": position.stop_loss_price.map(|p| p.value()),
-                    "take_profit_price": position.take_profit_price.map(|p| p.value())
+                    "take_profit_price": position.take_profit_price.map(|p| p.value()),
+                    "group_id": position.group_id
                 }),
             )
         })
@@ -1312,6 +1670,56 @@ async fn get_positions(State(app_state): State<AppState>) -> Json<serde_json::Va
     }))
 }
 
+/// Open position groups of multi-asset strategies with their combined PnL
+async fn get_position_groups(State(app_state): State<AppState>) -> Json<serde_json::Value> {
+    use crate::domain::entities::position_group::PositionGroup;
+
+    let mut groups = app_state.mpc_service.get_position_groups().await;
+    groups.sort_by(|a, b| a.0.opened_at.cmp(&b.0.opened_at));
+    let group_data: Vec<serde_json::Value> = groups
+        .iter()
+        .map(|(group, legs)| {
+            let leg_data: Vec<serde_json::Value> = legs
+                .iter()
+                .map(|leg| {
+                    serde_json::json!({
+                        "position_id": leg.id,
+                        "symbol": leg.symbol,
+                        "side": format!("{:?}", leg.side),
+                        "quantity": leg.quantity.value(),
+                        "entry_price": leg.entry_price.value(),
+                        "current_price": leg.current_price.map(|p| p.value()),
+                        "unrealized_pnl": leg.unrealized_pnl().map(|p| p.value())
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "id": group.id,
+                "strategy": group.strategy,
+                "hedge_ratio": group.hedge_ratio,
+                "entry_zscore": group.entry_zscore,
+                "opened_at": group.opened_at.to_rfc3339(),
+                "unrealized_pnl": PositionGroup::unrealized_pnl(legs),
+                "return_pct": PositionGroup::return_pct(legs),
+                "net_exposure": PositionGroup::net_exposure(legs),
+                "legs": leg_data
+            })
+        })
+        .collect();
+    let config = &app_state.mpc_service.config;
+
+    Json(serde_json::json!({
+        "pairs_trading_enabled": config.pairs_trading_enabled,
+        "pairs": config
+            .pairs_trading_pairs
+            .iter()
+            .map(|(base, hedge)| format!("{}:{}", base, hedge))
+            .collect::<Vec<_>>(),
+        "groups": group_data,
+        "count": groups.len()
+    }))
+}
+
 /// Get total unrealized PnL across all positions
 async fn get_total_pnl(State(app_state): State<AppState>) -> Json<serde_json::Value> {
     let total_pnl = app_state.mpc_service.get_total_unrealized_pnl().await;
@@ -1322,6 +1730,80 @@ async fn get_total_pnl(State(app_state): State<AppState>) -> Json<serde_json::Va
     }))
 }
 
+/// Query parameters of `/pnl/realized`
+#[derive(Debug, serde::Deserialize)]
+struct RealizedPnlParams {
+    group_by: Option<String>, // Comma-separated: strategy, symbol, exchange, day
+}
+
+/// Realized PnL from the lot ledger, grouped by `group_by`, with open lots
+async fn get_realized_pnl(
+    State(app_state): State<AppState>,
+    Query(params): Query<RealizedPnlParams>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    use crate::domain::services::pnl_ledger::PnlGroup;
+
+    let groups = PnlGroup::parse_list(params.group_by.as_deref().unwrap_or("")).map_err(|e| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": e})),
+        )
+    })?;
+    let report = app_state.mpc_service.get_realized_pnl(&groups).await;
+
+    Ok(Json(serde_json::to_value(report).unwrap_or_default()))
+}
+
+/// Body of `POST /pnl/funding`
+#[derive(Debug, serde::Deserialize)]
+struct FundingPaymentBody {
+    symbol: String,
+    exchange: String,
+    /// Negative when paid, positive when received
+    amount: f64,
+    /// Defaults to the strategy of the oldest open lot
+    strategy: Option<String>,
+    /// Defaults to now
+    paid_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Record a funding payment in the realized PnL ledger
+async fn record_funding_payment(
+    State(app_state): State<AppState>,
+    Json(body): Json<FundingPaymentBody>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    use crate::domain::services::pnl_ledger::FundingPayment;
+
+    let exchange = Exchange::from_name(&body.exchange).ok_or_else(|| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": format!("Unknown exchange: {}", body.exchange)})),
+        )
+    })?;
+    let payment = FundingPayment {
+        symbol: crate::config::TradingConfig::normalize_symbol(&body.symbol),
+        exchange: exchange.name().to_string(),
+        strategy: body.strategy,
+        amount: body.amount,
+        paid_at: body.paid_at.unwrap_or_else(chrono::Utc::now),
+    };
+    let entry = app_state
+        .mpc_service
+        .record_funding_payment(payment)
+        .await
+        .map_err(|e| {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "entry": entry
+    })))
+}
+
 /// Get current portfolio state
 async fn get_portfolio(State(app_state): State<AppState>) -> Json<serde_json::Value> {
     use crate::application::services::mpc_service::PortfolioState;
@@ -1432,10 +1914,449 @@ async fn get_config(State(app_state): State<AppState>) -> Json<serde_json::Value
         "portfolio_percentage_per_position": mpc_service.config.portfolio_percentage_per_position,
         "max_trades_per_hour": mpc_service.config.max_trades_per_hour,
         "max_trades_per_day": mpc_service.config.max_trades_per_day,
+        "max_holding_minutes": mpc_service.config.time_exit.max_holding.map(|d| d.num_minutes()),
+        "no_progress_minutes": mpc_service.config.time_exit.no_progress_after.map(|d| d.num_minutes()),
+        "no_progress_min_percentage": mpc_service.config.time_exit.min_progress_pct,
         "symbols_count": mpc_service.config.symbols.len()
     }))
 }
 
+/// List registered strategies with their parameters and the weights of each symbol group
+async fn get_strategies(State(app_state): State<AppState>) -> Json<serde_json::Value> {
+    let (strategies, groups) = app_state.mpc_service.get_active_strategies().await;
+    let allocator = app_state.mpc_service.get_allocator_state().await;
+    let group_data: Vec<serde_json::Value> = groups
+        .iter()
+        .map(|group| {
+            serde_json::json!({
+                "name": group.name,
+                "symbols": group.symbols,
+                "categories": group.categories,
+                "default": group.symbols.is_empty() && group.categories.is_empty(),
+                "min_confidence_threshold": group.min_confidence_threshold,
+                "weights": group
+                    .weights
+                    .iter()
+                    .map(|(strategy, weight)| serde_json::json!({
+                        "strategy": strategy,
+                        "weight": weight
+                    }))
+                    .collect::<Vec<_>>(),
+                "regimes": group.regimes
+            })
+        })
+        .collect();
+
+    Json(serde_json::json!({
+        "strategies": strategies,
+        "groups": group_data,
+        "allocator": allocator,
+        "count": strategies.len()
+    }))
+}
+
+/// Paper results of the shadow combiners next to the live results
+async fn get_shadow_strategies(State(app_state): State<AppState>) -> Json<serde_json::Value> {
+    let (live, shadows) = app_state.mpc_service.get_shadow_comparison().await;
+    let shadow_data: Vec<serde_json::Value> = shadows
+        .iter()
+        .map(|shadow| {
+            serde_json::json!({
+                "name": shadow.name,
+                "symbols": shadow.symbols,
+                "categories": shadow.categories,
+                "min_confidence_threshold": shadow.min_confidence_threshold,
+                "weights": shadow
+                    .weights
+                    .iter()
+                    .map(|(strategy, weight)| serde_json::json!({
+                        "strategy": strategy,
+                        "weight": weight
+                    }))
+                    .collect::<Vec<_>>(),
+                "signals_generated": shadow.signals_generated,
+                "signals_executed": shadow.signals_executed,
+                "results": shadow.results,
+                "live": shadow.live,
+                "realized_pnl_vs_live": shadow.results.realized_pnl - shadow.live.realized_pnl
+            })
+        })
+        .collect();
+
+    Json(serde_json::json!({
+        "live": live,
+        "shadows": shadow_data,
+        "count": shadows.len()
+    }))
+}
+
+/// Resting market making quotes and the inventory they built up
+async fn get_market_making(State(app_state): State<AppState>) -> Json<serde_json::Value> {
+    let (mut quotes, mut inventory) = app_state.mpc_service.get_market_making_state().await;
+    quotes.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    inventory.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    let config = &app_state.mpc_service.config;
+
+    Json(serde_json::json!({
+        "enabled": config.market_making_enabled,
+        "symbols": config.market_making_symbols,
+        "quotes": quotes,
+        "inventory": inventory
+    }))
+}
+
+/// Cross-exchange divergences, widest first, and recent arbitrage attempts
+async fn get_arbitrage(State(app_state): State<AppState>) -> Json<serde_json::Value> {
+    let (mut divergences, executions) = app_state.mpc_service.get_arbitrage_state().await;
+    divergences.sort_by(|a, b| b.net_spread_bps.total_cmp(&a.net_spread_bps));
+    let config = &app_state.mpc_service.config;
+    let opportunities: Vec<_> = divergences
+        .iter()
+        .filter(|d| d.is_opportunity(&config.arbitrage))
+        .map(|d| d.symbol.clone())
+        .collect();
+
+    Json(serde_json::json!({
+        "enabled": config.arbitrage_enabled,
+        "execute": config.arbitrage_execute,
+        "min_net_spread_bps": config.arbitrage.min_net_spread_bps,
+        "opportunities": opportunities,
+        "divergences": divergences,
+        "executions": executions
+    }))
+}
+
+/// Symbols added by the dynamic universe, per exchange
+async fn get_symbol_universe(State(app_state): State<AppState>) -> Json<serde_json::Value> {
+    let universes = app_state.mpc_service.get_symbol_universes().await;
+    let config = &app_state.mpc_service.config;
+    let members: HashMap<&str, _> = universes
+        .iter()
+        .map(|(exchange, members)| (exchange.name(), members))
+        .collect();
+
+    Json(serde_json::json!({
+        "enabled": config.dynamic_universe_enabled,
+        "top_n": config.dynamic_universe.top_n,
+        "hysteresis_minutes": config.dynamic_universe.hysteresis.num_minutes(),
+        "members": members
+    }))
+}
+
+fn reconciliation_report_json(report: &ReconciliationReport) -> serde_json::Value {
+    serde_json::json!({
+        "id": report.id(),
+        "exchange": report.exchange.name(),
+        "timestamp": report.timestamp.to_rfc3339(),
+        "status": report.status.to_string(),
+        "discrepancy_count": report.discrepancy_count(),
+        "discrepancies": report.discrepancies,
+        "local_balances": report.local_balances,
+        "exchange_balances": report.exchange_balances,
+        "recovery_status": report.remediation.as_ref().map(|r| r.status.as_str()),
+        "recovery_steps": report.remediation.as_ref().map(|r| &r.steps),
+        "operator_notes": report.operator_notes
+    })
+}
+
+/// Latest reconciliation report of every reconciled exchange, the reports
+/// waiting for an operator and the currencies paused until they are resolved
+async fn get_reconciliation(State(app_state): State<AppState>) -> Json<serde_json::Value> {
+    let mpc_service = &app_state.mpc_service;
+    match mpc_service.get_reconciliation_status().await {
+        Ok(reports) => Json(serde_json::json!({
+            "enabled": true,
+            "interval_seconds": mpc_service.config.reconciliation_interval_seconds,
+            "reports": reports.iter().map(reconciliation_report_json).collect::<Vec<_>>(),
+            "open_remediations": mpc_service
+                .get_open_remediations()
+                .await
+                .unwrap_or_default()
+                .iter()
+                .map(reconciliation_report_json)
+                .collect::<Vec<_>>(),
+            "paused_currencies": mpc_service
+                .get_paused_currencies()
+                .await
+                .into_iter()
+                .map(|(currency, exchange)| serde_json::json!({
+                    "currency": currency,
+                    "paused_by": exchange.name()
+                }))
+                .collect::<Vec<_>>()
+        })),
+        Err(e) => Json(serde_json::json!({
+            "enabled": false,
+            "error": e
+        })),
+    }
+}
+
+/// Reconcile every exchange now instead of waiting for the next interval
+async fn run_reconciliation(State(app_state): State<AppState>) -> Json<serde_json::Value> {
+    match app_state.mpc_service.run_reconciliation().await {
+        Ok(reports) => Json(serde_json::json!({
+            "success": true,
+            "reports": reports.iter().map(reconciliation_report_json).collect::<Vec<_>>()
+        })),
+        Err(e) => Json(serde_json::json!({
+            "success": false,
+            "error": e
+        })),
+    }
+}
+
+/// Query parameters of `/reconciliation/:exchange/history`
+#[derive(Debug, serde::Deserialize)]
+struct ReconciliationHistoryParams {
+    days: Option<u32>,
+}
+
+/// Persisted reconciliation reports of an exchange, newest first
+async fn get_reconciliation_history(
+    State(app_state): State<AppState>,
+    Path(exchange): Path<String>,
+    Query(params): Query<ReconciliationHistoryParams>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    let exchange = Exchange::from_name(&exchange).ok_or_else(|| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": format!("Unknown exchange: {}", exchange)})),
+        )
+    })?;
+    let days = params.days.unwrap_or(7).clamp(1, 365);
+
+    let reports = app_state
+        .mpc_service
+        .get_reconciliation_history(&exchange, days)
+        .await
+        .map_err(|e| {
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": e})),
+            )
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "exchange": exchange.name(),
+        "days": days,
+        "reports": reports.iter().map(reconciliation_report_json).collect::<Vec<_>>(),
+        "count": reports.len()
+    })))
+}
+
+/// Body of the reconciliation annotate and resolve endpoints
+#[derive(Debug, serde::Deserialize)]
+struct ReconciliationNote {
+    note: Option<String>,
+}
+
+fn reconciliation_error(e: String) -> (axum::http::StatusCode, Json<serde_json::Value>) {
+    let status = if e.starts_with("Validation Error") {
+        axum::http::StatusCode::BAD_REQUEST
+    } else {
+        axum::http::StatusCode::INTERNAL_SERVER_ERROR
+    };
+    (status, Json(serde_json::json!({"error": e})))
+}
+
+/// Append an operator note to a reconciliation report
+async fn annotate_reconciliation(
+    State(app_state): State<AppState>,
+    Path(id): Path<String>,
+    Json(body): Json<ReconciliationNote>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    let note = body.note.unwrap_or_default();
+    if note.trim().is_empty() {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "note is required"})),
+        ));
+    }
+    app_state
+        .mpc_service
+        .annotate_reconciliation(&id, &note)
+        .await
+        .map_err(reconciliation_error)?;
+
+    Ok(Json(serde_json::json!({"success": true, "id": id})))
+}
+
+/// Resolve a paused or unacknowledged reconciliation report, adopting its
+/// exchange balances and resuming trading on the currencies it paused
+async fn resolve_reconciliation(
+    State(app_state): State<AppState>,
+    Path(id): Path<String>,
+    body: Option<Json<ReconciliationNote>>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    let note = body.and_then(|Json(body)| body.note);
+    let report = app_state
+        .mpc_service
+        .resolve_reconciliation(&id, note.as_deref())
+        .await
+        .map_err(reconciliation_error)?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "report": reconciliation_report_json(&report)
+    })))
+}
+
+fn position_reconciliation_json(report: &PositionReconciliationReport) -> serde_json::Value {
+    serde_json::json!({
+        "exchange": report.exchange.name(),
+        "timestamp": report.timestamp.to_rfc3339(),
+        "status": report.status.to_string(),
+        "discrepancies": report.discrepancies,
+        "unresolved": report.unresolved(),
+        "pending": report.pending,
+        "adopted": report.adopted,
+        "closed": report.closed
+    })
+}
+
+/// Latest position reconciliation of every reconciled exchange
+async fn get_position_reconciliation(State(app_state): State<AppState>) -> Json<serde_json::Value> {
+    let mpc_service = &app_state.mpc_service;
+    Json(serde_json::json!({
+        "adopt_orphans": mpc_service.config.position_reconciliation.adopt_orphans,
+        "close_ghosts": mpc_service.config.position_reconciliation.close_ghosts,
+        "reports": mpc_service
+            .get_position_reconciliations()
+            .await
+            .iter()
+            .map(position_reconciliation_json)
+            .collect::<Vec<_>>()
+    }))
+}
+
+/// Reconcile positions and open orders now
+async fn run_position_reconciliation(State(app_state): State<AppState>) -> Json<serde_json::Value> {
+    match app_state.mpc_service.run_position_reconciliation().await {
+        Ok(report) => Json(serde_json::json!({
+            "success": true,
+            "report": position_reconciliation_json(&report)
+        })),
+        Err(e) => Json(serde_json::json!({
+            "success": false,
+            "error": e
+        })),
+    }
+}
+
+fn position_reconciliation_exchange(
+    exchange: &str,
+) -> Result<Exchange, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    Exchange::from_name(exchange).ok_or_else(|| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": format!("Unknown exchange: {}", exchange)})),
+        )
+    })
+}
+
+/// Track an orphan venue position as a local position
+async fn adopt_orphan_position(
+    State(app_state): State<AppState>,
+    Path((exchange, symbol)): Path<(String, String)>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    let exchange = position_reconciliation_exchange(&exchange)?;
+    let position_id = app_state
+        .mpc_service
+        .adopt_orphan_position(&exchange, &symbol)
+        .await
+        .map_err(|e| {
+            (
+                axum::http::StatusCode::CONFLICT,
+                Json(serde_json::json!({"error": e})),
+            )
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "position_id": position_id
+    })))
+}
+
+/// Close local positions the venue no longer holds, without sending an order
+async fn close_ghost_positions(
+    State(app_state): State<AppState>,
+    Path((exchange, symbol)): Path<(String, String)>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    let exchange = position_reconciliation_exchange(&exchange)?;
+    let closed = app_state
+        .mpc_service
+        .close_ghost_positions(&exchange, &symbol)
+        .await
+        .map_err(|e| {
+            (
+                axum::http::StatusCode::CONFLICT,
+                Json(serde_json::json!({"error": e})),
+            )
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "closed": closed
+    })))
+}
+
+/// Current market regime of every classified symbol
+async fn get_market_regimes(State(app_state): State<AppState>) -> Json<serde_json::Value> {
+    let regimes = app_state.mpc_service.get_market_regimes().await;
+    let mut regime_data: Vec<serde_json::Value> = regimes
+        .iter()
+        .map(|(symbol, reading)| {
+            serde_json::json!({
+                "symbol": symbol,
+                "regime": reading.regime,
+                "adx": reading.adx,
+                "plus_di": reading.plus_di,
+                "minus_di": reading.minus_di,
+                "realized_volatility": reading.realized_volatility,
+                "bollinger_bandwidth": reading.bollinger_bandwidth
+            })
+        })
+        .collect();
+    regime_data.sort_by(|a, b| a["symbol"].as_str().cmp(&b["symbol"].as_str()));
+
+    Json(serde_json::json!({
+        "regimes": regime_data,
+        "count": regimes.len()
+    }))
+}
+
+/// Strategy metrics per (strategy, symbol) pair
+async fn get_symbol_strategy_metrics(State(app_state): State<AppState>) -> Json<serde_json::Value> {
+    let pair_metrics = app_state.mpc_service.get_symbol_strategy_metrics().await;
+    let mut metrics_data: Vec<serde_json::Value> = pair_metrics
+        .iter()
+        .map(|((strategy, symbol), metrics)| {
+            serde_json::json!({
+                "strategy": strategy,
+                "symbol": symbol,
+                "signals_generated": metrics.signals_generated,
+                "signals_executed": metrics.signals_executed,
+                "execution_rate": metrics.execution_rate,
+                "strategy_pnl": metrics.strategy_pnl.value(),
+                "winning_trades": metrics.winning_trades,
+                "losing_trades": metrics.losing_trades,
+                "win_rate": metrics.win_rate(),
+                "performance_score": metrics.performance_score
+            })
+        })
+        .collect();
+    metrics_data.sort_by(|a, b| {
+        (a["strategy"].as_str(), a["symbol"].as_str())
+            .cmp(&(b["strategy"].as_str(), b["symbol"].as_str()))
+    });
+
+    Json(serde_json::json!({
+        "metrics": metrics_data,
+        "count": pair_metrics.len()
+    }))
+}
+
 /// Get current trading metrics
 async fn get_metrics(State(app_state): State<AppState>) -> Json<serde_json::Value> {
     let trading_metrics = app_state.mpc_service.get_trading_metrics().await;
@@ -1614,6 +2535,155 @@ async fn alerting_task(app_state: AppState) {
     }
 }
 
+/// Background task keeping market making quotes fresh
+async fn market_making_task(app_state: AppState, interval_duration: Duration) {
+    let mut interval = tokio::time::interval(interval_duration);
+
+    loop {
+        interval.tick().await;
+
+        for result in app_state.mpc_service.run_market_making().await {
+            match result {
+                Ok(message) => debug!("Market making: {}", message),
+                Err(e) => warn!("Market making: {}", e),
+            }
+        }
+    }
+}
+
+/// Background task opening and closing pairs on their spread z-score
+async fn pairs_trading_task(app_state: AppState, interval_duration: Duration) {
+    let mut interval = tokio::time::interval(interval_duration);
+
+    loop {
+        interval.tick().await;
+
+        for result in app_state.mpc_service.run_pairs_trading().await {
+            match result {
+                Ok(message) => debug!("Pairs trading: {}", message),
+                Err(e) => warn!("Pairs trading: {}", e),
+            }
+        }
+    }
+}
+
+/// Background task comparing prices across exchanges
+async fn arbitrage_task(app_state: AppState, interval_duration: Duration) {
+    let mut interval = tokio::time::interval(interval_duration);
+
+    loop {
+        interval.tick().await;
+
+        for result in app_state.mpc_service.scan_arbitrage().await {
+            match result {
+                Ok(message) => info!("Arbitrage: {}", message),
+                Err(e) => debug!("Arbitrage: {}", e),
+            }
+        }
+    }
+}
+
+/// Background task screening exchange market lists into the symbol universe
+async fn dynamic_universe_task(app_state: AppState, interval_duration: Duration) {
+    let mut interval = tokio::time::interval(interval_duration);
+
+    loop {
+        interval.tick().await;
+
+        for result in app_state.mpc_service.run_dynamic_universe().await {
+            match result {
+                Ok(message) => info!("Universe: {}", message),
+                Err(e) => warn!("Universe: {}", e),
+            }
+        }
+    }
+}
+
+/// Background task entering the exchange's funding payments in the PnL ledger
+async fn funding_sync_task(app_state: AppState, interval_duration: Duration) {
+    let mut interval = tokio::time::interval(interval_duration);
+
+    loop {
+        interval.tick().await;
+
+        match app_state.mpc_service.sync_funding_payments().await {
+            Ok(0) => debug!("Funding sync: no new payments"),
+            Ok(entered) => info!("Funding sync: {} payment(s) entered", entered),
+            Err(e) => warn!("Funding sync: {}", e),
+        }
+    }
+}
+
+/// Background task reconciling local balances against the exchanges
+async fn reconciliation_task(app_state: AppState, interval_duration: Duration) {
+    let mut interval = tokio::time::interval(interval_duration);
+
+    loop {
+        interval.tick().await;
+
+        match app_state.mpc_service.run_reconciliation().await {
+            Ok(reports) => {
+                for report in reports {
+                    match report.status {
+                        ReconciliationStatus::Ok => {
+                            debug!("Reconciliation: {} balances match", report.exchange.name())
+                        }
+                        status => warn!(
+                            "Reconciliation: {} {} with {} discrepancies",
+                            report.exchange.name(),
+                            status,
+                            report.discrepancy_count()
+                        ),
+                    }
+                }
+            }
+            Err(e) => warn!("Reconciliation: {}", e),
+        }
+
+        match app_state.mpc_service.run_position_reconciliation().await {
+            Ok(report) if report.status == ReconciliationStatus::Ok => {
+                debug!(
+                    "Position reconciliation: {} matches",
+                    report.exchange.name()
+                )
+            }
+            Ok(report) => warn!(
+                "Position reconciliation: {} {} with {} discrepancies ({} adopted, {} closed)",
+                report.exchange.name(),
+                report.status,
+                report.discrepancies.len(),
+                report.adopted.len(),
+                report.closed.len()
+            ),
+            Err(e) => debug!("Position reconciliation: {}", e),
+        }
+    }
+}
+
+/// Background task publishing screening results to the API and to the
+/// screening categories of strategy groups
+///
+/// Holds the actor's message sender so the actor keeps running.
+async fn screening_results_task(
+    app_state: AppState,
+    mut results_rx: mpsc::Receiver<ScreeningResults>,
+    _msg_tx: mpsc::Sender<ScreeningMessage>,
+) {
+    while let Some(screening) = results_rx.recv().await {
+        info!(
+            "Screened {} symbols on {}",
+            screening.results.len(),
+            screening.exchange
+        );
+        app_state
+            .mpc_service
+            .update_symbol_categories(&screening.results)
+            .await;
+        *app_state.screening.latest.write().await = screening.results;
+    }
+    warn!("Screening actor stopped");
+}
+
 /// Background task for refreshing portfolio value from all exchanges
 async fn portfolio_refresh_task(app_state: AppState, interval_duration: Duration) {
     let mut interval = tokio::time::interval(interval_duration);
@@ -1640,6 +2710,9 @@ async fn portfolio_refresh_task(app_state: AppState, interval_duration: Duration
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::services::strategies::{
+        FastScalping, MomentumScalping, SignalCombiner, Strategy,
+    };
     use std::time::Duration;
 
     #[tokio::test]
@@ -1650,6 +2723,7 @@ mod tests {
         let app_state = AppState {
             mpc_service: std::sync::Arc::new(mpc_service),
             metrics_tx: metrics_tx.clone(),
+            screening: ScreeningState::new("dydx".to_string(), None),
         };
 
         let mut rx = metrics_tx.subscribe();
@@ -1701,6 +2775,7 @@ mod tests {
         let app_state = AppState {
             mpc_service: std::sync::Arc::new(service),
             metrics_tx,
+            screening: ScreeningState::new("dydx".to_string(), None),
         };
 
         let handle = tokio::spawn(strategy_weight_adjustment_task(
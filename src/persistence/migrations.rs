@@ -0,0 +1,879 @@
+//! Versioned schema migrations
+//!
+//! Migrations are applied in version order, each in its own transaction, and
+//! recorded in `schema_version` with a checksum of their statements. A
+//! recorded migration whose statements have changed since it was applied
+//! stops startup instead of leaving the schema in an unknown state.
+//!
+//! Migrations are append-only: never edit one that has shipped, add a new
+//! version instead. Checksums collapse whitespace, so reindenting a shipped
+//! statement is harmless. This list is the SQLite schema; the PostgreSQL
+//! backend keeps its own list in `postgres` and shares the bookkeeping here.
+
+use super::{DatabaseError, DbPool};
+use sha2::{Digest, Sha256};
+use sqlx::SqliteConnection;
+use tracing::info;
+
+/// One step of the schema history
+#[derive(Debug)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    /// Adoption step for databases created before migrations were versioned:
+    /// an `ALTER TABLE ... ADD COLUMN` is skipped when the table already has
+    /// the column, and a `CREATE INDEX` when the table lacks one of its
+    /// columns. Any other migration fails on such statements.
+    pub adopts: bool,
+    pub statements: &'static [&'static str],
+}
+
+impl Migration {
+    /// SHA-256 of the statements with their whitespace collapsed, hex encoded
+    pub fn checksum(&self) -> String {
+        self.digest(|statement| statement.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    /// Checksum recorded before whitespace was collapsed, which migrations
+    /// applied back then still carry
+    pub fn legacy_checksum(&self) -> String {
+        self.digest(|statement| statement.trim().to_string())
+    }
+
+    fn digest(&self, normalize: impl Fn(&str) -> String) -> String {
+        let mut hasher = Sha256::new();
+        for statement in self.statements {
+            hasher.update(normalize(statement).as_bytes());
+            hasher.update(b"\n;\n");
+        }
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Every SQLite migration, oldest first
+///
+/// Version 1 is the schema created inline before migrations were versioned.
+/// Its tables are created only when missing, so databases from that time are
+/// adopted as they are; version 5 adds the columns the inline schema grew
+/// over time to the tables such databases already had, and creates the
+/// indexes on them version 1 had to skip. Both are adoption steps.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "baseline",
+        adopts: true,
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS positions (
+                id TEXT PRIMARY KEY,
+                symbol TEXT NOT NULL,
+                exchange TEXT NOT NULL,
+                side TEXT NOT NULL CHECK(side IN ('long', 'short')),
+                entry_price REAL NOT NULL,
+                quantity REAL NOT NULL,
+                current_price REAL NOT NULL,
+                unrealized_pnl REAL NOT NULL,
+                status TEXT NOT NULL CHECK(status IN ('open', 'closed')),
+                opened_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                closed_at DATETIME,
+                stop_loss REAL,
+                take_profit REAL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS trades (
+                id TEXT PRIMARY KEY,
+                position_id TEXT,
+                symbol TEXT NOT NULL,
+                exchange TEXT NOT NULL,
+                side TEXT NOT NULL CHECK(side IN ('buy', 'sell')),
+                price REAL NOT NULL,
+                quantity REAL NOT NULL,
+                fee REAL NOT NULL DEFAULT 0.0,
+                exchange_order_id TEXT,
+                executed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                strategy TEXT NOT NULL,
+                signal_confidence REAL,
+                exit_reason TEXT,
+                signal_details TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (position_id) REFERENCES positions(id)
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS signals (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                symbol TEXT NOT NULL,
+                action TEXT NOT NULL,
+                confidence REAL NOT NULL,
+                price REAL,
+                weights TEXT NOT NULL,
+                details TEXT NOT NULL,
+                executed INTEGER NOT NULL DEFAULT 0,
+                skip_reason TEXT,
+                outcome TEXT,
+                regime TEXT,
+                generated_at DATETIME NOT NULL,
+                decided_at DATETIME
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                event_type TEXT NOT NULL,
+                exchange TEXT NOT NULL,
+                symbol TEXT,
+                details TEXT NOT NULL,
+                timestamp DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS reconciliation_audit (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                reconciliation_id TEXT NOT NULL UNIQUE,
+                exchange_id TEXT NOT NULL,
+                reconciliation_timestamp DATETIME NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                status TEXT NOT NULL,
+                discrepancy_count INTEGER NOT NULL,
+                local_balances_json TEXT NOT NULL,
+                exchange_balances_json TEXT NOT NULL,
+                discrepancies_json TEXT NOT NULL,
+                recovery_attempted BOOLEAN DEFAULT 0,
+                recovery_status TEXT,
+                recovery_details_json TEXT,
+                operator_notes TEXT
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS dydx_order_metadata (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                order_id TEXT NOT NULL UNIQUE,
+                dydx_order_id TEXT NOT NULL,
+                good_until_block INTEGER,
+                client_id INTEGER,
+                subaccount_number INTEGER,
+                order_flags INTEGER DEFAULT 0,
+                clob_pair_id INTEGER DEFAULT 0,
+                symbol TEXT NOT NULL,
+                side TEXT NOT NULL,
+                quantity REAL NOT NULL,
+                price REAL NOT NULL,
+                order_type TEXT NOT NULL,
+                placed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                status TEXT NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS bracket_orders (
+                id TEXT PRIMARY KEY,
+                position_id TEXT,
+                symbol TEXT NOT NULL,
+                exchange TEXT NOT NULL,
+                exit_side TEXT NOT NULL,
+                quantity REAL NOT NULL,
+                linking TEXT NOT NULL,
+                entry_order_id TEXT,
+                entry_price REAL,
+                entry_state TEXT,
+                stop_loss_price REAL NOT NULL,
+                stop_loss_order_id TEXT,
+                stop_loss_state TEXT NOT NULL,
+                take_profit_price REAL NOT NULL,
+                take_profit_order_id TEXT,
+                take_profit_state TEXT NOT NULL,
+                active INTEGER NOT NULL DEFAULT 1,
+                created_at DATETIME NOT NULL,
+                updated_at DATETIME NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS allocator_state (
+                name TEXT PRIMARY KEY,
+                policy TEXT NOT NULL,
+                state TEXT NOT NULL,
+                updated_at DATETIME NOT NULL
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_reconciliation_exchange_time ON reconciliation_audit(exchange_id, reconciliation_timestamp)",
+            "CREATE INDEX IF NOT EXISTS idx_reconciliation_status ON reconciliation_audit(status)",
+            "CREATE INDEX IF NOT EXISTS idx_positions_status ON positions(status)",
+            "CREATE INDEX IF NOT EXISTS idx_positions_symbol ON positions(symbol)",
+            "CREATE INDEX IF NOT EXISTS idx_trades_position_id ON trades(position_id)",
+            "CREATE INDEX IF NOT EXISTS idx_trades_executed_at ON trades(executed_at)",
+            "CREATE INDEX IF NOT EXISTS idx_trades_exit_reason ON trades(exit_reason)",
+            "CREATE INDEX IF NOT EXISTS idx_signals_symbol_time ON signals(symbol, generated_at)",
+            "CREATE INDEX IF NOT EXISTS idx_signals_generated_at ON signals(generated_at)",
+            "CREATE INDEX IF NOT EXISTS idx_audit_timestamp ON audit_log(timestamp)",
+            "CREATE INDEX IF NOT EXISTS idx_dydx_order_status ON dydx_order_metadata(status)",
+            "CREATE INDEX IF NOT EXISTS idx_dydx_order_placed_at ON dydx_order_metadata(placed_at)",
+            "CREATE INDEX IF NOT EXISTS idx_bracket_orders_active ON bracket_orders(active)",
+        ],
+    },
+    Migration {
+        version: 2,
+        name: "screening_results",
+        adopts: false,
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS symbol_screening_results (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                symbol TEXT NOT NULL,
+                exchange TEXT NOT NULL,
+                volatility_score REAL NOT NULL,
+                volume_score REAL NOT NULL,
+                spread_score REAL NOT NULL,
+                momentum_score REAL NOT NULL,
+                overall_score REAL NOT NULL,
+                recommendation TEXT NOT NULL,
+                component_scores TEXT,
+                screened_at TIMESTAMP NOT NULL,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_symbol_exchange ON symbol_screening_results(symbol, exchange)",
+            "CREATE INDEX IF NOT EXISTS idx_screened_at ON symbol_screening_results(screened_at)",
+        ],
+    },
+    Migration {
+        version: 3,
+        name: "dydx_order_cancellation",
+        adopts: false,
+        statements: &[
+            "ALTER TABLE dydx_order_metadata ADD COLUMN cancelled_at DATETIME",
+            "ALTER TABLE dydx_order_metadata ADD COLUMN tx_hash TEXT",
+        ],
+    },
+    Migration {
+        version: 4,
+        name: "position_groups",
+        adopts: false,
+        statements: &[
+            r#"
+            CREATE TABLE position_groups (
+                id TEXT PRIMARY KEY,
+                strategy TEXT NOT NULL,
+                hedge_ratio REAL NOT NULL,
+                entry_zscore REAL NOT NULL,
+                opened_at DATETIME NOT NULL
+            )
+            "#,
+            "ALTER TABLE positions ADD COLUMN group_id TEXT REFERENCES position_groups(id)",
+        ],
+    },
+    Migration {
+        version: 5,
+        name: "adopted_columns",
+        adopts: true,
+        statements: &[
+            "ALTER TABLE trades ADD COLUMN exit_reason TEXT",
+            "ALTER TABLE trades ADD COLUMN signal_details TEXT",
+            "ALTER TABLE signals ADD COLUMN regime TEXT",
+            "ALTER TABLE dydx_order_metadata ADD COLUMN order_flags INTEGER DEFAULT 0",
+            "ALTER TABLE dydx_order_metadata ADD COLUMN clob_pair_id INTEGER DEFAULT 0",
+            "ALTER TABLE symbol_screening_results ADD COLUMN component_scores TEXT",
+            "CREATE INDEX IF NOT EXISTS idx_trades_exit_reason ON trades(exit_reason)",
+        ],
+    },
+    Migration {
+        version: 6,
+        name: "trade_fee_estimated",
+        adopts: false,
+        // Trades recorded before fills reported their fees carry estimates
+        statements: &["ALTER TABLE trades ADD COLUMN fee_estimated BOOLEAN NOT NULL DEFAULT 1"],
+    },
+    Migration {
+        version: 7,
+        name: "funding_payments",
+        adopts: false,
+        // Funding payments used to be kept in the audit log; the ones already
+        // there are copied over, their JSON times rewritten to the stored form
+        statements: &[
+            r#"
+            CREATE TABLE funding_payments (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                exchange TEXT NOT NULL,
+                symbol TEXT NOT NULL,
+                strategy TEXT NOT NULL,
+                amount REAL NOT NULL,
+                paid_at DATETIME NOT NULL,
+                UNIQUE (exchange, symbol, paid_at)
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_funding_payments_paid_at ON funding_payments(paid_at)",
+            r#"
+            INSERT OR IGNORE INTO funding_payments (exchange, symbol, strategy, amount, paid_at)
+            SELECT exchange, symbol, json_extract(details, '$.strategy'),
+                json_extract(details, '$.amount'),
+                replace(json_extract(details, '$.paid_at'), 'Z', '+00:00')
+            FROM audit_log
+            WHERE event_type = 'funding_payment' AND symbol IS NOT NULL
+            ORDER BY id
+            "#,
+        ],
+    },
+];
+
+/// Highest version recorded in `schema_version`, 0 for an empty database
+pub async fn current_version(pool: &DbPool) -> Result<i64, DatabaseError> {
+    ensure_version_table(pool).await?;
+    let version: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM schema_version")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            DatabaseError::MigrationError(format!("Failed to read schema version: {}", e))
+        })?;
+    Ok(version.unwrap_or(0))
+}
+
+/// Verify applied migrations and apply pending ones
+///
+/// Returns the versions applied by this call.
+pub async fn migrate(pool: &DbPool) -> Result<Vec<i64>, DatabaseError> {
+    migrate_with(pool, MIGRATIONS).await
+}
+
+async fn migrate_with(pool: &DbPool, migrations: &[Migration]) -> Result<Vec<i64>, DatabaseError> {
+    ensure_version_table(pool).await?;
+
+    let recorded: Vec<(i64, String)> =
+        sqlx::query_as("SELECT version, checksum FROM schema_version ORDER BY version")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                DatabaseError::MigrationError(format!("Failed to read schema version: {}", e))
+            })?;
+
+    let mut applied = Vec::new();
+    for migration in pending(migrations, &recorded)? {
+        apply(pool, migration).await?;
+        applied.push(migration.version);
+    }
+    log_outcome(&recorded, &applied);
+    Ok(applied)
+}
+
+/// Migrations still to apply, once the recorded ones are verified
+///
+/// `recorded` holds the version and checksum of each applied migration.
+/// Fails on a version this build does not know or a changed checksum.
+pub(crate) fn pending<'a>(
+    migrations: &'a [Migration],
+    recorded: &[(i64, String)],
+) -> Result<Vec<&'a Migration>, DatabaseError> {
+    let mut latest = 0;
+    for (version, checksum) in recorded {
+        let Some(migration) = migrations.iter().find(|m| m.version == *version) else {
+            return Err(DatabaseError::MigrationError(format!(
+                "Database is at schema version {} which this build does not know; upgrade the binary",
+                version
+            )));
+        };
+        if migration.checksum() != *checksum && migration.legacy_checksum() != *checksum {
+            return Err(DatabaseError::MigrationError(format!(
+                "Checksum mismatch for migration {} ({}): it was changed after being applied",
+                version, migration.name
+            )));
+        }
+        latest = latest.max(*version);
+    }
+    Ok(migrations.iter().filter(|m| m.version > latest).collect())
+}
+
+pub(crate) fn log_outcome(recorded: &[(i64, String)], applied: &[i64]) {
+    match applied.last() {
+        Some(version) => info!(
+            "✓ Applied {} migration(s), database schema at version {}",
+            applied.len(),
+            version
+        ),
+        None => info!(
+            "✓ Database schema up to date (version {})",
+            recorded.last().map_or(0, |(version, _)| *version)
+        ),
+    }
+}
+
+async fn ensure_version_table(pool: &DbPool) -> Result<(), DatabaseError> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            checksum TEXT NOT NULL,
+            applied_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        DatabaseError::MigrationError(format!("Failed to create schema_version table: {}", e))
+    })?;
+    Ok(())
+}
+
+/// Run one migration and record it, all or nothing
+async fn apply(pool: &DbPool, migration: &Migration) -> Result<(), DatabaseError> {
+    let failed = |e: sqlx::Error| {
+        DatabaseError::MigrationError(format!(
+            "Migration {} ({}) failed: {}",
+            migration.version, migration.name, e
+        ))
+    };
+
+    let mut tx = pool.begin().await.map_err(failed)?;
+    for statement in migration.statements {
+        if migration.adopts && adopted(&mut *tx, statement).await.map_err(failed)? {
+            continue;
+        }
+        sqlx::query(statement)
+            .execute(&mut *tx)
+            .await
+            .map_err(failed)?;
+    }
+    sqlx::query("INSERT INTO schema_version (version, name, checksum) VALUES (?1, ?2, ?3)")
+        .bind(migration.version)
+        .bind(migration.name)
+        .bind(migration.checksum())
+        .execute(&mut *tx)
+        .await
+        .map_err(failed)?;
+    tx.commit().await.map_err(failed)?;
+
+    info!(
+        "Applied migration {} ({})",
+        migration.version, migration.name
+    );
+    Ok(())
+}
+
+/// Whether an adoption step skips `statement`: the column it adds is
+/// already there, or the index it creates covers a column the table lacks
+/// (a later migration adds the column and creates the index)
+async fn adopted(conn: &mut SqliteConnection, statement: &str) -> Result<bool, sqlx::Error> {
+    if let Some((table, column)) = added_column(statement) {
+        return has_column(conn, table, column).await;
+    }
+    if let Some((table, columns)) = indexed_columns(statement) {
+        for column in columns {
+            if !has_column(conn, table, column).await? {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+async fn has_column(
+    conn: &mut SqliteConnection,
+    table: &str,
+    column: &str,
+) -> Result<bool, sqlx::Error> {
+    let existing: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2")
+            .bind(table)
+            .bind(column)
+            .fetch_one(conn)
+            .await?;
+    Ok(existing > 0)
+}
+
+/// Table and column added by an `ALTER TABLE <table> ADD COLUMN <column>` statement
+fn added_column(statement: &str) -> Option<(&str, &str)> {
+    let words: Vec<&str> = statement.split_whitespace().collect();
+    match words.as_slice() {
+        [alter, table_keyword, table, add, column_keyword, column, ..]
+            if alter.eq_ignore_ascii_case("ALTER")
+                && table_keyword.eq_ignore_ascii_case("TABLE")
+                && add.eq_ignore_ascii_case("ADD")
+                && column_keyword.eq_ignore_ascii_case("COLUMN") =>
+        {
+            Some((*table, *column))
+        }
+        _ => None,
+    }
+}
+
+/// Table and columns of a `CREATE [UNIQUE] INDEX ... ON <table>(<columns>)` statement
+fn indexed_columns(statement: &str) -> Option<(&str, Vec<&str>)> {
+    let mut words = statement.split_whitespace();
+    if !words.next()?.eq_ignore_ascii_case("CREATE") {
+        return None;
+    }
+    let mut keyword = words.next()?;
+    if keyword.eq_ignore_ascii_case("UNIQUE") {
+        keyword = words.next()?;
+    }
+    if !keyword.eq_ignore_ascii_case("INDEX") {
+        return None;
+    }
+    let on = statement
+        .to_ascii_uppercase()
+        .find(" ON ")
+        .map(|position| position + " ON ".len())?;
+    let (table, rest) = statement[on..].split_once('(')?;
+    let (columns, _) = rest.split_once(')')?;
+    Some((table.trim(), columns.split(',').map(str::trim).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn empty_pool() -> DbPool {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_migrations_are_ordered_and_unique() {
+        let versions: Vec<i64> = MIGRATIONS.iter().map(|m| m.version).collect();
+        let expected: Vec<i64> = (1..=MIGRATIONS.len() as i64).collect();
+        assert_eq!(versions, expected);
+    }
+
+    #[test]
+    fn test_shipped_baseline_keeps_its_checksum() {
+        assert_eq!(
+            MIGRATIONS[0].checksum(),
+            "b32f3b2b29e989598a59dd38c504d7207eafbaca75e4419f23afdb0c4488a689"
+        );
+        assert_eq!(
+            MIGRATIONS[0].legacy_checksum(),
+            "d0a8e17310dd574fa3e2abc5f7c23c29a88406eb6a35c5e5a859c1d20887508f"
+        );
+    }
+
+    #[test]
+    fn test_shipped_postgres_baseline_keeps_its_checksum() {
+        let baseline = &crate::persistence::postgres::MIGRATIONS[0];
+        assert_eq!(
+            baseline.checksum(),
+            "1bc506e3d4f56d4e424487332e3c99fda5027e31b9385d1b9b48bb8ee3f8f8a0"
+        );
+        assert_eq!(
+            baseline.legacy_checksum(),
+            "529e61072bbb848d3936ae207f060f1a252b4d2d7840ed2648c9dfdbe2d19097"
+        );
+    }
+
+    #[test]
+    fn test_reindenting_keeps_the_checksum() {
+        let migration = |statements: &'static [&'static str]| Migration {
+            version: 1,
+            name: "indexes",
+            adopts: false,
+            statements,
+        };
+        let flat = migration(&["CREATE INDEX idx ON trades(symbol, executed_at)"]);
+        let indented =
+            migration(&["\n    CREATE INDEX idx\n        ON trades(symbol,  executed_at)\n"]);
+        assert_eq!(flat.checksum(), indented.checksum());
+        assert_ne!(flat.legacy_checksum(), indented.legacy_checksum());
+    }
+
+    #[tokio::test]
+    async fn test_checksums_recorded_before_collapsing_whitespace_verify() {
+        let pool = empty_pool().await;
+        migrate(&pool).await.unwrap();
+        sqlx::query("UPDATE schema_version SET checksum = ?1 WHERE version = 1")
+            .bind(MIGRATIONS[0].legacy_checksum())
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(migrate(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_only_adoption_steps_tolerate_existing_columns() {
+        const ADDS_TWICE: &[Migration] = &[
+            Migration {
+                version: 1,
+                name: "table",
+                adopts: false,
+                statements: &["CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT)"],
+            },
+            Migration {
+                version: 2,
+                name: "body",
+                adopts: false,
+                statements: &["ALTER TABLE notes ADD COLUMN body TEXT"],
+            },
+        ];
+        let pool = empty_pool().await;
+        let err = migrate_with(&pool, ADDS_TWICE).await.unwrap_err();
+        assert!(err.to_string().contains("Migration 2 (body) failed"));
+        assert_eq!(current_version(&pool).await.unwrap(), 1);
+
+        const ADOPTS: &[Migration] = &[Migration {
+            version: 2,
+            name: "body",
+            adopts: true,
+            statements: &[
+                "ALTER TABLE notes ADD COLUMN body TEXT",
+                "CREATE INDEX IF NOT EXISTS idx_notes_author ON notes(author)",
+            ],
+        }];
+        sqlx::query("DELETE FROM schema_version")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(migrate_with(&pool, ADOPTS).await.unwrap(), vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_applies_once_and_records_versions() {
+        let pool = empty_pool().await;
+        let applied = migrate(&pool).await.unwrap();
+        assert_eq!(applied.len(), MIGRATIONS.len());
+        assert_eq!(
+            current_version(&pool).await.unwrap(),
+            MIGRATIONS.len() as i64
+        );
+
+        assert!(migrate(&pool).await.unwrap().is_empty());
+
+        let columns: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info('dydx_order_metadata')")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert!(columns.iter().any(|c| c == "tx_hash"));
+        assert!(columns.iter().any(|c| c == "cancelled_at"));
+    }
+
+    #[tokio::test]
+    async fn test_funding_payments_are_copied_from_the_audit_log() {
+        use crate::persistence::models::CreateAuditLog;
+        use crate::persistence::repository::{
+            AuditLogRepository, FundingPaymentRepository, SqliteAuditLogRepository,
+            SqliteFundingPaymentRepository,
+        };
+
+        let pool = empty_pool().await;
+        migrate_with(&pool, &MIGRATIONS[..6]).await.unwrap();
+        let audit = SqliteAuditLogRepository::new(pool.clone());
+        let paid_at = chrono::Utc::now();
+        // Logged twice by a sync that overlapped a manual entry
+        for symbol in [Some("BTC-USD"), Some("BTC-USD"), None] {
+            audit
+                .create(CreateAuditLog {
+                    event_type: "funding_payment".to_string(),
+                    exchange: "dydx".to_string(),
+                    symbol: symbol.map(str::to_string),
+                    details: serde_json::json!({
+                        "amount": -1.5,
+                        "strategy": "SignalCombiner",
+                        "paid_at": paid_at,
+                    }),
+                })
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(migrate(&pool).await.unwrap()[0], 7);
+        let payments = SqliteFundingPaymentRepository::new(pool)
+            .get_since(paid_at)
+            .await
+            .unwrap();
+        assert_eq!(payments.len(), 1);
+        assert_eq!(payments[0].symbol, "BTC-USD");
+        assert_eq!(payments[0].strategy, "SignalCombiner");
+        assert_eq!(payments[0].amount, -1.5);
+        assert_eq!(payments[0].paid_at, paid_at);
+    }
+
+    #[tokio::test]
+    async fn test_pre_migration_database_gains_later_columns() {
+        let pool = empty_pool().await;
+        // Tables as the inline schema first created them
+        for statement in [
+            r#"
+            CREATE TABLE trades (
+                id TEXT PRIMARY KEY,
+                position_id TEXT,
+                symbol TEXT NOT NULL,
+                exchange TEXT NOT NULL,
+                side TEXT NOT NULL CHECK(side IN ('buy', 'sell')),
+                price REAL NOT NULL,
+                quantity REAL NOT NULL,
+                fee REAL NOT NULL DEFAULT 0.0,
+                exchange_order_id TEXT,
+                executed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                strategy TEXT NOT NULL,
+                signal_confidence REAL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            r#"
+            CREATE TABLE signals (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                symbol TEXT NOT NULL,
+                action TEXT NOT NULL,
+                confidence REAL NOT NULL,
+                price REAL,
+                weights TEXT NOT NULL,
+                details TEXT NOT NULL,
+                executed INTEGER NOT NULL DEFAULT 0,
+                skip_reason TEXT,
+                outcome TEXT,
+                generated_at DATETIME NOT NULL,
+                decided_at DATETIME
+            )
+            "#,
+            r#"
+            CREATE TABLE dydx_order_metadata (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                order_id TEXT NOT NULL UNIQUE,
+                dydx_order_id TEXT NOT NULL,
+                good_until_block INTEGER,
+                client_id INTEGER,
+                subaccount_number INTEGER,
+                symbol TEXT NOT NULL,
+                side TEXT NOT NULL,
+                quantity REAL NOT NULL,
+                price REAL NOT NULL,
+                order_type TEXT NOT NULL,
+                placed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                status TEXT NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            r#"
+            CREATE TABLE symbol_screening_results (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                symbol TEXT NOT NULL,
+                exchange TEXT NOT NULL,
+                volatility_score REAL NOT NULL,
+                volume_score REAL NOT NULL,
+                spread_score REAL NOT NULL,
+                momentum_score REAL NOT NULL,
+                overall_score REAL NOT NULL,
+                recommendation TEXT NOT NULL,
+                screened_at TIMESTAMP NOT NULL,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            "INSERT INTO trades (id, symbol, exchange, side, price, quantity, strategy) VALUES ('t1', 'BTC-USD', 'coinbase', 'buy', 50000.0, 0.1, 'Momentum')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        migrate(&pool).await.unwrap();
+
+        for (table, column) in [
+            ("trades", "exit_reason"),
+            ("trades", "signal_details"),
+            ("signals", "regime"),
+            ("dydx_order_metadata", "order_flags"),
+            ("dydx_order_metadata", "clob_pair_id"),
+            ("symbol_screening_results", "component_scores"),
+        ] {
+            let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?1)")
+                .bind(table)
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+            assert!(
+                columns.iter().any(|c| c == column),
+                "{}.{} missing",
+                table,
+                column
+            );
+        }
+        let kept: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM trades WHERE id = 't1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(kept, 1);
+    }
+
+    #[test]
+    fn test_added_column_is_parsed_from_alter_statements() {
+        assert_eq!(
+            added_column("ALTER TABLE trades ADD COLUMN exit_reason TEXT"),
+            Some(("trades", "exit_reason"))
+        );
+        assert_eq!(
+            added_column("CREATE INDEX IF NOT EXISTS idx ON trades(exit_reason)"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_indexed_columns_are_parsed_from_index_statements() {
+        assert_eq!(
+            indexed_columns("CREATE INDEX IF NOT EXISTS idx ON signals(symbol, generated_at)"),
+            Some(("signals", vec!["symbol", "generated_at"]))
+        );
+        assert_eq!(
+            indexed_columns("CREATE UNIQUE INDEX idx ON trades (exit_reason)"),
+            Some(("trades", vec!["exit_reason"]))
+        );
+        assert_eq!(
+            indexed_columns("ALTER TABLE trades ADD COLUMN exit_reason TEXT"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_changed_migration_fails_checksum() {
+        let pool = empty_pool().await;
+        migrate(&pool).await.unwrap();
+
+        sqlx::query("UPDATE schema_version SET checksum = 'edited' WHERE version = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let err = migrate(&pool).await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Checksum mismatch for migration 2"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_migration_is_rolled_back() {
+        const BROKEN: &[Migration] = &[Migration {
+            version: 1,
+            name: "broken",
+            adopts: false,
+            statements: &[
+                "CREATE TABLE half_done (id INTEGER PRIMARY KEY)",
+                "ALTER TABLE missing_table ADD COLUMN x TEXT",
+            ],
+        }];
+
+        let pool = empty_pool().await;
+        assert!(migrate_with(&pool, BROKEN).await.is_err());
+        assert_eq!(current_version(&pool).await.unwrap(), 0);
+
+        let tables: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'half_done'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(tables, 0);
+    }
+
+    #[tokio::test]
+    async fn test_newer_database_is_rejected() {
+        let pool = empty_pool().await;
+        migrate(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO schema_version (version, name, checksum) VALUES (99, 'future', '')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert!(migrate(&pool).await.is_err());
+    }
+}
@@ -1,7 +1,12 @@
 //! Database Models
 //!
-//! Persistent data structures for positions, trades, and audit logs.
+//! Persistent data structures for positions, trades, signals, allocator state,
+//! and audit logs.
 
+use crate::domain::entities::bracket_order::{
+    BracketLeg, BracketLegRole, BracketLegState, BracketLinking, BracketOrder,
+};
+use crate::domain::entities::order::OrderSide;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -24,6 +29,17 @@ pub struct PositionRecord {
     pub take_profit: Option<f64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub group_id: Option<String>, // Position group the leg belongs to
+}
+
+/// Position group record in database (legs link to it by `group_id`)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PositionGroupRecord {
+    pub id: String,
+    pub strategy: String,
+    pub hedge_ratio: f64,
+    pub entry_zscore: f64,
+    pub opened_at: DateTime<Utc>,
 }
 
 /// Trade record in database
@@ -37,10 +53,13 @@ pub struct TradeRecord {
     pub price: f64,
     pub quantity: f64,
     pub fee: f64,
+    pub fee_estimated: bool, // Fee taken from the fee schedule, not reported by the fill
     pub exchange_order_id: Option<String>,
     pub executed_at: DateTime<Utc>,
     pub strategy: String,
     pub signal_confidence: Option<f64>,
+    pub exit_reason: Option<String>, // Set on closing trades (e.g. "max_holding_time")
+    pub signal_details: Option<String>, // JSON votes and indicator readings of the opening signal
     pub created_at: DateTime<Utc>,
 }
 
@@ -55,6 +74,58 @@ pub struct AuditLogRecord {
     pub timestamp: DateTime<Utc>,
 }
 
+/// Generated signal record in database (one row per signal, outcome filled in once decided)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SignalRecord {
+    pub id: i64,
+    pub symbol: String,
+    pub action: String, // "buy", "sell" or "hold"
+    pub confidence: f64,
+    pub price: Option<f64>, // Last known price when the signal was generated
+    pub weights: String,    // JSON object of strategy name -> combiner weight
+    pub details: String,    // JSON votes and indicator readings
+    pub executed: bool,     // An order was placed for this signal
+    pub skip_reason: Option<String>, // Why no order was placed (see SignalSkipReason)
+    pub outcome: Option<String>, // Message returned by the execution attempt
+    pub regime: Option<String>, // Market regime when generated, e.g. "ranging"
+    pub generated_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>, // Null while the signal is still pending
+}
+
+/// Why a generated signal did not lead to an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalSkipReason {
+    /// The combined signal was HOLD
+    Hold,
+    /// Confidence below the threshold of the symbol's group
+    Confidence,
+    /// Trade frequency or position count limits reached
+    Limits,
+    /// Position sizing refused the trade (portfolio value, zero size)
+    Risk,
+    /// Rejected before evaluation (whitelist, automated trading disabled, no trader)
+    Rejected,
+    /// The order was dispatched but failed
+    Failed,
+    /// Replaced by a newer signal for the same symbol before it was evaluated
+    Superseded,
+}
+
+impl SignalSkipReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignalSkipReason::Hold => "hold",
+            SignalSkipReason::Confidence => "confidence",
+            SignalSkipReason::Limits => "limits",
+            SignalSkipReason::Risk => "risk",
+            SignalSkipReason::Rejected => "rejected",
+            SignalSkipReason::Failed => "failed",
+            SignalSkipReason::Superseded => "superseded",
+        }
+    }
+}
+
 /// dYdX order metadata record in database
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct DydxOrderMetadataRecord {
@@ -76,6 +147,131 @@ pub struct DydxOrderMetadataRecord {
     pub status: String,                      // 'active', 'cancelled', 'expired', 'filled'
 }
 
+/// Bracket order record in database (one row per bracket, one column group per leg)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BracketOrderRecord {
+    pub id: String,
+    pub position_id: Option<String>,
+    pub symbol: String,
+    pub exchange: String,
+    pub exit_side: String, // "buy" or "sell"
+    pub quantity: f64,
+    pub linking: String, // "native" or "emulated"
+    pub entry_order_id: Option<String>,
+    pub entry_price: Option<f64>,
+    pub entry_state: Option<String>, // null for OCO brackets without an entry leg
+    pub stop_loss_price: f64,
+    pub stop_loss_order_id: Option<String>,
+    pub stop_loss_state: String,
+    pub take_profit_price: f64,
+    pub take_profit_order_id: Option<String>,
+    pub take_profit_state: String,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&BracketOrder> for BracketOrderRecord {
+    fn from(bracket: &BracketOrder) -> Self {
+        Self {
+            id: bracket.id.clone(),
+            position_id: bracket.position_id.clone(),
+            symbol: bracket.symbol.clone(),
+            exchange: bracket.exchange.clone(),
+            exit_side: match bracket.exit_side {
+                OrderSide::Buy => "buy".to_string(),
+                OrderSide::Sell => "sell".to_string(),
+            },
+            quantity: bracket.quantity,
+            linking: bracket.linking.as_str().to_string(),
+            entry_order_id: bracket
+                .entry
+                .as_ref()
+                .and_then(|leg| leg.exchange_order_id.clone()),
+            entry_price: bracket.entry.as_ref().and_then(|leg| leg.price),
+            entry_state: bracket
+                .entry
+                .as_ref()
+                .map(|leg| leg.state.as_str().to_string()),
+            stop_loss_price: bracket.stop_loss.price.unwrap_or_default(),
+            stop_loss_order_id: bracket.stop_loss.exchange_order_id.clone(),
+            stop_loss_state: bracket.stop_loss.state.as_str().to_string(),
+            take_profit_price: bracket.take_profit.price.unwrap_or_default(),
+            take_profit_order_id: bracket.take_profit.exchange_order_id.clone(),
+            take_profit_state: bracket.take_profit.state.as_str().to_string(),
+            active: bracket.is_active(),
+            created_at: bracket.created_at,
+            updated_at: bracket.updated_at,
+        }
+    }
+}
+
+impl TryFrom<BracketOrderRecord> for BracketOrder {
+    type Error = String;
+
+    fn try_from(record: BracketOrderRecord) -> Result<Self, Self::Error> {
+        let exit_side = match record.exit_side.as_str() {
+            "buy" => OrderSide::Buy,
+            "sell" => OrderSide::Sell,
+            other => return Err(format!("Unknown bracket exit side: {}", other)),
+        };
+        let entry = match record.entry_state {
+            Some(state) => Some(BracketLeg {
+                role: BracketLegRole::Entry,
+                price: record.entry_price,
+                exchange_order_id: record.entry_order_id,
+                state: BracketLegState::parse(&state)?,
+            }),
+            None => None,
+        };
+
+        Ok(BracketOrder {
+            id: record.id,
+            position_id: record.position_id,
+            symbol: record.symbol,
+            exchange: record.exchange,
+            exit_side,
+            quantity: record.quantity,
+            linking: BracketLinking::parse(&record.linking)?,
+            entry,
+            stop_loss: BracketLeg {
+                role: BracketLegRole::StopLoss,
+                price: Some(record.stop_loss_price),
+                exchange_order_id: record.stop_loss_order_id,
+                state: BracketLegState::parse(&record.stop_loss_state)?,
+            },
+            take_profit: BracketLeg {
+                role: BracketLegRole::TakeProfit,
+                price: Some(record.take_profit_price),
+                exchange_order_id: record.take_profit_order_id,
+                state: BracketLegState::parse(&record.take_profit_state)?,
+            },
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+        })
+    }
+}
+
+/// Strategy weight allocator state in database (one row per allocator)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AllocatorStateRecord {
+    pub name: String,
+    pub policy: String,
+    pub state: String, // JSON string
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Funding payment entered in the PnL ledger, in database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FundingPaymentRecord {
+    pub id: i64,
+    pub exchange: String,
+    pub symbol: String,
+    pub strategy: String, // Strategy the payment was credited to
+    pub amount: f64,      // Negative when paid
+    pub paid_at: DateTime<Utc>,
+}
+
 /// Create position input
 #[derive(Debug, Clone)]
 pub struct CreatePosition {
@@ -87,6 +283,7 @@ pub struct CreatePosition {
     pub quantity: f64,
     pub stop_loss: Option<f64>,
     pub take_profit: Option<f64>,
+    pub group_id: Option<String>,
 }
 
 /// Update position input
@@ -107,9 +304,12 @@ pub struct CreateTrade {
     pub price: f64,
     pub quantity: f64,
     pub fee: f64,
+    pub fee_estimated: bool,
     pub exchange_order_id: Option<String>,
     pub strategy: String,
     pub signal_confidence: Option<f64>,
+    pub exit_reason: Option<String>,
+    pub signal_details: Option<String>,
 }
 
 /// Create audit log input
@@ -121,6 +321,16 @@ pub struct CreateAuditLog {
     pub details: serde_json::Value,
 }
 
+/// Create funding payment input
+#[derive(Debug, Clone)]
+pub struct CreateFundingPayment {
+    pub exchange: String,
+    pub symbol: String,
+    pub strategy: String,
+    pub amount: f64,
+    pub paid_at: DateTime<Utc>,
+}
+
 /// Create dYdX order metadata input
 #[derive(Debug, Clone)]
 pub struct CreateDydxOrderMetadata {
@@ -137,3 +347,24 @@ pub struct CreateDydxOrderMetadata {
     pub price: Option<String>,
     pub order_type: String,
 }
+
+/// Create signal input
+#[derive(Debug, Clone)]
+pub struct CreateSignal {
+    pub symbol: String,
+    pub action: String,
+    pub confidence: f64,
+    pub price: Option<f64>,
+    pub weights: serde_json::Value,
+    pub details: serde_json::Value,
+    pub regime: Option<String>,
+}
+
+/// Filters for querying signal history (all optional, newest first)
+#[derive(Debug, Clone, Default)]
+pub struct SignalQuery {
+    pub symbol: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: i64,
+}
@@ -0,0 +1,1890 @@
+//! PostgreSQL backend
+//!
+//! Selected when `DATABASE_URL` starts with `postgres://` or `postgresql://`,
+//! so several bot instances can report into one database. It implements
+//! every repository trait.
+//!
+//! Positions, position groups, trades, funding payments, the audit log, dYdX
+//! order metadata, reconciliation reports, signals, bracket orders and
+//! allocator state carry the `instance_id` of the bot that wrote them, and
+//! each repository only reads and changes its own instance's rows, so one
+//! instance never recovers, reconciles or replays the funding of another.
+//! Rows with their own ids are keyed by `(instance_id, id)`, since instances
+//! mint ids from the same clock. Screening results are shared.
+
+use super::migrations::{self, Migration};
+use super::models::*;
+use super::reconciliation_audit::{
+    db_error, note_line, superseded_reports, ReconciliationRepository, ReportRow, REPORT_COLUMNS,
+};
+use super::repository::{
+    AllocatorStateRepository, AuditLogRepository, BracketOrderRepository,
+    DydxOrderMetadataRepository, FundingPaymentRepository, PositionRepository, SignalRepository,
+    TradeRepository,
+};
+use super::screening_repository::{ScreeningRepository, ScreeningRow, RESULT_COLUMNS};
+use super::DatabaseError;
+use crate::domain::entities::bracket_order::BracketOrder;
+use crate::domain::entities::exchange::Exchange;
+use crate::domain::entities::symbol_screening::{RecommendationCategory, SymbolScreeningResult};
+use crate::domain::services::reconciliation::*;
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::postgres::{PgConnection, PgPool, PgPoolOptions};
+use sqlx::{Connection, Row};
+use tracing::{debug, error, info};
+
+/// PostgreSQL connection pool
+pub type PgDbPool = PgPool;
+
+/// Advisory lock held while migrating, so instances starting together
+/// apply each migration once
+const MIGRATION_LOCK: i64 = 0x6e7a_657a_61;
+
+/// Statements of the baseline migration, kept byte for byte as first
+/// shipped since the legacy checksum recorded for it covers their indentation
+const BASELINE: &[&str] = &[
+    r#"
+        CREATE TABLE IF NOT EXISTS positions (
+            id TEXT PRIMARY KEY,
+            symbol TEXT NOT NULL,
+            exchange TEXT NOT NULL,
+            side TEXT NOT NULL CHECK(side IN ('long', 'short')),
+            entry_price DOUBLE PRECISION NOT NULL,
+            quantity DOUBLE PRECISION NOT NULL,
+            current_price DOUBLE PRECISION NOT NULL,
+            unrealized_pnl DOUBLE PRECISION NOT NULL,
+            status TEXT NOT NULL CHECK(status IN ('open', 'closed')),
+            opened_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            closed_at TIMESTAMPTZ,
+            stop_loss DOUBLE PRECISION,
+            take_profit DOUBLE PRECISION,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )
+    "#,
+    // No foreign key on position_id: SQLite never enforced it either
+    r#"
+        CREATE TABLE IF NOT EXISTS trades (
+            id TEXT PRIMARY KEY,
+            position_id TEXT,
+            symbol TEXT NOT NULL,
+            exchange TEXT NOT NULL,
+            side TEXT NOT NULL CHECK(side IN ('buy', 'sell')),
+            price DOUBLE PRECISION NOT NULL,
+            quantity DOUBLE PRECISION NOT NULL,
+            fee DOUBLE PRECISION NOT NULL DEFAULT 0.0,
+            exchange_order_id TEXT,
+            executed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            strategy TEXT NOT NULL,
+            signal_confidence DOUBLE PRECISION,
+            exit_reason TEXT,
+            signal_details TEXT,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )
+    "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id BIGSERIAL PRIMARY KEY,
+            event_type TEXT NOT NULL,
+            exchange TEXT NOT NULL,
+            symbol TEXT,
+            details TEXT NOT NULL,
+            timestamp TIMESTAMPTZ NOT NULL DEFAULT now()
+        )
+    "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS reconciliation_audit (
+            id BIGSERIAL PRIMARY KEY,
+            reconciliation_id TEXT NOT NULL UNIQUE,
+            exchange_id TEXT NOT NULL,
+            reconciliation_timestamp TIMESTAMPTZ NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            status TEXT NOT NULL,
+            discrepancy_count BIGINT NOT NULL,
+            local_balances_json TEXT NOT NULL,
+            exchange_balances_json TEXT NOT NULL,
+            discrepancies_json TEXT NOT NULL,
+            recovery_attempted BOOLEAN DEFAULT FALSE,
+            recovery_status TEXT,
+            recovery_details_json TEXT,
+            operator_notes TEXT
+        )
+    "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS dydx_order_metadata (
+            id BIGSERIAL PRIMARY KEY,
+            order_id TEXT NOT NULL UNIQUE,
+            dydx_order_id TEXT NOT NULL,
+            good_until_block BIGINT,
+            client_id BIGINT,
+            subaccount_number INTEGER,
+            order_flags INTEGER DEFAULT 0,
+            clob_pair_id INTEGER DEFAULT 0,
+            symbol TEXT NOT NULL,
+            side TEXT NOT NULL,
+            quantity TEXT NOT NULL,
+            price TEXT,
+            order_type TEXT NOT NULL,
+            placed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            cancelled_at TIMESTAMPTZ,
+            tx_hash TEXT,
+            status TEXT NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )
+    "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS symbol_screening_results (
+            id BIGSERIAL PRIMARY KEY,
+            symbol TEXT NOT NULL,
+            exchange TEXT NOT NULL,
+            volatility_score DOUBLE PRECISION NOT NULL,
+            volume_score DOUBLE PRECISION NOT NULL,
+            spread_score DOUBLE PRECISION NOT NULL,
+            momentum_score DOUBLE PRECISION NOT NULL,
+            overall_score DOUBLE PRECISION NOT NULL,
+            recommendation TEXT NOT NULL,
+            component_scores TEXT,
+            screened_at TIMESTAMPTZ NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )
+    "#,
+    "CREATE INDEX IF NOT EXISTS idx_positions_status ON positions(status)",
+    "CREATE INDEX IF NOT EXISTS idx_positions_symbol ON positions(symbol)",
+    "CREATE INDEX IF NOT EXISTS idx_trades_position_id ON trades(position_id)",
+    "CREATE INDEX IF NOT EXISTS idx_trades_executed_at ON trades(executed_at)",
+    "CREATE INDEX IF NOT EXISTS idx_trades_exit_reason ON trades(exit_reason)",
+    "CREATE INDEX IF NOT EXISTS idx_audit_timestamp ON audit_log(timestamp)",
+    "CREATE INDEX IF NOT EXISTS idx_reconciliation_exchange_time ON reconciliation_audit(exchange_id, reconciliation_timestamp)",
+    "CREATE INDEX IF NOT EXISTS idx_reconciliation_status ON reconciliation_audit(status)",
+    "CREATE INDEX IF NOT EXISTS idx_dydx_order_status ON dydx_order_metadata(status)",
+    "CREATE INDEX IF NOT EXISTS idx_dydx_order_placed_at ON dydx_order_metadata(placed_at)",
+    "CREATE INDEX IF NOT EXISTS idx_symbol_exchange ON symbol_screening_results(symbol, exchange)",
+    "CREATE INDEX IF NOT EXISTS idx_screened_at ON symbol_screening_results(screened_at)",
+];
+
+/// Every PostgreSQL migration, oldest first
+///
+/// Mirrors the SQLite tables behind the repository traits at their latest
+/// version. Migrations are append-only, like the SQLite ones.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "baseline",
+        adopts: false,
+        statements: BASELINE,
+    },
+    Migration {
+        version: 2,
+        name: "position_groups",
+        adopts: false,
+        statements: &[
+            r#"
+            CREATE TABLE position_groups (
+                id TEXT PRIMARY KEY,
+                strategy TEXT NOT NULL,
+                hedge_ratio DOUBLE PRECISION NOT NULL,
+                entry_zscore DOUBLE PRECISION NOT NULL,
+                opened_at TIMESTAMPTZ NOT NULL
+            )
+            "#,
+            "ALTER TABLE positions ADD COLUMN group_id TEXT REFERENCES position_groups(id)",
+        ],
+    },
+    Migration {
+        version: 3,
+        name: "instance_scope",
+        adopts: false,
+        statements: &[
+            "ALTER TABLE positions ADD COLUMN instance_id TEXT NOT NULL DEFAULT 'default'",
+            "ALTER TABLE trades ADD COLUMN instance_id TEXT NOT NULL DEFAULT 'default'",
+            "ALTER TABLE dydx_order_metadata ADD COLUMN instance_id TEXT NOT NULL DEFAULT 'default'",
+            "ALTER TABLE reconciliation_audit ADD COLUMN instance_id TEXT NOT NULL DEFAULT 'default'",
+            "CREATE INDEX IF NOT EXISTS idx_positions_instance_status ON positions(instance_id, status)",
+            "CREATE INDEX IF NOT EXISTS idx_trades_instance_executed_at ON trades(instance_id, executed_at)",
+            "CREATE INDEX IF NOT EXISTS idx_dydx_order_instance_status ON dydx_order_metadata(instance_id, status)",
+            "CREATE INDEX IF NOT EXISTS idx_reconciliation_instance_exchange_time ON reconciliation_audit(instance_id, exchange_id, reconciliation_timestamp)",
+        ],
+    },
+    // Funding payments are replayed from the audit log into the PnL ledger
+    Migration {
+        version: 4,
+        name: "audit_instance_scope",
+        adopts: false,
+        statements: &[
+            "ALTER TABLE audit_log ADD COLUMN instance_id TEXT NOT NULL DEFAULT 'default'",
+            "CREATE INDEX IF NOT EXISTS idx_audit_instance_event_time ON audit_log(instance_id, event_type, timestamp)",
+        ],
+    },
+    // Report ids are only unique per instance: two instances reconciling the
+    // same exchange in the same millisecond get the same id
+    Migration {
+        version: 5,
+        name: "reconciliation_instance_key",
+        adopts: false,
+        statements: &[
+            "ALTER TABLE reconciliation_audit DROP CONSTRAINT IF EXISTS reconciliation_audit_reconciliation_id_key",
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_reconciliation_instance_id ON reconciliation_audit(instance_id, reconciliation_id)",
+        ],
+    },
+    Migration {
+        version: 6,
+        name: "bot_state",
+        adopts: false,
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS signals (
+                id BIGSERIAL PRIMARY KEY,
+                instance_id TEXT NOT NULL,
+                symbol TEXT NOT NULL,
+                action TEXT NOT NULL,
+                confidence DOUBLE PRECISION NOT NULL,
+                price DOUBLE PRECISION,
+                weights TEXT NOT NULL,
+                details TEXT NOT NULL,
+                executed BOOLEAN NOT NULL DEFAULT FALSE,
+                skip_reason TEXT,
+                outcome TEXT,
+                regime TEXT,
+                generated_at TIMESTAMPTZ NOT NULL,
+                decided_at TIMESTAMPTZ
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS bracket_orders (
+                instance_id TEXT NOT NULL,
+                id TEXT NOT NULL,
+                position_id TEXT,
+                symbol TEXT NOT NULL,
+                exchange TEXT NOT NULL,
+                exit_side TEXT NOT NULL,
+                quantity DOUBLE PRECISION NOT NULL,
+                linking TEXT NOT NULL,
+                entry_order_id TEXT,
+                entry_price DOUBLE PRECISION,
+                entry_state TEXT,
+                stop_loss_price DOUBLE PRECISION NOT NULL,
+                stop_loss_order_id TEXT,
+                stop_loss_state TEXT NOT NULL,
+                take_profit_price DOUBLE PRECISION NOT NULL,
+                take_profit_order_id TEXT,
+                take_profit_state TEXT NOT NULL,
+                active BOOLEAN NOT NULL DEFAULT TRUE,
+                created_at TIMESTAMPTZ NOT NULL,
+                updated_at TIMESTAMPTZ NOT NULL,
+                PRIMARY KEY (instance_id, id)
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS allocator_state (
+                instance_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                policy TEXT NOT NULL,
+                state TEXT NOT NULL,
+                updated_at TIMESTAMPTZ NOT NULL,
+                PRIMARY KEY (instance_id, name)
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_signals_instance_symbol_time ON signals(instance_id, symbol, generated_at)",
+            "CREATE INDEX IF NOT EXISTS idx_signals_instance_generated_at ON signals(instance_id, generated_at)",
+            "CREATE INDEX IF NOT EXISTS idx_bracket_orders_instance_active ON bracket_orders(instance_id, active)",
+        ],
+    },
+    Migration {
+        version: 7,
+        name: "trade_fee_estimated",
+        adopts: false,
+        // Trades recorded before fills reported their fees carry estimates
+        statements: &["ALTER TABLE trades ADD COLUMN fee_estimated BOOLEAN NOT NULL DEFAULT TRUE"],
+    },
+    // Position, trade, group and order ids are millisecond timestamps, so
+    // they are only unique per instance, like report ids since version 5
+    Migration {
+        version: 8,
+        name: "instance_keys",
+        adopts: false,
+        statements: &[
+            "ALTER TABLE position_groups ADD COLUMN instance_id TEXT NOT NULL DEFAULT 'default'",
+            "UPDATE position_groups g SET instance_id = p.instance_id FROM positions p WHERE p.group_id = g.id",
+            "ALTER TABLE positions DROP CONSTRAINT IF EXISTS positions_group_id_fkey",
+            "ALTER TABLE position_groups DROP CONSTRAINT position_groups_pkey",
+            "ALTER TABLE position_groups ADD PRIMARY KEY (instance_id, id)",
+            "ALTER TABLE positions DROP CONSTRAINT positions_pkey",
+            "ALTER TABLE positions ADD PRIMARY KEY (instance_id, id)",
+            "ALTER TABLE positions ADD CONSTRAINT positions_group_id_fkey FOREIGN KEY (instance_id, group_id) REFERENCES position_groups(instance_id, id)",
+            "ALTER TABLE trades DROP CONSTRAINT trades_pkey",
+            "ALTER TABLE trades ADD PRIMARY KEY (instance_id, id)",
+            "ALTER TABLE dydx_order_metadata DROP CONSTRAINT IF EXISTS dydx_order_metadata_order_id_key",
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_dydx_order_instance_order_id ON dydx_order_metadata(instance_id, order_id)",
+        ],
+    },
+    // Funding payments move out of the audit log into their own table, so
+    // the PnL ledger replays them by time instead of scanning every event
+    Migration {
+        version: 9,
+        name: "funding_payments",
+        adopts: false,
+        statements: &[
+            r#"
+            CREATE TABLE funding_payments (
+                id BIGSERIAL PRIMARY KEY,
+                instance_id TEXT NOT NULL,
+                exchange TEXT NOT NULL,
+                symbol TEXT NOT NULL,
+                strategy TEXT NOT NULL,
+                amount DOUBLE PRECISION NOT NULL,
+                paid_at TIMESTAMPTZ NOT NULL,
+                UNIQUE (instance_id, exchange, symbol, paid_at)
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_funding_payments_instance_paid_at ON funding_payments(instance_id, paid_at)",
+            r#"
+            INSERT INTO funding_payments (instance_id, exchange, symbol, strategy, amount, paid_at)
+            SELECT instance_id, exchange, symbol, (details::jsonb)->>'strategy',
+                ((details::jsonb)->>'amount')::DOUBLE PRECISION,
+                ((details::jsonb)->>'paid_at')::TIMESTAMPTZ
+            FROM audit_log
+            WHERE event_type = 'funding_payment' AND symbol IS NOT NULL
+            ORDER BY id
+            ON CONFLICT DO NOTHING
+            "#,
+        ],
+    },
+];
+
+/// Connect to PostgreSQL and bring the schema up to the latest version
+pub async fn init_database(database_url: &str) -> Result<PgDbPool, DatabaseError> {
+    // The URL carries credentials, so it is not logged
+    info!("Initializing PostgreSQL database");
+
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(database_url)
+        .await?;
+    migrate(&pool).await?;
+
+    info!("✓ Database initialized successfully");
+    Ok(pool)
+}
+
+/// Verify applied migrations and apply pending ones
+///
+/// Returns the versions applied by this call.
+pub async fn migrate(pool: &PgDbPool) -> Result<Vec<i64>, DatabaseError> {
+    let mut conn = pool.acquire().await?;
+    sqlx::query("SELECT pg_advisory_lock($1)")
+        .bind(MIGRATION_LOCK)
+        .execute(&mut *conn)
+        .await?;
+    let result = migrate_locked(&mut conn).await;
+    sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(MIGRATION_LOCK)
+        .execute(&mut *conn)
+        .await?;
+    result
+}
+
+async fn migrate_locked(conn: &mut PgConnection) -> Result<Vec<i64>, DatabaseError> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version BIGINT PRIMARY KEY,
+            name TEXT NOT NULL,
+            checksum TEXT NOT NULL,
+            applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| {
+        DatabaseError::MigrationError(format!("Failed to create schema_version table: {}", e))
+    })?;
+
+    let recorded: Vec<(i64, String)> =
+        sqlx::query_as("SELECT version, checksum FROM schema_version ORDER BY version")
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| {
+                DatabaseError::MigrationError(format!("Failed to read schema version: {}", e))
+            })?;
+
+    let mut applied = Vec::new();
+    for migration in migrations::pending(MIGRATIONS, &recorded)? {
+        let failed = |e: sqlx::Error| {
+            DatabaseError::MigrationError(format!(
+                "Migration {} ({}) failed: {}",
+                migration.version, migration.name, e
+            ))
+        };
+        let mut tx = conn.begin().await.map_err(failed)?;
+        for statement in migration.statements {
+            sqlx::query(statement)
+                .execute(&mut *tx)
+                .await
+                .map_err(failed)?;
+        }
+        sqlx::query("INSERT INTO schema_version (version, name, checksum) VALUES ($1, $2, $3)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(migration.checksum())
+            .execute(&mut *tx)
+            .await
+            .map_err(failed)?;
+        tx.commit().await.map_err(failed)?;
+
+        info!(
+            "Applied migration {} ({})",
+            migration.version, migration.name
+        );
+        applied.push(migration.version);
+    }
+    migrations::log_outcome(&recorded, &applied);
+    Ok(applied)
+}
+
+/// PostgreSQL implementation of position repository
+pub struct PostgresPositionRepository {
+    pool: PgDbPool,
+    instance_id: String,
+}
+
+impl PostgresPositionRepository {
+    pub fn new(pool: PgDbPool, instance_id: impl Into<String>) -> Self {
+        Self {
+            pool,
+            instance_id: instance_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl PositionRepository for PostgresPositionRepository {
+    async fn create(&self, position: CreatePosition) -> Result<PositionRecord, DatabaseError> {
+        let now = Utc::now();
+        let record = sqlx::query_as::<_, PositionRecord>(
+            r#"
+            INSERT INTO positions (
+                id, symbol, exchange, side, entry_price, quantity,
+                current_price, unrealized_pnl, status, opened_at,
+                stop_loss, take_profit, created_at, updated_at, group_id, instance_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $5, 0.0, 'open', $7, $8, $9, $7, $7, $10, $11)
+            RETURNING *
+            "#,
+        )
+        .bind(&position.id)
+        .bind(&position.symbol)
+        .bind(&position.exchange)
+        .bind(&position.side)
+        .bind(position.entry_price)
+        .bind(position.quantity)
+        .bind(now)
+        .bind(position.stop_loss)
+        .bind(position.take_profit)
+        .bind(&position.group_id)
+        .bind(&self.instance_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to create position: {}", e);
+            DatabaseError::QueryError(format!("Failed to create position: {}", e))
+        })?;
+
+        debug!("Created position: {} for {}", record.id, record.symbol);
+        Ok(record)
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<PositionRecord>, DatabaseError> {
+        let record = sqlx::query_as::<_, PositionRecord>(
+            "SELECT * FROM positions WHERE id = $1 AND instance_id = $2",
+        )
+        .bind(id)
+        .bind(&self.instance_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get position {}: {}", id, e);
+            DatabaseError::QueryError(format!("Failed to get position: {}", e))
+        })?;
+
+        Ok(record)
+    }
+
+    async fn update(&self, id: &str, update: UpdatePosition) -> Result<(), DatabaseError> {
+        let now = Utc::now();
+        let rows_affected = sqlx::query(
+            r#"
+            UPDATE positions
+            SET current_price = $1, unrealized_pnl = $2, updated_at = $3
+            WHERE id = $4 AND instance_id = $5
+            "#,
+        )
+        .bind(update.current_price)
+        .bind(update.unrealized_pnl)
+        .bind(now)
+        .bind(id)
+        .bind(&self.instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to update position {}: {}", id, e);
+            DatabaseError::QueryError(format!("Failed to update position: {}", e))
+        })?
+        .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(DatabaseError::QueryError(format!(
+                "Position not found: {}",
+                id
+            )));
+        }
+
+        debug!("Updated position: {}", id);
+        Ok(())
+    }
+
+    async fn close(
+        &self,
+        id: &str,
+        final_price: f64,
+        realized_pnl: f64,
+    ) -> Result<(), DatabaseError> {
+        let now = Utc::now();
+        let rows_affected = sqlx::query(
+            r#"
+            UPDATE positions
+            SET status = 'closed', current_price = $1, unrealized_pnl = $2,
+                closed_at = $3, updated_at = $3
+            WHERE id = $4 AND status = 'open' AND instance_id = $5
+            "#,
+        )
+        .bind(final_price)
+        .bind(realized_pnl)
+        .bind(now)
+        .bind(id)
+        .bind(&self.instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to close position {}: {}", id, e);
+            DatabaseError::QueryError(format!("Failed to close position: {}", e))
+        })?
+        .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(DatabaseError::QueryError(format!(
+                "Position not found or already closed: {}",
+                id
+            )));
+        }
+
+        debug!("Closed position: {}", id);
+        Ok(())
+    }
+
+    async fn get_open_positions(&self) -> Result<Vec<PositionRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, PositionRecord>(
+            "SELECT * FROM positions WHERE status = 'open' AND instance_id = $1 ORDER BY opened_at DESC",
+        )
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get open positions: {}", e);
+            DatabaseError::QueryError(format!("Failed to get open positions: {}", e))
+        })?;
+
+        Ok(records)
+    }
+
+    async fn get_by_symbol(&self, symbol: &str) -> Result<Vec<PositionRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, PositionRecord>(
+            "SELECT * FROM positions WHERE symbol = $1 AND instance_id = $2 ORDER BY opened_at DESC",
+        )
+        .bind(symbol)
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get positions for {}: {}", symbol, e);
+            DatabaseError::QueryError(format!("Failed to get positions: {}", e))
+        })?;
+
+        Ok(records)
+    }
+
+    async fn count_open_by_symbol(&self, symbol: &str) -> Result<i64, DatabaseError> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) as count FROM positions WHERE symbol = $1 AND status = 'open' AND instance_id = $2",
+        )
+        .bind(symbol)
+        .bind(&self.instance_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to count open positions for {}: {}", symbol, e);
+            DatabaseError::QueryError(format!("Failed to count positions: {}", e))
+        })?;
+
+        let count: i64 = row.get("count");
+        Ok(count)
+    }
+    async fn create_group(&self, group: PositionGroupRecord) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO position_groups (
+                id, strategy, hedge_ratio, entry_zscore, opened_at, instance_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(&group.id)
+        .bind(&group.strategy)
+        .bind(group.hedge_ratio)
+        .bind(group.entry_zscore)
+        .bind(group.opened_at)
+        .bind(&self.instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to create position group {}: {}", group.id, e);
+            DatabaseError::QueryError(format!("Failed to create position group: {}", e))
+        })?;
+
+        debug!(
+            "Created position group: {} for {}",
+            group.id, group.strategy
+        );
+        Ok(())
+    }
+
+    async fn get_group(&self, id: &str) -> Result<Option<PositionGroupRecord>, DatabaseError> {
+        let record = sqlx::query_as::<_, PositionGroupRecord>(
+            "SELECT * FROM position_groups WHERE id = $1 AND instance_id = $2",
+        )
+        .bind(id)
+        .bind(&self.instance_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get position group {}: {}", id, e);
+            DatabaseError::QueryError(format!("Failed to get position group: {}", e))
+        })?;
+
+        Ok(record)
+    }
+}
+
+/// PostgreSQL implementation of trade repository
+pub struct PostgresTradeRepository {
+    pool: PgDbPool,
+    instance_id: String,
+}
+
+impl PostgresTradeRepository {
+    pub fn new(pool: PgDbPool, instance_id: impl Into<String>) -> Self {
+        Self {
+            pool,
+            instance_id: instance_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl TradeRepository for PostgresTradeRepository {
+    async fn create(&self, trade: CreateTrade) -> Result<TradeRecord, DatabaseError> {
+        let now = Utc::now();
+        let record = sqlx::query_as::<_, TradeRecord>(
+            r#"
+            INSERT INTO trades (
+                id, position_id, symbol, exchange, side, price, quantity,
+                fee, exchange_order_id, executed_at, strategy, signal_confidence, exit_reason,
+                signal_details, created_at, instance_id, fee_estimated
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $10, $15, $16)
+            RETURNING *
+            "#,
+        )
+        .bind(&trade.id)
+        .bind(&trade.position_id)
+        .bind(&trade.symbol)
+        .bind(&trade.exchange)
+        .bind(&trade.side)
+        .bind(trade.price)
+        .bind(trade.quantity)
+        .bind(trade.fee)
+        .bind(&trade.exchange_order_id)
+        .bind(now)
+        .bind(&trade.strategy)
+        .bind(trade.signal_confidence)
+        .bind(&trade.exit_reason)
+        .bind(&trade.signal_details)
+        .bind(&self.instance_id)
+        .bind(trade.fee_estimated)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to create trade: {}", e);
+            DatabaseError::QueryError(format!("Failed to create trade: {}", e))
+        })?;
+
+        debug!("Created trade: {} for {}", record.id, record.symbol);
+        Ok(record)
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<TradeRecord>, DatabaseError> {
+        let record = sqlx::query_as::<_, TradeRecord>(
+            "SELECT * FROM trades WHERE id = $1 AND instance_id = $2",
+        )
+        .bind(id)
+        .bind(&self.instance_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get trade {}: {}", id, e);
+            DatabaseError::QueryError(format!("Failed to get trade: {}", e))
+        })?;
+
+        Ok(record)
+    }
+
+    async fn get_by_position(&self, position_id: &str) -> Result<Vec<TradeRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, TradeRecord>(
+            "SELECT * FROM trades WHERE position_id = $1 AND instance_id = $2 ORDER BY executed_at DESC",
+        )
+        .bind(position_id)
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get trades for position {}: {}", position_id, e);
+            DatabaseError::QueryError(format!("Failed to get trades: {}", e))
+        })?;
+
+        Ok(records)
+    }
+
+    async fn get_by_exchange_order(
+        &self,
+        exchange_order_id: &str,
+    ) -> Result<Vec<TradeRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, TradeRecord>(
+            "SELECT * FROM trades WHERE exchange_order_id = $1 AND instance_id = $2 ORDER BY executed_at DESC",
+        )
+        .bind(exchange_order_id)
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to get trades for order {}: {}",
+                exchange_order_id, e
+            );
+            DatabaseError::QueryError(format!("Failed to get trades: {}", e))
+        })?;
+
+        Ok(records)
+    }
+
+    async fn get_recent(&self, limit: i64) -> Result<Vec<TradeRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, TradeRecord>(
+            "SELECT * FROM trades WHERE instance_id = $2 ORDER BY executed_at DESC LIMIT $1",
+        )
+        .bind(limit)
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get recent trades: {}", e);
+            DatabaseError::QueryError(format!("Failed to get recent trades: {}", e))
+        })?;
+
+        Ok(records)
+    }
+
+    async fn get_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<TradeRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, TradeRecord>(
+            "SELECT * FROM trades WHERE executed_at >= $1 AND instance_id = $2 ORDER BY executed_at ASC",
+        )
+        .bind(since)
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get trades since {}: {}", since, e);
+            DatabaseError::QueryError(format!("Failed to get trades: {}", e))
+        })?;
+
+        Ok(records)
+    }
+
+    async fn get_by_exit_reason(
+        &self,
+        exit_reason: &str,
+        limit: i64,
+    ) -> Result<Vec<TradeRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, TradeRecord>(
+            "SELECT * FROM trades WHERE exit_reason = $1 AND instance_id = $3 ORDER BY executed_at DESC LIMIT $2",
+        )
+        .bind(exit_reason)
+        .bind(limit)
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to get trades for exit reason {}: {}",
+                exit_reason, e
+            );
+            DatabaseError::QueryError(format!("Failed to get trades: {}", e))
+        })?;
+
+        Ok(records)
+    }
+
+    async fn get_by_symbol_range(
+        &self,
+        symbol: &str,
+        start: chrono::DateTime<Utc>,
+        end: chrono::DateTime<Utc>,
+    ) -> Result<Vec<TradeRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, TradeRecord>(
+            r#"
+            SELECT * FROM trades
+            WHERE symbol = $1 AND executed_at >= $2 AND executed_at <= $3 AND instance_id = $4
+            ORDER BY executed_at DESC
+            "#,
+        )
+        .bind(symbol)
+        .bind(start)
+        .bind(end)
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get trades for {} in date range: {}", symbol, e);
+            DatabaseError::QueryError(format!("Failed to get trades: {}", e))
+        })?;
+
+        Ok(records)
+    }
+}
+
+/// PostgreSQL implementation of funding payment repository
+pub struct PostgresFundingPaymentRepository {
+    pool: PgDbPool,
+    instance_id: String,
+}
+
+impl PostgresFundingPaymentRepository {
+    pub fn new(pool: PgDbPool, instance_id: impl Into<String>) -> Self {
+        Self {
+            pool,
+            instance_id: instance_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl FundingPaymentRepository for PostgresFundingPaymentRepository {
+    async fn create(&self, payment: CreateFundingPayment) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO funding_payments (exchange, symbol, strategy, amount, paid_at, instance_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (instance_id, exchange, symbol, paid_at) DO NOTHING
+            "#,
+        )
+        .bind(&payment.exchange)
+        .bind(&payment.symbol)
+        .bind(&payment.strategy)
+        .bind(payment.amount)
+        .bind(payment.paid_at)
+        .bind(&self.instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to record funding payment on {}: {}",
+                payment.symbol, e
+            );
+            DatabaseError::QueryError(format!("Failed to record funding payment: {}", e))
+        })?;
+
+        debug!(
+            "Recorded funding payment: {} {} at {}",
+            payment.exchange, payment.symbol, payment.paid_at
+        );
+        Ok(())
+    }
+
+    async fn get_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<FundingPaymentRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, FundingPaymentRecord>(
+            "SELECT * FROM funding_payments WHERE paid_at >= $1 AND instance_id = $2 ORDER BY paid_at ASC, id ASC",
+        )
+        .bind(since)
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get funding payments since {}: {}", since, e);
+            DatabaseError::QueryError(format!("Failed to get funding payments: {}", e))
+        })?;
+
+        Ok(records)
+    }
+}
+
+/// PostgreSQL implementation of audit log repository
+pub struct PostgresAuditLogRepository {
+    pool: PgDbPool,
+    instance_id: String,
+}
+
+impl PostgresAuditLogRepository {
+    pub fn new(pool: PgDbPool, instance_id: impl Into<String>) -> Self {
+        Self {
+            pool,
+            instance_id: instance_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditLogRepository for PostgresAuditLogRepository {
+    async fn create(&self, log: CreateAuditLog) -> Result<AuditLogRecord, DatabaseError> {
+        let now = Utc::now();
+        let details_json = serde_json::to_string(&log.details).map_err(|e| {
+            DatabaseError::QueryError(format!("Failed to serialize details: {}", e))
+        })?;
+
+        let record = sqlx::query_as::<_, AuditLogRecord>(
+            r#"
+            INSERT INTO audit_log (event_type, exchange, symbol, details, timestamp, instance_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(&log.event_type)
+        .bind(&log.exchange)
+        .bind(&log.symbol)
+        .bind(&details_json)
+        .bind(now)
+        .bind(&self.instance_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to create audit log: {}", e);
+            DatabaseError::QueryError(format!("Failed to create audit log: {}", e))
+        })?;
+
+        debug!(
+            "Created audit log: {} for {}",
+            record.event_type, record.exchange
+        );
+        Ok(record)
+    }
+
+    async fn get_recent(&self, limit: i64) -> Result<Vec<AuditLogRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, AuditLogRecord>(
+            "SELECT * FROM audit_log WHERE instance_id = $2 ORDER BY timestamp DESC LIMIT $1",
+        )
+        .bind(limit)
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get recent audit logs: {}", e);
+            DatabaseError::QueryError(format!("Failed to get audit logs: {}", e))
+        })?;
+
+        Ok(records)
+    }
+
+    async fn get_by_event_type(
+        &self,
+        event_type: &str,
+        limit: i64,
+    ) -> Result<Vec<AuditLogRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, AuditLogRecord>(
+            "SELECT * FROM audit_log WHERE event_type = $1 AND instance_id = $3 ORDER BY timestamp DESC LIMIT $2",
+        )
+        .bind(event_type)
+        .bind(limit)
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to get audit logs for event type {}: {}",
+                event_type, e
+            );
+            DatabaseError::QueryError(format!("Failed to get audit logs: {}", e))
+        })?;
+
+        Ok(records)
+    }
+}
+
+/// PostgreSQL implementation of dYdX order metadata repository
+pub struct PostgresDydxOrderMetadataRepository {
+    pool: PgDbPool,
+    instance_id: String,
+}
+
+impl PostgresDydxOrderMetadataRepository {
+    pub fn new(pool: PgDbPool, instance_id: impl Into<String>) -> Self {
+        Self {
+            pool,
+            instance_id: instance_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl DydxOrderMetadataRepository for PostgresDydxOrderMetadataRepository {
+    async fn create(
+        &self,
+        metadata: CreateDydxOrderMetadata,
+    ) -> Result<DydxOrderMetadataRecord, DatabaseError> {
+        let now = Utc::now();
+        let record = sqlx::query_as::<_, DydxOrderMetadataRecord>(
+            r#"
+            INSERT INTO dydx_order_metadata (
+                order_id, dydx_order_id, good_until_block, client_id, subaccount_number,
+                order_flags, clob_pair_id, symbol, side, quantity, price, order_type, placed_at, status,
+                instance_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, 'active', $14)
+            RETURNING *
+            "#,
+        )
+        .bind(&metadata.order_id)
+        .bind(&metadata.dydx_order_id)
+        .bind(metadata.good_until_block)
+        .bind(metadata.client_id)
+        .bind(metadata.subaccount_number)
+        .bind(metadata.order_flags)
+        .bind(metadata.clob_pair_id)
+        .bind(&metadata.symbol)
+        .bind(&metadata.side)
+        .bind(&metadata.quantity)
+        .bind(&metadata.price)
+        .bind(&metadata.order_type)
+        .bind(now)
+        .bind(&self.instance_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to create dYdX order metadata: {}", e);
+            DatabaseError::QueryError(format!("Failed to create dYdX order metadata: {}", e))
+        })?;
+
+        debug!(
+            "Created dYdX order metadata: {} for {}",
+            record.order_id, record.symbol
+        );
+        Ok(record)
+    }
+
+    async fn get_by_order_id(
+        &self,
+        order_id: &str,
+    ) -> Result<Option<DydxOrderMetadataRecord>, DatabaseError> {
+        let record = sqlx::query_as::<_, DydxOrderMetadataRecord>(
+            "SELECT * FROM dydx_order_metadata WHERE order_id = $1 AND instance_id = $2",
+        )
+        .bind(order_id)
+        .bind(&self.instance_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get dYdX order metadata {}: {}", order_id, e);
+            DatabaseError::QueryError(format!("Failed to get dYdX order metadata: {}", e))
+        })?;
+
+        Ok(record)
+    }
+
+    async fn update_cancelled(&self, order_id: &str, tx_hash: &str) -> Result<(), DatabaseError> {
+        let now = Utc::now();
+        let rows_affected = sqlx::query(
+            r#"
+            UPDATE dydx_order_metadata
+            SET status = 'cancelled', cancelled_at = $1, tx_hash = $2
+            WHERE order_id = $3 AND status = 'active' AND instance_id = $4
+            "#,
+        )
+        .bind(now)
+        .bind(tx_hash)
+        .bind(order_id)
+        .bind(&self.instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to update cancelled status for order {}: {}",
+                order_id, e
+            );
+            DatabaseError::QueryError(format!("Failed to update cancelled status: {}", e))
+        })?
+        .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(DatabaseError::QueryError(format!(
+                "Order not found or already cancelled: {}",
+                order_id
+            )));
+        }
+
+        debug!("Updated order {} to cancelled", order_id);
+        Ok(())
+    }
+
+    async fn update_expired(&self, order_id: &str) -> Result<(), DatabaseError> {
+        let rows_affected = sqlx::query(
+            r#"
+            UPDATE dydx_order_metadata
+            SET status = 'expired'
+            WHERE order_id = $1 AND status = 'active' AND instance_id = $2
+            "#,
+        )
+        .bind(order_id)
+        .bind(&self.instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to update expired status for order {}: {}",
+                order_id, e
+            );
+            DatabaseError::QueryError(format!("Failed to update expired status: {}", e))
+        })?
+        .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(DatabaseError::QueryError(format!(
+                "Order not found or not active: {}",
+                order_id
+            )));
+        }
+
+        debug!("Updated order {} to expired", order_id);
+        Ok(())
+    }
+
+    async fn update_filled(&self, order_id: &str) -> Result<(), DatabaseError> {
+        let rows_affected = sqlx::query(
+            r#"
+            UPDATE dydx_order_metadata
+            SET status = 'filled'
+            WHERE order_id = $1 AND status = 'active' AND instance_id = $2
+            "#,
+        )
+        .bind(order_id)
+        .bind(&self.instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to update filled status for order {}: {}",
+                order_id, e
+            );
+            DatabaseError::QueryError(format!("Failed to update filled status: {}", e))
+        })?
+        .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(DatabaseError::QueryError(format!(
+                "Order not found or not active: {}",
+                order_id
+            )));
+        }
+
+        debug!("Updated order {} to filled", order_id);
+        Ok(())
+    }
+
+    async fn get_active_orders(&self) -> Result<Vec<DydxOrderMetadataRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, DydxOrderMetadataRecord>(
+            "SELECT * FROM dydx_order_metadata WHERE status = 'active' AND instance_id = $1 ORDER BY placed_at ASC",
+        )
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get active dYdX orders: {}", e);
+            DatabaseError::QueryError(format!("Failed to get active orders: {}", e))
+        })?;
+
+        Ok(records)
+    }
+}
+
+/// PostgreSQL implementation of signal repository
+pub struct PostgresSignalRepository {
+    pool: PgDbPool,
+    instance_id: String,
+}
+
+impl PostgresSignalRepository {
+    pub fn new(pool: PgDbPool, instance_id: impl Into<String>) -> Self {
+        Self {
+            pool,
+            instance_id: instance_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl SignalRepository for PostgresSignalRepository {
+    async fn create(&self, signal: CreateSignal) -> Result<SignalRecord, DatabaseError> {
+        let now = Utc::now();
+        let record = sqlx::query_as::<_, SignalRecord>(
+            r#"
+            INSERT INTO signals (
+                symbol, action, confidence, price, weights, details, executed, regime,
+                generated_at, instance_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, FALSE, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+        .bind(&signal.symbol)
+        .bind(&signal.action)
+        .bind(signal.confidence)
+        .bind(signal.price)
+        .bind(signal.weights.to_string())
+        .bind(signal.details.to_string())
+        .bind(&signal.regime)
+        .bind(now)
+        .bind(&self.instance_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to record signal for {}: {}", signal.symbol, e);
+            DatabaseError::QueryError(format!("Failed to record signal: {}", e))
+        })?;
+
+        debug!("Recorded signal {} for {}", record.id, record.symbol);
+        Ok(record)
+    }
+
+    async fn record_outcome(
+        &self,
+        id: i64,
+        skip_reason: Option<SignalSkipReason>,
+        outcome: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE signals
+            SET executed = $2, skip_reason = $3, outcome = $4, decided_at = $5
+            WHERE id = $1 AND instance_id = $6
+            "#,
+        )
+        .bind(id)
+        .bind(skip_reason.is_none())
+        .bind(skip_reason.map(|reason| reason.as_str()))
+        .bind(outcome)
+        .bind(Utc::now())
+        .bind(&self.instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to record outcome of signal {}: {}", id, e);
+            DatabaseError::QueryError(format!("Failed to record signal outcome: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    async fn query(&self, query: &SignalQuery) -> Result<Vec<SignalRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, SignalRecord>(
+            r#"
+            SELECT * FROM signals
+            WHERE instance_id = $5
+              AND ($1::TEXT IS NULL OR symbol = $1)
+              AND ($2::TIMESTAMPTZ IS NULL OR generated_at >= $2)
+              AND ($3::TIMESTAMPTZ IS NULL OR generated_at <= $3)
+            ORDER BY generated_at DESC, id DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(&query.symbol)
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.limit)
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to query signal history: {}", e);
+            DatabaseError::QueryError(format!("Failed to query signals: {}", e))
+        })?;
+
+        Ok(records)
+    }
+}
+
+/// PostgreSQL implementation of bracket order repository
+pub struct PostgresBracketOrderRepository {
+    pool: PgDbPool,
+    instance_id: String,
+}
+
+impl PostgresBracketOrderRepository {
+    pub fn new(pool: PgDbPool, instance_id: impl Into<String>) -> Self {
+        Self {
+            pool,
+            instance_id: instance_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl BracketOrderRepository for PostgresBracketOrderRepository {
+    async fn save(&self, bracket: &BracketOrder) -> Result<(), DatabaseError> {
+        let record = BracketOrderRecord::from(bracket);
+        sqlx::query(
+            r#"
+            INSERT INTO bracket_orders (
+                id, position_id, symbol, exchange, exit_side, quantity, linking,
+                entry_order_id, entry_price, entry_state,
+                stop_loss_price, stop_loss_order_id, stop_loss_state,
+                take_profit_price, take_profit_order_id, take_profit_state,
+                active, created_at, updated_at, instance_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
+            ON CONFLICT(instance_id, id) DO UPDATE SET
+                position_id = excluded.position_id,
+                exchange = excluded.exchange,
+                linking = excluded.linking,
+                entry_order_id = excluded.entry_order_id,
+                entry_price = excluded.entry_price,
+                entry_state = excluded.entry_state,
+                stop_loss_order_id = excluded.stop_loss_order_id,
+                stop_loss_state = excluded.stop_loss_state,
+                take_profit_order_id = excluded.take_profit_order_id,
+                take_profit_state = excluded.take_profit_state,
+                active = excluded.active,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&record.id)
+        .bind(&record.position_id)
+        .bind(&record.symbol)
+        .bind(&record.exchange)
+        .bind(&record.exit_side)
+        .bind(record.quantity)
+        .bind(&record.linking)
+        .bind(&record.entry_order_id)
+        .bind(record.entry_price)
+        .bind(&record.entry_state)
+        .bind(record.stop_loss_price)
+        .bind(&record.stop_loss_order_id)
+        .bind(&record.stop_loss_state)
+        .bind(record.take_profit_price)
+        .bind(&record.take_profit_order_id)
+        .bind(&record.take_profit_state)
+        .bind(record.active)
+        .bind(record.created_at)
+        .bind(record.updated_at)
+        .bind(&self.instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to save bracket order {}: {}", record.id, e);
+            DatabaseError::QueryError(format!("Failed to save bracket order: {}", e))
+        })?;
+
+        debug!("Saved bracket order: {} for {}", record.id, record.symbol);
+        Ok(())
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<BracketOrder>, DatabaseError> {
+        let record = sqlx::query_as::<_, BracketOrderRecord>(
+            "SELECT * FROM bracket_orders WHERE id = $1 AND instance_id = $2",
+        )
+        .bind(id)
+        .bind(&self.instance_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get bracket order {}: {}", id, e);
+            DatabaseError::QueryError(format!("Failed to get bracket order: {}", e))
+        })?;
+
+        record
+            .map(BracketOrder::try_from)
+            .transpose()
+            .map_err(DatabaseError::QueryError)
+    }
+
+    async fn get_active(&self) -> Result<Vec<BracketOrder>, DatabaseError> {
+        let records = sqlx::query_as::<_, BracketOrderRecord>(
+            "SELECT * FROM bracket_orders WHERE active AND instance_id = $1 ORDER BY created_at ASC",
+        )
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get active bracket orders: {}", e);
+            DatabaseError::QueryError(format!("Failed to get active bracket orders: {}", e))
+        })?;
+
+        records
+            .into_iter()
+            .map(BracketOrder::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(DatabaseError::QueryError)
+    }
+}
+
+/// PostgreSQL implementation of allocator state repository
+pub struct PostgresAllocatorStateRepository {
+    pool: PgDbPool,
+    instance_id: String,
+}
+
+impl PostgresAllocatorStateRepository {
+    pub fn new(pool: PgDbPool, instance_id: impl Into<String>) -> Self {
+        Self {
+            pool,
+            instance_id: instance_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl AllocatorStateRepository for PostgresAllocatorStateRepository {
+    async fn save(
+        &self,
+        name: &str,
+        policy: &str,
+        state: &serde_json::Value,
+    ) -> Result<(), DatabaseError> {
+        let state_json = serde_json::to_string(state).map_err(|e| {
+            DatabaseError::QueryError(format!("Failed to serialize allocator state: {}", e))
+        })?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO allocator_state (name, policy, state, updated_at, instance_id)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT(instance_id, name) DO UPDATE SET
+                policy = excluded.policy,
+                state = excluded.state,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(name)
+        .bind(policy)
+        .bind(&state_json)
+        .bind(Utc::now())
+        .bind(&self.instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to save allocator state {}: {}", name, e);
+            DatabaseError::QueryError(format!("Failed to save allocator state: {}", e))
+        })?;
+
+        debug!("Saved allocator state: {} ({})", name, policy);
+        Ok(())
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<AllocatorStateRecord>, DatabaseError> {
+        sqlx::query_as::<_, AllocatorStateRecord>(
+            "SELECT * FROM allocator_state WHERE name = $1 AND instance_id = $2",
+        )
+        .bind(name)
+        .bind(&self.instance_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get allocator state {}: {}", name, e);
+            DatabaseError::QueryError(format!("Failed to get allocator state: {}", e))
+        })
+    }
+}
+
+/// PostgreSQL implementation of screening repository
+#[derive(Clone)]
+pub struct PostgresScreeningRepository {
+    pool: PgDbPool,
+}
+
+impl PostgresScreeningRepository {
+    pub fn new(pool: PgDbPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ScreeningRepository for PostgresScreeningRepository {
+    async fn save(&self, result: &SymbolScreeningResult) -> Result<i64, sqlx::Error> {
+        let recommendation = format!("{:?}", result.recommendation);
+
+        let row = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO symbol_screening_results
+            (symbol, exchange, volatility_score, volume_score, spread_score, momentum_score,
+             overall_score, recommendation, component_scores, screened_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id
+            "#,
+        )
+        .bind(&result.symbol)
+        .bind(&result.exchange)
+        .bind(result.volatility_score)
+        .bind(result.volume_score)
+        .bind(result.spread_score)
+        .bind(result.momentum_score)
+        .bind(result.overall_score)
+        .bind(&recommendation)
+        .bind(serde_json::to_string(&result.component_scores).ok())
+        .bind(result.screened_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    async fn get_recent(&self, limit: i64) -> Result<Vec<SymbolScreeningResult>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ScreeningRow>(&format!(
+            r#"
+            SELECT {}
+            FROM symbol_screening_results
+            ORDER BY screened_at DESC
+            LIMIT $1
+            "#,
+            RESULT_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(ScreeningRow::into_result).collect())
+    }
+
+    async fn get_by_symbol(
+        &self,
+        symbol: &str,
+        limit: i64,
+    ) -> Result<Vec<SymbolScreeningResult>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ScreeningRow>(&format!(
+            r#"
+            SELECT {}
+            FROM symbol_screening_results
+            WHERE symbol = $1
+            ORDER BY screened_at DESC
+            LIMIT $2
+            "#,
+            RESULT_COLUMNS
+        ))
+        .bind(symbol)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(ScreeningRow::into_result).collect())
+    }
+
+    async fn delete_old(&self, older_than_days: i64) -> Result<(), sqlx::Error> {
+        let cutoff = Utc::now() - chrono::Duration::days(older_than_days);
+        sqlx::query("DELETE FROM symbol_screening_results WHERE screened_at < $1")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_by_symbol_and_exchange(
+        &self,
+        symbol: &str,
+        exchange: &str,
+        recommendation: Option<RecommendationCategory>,
+        limit: i64,
+    ) -> Result<Vec<SymbolScreeningResult>, sqlx::Error> {
+        let recommendation = recommendation.map(|r| format!("{:?}", r));
+        let rows = sqlx::query_as::<_, ScreeningRow>(&format!(
+            r#"
+            SELECT {}
+            FROM symbol_screening_results
+            WHERE symbol = $1 AND exchange = $2
+              AND ($3::TEXT IS NULL OR recommendation = $3)
+            ORDER BY screened_at DESC
+            LIMIT $4
+            "#,
+            RESULT_COLUMNS
+        ))
+        .bind(symbol)
+        .bind(exchange)
+        .bind(&recommendation)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(ScreeningRow::into_result).collect())
+    }
+}
+
+/// PostgreSQL implementation of reconciliation repository
+pub struct PostgresReconciliationRepository {
+    pool: PgDbPool,
+    instance_id: String,
+}
+
+impl PostgresReconciliationRepository {
+    pub fn new(pool: PgDbPool, instance_id: impl Into<String>) -> Self {
+        Self {
+            pool,
+            instance_id: instance_id.into(),
+        }
+    }
+
+    async fn get_reconciliation(
+        &self,
+        id: &str,
+    ) -> Result<ReconciliationReport, ReconciliationError> {
+        let row = sqlx::query_as::<_, ReportRow>(&format!(
+            "SELECT {} FROM reconciliation_audit WHERE reconciliation_id = $1 AND instance_id = $2",
+            REPORT_COLUMNS
+        ))
+        .bind(id)
+        .bind(&self.instance_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.and_then(ReportRow::into_report).ok_or_else(|| {
+            ReconciliationError::ValidationError(format!("Unknown reconciliation {}", id))
+        })
+    }
+}
+
+#[async_trait]
+impl ReconciliationRepository for PostgresReconciliationRepository {
+    async fn save_reconciliation(
+        &self,
+        report: &ReconciliationReport,
+    ) -> Result<(), ReconciliationError> {
+        let to_json = |value: serde_json::Result<String>| {
+            value.map_err(|e| ReconciliationError::ParseError(e.to_string()))
+        };
+        let discrepancies_json = to_json(serde_json::to_string(&report.discrepancies))?;
+        let local_balances_json = to_json(serde_json::to_string(&report.local_balances))?;
+        let exchange_balances_json = to_json(serde_json::to_string(&report.exchange_balances))?;
+        let recovery_details_json = match &report.remediation {
+            Some(remediation) => Some(to_json(serde_json::to_string(&remediation.steps))?),
+            None => None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO reconciliation_audit (
+                reconciliation_id, exchange_id, reconciliation_timestamp,
+                status, discrepancy_count, local_balances_json,
+                exchange_balances_json, discrepancies_json, created_at,
+                recovery_attempted, recovery_status, recovery_details_json,
+                operator_notes, instance_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            "#,
+        )
+        .bind(report.id())
+        .bind(report.exchange.name())
+        .bind(report.timestamp)
+        .bind(report.status.to_string())
+        .bind(report.discrepancy_count() as i64)
+        .bind(&local_balances_json)
+        .bind(&exchange_balances_json)
+        .bind(&discrepancies_json)
+        .bind(Utc::now())
+        .bind(report.remediation.is_some())
+        .bind(
+            report
+                .remediation
+                .as_ref()
+                .map(|remediation| remediation.status.as_str()),
+        )
+        .bind(recovery_details_json)
+        .bind(&report.operator_notes)
+        .bind(&self.instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        debug!("Saved reconciliation audit for {:?}", report.exchange);
+        Ok(())
+    }
+
+    async fn get_last_reconciliation(
+        &self,
+        exchange: &Exchange,
+    ) -> Result<Option<ReconciliationReport>, ReconciliationError> {
+        let row = sqlx::query_as::<_, ReportRow>(&format!(
+            r#"
+            SELECT {}
+            FROM reconciliation_audit
+            WHERE exchange_id = $1 AND instance_id = $2
+            ORDER BY reconciliation_timestamp DESC
+            LIMIT 1
+            "#,
+            REPORT_COLUMNS
+        ))
+        .bind(exchange.name())
+        .bind(&self.instance_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(row.and_then(ReportRow::into_report))
+    }
+
+    async fn get_reconciliation_history(
+        &self,
+        exchange: &Exchange,
+        days: u32,
+    ) -> Result<Vec<ReconciliationReport>, ReconciliationError> {
+        let cutoff = Utc::now() - chrono::Duration::days(days as i64);
+
+        let rows = sqlx::query_as::<_, ReportRow>(&format!(
+            r#"
+            SELECT {}
+            FROM reconciliation_audit
+            WHERE exchange_id = $1 AND reconciliation_timestamp >= $2 AND instance_id = $3
+            ORDER BY reconciliation_timestamp DESC
+            "#,
+            REPORT_COLUMNS
+        ))
+        .bind(exchange.name())
+        .bind(cutoff)
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(ReportRow::into_report)
+            .collect())
+    }
+
+    async fn get_open_remediations(
+        &self,
+    ) -> Result<Vec<ReconciliationReport>, ReconciliationError> {
+        let rows = sqlx::query_as::<_, ReportRow>(&format!(
+            r#"
+            SELECT {}
+            FROM reconciliation_audit
+            WHERE recovery_status IN ('paused', 'awaiting_acknowledgement') AND instance_id = $1
+            ORDER BY reconciliation_timestamp ASC
+            "#,
+            REPORT_COLUMNS
+        ))
+        .bind(&self.instance_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(ReportRow::into_report)
+            .collect())
+    }
+
+    async fn annotate_reconciliation(
+        &self,
+        id: &str,
+        note: &str,
+    ) -> Result<(), ReconciliationError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE reconciliation_audit
+            SET operator_notes = COALESCE(operator_notes || chr(10), '') || $2
+            WHERE reconciliation_id = $1 AND instance_id = $3
+            "#,
+        )
+        .bind(id)
+        .bind(note_line(note))
+        .bind(&self.instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(ReconciliationError::ValidationError(format!(
+                "Unknown reconciliation {}",
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn resolve_reconciliation(
+        &self,
+        id: &str,
+        note: Option<&str>,
+    ) -> Result<ReconciliationReport, ReconciliationError> {
+        let report = self.get_reconciliation(id).await?;
+        if !report
+            .remediation
+            .as_ref()
+            .is_some_and(|remediation| remediation.status.is_open())
+        {
+            return Err(ReconciliationError::ValidationError(format!(
+                "Reconciliation {} has nothing to resolve",
+                id
+            )));
+        }
+
+        let open = self.get_open_remediations().await?;
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        for superseded in superseded_reports(&report, &open) {
+            sqlx::query(
+                r#"
+                UPDATE reconciliation_audit
+                SET recovery_status = $1
+                WHERE reconciliation_id = $2
+                  AND recovery_status IN ('paused', 'awaiting_acknowledgement')
+                  AND instance_id = $3
+                "#,
+            )
+            .bind(RecoveryStatus::Resolved.as_str())
+            .bind(&superseded)
+            .bind(&self.instance_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        }
+        if let Some(note) = note {
+            sqlx::query(
+                r#"
+                UPDATE reconciliation_audit
+                SET operator_notes = COALESCE(operator_notes || chr(10), '') || $2
+                WHERE reconciliation_id = $1 AND instance_id = $3
+                "#,
+            )
+            .bind(id)
+            .bind(note_line(note))
+            .bind(&self.instance_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        }
+        tx.commit().await.map_err(db_error)?;
+
+        debug!("Resolved reconciliation {}", id);
+        self.get_reconciliation(id).await
+    }
+}
@@ -2,15 +2,19 @@
 //!
 //! This repository handles persistence of reconciliation audit trails to the database.
 
-use super::models::*;
-use super::{DatabaseError, DbPool};
+use super::DbPool;
 use crate::domain::entities::exchange::Exchange;
 use crate::domain::services::reconciliation::*;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use sqlx::Row;
+use sqlx::FromRow;
 use tracing::{debug, error};
 
+/// Columns read back into a `ReconciliationReport`
+pub(crate) const REPORT_COLUMNS: &str = "exchange_id, reconciliation_timestamp, status, discrepancies_json, \
+     local_balances_json, exchange_balances_json, recovery_status, recovery_details_json, \
+     operator_notes";
+
 /// Reconciliation repository trait
 #[async_trait]
 pub trait ReconciliationRepository: Send + Sync {
@@ -27,6 +31,25 @@ pub trait ReconciliationRepository: Send + Sync {
         exchange: &Exchange,
         days: u32,
     ) -> Result<Vec<ReconciliationReport>, ReconciliationError>;
+
+    /// Reports whose remediation still waits for an operator, oldest first
+    async fn get_open_remediations(&self)
+        -> Result<Vec<ReconciliationReport>, ReconciliationError>;
+
+    /// Append an operator note to a report
+    async fn annotate_reconciliation(
+        &self,
+        id: &str,
+        note: &str,
+    ) -> Result<(), ReconciliationError>;
+
+    /// Resolve an open report, and the earlier open reports of its exchange
+    /// it supersedes, returning the resolved report
+    async fn resolve_reconciliation(
+        &self,
+        id: &str,
+        note: Option<&str>,
+    ) -> Result<ReconciliationReport, ReconciliationError>;
 }
 
 /// SQLite implementation of reconciliation repository
@@ -38,6 +61,115 @@ impl SqliteReconciliationRepository {
     pub fn new(pool: DbPool) -> Self {
         Self { pool }
     }
+
+    async fn get_reconciliation(
+        &self,
+        id: &str,
+    ) -> Result<ReconciliationReport, ReconciliationError> {
+        let row = sqlx::query_as::<_, ReportRow>(&format!(
+            "SELECT {} FROM reconciliation_audit WHERE reconciliation_id = ?1",
+            REPORT_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.and_then(ReportRow::into_report).ok_or_else(|| {
+            ReconciliationError::ValidationError(format!("Unknown reconciliation {}", id))
+        })
+    }
+}
+
+/// Stored report, shared by every backend
+#[derive(Debug, FromRow)]
+pub(crate) struct ReportRow {
+    exchange_id: String,
+    reconciliation_timestamp: DateTime<Utc>,
+    status: String,
+    discrepancies_json: String,
+    local_balances_json: String,
+    exchange_balances_json: String,
+    recovery_status: Option<String>,
+    recovery_details_json: Option<String>,
+    operator_notes: Option<String>,
+}
+
+impl ReportRow {
+    pub(crate) fn into_report(self) -> Option<ReconciliationReport> {
+        let remediation = self
+            .recovery_status
+            .as_deref()
+            .and_then(RecoveryStatus::from_name)
+            .map(|status| Remediation {
+                status,
+                steps: self
+                    .recovery_details_json
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
+            });
+
+        Some(ReconciliationReport {
+            exchange: Exchange::from_name(&self.exchange_id)?,
+            timestamp: self.reconciliation_timestamp,
+            status: status_from_str(&self.status),
+            discrepancies: serde_json::from_str(&self.discrepancies_json).unwrap_or_default(),
+            local_balances: serde_json::from_str(&self.local_balances_json).unwrap_or_default(),
+            exchange_balances: serde_json::from_str(&self.exchange_balances_json)
+                .unwrap_or_default(),
+            remediation,
+            operator_notes: self.operator_notes,
+        })
+    }
+}
+
+fn status_from_str(status: &str) -> ReconciliationStatus {
+    match status {
+        "MINOR" => ReconciliationStatus::Minor,
+        "MAJOR" => ReconciliationStatus::Major,
+        "CRITICAL" => ReconciliationStatus::Critical,
+        _ => ReconciliationStatus::Ok,
+    }
+}
+
+pub(crate) fn db_error(e: sqlx::Error) -> ReconciliationError {
+    error!("Reconciliation audit query failed: {}", e);
+    ReconciliationError::ApiError(format!("Database error: {}", e))
+}
+
+/// Open reports closed by resolving `report`
+///
+/// Besides the report itself, an earlier open report of the same exchange is
+/// superseded when every currency it remediates is among the report's
+/// discrepancies; reports on other currencies stay open.
+pub(crate) fn superseded_reports(
+    report: &ReconciliationReport,
+    open: &[ReconciliationReport],
+) -> Vec<String> {
+    let id = report.id();
+    let mut ids: Vec<String> = open
+        .iter()
+        .filter(|other| other.exchange == report.exchange && other.timestamp <= report.timestamp)
+        .filter(|other| {
+            other.remediation.as_ref().is_some_and(|remediation| {
+                remediation.steps.iter().all(|step| {
+                    report
+                        .discrepancies
+                        .iter()
+                        .any(|discrepancy| discrepancy.currency() == step.currency)
+                })
+            })
+        })
+        .map(ReconciliationReport::id)
+        .filter(|other| *other != id)
+        .collect();
+    ids.push(id);
+    ids
+}
+
+/// Operator note prefixed with the time it was written
+pub(crate) fn note_line(note: &str) -> String {
+    format!("{} {}", Utc::now().to_rfc3339(), note.trim())
 }
 
 #[async_trait]
@@ -46,48 +178,50 @@ impl ReconciliationRepository for SqliteReconciliationRepository {
         &self,
         report: &ReconciliationReport,
     ) -> Result<(), ReconciliationError> {
-        let exchange_str = match report.exchange {
-            Exchange::Coinbase => "coinbase",
-            Exchange::Dydx => "dydx",
-            _ => "unknown",
+        let to_json = |value: serde_json::Result<String>| {
+            value.map_err(|e| ReconciliationError::ParseError(e.to_string()))
         };
-
-        let discrepancies_json = serde_json::to_string(&report.discrepancies)
-            .map_err(|e| ReconciliationError::ParseError(e.to_string()))?;
-
-        let status_str = match report.status {
-            ReconciliationStatus::Ok => "OK",
-            ReconciliationStatus::Minor => "MINOR",
-            ReconciliationStatus::Major => "MAJOR",
-            ReconciliationStatus::Critical => "CRITICAL",
+        let discrepancies_json = to_json(serde_json::to_string(&report.discrepancies))?;
+        let local_balances_json = to_json(serde_json::to_string(&report.local_balances))?;
+        let exchange_balances_json = to_json(serde_json::to_string(&report.exchange_balances))?;
+        let recovery_details_json = match &report.remediation {
+            Some(remediation) => Some(to_json(serde_json::to_string(&remediation.steps))?),
+            None => None,
         };
 
         sqlx::query(
             r#"
             INSERT INTO reconciliation_audit (
                 reconciliation_id, exchange_id, reconciliation_timestamp,
-                status, discrepancy_count, discrepancies_json, created_at
+                status, discrepancy_count, local_balances_json,
+                exchange_balances_json, discrepancies_json, created_at,
+                recovery_attempted, recovery_status, recovery_details_json,
+                operator_notes
             )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
             "#,
         )
-        .bind(format!(
-            "rec_{}_{}",
-            exchange_str,
-            report.timestamp.timestamp()
-        ))
-        .bind(exchange_str)
+        .bind(report.id())
+        .bind(report.exchange.name())
         .bind(report.timestamp)
-        .bind(status_str)
+        .bind(report.status.to_string())
         .bind(report.discrepancy_count() as i64)
+        .bind(&local_balances_json)
+        .bind(&exchange_balances_json)
         .bind(&discrepancies_json)
         .bind(Utc::now())
+        .bind(report.remediation.is_some())
+        .bind(
+            report
+                .remediation
+                .as_ref()
+                .map(|remediation| remediation.status.as_str()),
+        )
+        .bind(recovery_details_json)
+        .bind(&report.operator_notes)
         .execute(&self.pool)
         .await
-        .map_err(|e| {
-            error!("Failed to save reconciliation audit: {}", e);
-            ReconciliationError::ApiError(format!("Database error: {}", e))
-        })?;
+        .map_err(db_error)?;
 
         debug!("Saved reconciliation audit for {:?}", report.exchange);
         Ok(())
@@ -97,60 +231,22 @@ impl ReconciliationRepository for SqliteReconciliationRepository {
         &self,
         exchange: &Exchange,
     ) -> Result<Option<ReconciliationReport>, ReconciliationError> {
-        let exchange_str = match exchange {
-            Exchange::Coinbase => "coinbase",
-            Exchange::Dydx => "dydx",
-            _ => "unknown",
-        };
-
-        let row = sqlx::query(
+        let row = sqlx::query_as::<_, ReportRow>(&format!(
             r#"
-            SELECT reconciliation_timestamp, status, discrepancy_count, discrepancies_json, exchange_id
+            SELECT {}
             FROM reconciliation_audit
             WHERE exchange_id = ?1
             ORDER BY reconciliation_timestamp DESC
             LIMIT 1
             "#,
-        )
-        .bind(exchange_str)
+            REPORT_COLUMNS
+        ))
+        .bind(exchange.name())
         .fetch_optional(&self.pool)
         .await
-        .map_err(|e| {
-            error!("Failed to get last reconciliation for {:?}: {}", exchange, e);
-            ReconciliationError::ApiError(format!("Database error: {}", e))
-        })?;
-
-        match row {
-            Some(row) => {
-                let timestamp: DateTime<Utc> = row.get("reconciliation_timestamp");
-                let status_str: String = row.get("status");
-                let discrepancy_count: i64 = row.get("discrepancy_count");
-                let discrepancies_json: String = row.get("discrepancies_json");
-
-                let status = match status_str.as_str() {
-                    "OK" => ReconciliationStatus::Ok,
-                    "MINOR" => ReconciliationStatus::Minor,
-                    "MAJOR" => ReconciliationStatus::Major,
-                    "CRITICAL" => ReconciliationStatus::Critical,
-                    _ => ReconciliationStatus::Ok,
-                };
-
-                let discrepancies: Vec<BalanceDiscrepancy> =
-                    serde_json::from_str(&discrepancies_json).unwrap_or_default();
-
-                let report = ReconciliationReport {
-                    exchange: exchange.clone(),
-                    timestamp,
-                    status,
-                    discrepancies,
-                    local_balances: Vec::new(),
-                    exchange_balances: std::collections::HashMap::new(),
-                };
-
-                Ok(Some(report))
-            }
-            None => Ok(None),
-        }
+        .map_err(db_error)?;
+
+        Ok(row.and_then(ReportRow::into_report))
     }
 
     async fn get_reconciliation_history(
@@ -158,61 +254,124 @@ impl ReconciliationRepository for SqliteReconciliationRepository {
         exchange: &Exchange,
         days: u32,
     ) -> Result<Vec<ReconciliationReport>, ReconciliationError> {
-        let exchange_str = match exchange {
-            Exchange::Coinbase => "coinbase",
-            Exchange::Dydx => "dydx",
-            _ => "unknown",
-        };
-
         let cutoff = Utc::now() - chrono::Duration::days(days as i64);
 
-        let rows = sqlx::query(
+        let rows = sqlx::query_as::<_, ReportRow>(&format!(
             r#"
-            SELECT reconciliation_timestamp, status, discrepancy_count, discrepancies_json, exchange_id
+            SELECT {}
             FROM reconciliation_audit
             WHERE exchange_id = ?1 AND reconciliation_timestamp >= ?2
             ORDER BY reconciliation_timestamp DESC
             "#,
-        )
-        .bind(exchange_str)
+            REPORT_COLUMNS
+        ))
+        .bind(exchange.name())
         .bind(cutoff)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| {
-            error!("Failed to get reconciliation history for {:?}: {}", exchange, e);
-            ReconciliationError::ApiError(format!("Database error: {}", e))
-        })?;
-
-        let mut reports = Vec::new();
-        for row in rows {
-            let timestamp: DateTime<Utc> = row.get("reconciliation_timestamp");
-            let status_str: String = row.get("status");
-            let discrepancies_json: String = row.get("discrepancies_json");
-
-            let status = match status_str.as_str() {
-                "OK" => ReconciliationStatus::Ok,
-                "MINOR" => ReconciliationStatus::Minor,
-                "MAJOR" => ReconciliationStatus::Major,
-                "CRITICAL" => ReconciliationStatus::Critical,
-                _ => ReconciliationStatus::Ok,
-            };
-
-            let discrepancies: Vec<BalanceDiscrepancy> =
-                serde_json::from_str(&discrepancies_json).unwrap_or_default();
-
-            let report = ReconciliationReport {
-                exchange: exchange.clone(),
-                timestamp,
-                status,
-                discrepancies,
-                local_balances: Vec::new(),
-                exchange_balances: std::collections::HashMap::new(),
-            };
+        .map_err(db_error)?;
 
-            reports.push(report);
+        Ok(rows.into_iter().filter_map(ReportRow::into_report).collect())
+    }
+
+    async fn get_open_remediations(
+        &self,
+    ) -> Result<Vec<ReconciliationReport>, ReconciliationError> {
+        let rows = sqlx::query_as::<_, ReportRow>(&format!(
+            r#"
+            SELECT {}
+            FROM reconciliation_audit
+            WHERE recovery_status IN ('paused', 'awaiting_acknowledgement')
+            ORDER BY reconciliation_timestamp ASC
+            "#,
+            REPORT_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(rows.into_iter().filter_map(ReportRow::into_report).collect())
+    }
+
+    async fn annotate_reconciliation(
+        &self,
+        id: &str,
+        note: &str,
+    ) -> Result<(), ReconciliationError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE reconciliation_audit
+            SET operator_notes = COALESCE(operator_notes || char(10), '') || ?2
+            WHERE reconciliation_id = ?1
+            "#,
+        )
+        .bind(id)
+        .bind(note_line(note))
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(ReconciliationError::ValidationError(format!(
+                "Unknown reconciliation {}",
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn resolve_reconciliation(
+        &self,
+        id: &str,
+        note: Option<&str>,
+    ) -> Result<ReconciliationReport, ReconciliationError> {
+        let report = self.get_reconciliation(id).await?;
+        if !report
+            .remediation
+            .as_ref()
+            .is_some_and(|remediation| remediation.status.is_open())
+        {
+            return Err(ReconciliationError::ValidationError(format!(
+                "Reconciliation {} has nothing to resolve",
+                id
+            )));
+        }
+
+        let open = self.get_open_remediations().await?;
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        for superseded in superseded_reports(&report, &open) {
+            sqlx::query(
+                r#"
+                UPDATE reconciliation_audit
+                SET recovery_status = ?1
+                WHERE reconciliation_id = ?2
+                  AND recovery_status IN ('paused', 'awaiting_acknowledgement')
+                "#,
+            )
+            .bind(RecoveryStatus::Resolved.as_str())
+            .bind(&superseded)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        }
+        if let Some(note) = note {
+            sqlx::query(
+                r#"
+                UPDATE reconciliation_audit
+                SET operator_notes = COALESCE(operator_notes || char(10), '') || ?2
+                WHERE reconciliation_id = ?1
+                "#,
+            )
+            .bind(id)
+            .bind(note_line(note))
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
         }
+        tx.commit().await.map_err(db_error)?;
 
-        Ok(reports)
+        debug!("Resolved reconciliation {}", id);
+        self.get_reconciliation(id).await
     }
 }
 
@@ -225,3 +384,136 @@ pub struct ReconciliationAuditEntry {
     pub status: ReconciliationStatus,
     pub discrepancy_count: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::init_database;
+
+    fn open_report(exchange: Exchange, seconds_ago: i64) -> ReconciliationReport {
+        open_report_on(exchange, "USD", seconds_ago)
+    }
+
+    fn open_report_on(
+        exchange: Exchange,
+        currency: &str,
+        seconds_ago: i64,
+    ) -> ReconciliationReport {
+        let mut report = ReconciliationReport::new(exchange);
+        report.timestamp = Utc::now() - chrono::Duration::seconds(seconds_ago);
+        report.add_discrepancy(BalanceDiscrepancy::Missing {
+            currency: currency.to_string(),
+            amount: 500.0,
+        });
+        report.remediation = RemediationPolicy {
+            critical: RemediationAction::PauseTrading,
+            ..Default::default()
+        }
+        .plan(&report);
+        report
+    }
+
+    #[tokio::test]
+    async fn test_resolving_closes_earlier_open_remediations_of_the_same_currencies() {
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        let repo = SqliteReconciliationRepository::new(pool);
+
+        let first = open_report(Exchange::Dydx, 20);
+        let second = open_report(Exchange::Dydx, 10);
+        let other = open_report(Exchange::Coinbase, 15);
+        for report in [&first, &second, &other] {
+            repo.save_reconciliation(report).await.unwrap();
+        }
+        assert_eq!(repo.get_open_remediations().await.unwrap().len(), 3);
+
+        repo.annotate_reconciliation(&second.id(), "withdrawal to cold wallet")
+            .await
+            .unwrap();
+        assert!(repo
+            .annotate_reconciliation("rec_dydx_0", "x")
+            .await
+            .is_err());
+
+        let resolved = repo
+            .resolve_reconciliation(&second.id(), Some("confirmed"))
+            .await
+            .unwrap();
+        let remediation = resolved.remediation.unwrap();
+        assert_eq!(remediation.status, RecoveryStatus::Resolved);
+        assert_eq!(remediation.paused_currencies(), vec!["USD"]);
+        let notes = resolved.operator_notes.unwrap();
+        assert!(notes.contains("withdrawal to cold wallet\n"));
+        assert!(notes.ends_with("confirmed"));
+
+        let open = repo.get_open_remediations().await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].exchange, Exchange::Coinbase);
+        assert!(repo
+            .resolve_reconciliation(&first.id(), None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolving_leaves_open_remediations_of_other_currencies() {
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        let repo = SqliteReconciliationRepository::new(pool);
+
+        let older = open_report_on(Exchange::Dydx, "USD", 20);
+        let newer = open_report_on(Exchange::Dydx, "ETH", 10);
+        repo.save_reconciliation(&older).await.unwrap();
+        repo.save_reconciliation(&newer).await.unwrap();
+
+        repo.resolve_reconciliation(&older.id(), None)
+            .await
+            .unwrap();
+
+        let open = repo.get_open_remediations().await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].id(), newer.id());
+        assert_eq!(
+            open[0].remediation.as_ref().unwrap().paused_currencies(),
+            vec!["ETH"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reports_round_trip_with_balances() {
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        let repo = SqliteReconciliationRepository::new(pool);
+
+        let mut report = ReconciliationReport::new(Exchange::Dydx);
+        report.add_discrepancy(BalanceDiscrepancy::Missing {
+            currency: "USD".to_string(),
+            amount: 250.0,
+        });
+        report.local_balances = vec![Balance {
+            currency: "USD".to_string(),
+            amount: 250.0,
+        }];
+        repo.save_reconciliation(&report).await.unwrap();
+
+        let mut later = ReconciliationReport::new(Exchange::Dydx);
+        later.timestamp = report.timestamp + chrono::Duration::seconds(1);
+        repo.save_reconciliation(&later).await.unwrap();
+        repo.save_reconciliation(&ReconciliationReport::new(Exchange::Coinbase))
+            .await
+            .unwrap();
+
+        let last = repo
+            .get_last_reconciliation(&Exchange::Dydx)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(last.status, ReconciliationStatus::Ok);
+
+        let history = repo
+            .get_reconciliation_history(&Exchange::Dydx, 1)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].status, ReconciliationStatus::Critical);
+        assert_eq!(history[1].discrepancies, report.discrepancies);
+        assert_eq!(history[1].local_balances, report.local_balances);
+    }
+}
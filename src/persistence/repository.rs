@@ -1,34 +1,75 @@
 //! Database Repository
 //!
-//! Data access layer for positions, trades, and audit logs.
+//! Data access layer for positions, trades, funding payments, signals, and
+//! audit logs.
 
 use super::models::*;
 use super::{DatabaseError, DbPool};
+use crate::domain::entities::bracket_order::BracketOrder;
+use async_trait::async_trait;
 use chrono::Utc;
 use sqlx::Row;
 use tracing::{debug, error};
 
 /// Position repository
-pub struct PositionRepository {
+#[async_trait]
+pub trait PositionRepository: Send + Sync {
+    /// Create a new position
+    async fn create(&self, position: CreatePosition) -> Result<PositionRecord, DatabaseError>;
+
+    /// Get position by ID
+    async fn get(&self, id: &str) -> Result<Option<PositionRecord>, DatabaseError>;
+
+    /// Update position price and PnL
+    async fn update(&self, id: &str, update: UpdatePosition) -> Result<(), DatabaseError>;
+
+    /// Close a position
+    async fn close(
+        &self,
+        id: &str,
+        final_price: f64,
+        realized_pnl: f64,
+    ) -> Result<(), DatabaseError>;
+
+    /// Get all open positions
+    async fn get_open_positions(&self) -> Result<Vec<PositionRecord>, DatabaseError>;
+
+    /// Get positions by symbol
+    async fn get_by_symbol(&self, symbol: &str) -> Result<Vec<PositionRecord>, DatabaseError>;
+
+    /// Get open positions count by symbol
+    async fn count_open_by_symbol(&self, symbol: &str) -> Result<i64, DatabaseError>;
+
+    /// Record a position group whose legs are created with its `group_id`
+    async fn create_group(&self, group: PositionGroupRecord) -> Result<(), DatabaseError>;
+
+    /// Get position group by ID
+    async fn get_group(&self, id: &str) -> Result<Option<PositionGroupRecord>, DatabaseError>;
+}
+
+/// SQLite implementation of position repository
+pub struct SqlitePositionRepository {
     pool: DbPool,
 }
 
-impl PositionRepository {
+impl SqlitePositionRepository {
     pub fn new(pool: DbPool) -> Self {
         Self { pool }
     }
+}
 
-    /// Create a new position
-    pub async fn create(&self, position: CreatePosition) -> Result<PositionRecord, DatabaseError> {
+#[async_trait]
+impl PositionRepository for SqlitePositionRepository {
+    async fn create(&self, position: CreatePosition) -> Result<PositionRecord, DatabaseError> {
         let now = Utc::now();
         let record = sqlx::query_as::<_, PositionRecord>(
             r#"
             INSERT INTO positions (
                 id, symbol, exchange, side, entry_price, quantity,
                 current_price, unrealized_pnl, status, opened_at,
-                stop_loss, take_profit, created_at, updated_at
+                stop_loss, take_profit, created_at, updated_at, group_id
             )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?5, 0.0, 'open', ?7, ?8, ?9, ?7, ?7)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?5, 0.0, 'open', ?7, ?8, ?9, ?7, ?7, ?10)
             RETURNING *
             "#,
         )
@@ -41,6 +82,7 @@ impl PositionRepository {
         .bind(now)
         .bind(position.stop_loss)
         .bind(position.take_profit)
+        .bind(&position.group_id)
         .fetch_one(&self.pool)
         .await
         .map_err(|e| {
@@ -52,8 +94,7 @@ impl PositionRepository {
         Ok(record)
     }
 
-    /// Get position by ID
-    pub async fn get(&self, id: &str) -> Result<Option<PositionRecord>, DatabaseError> {
+    async fn get(&self, id: &str) -> Result<Option<PositionRecord>, DatabaseError> {
         let record = sqlx::query_as::<_, PositionRecord>("SELECT * FROM positions WHERE id = ?1")
             .bind(id)
             .fetch_optional(&self.pool)
@@ -66,8 +107,7 @@ impl PositionRepository {
         Ok(record)
     }
 
-    /// Update position price and PnL
-    pub async fn update(&self, id: &str, update: UpdatePosition) -> Result<(), DatabaseError> {
+    async fn update(&self, id: &str, update: UpdatePosition) -> Result<(), DatabaseError> {
         let now = Utc::now();
         let rows_affected = sqlx::query(
             r#"
@@ -99,8 +139,7 @@ impl PositionRepository {
         Ok(())
     }
 
-    /// Close a position
-    pub async fn close(
+    async fn close(
         &self,
         id: &str,
         final_price: f64,
@@ -138,8 +177,7 @@ impl PositionRepository {
         Ok(())
     }
 
-    /// Get all open positions
-    pub async fn get_open_positions(&self) -> Result<Vec<PositionRecord>, DatabaseError> {
+    async fn get_open_positions(&self) -> Result<Vec<PositionRecord>, DatabaseError> {
         let records = sqlx::query_as::<_, PositionRecord>(
             "SELECT * FROM positions WHERE status = 'open' ORDER BY opened_at DESC",
         )
@@ -153,8 +191,7 @@ impl PositionRepository {
         Ok(records)
     }
 
-    /// Get positions by symbol
-    pub async fn get_by_symbol(&self, symbol: &str) -> Result<Vec<PositionRecord>, DatabaseError> {
+    async fn get_by_symbol(&self, symbol: &str) -> Result<Vec<PositionRecord>, DatabaseError> {
         let records = sqlx::query_as::<_, PositionRecord>(
             "SELECT * FROM positions WHERE symbol = ?1 ORDER BY opened_at DESC",
         )
@@ -169,8 +206,7 @@ impl PositionRepository {
         Ok(records)
     }
 
-    /// Get open positions count by symbol
-    pub async fn count_open_by_symbol(&self, symbol: &str) -> Result<i64, DatabaseError> {
+    async fn count_open_by_symbol(&self, symbol: &str) -> Result<i64, DatabaseError> {
         let row = sqlx::query(
             "SELECT COUNT(*) as count FROM positions WHERE symbol = ?1 AND status = 'open'",
         )
@@ -185,28 +221,116 @@ impl PositionRepository {
         let count: i64 = row.get("count");
         Ok(count)
     }
+    async fn create_group(&self, group: PositionGroupRecord) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO position_groups (id, strategy, hedge_ratio, entry_zscore, opened_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(&group.id)
+        .bind(&group.strategy)
+        .bind(group.hedge_ratio)
+        .bind(group.entry_zscore)
+        .bind(group.opened_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to create position group {}: {}", group.id, e);
+            DatabaseError::QueryError(format!("Failed to create position group: {}", e))
+        })?;
+
+        debug!(
+            "Created position group: {} for {}",
+            group.id, group.strategy
+        );
+        Ok(())
+    }
+
+    async fn get_group(&self, id: &str) -> Result<Option<PositionGroupRecord>, DatabaseError> {
+        let record =
+            sqlx::query_as::<_, PositionGroupRecord>("SELECT * FROM position_groups WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| {
+                    error!("Failed to get position group {}: {}", id, e);
+                    DatabaseError::QueryError(format!("Failed to get position group: {}", e))
+                })?;
+
+        Ok(record)
+    }
 }
 
 /// Trade repository
-pub struct TradeRepository {
+#[async_trait]
+pub trait TradeRepository: Send + Sync {
+    /// Create a new trade
+    async fn create(&self, trade: CreateTrade) -> Result<TradeRecord, DatabaseError>;
+
+    /// Get trade by ID
+    async fn get(&self, id: &str) -> Result<Option<TradeRecord>, DatabaseError>;
+
+    /// Get trades by position ID
+    async fn get_by_position(
+        &self,
+        position_id: &str,
+    ) -> Result<Vec<TradeRecord>, DatabaseError>;
+
+    /// Get trades placed by an exchange order
+    async fn get_by_exchange_order(
+        &self,
+        exchange_order_id: &str,
+    ) -> Result<Vec<TradeRecord>, DatabaseError>;
+
+    /// Get recent trades (last N)
+    async fn get_recent(&self, limit: i64) -> Result<Vec<TradeRecord>, DatabaseError>;
+
+    /// Get trades executed since a point in time (oldest first)
+    async fn get_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<TradeRecord>, DatabaseError>;
+
+    /// Get closing trades by exit reason (most recent first)
+    async fn get_by_exit_reason(
+        &self,
+        exit_reason: &str,
+        limit: i64,
+    ) -> Result<Vec<TradeRecord>, DatabaseError>;
+
+    /// Get trades by symbol and date range
+    async fn get_by_symbol_range(
+        &self,
+        symbol: &str,
+        start: chrono::DateTime<Utc>,
+        end: chrono::DateTime<Utc>,
+    ) -> Result<Vec<TradeRecord>, DatabaseError>;
+}
+
+/// SQLite implementation of trade repository
+pub struct SqliteTradeRepository {
     pool: DbPool,
 }
 
-impl TradeRepository {
+impl SqliteTradeRepository {
     pub fn new(pool: DbPool) -> Self {
         Self { pool }
     }
+}
 
-    /// Create a new trade
-    pub async fn create(&self, trade: CreateTrade) -> Result<TradeRecord, DatabaseError> {
+#[async_trait]
+impl TradeRepository for SqliteTradeRepository {
+    async fn create(&self, trade: CreateTrade) -> Result<TradeRecord, DatabaseError> {
         let now = Utc::now();
         let record = sqlx::query_as::<_, TradeRecord>(
             r#"
             INSERT INTO trades (
                 id, position_id, symbol, exchange, side, price, quantity,
-                fee, exchange_order_id, executed_at, strategy, signal_confidence, created_at
+                fee, exchange_order_id, executed_at, strategy, signal_confidence, exit_reason,
+                signal_details, created_at, fee_estimated
             )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?10)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?10, ?15)
             RETURNING *
             "#,
         )
@@ -222,6 +346,9 @@ impl TradeRepository {
         .bind(now)
         .bind(&trade.strategy)
         .bind(trade.signal_confidence)
+        .bind(&trade.exit_reason)
+        .bind(&trade.signal_details)
+        .bind(trade.fee_estimated)
         .fetch_one(&self.pool)
         .await
         .map_err(|e| {
@@ -233,8 +360,7 @@ impl TradeRepository {
         Ok(record)
     }
 
-    /// Get trade by ID
-    pub async fn get(&self, id: &str) -> Result<Option<TradeRecord>, DatabaseError> {
+    async fn get(&self, id: &str) -> Result<Option<TradeRecord>, DatabaseError> {
         let record = sqlx::query_as::<_, TradeRecord>("SELECT * FROM trades WHERE id = ?1")
             .bind(id)
             .fetch_optional(&self.pool)
@@ -247,8 +373,7 @@ impl TradeRepository {
         Ok(record)
     }
 
-    /// Get trades by position ID
-    pub async fn get_by_position(
+    async fn get_by_position(
         &self,
         position_id: &str,
     ) -> Result<Vec<TradeRecord>, DatabaseError> {
@@ -266,8 +391,28 @@ impl TradeRepository {
         Ok(records)
     }
 
-    /// Get recent trades (last N)
-    pub async fn get_recent(&self, limit: i64) -> Result<Vec<TradeRecord>, DatabaseError> {
+    async fn get_by_exchange_order(
+        &self,
+        exchange_order_id: &str,
+    ) -> Result<Vec<TradeRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, TradeRecord>(
+            "SELECT * FROM trades WHERE exchange_order_id = ?1 ORDER BY executed_at DESC",
+        )
+        .bind(exchange_order_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to get trades for order {}: {}",
+                exchange_order_id, e
+            );
+            DatabaseError::QueryError(format!("Failed to get trades: {}", e))
+        })?;
+
+        Ok(records)
+    }
+
+    async fn get_recent(&self, limit: i64) -> Result<Vec<TradeRecord>, DatabaseError> {
         let records = sqlx::query_as::<_, TradeRecord>(
             "SELECT * FROM trades ORDER BY executed_at DESC LIMIT ?1",
         )
@@ -282,8 +427,48 @@ impl TradeRepository {
         Ok(records)
     }
 
-    /// Get trades by symbol and date range
-    pub async fn get_by_symbol_range(
+    async fn get_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<TradeRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, TradeRecord>(
+            "SELECT * FROM trades WHERE executed_at >= ?1 ORDER BY executed_at ASC",
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get trades since {}: {}", since, e);
+            DatabaseError::QueryError(format!("Failed to get trades: {}", e))
+        })?;
+
+        Ok(records)
+    }
+
+    async fn get_by_exit_reason(
+        &self,
+        exit_reason: &str,
+        limit: i64,
+    ) -> Result<Vec<TradeRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, TradeRecord>(
+            "SELECT * FROM trades WHERE exit_reason = ?1 ORDER BY executed_at DESC LIMIT ?2",
+        )
+        .bind(exit_reason)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to get trades for exit reason {}: {}",
+                exit_reason, e
+            );
+            DatabaseError::QueryError(format!("Failed to get trades: {}", e))
+        })?;
+
+        Ok(records)
+    }
+
+    async fn get_by_symbol_range(
         &self,
         symbol: &str,
         start: chrono::DateTime<Utc>,
@@ -311,17 +496,36 @@ impl TradeRepository {
 }
 
 /// Audit log repository
-pub struct AuditLogRepository {
+#[async_trait]
+pub trait AuditLogRepository: Send + Sync {
+    /// Create a new audit log entry
+    async fn create(&self, log: CreateAuditLog) -> Result<AuditLogRecord, DatabaseError>;
+
+    /// Get recent audit logs
+    async fn get_recent(&self, limit: i64) -> Result<Vec<AuditLogRecord>, DatabaseError>;
+
+    /// Get audit logs by event type
+    async fn get_by_event_type(
+        &self,
+        event_type: &str,
+        limit: i64,
+    ) -> Result<Vec<AuditLogRecord>, DatabaseError>;
+}
+
+/// SQLite implementation of audit log repository
+pub struct SqliteAuditLogRepository {
     pool: DbPool,
 }
 
-impl AuditLogRepository {
+impl SqliteAuditLogRepository {
     pub fn new(pool: DbPool) -> Self {
         Self { pool }
     }
+}
 
-    /// Create a new audit log entry
-    pub async fn create(&self, log: CreateAuditLog) -> Result<AuditLogRecord, DatabaseError> {
+#[async_trait]
+impl AuditLogRepository for SqliteAuditLogRepository {
+    async fn create(&self, log: CreateAuditLog) -> Result<AuditLogRecord, DatabaseError> {
         let now = Utc::now();
         let details_json = serde_json::to_string(&log.details).map_err(|e| {
             DatabaseError::QueryError(format!("Failed to serialize details: {}", e))
@@ -353,8 +557,7 @@ impl AuditLogRepository {
         Ok(record)
     }
 
-    /// Get recent audit logs
-    pub async fn get_recent(&self, limit: i64) -> Result<Vec<AuditLogRecord>, DatabaseError> {
+    async fn get_recent(&self, limit: i64) -> Result<Vec<AuditLogRecord>, DatabaseError> {
         let records = sqlx::query_as::<_, AuditLogRecord>(
             "SELECT * FROM audit_log ORDER BY timestamp DESC LIMIT ?1",
         )
@@ -369,8 +572,7 @@ impl AuditLogRepository {
         Ok(records)
     }
 
-    /// Get audit logs by event type
-    pub async fn get_by_event_type(
+    async fn get_by_event_type(
         &self,
         event_type: &str,
         limit: i64,
@@ -394,18 +596,249 @@ impl AuditLogRepository {
     }
 }
 
-/// dYdX order metadata repository
-pub struct DydxOrderMetadataRepository {
+/// Funding payment repository
+///
+/// Keeps the funding payments entered in the PnL ledger, so it can be
+/// rebuilt after a restart.
+#[async_trait]
+pub trait FundingPaymentRepository: Send + Sync {
+    /// Record a funding payment; one already recorded for the same exchange,
+    /// symbol and time is left as it is
+    async fn create(&self, payment: CreateFundingPayment) -> Result<(), DatabaseError>;
+
+    /// Get funding payments paid at or after `since`, oldest first
+    async fn get_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<FundingPaymentRecord>, DatabaseError>;
+}
+
+/// SQLite implementation of funding payment repository
+pub struct SqliteFundingPaymentRepository {
     pool: DbPool,
 }
 
-impl DydxOrderMetadataRepository {
+impl SqliteFundingPaymentRepository {
     pub fn new(pool: DbPool) -> Self {
         Self { pool }
     }
+}
+
+#[async_trait]
+impl FundingPaymentRepository for SqliteFundingPaymentRepository {
+    async fn create(&self, payment: CreateFundingPayment) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO funding_payments (exchange, symbol, strategy, amount, paid_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(exchange, symbol, paid_at) DO NOTHING
+            "#,
+        )
+        .bind(&payment.exchange)
+        .bind(&payment.symbol)
+        .bind(&payment.strategy)
+        .bind(payment.amount)
+        .bind(payment.paid_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to record funding payment on {}: {}",
+                payment.symbol, e
+            );
+            DatabaseError::QueryError(format!("Failed to record funding payment: {}", e))
+        })?;
+
+        debug!(
+            "Recorded funding payment: {} {} at {}",
+            payment.exchange, payment.symbol, payment.paid_at
+        );
+        Ok(())
+    }
+
+    async fn get_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<FundingPaymentRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, FundingPaymentRecord>(
+            "SELECT * FROM funding_payments WHERE paid_at >= ?1 ORDER BY paid_at ASC, id ASC",
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get funding payments since {}: {}", since, e);
+            DatabaseError::QueryError(format!("Failed to get funding payments: {}", e))
+        })?;
+
+        Ok(records)
+    }
+}
+
+/// Signal repository
+///
+/// Keeps every generated signal so missed and taken trades can be audited.
+#[async_trait]
+pub trait SignalRepository: Send + Sync {
+    /// Record a newly generated signal as pending
+    async fn create(&self, signal: CreateSignal) -> Result<SignalRecord, DatabaseError>;
+
+    /// Record whether a signal was executed, or why it was skipped
+    async fn record_outcome(
+        &self,
+        id: i64,
+        skip_reason: Option<SignalSkipReason>,
+        outcome: &str,
+    ) -> Result<(), DatabaseError>;
+
+    /// Get signals matching the filters, newest first
+    async fn query(&self, query: &SignalQuery) -> Result<Vec<SignalRecord>, DatabaseError>;
+}
+
+/// SQLite implementation of signal repository
+pub struct SqliteSignalRepository {
+    pool: DbPool,
+}
+
+impl SqliteSignalRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SignalRepository for SqliteSignalRepository {
+    async fn create(&self, signal: CreateSignal) -> Result<SignalRecord, DatabaseError> {
+        let now = Utc::now();
+        let record = sqlx::query_as::<_, SignalRecord>(
+            r#"
+            INSERT INTO signals (
+                symbol, action, confidence, price, weights, details, executed, regime,
+                generated_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, ?7, ?8)
+            RETURNING *
+            "#,
+        )
+        .bind(&signal.symbol)
+        .bind(&signal.action)
+        .bind(signal.confidence)
+        .bind(signal.price)
+        .bind(signal.weights.to_string())
+        .bind(signal.details.to_string())
+        .bind(&signal.regime)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to record signal for {}: {}", signal.symbol, e);
+            DatabaseError::QueryError(format!("Failed to record signal: {}", e))
+        })?;
+
+        debug!("Recorded signal {} for {}", record.id, record.symbol);
+        Ok(record)
+    }
+
+    async fn record_outcome(
+        &self,
+        id: i64,
+        skip_reason: Option<SignalSkipReason>,
+        outcome: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE signals
+            SET executed = ?2, skip_reason = ?3, outcome = ?4, decided_at = ?5
+            WHERE id = ?1
+            "#,
+        )
+        .bind(id)
+        .bind(skip_reason.is_none())
+        .bind(skip_reason.map(|reason| reason.as_str()))
+        .bind(outcome)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to record outcome of signal {}: {}", id, e);
+            DatabaseError::QueryError(format!("Failed to record signal outcome: {}", e))
+        })?;
+
+        Ok(())
+    }
 
+    async fn query(&self, query: &SignalQuery) -> Result<Vec<SignalRecord>, DatabaseError> {
+        let records = sqlx::query_as::<_, SignalRecord>(
+            r#"
+            SELECT * FROM signals
+            WHERE (?1 IS NULL OR symbol = ?1)
+              AND (?2 IS NULL OR generated_at >= ?2)
+              AND (?3 IS NULL OR generated_at <= ?3)
+            ORDER BY generated_at DESC, id DESC
+            LIMIT ?4
+            "#,
+        )
+        .bind(&query.symbol)
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to query signal history: {}", e);
+            DatabaseError::QueryError(format!("Failed to query signals: {}", e))
+        })?;
+
+        Ok(records)
+    }
+}
+
+/// dYdX order metadata repository
+#[async_trait]
+pub trait DydxOrderMetadataRepository: Send + Sync {
     /// Create new dYdX order metadata
-    pub async fn create(
+    async fn create(
+        &self,
+        metadata: CreateDydxOrderMetadata,
+    ) -> Result<DydxOrderMetadataRecord, DatabaseError>;
+
+    /// Get metadata by order ID
+    async fn get_by_order_id(
+        &self,
+        order_id: &str,
+    ) -> Result<Option<DydxOrderMetadataRecord>, DatabaseError>;
+
+    /// Update order status to cancelled
+    async fn update_cancelled(
+        &self,
+        order_id: &str,
+        tx_hash: &str,
+    ) -> Result<(), DatabaseError>;
+
+    /// Update order status to expired
+    async fn update_expired(&self, order_id: &str) -> Result<(), DatabaseError>;
+
+    /// Update order status to filled
+    async fn update_filled(&self, order_id: &str) -> Result<(), DatabaseError>;
+
+    /// Get active orders that may have expired
+    async fn get_active_orders(&self) -> Result<Vec<DydxOrderMetadataRecord>, DatabaseError>;
+}
+
+/// SQLite implementation of dYdX order metadata repository
+pub struct SqliteDydxOrderMetadataRepository {
+    pool: DbPool,
+}
+
+impl SqliteDydxOrderMetadataRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl DydxOrderMetadataRepository for SqliteDydxOrderMetadataRepository {
+    async fn create(
         &self,
         metadata: CreateDydxOrderMetadata,
     ) -> Result<DydxOrderMetadataRecord, DatabaseError> {
@@ -447,8 +880,7 @@ impl DydxOrderMetadataRepository {
         Ok(record)
     }
 
-    /// Get metadata by order ID
-    pub async fn get_by_order_id(
+    async fn get_by_order_id(
         &self,
         order_id: &str,
     ) -> Result<Option<DydxOrderMetadataRecord>, DatabaseError> {
@@ -466,8 +898,7 @@ impl DydxOrderMetadataRepository {
         Ok(record)
     }
 
-    /// Update order status to cancelled
-    pub async fn update_cancelled(
+    async fn update_cancelled(
         &self,
         order_id: &str,
         tx_hash: &str,
@@ -505,8 +936,7 @@ impl DydxOrderMetadataRepository {
         Ok(())
     }
 
-    /// Update order status to expired
-    pub async fn update_expired(&self, order_id: &str) -> Result<(), DatabaseError> {
+    async fn update_expired(&self, order_id: &str) -> Result<(), DatabaseError> {
         let rows_affected = sqlx::query(
             r#"
             UPDATE dydx_order_metadata
@@ -537,8 +967,38 @@ impl DydxOrderMetadataRepository {
         Ok(())
     }
 
-    /// Get active orders that may have expired
-    pub async fn get_active_orders(&self) -> Result<Vec<DydxOrderMetadataRecord>, DatabaseError> {
+    async fn update_filled(&self, order_id: &str) -> Result<(), DatabaseError> {
+        let rows_affected = sqlx::query(
+            r#"
+            UPDATE dydx_order_metadata
+            SET status = 'filled'
+            WHERE order_id = ?1 AND status = 'active'
+            "#,
+        )
+        .bind(order_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to update filled status for order {}: {}",
+                order_id, e
+            );
+            DatabaseError::QueryError(format!("Failed to update filled status: {}", e))
+        })?
+        .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(DatabaseError::QueryError(format!(
+                "Order not found or not active: {}",
+                order_id
+            )));
+        }
+
+        debug!("Updated order {} to filled", order_id);
+        Ok(())
+    }
+
+    async fn get_active_orders(&self) -> Result<Vec<DydxOrderMetadataRecord>, DatabaseError> {
         let records = sqlx::query_as::<_, DydxOrderMetadataRecord>(
             "SELECT * FROM dydx_order_metadata WHERE status = 'active' ORDER BY placed_at ASC",
         )
@@ -553,6 +1013,204 @@ impl DydxOrderMetadataRepository {
     }
 }
 
+/// Bracket order repository
+///
+/// Stores leg state so linked exits survive restarts.
+#[async_trait]
+pub trait BracketOrderRepository: Send + Sync {
+    /// Insert a bracket or overwrite its leg state
+    async fn save(&self, bracket: &BracketOrder) -> Result<(), DatabaseError>;
+
+    /// Get bracket by ID
+    async fn get(&self, id: &str) -> Result<Option<BracketOrder>, DatabaseError>;
+
+    /// Get brackets that still have open legs
+    async fn get_active(&self) -> Result<Vec<BracketOrder>, DatabaseError>;
+}
+
+/// SQLite implementation of bracket order repository
+pub struct SqliteBracketOrderRepository {
+    pool: DbPool,
+}
+
+impl SqliteBracketOrderRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BracketOrderRepository for SqliteBracketOrderRepository {
+    async fn save(&self, bracket: &BracketOrder) -> Result<(), DatabaseError> {
+        let record = BracketOrderRecord::from(bracket);
+        sqlx::query(
+            r#"
+            INSERT INTO bracket_orders (
+                id, position_id, symbol, exchange, exit_side, quantity, linking,
+                entry_order_id, entry_price, entry_state,
+                stop_loss_price, stop_loss_order_id, stop_loss_state,
+                take_profit_price, take_profit_order_id, take_profit_state,
+                active, created_at, updated_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)
+            ON CONFLICT(id) DO UPDATE SET
+                position_id = excluded.position_id,
+                exchange = excluded.exchange,
+                linking = excluded.linking,
+                entry_order_id = excluded.entry_order_id,
+                entry_price = excluded.entry_price,
+                entry_state = excluded.entry_state,
+                stop_loss_order_id = excluded.stop_loss_order_id,
+                stop_loss_state = excluded.stop_loss_state,
+                take_profit_order_id = excluded.take_profit_order_id,
+                take_profit_state = excluded.take_profit_state,
+                active = excluded.active,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&record.id)
+        .bind(&record.position_id)
+        .bind(&record.symbol)
+        .bind(&record.exchange)
+        .bind(&record.exit_side)
+        .bind(record.quantity)
+        .bind(&record.linking)
+        .bind(&record.entry_order_id)
+        .bind(record.entry_price)
+        .bind(&record.entry_state)
+        .bind(record.stop_loss_price)
+        .bind(&record.stop_loss_order_id)
+        .bind(&record.stop_loss_state)
+        .bind(record.take_profit_price)
+        .bind(&record.take_profit_order_id)
+        .bind(&record.take_profit_state)
+        .bind(record.active)
+        .bind(record.created_at)
+        .bind(record.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to save bracket order {}: {}", record.id, e);
+            DatabaseError::QueryError(format!("Failed to save bracket order: {}", e))
+        })?;
+
+        debug!("Saved bracket order: {} for {}", record.id, record.symbol);
+        Ok(())
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<BracketOrder>, DatabaseError> {
+        let record =
+            sqlx::query_as::<_, BracketOrderRecord>("SELECT * FROM bracket_orders WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| {
+                    error!("Failed to get bracket order {}: {}", id, e);
+                    DatabaseError::QueryError(format!("Failed to get bracket order: {}", e))
+                })?;
+
+        record
+            .map(BracketOrder::try_from)
+            .transpose()
+            .map_err(DatabaseError::QueryError)
+    }
+
+    async fn get_active(&self) -> Result<Vec<BracketOrder>, DatabaseError> {
+        let records = sqlx::query_as::<_, BracketOrderRecord>(
+            "SELECT * FROM bracket_orders WHERE active = 1 ORDER BY created_at ASC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to get active bracket orders: {}", e);
+            DatabaseError::QueryError(format!("Failed to get active bracket orders: {}", e))
+        })?;
+
+        records
+            .into_iter()
+            .map(BracketOrder::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(DatabaseError::QueryError)
+    }
+}
+
+/// Allocator state repository
+///
+/// Keeps the statistics behind adaptive strategy weights across restarts.
+#[async_trait]
+pub trait AllocatorStateRepository: Send + Sync {
+    /// Insert or overwrite the state of a named allocator
+    async fn save(
+        &self,
+        name: &str,
+        policy: &str,
+        state: &serde_json::Value,
+    ) -> Result<(), DatabaseError>;
+
+    /// Get the state of a named allocator
+    async fn get(&self, name: &str) -> Result<Option<AllocatorStateRecord>, DatabaseError>;
+}
+
+/// SQLite implementation of allocator state repository
+pub struct SqliteAllocatorStateRepository {
+    pool: DbPool,
+}
+
+impl SqliteAllocatorStateRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AllocatorStateRepository for SqliteAllocatorStateRepository {
+    async fn save(
+        &self,
+        name: &str,
+        policy: &str,
+        state: &serde_json::Value,
+    ) -> Result<(), DatabaseError> {
+        let state_json = serde_json::to_string(state).map_err(|e| {
+            DatabaseError::QueryError(format!("Failed to serialize allocator state: {}", e))
+        })?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO allocator_state (name, policy, state, updated_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(name) DO UPDATE SET
+                policy = excluded.policy,
+                state = excluded.state,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(name)
+        .bind(policy)
+        .bind(&state_json)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to save allocator state {}: {}", name, e);
+            DatabaseError::QueryError(format!("Failed to save allocator state: {}", e))
+        })?;
+
+        debug!("Saved allocator state: {} ({})", name, policy);
+        Ok(())
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<AllocatorStateRecord>, DatabaseError> {
+        sqlx::query_as::<_, AllocatorStateRecord>("SELECT * FROM allocator_state WHERE name = ?1")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("Failed to get allocator state {}: {}", name, e);
+                DatabaseError::QueryError(format!("Failed to get allocator state: {}", e))
+            })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -561,7 +1219,7 @@ mod tests {
     #[tokio::test]
     async fn test_position_crud() {
         let pool = init_database("sqlite::memory:").await.unwrap();
-        let repo = PositionRepository::new(pool);
+        let repo = SqlitePositionRepository::new(pool);
 
         // Create position
         let position = CreatePosition {
@@ -573,6 +1231,7 @@ mod tests {
             quantity: 0.1,
             stop_loss: Some(49000.0),
             take_profit: Some(52000.0),
+            group_id: None,
         };
 
         let created = repo.create(position).await.unwrap();
@@ -596,10 +1255,105 @@ mod tests {
         assert_eq!(closed.status, "closed");
     }
 
+    #[tokio::test]
+    async fn test_trade_exit_reason_roundtrip() {
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        SqlitePositionRepository::new(pool.clone())
+            .create(CreatePosition {
+                id: "pos-1".to_string(),
+                symbol: "BTC-USD".to_string(),
+                exchange: "coinbase".to_string(),
+                side: "long".to_string(),
+                entry_price: 49000.0,
+                quantity: 0.1,
+                stop_loss: None,
+                take_profit: None,
+                group_id: None,
+            })
+            .await
+            .unwrap();
+        let repo = SqliteTradeRepository::new(pool);
+
+        let trade = |id: &str, exit_reason: Option<&str>| CreateTrade {
+            id: id.to_string(),
+            position_id: Some("pos-1".to_string()),
+            symbol: "BTC-USD".to_string(),
+            exchange: "coinbase".to_string(),
+            side: "sell".to_string(),
+            price: 50000.0,
+            quantity: 0.1,
+            fee: 0.0,
+            fee_estimated: true,
+            exchange_order_id: None,
+            strategy: "FastScalping".to_string(),
+            signal_confidence: None,
+            exit_reason: exit_reason.map(str::to_string),
+            signal_details: None,
+        };
+
+        repo.create(trade("t-1", None)).await.unwrap();
+        let created = repo
+            .create(trade("t-2", Some("max_holding_time")))
+            .await
+            .unwrap();
+        assert_eq!(created.exit_reason.as_deref(), Some("max_holding_time"));
+
+        let time_exits = repo
+            .get_by_exit_reason("max_holding_time", 10)
+            .await
+            .unwrap();
+        assert_eq!(time_exits.len(), 1);
+        assert_eq!(time_exits[0].id, "t-2");
+    }
+
+    #[tokio::test]
+    async fn test_bracket_order_state_survives_reload() {
+        use crate::domain::entities::bracket_order::{
+            BracketExitIds, BracketLegRole, BracketLegState, BracketLinking,
+        };
+        use crate::domain::entities::order::OrderSide;
+
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        let repo = SqliteBracketOrderRepository::new(pool);
+
+        let mut bracket = BracketOrder::oco(
+            "br-1".to_string(),
+            "BTC-USD".to_string(),
+            OrderSide::Sell,
+            0.1,
+            49000.0,
+            52000.0,
+        )
+        .unwrap();
+        bracket.exchange = "dydx".to_string();
+        bracket.link_entry("entry-1".to_string(), Some(50000.0));
+        bracket.record_fill(BracketLegRole::Entry);
+        bracket.attach_native_exits(BracketExitIds {
+            stop_loss: "sl-1".to_string(),
+            take_profit: "tp-1".to_string(),
+        });
+        repo.save(&bracket).await.unwrap();
+
+        let loaded = repo.get("br-1").await.unwrap().unwrap();
+        assert_eq!(loaded.linking, BracketLinking::Native);
+        assert!(loaded.is_entry_filled());
+        assert_eq!(loaded.stop_loss.exchange_order_id.as_deref(), Some("sl-1"));
+        assert_eq!(loaded.take_profit.state, BracketLegState::Working);
+        assert_eq!(repo.get_active().await.unwrap().len(), 1);
+
+        bracket.record_fill(BracketLegRole::StopLoss);
+        repo.save(&bracket).await.unwrap();
+
+        let closed = repo.get("br-1").await.unwrap().unwrap();
+        assert_eq!(closed.filled_exit(), Some(BracketLegRole::StopLoss));
+        assert_eq!(closed.take_profit.state, BracketLegState::Cancelled);
+        assert!(repo.get_active().await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_dydx_metadata_crud() {
         let pool = init_database("sqlite::memory:").await.unwrap();
-        let repo = DydxOrderMetadataRepository::new(pool);
+        let repo = SqliteDydxOrderMetadataRepository::new(pool);
 
         // Create metadata
         let metadata = CreateDydxOrderMetadata {
@@ -657,4 +1411,102 @@ mod tests {
         let active = repo.get_active_orders().await.unwrap();
         assert_eq!(active.len(), 0); // Should be empty since we marked it as expired
     }
+
+    #[tokio::test]
+    async fn test_signal_history_records_outcomes_and_filters() {
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        let repo = SqliteSignalRepository::new(pool);
+
+        let signal = |symbol: &str, action: &str| CreateSignal {
+            symbol: symbol.to_string(),
+            action: action.to_string(),
+            confidence: 0.8,
+            price: Some(50000.0),
+            weights: serde_json::json!({"FastScalping": 0.4, "MomentumScalping": 0.6}),
+            details: serde_json::json!({"votes": [], "indicators": {}}),
+            regime: Some("ranging".to_string()),
+        };
+
+        let taken = repo.create(signal("BTC-USD", "buy")).await.unwrap();
+        assert!(!taken.executed);
+        assert!(taken.decided_at.is_none());
+        let missed = repo.create(signal("BTC-USD", "sell")).await.unwrap();
+        repo.create(signal("ETH-USD", "buy")).await.unwrap();
+
+        repo.record_outcome(taken.id, None, "Order executed")
+            .await
+            .unwrap();
+        repo.record_outcome(
+            missed.id,
+            Some(SignalSkipReason::Confidence),
+            "Signal confidence too low",
+        )
+        .await
+        .unwrap();
+
+        let btc = repo
+            .query(&SignalQuery {
+                symbol: Some("BTC-USD".to_string()),
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(btc.len(), 2);
+        assert_eq!(btc[0].id, missed.id); // Newest first
+        assert!(!btc[0].executed);
+        assert_eq!(btc[0].skip_reason.as_deref(), Some("confidence"));
+        assert!(btc[1].executed);
+        assert!(btc[1].skip_reason.is_none());
+        assert!(btc[1].decided_at.is_some());
+        assert_eq!(btc[1].regime.as_deref(), Some("ranging"));
+        let weights: serde_json::Value = serde_json::from_str(&btc[1].weights).unwrap();
+        assert_eq!(weights["MomentumScalping"], 0.6);
+
+        let all = repo
+            .query(&SignalQuery {
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 3);
+
+        let future = repo
+            .query(&SignalQuery {
+                from: Some(Utc::now() + chrono::Duration::hours(1)),
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(future.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_allocator_state_upsert() {
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        let repo = SqliteAllocatorStateRepository::new(pool);
+        assert!(repo.get("strategy_weights").await.unwrap().is_none());
+
+        repo.save(
+            "strategy_weights",
+            "thompson",
+            &serde_json::json!({ "arms": { "A": { "wins": 1 } } }),
+        )
+        .await
+        .unwrap();
+        repo.save(
+            "strategy_weights",
+            "ucb",
+            &serde_json::json!({ "arms": { "A": { "wins": 2 } } }),
+        )
+        .await
+        .unwrap();
+
+        let record = repo.get("strategy_weights").await.unwrap().unwrap();
+        assert_eq!(record.policy, "ucb");
+        let state: serde_json::Value = serde_json::from_str(&record.state).unwrap();
+        assert_eq!(state["arms"]["A"]["wins"], 2);
+    }
 }
@@ -1,64 +1,67 @@
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use sqlx::{Row, SqlitePool};
+use sqlx::{FromRow, SqlitePool};
+use std::collections::BTreeMap;
 
 use crate::domain::entities::symbol_screening::{RecommendationCategory, SymbolScreeningResult};
 
+/// Columns read back into a `SymbolScreeningResult`
+pub(crate) const RESULT_COLUMNS: &str = "symbol, exchange, volatility_score, volume_score, \
+     spread_score, momentum_score, overall_score, recommendation, component_scores, screened_at";
+
 /// Repository for persisting and retrieving screening results
-pub struct ScreeningRepository {
-    pool: SqlitePool,
-}
+#[async_trait]
+pub trait ScreeningRepository: Send + Sync {
+    /// Persist screening result to database
+    async fn save(&self, result: &SymbolScreeningResult) -> Result<i64, sqlx::Error>;
 
-impl ScreeningRepository {
-    pub fn new(pool: SqlitePool) -> Self {
-        ScreeningRepository { pool }
-    }
+    /// Retrieve recent screening results
+    async fn get_recent(&self, limit: i64) -> Result<Vec<SymbolScreeningResult>, sqlx::Error>;
 
-    /// Initialize database tables
-    pub async fn init(&self) -> Result<(), sqlx::Error> {
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS symbol_screening_results (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                symbol TEXT NOT NULL,
-                exchange TEXT NOT NULL,
-                volatility_score REAL NOT NULL,
-                volume_score REAL NOT NULL,
-                spread_score REAL NOT NULL,
-                momentum_score REAL NOT NULL,
-                overall_score REAL NOT NULL,
-                recommendation TEXT NOT NULL,
-                screened_at TIMESTAMP NOT NULL,
-                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+    /// Retrieve historical screening scores by symbol
+    async fn get_by_symbol(
+        &self,
+        symbol: &str,
+        limit: i64,
+    ) -> Result<Vec<SymbolScreeningResult>, sqlx::Error>;
 
-        // Create indexes for common queries
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_symbol_exchange ON symbol_screening_results(symbol, exchange)")
-            .execute(&self.pool)
-            .await?;
+    /// Delete old screening records (older than days)
+    async fn delete_old(&self, older_than_days: i64) -> Result<(), sqlx::Error>;
 
-        sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_screened_at ON symbol_screening_results(screened_at)",
-        )
-        .execute(&self.pool)
-        .await?;
+    /// Query by symbol and exchange, optionally only one recommendation level
+    async fn get_by_symbol_and_exchange(
+        &self,
+        symbol: &str,
+        exchange: &str,
+        recommendation: Option<RecommendationCategory>,
+        limit: i64,
+    ) -> Result<Vec<SymbolScreeningResult>, sqlx::Error>;
+}
 
-        Ok(())
+/// SQLite implementation of screening repository
+#[derive(Clone)]
+pub struct SqliteScreeningRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteScreeningRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        SqliteScreeningRepository { pool }
     }
+}
 
-    /// Persist screening result to database
-    pub async fn save(&self, result: &SymbolScreeningResult) -> Result<i64, sqlx::Error> {
+#[async_trait]
+impl ScreeningRepository for SqliteScreeningRepository {
+    async fn save(&self, result: &SymbolScreeningResult) -> Result<i64, sqlx::Error> {
         let recommendation = format!("{:?}", result.recommendation);
 
         let row = sqlx::query_scalar::<_, i64>(
             r#"
             INSERT INTO symbol_screening_results
             (symbol, exchange, volatility_score, volume_score, spread_score, momentum_score,
-             overall_score, recommendation, screened_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+             overall_score, recommendation, component_scores, screened_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING id
             "#,
         )
         .bind(&result.symbol)
@@ -69,6 +72,7 @@ impl ScreeningRepository {
         .bind(result.momentum_score)
         .bind(result.overall_score)
         .bind(&recommendation)
+        .bind(serde_json::to_string(&result.component_scores).ok())
         .bind(result.screened_at)
         .fetch_one(&self.pool)
         .await?;
@@ -76,208 +80,154 @@ impl ScreeningRepository {
         Ok(row)
     }
 
-    /// Retrieve recent screening results
-    pub async fn get_recent(&self, limit: i64) -> Result<Vec<SymbolScreeningResult>, sqlx::Error> {
-        let rows = sqlx::query(
+    async fn get_recent(&self, limit: i64) -> Result<Vec<SymbolScreeningResult>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ScreeningRow>(&format!(
             r#"
-            SELECT symbol, exchange, volatility_score, volume_score, spread_score,
-                   momentum_score, overall_score, recommendation, screened_at
+            SELECT {}
             FROM symbol_screening_results
             ORDER BY screened_at DESC
             LIMIT ?
             "#,
-        )
+            RESULT_COLUMNS
+        ))
         .bind(limit)
         .fetch_all(&self.pool)
         .await?;
 
-        let results = rows
-            .into_iter()
-            .map(|row| {
-                let symbol = row.get::<String, _>("symbol");
-                let exchange = row.get::<String, _>("exchange");
-                let volatility_score = row.get::<f64, _>("volatility_score");
-                let volume_score = row.get::<f64, _>("volume_score");
-                let spread_score = row.get::<f64, _>("spread_score");
-                let momentum_score = row.get::<f64, _>("momentum_score");
-                let overall_score = row.get::<f64, _>("overall_score");
-                let recommendation_str = row.get::<String, _>("recommendation");
-                let screened_at = row.get::<DateTime<Utc>, _>("screened_at");
-
-                let recommendation = match recommendation_str.as_str() {
-                    "BestCandidate" => RecommendationCategory::BestCandidate,
-                    "GoodCandidate" => RecommendationCategory::GoodCandidate,
-                    "FairCandidate" => RecommendationCategory::FairCandidate,
-                    _ => RecommendationCategory::Avoid,
-                };
-
-                SymbolScreeningResult {
-                    symbol,
-                    exchange,
-                    volatility_score,
-                    volume_score,
-                    spread_score,
-                    momentum_score,
-                    overall_score,
-                    recommendation,
-                    screened_at,
-                }
-            })
-            .collect();
-
-        Ok(results)
+        Ok(rows.into_iter().map(ScreeningRow::into_result).collect())
     }
 
-    /// Retrieve historical screening scores by symbol
-    pub async fn get_by_symbol(
+    async fn get_by_symbol(
         &self,
         symbol: &str,
         limit: i64,
     ) -> Result<Vec<SymbolScreeningResult>, sqlx::Error> {
-        let rows = sqlx::query(
+        let rows = sqlx::query_as::<_, ScreeningRow>(&format!(
             r#"
-            SELECT symbol, exchange, volatility_score, volume_score, spread_score,
-                   momentum_score, overall_score, recommendation, screened_at
+            SELECT {}
             FROM symbol_screening_results
             WHERE symbol = ?
             ORDER BY screened_at DESC
             LIMIT ?
             "#,
-        )
+            RESULT_COLUMNS
+        ))
         .bind(symbol)
         .bind(limit)
         .fetch_all(&self.pool)
         .await?;
 
-        let results = rows
-            .into_iter()
-            .map(|row| {
-                let symbol = row.get::<String, _>("symbol");
-                let exchange = row.get::<String, _>("exchange");
-                let volatility_score = row.get::<f64, _>("volatility_score");
-                let volume_score = row.get::<f64, _>("volume_score");
-                let spread_score = row.get::<f64, _>("spread_score");
-                let momentum_score = row.get::<f64, _>("momentum_score");
-                let overall_score = row.get::<f64, _>("overall_score");
-                let recommendation_str = row.get::<String, _>("recommendation");
-                let screened_at = row.get::<DateTime<Utc>, _>("screened_at");
-
-                let recommendation = match recommendation_str.as_str() {
-                    "BestCandidate" => RecommendationCategory::BestCandidate,
-                    "GoodCandidate" => RecommendationCategory::GoodCandidate,
-                    "FairCandidate" => RecommendationCategory::FairCandidate,
-                    _ => RecommendationCategory::Avoid,
-                };
-
-                SymbolScreeningResult {
-                    symbol,
-                    exchange,
-                    volatility_score,
-                    volume_score,
-                    spread_score,
-                    momentum_score,
-                    overall_score,
-                    recommendation,
-                    screened_at,
-                }
-            })
-            .collect();
-
-        Ok(results)
+        Ok(rows.into_iter().map(ScreeningRow::into_result).collect())
     }
 
-    /// Delete old screening records (older than days)
-    pub async fn delete_old(&self, older_than_days: i64) -> Result<(), sqlx::Error> {
-        sqlx::query(
-            r#"
-            DELETE FROM symbol_screening_results
-            WHERE screened_at < datetime('now', ? || ' days')
-            "#,
-        )
-        .bind(-older_than_days)
-        .execute(&self.pool)
-        .await?;
+    async fn delete_old(&self, older_than_days: i64) -> Result<(), sqlx::Error> {
+        let cutoff = Utc::now() - chrono::Duration::days(older_than_days);
+        sqlx::query("DELETE FROM symbol_screening_results WHERE screened_at < ?")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await?;
 
         Ok(())
     }
 
-    /// Query by symbol and exchange
-    pub async fn get_by_symbol_and_exchange(
+    async fn get_by_symbol_and_exchange(
         &self,
         symbol: &str,
         exchange: &str,
+        recommendation: Option<RecommendationCategory>,
         limit: i64,
     ) -> Result<Vec<SymbolScreeningResult>, sqlx::Error> {
-        let rows = sqlx::query(
+        let recommendation = recommendation.map(|r| format!("{:?}", r));
+        let rows = sqlx::query_as::<_, ScreeningRow>(&format!(
             r#"
-            SELECT symbol, exchange, volatility_score, volume_score, spread_score,
-                   momentum_score, overall_score, recommendation, screened_at
+            SELECT {}
             FROM symbol_screening_results
-            WHERE symbol = ? AND exchange = ?
+            WHERE symbol = ? AND exchange = ? AND (? IS NULL OR recommendation = ?)
             ORDER BY screened_at DESC
             LIMIT ?
             "#,
-        )
+            RESULT_COLUMNS
+        ))
         .bind(symbol)
         .bind(exchange)
+        .bind(&recommendation)
+        .bind(&recommendation)
         .bind(limit)
         .fetch_all(&self.pool)
         .await?;
 
-        let results = rows
-            .into_iter()
-            .map(|row| {
-                let symbol = row.get::<String, _>("symbol");
-                let exchange = row.get::<String, _>("exchange");
-                let volatility_score = row.get::<f64, _>("volatility_score");
-                let volume_score = row.get::<f64, _>("volume_score");
-                let spread_score = row.get::<f64, _>("spread_score");
-                let momentum_score = row.get::<f64, _>("momentum_score");
-                let overall_score = row.get::<f64, _>("overall_score");
-                let recommendation_str = row.get::<String, _>("recommendation");
-                let screened_at = row.get::<DateTime<Utc>, _>("screened_at");
-
-                let recommendation = match recommendation_str.as_str() {
-                    "BestCandidate" => RecommendationCategory::BestCandidate,
-                    "GoodCandidate" => RecommendationCategory::GoodCandidate,
-                    "FairCandidate" => RecommendationCategory::FairCandidate,
-                    _ => RecommendationCategory::Avoid,
-                };
-
-                SymbolScreeningResult {
-                    symbol,
-                    exchange,
-                    volatility_score,
-                    volume_score,
-                    spread_score,
-                    momentum_score,
-                    overall_score,
-                    recommendation,
-                    screened_at,
-                }
-            })
-            .collect();
-
-        Ok(results)
+        Ok(rows.into_iter().map(ScreeningRow::into_result).collect())
+    }
+}
+
+/// Stored screening result, shared by every backend
+#[derive(Debug, FromRow)]
+pub(crate) struct ScreeningRow {
+    symbol: String,
+    exchange: String,
+    volatility_score: f64,
+    volume_score: f64,
+    spread_score: f64,
+    momentum_score: f64,
+    overall_score: f64,
+    recommendation: String,
+    component_scores: Option<String>,
+    screened_at: DateTime<Utc>,
+}
+
+impl ScreeningRow {
+    pub(crate) fn into_result(self) -> SymbolScreeningResult {
+        let recommendation = match self.recommendation.as_str() {
+            "BestCandidate" => RecommendationCategory::BestCandidate,
+            "GoodCandidate" => RecommendationCategory::GoodCandidate,
+            "FairCandidate" => RecommendationCategory::FairCandidate,
+            _ => RecommendationCategory::Avoid,
+        };
+
+        // Rows saved before component scores were recorded only have the four
+        let component_scores = self
+            .component_scores
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_else(|| {
+                BTreeMap::from([
+                    ("volatility".to_string(), self.volatility_score),
+                    ("volume".to_string(), self.volume_score),
+                    ("spread".to_string(), self.spread_score),
+                    ("momentum".to_string(), self.momentum_score),
+                ])
+            });
+
+        SymbolScreeningResult {
+            symbol: self.symbol,
+            exchange: self.exchange,
+            volatility_score: self.volatility_score,
+            volume_score: self.volume_score,
+            spread_score: self.spread_score,
+            momentum_score: self.momentum_score,
+            component_scores,
+            overall_score: self.overall_score,
+            recommendation,
+            screened_at: self.screened_at,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::persistence::init_database;
 
     #[tokio::test]
-    async fn test_screening_repository_initialization() {
-        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
-        let repo = ScreeningRepository::new(pool);
-        repo.init().await.unwrap();
+    async fn test_screening_tables_are_migrated() {
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        let repo = SqliteScreeningRepository::new(pool);
+        assert!(repo.get_recent(10).await.unwrap().is_empty());
     }
 
     #[tokio::test]
     async fn test_screening_repository_persist_and_retrieve() {
-        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
-        let repo = ScreeningRepository::new(pool);
-        repo.init().await.unwrap();
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        let repo = SqliteScreeningRepository::new(pool);
 
         let result = SymbolScreeningResult::new(
             "BTC-USD".to_string(),
@@ -298,9 +248,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_screening_repository_get_by_symbol() {
-        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
-        let repo = ScreeningRepository::new(pool);
-        repo.init().await.unwrap();
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        let repo = SqliteScreeningRepository::new(pool);
 
         for i in 0..5 {
             let result = SymbolScreeningResult::new(
@@ -320,9 +269,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_screening_repository_get_by_symbol_and_exchange() {
-        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
-        let repo = ScreeningRepository::new(pool);
-        repo.init().await.unwrap();
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        let repo = SqliteScreeningRepository::new(pool);
 
         let result = SymbolScreeningResult::new(
             "BTC-USD".to_string(),
@@ -335,17 +283,94 @@ mod tests {
         repo.save(&result).await.unwrap();
 
         let retrieved = repo
-            .get_by_symbol_and_exchange("BTC-USD", "dydx", 10)
+            .get_by_symbol_and_exchange("BTC-USD", "dydx", None, 10)
             .await
             .unwrap();
         assert_eq!(retrieved.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_recommendation_filter_is_applied_before_the_limit() {
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        let repo = SqliteScreeningRepository::new(pool);
+
+        // One strong run followed by newer weak ones
+        for score in [0.9, 0.1, 0.1, 0.1] {
+            let result = SymbolScreeningResult::new(
+                "BTC-USD".to_string(),
+                "coinbase".to_string(),
+                score,
+                score,
+                score,
+                score,
+            );
+            repo.save(&result).await.unwrap();
+        }
+
+        let best = repo
+            .get_by_symbol_and_exchange(
+                "BTC-USD",
+                "coinbase",
+                Some(RecommendationCategory::BestCandidate),
+                2,
+            )
+            .await
+            .unwrap();
+        assert_eq!(best.len(), 1);
+        assert_eq!(
+            best[0].recommendation,
+            RecommendationCategory::BestCandidate
+        );
+    }
+
+    #[tokio::test]
+    async fn test_screening_repository_persists_component_scores() {
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        let repo = SqliteScreeningRepository::new(pool);
+
+        let components = BTreeMap::from([
+            ("volume".to_string(), 0.7),
+            ("depth".to_string(), 0.4),
+            ("funding".to_string(), 0.9),
+        ]);
+        let result = SymbolScreeningResult::from_components(
+            "ETH-USD".to_string(),
+            "dydx".to_string(),
+            components.clone(),
+            0.6,
+        );
+        repo.save(&result).await.unwrap();
+
+        let retrieved = repo.get_recent(1).await.unwrap();
+        assert_eq!(retrieved[0].component_scores, components);
+        assert_eq!(retrieved[0].volume_score, 0.7);
+    }
+
+    #[tokio::test]
+    async fn test_rows_without_components_fall_back_to_fixed_scores() {
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "INSERT INTO symbol_screening_results (symbol, exchange, volatility_score,
+             volume_score, spread_score, momentum_score, overall_score, recommendation,
+             screened_at) VALUES ('BTC-USD', 'dydx', 0.8, 0.7, 0.9, 0.6, 0.75,
+             'BestCandidate', '2025-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let repo = SqliteScreeningRepository::new(pool);
+
+        // Older rows fall back to the four fixed components
+        let retrieved = repo.get_recent(1).await.unwrap();
+        assert_eq!(retrieved[0].component_scores.len(), 4);
+        assert_eq!(retrieved[0].component_scores["spread"], 0.9);
+    }
+
     #[tokio::test]
     async fn test_screening_repository_delete_old() {
-        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
-        let repo = ScreeningRepository::new(pool);
-        repo.init().await.unwrap();
+        let pool = init_database("sqlite::memory:").await.unwrap();
+        let repo = SqliteScreeningRepository::new(pool);
 
         let result = SymbolScreeningResult::new(
             "BTC-USD".to_string(),
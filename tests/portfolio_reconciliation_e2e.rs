@@ -23,9 +23,9 @@ use nzeza::application::actors::reconciliation_actor::{
 };
 use nzeza::domain::entities::exchange::Exchange;
 use nzeza::domain::services::portfolio_reconciliation::{
-    Balance, BalanceDiscrepancy, ConcretePortfolioReconciliationService, DiscrepancySeverity,
-    ExchangeBalances, Portfolio, PortfolioReconciliationService, ReconciliationError,
-    ReconciliationReport, ReconciliationStatus,
+    compare_balances, Balance, BalanceDiscrepancy, ConcretePortfolioReconciliationService,
+    DiscrepancySeverity, ExchangeBalances, OwnActivity, Portfolio, PortfolioReconciliationService,
+    ReconciliationConfig, ReconciliationError, ReconciliationReport, ReconciliationStatus,
 };
 
 // Mock Exchange Client for testing
@@ -408,6 +408,37 @@ async fn test_should_detect_zero_value_balance_changes() {
     }
 }
 
+#[tokio::test]
+async fn test_should_compare_balances_in_both_directions() {
+    // Given: Exchange holds SOL we never expected, local dust of a sold coin
+    let mut local_portfolio = Portfolio::new();
+    local_portfolio.add_balance("BTC".to_string(), 1.0);
+    local_portfolio.add_balance("DOGE".to_string(), 0.00001);
+
+    let mut exchange_balances = ExchangeBalances::new(Exchange::Coinbase);
+    exchange_balances.add_balance("BTC".to_string(), 1.0);
+    exchange_balances.add_balance("SOL".to_string(), 3.0);
+
+    // When: Comparing with the shared balance comparison
+    let discrepancies = compare_balances(
+        &local_portfolio,
+        &exchange_balances,
+        &ReconciliationConfig::default(),
+    );
+
+    // Then: The unexpected SOL is a mismatch against zero, the dust is ignored
+    assert_eq!(
+        discrepancies,
+        vec![BalanceDiscrepancy::Mismatch {
+            currency: "SOL".to_string(),
+            local: 0.0,
+            exchange: 3.0,
+            diff: 3.0,
+        }]
+    );
+    assert_eq!(discrepancies[0].gap(), 3.0);
+}
+
 // =============================================================================
 // CATEGORY 3: Reconciliation Logic Tests (5 tests)
 // =============================================================================
@@ -640,6 +671,7 @@ async fn test_reconciliation_actor_should_handle_reconcile_message() {
     // When: Sending reconcile message
     let message = ReconciliationMessage::ReconcileExchange {
         exchange: Exchange::Coinbase,
+        activity: OwnActivity::default(),
         reply: reply_tx,
     };
 
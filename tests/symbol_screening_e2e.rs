@@ -26,6 +26,7 @@ async fn test_end_to_end_symbol_screening_workflow() {
             volumes: vec![1000000.0, 1200000.0, 1500000.0],
             bid: 100.0,
             ask: 100.2,
+            ..Default::default()
         },
     );
 
@@ -42,6 +43,7 @@ async fn test_end_to_end_symbol_screening_workflow() {
             volumes: vec![500000.0, 550000.0, 600000.0],
             bid: 2000.0,
             ask: 2000.5,
+            ..Default::default()
         },
     );
 
@@ -58,6 +60,7 @@ async fn test_end_to_end_symbol_screening_workflow() {
             volumes: vec![100000.0, 90000.0, 80000.0],
             bid: 150.0,
             ask: 150.1,
+            ..Default::default()
         },
     );
 
@@ -104,6 +107,7 @@ async fn test_end_to_end_symbol_screening_workflow() {
             volumes: vec![1000000.0],
             bid: 100.0,
             ask: 100.2,
+            ..Default::default()
         },
     );
 
@@ -129,6 +133,7 @@ async fn test_symbol_screening_recommendation_categorization() {
         volumes: vec![1000000.0],
         bid: 100.0,
         ask: 100.0,
+        ..Default::default()
     };
 
     let result = service
@@ -148,6 +153,7 @@ async fn test_symbol_screening_recommendation_categorization() {
         volumes: vec![],
         bid: 0.0,
         ask: 0.0,
+        ..Default::default()
     };
 
     let result_poor = service
@@ -215,6 +221,7 @@ async fn test_symbol_screening_cache_expiration() {
         volumes: vec![500_000.0],
         bid: 100.0,
         ask: 100.1,
+        ..Default::default()
     };
 
     // First screening
@@ -276,6 +283,7 @@ async fn test_symbol_screening_multiple_exchanges() {
         volumes: vec![500_000.0],
         bid: 100.0,
         ask: 100.1,
+        ..Default::default()
     };
 
     let result_dydx = service